@@ -1,5 +1,5 @@
 use std::cmp::max;
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 
 const SUBSTITUTION_COST: usize = 1;
 const DELETION_COST: usize = 1;
@@ -15,6 +15,22 @@ pub enum Operation {
 
 use Operation::*;
 
+/// The algorithm used to align the tokens of a minus line with those of a plus line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WordDiffAlgorithm {
+    /// Minimal edit distance, allowing substitutions (the default).
+    Levenshtein,
+    /// Minimal number of insertions and deletions (Myers' O(ND) algorithm).
+    Myers,
+    /// Align on tokens which occur exactly once in both lines first.
+    Patience,
+    /// Align on the least frequent common tokens first, as in `git diff --histogram`.
+    Histogram,
+}
+
+// Tokens occurring more often than this are not used as anchors by the histogram algorithm.
+const HISTOGRAM_MAX_CHAIN_LENGTH: usize = 64;
+
 /// Needleman-Wunsch / Wagner-Fischer table for computation of edit distance and associated
 /// alignment.
 #[derive(Clone, Debug)]
@@ -30,6 +46,8 @@ pub struct Alignment<'a> {
     pub y: Vec<&'a str>,
     table: Vec<Cell>,
     dim: [usize; 2],
+    // Operations computed without the table, by algorithms other than Levenshtein.
+    ops: Option<Vec<Operation>>,
 }
 
 impl<'a> Alignment<'a> {
@@ -46,11 +64,35 @@ impl<'a> Alignment<'a> {
             };
             dim[0] * dim[1]
         ];
-        let mut alignment = Self { x, y, table, dim };
+        let mut alignment = Self {
+            x,
+            y,
+            table,
+            dim,
+            ops: None,
+        };
         alignment.fill();
         alignment
     }
 
+    /// Align x and y using the given algorithm.
+    pub fn with_algorithm(x: Vec<&'a str>, y: Vec<&'a str>, algorithm: WordDiffAlgorithm) -> Self {
+        let mut ops = Vec::with_capacity(max(x.len(), y.len()));
+        match algorithm {
+            WordDiffAlgorithm::Levenshtein => return Self::new(x, y),
+            WordDiffAlgorithm::Myers => myers(&x, &y, &mut ops),
+            WordDiffAlgorithm::Patience => patience(&x, &y, &mut ops),
+            WordDiffAlgorithm::Histogram => histogram(&x, &y, &mut ops),
+        }
+        Self {
+            x,
+            y,
+            table: Vec::new(),
+            dim: [0, 0],
+            ops: Some(ops),
+        }
+    }
+
     /// Fill table for Levenshtein distance / alignment computation
     pub fn fill(&mut self) {
         // x is written along the top of the table; y is written down the left side of the
@@ -106,6 +148,9 @@ impl<'a> Alignment<'a> {
 
     /// Read edit operations from the table.
     pub fn operations(&self) -> Vec<Operation> {
+        if let Some(ops) = &self.ops {
+            return ops.clone();
+        }
         let mut ops = VecDeque::with_capacity(max(self.x.len(), self.y.len()));
         let mut cell = &self.table[self.index(self.x.len(), self.y.len())];
         loop {
@@ -193,6 +238,197 @@ impl<'a> Alignment<'a> {
     }
 }
 
+/// Append the operations of a shortest edit script (insertions and deletions only) to `ops`.
+fn myers(x: &[&str], y: &[&str], ops: &mut Vec<Operation>) {
+    let (n, m) = (x.len() as isize, y.len() as isize);
+    let offset = n + m;
+    let mut v = vec![0_isize; 2 * offset as usize + 2];
+    let mut trace = Vec::new();
+    'search: for d in 0..=offset {
+        trace.push(v.clone());
+        for k in (-d..=d).step_by(2) {
+            let index = (k + offset) as usize;
+            let mut i = if k == -d || (k != d && v[index - 1] < v[index + 1]) {
+                v[index + 1]
+            } else {
+                v[index - 1] + 1
+            };
+            let mut j = i - k;
+            while i < n && j < m && x[i as usize] == y[j as usize] {
+                i += 1;
+                j += 1;
+            }
+            v[index] = i;
+            if i >= n && j >= m {
+                break 'search;
+            }
+        }
+    }
+
+    // Walk back from the end of both sequences to recover the path.
+    let mut script = Vec::with_capacity((n + m) as usize);
+    let (mut i, mut j) = (n, m);
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let k = i - j;
+        let prev_k =
+            if k == -d || (k != d && v[(k - 1 + offset) as usize] < v[(k + 1 + offset) as usize]) {
+                k + 1
+            } else {
+                k - 1
+            };
+        let prev_i = v[(prev_k + offset) as usize];
+        let prev_j = prev_i - prev_k;
+        while i > prev_i && j > prev_j {
+            script.push(NoOp);
+            i -= 1;
+            j -= 1;
+        }
+        if d > 0 {
+            script.push(if i == prev_i { Insertion } else { Deletion });
+        }
+        i = prev_i;
+        j = prev_j;
+    }
+    ops.extend(script.into_iter().rev());
+}
+
+/// Append the operations aligning x and y to `ops`, recursively splitting both at the anchors
+/// found by `find_anchors`, which returns matching (x index, y index, length) regions in
+/// increasing order. Falls back to Myers if no anchors are found.
+fn split_at_anchors<'a, F>(x: &[&'a str], y: &[&'a str], ops: &mut Vec<Operation>, find_anchors: F)
+where
+    F: Fn(&[&'a str], &[&'a str]) -> Vec<(usize, usize, usize)> + Copy,
+{
+    let prefix = x.iter().zip(y).take_while(|(a, b)| a == b).count();
+    let (x, y) = (&x[prefix..], &y[prefix..]);
+    let suffix = x
+        .iter()
+        .rev()
+        .zip(y.iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (x, y) = (&x[..x.len() - suffix], &y[..y.len() - suffix]);
+    ops.resize(ops.len() + prefix, NoOp);
+
+    if x.is_empty() || y.is_empty() {
+        ops.resize(ops.len() + x.len(), Deletion);
+        ops.resize(ops.len() + y.len(), Insertion);
+    } else {
+        let anchors = find_anchors(x, y);
+        if anchors.is_empty() {
+            myers(x, y, ops);
+        } else {
+            let (mut i, mut j) = (0, 0);
+            for (anchor_i, anchor_j, len) in anchors {
+                split_at_anchors(&x[i..anchor_i], &y[j..anchor_j], ops, find_anchors);
+                ops.resize(ops.len() + len, NoOp);
+                i = anchor_i + len;
+                j = anchor_j + len;
+            }
+            split_at_anchors(&x[i..], &y[j..], ops, find_anchors);
+        }
+    }
+    ops.resize(ops.len() + suffix, NoOp);
+}
+
+fn patience(x: &[&str], y: &[&str], ops: &mut Vec<Operation>) {
+    split_at_anchors(x, y, ops, |x, y| {
+        // Tokens occurring exactly once in both x and y, in the order of x.
+        let mut counts = HashMap::<&str, (usize, usize, usize)>::new();
+        for (i, token) in x.iter().enumerate() {
+            let entry = counts.entry(token).or_insert((0, 0, 0));
+            entry.0 += 1;
+            entry.2 = i;
+        }
+        let mut unique = vec![None; x.len()];
+        for (j, token) in y.iter().enumerate() {
+            if let Some(entry) = counts.get_mut(token) {
+                entry.1 += 1;
+                unique[entry.2] = Some(j);
+            }
+        }
+        let candidates: Vec<(usize, usize)> = unique
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, j)| j.map(|j| (i, j)))
+            .filter(|(i, _)| counts[x[*i]].0 == 1 && counts[x[*i]].1 == 1)
+            .collect();
+        longest_increasing_subsequence(&candidates)
+            .into_iter()
+            .map(|(i, j)| (i, j, 1))
+            .collect()
+    });
+}
+
+/// Patience sorting: the longest subsequence of `pairs` (sorted by their first element) whose
+/// second elements are increasing.
+fn longest_increasing_subsequence(pairs: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    // piles[p] is the index in pairs of the top card of pile p.
+    let mut piles: Vec<usize> = Vec::new();
+    let mut predecessors = vec![None; pairs.len()];
+    for (index, &(_, j)) in pairs.iter().enumerate() {
+        let pile = piles.partition_point(|&top| pairs[top].1 < j);
+        if pile > 0 {
+            predecessors[index] = Some(piles[pile - 1]);
+        }
+        if pile == piles.len() {
+            piles.push(index);
+        } else {
+            piles[pile] = index;
+        }
+    }
+    let mut subsequence = Vec::with_capacity(piles.len());
+    let mut index = piles.last().copied();
+    while let Some(i) = index {
+        subsequence.push(pairs[i]);
+        index = predecessors[i];
+    }
+    subsequence.reverse();
+    subsequence
+}
+
+fn histogram(x: &[&str], y: &[&str], ops: &mut Vec<Operation>) {
+    split_at_anchors(x, y, ops, |x, y| {
+        let mut occurrences = HashMap::<&str, Vec<usize>>::new();
+        for (i, token) in x.iter().enumerate() {
+            occurrences.entry(token).or_default().push(i);
+        }
+        // The longest common region around the least frequent token of x which also occurs in y.
+        let mut best: Option<(usize, usize, usize, usize)> = None; // (count, i, j, len)
+        for (j, token) in y.iter().enumerate() {
+            let occurrences = match occurrences.get(token) {
+                Some(occurrences) if occurrences.len() <= HISTOGRAM_MAX_CHAIN_LENGTH => occurrences,
+                _ => continue,
+            };
+            for &i in occurrences {
+                let (mut start_i, mut start_j) = (i, j);
+                while start_i > 0 && start_j > 0 && x[start_i - 1] == y[start_j - 1] {
+                    start_i -= 1;
+                    start_j -= 1;
+                }
+                let len = x[start_i..]
+                    .iter()
+                    .zip(&y[start_j..])
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                let count = occurrences.len();
+                let is_better = match best {
+                    Some((best_count, _, _, best_len)) => {
+                        count < best_count || (count == best_count && len > best_len)
+                    }
+                    None => true,
+                };
+                if is_better {
+                    best = Some((count, start_i, start_j, len));
+                }
+            }
+        }
+        best.map(|(_, i, j, len)| vec![(i, j, len)])
+            .unwrap_or_default()
+    });
+}
+
 fn run_length_encode<T>(sequence: Vec<T>) -> Vec<(T, usize)>
 where
     T: Copy,
@@ -292,6 +528,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_word_diff_algorithms() {
+        for algorithm in [
+            WordDiffAlgorithm::Myers,
+            WordDiffAlgorithm::Patience,
+            WordDiffAlgorithm::Histogram,
+        ] {
+            for (before, after) in [
+                ("kitten", "sitting"),
+                ("saturday", "sunday"),
+                ("", "abc"),
+                ("abc", ""),
+                ("abcabba", "cbabac"),
+            ] {
+                let ops = operations_with(before, after, algorithm);
+                let count = |op| ops.iter().filter(|o| **o == op).count();
+                assert_eq!(count(Substitution), 0);
+                assert_eq!(count(NoOp) + count(Deletion), before.len());
+                assert_eq!(count(NoOp) + count(Insertion), after.len());
+            }
+        }
+        // Myers finds a longest common subsequence.
+        let ops = operations_with("abcabba", "cbabac", WordDiffAlgorithm::Myers);
+        assert_eq!(ops.iter().filter(|op| **op == NoOp).count(), 4);
+    }
+
+    #[test]
+    fn test_word_diff_algorithm_anchors() {
+        // The unique token "b" is aligned first, although aligning both "a"s would match more.
+        let (before, after) = ("aab", "baa");
+        assert_eq!(
+            operations_with(before, after, WordDiffAlgorithm::Myers),
+            vec![Insertion, NoOp, NoOp, Deletion]
+        );
+        assert_eq!(
+            operations_with(before, after, WordDiffAlgorithm::Patience),
+            vec![Deletion, Deletion, NoOp, Insertion, Insertion]
+        );
+        assert_eq!(
+            operations_with(before, after, WordDiffAlgorithm::Histogram),
+            vec![Deletion, Deletion, NoOp, Insertion, Insertion]
+        );
+    }
+
     fn assert_string_distance_parts(s1: &str, s2: &str, parts: (usize, usize)) {
         let (numer, _) = parts;
         assert_string_levenshtein_distance(s1, s2, numer);
@@ -320,6 +600,14 @@ mod tests {
         Alignment::new(x, y).levenshtein_distance()
     }
 
+    fn operations_with<'a>(x: &'a str, y: &'a str, algorithm: WordDiffAlgorithm) -> Vec<Operation> {
+        let (x, y) = (
+            x.graphemes(true).collect::<Vec<&str>>(),
+            y.graphemes(true).collect::<Vec<&str>>(),
+        );
+        Alignment::with_algorithm(x, y, algorithm).operations()
+    }
+
     fn operations<'a>(x: &'a str, y: &'a str) -> Vec<Operation> {
         let (x, y) = (
             x.graphemes(true).collect::<Vec<&str>>(),
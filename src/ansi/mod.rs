@@ -2,6 +2,7 @@ mod console_tests;
 mod iterator;
 
 use std::borrow::Cow;
+use std::io::Write;
 
 use ansi_term::Style;
 use itertools::Itertools;
@@ -14,11 +15,50 @@ pub const ANSI_CSI_CLEAR_TO_EOL: &str = "\x1b[0K";
 pub const ANSI_CSI_CLEAR_TO_BOL: &str = "\x1b[1K";
 pub const ANSI_SGR_RESET: &str = "\x1b[0m";
 pub const ANSI_SGR_REVERSE: &str = "\x1b[7m";
+pub const ANSI_SGR_NO_REVERSE: &str = "\x1b[27m";
 
 pub fn strip_ansi_codes(s: &str) -> String {
     strip_ansi_codes_from_strings_iterator(ansi_strings_iterator(s))
 }
 
+/// A writer which removes ANSI escape sequences from everything written to it. Output is
+/// buffered by line, since a single escape sequence may be split across several writes.
+pub struct StripAnsiWriter<'a> {
+    writer: &'a mut dyn Write,
+    buffer: Vec<u8>,
+}
+
+impl<'a> StripAnsiWriter<'a> {
+    pub fn new(writer: &'a mut dyn Write) -> Self {
+        Self {
+            writer,
+            buffer: Vec::new(),
+        }
+    }
+
+    fn write_buffered(&mut self, end: usize) -> std::io::Result<()> {
+        let rest = self.buffer.split_off(end);
+        let text = strip_ansi_codes(&String::from_utf8_lossy(&self.buffer));
+        self.buffer = rest;
+        self.writer.write_all(text.as_bytes())
+    }
+}
+
+impl Write for StripAnsiWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        if let Some(newline) = self.buffer.iter().rposition(|&b| b == b'\n') {
+            self.write_buffered(newline + 1)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.write_buffered(self.buffer.len())?;
+        self.writer.flush()
+    }
+}
+
 pub fn measure_text_width(s: &str) -> usize {
     // TODO: how should e.g. '\n' be handled?
     strip_ansi_codes(s).width()
@@ -65,6 +105,84 @@ pub fn truncate_str<'a, 'b>(s: &'a str, display_width: usize, tail: &'b str) ->
     Cow::from(format!("{}{}", result, result_tail))
 }
 
+/// Which part of a string too wide to be displayed is removed, see --truncation-side.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TruncationSide {
+    Left,
+    Right,
+    Middle,
+}
+
+/// Truncate string such that `symbol` marks the removed part, which is its end, its beginning or
+/// its middle, according to `side`. All ANSI escape sequences are kept, and those preceding the
+/// symbol are repeated after it, since the symbol may reset the style.
+pub fn truncate_str_on_side<'a>(
+    s: &'a str,
+    display_width: usize,
+    symbol: &str,
+    side: TruncationSide,
+) -> Cow<'a, str> {
+    if side == TruncationSide::Right {
+        return truncate_str(s, display_width, symbol);
+    }
+    let items = ansi_strings_iterator(s).collect::<Vec<(&str, bool)>>();
+    let graphemes: Vec<&str> = items
+        .iter()
+        .filter(|(_, is_ansi)| !is_ansi)
+        .flat_map(|(t, _)| t.graphemes(true))
+        .collect();
+    if graphemes.iter().map(|g| g.width()).sum::<usize>() <= display_width {
+        return Cow::from(s);
+    }
+    let symbol = truncate_str(symbol, display_width, "");
+    let available = display_width.saturating_sub(measure_text_width(&symbol));
+    let (head_width, tail_width) = match side {
+        TruncationSide::Left => (0, available),
+        _ => (available - available / 2, available / 2),
+    };
+    // The numbers of graphemes kept at the beginning and at the end.
+    let mut used = 0;
+    let n_head = graphemes
+        .iter()
+        .take_while(|g| {
+            used += g.width();
+            used <= head_width
+        })
+        .count();
+    let mut used = 0;
+    let n_tail = graphemes
+        .iter()
+        .rev()
+        .take_while(|g| {
+            used += g.width();
+            used <= tail_width
+        })
+        .count();
+    let tail_start = graphemes.len() - n_tail.min(graphemes.len() - n_head);
+
+    let mut result = String::new();
+    let mut ansi_sequences = String::new();
+    let mut i = 0;
+    for (t, is_ansi) in items {
+        if is_ansi {
+            result.push_str(t);
+            ansi_sequences.push_str(t);
+            continue;
+        }
+        for g in t.graphemes(true) {
+            if i == n_head {
+                result.push_str(&symbol);
+                result.push_str(&ansi_sequences);
+            }
+            if i < n_head || i >= tail_start {
+                result.push_str(g);
+            }
+            i += 1;
+        }
+    }
+    Cow::from(result)
+}
+
 pub fn parse_style_sections(s: &str) -> Vec<(ansi_term::Style, &str)> {
     let mut sections = Vec::new();
     let mut curr_style = Style::default();
@@ -124,6 +242,49 @@ pub fn ansi_preserving_slice(s: &str, start: usize) -> String {
         .join("")
 }
 
+/// Return `s` with the text in `ranges` displayed in reverse video. The ranges are byte ranges of
+/// the text without ANSI escape sequences, as returned by `strip_ansi_codes`, and must be sorted
+/// and not overlap. Reverse video is turned on again after any escape sequence within a range,
+/// since the sequence may reset it.
+pub fn reverse_text_ranges(s: &str, ranges: &[(usize, usize)]) -> String {
+    let mut boundaries = ranges
+        .iter()
+        .flat_map(|&(start, end)| [start, end])
+        .peekable();
+    let mut result = String::new();
+    let mut index = 0;
+    let mut is_reversed = false;
+    let mut toggle_at = |index: usize, result: &mut String, is_reversed: &mut bool| {
+        while boundaries.next_if(|&boundary| boundary <= index).is_some() {
+            *is_reversed = !*is_reversed;
+            result.push_str(if *is_reversed {
+                ANSI_SGR_REVERSE
+            } else {
+                ANSI_SGR_NO_REVERSE
+            });
+        }
+    };
+    for (t, is_ansi) in ansi_strings_iterator(s) {
+        if is_ansi {
+            result.push_str(t);
+            if is_reversed {
+                result.push_str(ANSI_SGR_REVERSE);
+            }
+            continue;
+        }
+        for c in t.chars() {
+            toggle_at(index, &mut result, &mut is_reversed);
+            result.push(c);
+            index += c.len_utf8();
+        }
+    }
+    toggle_at(index, &mut result, &mut is_reversed);
+    if is_reversed {
+        result.push_str(ANSI_SGR_NO_REVERSE);
+    }
+    result
+}
+
 /// Return the byte index in `s` of the i-th text byte in `s`. I.e. `i` counts
 /// bytes in non-ANSI-escape-sequence content only.
 pub fn ansi_preserving_index(s: &str, i: usize) -> Option<usize> {
@@ -182,8 +343,9 @@ mod tests {
 
     // Note that src/ansi/console_tests.rs contains additional test coverage for this module.
     use super::{
-        ansi_preserving_slice, measure_text_width, parse_first_style,
+        ansi_preserving_slice, measure_text_width, parse_first_style, reverse_text_ranges,
         string_starts_with_ansi_style_sequence, strip_ansi_codes, truncate_str,
+        truncate_str_on_side, TruncationSide,
     };
 
     #[test]
@@ -203,6 +365,23 @@ mod tests {
         assert_eq!(measure_text_width("a\nb\n"), 2);
     }
 
+    #[test]
+    fn test_reverse_text_ranges() {
+        assert_eq!(
+            reverse_text_ranges("a = 12", &[(4, 6)]),
+            "a = \x1b[7m12\x1b[27m"
+        );
+        assert_eq!(
+            reverse_text_ranges("\x1b[31ma\x1b[0m = 1", &[(0, 3), (4, 5)]),
+            "\x1b[31m\x1b[7ma\x1b[0m\x1b[7m =\x1b[27m \x1b[7m1\x1b[27m"
+        );
+        assert_eq!(
+            reverse_text_ranges("バー", &[(3, 6)]),
+            "バ\x1b[7mー\x1b[27m"
+        );
+        assert_eq!(reverse_text_ranges("abc", &[]), "abc");
+    }
+
     #[test]
     fn test_strip_ansi_codes_osc_hyperlink() {
         assert_eq!(strip_ansi_codes("\x1b[38;5;4m\x1b]8;;file:///Users/dan/src/delta/src/ansi/mod.rs\x1b\\src/ansi/mod.rs\x1b]8;;\x1b\\\x1b[0m\n"),
@@ -290,4 +469,23 @@ mod tests {
         assert_eq!(truncate_str("123", 2, "→"), "1→");
         assert_eq!(truncate_str("12ݶ", 1, "ݶ"), "ݶ");
     }
+
+    #[test]
+    fn test_truncate_str_on_side() {
+        use TruncationSide::*;
+        assert_eq!(truncate_str_on_side("123456", 6, "…", Middle), "123456");
+        assert_eq!(truncate_str_on_side("123456", 4, "…", Right), "123…");
+        assert_eq!(truncate_str_on_side("123456", 4, "…", Left), "…456");
+        assert_eq!(truncate_str_on_side("1234567", 4, "…", Middle), "12…7");
+        assert_eq!(truncate_str_on_side("1234567", 5, "..", Middle), "12..7");
+        assert_eq!(
+            truncate_str_on_side("a/日本/b.rs", 7, "…", Middle),
+            "a/….rs"
+        );
+        // The style preceding the symbol is restored after it.
+        assert_eq!(
+            truncate_str_on_side("\x1b[31m123456\x1b[0m", 4, "\x1b[7m…\x1b[0m", Middle),
+            "\x1b[31m12\x1b[7m…\x1b[0m\x1b[31m6\x1b[0m"
+        );
+    }
 }
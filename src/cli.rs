@@ -6,14 +6,15 @@ use bat::assets::HighlightingAssets;
 use clap::{AppSettings, ColorChoice, FromArgMatches, IntoApp, Parser};
 use lazy_static::lazy_static;
 use syntect::highlighting::Theme as SyntaxTheme;
-use syntect::parsing::SyntaxSet;
 
 use crate::config::delta_unreachable;
 use crate::env::DeltaEnv;
 use crate::git_config::{GitConfig, GitConfigEntry};
 use crate::options;
+use crate::options::option_value::OptionProvenance;
 use crate::utils;
 use crate::utils::bat::output::PagingMode;
+use crate::utils::syntect::LazySyntaxSet;
 
 #[derive(Parser)]
 #[clap(
@@ -200,6 +201,21 @@ width of 4 characters, followed by a unicode dividing-line character (│).
 
 Use '<' for left-align, '^' for center-align, and '>' for right-align.
 
+Line numbers wider than the format width widen the column for the rest of the file. The widths of
+the default formats, and those of any format when --line-numbers-min-width is given, are replaced
+by a floor of 1 (or the given minimum), so that the column width follows the largest line number of
+each file. The text around the placeholders, e.g. separators or prefixes, adds its display width
+to the column: wide characters count as two columns and ANSI escape sequences as none.
+
+The placeholders {rm} and {rp} display relative line numbers instead: the first changed line of
+each hunk shows its absolute line number and the other lines their distance to it, as with vim's
+'relativenumber'. --line-numbers-relative switches the formats to use these placeholders.
+
+The placeholders {snm} and {snp} display the same line numbers as {nm} and {np}, but are left blank
+when the number is equal to the one displayed on the previous line. {hunk} displays the index of the
+hunk within its file and {file} the index of the file within the diff, both counting from 1. They
+are only as wide as the indices require, unless a width is given. Any other placeholder is an error.
+
 
 If something isn't working correctly, or you have a feature request, please open an issue at
 https://github.com/dandavison/delta/issues.
@@ -208,6 +224,47 @@ For a short help summary, please use delta -h.
 "
 )]
 pub struct Opt {
+    #[clap(long = "binary-style", default_value = "yellow", value_name = "STYLE")]
+    /// Style string for the line summarizing a change to a binary file.
+    ///
+    /// Instead of git's "Binary files a/x and b/x differ" line, delta shows the sizes of the two
+    /// versions of the file, e.g. "binary: 14.2 KiB → 15.0 KiB (+0.8 KiB)", or a single size if
+    /// the file was added or removed. The sizes are read from the repository, from the GIT binary
+    /// patch emitted by `git diff --binary`, or from the files on disk, and git's line is kept if
+    /// none of these is available. See STYLES section.
+    pub binary_style: String,
+
+    #[clap(
+        long = "binary-diff",
+        default_value = "summary",
+        value_name = "summary|hexdump"
+    )]
+    /// How to display a change to a binary file.
+    ///
+    /// With "summary" a line gives the sizes of the two versions of the file, see --binary-style.
+    /// With "hexdump" this line is followed by a hex dump of the bytes of the two files, in which
+    /// lines that differ are shown as removed and added lines (side by side with --side-by-side)
+    /// and the bytes that differ are emphasized, using the minus and plus styles. The offsets of
+    /// the lines are shown in the line number gutter. The hex dump is only available when delta
+    /// is comparing two files given on the command line, and neither file is larger than
+    /// --binary-diff-max-size.
+    pub binary_diff: String,
+
+    #[clap(
+        long = "binary-diff-max-size",
+        default_value = "65536",
+        value_name = "BYTES"
+    )]
+    /// Maximum size of a binary file displayed as a hex dump by --binary-diff=hexdump.
+    pub binary_diff_max_size: usize,
+
+    #[clap(long = "blame-boundary-style", value_name = "STYLE")]
+    /// Style string for the blame format section of the lines of a boundary commit.
+    ///
+    /// The lines of the boundary commit, marked by '^', are those from before the range of commits
+    /// of `git blame`. By default they are painted like other lines, see blame-palette.
+    pub blame_boundary_style: Option<String>,
+
     #[clap(long = "blame-code-style", value_name = "STYLE")]
     /// Style string for the code section of a git blame line.
     ///
@@ -216,6 +273,17 @@ pub struct Opt {
     /// setting this option to 'syntax' will syntax-highlight the code with no background color.
     pub blame_code_style: Option<String>,
 
+    #[clap(
+        long = "blame-field-separator",
+        default_value = "",
+        value_name = "STRING"
+    )]
+    /// Separator inserted between the fields of the git blame commit metadata.
+    ///
+    /// The separator is placed between consecutive placeholders of blame-format and is styled
+    /// with blame-separator-style. E.g. --blame-field-separator='│'.
+    pub blame_field_separator: String,
+
     #[clap(
         long = "blame-format",
         default_value = "{timestamp:<15} {author:<15.14} {commit:<8}",
@@ -223,9 +291,22 @@ pub struct Opt {
     )]
     /// Format string for git blame commit metadata.
     ///
-    /// Available placeholders are "{timestamp}", "{author}", and "{commit}".
+    /// Available placeholders are "{timestamp}", "{author}", "{email}", "{summary}", "{commit}",
+    /// and "{short-hash}". The width of "{short-hash:N}" is the length of the abbreviated commit, 7
+    /// by default, including the '^' marking a boundary commit. Widths and precisions are in
+    /// display columns; a field truncated to its precision ends with '…'. The email is known for
+    /// `git blame --porcelain` and `git blame -e` output, and the summary of the commit for
+    /// `git blame --porcelain` output.
     pub blame_format: String,
 
+    #[clap(long = "blame-metadata-width", default_value = "0", value_name = "N")]
+    /// Width of the blame format section of git blame lines.
+    ///
+    /// The fields of blame-format are truncated, ending with '…', or padded so that the code
+    /// section of all lines starts at the same column, however long e.g. author names are. Zero,
+    /// the default, means the width given by blame-format.
+    pub blame_metadata_width: usize,
+
     #[clap(long = "blame-palette", value_name = "COLORS")]
     /// Background colors used for git blame lines (space-separated string).
     ///
@@ -233,6 +314,37 @@ pub struct Opt {
     /// needed.
     pub blame_palette: Option<String>,
 
+    #[clap(
+        long = "blame-palette-assignment",
+        default_value = "sequential",
+        value_name = "sequential|hashed"
+    )]
+    /// How the colors of blame-palette are assigned to git blame lines.
+    ///
+    /// With "sequential" the colors are assigned in order of appearance, and so an author or
+    /// commit may have different colors in different files. With "hashed" the color of a line is
+    /// derived from its author (see --blame-palette-hash-key), and so is the same for an author
+    /// in any output. Different authors may have the same color: see --blame-palette-seed.
+    pub blame_palette_assignment: String,
+
+    #[clap(
+        long = "blame-palette-hash-key",
+        default_value = "author",
+        value_name = "author|email"
+    )]
+    /// What determines the color of a line under --blame-palette-assignment=hashed.
+    ///
+    /// The author email is known for `git blame --porcelain` output only; otherwise the author
+    /// name is used, which is the email under `git blame -e`.
+    pub blame_palette_hash_key: String,
+
+    #[clap(long = "blame-palette-seed", default_value = "0", value_name = "N")]
+    /// Seed of the hash of --blame-palette-assignment=hashed.
+    ///
+    /// Changing it reshuffles the colors of the authors, e.g. if two frequent authors have the
+    /// same color.
+    pub blame_palette_seed: usize,
+
     #[clap(
         long = "blame-separator-format",
         default_value = "│{n:^4}│",
@@ -249,7 +361,7 @@ pub struct Opt {
     pub blame_separator_format: String,
 
     #[clap(long = "blame-separator-style", value_name = "STYLE")]
-    /// Style string for the blame-separator-format.
+    /// Style string for the blame-separator-format and blame-field-separator.
     pub blame_separator_style: Option<String>,
 
     #[clap(
@@ -260,6 +372,95 @@ pub struct Opt {
     /// Format of `git blame` timestamp in raw git output received by delta.
     pub blame_timestamp_format: String,
 
+    #[clap(
+        long = "blame-timestamp-relative",
+        default_value = "true",
+        value_name = "true|false"
+    )]
+    /// Whether to display git blame timestamps relative to now.
+    ///
+    /// By default the "{timestamp}" placeholder of blame-format is displayed as e.g. "3 days ago",
+    /// which fits its default width. Set this to "false" to display the date and time instead.
+    /// A timestamp which cannot be parsed using blame-timestamp-format is displayed as received.
+    pub blame_timestamp_relative: String,
+
+    #[clap(
+        long = "blame-timestamp-relative-threshold",
+        default_value = "0",
+        value_name = "DAYS"
+    )]
+    /// Display the dates of git blame commits older than this many days.
+    ///
+    /// With blame-timestamp-relative, the timestamps of older commits are displayed as their date,
+    /// e.g. "2021-08-22", rather than relative to now. Zero means no threshold.
+    pub blame_timestamp_relative_threshold: usize,
+
+    #[clap(long = "blame-uncommitted-style", value_name = "STYLE")]
+    /// Style string for the blame format section of uncommitted lines.
+    ///
+    /// Lines which are not committed yet are attributed by `git blame` to the all-zero commit. By
+    /// default they are painted like other lines, see blame-palette.
+    pub blame_uncommitted_style: Option<String>,
+
+    #[clap(long = "change-magnitude-shading")]
+    /// Shade the background of changed lines by how much of them changed.
+    ///
+    /// The background color of a removed or added line paired with a line from the other side is
+    /// faded towards black, or white with --light, the more the smaller the fraction of the line
+    /// which changed: a line with a small edit gets a subtle background, and a rewritten line gets
+    /// the full background of minus-non-emph-style or plus-non-emph-style. Emphasized sections are
+    /// not affected.
+    pub change_magnitude_shading: bool,
+
+    #[clap(long = "collapse-file-glob", value_name = "GLOBS")]
+    /// Collapse the files whose path matches one of these globs (comma-separated).
+    ///
+    /// A glob is matched against the path of the file and against its name: e.g.
+    /// --collapse-file-glob='*.snap,*.lock'. See --collapse-files-over.
+    pub collapse_file_glob: Option<String>,
+
+    #[clap(long = "collapse-files-over", default_value = "0", value_name = "N")]
+    /// Collapse the files with more than N added and removed lines.
+    ///
+    /// The hunks of a collapsed file are not displayed: its header is followed by a line
+    /// summarizing its changes, e.g. "… 842 lines (+512 −330) collapsed", see
+    /// --collapsed-file-style. This line is matched by the navigate regex, see --navigate. The
+    /// default of 0 collapses no files. Files are not collapsed in --color-only mode.
+    pub collapse_files_over: usize,
+
+    #[clap(long = "collapse-renamed-paths")]
+    /// Collapse the directories common to the old and new paths of a renamed or copied file.
+    ///
+    /// The differing parts of the paths are enclosed in braces, as in the output of git diff
+    /// --stat: e.g. "src/{utils ⟶   helpers}/io.rs".
+    pub collapse_renamed_paths: bool,
+
+    #[clap(
+        long = "collapsed-file-style",
+        default_value = "dim",
+        value_name = "STYLE"
+    )]
+    /// Style string for the line replacing the hunks of a collapsed file.
+    ///
+    /// See --collapse-files-over and STYLES section.
+    pub collapsed_file_style: String,
+
+    #[clap(
+        long = "color",
+        default_value = "auto",
+        value_name = "auto|always|never"
+    )]
+    /// Whether to style the output.
+    ///
+    /// Options are auto, always, and never. "never" removes all styling from the output, like
+    /// --plain. "auto" means that the environment decides, in this order of precedence: if the
+    /// environment variable NO_COLOR is set to a non-empty value, the output is not styled; if
+    /// CLICOLOR_FORCE is set to a value other than "0", the output is styled; otherwise the output
+    /// is styled, even when it is not written to a terminal, since delta's output is usually read
+    /// in a pager. "always" styles the output regardless of NO_COLOR. Use show-config to see the
+    /// result.
+    pub color: String,
+
     #[clap(long = "color-only")]
     /// Do not alter the input structurally in any way.
     ///
@@ -275,17 +476,43 @@ pub struct Opt {
     /// Style string for the commit hash decoration.
     ///
     /// See STYLES section. The style string should contain one of the special attributes 'box',
-    /// 'ul' (underline), 'ol' (overline), or the combination 'ul ol'.
+    /// 'ul' (underline), 'ol' (overline), or the combination 'ul ol'. A box also encloses the
+    /// author and date lines following the commit line.
     pub commit_decoration_style: String,
 
+    #[clap(long = "commit-format", value_name = "FMT")]
+    /// Format string for the commit line.
+    ///
+    /// The placeholders "{label}", "{hash}", "{author}" and "{date}" will be replaced by the text
+    /// preceding the commit hash (e.g. "commit"), the commit hash, and the values of the Author and
+    /// Date lines following the commit line. Placeholders accept a width and precision, e.g.
+    /// "{hash:.8}" for the first 8 characters of the hash. Values which are not present in the
+    /// input are replaced by the empty string. For example:
+    /// --commit-format='{label} {hash:.8} — {author}, {date}'. See also --hide-commit-metadata.
+    pub commit_format: Option<String>,
+
     #[clap(
         long = "commit-regex",
         default_value = r"^commit ",
         value_name = "REGEX"
     )]
     /// Regular expression used to identify the commit line when parsing git output.
+    ///
+    /// The regular expression is matched against the line with ANSI escape sequences removed. Use
+    /// it to recognize the commit lines of custom `git log --format` output. A capture group named
+    /// "commit" identifies the commit hash, which is then used for commit hyperlinks and the
+    /// "{commit}" placeholder of --hyperlinks-file-commit-link-format, e.g.
+    /// --commit-regex='^(?P<commit>[0-9a-f]{7,40}) '. Lines starting with "commit " are still
+    /// recognized as commit lines, unless --commit-regex-exclusive is given.
     pub commit_regex: String,
 
+    #[clap(long = "commit-regex-exclusive")]
+    /// Only recognize commit lines matching --commit-regex.
+    ///
+    /// By default lines starting with "commit ", as written by `git log` and `git show`, are
+    /// recognized as commit lines in addition to those matching --commit-regex.
+    pub commit_regex_exclusive: bool,
+
     #[clap(long = "commit-style", default_value = "raw", value_name = "STYLE")]
     /// Style string for the commit hash line.
     ///
@@ -306,6 +533,14 @@ pub struct Opt {
     /// set this in per-repository git config (.git/config)
     pub default_language: Option<String>,
 
+    #[clap(long = "detect-background")]
+    /// Query the terminal for its background color to choose between light and dark mode.
+    ///
+    /// Used when neither --light nor --dark is given. The terminal is asked for its background
+    /// color with an OSC 11 escape sequence; if it does not answer within a short timeout, or
+    /// output is not a terminal, light or dark mode is inferred from the syntax theme as usual.
+    pub detect_background: bool,
+
     #[clap(long = "diff-highlight")]
     /// Emulate diff-highlight.
     ///
@@ -324,6 +559,104 @@ pub struct Opt {
     /// If a relativized file path exceeds this width then the diff stat will be misaligned.
     pub diff_stat_align_width: usize,
 
+    #[clap(long = "diff-stat-rescale")]
+    /// Rescale the bars of a diff stat section to fill the width of the terminal, or --width.
+    ///
+    /// The longest bar of a `git diff --stat` section then extends to that width, and the other
+    /// bars are scaled in proportion. By default the bars have the lengths computed by git. The
+    /// `+` and `-` of the bars are painted with the colors of --plus-style and --minus-style.
+    pub diff_stat_rescale: bool,
+
+    #[clap(
+        long = "diff-stat-summary-style",
+        default_value = "normal",
+        value_name = "STYLE"
+    )]
+    /// Style string for the line ending a diff stat section.
+    ///
+    /// E.g. "3 files changed, 12 insertions(+), 4 deletions(-)". See STYLES section.
+    pub diff_stat_summary_style: String,
+
+    #[clap(long = "diff-summary")]
+    /// Write a line summarizing the changes at the end of the diff of each commit.
+    ///
+    /// The line counts the files, and the added and removed lines, of the hunks displayed by
+    /// delta, e.g. "Σ 14 files, +362 −121". It is written at the end of the input when the diff is
+    /// not part of `git log -p` output. It does not require git's --stat, and it is not written
+    /// for merge conflicts, nor in --color-only mode. See --diff-summary-style.
+    pub diff_summary: bool,
+
+    #[clap(
+        long = "diff-summary-style",
+        default_value = "bold",
+        value_name = "STYLE"
+    )]
+    /// Style string for the line written by --diff-summary.
+    ///
+    /// See STYLES section.
+    pub diff_summary_style: String,
+
+    #[clap(long = "dim-unchanged-words")]
+    /// Dim the unchanged sections of changed lines.
+    ///
+    /// In a removed or added line paired with a line from the other side, the sections which are
+    /// not emphasized as changed are displayed in minus-non-emph-style or plus-non-emph-style with
+    /// the dim attribute added, so that only the edits stand out.
+    pub dim_unchanged_words: bool,
+
+    #[clap(long = "dry-run")]
+    /// Report how delta would run, without reading input.
+    ///
+    /// Writes the resolved features, the pager command, whether light or dark mode is in effect,
+    /// the terminal width, and whether hyperlinks and 24-bit color are enabled to standard error,
+    /// and exits. Use --show-config for the values of all other options.
+    pub dry_run: bool,
+
+    #[clap(long = "emit-manifest", value_name = "PATH")]
+    /// Write a manifest of the changed files to PATH, for tools such as code review bots.
+    ///
+    /// The manifest is a JSON array with an object for each changed file, written and flushed as
+    /// the diff is processed, and closed when delta exits. Each object has the status of the file
+    /// (added, modified, renamed, copied or deleted), its old and new paths, the commit, if any,
+    /// the numbers of added and removed lines, and the line ranges of its hunks, e.g.
+    /// [{"status":"modified","old_path":"a.rs","new_path":"a.rs","commit":null,"insertions":1,
+    /// "deletions":1,"hunks":[{"old_start":1,"old_lines":3,"new_start":1,"new_lines":3}]}]. The
+    /// output of delta is unchanged.
+    pub emit_manifest: Option<String>,
+
+    #[clap(
+        long = "emph-mode",
+        default_value = "span",
+        value_name = "span|unique-tokens"
+    )]
+    /// Which sections of a changed line paired with a line from the other side are emphasized.
+    ///
+    /// With "span" the sections changed by the inferred word-level edits are emphasized. With
+    /// "unique-tokens" only the words between the longest common prefix and suffix of the two
+    /// lines are emphasized, as diff-highlight does: this reads better for small edits in long
+    /// lines. Lines which differ only in whitespace, e.g. reindented lines, are not emphasized.
+    pub emph_mode: String,
+
+    #[clap(long = "exclude", value_name = "GLOBS")]
+    /// Globs of paths to skip when diffing two directories (space-separated).
+    ///
+    /// A glob is matched against the path of each file and directory relative to the directories
+    /// being compared, and against its name: e.g. --exclude='.git *.lock' skips all .git
+    /// directories and lock files. "**" matches across directories.
+    pub exclude: Option<String>,
+
+    #[clap(
+        long = "exclude-files",
+        value_name = "PATTERN",
+        multiple_occurrences = true
+    )]
+    /// Do not display the diffs of the files matching PATTERN (may be repeated).
+    ///
+    /// The header, hunks and diff stat line of an excluded file are dropped from the output. See
+    /// --include-files for the syntax of PATTERN. A renamed file is displayed if either its old or
+    /// its new path is not excluded.
+    pub exclude_files: Vec<String>,
+
     #[clap(long = "features", value_name = "FEATURES")]
     /// Names of delta features to activate (space-separated).
     ///
@@ -334,6 +667,18 @@ pub struct Opt {
     /// side-by-side temporarily.
     pub features: Option<String>,
 
+    #[clap(long = "features-order", value_name = "FEATURES")]
+    /// Names of features in order of increasing precedence (space-separated).
+    ///
+    /// By default, the precedence of a feature depends on where it was enabled: features in
+    /// --features take precedence over builtin features enabled by flags such as --side-by-side,
+    /// which take precedence over features enabled in git config, and the features enabled by
+    /// another feature rank just below it. The enabled features named in this list are instead
+    /// applied after all others, strictly from left to right: when several of them set the same
+    /// option, the rightmost one wins. Names of features which are not enabled are ignored. Use
+    /// --show-config to see which feature set each style.
+    pub features_order: Option<String>,
+
     #[clap(
         long = "file-added-label",
         default_value = "added:",
@@ -341,7 +686,15 @@ pub struct Opt {
     )]
     /// Text to display before an added file path.
     ///
-    /// Used in the default value of navigate-regex.
+    /// Used in the default value of navigate-regex. This and the other file labels may contain the
+    /// placeholders "{path}", "{insertions}", "{deletions}" and "{hunks}", which are replaced by
+    /// the file path, the numbers of added and removed lines, and the number of hunks. For `svn
+    /// diff` output, "{old-revision}" and "{new-revision}" are replaced by the revisions compared,
+    /// e.g. "123" or "working copy". When the label contains "{path}", the path is not displayed
+    /// after the label. For example:
+    /// --file-modified-label='M ({insertions}+ {deletions}-)'. When a label displays counts, the
+    /// diff of each file is read before it is displayed. An empty label displays the path alone,
+    /// in the same style.
     pub file_added_label: String,
 
     #[clap(
@@ -350,6 +703,8 @@ pub struct Opt {
         value_name = "STRING"
     )]
     /// Text to display before a copied file path.
+    ///
+    /// See --file-added-label for placeholders.
     pub file_copied_label: String,
 
     #[clap(
@@ -370,9 +725,21 @@ pub struct Opt {
     )]
     /// Text to display before a modified file path.
     ///
-    /// Used in the default value of navigate-regex.
+    /// Used in the default value of navigate-regex. See --file-added-label for placeholders.
     pub file_modified_label: String,
 
+    #[clap(
+        long = "file-path-reminder-interval",
+        default_value = "0",
+        value_name = "N"
+    )]
+    /// Repeat the file header every N lines of the diff of a file.
+    ///
+    /// Once the file header has scrolled out of view in the pager, the file of the displayed hunk
+    /// lines is otherwise no longer visible. The reminder is a single line in file-style, dimmed.
+    /// Zero, the default, disables the reminders.
+    pub file_path_reminder_interval: usize,
+
     #[clap(
         long = "file-removed-label",
         default_value = "removed:",
@@ -380,9 +747,20 @@ pub struct Opt {
     )]
     /// Text to display before a removed file path.
     ///
-    /// Used in the default value of navigate-regex.
+    /// Used in the default value of navigate-regex. See --file-added-label for placeholders.
     pub file_removed_label: String,
 
+    #[clap(long = "file-renamed-format", value_name = "FMT")]
+    /// Format string for the file header of a renamed or copied file.
+    ///
+    /// The placeholders "{label}", "{old}", "{new}" and "{similarity}" will be replaced by
+    /// --file-renamed-label (or --file-copied-label for a copied file), the old and new paths, and
+    /// the similarity index of the two files as a percentage. For example:
+    /// --file-renamed-format='{label} {old} → {new} ({similarity}%)'. By default, the label and
+    /// the paths separated by --right-arrow are displayed. An empty label is omitted together with
+    /// the whitespace following it.
+    pub file_renamed_format: Option<String>,
+
     #[clap(
         long = "file-renamed-label",
         default_value = "renamed:",
@@ -390,7 +768,7 @@ pub struct Opt {
     )]
     /// Text to display before a renamed file path.
     ///
-    /// Used in the default value of navigate-regex.
+    /// Used in the default value of navigate-regex. See --file-added-label for placeholders.
     pub file_renamed_label: String,
 
     #[clap(long = "file-style", default_value = "blue", value_name = "STYLE")]
@@ -401,20 +779,80 @@ pub struct Opt {
 
     #[structopt(long = "file-transformation", value_name = "SED_CMD")]
     /// Sed-style command transforming file paths for display.
+    ///
+    /// Multiple commands can be given separated by ';' (use '\;' for a literal semicolon) and are
+    /// applied in order, e.g. 's,^packages/,,;s,/src/,/,'. Only the displayed path is
+    /// transformed: hyperlinks still point to the real file.
     pub file_regex_replacement: Option<String>,
 
+    #[clap(long = "generate-lesskey")]
+    /// Print a lesskey file binding n and N to the navigate regex.
+    ///
+    /// The bindings use the same regex as --navigate, so that custom --navigate-regex and label
+    /// values are taken into account; recommended less environment settings are included. Load
+    /// the file in the pager command, e.g. 'less --lesskey-src=FILE'. If the installed less is
+    /// too old to read lesskey source files (before version 582), then combine this with
+    /// --output=FILE to write a compiled lesskey file instead, to be loaded with 'less -k FILE'.
+    pub generate_lesskey: bool,
+
+    #[clap(
+        long = "git-diff-line-style",
+        default_value = "normal",
+        value_name = "STYLE"
+    )]
+    /// Style string for the diff line displayed by --show-git-diff-line.
+    ///
+    /// The style string may contain the special attributes 'box', 'underline' and 'overline', to
+    /// decorate the line. See STYLES section.
+    pub git_diff_line_style: String,
+
     #[clap(long = "grep-context-line-style", value_name = "STYLE")]
     /// Style string for non-matching lines of grep output.
     ///
     /// See STYLES section. Defaults to zero-style.
     pub grep_context_line_style: Option<String>,
 
+    #[clap(
+        long = "grep-context-separator",
+        default_value = "rule",
+        value_name = "STRING"
+    )]
+    /// How to display the separator lines between groups of grep matches.
+    ///
+    /// `grep -C`, `git grep -W` and `rg -C` emit a "--" line between non-adjacent groups of
+    /// lines (rg allows another string with --context-separator); for `rg --json` output, which
+    /// has no separator lines, delta emits "--" where rg would. The value "rule" draws a
+    /// horizontal rule across the decorations width, "keep" displays the separator as emitted, and
+    /// any other value is displayed in its place. See also --grep-context-separator-style.
+    pub grep_context_separator: String,
+
+    #[clap(
+        long = "grep-context-separator-style",
+        default_value = "dim",
+        value_name = "STYLE"
+    )]
+    /// Style string for the separator lines between groups of grep matches.
+    ///
+    /// See STYLES section and --grep-context-separator.
+    pub grep_context_separator_style: String,
+
     #[clap(long = "grep-file-style", value_name = "STYLE")]
     /// Style string for file paths in grep output.
     ///
     /// See STYLES section. Defaults to hunk-header-file-path-style.
     pub grep_file_style: Option<String>,
 
+    #[clap(
+        long = "grep-function-context-style",
+        default_value = "syntax dim",
+        value_name = "STYLE"
+    )]
+    /// Style string for the function lines of `git grep -W` output.
+    ///
+    /// These are the lines with "=" separators, which git grep emits for the first line of the
+    /// function containing a match. See STYLES section.
+    pub grep_function_context_style: String,
+
     #[clap(long = "grep-line-number-style", value_name = "STYLE")]
     /// Style string for line numbers in grep output.
     ///
@@ -445,6 +883,66 @@ pub struct Opt {
     /// lines: set this option to "keep" to keep the original separator symbols.
     pub grep_separator_symbol: String,
 
+    #[clap(
+        long = "grep-syntax-highlighting",
+        default_value = "true",
+        value_name = "true|false"
+    )]
+    /// Whether to syntax-highlight the lines of grep output.
+    ///
+    /// The language is determined by the extension of the file path of each line, or by
+    /// default-language. Since grep lines are not contiguous, each line is highlighted on its own,
+    /// so constructs spanning several lines, such as block comments, may be highlighted
+    /// inaccurately. The grep-match-word-style is applied on top of the syntax highlighting. Set
+    /// this to "false" to save the cost of highlighting large amounts of grep output.
+    pub grep_syntax_highlighting: String,
+
+    #[clap(long = "hexdump-binary", default_value = "0", value_name = "N")]
+    /// Show a hexdump of the first N bytes of a changed binary file.
+    ///
+    /// The bytes of the old and new versions of the file are shown side by side below the line
+    /// summarizing the change (see --binary-style), in the format of --binary-diff=hexdump. The
+    /// bytes are only available when they can be read from the repository or from the files on
+    /// disk.
+    pub hexdump_binary: usize,
+
+    #[clap(
+        long = "hidden-context-style",
+        default_value = "dim",
+        value_name = "STYLE"
+    )]
+    /// Style string for the line replacing unchanged lines hidden by --hide-context.
+    ///
+    /// See STYLES section.
+    pub hidden_context_style: String,
+
+    #[clap(long = "hide-commit-metadata")]
+    /// Remove the Author and Date lines following the commit line (requires --commit-format).
+    ///
+    /// Use this when --commit-format already displays the author and date.
+    pub hide_commit_metadata: bool,
+
+    #[clap(long = "hide-empty-commits")]
+    /// Drop the commits whose files are all hidden by --include-files or --exclude-files.
+    ///
+    /// By default the commit line and message of such a commit are still displayed.
+    pub hide_empty_commits: bool,
+
+    #[clap(
+        long = "hide-context",
+        value_name = "N",
+        min_values = 0,
+        require_equals = true,
+        default_missing_value = "0"
+    )]
+    /// Display only the removed and added lines of hunks, and at most N unchanged lines around them.
+    ///
+    /// The other unchanged lines of a hunk are replaced by a line saying how many were hidden,
+    /// e.g. "⋯ 17 unchanged lines", see --hidden-context-style. Without a value, all unchanged
+    /// lines are hidden. The line numbers of the lines displayed are not affected. Unchanged lines
+    /// are not hidden in --color-only mode.
+    pub hide_context: Option<String>,
+
     #[clap(
         long = "hunk-header-decoration-style",
         default_value = "blue box",
@@ -467,6 +965,13 @@ pub struct Opt {
     /// 'file' special attribute.
     pub hunk_header_file_style: String,
 
+    #[clap(long = "hunk-header-function-style", value_name = "STYLE")]
+    /// Style string for the function context part of the hunk-header.
+    ///
+    /// See STYLES section. This is the text following the line numbers of the hunk, usually the
+    /// line of the enclosing function or section. Defaults to hunk-header-style.
+    pub hunk_header_function_style: Option<String>,
+
     #[clap(
         long = "hunk-header-line-number-style",
         default_value = "blue",
@@ -513,10 +1018,27 @@ pub struct Opt {
     #[clap(long = "hyperlinks-commit-link-format", value_name = "FMT")]
     /// Format string for commit hyperlinks (requires --hyperlinks).
     ///
-    /// The placeholder "{commit}" will be replaced by the commit hash. For example:
-    /// --hyperlinks-commit-link-format='https://mygitrepo/{commit}/'
+    /// The placeholder "{commit}" will be replaced by the commit hash as displayed, which may be
+    /// abbreviated (e.g. by `git log --abbrev-commit`): forges such as GitHub and GitLab resolve
+    /// abbreviated hashes to the full commit. For example:
+    /// --hyperlinks-commit-link-format='https://mygitrepo/{commit}/'. If this is not set, the
+    /// format is inferred from the URL of the remote named by --hyperlinks-remote, provided it is
+    /// hosted by GitHub, GitLab, Bitbucket, Codeberg or gitea.com.
     pub hyperlinks_commit_link_format: Option<String>,
 
+    #[clap(long = "hyperlinks-file-commit-link-format", value_name = "FMT")]
+    /// Format string for hyperlinks to files as of a commit (requires --hyperlinks).
+    ///
+    /// Used instead of --hyperlinks-file-link-format for the files of a commit shown by e.g. `git
+    /// show` or `git log -p`, since the local file may differ from the file at that commit, or may
+    /// not exist at all. The placeholders "{commit}", "{path}", "{line}" and "{column}" will be
+    /// replaced by the commit hash, the path of the file relative to the root of the repository,
+    /// the line number and the column, respectively. For example:
+    /// --hyperlinks-file-commit-link-format='https://mygitrepo/blob/{commit}/{path}#L{line}'.
+    /// Files of diffs that are not part of a commit, such as those of `git diff`, still link to
+    /// the local file.
+    pub hyperlinks_file_commit_link_format: Option<String>,
+
     #[clap(
         long = "hyperlinks-file-link-format",
         default_value = "file://{path}",
@@ -524,16 +1046,137 @@ pub struct Opt {
     )]
     /// Format string for file hyperlinks (requires --hyperlinks).
     ///
-    /// The placeholders "{path}" and "{line}" will be replaced by the absolute file path and the
-    /// line number, respectively. The default value of this option creates hyperlinks using
-    /// standard file URLs; your operating system should open these in the application registered
-    /// for that file type. However, these do not make use of the line number. In order for the link
-    /// to open the file at the correct line number, you could use a custom URL format such as
-    /// "file-line://{path}:{line}" and register an application to handle the custom "file-line" URL
-    /// scheme by opening the file in your editor/IDE at the indicated line number. See
-    /// https://github.com/dandavison/open-in-editor for an example.
+    /// The placeholders "{path}", "{line}" and "{column}" will be replaced by the absolute file
+    /// path, the line number and the column, respectively. The column is known for the output of
+    /// `git grep --column`, `rg --column`, `rg --vimgrep` and `rg --json`; elsewhere it is 1. The
+    /// default value of this option creates hyperlinks using standard file URLs; your operating
+    /// system should open these in the application registered for that file type. However, these do
+    /// not make use of the line number. In order for the link to open the file at the correct line
+    /// number, you could use a custom URL format such as "file-line://{path}:{line}" and register an
+    /// application to handle the custom "file-line" URL scheme by opening the file in your
+    /// editor/IDE at the indicated line number. See https://github.com/dandavison/open-in-editor
+    /// for an example.
     pub hyperlinks_file_link_format: String,
 
+    #[clap(long = "hyperlinks-file-link-rules", value_name = "RULES")]
+    /// Per-path format strings for file hyperlinks (requires --hyperlinks).
+    ///
+    /// A list of rules of the form GLOB=FORMAT separated by ';', e.g.
+    /// "services/web/**=vscode://file/{path}:{line}; vendored/**=https://github.com/upstream/repo/blob/main/{path}#L{line}".
+    /// The globs are matched in order against the path of the file relative to the root of the
+    /// repository; "**" matches any number of directories and "*" and "?" match within a path
+    /// component. The format of the first matching rule is used in place of
+    /// --hyperlinks-file-commit-link-format and --hyperlinks-file-link-format, which apply to
+    /// files matching no rule. The placeholders are those of --hyperlinks-file-link-format, except
+    /// that in an "http://" or "https://" URL "{path}" is the path of the file relative to the root
+    /// of the repository, as in --hyperlinks-file-commit-link-format.
+    pub hyperlinks_file_link_rules: Option<String>,
+
+    #[clap(long = "hyperlinks-infer-remote")]
+    /// Link file names in the headers of committed changes to the file at that commit.
+    ///
+    /// Applies to output containing commit lines, such as that of `git show` and `git log -p`,
+    /// when the URL of the remote can be inferred, see --hyperlinks-commit-link-format. Other file
+    /// names link to the local file (requires --hyperlinks).
+    pub hyperlinks_infer_remote: bool,
+
+    #[clap(long = "hyperlinks-minus-line-numbers")]
+    /// Also hyperlink the line numbers of the old version of the file (requires --hyperlinks).
+    ///
+    /// By default only the line numbers of the new version of the file link to the file, since
+    /// these refer to the file as it exists locally. With this option the line numbers of the old
+    /// version link to the same file, using the old line number.
+    pub hyperlinks_minus_line_numbers: bool,
+
+    #[clap(
+        long = "hyperlinks-remote",
+        default_value = "origin",
+        value_name = "NAME"
+    )]
+    /// Name of the git remote from which hyperlink URLs are inferred.
+    ///
+    /// See --hyperlinks-commit-link-format and --hyperlinks-infer-remote.
+    pub hyperlinks_remote: String,
+
+    #[clap(long = "idempotent")]
+    /// Pass input which is already delta's output through unchanged.
+    ///
+    /// Without this, running delta on its own output, e.g. when delta is invoked twice in a
+    /// pipeline, mangles it. The input is recognized as delta's output by the decorations of the
+    /// first file or hunk, which replace the "diff", "---", "+++" and "@@" lines of a diff; if
+    /// these have been removed by a style such as 'omit', the input is processed as usual.
+    pub idempotent: bool,
+
+    #[clap(
+        long = "image-preview-max-height",
+        default_value = "10",
+        value_name = "N"
+    )]
+    /// Height, in terminal cells, of the image previews displayed by --image-previews.
+    pub image_preview_max_height: usize,
+
+    #[clap(
+        long = "image-previews",
+        default_value = "off",
+        value_name = "auto|kitty|iterm2|off"
+    )]
+    /// Display a preview of changed image files below their file header.
+    ///
+    /// The new version of a PNG, JPEG or GIF file in the worktree is displayed using the graphics
+    /// protocol of the terminal: "kitty" for the kitty graphics protocol, which only supports PNG
+    /// images, and "iterm2" for iTerm2's inline images, also supported by e.g. WezTerm. With
+    /// "auto" the protocol is chosen from the TERM and TERM_PROGRAM environment variables.
+    /// Previews are never displayed when the output is not a terminal, or when it goes through
+    /// less, which does not pass the images through: use e.g. --paging=never. See
+    /// --image-preview-max-height.
+    pub image_previews: String,
+
+    #[clap(
+        long = "include-files",
+        value_name = "PATTERN",
+        multiple_occurrences = true
+    )]
+    /// Display only the diffs of the files matching PATTERN (may be repeated).
+    ///
+    /// PATTERN is a glob, matched against the path of a file relative to the repository root and
+    /// against its name, in which "**" matches across directories: e.g. --include-files='src/**'.
+    /// A PATTERN starting with "regex:" is a regular expression searched for in the path, e.g.
+    /// --include-files='regex:^(src|lib)/'. A renamed file is displayed if either its old or its
+    /// new path matches. The diffs of other files, including their diff stat lines, are dropped
+    /// from the output; commit lines are still displayed, see --hide-empty-commits. Files are not
+    /// filtered in --color-only mode. See --exclude-files.
+    pub include_files: Vec<String>,
+
+    #[clap(
+        long = "index-line-hash-style",
+        default_value = "index-line-style",
+        value_name = "STYLE"
+    )]
+    /// Style string for the object names of the two versions of a file in the "index" line.
+    ///
+    /// See --keep-index-line.
+    pub index_line_hash_style: String,
+
+    #[clap(
+        long = "index-line-mode-style",
+        default_value = "index-line-style",
+        value_name = "STYLE"
+    )]
+    /// Style string for the file mode in the "index" line.
+    ///
+    /// See --keep-index-line.
+    pub index_line_mode_style: String,
+
+    #[clap(
+        long = "index-line-style",
+        default_value = "normal",
+        value_name = "STYLE"
+    )]
+    /// Style string for the "index" line of the diff header.
+    ///
+    /// See --keep-index-line.
+    pub index_line_style: String,
+
     #[clap(
         long = "inline-hint-style",
         default_value = "blue",
@@ -545,6 +1188,14 @@ pub struct Opt {
     /// to highlight tabs, and the symbols used to indicate wrapped lines. See STYLES section.
     pub inline_hint_style: String,
 
+    #[clap(long = "input", value_name = "PATH")]
+    /// Read the diff from a file instead of standard input.
+    ///
+    /// A file compressed with gzip or zstd (e.g. file.diff.gz) is detected by its first bytes and
+    /// decompressed as it is read, when delta is built with the compressed-input feature. Other
+    /// files are read unchanged.
+    pub input: Option<String>,
+
     #[clap(
         long = "inspect-raw-lines",
         default_value = "true",
@@ -557,6 +1208,44 @@ pub struct Opt {
     /// Git's --color-moved feature. Set this to "false" to disable this behavior.
     pub inspect_raw_lines: String,
 
+    #[clap(
+        long = "inspect-raw-lines-threshold",
+        default_value = "0",
+        value_name = "N"
+    )]
+    /// Keep the ANSI styling of lines colored by something other than git.
+    ///
+    /// With --inspect-raw-lines, a line is emitted with its original styling if it contains at
+    /// least N sections styled by SGR sequences other than git's diff coloring, e.g. because it
+    /// was pre-colored by a custom git diff driver. Sections consisting only of whitespace, which
+    /// git colors to highlight whitespace errors, are not counted. The default of 0 disables this
+    /// check: only lines not starting with git's diff coloring, such as moved lines, are emitted
+    /// with their original styling.
+    pub inspect_raw_lines_threshold: usize,
+
+    #[clap(long = "keep-binary-patch")]
+    /// Keep the GIT binary patch emitted by `git diff --binary`.
+    ///
+    /// By default the base85-encoded data of a binary patch is replaced by the line summarizing
+    /// the change to the binary file, see --binary-style.
+    pub keep_binary_patch: bool,
+
+    #[clap(long = "keep-index-line")]
+    /// Keep the "index" line of the diff header, e.g. "index 663a5d4..a59fe84 100644".
+    ///
+    /// By default it is dropped. With this option it is emitted before the file header, styled
+    /// with --index-line-style, the object names of the two versions of the file with
+    /// --index-line-hash-style and the file mode with --index-line-mode-style.
+    pub keep_index_line: bool,
+
+    #[clap(long = "keep-mode-lines")]
+    /// Keep the raw file mode lines of the diff header.
+    ///
+    /// By default the "old mode", "new mode" and "new file mode" lines are replaced by the line
+    /// summarizing the mode change, see --mode-change-style. With this option they are emitted
+    /// unchanged before the file header.
+    pub keep_mode_lines: bool,
+
     #[clap(long = "keep-plus-minus-markers")]
     /// Prefix added/removed lines with a +/- character, as git does.
     ///
@@ -564,6 +1253,13 @@ pub struct Opt {
     /// output.
     pub keep_plus_minus_markers: bool,
 
+    #[clap(long = "language", value_name = "LANG", requires = "sample-file")]
+    /// Language used to highlight --sample-file in the --show-syntax-themes preview.
+    ///
+    /// Either a language name or a file extension, as listed by --list-languages. Defaults to the
+    /// language inferred from the extension of the sample file.
+    pub language: Option<String>,
+
     #[clap(long = "light")]
     /// Use default colors appropriate for a light terminal background.
     ///
@@ -616,6 +1312,17 @@ pub struct Opt {
     /// See STYLES and LINE NUMBERS sections.
     pub line_numbers_left_style: String,
 
+    #[clap(long = "line-numbers-min-width", default_value = "0", value_name = "N")]
+    /// Minimum width of the line number columns.
+    ///
+    /// By default (0) the widths of the default formats are replaced by 1, and the line numbers of
+    /// other formats are padded to the widths given in --line-numbers-left-format and
+    /// --line-numbers-right-format. Otherwise the widths of all formats are replaced by N. Columns
+    /// whose width is replaced are only as wide as the largest line number in the hunk headers of
+    /// each file so far requires: they do not shift within a hunk, and only widen at the hunk
+    /// headers of a file. See LINE NUMBERS section.
+    pub line_numbers_min_width: usize,
+
     #[clap(
         long = "line-numbers-minus-style",
         default_value = "auto",
@@ -636,6 +1343,15 @@ pub struct Opt {
     /// See STYLES and LINE NUMBERS sections.
     pub line_numbers_plus_style: String,
 
+    #[clap(long = "line-numbers-relative")]
+    /// Display line numbers relative to the first change of each hunk.
+    ///
+    /// The first changed line of each hunk shows its absolute line number, the lines before and
+    /// after it show their distance to it, as with vim's 'relativenumber'. This replaces the {nm} and {np} placeholders of --line-numbers-left-format
+    /// and --line-numbers-right-format by {rm} and {rp}, unless a format already uses the latter.
+    /// Requires --line-numbers or --side-by-side.
+    pub line_numbers_relative: bool,
+
     #[clap(
         long = "line-numbers-right-format",
         default_value = "{np:^4}│",
@@ -676,28 +1392,83 @@ pub struct Opt {
     /// List available syntax-highlighting color themes.
     pub list_syntax_themes: bool,
 
+    #[clap(long = "list-themes")]
+    /// List the builtin themes available for --theme.
+    pub list_themes: bool,
+
     #[clap(long = "map-styles", value_name = "STYLES_MAP")]
     /// Map styles encountered in raw input to desired output styles.
     ///
     /// An example is --map-styles='bold purple => red "#eeeeee", bold cyan => syntax "#eeeeee"'
     pub map_styles: Option<String>,
 
+    #[clap(
+        long = "long-line-display",
+        default_value = "wrap",
+        value_name = "wrap|truncate|truncate-left"
+    )]
+    /// How to display lines which are too long to fit.
+    ///
+    /// With "wrap" long lines are wrapped in side-by-side mode, see --wrap-max-lines. With
+    /// "truncate" they are truncated as set by --truncation-side. "truncate-left" is short for
+    /// "truncate" with --truncation-side=left, so that the end of the line is visible. The available
+    /// width is the panel width in side-by-side mode and the terminal width otherwise.
+    pub long_line_display: String,
+
     #[clap(long = "max-line-distance", default_value = "0.6", value_name = "DIST")]
     /// Maximum line pair distance parameter in within-line diff algorithm.
     ///
     /// This parameter is the maximum distance (0.0 - 1.0) between two lines for them to be inferred
     /// to be homologous. Homologous line pairs are highlighted according to the deletion and
-    /// insertion operations transforming one into the other.
-    pub max_line_distance: f64,
+    /// insertion operations transforming one into the other. With "auto" the maximum distance
+    /// depends on the number of words of the lines: it is larger for short lines, in which even a
+    /// large edit is readable, and smaller for long lines.
+    pub max_line_distance: String,
 
     #[clap(long = "max-line-length", default_value = "512", value_name = "N")]
     /// Truncate lines longer than this.
     ///
-    /// To prevent any truncation, set to zero. Note that delta will be slow on very long lines
-    /// (e.g. minified .js) if truncation is disabled. When wrapping lines it is automatically set
-    /// to fit at least all visible characters.
+    /// To prevent any truncation, set to zero: long lines are then displayed according to
+    /// --long-line-display. Note that delta will be slow on very long lines (e.g. minified .js) if
+    /// truncation is disabled, unless --max-syntax-highlighting-length is set. When wrapping lines
+    /// it is automatically set to fit at least all visible characters. Unless --truncation-side is
+    /// "right" lines are not truncated, since their end is displayed.
     pub max_line_length: usize,
 
+    #[clap(
+        long = "max-syntax-highlighting-length",
+        default_value = "0",
+        value_name = "N"
+    )]
+    /// Do not syntax-highlight lines longer than this.
+    ///
+    /// Such lines are still displayed in full, in the plain style of their kind, since syntax
+    /// highlighting is what makes very long lines, e.g. of minified code or data, slow. This is
+    /// independent of --max-line-length, which may be larger. Zero means no limit.
+    pub max_syntax_highlighting_length: usize,
+
+    #[clap(
+        long = "merge-conflict-base-decoration-style",
+        default_value = "box",
+        value_name = "STYLE"
+    )]
+    /// Style string for the decoration of the header above the base section of a merge conflict.
+    ///
+    /// See --merge-conflict-base-style. The style string should contain one of the special
+    /// attributes 'box', 'ul' (underline), 'ol' (overline), or the combination 'ul ol'.
+    pub merge_conflict_base_decoration_style: String,
+
+    #[clap(
+        long = "merge-conflict-base-style",
+        default_value = "normal",
+        value_name = "STYLE"
+    )]
+    /// Style string for the header above the base section of a merge conflict.
+    ///
+    /// The base section, marked by "|||||||" with merge.conflictStyle=diff3 or zdiff3, is
+    /// displayed as a block of its own with --merge-conflict-display=blocks. See STYLES section.
+    pub merge_conflict_base_style: String,
+
     #[clap(
         long = "merge-conflict-begin-symbol",
         default_value = "▼",
@@ -708,6 +1479,21 @@ pub struct Opt {
     /// The string will be repeated until it reaches the required length.
     pub merge_conflict_begin_symbol: String,
 
+    #[clap(
+        long = "merge-conflict-display",
+        default_value = "derived-diffs",
+        value_name = "blocks|derived-diffs"
+    )]
+    /// How to display a merge conflict.
+    ///
+    /// With "derived-diffs" the diffs between the ancestral commit and each of the 'ours' and
+    /// 'theirs' branches are displayed. With "blocks" the lines of the 'ours' branch, of the base
+    /// (if the conflict has a base section, as with merge.conflictStyle=diff3 or zdiff3) and of
+    /// the 'theirs' branch are displayed as three blocks, each below a header naming it. With
+    /// --side-by-side the 'ours' and 'theirs' lines are displayed in the left and right panels
+    /// instead, below the base section.
+    pub merge_conflict_display: String,
+
     #[clap(
         long = "merge-conflict-end-symbol",
         default_value = "▲",
@@ -763,6 +1549,37 @@ pub struct Opt {
     /// STYLES section.
     pub merge_conflict_theirs_diff_header_style: String,
 
+    #[clap(long = "merge-parent-palette", value_name = "COLORS")]
+    /// Foreground colors of the lines of each parent in a combined diff (space-separated string).
+    ///
+    /// The lines of a combined diff, e.g. of a merge commit shown by `git show`, have one +/-
+    /// marker column per parent. The marker in the column of the Nth parent is painted with the
+    /// Nth color, and so is the code of a line changed relative to the Nth parent only, e.g. '+ '
+    /// or ' -', rather than being syntax highlighted; colors are recycled as needed. Lines changed
+    /// relative to several parents are painted with minus-style and plus-style. Defaults to
+    /// 'cyan magenta', or 'blue magenta' in light mode; an empty string disables it.
+    pub merge_parent_palette: Option<String>,
+
+    #[clap(long = "min-emph-length", default_value = "0", value_name = "N")]
+    /// Minimum width of an emphasized section of a line.
+    ///
+    /// Changed sections of homologous lines narrower than this, ignoring surrounding whitespace,
+    /// are not emphasized, so that a change of a character or two in otherwise identical lines is
+    /// not highlighted. By default all changed sections are emphasized.
+    pub min_emph_length: usize,
+
+    #[clap(
+        long = "minus-empty-line-marker",
+        default_value = "",
+        value_name = "STRING"
+    )]
+    /// Marker displayed in place of the content of removed empty lines.
+    ///
+    /// By default removed empty lines are only marked by a background color, see
+    /// --minus-empty-line-marker-style. If a marker such as "⏎" is given, it is displayed with
+    /// that style whenever a removed line is empty.
+    pub minus_empty_line_marker: String,
+
     #[clap(
         long = "--minus-empty-line-marker-style",
         default_value = "normal auto",
@@ -770,7 +1587,7 @@ pub struct Opt {
     )]
     /// Style string for removed empty line marker.
     ///
-    /// Used only if --minus-style has no background color.
+    /// Used only if --minus-style has no background color, or if --minus-empty-line-marker is set.
     pub minus_empty_line_marker_style: String,
 
     #[clap(
@@ -803,6 +1620,29 @@ pub struct Opt {
     /// See STYLES section.
     pub minus_style: String,
 
+    #[clap(long = "minus-symbol", value_name = "STRING")]
+    /// Symbol prefixing removed lines.
+    ///
+    /// E.g. "▍", which is colored by minus-style, for a bar in the gutter marking the changed lines.
+    /// Given this option, removed lines are prefixed by the symbol whether or not
+    /// --keep-plus-minus-markers is given, and the empty string means no prefix. Unchanged lines
+    /// are prefixed by spaces, so that the code of all lines is aligned. Defaults to "-" with
+    /// --keep-plus-minus-markers.
+    pub minus_symbol: Option<String>,
+
+    #[clap(
+        long = "mode-change-style",
+        default_value = "yellow",
+        value_name = "STYLE"
+    )]
+    /// Style string for the line summarizing a file mode change.
+    ///
+    /// A change of file permissions is shown below the file header as a line such as
+    /// "mode changed: 644 → 755 (+x)", where "+x" and "-x" indicate that the file gained or lost
+    /// its executable bit. A new executable file is shown as "new file mode: 755 (+x)". Use
+    /// "omit" to hide the mode change. See STYLES section.
+    pub mode_change_style: String,
+
     #[clap(long = "navigate")]
     /// Activate diff navigation.
     ///
@@ -810,6 +1650,20 @@ pub struct Opt {
     /// --file-modified-label, --file-removed-label, --file-added-label, --file-renamed-label.
     pub navigate: bool,
 
+    #[clap(
+        long = "navigate-granularity",
+        default_value = "hunk",
+        value_name = "commit|file|hunk"
+    )]
+    /// Whether n and N jump between commits, files, or hunks in navigate mode.
+    ///
+    /// With "hunk" the --hunk-label marker is displayed before each hunk header and included in
+    /// the default value of navigate-regex. With "file" only commit lines and the file labels are
+    /// used, which is quicker for reviewing diffs touching many files. With "commit" only commit
+    /// lines are used. Files whose label is empty cannot be navigated to. An explicit
+    /// --navigate-regex takes precedence over this option.
+    pub navigate_granularity: String,
+
     #[clap(long = "navigate-regex", value_name = "REGEX")]
     /// Regular expression defining navigation stop points.
     pub navigate_regex: Option<String>,
@@ -820,22 +1674,89 @@ pub struct Opt {
     /// See GIT CONFIG section.
     pub no_gitconfig: bool,
 
+    #[clap(long = "only-in-style", value_name = "STYLE")]
+    /// Style string for the "Only in" lines of `diff -r` output.
+    ///
+    /// These lines name a file which is in only one of the directories compared. Delta displays
+    /// them as notices that the file was removed or added, labeled with --file-removed-label or
+    /// --file-added-label. See STYLES section. Defaults to file-style.
+    pub only_in_style: Option<String>,
+
+    #[clap(long = "output", value_name = "PATH")]
+    /// Write output to a file instead of the terminal.
+    ///
+    /// The file is created or truncated, and written with all styling (ANSI escape sequences)
+    /// included. No pager is used.
+    pub output: Option<String>,
+
     #[clap(long = "pager", value_name = "CMD")]
     /// Which pager to use.
     ///
     /// The default pager is `less`. You can also change pager by setting the environment variables
     /// DELTA_PAGER, BAT_PAGER, or PAGER (and that is their order of priority). This option
-    /// overrides all environment variables above.
+    /// overrides all environment variables above, but DELTA_PAGER overrides a pager set in git
+    /// config.
+    ///
+    /// The command is split into words as a shell would, respecting quotes and escapes, and may
+    /// start with environment variables to set for the pager, e.g. `env LESS= less -R`. See
+    /// --show-config for the command line that will be run.
     pub pager: Option<String>,
 
+    #[clap(
+        long = "pager-fallbacks",
+        default_value = "less,more",
+        value_name = "CMDS"
+    )]
+    /// Pagers to try, in order, if the pager cannot be started.
+    ///
+    /// A comma-separated list of pager commands. If none of them can be started either, the output
+    /// is written to the terminal without a pager. A warning names the fallback used. Pagers
+    /// running the same program as the pager, or as an earlier fallback, are not tried again.
+    pub pager_fallbacks: String,
+
+    #[clap(long = "pager-less-options", value_name = "OPTIONS")]
+    /// Behaviors of less to turn on or off, when the pager is less.
+    ///
+    /// A comma-separated list of: mouse (scroll with the mouse wheel), wrap (wrap long lines,
+    /// rather than chopping them), and quit-if-one-screen (exit if the output fits on one
+    /// screen). Each may be prefixed by "no-" to turn it off, e.g.
+    /// --pager-less-options='mouse,no-wrap'. They are translated to the corresponding less flags
+    /// and take precedence over those of the pager command. quit-if-one-screen and
+    /// no-quit-if-one-screen are the same as --pager-quit-if-one-screen=true and false, which
+    /// takes precedence unless it is "auto". If the pager is not less, they are ignored with a
+    /// warning.
+    pub pager_less_options: Option<String>,
+
+    #[clap(
+        long = "pager-quit-if-one-screen",
+        default_value = "auto",
+        value_name = "true|false|auto"
+    )]
+    /// Whether less exits immediately if the output fits on one screen.
+    ///
+    /// With "true", delta passes --quit-if-one-screen to less, together with --no-init for
+    /// versions of less which need it to display the output before exiting. With "false", delta
+    /// removes -F and --quit-if-one-screen from the arguments of the pager command, and passes -+F
+    /// so that a -F in the LESS environment variable is overridden too. With "auto", less quits if
+    /// one screen when --paging is "auto" and its arguments are not those of the pager command,
+    /// unless --pager-less-options has quit-if-one-screen or no-quit-if-one-screen. The arguments
+    /// of pagers other than less are never changed.
+    pub pager_quit_if_one_screen: String,
+
     #[clap(
         long = "paging",
         default_value = "auto",
-        value_name = "auto|always|never"
+        value_name = "auto|always|never|internal"
     )]
     /// Whether to use a pager when displaying output.
     ///
-    /// Options are: auto, always, and never.
+    /// Options are: auto, always, never, and internal. "internal" uses a minimal pager built into
+    /// delta instead of --pager, which displays the first screen before the whole input has been
+    /// read. It scrolls with the arrow keys, PgUp, PgDn, g and G, searches with / (n and N jump to
+    /// the next and previous match, or with --navigate to the next and previous section), and
+    /// quits with q. The internal pager is also used when no pager can be started, and is not used
+    /// when the output is not a terminal. --pager-quit-if-one-screen=true makes it quit when the
+    /// output fits on one screen.
     pub paging_mode: String,
 
     #[clap(long = "parse-ansi")]
@@ -855,6 +1776,18 @@ pub struct Opt {
     /// See STYLES section.
     pub plus_emph_style: String,
 
+    #[clap(
+        long = "plus-empty-line-marker",
+        default_value = "",
+        value_name = "STRING"
+    )]
+    /// Marker displayed in place of the content of added empty lines.
+    ///
+    /// By default added empty lines are only marked by a background color, see
+    /// --plus-empty-line-marker-style. If a marker such as "⏎" is given, it is displayed with
+    /// that style whenever an added line is empty.
+    pub plus_empty_line_marker: String,
+
     #[clap(
         long = "plus-empty-line-marker-style",
         default_value = "normal auto",
@@ -862,7 +1795,7 @@ pub struct Opt {
     )]
     /// Style string for added empty line marker.
     ///
-    /// Used only if --plus-style has no background color.
+    /// Used only if --plus-style has no background color, or if --plus-empty-line-marker is set.
     pub plus_empty_line_marker_style: String,
 
     #[clap(
@@ -885,12 +1818,87 @@ pub struct Opt {
     /// See STYLES section.
     pub plus_style: String,
 
+    #[clap(long = "plus-symbol", value_name = "STRING")]
+    /// Symbol prefixing added lines.
+    ///
+    /// See --minus-symbol. Defaults to "+" with --keep-plus-minus-markers.
+    pub plus_symbol: Option<String>,
+
+    #[clap(long = "preserve-context-ansi")]
+    /// Keep the ANSI styling of unchanged lines received as input.
+    ///
+    /// By default delta removes color escape sequences from context lines and applies
+    /// zero-style, including syntax highlighting. With this option, context lines which contain
+    /// ANSI escape sequences, e.g. because they were colored by a custom diff tool, are emitted
+    /// with their original styling instead (subject to --map-styles). Added and removed lines are
+    /// not affected.
+    pub preserve_context_ansi: bool,
+
+    #[clap(long = "plain")]
+    /// Remove all styling from the output, but keep its layout.
+    ///
+    /// No ANSI escape sequences (colors, text attributes, hyperlinks) are emitted, whereas
+    /// structural transformations such as decorations, line numbers and side-by-side columns are
+    /// kept. This is useful for piping delta's output into text-based tools. Unlike --raw, which
+    /// passes the input through unaltered, the diff is still reformatted by delta.
+    pub plain: bool,
+
+    #[clap(
+        long = "range-diff-added-style",
+        default_value = "green",
+        value_name = "STYLE"
+    )]
+    /// Style string for the lines of `git range-diff` output pairing commits which are only in the new range.
+    ///
+    /// These are the lines with the ">" marker. See STYLES section.
+    pub range_diff_added_style: String,
+
+    #[clap(
+        long = "range-diff-modified-style",
+        default_value = "yellow",
+        value_name = "STYLE"
+    )]
+    /// Style string for the lines of `git range-diff` output pairing commits whose patch differs between the ranges.
+    ///
+    /// These are the lines with the "!" marker. See STYLES section.
+    pub range_diff_modified_style: String,
+
+    #[clap(
+        long = "range-diff-removed-style",
+        default_value = "red",
+        value_name = "STYLE"
+    )]
+    /// Style string for the lines of `git range-diff` output pairing commits which are only in the old range.
+    ///
+    /// These are the lines with the "<" marker. See STYLES section.
+    pub range_diff_removed_style: String,
+
+    #[clap(
+        long = "range-diff-unchanged-style",
+        default_value = "normal",
+        value_name = "STYLE"
+    )]
+    /// Style string for the lines of `git range-diff` output pairing commits whose patch is the same in both ranges.
+    ///
+    /// These are the lines with the "=" marker. See STYLES section.
+    pub range_diff_unchanged_style: String,
+
     #[clap(long = "raw")]
     /// Do not alter the input in any way.
     ///
     /// This is mainly intended for testing delta.
     pub raw: bool,
 
+    #[clap(long = "raw-file-glob", value_name = "GLOBS")]
+    /// Paint the files whose path matches one of these globs (comma-separated) without highlighting.
+    ///
+    /// The added and removed lines of such a file are painted with plus-style and minus-style only:
+    /// they are not syntax highlighted, their within-line edits are not inferred, and long lines
+    /// are not wrapped. File and hunk headers and line numbers are displayed as usual. This speeds
+    /// up delta for large generated files, e.g. --raw-file-glob='*.lock,*.min.js,package-lock.json'.
+    /// Globs are matched as for --collapse-file-glob, which can be used independently.
+    pub raw_file_glob: Option<String>,
+
     #[clap(long = "relative-paths")]
     /// Output all file paths relative to the current directory.
     ///
@@ -903,6 +1911,13 @@ pub struct Opt {
     /// For example, a unified diff heading, a rename, or a chmod.
     pub right_arrow: String,
 
+    #[clap(long = "sample-file", value_name = "PATH")]
+    /// File whose contents are used for the --show-syntax-themes preview.
+    ///
+    /// The file is displayed as if it had been added in a diff, so that the preview shows code in
+    /// the language you actually work in. See also --language.
+    pub sample_file: Option<PathBuf>,
+
     #[clap(long = "show-colors")]
     /// Show available named colors.
     ///
@@ -918,11 +1933,19 @@ pub struct Opt {
     /// --zero-style, --plus-style, --light, --dark, etc.
     pub show_config: bool,
 
+    #[clap(long = "show-git-diff-line")]
+    /// Display the diff line starting the diff of each file.
+    ///
+    /// The line, e.g. "diff --git a/src/main.rs b/src/main.rs", is displayed as it is received,
+    /// in --git-diff-line-style, above the file header. By default it is not displayed.
+    pub show_git_diff_line: bool,
+
     #[clap(long = "show-syntax-themes")]
     /// Show example diff for available syntax-highlighting themes.
     ///
     /// If diff output is supplied on standard input then this will be used for the demo. For
-    /// example: `git show | delta --show-syntax-themes`.
+    /// example: `git show | delta --show-syntax-themes`. Use --sample-file to preview your own code
+    /// instead, and --theme-filter to only show some of the themes.
     pub show_syntax_themes: bool,
 
     #[clap(long = "show-themes")]
@@ -940,12 +1963,102 @@ pub struct Opt {
     /// Display diffs in side-by-side layout.
     pub side_by_side: bool,
 
+    #[clap(
+        long = "side-by-side-align",
+        default_value = "content",
+        value_name = "none|content"
+    )]
+    /// How removed and added lines are arranged in the side-by-side panels.
+    ///
+    /// "content" is delta's usual layout: a removed line and an added line are displayed in the same
+    /// row only if delta infers that one is an edit of the other (see --max-line-distance), and
+    /// other lines get a row of their own with the opposite panel left blank. "none" ignores this
+    /// inference for the layout: the removed and added lines of a change are paired row by row in
+    /// order, which takes less space but may put unrelated lines next to each other. Within-line
+    /// highlighting is the same with both values.
+    pub side_by_side_align: String,
+
+    #[clap(
+        long = "side-by-side-left-percent",
+        default_value = "50",
+        value_name = "PERCENT"
+    )]
+    /// Width of the left panel in side-by-side mode, as a percentage of the total width.
+    ///
+    /// The right panel takes the remaining width. Must be between 0% and 100% (exclusive), e.g.
+    /// 40 or 40%.
+    pub side_by_side_left_percent: String,
+
+    #[clap(long = "side-by-side-wrap", value_name = "wrap|truncate|truncate-left")]
+    /// How to display lines which are too long to fit in a side-by-side panel.
+    ///
+    /// Takes the same values as --long-line-display, which it overrides in side-by-side mode, so
+    /// that e.g. lines can be truncated in side-by-side mode, where the panels are narrow, while
+    /// left as they are in unified mode. Defaults to the value of --long-line-display. If the line
+    /// numbers take the whole width of a panel, only they are displayed.
+    pub side_by_side_wrap: Option<String>,
+
+    #[clap(long = "stdin-label", value_name = "TEXT")]
+    /// Text of a banner line written before the diff.
+    ///
+    /// This identifies the diff received on standard input, e.g. when delta output is captured in
+    /// a report: `git diff | delta --stdin-label="Changes since v1.2"`. It is distinct from the
+    /// file headers within the diff. See --stdin-label-style. Nothing is written in --color-only
+    /// mode.
+    pub stdin_label: Option<String>,
+
+    #[clap(
+        long = "stdin-label-style",
+        default_value = "bold",
+        value_name = "STYLE"
+    )]
+    /// Style string for the banner line written by --stdin-label.
+    ///
+    /// The style string may contain the special attributes 'box', 'underline' and 'overline', to
+    /// decorate the banner. See STYLES section.
+    pub stdin_label_style: String,
+
+    #[clap(
+        long = "submodule-commit-added-style",
+        default_value = "green",
+        value_name = "STYLE"
+    )]
+    /// Style string for the commits added to a submodule.
+    ///
+    /// With `git diff --submodule=log`, the commits of a changed submodule are listed after its
+    /// "Submodule" line, those added preceded by ">". See STYLES section.
+    pub submodule_commit_added_style: String,
+
+    #[clap(
+        long = "submodule-commit-removed-style",
+        default_value = "red",
+        value_name = "STYLE"
+    )]
+    /// Style string for the commits removed from a submodule.
+    ///
+    /// With `git diff --submodule=log`, the commits of a changed submodule are listed after its
+    /// "Submodule" line, those removed, e.g. by a rewind, preceded by "<". See STYLES section.
+    pub submodule_commit_removed_style: String,
+
+    #[clap(
+        long = "submodule-style",
+        default_value = "file-style",
+        value_name = "STYLE"
+    )]
+    /// Style string for the border drawn to the left of the diff of a submodule.
+    ///
+    /// With `git diff --submodule=diff`, the diffs of the files of a changed submodule follow a
+    /// "Submodule" line. Delta displays them indented by a vertical bar in this style, so that
+    /// they read as belonging to the submodule. See STYLES section.
+    pub submodule_style: String,
+
     #[clap(long = "syntax-theme", value_name = "SYNTAX_THEME")]
     /// The syntax-highlighting theme to use.
     ///
     /// Use --show-syntax-themes to demo available themes. Defaults to the value of the BAT_THEME
     /// environment variable, if that contains a valid theme name. --syntax-theme=none disables all
-    /// syntax highlighting.
+    /// syntax highlighting. Case is ignored in theme names; an unknown name is an error, which
+    /// suggests the closest theme names.
     pub syntax_theme: Option<String>,
 
     #[clap(long = "tabs", default_value = "4", value_name = "N")]
@@ -957,6 +2070,21 @@ pub struct Opt {
     /// incorrect.
     pub tab_width: usize,
 
+    #[clap(long = "theme", value_name = "NAME")]
+    /// Use a builtin theme: a coordinated set of delta styles.
+    ///
+    /// A theme sets the styles of removed and added lines, line numbers, hunk headers, file and
+    /// commit decorations. Unlike --syntax-theme it does not affect syntax highlighting. Options
+    /// set on the command line, in git config or by --features take precedence over the theme.
+    /// Use --list-themes to list the available themes.
+    pub theme: Option<String>,
+
+    #[clap(long = "theme-filter", value_name = "STRING")]
+    /// Only show the syntax themes whose names contain STRING in the --show-syntax-themes preview.
+    ///
+    /// The match is case-insensitive.
+    pub theme_filter: Option<String>,
+
     #[clap(
         long = "true-color",
         default_value = "auto",
@@ -971,6 +2099,60 @@ pub struct Opt {
     /// need to do anything.
     pub true_color: String,
 
+    #[clap(
+        long = "truncation-side",
+        default_value = "right",
+        value_name = "right|left|middle"
+    )]
+    /// Which part of a line which is too long is removed when it is truncated.
+    ///
+    /// With "right" the end of the line is removed, with "left" its beginning, and with "middle"
+    /// its middle, so that both ends stay visible. The removed part is marked by
+    /// --truncation-symbol. Applies to lines truncated by --long-line-display=truncate and, in
+    /// side-by-side mode, to lines which are still too long after wrapping. With "left" or "middle"
+    /// file headers which are too wide for the terminal are truncated too, e.g. to keep both ends of
+    /// a long path visible.
+    pub truncation_side: String,
+
+    #[clap(long = "truncation-symbol", default_value = "→", value_name = "STRING")]
+    /// Symbol marking the removed part of a truncated line.
+    ///
+    /// The symbol is displayed in reverse video. See --truncation-side.
+    pub truncation_symbol: String,
+
+    #[clap(long = "validate-config")]
+    /// Check the delta configuration in git config, and exit.
+    ///
+    /// The [delta] section and the sections of custom features are checked for unknown options,
+    /// invalid style strings, and features which do not exist. All errors are reported, grouped by
+    /// section, and the exit code is 1 if there are any.
+    pub validate_config: bool,
+
+    #[clap(long = "vcs", value_name = "git|jj|hg")]
+    /// Version control system whose output delta is reading.
+    ///
+    /// With "jj", delta recognizes the file headers of Jujutsu's default diff format (e.g.
+    /// "Modified regular file src/main.rs:") and displays them like git's, using
+    /// --file-added-label, --file-modified-label and --file-removed-label. The "Commit ID" and
+    /// "Change ID" lines of `jj show` are styled with --commit-style. With "hg", --navigate also
+    /// stops at the "changeset:" lines of `hg log`; the diffs of Mercurial are otherwise recognized
+    /// whatever the value. Defaults to "jj" when delta is called by `jj diff`, `jj log` or `jj
+    /// show`, and to "git" otherwise.
+    pub vcs: Option<String>,
+
+    #[clap(
+        long = "whitespace-error-scope",
+        default_value = "added",
+        value_name = "added|all"
+    )]
+    /// Which lines whitespace errors are highlighted in.
+    ///
+    /// With "added", lines added by the diff which consist only of whitespace are styled with
+    /// whitespace-error-style. With "all", the trailing whitespace and an indentation with a space
+    /// before a tab are styled with whitespace-error-style as well, both in added lines and in
+    /// unchanged context lines, which helps to spot existing whitespace problems near your changes.
+    pub whitespace_error_scope: String,
+
     #[clap(
         long = "whitespace-error-style",
         default_value = "auto auto",
@@ -991,6 +2173,29 @@ pub struct Opt {
     /// full terminal width.
     pub width: Option<String>,
 
+    #[clap(
+        long = "word-diff-algorithm",
+        default_value = "levenshtein",
+        value_name = "levenshtein|myers|patience|histogram"
+    )]
+    /// Algorithm used to align the words of a removed line with those of an added line.
+    ///
+    /// The default "levenshtein" computes a minimal edit distance, including substitutions.
+    /// "myers" computes a minimal sequence of insertions and deletions. "patience" and "histogram"
+    /// first align words which are unique, respectively rare, in both lines, like the git diff
+    /// algorithms of the same names. They are faster on long lines and often produce a more
+    /// intuitive alignment when words are repeated or moved.
+    pub word_diff_algorithm: String,
+
+    #[clap(long = "word-diff-context", default_value = "0", value_name = "N")]
+    /// Minimum width of unchanged text between two changed sections of a line.
+    ///
+    /// Unchanged text narrower than N between two changed sections of homologous lines is
+    /// emphasized with them, so that a line with many small changes is highlighted as one
+    /// contiguous change rather than fragments. By default the changed sections are not joined.
+    /// See also --min-emph-length.
+    pub word_diff_context: usize,
+
     #[clap(long = "word-diff-regex", default_value = r"\w+", value_name = "REGEX")]
     /// Regular expression defining a 'word' in within-line diff algorithm.
     ///
@@ -999,6 +2204,25 @@ pub struct Opt {
     /// --max-line-distance=1.0 (this is more similar to `git --word-diff`).
     pub tokenization_regex: String,
 
+    #[clap(long = "word-diff-regex-overrides", value_name = "LANG=REGEX, ...")]
+    /// Regular expressions defining a 'word' in the lines of files in particular languages.
+    ///
+    /// A comma-separated list of entries, each a language and the regular expression used instead
+    /// of --word-diff-regex for files in that language, e.g.
+    /// --word-diff-regex-overrides='css=[#\w-]+, lisp=[()]|[^\s()]+'. The language is a syntax
+    /// name, as listed by --list-languages, or a file extension, compared case-insensitively. An
+    /// entry starts after a comma followed by a language and "=", so a regular expression may
+    /// contain commas otherwise.
+    pub word_diff_regex_overrides: Option<String>,
+
+    #[clap(long = "wrap-at", default_value = "char", value_name = "char|word")]
+    /// Where to break lines which are too long (requires wrapping).
+    ///
+    /// With "char" lines are broken exactly at the panel width. With "word" lines are broken after
+    /// the last whitespace or punctuation character which still fits, so that words and identifiers
+    /// are not split across lines. If a single word does not fit it is broken at the panel width.
+    pub wrap_at: String,
+
     #[clap(long = "wrap-left-symbol", default_value = "↵", value_name = "STRING")]
     /// End-of-line wrapped content symbol (left-aligned).
     ///
@@ -1013,6 +2237,15 @@ pub struct Opt {
     /// value of "unlimited" means a line will be wrapped as many times as required.
     pub wrap_max_lines: String,
 
+    #[clap(long = "wrap-prefer-word-boundary")]
+    /// Prefer breaking wrapped lines between two tokens (requires wrapping).
+    ///
+    /// If the line can be broken after whitespace, or between an identifier and an operator or
+    /// punctuation, at most --wrap-word-boundary-slack characters before the panel width, it is
+    /// broken there rather than in the middle of a token, whose colors would otherwise be split
+    /// across lines. Otherwise it is broken at the panel width. Has no effect with --wrap-at=word.
+    pub wrap_prefer_word_boundary: bool,
+
     #[clap(
         long = "wrap-right-percent",
         default_value = "37.0",
@@ -1041,6 +2274,37 @@ pub struct Opt {
     /// line and continues right-aligned.
     pub wrap_right_symbol: String,
 
+    #[clap(
+        long = "wrap-truncation-format",
+        default_value = "",
+        value_name = "FMT"
+    )]
+    /// Format string for the indicator shown where a line which is too long is truncated.
+    ///
+    /// By default only a single truncation symbol is shown. The placeholder {hidden} is replaced by
+    /// the number of panel rows the hidden text would have needed, and {hidden_chars} by the number
+    /// of hidden characters, e.g. "… {hidden} more". If the indicator does not fit into a very
+    /// narrow panel a single symbol is shown instead. Applies to lines truncated after
+    /// --wrap-max-lines and to lines truncated by --long-line-display=truncate, both in unified
+    /// and side-by-side mode.
+    pub wrap_truncation_format: String,
+
+    #[clap(
+        long = "wrap-truncation-style",
+        default_value = "inline-hint-style",
+        value_name = "STYLE"
+    )]
+    /// Style string for the indicator configured by --wrap-truncation-format.
+    pub wrap_truncation_style: String,
+
+    #[clap(
+        long = "wrap-word-boundary-slack",
+        default_value = "10",
+        value_name = "N"
+    )]
+    /// How far before the panel width --wrap-prefer-word-boundary looks for a token boundary.
+    pub wrap_word_boundary_slack: usize,
+
     #[clap(
         long = "zero-style",
         default_value = "syntax normal",
@@ -1058,7 +2322,8 @@ pub struct Opt {
     #[clap(parse(from_os_str))]
     /// First file to be compared when delta is being used in diff mode
     ///
-    /// `delta file_1 file_2` is equivalent to `diff -u file_1 file_2 | delta`.
+    /// `delta file_1 file_2` is equivalent to `diff -u file_1 file_2 | delta`. If both paths are
+    /// directories, the files under them are compared recursively, see --exclude.
     pub minus_file: Option<PathBuf>,
 
     #[clap(parse(from_os_str))]
@@ -1083,16 +2348,18 @@ pub struct ComputedValues {
     pub available_terminal_width: usize,
     pub stdout_is_term: bool,
     pub background_color_extends_to_terminal_width: bool,
+    pub color: bool,
     pub decorations_width: Width,
     pub inspect_raw_lines: InspectRawLines,
     pub is_light_mode: bool,
+    pub option_provenances: HashMap<String, OptionProvenance>,
     pub paging_mode: PagingMode,
-    pub syntax_set: SyntaxSet,
+    pub syntax_set: LazySyntaxSet,
     pub syntax_theme: Option<SyntaxTheme>,
     pub true_color: bool,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Width {
     Fixed(usize),
     Variable,
@@ -1186,10 +2453,17 @@ impl Opt {
 // pseudo-flag commands such as --list-languages
 lazy_static! {
     static ref IGNORED_OPTION_NAMES: HashSet<&'static str> = vec![
+        "dry-run",
+        "generate-lesskey",
+        "language",
         "list-languages",
         "list-syntax-themes",
+        "list-themes",
+        "sample-file",
         "show-config",
         "show-syntax-themes",
+        "theme-filter",
+        "validate-config",
     ]
     .into_iter()
     .collect();
@@ -10,14 +10,21 @@ use crate::git_config::GitConfig;
 use crate::utils;
 
 pub fn parse_color(s: &str, true_color: bool, git_config: Option<&GitConfig>) -> Option<Color> {
+    try_parse_color(s, true_color, git_config).unwrap_or_else(|err| fatal(err))
+}
+
+/// Like `parse_color`, but return an error instead of exiting if the color is invalid.
+pub fn try_parse_color(
+    s: &str,
+    true_color: bool,
+    git_config: Option<&GitConfig>,
+) -> Result<Option<Color>, String> {
     if s == "normal" {
-        return None;
+        return Ok(None);
     }
-    let die = || {
-        fatal(format!("Invalid color or style attribute: {}", s));
-    };
+    let error = || format!("Invalid color or style attribute: {}", s);
     let syntect_color = if s.starts_with('#') {
-        SyntectColor::from_str(s).unwrap_or_else(|_| die())
+        SyntectColor::from_str(s).map_err(|_| error())?
     } else {
         let syntect_color = s
             .parse::<u8>()
@@ -28,14 +35,17 @@ pub fn parse_color(s: &str, true_color: bool, git_config: Option<&GitConfig>) ->
         if syntect_color.is_none() {
             if let Some(git_config) = git_config {
                 if let Some(val) = git_config.get::<String>(&format!("delta.{}", s)) {
-                    return parse_color(&val, true_color, None);
+                    return try_parse_color(&val, true_color, None);
                 }
             }
-            die();
+            return Err(error());
         }
         syntect_color.unwrap()
     };
-    utils::bat::terminal::to_ansi_color(syntect_color, true_color)
+    Ok(utils::bat::terminal::to_ansi_color(
+        syntect_color,
+        true_color,
+    ))
 }
 
 pub fn color_to_string(color: Color) -> String {
@@ -105,6 +115,31 @@ fn ansi_16_color_number_to_name(n: u8) -> Option<&'static str> {
     None
 }
 
+/// Fade `color` towards black, or towards white in light mode, keeping `intensity` (0.0 - 1.0) of
+/// its difference from it.
+pub fn fade_color(color: Color, intensity: f64, is_light_mode: bool, true_color: bool) -> Color {
+    let (r, g, b) = match color {
+        Color::RGB(r, g, b) => (r, g, b),
+        Color::Fixed(n) => ansi_colours::rgb_from_ansi256(n),
+        Color::Black => ansi_colours::rgb_from_ansi256(0),
+        Color::Red => ansi_colours::rgb_from_ansi256(1),
+        Color::Green => ansi_colours::rgb_from_ansi256(2),
+        Color::Yellow => ansi_colours::rgb_from_ansi256(3),
+        Color::Blue => ansi_colours::rgb_from_ansi256(4),
+        Color::Purple => ansi_colours::rgb_from_ansi256(5),
+        Color::Cyan => ansi_colours::rgb_from_ansi256(6),
+        Color::White => ansi_colours::rgb_from_ansi256(7),
+    };
+    let base = if is_light_mode { 255.0 } else { 0.0 };
+    let fade = |c: u8| (base + (c as f64 - base) * intensity.clamp(0.0, 1.0)).round() as u8;
+    let (r, g, b) = (fade(r), fade(g), fade(b));
+    if true_color {
+        Color::RGB(r, g, b)
+    } else {
+        Color::Fixed(ansi_colours::ansi256_from_rgb((r, g, b)))
+    }
+}
+
 pub fn get_minus_background_color_default(is_light_mode: bool, is_true_color: bool) -> Color {
     match (is_light_mode, is_true_color) {
         (true, true) => LIGHT_THEME_MINUS_COLOR,
@@ -178,3 +213,9 @@ const DARK_THEME_PLUS_EMPH_COLOR_256: Color = Color::Fixed(28);
 pub const LIGHT_THEME_BLAME_PALETTE: &[&str] = &["#FFFFFF", "#DDDDDD", "#BBBBBB"];
 
 pub const DARK_THEME_BLAME_PALETTE: &[&str] = &["#000000", "#222222", "#444444"];
+
+// merge parents
+
+pub const LIGHT_THEME_MERGE_PARENT_PALETTE: &[&str] = &["blue", "magenta"];
+
+pub const DARK_THEME_MERGE_PARENT_PALETTE: &[&str] = &["cyan", "magenta"];
@@ -1,139 +1,268 @@
+use std::cell::Cell;
 use std::collections::HashMap;
 use std::path::PathBuf;
 
+use lazy_static::lazy_static;
 use regex::Regex;
 use syntect::highlighting::Style as SyntectStyle;
 use syntect::highlighting::Theme as SyntaxTheme;
-use syntect::parsing::SyntaxSet;
+use syntect::parsing::SyntaxReference;
+use unicode_width::UnicodeWidthStr;
 
+use crate::align;
 use crate::ansi;
 use crate::cli;
 use crate::color;
-use crate::delta::State;
+use crate::delta::{DiffType, InMergeConflict, MergeParents, State};
+use crate::edits::{EmphMode, MaxLineDistance};
 use crate::fatal;
+use crate::features::hyperlinks::{self, FileLinkRule};
+use crate::features::line_numbers;
 use crate::features::navigate;
 use crate::features::side_by_side::{self, ansifill, LeftRight};
 use crate::git_config::{GitConfig, GitConfigEntry};
 use crate::handlers;
+use crate::handlers::binary::BinaryDiff;
 use crate::handlers::blame::parse_blame_line_numbers;
-use crate::handlers::blame::BlameLineNumbers;
-use crate::minusplus::MinusPlus;
-use crate::paint::BgFillMethod;
+use crate::handlers::blame::{BlameLineNumbers, BlamePaletteAssignment, BlamePaletteHashKey};
+use crate::handlers::diff_header_jj::Vcs;
+use crate::handlers::file_filter::FileFilter;
+use crate::handlers::image_preview::{self, ImageProtocol};
+use crate::handlers::merge_conflict::MergeConflictDisplay;
+use crate::minusplus::*;
+use crate::options::option_value::OptionProvenance;
+use crate::paint::{BgFillMethod, WhitespaceErrorScope};
 use crate::parse_styles;
 use crate::style;
 use crate::style::Style;
 use crate::tests::TESTING;
 use crate::utils;
-use crate::utils::bat::output::PagingMode;
-use crate::utils::regex_replacement::RegexReplacement;
-use crate::wrapping::WrapConfig;
+use crate::utils::bat::output::{get_pager_command, parse_less_options, LessOption, PagingMode};
+use crate::utils::regex_replacement::RegexReplacements;
+use crate::utils::syntect::LazySyntaxSet;
+use crate::wrapping::{self, WrapConfig};
 
 pub const INLINE_SYMBOL_WIDTH_1: usize = 1;
 
 #[cfg_attr(test, derive(Clone))]
 pub struct Config {
-    pub available_terminal_width: usize,
+    // The widths of the output are narrowed within the diff of a submodule.
+    pub available_terminal_width: Cell<usize>,
     pub background_color_extends_to_terminal_width: bool,
+    pub binary_diff: BinaryDiff,
+    pub binary_diff_max_size: usize,
+    pub binary_style: Style,
+    pub blame_boundary_style: Option<Style>,
     pub blame_code_style: Option<Style>,
+    pub blame_field_separator: String,
     pub blame_format: String,
+    pub blame_metadata_width: usize,
     pub blame_separator_format: BlameLineNumbers,
     pub blame_palette: Vec<String>,
+    pub blame_palette_assignment: BlamePaletteAssignment,
+    pub blame_palette_hash_key: BlamePaletteHashKey,
+    pub blame_palette_seed: usize,
     pub blame_separator_style: Option<Style>,
     pub blame_timestamp_format: String,
+    pub blame_timestamp_relative: bool,
+    pub blame_timestamp_relative_threshold: usize,
+    pub blame_uncommitted_style: Option<Style>,
+    pub change_magnitude_shading: bool,
+    pub collapse_file_globs: Vec<Regex>,
+    pub collapse_files: bool,
+    pub collapse_files_over: usize,
+    pub collapse_renamed_paths: bool,
+    pub collapsed_file_style: Style,
+    pub color: bool,
     pub color_only: bool,
+    pub commit_format: Option<String>,
     pub commit_regex: Regex,
+    pub commit_regex_exclusive: bool,
     pub commit_style: Style,
     pub cwd_of_delta_process: Option<PathBuf>,
     pub cwd_of_user_shell_process: Option<PathBuf>,
     pub cwd_relative_to_repo_root: Option<String>,
-    pub decorations_width: cli::Width,
+    pub decorations_width: Cell<cli::Width>,
     pub default_language: Option<String>,
     pub diff_stat_align_width: usize,
+    pub diff_stat_rescale: bool,
+    pub diff_stat_summary_style: Style,
+    pub diff_summary: bool,
+    pub diff_summary_style: Style,
+    pub dim_unchanged_words: bool,
+    pub emit_manifest: Option<String>,
+    pub emph_mode: EmphMode,
     pub error_exit_code: i32,
+    pub exclude: Vec<Regex>,
     pub file_added_label: String,
     pub file_copied_label: String,
+    pub file_labels_use_stats: bool,
     pub file_modified_label: String,
+    pub file_path_reminder_interval: usize,
     pub file_removed_label: String,
+    pub file_renamed_format: Option<String>,
     pub file_renamed_label: String,
-    pub file_regex_replacement: Option<RegexReplacement>,
+    pub file_regex_replacement: Option<RegexReplacements>,
     pub right_arrow: String,
+    pub file_filter: FileFilter,
     pub file_style: Style,
+    pub filter_files: bool,
     pub git_config_entries: HashMap<String, GitConfigEntry>,
     pub git_config: Option<GitConfig>,
+    pub git_diff_line_style: Style,
     pub git_minus_style: Style,
     pub git_plus_style: Style,
     pub grep_context_line_style: Style,
+    pub grep_context_separator: String,
+    pub grep_context_separator_style: Style,
     pub grep_file_style: Style,
+    pub grep_function_context_style: Style,
     pub grep_line_number_style: Style,
     pub grep_match_line_style: Style,
     pub grep_match_word_style: Style,
     pub grep_separator_symbol: String,
+    pub grep_syntax_highlighting: bool,
     pub handle_merge_conflicts: bool,
+    pub hexdump_binary: usize,
+    pub image_preview_max_height: usize,
+    pub image_previews: Option<ImageProtocol>,
+    pub hidden_context_style: Style,
+    pub hide_commit_metadata: bool,
+    pub hide_context: Option<usize>,
+    pub hide_empty_commits: bool,
     pub hunk_header_file_style: Style,
+    pub hunk_header_function_style: Style,
     pub hunk_header_line_number_style: Style,
     pub hunk_header_style_include_file_path: bool,
     pub hunk_header_style_include_line_number: bool,
     pub hunk_header_style: Style,
     pub hunk_label: String,
     pub hyperlinks_commit_link_format: Option<String>,
+    pub hyperlinks_file_commit_link_format: Option<String>,
     pub hyperlinks_file_link_format: String,
+    pub hyperlinks_file_link_rules: Vec<FileLinkRule>,
+    pub hyperlinks_infer_remote: bool,
+    pub hyperlinks_minus_line_numbers: bool,
+    pub hyperlinks_remote: String,
     pub hyperlinks: bool,
+    pub idempotent: bool,
+    pub index_line_hash_style: Style,
+    pub index_line_mode_style: Style,
+    pub index_line_style: Style,
     pub inline_hint_style: Style,
+    pub input: Option<String>,
     pub inspect_raw_lines: cli::InspectRawLines,
+    pub inspect_raw_lines_threshold: usize,
+    pub is_light_mode: bool,
+    pub keep_binary_patch: bool,
+    pub keep_index_line: bool,
+    pub keep_mode_lines: bool,
     pub keep_plus_minus_markers: bool,
+    // The prefixes of minus and plus lines, of the same width, see --minus-symbol. They are empty
+    // if lines are not prefixed.
+    pub plus_minus_markers: MinusPlus<String>,
     pub line_buffer_size: usize,
     pub line_fill_method: BgFillMethod,
     pub line_numbers_format: LeftRight<String>,
+    // The widths replacing those of the placeholders of the left and right formats, if any.
+    pub line_numbers_min_width: LeftRight<Option<usize>>,
+    pub line_numbers_relative: bool,
     pub line_numbers_style_leftright: LeftRight<Style>,
     pub line_numbers_style_minusplus: MinusPlus<Style>,
     pub line_numbers_zero_style: Style,
     pub line_numbers: bool,
     pub styles_map: Option<HashMap<style::AnsiTermStyleEqualityKey, Style>>,
     pub max_line_distance_for_naively_paired_lines: f64,
-    pub max_line_distance: f64,
+    pub max_line_distance: MaxLineDistance,
     pub max_line_length: usize,
+    pub max_syntax_highlighting_length: usize,
+    pub merge_conflict_base_style: Style,
     pub merge_conflict_begin_symbol: String,
+    pub merge_conflict_display: MergeConflictDisplay,
     pub merge_conflict_ours_diff_header_style: Style,
     pub merge_conflict_theirs_diff_header_style: Style,
     pub merge_conflict_end_symbol: String,
+    pub merge_parent_palette: Vec<ansi_term::Color>,
+    // The minus and plus styles of the lines of a combined diff changed relative to the Nth parent
+    // only.
+    pub merge_parent_styles: MinusPlus<Vec<Style>>,
+    pub min_emph_length: usize,
     pub minus_emph_style: Style,
+    pub minus_empty_line_marker: String,
     pub minus_empty_line_marker_style: Style,
     pub minus_file: Option<PathBuf>,
     pub minus_non_emph_style: Style,
     pub minus_style: Style,
+    pub mode_change_style: Style,
     pub navigate_regex: Option<String>,
     pub navigate: bool,
     pub null_style: Style,
     pub null_syntect_style: SyntectStyle,
+    pub only_in_style: Style,
+    pub option_provenances: HashMap<String, OptionProvenance>,
+    pub output: Option<String>,
     pub pager: Option<String>,
+    pub pager_fallbacks: Vec<String>,
+    pub pager_less_options: Vec<LessOption>,
+    pub pager_quit_if_one_screen: Option<bool>,
     pub paging_mode: PagingMode,
+    pub plain: bool,
     pub plus_emph_style: Style,
+    pub plus_empty_line_marker: String,
     pub plus_empty_line_marker_style: Style,
     pub plus_file: Option<PathBuf>,
     pub plus_non_emph_style: Style,
     pub plus_style: Style,
+    pub preserve_context_ansi: bool,
+    pub range_diff_added_style: Style,
+    pub range_diff_modified_style: Style,
+    pub range_diff_removed_style: Style,
+    pub range_diff_unchanged_style: Style,
+    pub raw_file_globs: Vec<Regex>,
     pub relative_paths: bool,
+    pub show_git_diff_line: bool,
     pub show_themes: bool,
-    pub side_by_side_data: side_by_side::SideBySideData,
+    pub side_by_side_data: Cell<side_by_side::SideBySideData>,
     pub side_by_side: bool,
+    pub side_by_side_align: side_by_side::SideBySideAlign,
+    pub side_by_side_left_permille: usize,
+    pub side_by_side_wrap_config: WrapConfig,
+    pub stdin_label: Option<String>,
+    pub stdin_label_style: Style,
+    pub submodule_commit_added_style: Style,
+    pub submodule_commit_removed_style: Style,
+    pub submodule_style: Style,
     pub syntax_dummy_theme: SyntaxTheme,
-    pub syntax_set: SyntaxSet,
+    pub syntax_set: LazySyntaxSet,
     pub syntax_theme: Option<SyntaxTheme>,
     pub tab_width: usize,
     pub tokenization_regex: Regex,
+    pub word_diff_algorithm: align::WordDiffAlgorithm,
+    pub word_diff_context: usize,
+    // The languages, lowercased, and their regexes from --word-diff-regex-overrides.
+    pub word_diff_regex_overrides: Vec<(String, Regex)>,
     pub true_color: bool,
     pub truncation_symbol: String,
+    pub vcs: Option<Vcs>,
+    pub whitespace_error_scope: WhitespaceErrorScope,
     pub whitespace_error_style: Style,
     pub wrap_config: WrapConfig,
+    pub wrap_truncation_style: Style,
+    // The blank prefix of zero lines, of the width of plus_minus_markers.
+    pub zero_marker: String,
     pub zero_style: Style,
 }
 
 impl Config {
     pub fn get_style(&self, state: &State) -> &Style {
         match state {
-            State::HunkMinus(_, _) => &self.minus_style,
+            State::HunkMinus(_, _) => self
+                .get_merge_parent_style(state)
+                .unwrap_or(&self.minus_style),
             State::HunkZero(_, _) => &self.zero_style,
-            State::HunkPlus(_, _) => &self.plus_style,
+            State::HunkPlus(_, _) => self
+                .get_merge_parent_style(state)
+                .unwrap_or(&self.plus_style),
             State::CommitMeta => &self.commit_style,
             State::DiffHeader(_) => &self.file_style,
             State::HunkHeader(_, _, _, _) => &self.hunk_header_style,
@@ -141,6 +270,42 @@ impl Config {
             _ => delta_unreachable("Unreachable code reached in get_style."),
         }
     }
+
+    /// The style of a line of a combined diff which is changed relative to a single parent, i.e.
+    /// whose prefix has a single +/- marker, e.g. '+ ' or ' -', see merge-parent-palette.
+    pub fn get_merge_parent_style(&self, state: &State) -> Option<&Style> {
+        use DiffType::Combined;
+        use InMergeConflict::No;
+        let (prefix, styles) = match state {
+            State::HunkMinus(Combined(MergeParents::Prefix(prefix), No), _) => {
+                (prefix, &self.merge_parent_styles[Minus])
+            }
+            State::HunkPlus(Combined(MergeParents::Prefix(prefix), No), _) => {
+                (prefix, &self.merge_parent_styles[Plus])
+            }
+            _ => return None,
+        };
+        let mut markers = prefix.char_indices().filter(|(_, c)| *c != ' ');
+        match (markers.next(), markers.next()) {
+            (Some((parent, _)), None) if !styles.is_empty() => Some(&styles[parent % styles.len()]),
+            _ => None,
+        }
+    }
+
+    /// The regex defining a word in the lines of a file with this syntax: the one given for its
+    /// name or one of its file extensions by --word-diff-regex-overrides, if any.
+    pub fn get_tokenization_regex(&self, syntax: &SyntaxReference) -> &Regex {
+        self.word_diff_regex_overrides
+            .iter()
+            .find(|(language, _)| {
+                syntax.name.eq_ignore_ascii_case(language)
+                    || syntax
+                        .file_extensions
+                        .iter()
+                        .any(|extension| extension.eq_ignore_ascii_case(language))
+            })
+            .map_or(&self.tokenization_regex, |(_, regex)| regex)
+    }
 }
 
 impl From<cli::Opt> for Config {
@@ -149,6 +314,13 @@ impl From<cli::Opt> for Config {
         let styles_map = parse_styles::parse_styles_map(&opt);
 
         let wrap_config = WrapConfig::from_opt(&opt, styles["inline-hint-style"]);
+        let side_by_side_wrap_config =
+            WrapConfig::side_by_side_from_opt(&opt, styles["inline-hint-style"]);
+        let truncation_side = if opt.side_by_side {
+            side_by_side_wrap_config.truncation_side
+        } else {
+            wrap_config.truncation_side
+        };
 
         let max_line_distance_for_naively_paired_lines = opt
             .env
@@ -157,6 +329,18 @@ impl From<cli::Opt> for Config {
             .map(|s| s.parse::<f64>().unwrap_or(0.0))
             .unwrap_or(0.0);
 
+        let max_line_distance = match opt.max_line_distance.as_str() {
+            "auto" => MaxLineDistance::Auto,
+            distance => match distance.parse::<f64>() {
+                Ok(distance) if (0.0..=1.0).contains(&distance) => MaxLineDistance::Fixed(distance),
+                _ => fatal(format!(
+                    "Invalid value for max-line-distance: {}. \
+                     Expected a number between 0.0 and 1.0, or \"auto\".",
+                    distance
+                )),
+            },
+        };
+
         let commit_regex = Regex::new(&opt.commit_regex).unwrap_or_else(|_| {
             fatal(format!(
                 "Invalid commit-regex: {}. \
@@ -175,13 +359,221 @@ impl From<cli::Opt> for Config {
             ));
         });
 
+        let word_diff_regex_overrides =
+            parse_word_diff_regex_overrides(opt.word_diff_regex_overrides.as_deref());
+
+        let word_diff_algorithm = match opt.word_diff_algorithm.as_str() {
+            "levenshtein" => align::WordDiffAlgorithm::Levenshtein,
+            "myers" => align::WordDiffAlgorithm::Myers,
+            "patience" => align::WordDiffAlgorithm::Patience,
+            "histogram" => align::WordDiffAlgorithm::Histogram,
+            _ => fatal(format!(
+                "Invalid value for word-diff-algorithm: {}. \
+                 Expected \"levenshtein\", \"myers\", \"patience\" or \"histogram\".",
+                opt.word_diff_algorithm
+            )),
+        };
+
+        let emph_mode = match opt.emph_mode.as_str() {
+            "span" => EmphMode::Span,
+            "unique-tokens" => EmphMode::UniqueTokens,
+            _ => fatal(format!(
+                "Invalid value for emph-mode: {}. Expected \"span\" or \"unique-tokens\".",
+                opt.emph_mode
+            )),
+        };
+
+        let binary_diff = match opt.binary_diff.as_str() {
+            "summary" => BinaryDiff::Summary,
+            "hexdump" => BinaryDiff::Hexdump,
+            _ => fatal(format!(
+                "Invalid value for binary-diff: {}. Expected \"summary\" or \"hexdump\".",
+                opt.binary_diff
+            )),
+        };
+
+        let merge_conflict_display = match opt.merge_conflict_display.as_str() {
+            "blocks" => MergeConflictDisplay::Blocks,
+            "derived-diffs" => MergeConflictDisplay::DerivedDiffs,
+            _ => fatal(format!(
+                "Invalid value for merge-conflict-display: {}. Expected \"blocks\" or \"derived-diffs\".",
+                opt.merge_conflict_display
+            )),
+        };
+
+        let image_previews = match opt.image_previews.as_str() {
+            "auto" | "kitty" | "iterm2" | "off" => {
+                let pager = (opt.computed.paging_mode != PagingMode::Never)
+                    .then(|| get_pager_command(&opt.env, opt.pager.clone()).0);
+                image_preview::get_image_protocol(
+                    &opt.image_previews,
+                    &opt.env,
+                    opt.computed.stdout_is_term,
+                    pager.as_deref(),
+                )
+            }
+            _ => fatal(format!(
+                "Invalid value for image-previews: {}. \
+                 Expected \"auto\", \"kitty\", \"iterm2\", or \"off\".",
+                opt.image_previews
+            )),
+        };
+
+        let pager_less_options =
+            parse_less_options(opt.pager_less_options.as_deref().unwrap_or_default())
+                .unwrap_or_else(|err| fatal(err));
+
+        // quit-if-one-screen in --pager-less-options is applied as --pager-quit-if-one-screen, which
+        // takes precedence.
+        let pager_quit_if_one_screen = match opt.pager_quit_if_one_screen.as_str() {
+            "true" => Some(true),
+            "false" => Some(false),
+            "auto" => pager_less_options
+                .iter()
+                .rev()
+                .find_map(|option| match option {
+                    LessOption::QuitIfOneScreen(on) => Some(*on),
+                    _ => None,
+                }),
+            _ => fatal(format!(
+                r#"Invalid value for pager-quit-if-one-screen option: {}. Valid values are "true", "false", and "auto"."#,
+                opt.pager_quit_if_one_screen
+            )),
+        };
+
+        // Hiding unchanged lines changes the structure of the output.
+        let hide_context = match opt.hide_context.as_deref() {
+            Some(_) if opt.color_only => None,
+            Some(n) => Some(n.parse::<usize>().unwrap_or_else(|_| {
+                fatal(format!(
+                    "Invalid value for hide-context: {}. Expected a number of lines.",
+                    n
+                ))
+            })),
+            None => None,
+        };
+
+        let side_by_side_align = match opt.side_by_side_align.as_str() {
+            "content" => side_by_side::SideBySideAlign::Content,
+            "none" => side_by_side::SideBySideAlign::None,
+            _ => fatal(format!(
+                "Invalid value for side-by-side-align: {}. Expected \"none\" or \"content\".",
+                opt.side_by_side_align
+            )),
+        };
+
+        let marker = |symbol: &Option<String>, default: &str| match symbol {
+            Some(symbol) => symbol.clone(),
+            None if opt.keep_plus_minus_markers => default.to_string(),
+            None => "".to_string(),
+        };
+        let mut plus_minus_markers = MinusPlus::new(
+            marker(&opt.minus_symbol, "-"),
+            marker(&opt.plus_symbol, "+"),
+        );
+        let marker_width = plus_minus_markers
+            .minus
+            .width()
+            .max(plus_minus_markers.plus.width());
+        for marker in [&mut plus_minus_markers.minus, &mut plus_minus_markers.plus] {
+            let padding = " ".repeat(marker_width - marker.width());
+            marker.push_str(&padding);
+        }
+        let zero_marker = " ".repeat(marker_width);
+
+        let vcs = match opt.vcs.as_deref() {
+            None => None,
+            Some("git") => Some(Vcs::Git),
+            Some("jj") => Some(Vcs::Jj),
+            Some("hg") => Some(Vcs::Hg),
+            Some(vcs) => fatal(format!(
+                "Invalid value for vcs: {}. Expected \"git\", \"jj\" or \"hg\".",
+                vcs
+            )),
+        };
+
+        let whitespace_error_scope = match opt.whitespace_error_scope.as_str() {
+            "added" => WhitespaceErrorScope::Added,
+            "all" => WhitespaceErrorScope::All,
+            _ => fatal(format!(
+                "Invalid value for whitespace-error-scope: {}. Expected \"added\" or \"all\".",
+                opt.whitespace_error_scope
+            )),
+        };
+
+        let navigate_granularity = match opt.navigate_granularity.as_str() {
+            "commit" => navigate::NavigateGranularity::Commit,
+            "file" => navigate::NavigateGranularity::File,
+            "hunk" => navigate::NavigateGranularity::Hunk,
+            _ => fatal(format!(
+                "Invalid value for navigate-granularity: {}. \
+                 Expected \"commit\", \"file\", or \"hunk\".",
+                opt.navigate_granularity
+            )),
+        };
+
         let blame_palette = make_blame_palette(opt.blame_palette, opt.computed.is_light_mode);
+        let true_color = opt.computed.true_color;
+        let git_config = opt.git_config.as_ref();
+        let merge_parent_palette: Vec<_> = make_merge_parent_palette(
+            opt.merge_parent_palette.as_deref(),
+            opt.computed.is_light_mode,
+        )
+        .iter()
+        .filter_map(|s| color::parse_color(s, true_color, git_config))
+        .collect();
+        let merge_parent_styles = MinusPlus::new(
+            make_merge_parent_styles(styles["minus-style"], &merge_parent_palette),
+            make_merge_parent_styles(styles["plus-style"], &merge_parent_palette),
+        );
+        let blame_palette_assignment = match opt.blame_palette_assignment.as_str() {
+            "sequential" => BlamePaletteAssignment::Sequential,
+            "hashed" => BlamePaletteAssignment::Hashed,
+            _ => fatal(format!(
+                "Invalid value for blame-palette-assignment: {}. Expected \"sequential\" or \"hashed\".",
+                opt.blame_palette_assignment
+            )),
+        };
+        let blame_palette_hash_key = match opt.blame_palette_hash_key.as_str() {
+            "author" => BlamePaletteHashKey::Author,
+            "email" => BlamePaletteHashKey::Email,
+            _ => fatal(format!(
+                "Invalid value for blame-palette-hash-key: {}. Expected \"author\" or \"email\".",
+                opt.blame_palette_hash_key
+            )),
+        };
+        let blame_timestamp_relative = match opt.blame_timestamp_relative.as_str() {
+            "true" => true,
+            "false" => false,
+            _ => fatal(format!(
+                r#"Invalid value for blame-timestamp-relative option: {}. Valid values are "true", and "false"."#,
+                opt.blame_timestamp_relative
+            )),
+        };
+
+        let grep_syntax_highlighting = match opt.grep_syntax_highlighting.as_str() {
+            "true" => true,
+            "false" => false,
+            _ => fatal(format!(
+                r#"Invalid value for grep-syntax-highlighting option: {}. Valid values are "true", and "false"."#,
+                opt.grep_syntax_highlighting
+            )),
+        };
 
         let file_added_label = opt.file_added_label;
         let file_copied_label = opt.file_copied_label;
         let file_modified_label = opt.file_modified_label;
         let file_removed_label = opt.file_removed_label;
         let file_renamed_label = opt.file_renamed_label;
+        let file_labels_use_stats = [
+            &file_added_label,
+            &file_copied_label,
+            &file_modified_label,
+            &file_removed_label,
+            &file_renamed_label,
+        ]
+        .iter()
+        .any(|label| handlers::diff_header::label_uses_file_stats(label));
         let right_arrow = opt.right_arrow;
         let hunk_label = opt.hunk_label;
 
@@ -192,9 +584,77 @@ impl From<cli::Opt> for Config {
             _ => fatal("Invalid option for line-fill-method: Expected \"ansi\" or \"spaces\"."),
         };
 
+        // The widths of the placeholders of the built-in formats are replaced by the minimum width,
+        // so that the line number columns are only as wide as the line numbers require. Those of
+        // formats given by the user are kept, unless --line-numbers-min-width is given.
+        let line_numbers_min_width = {
+            let option_provenances = &opt.computed.option_provenances;
+            let line_numbers_min_width = opt.line_numbers_min_width;
+            let min_width = |option| {
+                let is_builtin = matches!(
+                    option_provenances.get(option),
+                    None | Some(OptionProvenance::BuiltinFeature(_))
+                );
+                match line_numbers_min_width {
+                    0 if is_builtin => Some(1),
+                    0 => None,
+                    min_width => Some(min_width),
+                }
+            };
+            LeftRight::new(
+                min_width("line-numbers-left-format"),
+                min_width("line-numbers-right-format"),
+            )
+        };
+
+        let line_numbers_format = {
+            // Formats which already use relative line numbers are used as given.
+            let line_numbers_relative = opt.line_numbers_relative;
+            let relative = |format: String| {
+                if line_numbers_relative && !format.contains("{rm") && !format.contains("{rp") {
+                    format.replace("{nm", "{rm").replace("{np", "{rp")
+                } else {
+                    format
+                }
+            };
+            line_numbers::check_format_string(
+                &opt.line_numbers_left_format,
+                "line-numbers-left-format",
+            );
+            line_numbers::check_format_string(
+                &opt.line_numbers_right_format,
+                "line-numbers-right-format",
+            );
+            LeftRight::new(
+                relative(opt.line_numbers_left_format),
+                relative(opt.line_numbers_right_format),
+            )
+        };
+        // Whether the formats display line numbers relative to the first change of each hunk.
+        let line_numbers_relative = [&line_numbers_format.minus, &line_numbers_format.plus]
+            .iter()
+            .any(|format| format.contains("{rm") || format.contains("{rp"));
+
+        let side_by_side_left_permille = {
+            let arg = &opt.side_by_side_left_percent;
+            let percent = wrapping::remove_percent_suffix(arg)
+                .parse::<f64>()
+                .unwrap_or_else(|err| {
+                    fatal(format!(
+                        "Could not parse side-by-side-left-percent argument {}: {}.",
+                        arg, err
+                    ))
+                });
+            if percent.is_finite() && percent > 0.0 && percent < 100.0 {
+                (percent * 10.0).round() as usize
+            } else {
+                fatal("Invalid value for side-by-side-left-percent, not between 0 and 100.")
+            }
+        };
         let side_by_side_data = side_by_side::SideBySideData::new_sbs(
             &opt.computed.decorations_width,
             &opt.computed.available_terminal_width,
+            side_by_side_left_permille,
         );
         let side_by_side_data = ansifill::UseFullPanelWidth::sbs_odd_fix(
             &opt.computed.decorations_width,
@@ -202,16 +662,32 @@ impl From<cli::Opt> for Config {
             side_by_side_data,
         );
 
+        let collapse_file_globs =
+            parse_file_globs(opt.collapse_file_glob.as_deref(), "collapse-file-glob");
+        let raw_file_globs = parse_file_globs(opt.raw_file_glob.as_deref(), "raw-file-glob");
+        // Collapsing files changes the structure of the output.
+        let collapse_files =
+            (opt.collapse_files_over > 0 || !collapse_file_globs.is_empty()) && !opt.color_only;
+
+        let file_filter = FileFilter::new(&opt.include_files, &opt.exclude_files)
+            .unwrap_or_else(|err| fatal(format!("Invalid file filter pattern: {}", err)));
+        // Filtering files changes the structure of the output.
+        let filter_files = !file_filter.is_empty() && !opt.color_only;
+
         let navigate_regex = if (opt.navigate || opt.show_themes)
             && (opt.navigate_regex.is_none() || opt.navigate_regex == Some("".to_string()))
         {
             Some(navigate::make_navigate_regex(
                 opt.show_themes,
+                navigate_granularity,
+                &opt.commit_regex,
                 &file_modified_label,
                 &file_added_label,
                 &file_removed_label,
                 &file_renamed_label,
                 &hunk_label,
+                collapse_files,
+                vcs == Some(Vcs::Hg),
             ))
         } else {
             opt.navigate_regex
@@ -230,48 +706,106 @@ impl From<cli::Opt> for Config {
         );
 
         Self {
-            available_terminal_width: opt.computed.available_terminal_width,
+            available_terminal_width: Cell::new(opt.computed.available_terminal_width),
             background_color_extends_to_terminal_width: opt
                 .computed
                 .background_color_extends_to_terminal_width,
             blame_format: opt.blame_format,
+            blame_metadata_width: opt.blame_metadata_width,
+            binary_diff,
+            binary_diff_max_size: opt.binary_diff_max_size,
+            binary_style: styles["binary-style"],
+            blame_boundary_style: styles.remove("blame-boundary-style"),
             blame_code_style: styles.remove("blame-code-style"),
+            blame_field_separator: opt.blame_field_separator,
             blame_palette,
+            blame_palette_assignment,
+            blame_palette_hash_key,
+            blame_palette_seed: opt.blame_palette_seed,
             blame_separator_format: parse_blame_line_numbers(&opt.blame_separator_format),
             blame_separator_style: styles.remove("blame-separator-style"),
             blame_timestamp_format: opt.blame_timestamp_format,
+            blame_timestamp_relative,
+            blame_timestamp_relative_threshold: opt.blame_timestamp_relative_threshold,
+            blame_uncommitted_style: styles.remove("blame-uncommitted-style"),
+            change_magnitude_shading: opt.change_magnitude_shading,
             commit_style: styles["commit-style"],
+            collapse_file_globs,
+            collapse_files,
+            collapse_files_over: opt.collapse_files_over,
+            collapse_renamed_paths: opt.collapse_renamed_paths,
+            collapsed_file_style: styles["collapsed-file-style"],
+            color: opt.computed.color,
             color_only: opt.color_only,
+            commit_format: opt.commit_format,
             commit_regex,
+            commit_regex_exclusive: opt.commit_regex_exclusive,
             cwd_of_delta_process,
             cwd_of_user_shell_process,
             cwd_relative_to_repo_root,
-            decorations_width: opt.computed.decorations_width,
+            decorations_width: Cell::new(opt.computed.decorations_width),
             default_language: opt.default_language,
             diff_stat_align_width: opt.diff_stat_align_width,
+            diff_stat_rescale: opt.diff_stat_rescale,
+            diff_stat_summary_style: styles["diff-stat-summary-style"],
+            // The summary line changes the structure of the output.
+            diff_summary: opt.diff_summary && !opt.color_only,
+            diff_summary_style: styles["diff-summary-style"],
+            dim_unchanged_words: opt.dim_unchanged_words,
+            emit_manifest: opt.emit_manifest,
+            emph_mode,
             error_exit_code: 2, // Use 2 for error because diff uses 0 and 1 for non-error.
+            exclude: opt
+                .exclude
+                .as_deref()
+                .unwrap_or_default()
+                .split_whitespace()
+                .map(|glob| {
+                    utils::path::glob_to_regex(glob)
+                        .unwrap_or_else(|err| fatal(format!("Invalid value for exclude: {}", err)))
+                })
+                .collect(),
             file_added_label,
             file_copied_label,
+            file_labels_use_stats,
             file_modified_label,
+            file_path_reminder_interval: opt.file_path_reminder_interval,
             file_removed_label,
+            file_renamed_format: opt.file_renamed_format,
             file_renamed_label,
-            file_regex_replacement: opt
-                .file_regex_replacement
-                .as_deref()
-                .and_then(RegexReplacement::from_sed_command),
+            file_regex_replacement: opt.file_regex_replacement.as_deref().map(|sed_commands| {
+                RegexReplacements::from_sed_commands(sed_commands).unwrap_or_else(|err| {
+                    fatal(format!("Invalid value for file-transformation: {}", err))
+                })
+            }),
             right_arrow,
             hunk_label,
+            file_filter,
             file_style: styles["file-style"],
+            filter_files,
             git_config: opt.git_config,
             git_config_entries: opt.git_config_entries,
+            git_diff_line_style: styles["git-diff-line-style"],
             grep_context_line_style: styles["grep-context-line-style"],
+            grep_context_separator: opt.grep_context_separator,
+            grep_context_separator_style: styles["grep-context-separator-style"],
             grep_file_style: styles["grep-file-style"],
+            grep_function_context_style: styles["grep-function-context-style"],
             grep_line_number_style: styles["grep-line-number-style"],
             grep_match_line_style: styles["grep-match-line-style"],
             grep_match_word_style: styles["grep-match-word-style"],
             grep_separator_symbol: opt.grep_separator_symbol,
+            grep_syntax_highlighting,
             handle_merge_conflicts: !opt.raw,
+            hexdump_binary: opt.hexdump_binary,
+            image_preview_max_height: opt.image_preview_max_height,
+            image_previews,
+            hidden_context_style: styles["hidden-context-style"],
+            hide_commit_metadata: opt.hide_commit_metadata,
+            hide_context,
+            hide_empty_commits: opt.hide_empty_commits,
             hunk_header_file_style: styles["hunk-header-file-style"],
+            hunk_header_function_style: styles["hunk-header-function-style"],
             hunk_header_line_number_style: styles["hunk-header-line-number-style"],
             hunk_header_style: styles["hunk-header-style"],
             hunk_header_style_include_file_path: opt
@@ -284,10 +818,37 @@ impl From<cli::Opt> for Config {
                 .any(|s| s == "line-number"),
             hyperlinks: opt.hyperlinks,
             hyperlinks_commit_link_format: opt.hyperlinks_commit_link_format,
+            hyperlinks_file_commit_link_format: opt.hyperlinks_file_commit_link_format,
             hyperlinks_file_link_format: opt.hyperlinks_file_link_format,
+            hyperlinks_file_link_rules: opt
+                .hyperlinks_file_link_rules
+                .as_deref()
+                .map(|rules| {
+                    hyperlinks::parse_file_link_rules(rules).unwrap_or_else(|err| {
+                        fatal(format!(
+                            "Invalid value for hyperlinks-file-link-rules: {}",
+                            err
+                        ))
+                    })
+                })
+                .unwrap_or_default(),
+            hyperlinks_infer_remote: opt.hyperlinks_infer_remote,
+            hyperlinks_minus_line_numbers: opt.hyperlinks_minus_line_numbers,
+            hyperlinks_remote: opt.hyperlinks_remote,
+            idempotent: opt.idempotent,
             inspect_raw_lines: opt.computed.inspect_raw_lines,
+            is_light_mode: opt.computed.is_light_mode,
+            inspect_raw_lines_threshold: opt.inspect_raw_lines_threshold,
+            index_line_hash_style: styles["index-line-hash-style"],
+            index_line_mode_style: styles["index-line-mode-style"],
+            index_line_style: styles["index-line-style"],
             inline_hint_style: styles["inline-hint-style"],
+            input: opt.input,
+            keep_binary_patch: opt.keep_binary_patch,
+            keep_index_line: opt.keep_index_line,
+            keep_mode_lines: opt.keep_mode_lines,
             keep_plus_minus_markers: opt.keep_plus_minus_markers,
+            plus_minus_markers,
             line_fill_method: if !opt.computed.stdout_is_term && !TESTING {
                 // Don't write ANSI sequences (which rely on the width of the
                 // current terminal) into a file. Also see UseFullPanelWidth.
@@ -297,10 +858,9 @@ impl From<cli::Opt> for Config {
                 line_fill_method
             },
             line_numbers: opt.line_numbers && !handlers::hunk::is_word_diff(),
-            line_numbers_format: LeftRight::new(
-                opt.line_numbers_left_format,
-                opt.line_numbers_right_format,
-            ),
+            line_numbers_format,
+            line_numbers_relative,
+            line_numbers_min_width,
             line_numbers_style_leftright: LeftRight::new(
                 styles["line-numbers-left-style"],
                 styles["line-numbers-right-style"],
@@ -311,53 +871,105 @@ impl From<cli::Opt> for Config {
             ),
             line_numbers_zero_style: styles["line-numbers-zero-style"],
             line_buffer_size: opt.line_buffer_size,
-            max_line_distance: opt.max_line_distance,
+            max_line_distance,
             max_line_distance_for_naively_paired_lines,
-            max_line_length: if opt.side_by_side {
-                wrap_config.config_max_line_length(
+            max_line_length: if truncation_side != ansi::TruncationSide::Right {
+                // The end of the line is displayed, so the input must not be truncated.
+                0
+            } else if opt.side_by_side {
+                side_by_side_wrap_config.config_max_line_length(
                     opt.max_line_length,
                     opt.computed.available_terminal_width,
                 )
             } else {
                 opt.max_line_length
             },
+            max_syntax_highlighting_length: opt.max_syntax_highlighting_length,
+            merge_conflict_base_style: styles["merge-conflict-base-style"],
             merge_conflict_begin_symbol: opt.merge_conflict_begin_symbol,
+            merge_conflict_display,
             merge_conflict_ours_diff_header_style: styles["merge-conflict-ours-diff-header-style"],
             merge_conflict_theirs_diff_header_style: styles
                 ["merge-conflict-theirs-diff-header-style"],
             merge_conflict_end_symbol: opt.merge_conflict_end_symbol,
+            merge_parent_palette,
+            merge_parent_styles,
+            min_emph_length: opt.min_emph_length,
             minus_emph_style: styles["minus-emph-style"],
+            minus_empty_line_marker: opt.minus_empty_line_marker,
             minus_empty_line_marker_style: styles["minus-empty-line-marker-style"],
             minus_file: opt.minus_file,
             minus_non_emph_style: styles["minus-non-emph-style"],
             minus_style: styles["minus-style"],
+            mode_change_style: styles["mode-change-style"],
             navigate: opt.navigate,
             navigate_regex,
             null_style: Style::new(),
             null_syntect_style: SyntectStyle::default(),
+            only_in_style: styles["only-in-style"],
+            option_provenances: opt.computed.option_provenances,
+            output: opt.output,
             pager: opt.pager,
+            pager_fallbacks: opt
+                .pager_fallbacks
+                .split(',')
+                .map(str::trim)
+                .filter(|pager| !pager.is_empty())
+                .map(String::from)
+                .collect(),
+            pager_less_options,
+            pager_quit_if_one_screen,
             paging_mode: opt.computed.paging_mode,
+            plain: opt.plain,
             plus_emph_style: styles["plus-emph-style"],
+            plus_empty_line_marker: opt.plus_empty_line_marker,
             plus_empty_line_marker_style: styles["plus-empty-line-marker-style"],
             plus_file: opt.plus_file,
             plus_non_emph_style: styles["plus-non-emph-style"],
             plus_style: styles["plus-style"],
+            preserve_context_ansi: opt.preserve_context_ansi,
+            range_diff_added_style: styles["range-diff-added-style"],
+            range_diff_modified_style: styles["range-diff-modified-style"],
+            range_diff_removed_style: styles["range-diff-removed-style"],
+            range_diff_unchanged_style: styles["range-diff-unchanged-style"],
+            raw_file_globs,
             git_minus_style: styles["git-minus-style"],
             git_plus_style: styles["git-plus-style"],
             relative_paths: opt.relative_paths,
+            show_git_diff_line: opt.show_git_diff_line,
             show_themes: opt.show_themes,
             side_by_side: opt.side_by_side && !handlers::hunk::is_word_diff(),
-            side_by_side_data,
+            side_by_side_align,
+            side_by_side_left_permille,
+            side_by_side_wrap_config,
+            side_by_side_data: Cell::new(side_by_side_data),
+            stdin_label: opt.stdin_label,
+            stdin_label_style: styles["stdin-label-style"],
+            submodule_commit_added_style: styles["submodule-commit-added-style"],
+            submodule_commit_removed_style: styles["submodule-commit-removed-style"],
+            submodule_style: styles["submodule-style"],
             styles_map,
             syntax_dummy_theme: SyntaxTheme::default(),
             syntax_set: opt.computed.syntax_set,
             syntax_theme: opt.computed.syntax_theme,
             tab_width: opt.tab_width,
             tokenization_regex,
+            word_diff_algorithm,
+            word_diff_context: opt.word_diff_context,
+            word_diff_regex_overrides,
             true_color: opt.computed.true_color,
-            truncation_symbol: format!("{}→{}", ansi::ANSI_SGR_REVERSE, ansi::ANSI_SGR_RESET),
+            truncation_symbol: format!(
+                "{}{}{}",
+                ansi::ANSI_SGR_REVERSE,
+                opt.truncation_symbol,
+                ansi::ANSI_SGR_RESET
+            ),
+            vcs,
             wrap_config,
+            wrap_truncation_style: styles["wrap-truncation-style"],
+            whitespace_error_scope,
             whitespace_error_style: styles["whitespace-error-style"],
+            zero_marker,
             zero_style: styles["zero-style"],
         }
     }
@@ -380,6 +992,92 @@ fn make_blame_palette(blame_palette: Option<String>, is_light_mode: bool) -> Vec
     }
 }
 
+fn make_merge_parent_palette(merge_parent_palette: Option<&str>, is_light_mode: bool) -> Vec<&str> {
+    match (merge_parent_palette, is_light_mode) {
+        (Some(string), _) => string.split_whitespace().collect(),
+        (None, true) => color::LIGHT_THEME_MERGE_PARENT_PALETTE.to_vec(),
+        (None, false) => color::DARK_THEME_MERGE_PARENT_PALETTE.to_vec(),
+    }
+}
+
+// The code of a line changed relative to one parent only is painted with the color of the parent,
+// rather than syntax highlighted.
+fn make_merge_parent_styles(style: Style, merge_parent_palette: &[ansi_term::Color]) -> Vec<Style> {
+    merge_parent_palette
+        .iter()
+        .map(|color| {
+            let mut style = style;
+            style.ansi_term_style.foreground = Some(*color);
+            style.is_syntax_highlighted = false;
+            style
+        })
+        .collect()
+}
+
+// Parse a comma- or whitespace-separated list of globs, see --collapse-file-glob.
+fn parse_file_globs(globs: Option<&str>, option: &str) -> Vec<Regex> {
+    globs
+        .unwrap_or_default()
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|glob| !glob.is_empty())
+        .map(|glob| {
+            utils::path::glob_to_regex(glob)
+                .unwrap_or_else(|err| fatal(format!("Invalid value for {}: {}", option, err)))
+        })
+        .collect()
+}
+
+lazy_static! {
+    // The start of an entry of --word-diff-regex-overrides, e.g. ", css=".
+    static ref WORD_DIFF_REGEX_OVERRIDE_LANGUAGE_REGEX: Regex =
+        Regex::new(r"(?:^|,)\s*([^,=]+?)\s*=").unwrap();
+}
+
+// Parse the entries of --word-diff-regex-overrides, e.g. "css=[#\w-]+, lisp=[()]|[^\s()]+". An
+// entry starts after a comma followed by a language and "=".
+fn parse_word_diff_regex_overrides(overrides: Option<&str>) -> Vec<(String, Regex)> {
+    let overrides = overrides.unwrap_or_default();
+    let languages: Vec<_> = WORD_DIFF_REGEX_OVERRIDE_LANGUAGE_REGEX
+        .captures_iter(overrides)
+        .collect();
+    let invalid = |entry: &str| -> ! {
+        fatal(format!(
+            "Invalid entry in word-diff-regex-overrides: {}. Expected LANG=REGEX.",
+            entry
+        ))
+    };
+    match languages.first() {
+        None if !overrides.trim().is_empty() => invalid(overrides.trim()),
+        Some(captures) if captures.get(0).unwrap().start() > 0 => {
+            invalid(&overrides[..captures.get(0).unwrap().start()])
+        }
+        _ => {}
+    }
+    languages
+        .iter()
+        .enumerate()
+        .map(|(i, captures)| {
+            let language = &captures[1];
+            let end = languages
+                .get(i + 1)
+                .map_or(overrides.len(), |next| next.get(0).unwrap().start());
+            let regex = overrides[captures.get(0).unwrap().end()..end].trim();
+            let regex = Regex::new(regex)
+                .ok()
+                .filter(|_| !regex.is_empty())
+                .unwrap_or_else(|| {
+                    fatal(format!(
+                        "Invalid regex in word-diff-regex-overrides for {}: {}. \
+                     The value must be a valid Rust regular expression. \
+                     See https://docs.rs/regex.",
+                        language, regex
+                    ))
+                });
+            (language.to_lowercase(), regex)
+        })
+        .collect()
+}
+
 /// Did the user supply `option` on the command line?
 pub fn user_supplied_option(option: &str, arg_matches: &clap::ArgMatches) -> bool {
     arg_matches.occurrences_of(option) > 0
@@ -421,7 +1119,7 @@ pub mod tests {
             Some(git_config_path),
         );
         assert_eq!(config.true_color, false);
-        assert_eq!(config.decorations_width, cli::Width::Fixed(100));
+        assert_eq!(config.decorations_width.get(), cli::Width::Fixed(100));
         assert_eq!(config.background_color_extends_to_terminal_width, true);
         assert_eq!(config.inspect_raw_lines, cli::InspectRawLines::True);
         assert_eq!(config.paging_mode, PagingMode::Never);
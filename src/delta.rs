@@ -1,7 +1,6 @@
 use std::borrow::Cow;
 use std::collections::HashMap;
-use std::io::BufRead;
-use std::io::Write;
+use std::io::{BufRead, BufWriter, Cursor, Read, Write};
 
 use bytelines::ByteLines;
 
@@ -9,10 +8,12 @@ use crate::ansi;
 use crate::config::delta_unreachable;
 use crate::config::Config;
 use crate::features;
+use crate::handlers::diff_header::HunkLine;
 use crate::handlers::hunk_header::ParsedHunkHeader;
 use crate::handlers::{self, merge_conflict};
 use crate::paint::Painter;
 use crate::style::DecorationStyle;
+use crate::utils::delta_output;
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum State {
@@ -72,6 +73,8 @@ impl DiffType {
 pub enum Source {
     GitDiff,     // Coming from a `git diff` command
     DiffUnified, // Coming from a `diff -u` command
+    Mercurial,   // Coming from an `hg diff` or `hg log -p` command
+    Svn,         // Coming from an `svn diff` command
     Unknown,
 }
 
@@ -98,6 +101,15 @@ pub struct StateMachine<'a> {
     pub plus_file_event: handlers::diff_header::FileEvent,
     pub diff_line: String,
     pub mode_info: String,
+    // The similarity index of a renamed or copied file, e.g. "95".
+    pub similarity_index: Option<String>,
+    // The object names of the old and new versions of the file, from the "index" line.
+    pub blob_ids: Option<(String, String)>,
+    // The GIT binary patch being read, if any.
+    pub binary_patch: Option<handlers::binary::BinaryPatch>,
+    // The counts of the changes to the current file, when the file labels display them.
+    pub file_stats: handlers::diff_header::FileStats,
+    pub file_revisions: handlers::diff_header::FileRevisions,
     pub painter: Painter<'a>,
     pub config: &'a Config,
 
@@ -108,14 +120,94 @@ pub struct StateMachine<'a> {
     // avoid emitting the file meta header line twice (#245).
     pub current_file_pair: Option<(String, String)>,
     pub handled_diff_header_header_line_file_pair: Option<(String, String)>,
+    // The commit of the most recent commit line, if any.
+    pub current_commit: Option<String>,
+    // The commit line and the author and date lines following it, while they are collected to be
+    // drawn in a box or formatted by --commit-format.
+    pub commit_meta_lines: Vec<(String, String)>,
+    // The lines of a diff stat section, while they are collected to be aligned, and the lines as
+    // they were read, which are written unchanged if the section does not end with a summary line.
+    pub diff_stat_lines: Vec<handlers::diff_stat::DiffStatLine>,
+    pub diff_stat_raw_lines: Vec<String>,
+    // The counts of the changes since the last commit line, for --diff-summary.
+    pub diff_summary: handlers::diff_summary::DiffSummary,
+    // The lines of a commit, while they are held back by --hide-empty-commits.
+    pub held_commit: Option<handlers::file_filter::HeldCommit>,
+    // Whether the hunks are in `git diff --word-diff=porcelain` format, and the lines of the
+    // current line of such a hunk, while they are collected.
+    pub word_diff_porcelain: handlers::word_diff::WordDiffPorcelain,
+    pub word_diff_records: Vec<(String, String)>,
+    // The lines of the diff of a submodule, from `git diff --submodule=diff`, while they are
+    // collected to be handled by a state machine of their own.
+    pub submodule_diff: Option<handlers::submodule::SubmoduleDiff>,
     pub blame_key_colors: HashMap<String, String>,
+    pub blame_porcelain: handlers::blame::BlamePorcelain,
+    // The writer of the manifest of the changed files, see --emit-manifest.
+    pub manifest: Option<handlers::manifest::Manifest>,
+    // Whether the lines are `git range-diff` output.
+    pub range_diff: bool,
+    // Whether the lines are a block of property changes of `svn diff` output.
+    pub svn_properties: bool,
+    // The path, line number and type of the previous line of `rg --json` output.
+    pub previous_ripgrep_json_line: Option<(String, Option<usize>, handlers::grep::LineType)>,
 }
 
 pub fn delta<I>(lines: ByteLines<I>, writer: &mut dyn Write, config: &Config) -> std::io::Result<()>
 where
     I: BufRead,
 {
-    StateMachine::new(writer, config).consume(lines)
+    // The output is written in blocks rather than line by line, see StateMachine::handle_line for
+    // when it is flushed.
+    let mut writer = BufWriter::new(writer);
+    if config.plain || !config.color {
+        let mut writer = ansi::StripAnsiWriter::new(&mut writer);
+        consume_or_pass_through(lines, &mut writer, config)?;
+        writer.flush()
+    } else {
+        consume_or_pass_through(lines, &mut writer, config)?;
+        writer.flush()
+    }
+}
+
+// With --idempotent, pass the input through unchanged if it is already delta's output. Deciding
+// this requires reading ahead, to the first file or hunk of the diff.
+fn consume_or_pass_through<I>(
+    mut lines: ByteLines<I>,
+    writer: &mut dyn Write,
+    config: &Config,
+) -> std::io::Result<()>
+where
+    I: BufRead,
+{
+    if !config.idempotent {
+        return StateMachine::new(writer, config).consume(lines);
+    }
+    let mut read_lines: Vec<u8> = Vec::new();
+    let mut n_read_lines = 0;
+    let mut is_delta_output = false;
+    while let Some(Ok(raw_line_bytes)) = lines.next() {
+        read_lines.extend_from_slice(raw_line_bytes);
+        read_lines.push(b'\n');
+        n_read_lines += 1;
+        if let Some(is_delta_output_line) = delta_output::is_delta_output_line(raw_line_bytes) {
+            is_delta_output = is_delta_output_line;
+            break;
+        }
+        if n_read_lines >= delta_output::MAX_LINES_TO_DETECT_DELTA_OUTPUT {
+            break;
+        }
+    }
+    if is_delta_output {
+        writer.write_all(&read_lines)?;
+        while let Some(Ok(raw_line_bytes)) = lines.next() {
+            writer.write_all(raw_line_bytes)?;
+            writer.write_all(b"\n")?;
+        }
+        Ok(())
+    } else {
+        let input = Cursor::new(read_lines).chain(delta_output::ByteLinesReader::new(lines));
+        StateMachine::new(writer, config).consume(ByteLines::new(input))
+    }
 }
 
 impl<'a> StateMachine<'a> {
@@ -131,52 +223,184 @@ impl<'a> StateMachine<'a> {
             plus_file_event: handlers::diff_header::FileEvent::NoEvent,
             diff_line: "".to_string(),
             mode_info: "".to_string(),
+            similarity_index: None,
+            blob_ids: None,
+            binary_patch: None,
+            file_stats: handlers::diff_header::FileStats::default(),
+            file_revisions: handlers::diff_header::FileRevisions::default(),
             current_file_pair: None,
             handled_diff_header_header_line_file_pair: None,
+            current_commit: None,
+            commit_meta_lines: Vec::new(),
+            diff_stat_lines: Vec::new(),
+            diff_stat_raw_lines: Vec::new(),
+            diff_summary: handlers::diff_summary::DiffSummary::default(),
+            held_commit: None,
+            word_diff_porcelain: handlers::word_diff::WordDiffPorcelain::default(),
+            word_diff_records: Vec::new(),
+            submodule_diff: None,
             painter: Painter::new(writer, config),
             config,
             blame_key_colors: HashMap::new(),
+            blame_porcelain: handlers::blame::BlamePorcelain::default(),
+            manifest: None,
+            range_diff: false,
+            svn_properties: false,
+            previous_ripgrep_json_line: None,
         }
     }
 
-    fn consume<I>(&mut self, mut lines: ByteLines<I>) -> std::io::Result<()>
+    fn consume<I>(&mut self, lines: ByteLines<I>) -> std::io::Result<()>
     where
         I: BufRead,
     {
-        while let Some(Ok(raw_line_bytes)) = lines.next() {
-            self.ingest_line(raw_line_bytes);
+        if let Some(path) = &self.config.emit_manifest {
+            self.manifest = Some(handlers::manifest::Manifest::create(path)?);
+        }
+        self.write_stdin_label()?;
+        self.handle_lines(lines)?;
+        self.write_diff_summary()?;
+        if let Some(manifest) = self.manifest.as_mut() {
+            manifest.finish()?;
+        }
+        Ok(())
+    }
 
-            if self.source == Source::Unknown {
-                self.source = detect_source(&self.line);
+    /// Handle the lines of a diff, and then any lines held back until its end.
+    pub fn handle_lines<I>(&mut self, mut lines: ByteLines<I>) -> std::io::Result<()>
+    where
+        I: BufRead,
+    {
+        if self.config.file_labels_use_stats
+            || self.config.collapse_files
+            || self.config.filter_files
+            || self.manifest.is_some()
+        {
+            // The diff of each file is read before it is handled, so that the counts of its
+            // changes are known when its header is written, when deciding whether to collapse
+            // it, and so that it can be dropped by the file filter. Otherwise lines are
+            // streamed. The manifest entry of each file is written once its diff is read.
+            let mut file_lines: Vec<Vec<u8>> = Vec::new();
+            let mut file_boundaries = handlers::diff_header::FileBoundaries::default();
+            while let Some(Ok(raw_line_bytes)) = lines.next() {
+                let line = ansi::strip_ansi_codes(&String::from_utf8_lossy(raw_line_bytes));
+                if file_boundaries.starts_file(&line) && !file_lines.is_empty() {
+                    self.handle_file_lines(&mut file_lines)?;
+                }
+                file_lines.push(raw_line_bytes.to_vec());
+            }
+            self.handle_file_lines(&mut file_lines)?;
+            self.end_held_commit()?;
+        } else {
+            while let Some(Ok(raw_line_bytes)) = lines.next() {
+                self.handle_line(raw_line_bytes)?;
             }
-
-            // Every method named handle_* must return std::io::Result<bool>.
-            // The bool indicates whether the line has been handled by that
-            // method (in which case no subsequent handlers are permitted to
-            // handle it).
-            let _ = self.handle_commit_meta_header_line()?
-                || self.handle_diff_stat_line()?
-                || self.handle_diff_header_diff_line()?
-                || self.handle_diff_header_file_operation_line()?
-                || self.handle_diff_header_minus_line()?
-                || self.handle_diff_header_plus_line()?
-                || self.handle_hunk_header_line()?
-                || self.handle_diff_header_mode_line()?
-                || self.handle_diff_header_misc_line()?
-                || self.handle_submodule_log_line()?
-                || self.handle_submodule_short_line()?
-                || self.handle_merge_conflict_line()?
-                || self.handle_hunk_line()?
-                || self.handle_git_show_file_line()?
-                || self.handle_blame_line()?
-                || self.handle_grep_line()?
-                || self.should_skip_line()
-                || self.emit_line_unchanged()?;
         }
 
+        self.write_submodule_diff()?;
+        self.write_word_diff_line()?;
+        self.write_commit_meta_lines()?;
+        self.write_diff_stat_lines()?;
+        self.write_binary_patch_summary()?;
         self.handle_pending_line_with_diff_name()?;
         self.painter.paint_buffered_minus_and_plus_lines();
-        self.painter.emit()?;
+        self.painter.emit()
+    }
+
+    fn handle_file_lines(&mut self, file_lines: &mut Vec<Vec<u8>>) -> std::io::Result<()> {
+        if matches!(file_lines.first(), Some(line) if self.is_submodule_diff_line(line)) {
+            // Handled, as a whole, by the state machine of the diff of the submodule.
+            for raw_line_bytes in file_lines.drain(..) {
+                self.handle_line(&raw_line_bytes)?;
+            }
+            return Ok(());
+        }
+        if self.config.filter_files {
+            self.filter_file_lines(file_lines)?;
+        }
+        // The lines of the file may end with the commit line of the next commit, so this is
+        // written before they are handled.
+        self.write_manifest_entry(file_lines)?;
+        let hunk_lines = handlers::diff_header::parse_hunk_lines(file_lines);
+        self.file_stats = handlers::diff_header::FileStats::from_hunk_lines(&hunk_lines);
+        // Whether the hunks are skipped, decided at the first hunk header, once the file header
+        // has been handled.
+        let mut is_collapsed = None;
+        for (raw_line_bytes, hunk_line) in file_lines.drain(..).zip(hunk_lines) {
+            if self.config.collapse_files && hunk_line != HunkLine::Outside {
+                if is_collapsed.is_none() {
+                    let should_collapse = self.should_collapse_file();
+                    if should_collapse {
+                        self.write_collapsed_file_summary()?;
+                    }
+                    is_collapsed = Some(should_collapse);
+                }
+                if is_collapsed == Some(true) {
+                    continue;
+                }
+            }
+            self.handle_line(&raw_line_bytes)?;
+        }
+        Ok(())
+    }
+
+    pub fn handle_line(&mut self, raw_line_bytes: &[u8]) -> std::io::Result<()> {
+        if self.handle_submodule_diff_line(raw_line_bytes)? {
+            return Ok(());
+        }
+        self.ingest_line(raw_line_bytes);
+
+        if self.source == Source::Unknown {
+            self.source = detect_source(&self.line);
+        }
+
+        // Every method named handle_* must return std::io::Result<bool>.
+        // The bool indicates whether the line has been handled by that
+        // method (in which case no subsequent handlers are permitted to
+        // handle it).
+        let _ = self.handle_range_diff_line()?
+            || self.handle_word_diff_porcelain_line()?
+            || self.handle_binary_patch_line()?
+            || self.handle_commit_meta_field_line()?
+            || self.handle_commit_meta_header_line()?
+            || self.handle_diff_stat_line()?
+            || self.handle_svn_property_line()?
+            || self.handle_diff_header_diff_line()?
+            || self.handle_diff_header_jj_line()?
+            || self.handle_diff_header_file_operation_line()?
+            || self.handle_diff_header_minus_line()?
+            || self.handle_diff_header_plus_line()?
+            || self.handle_hunk_header_line()?
+            || self.handle_diff_header_mode_line()?
+            || self.handle_diff_header_similarity_line()?
+            || self.handle_diff_header_index_line()?
+            || self.handle_binary_files_line()?
+            || self.handle_diff_header_misc_line()?
+            || self.handle_diff_only_in_line()?
+            || self.handle_submodule_log_line()?
+            || self.handle_submodule_log_commit_line()?
+            || self.handle_submodule_short_line()?
+            || self.handle_merge_conflict_line()?
+            || self.handle_hunk_line()?
+            || self.handle_git_show_file_line()?
+            || self.handle_blame_line()?
+            || self.handle_grep_line()?
+            || self.should_skip_line()
+            || self.emit_line_unchanged()?;
+
+        // The output of a hunk is written once the hunk is complete, at the latest at the next
+        // hunk header, and that of lines outside hunks as they are handled, so that it is not
+        // held back while the input is being read.
+        if !matches!(
+            self.state,
+            State::HunkZero(..)
+                | State::HunkMinus(..)
+                | State::HunkPlus(..)
+                | State::MergeConflict(..)
+        ) {
+            self.painter.emit()?;
+            self.painter.writer.flush()?;
+        }
         Ok(())
     }
 
@@ -230,6 +454,20 @@ impl<'a> StateMachine<'a> {
         Ok(handled_line)
     }
 
+    /// Write the banner line of --stdin-label, before the diff.
+    fn write_stdin_label(&mut self) -> std::io::Result<()> {
+        let label = match &self.config.stdin_label {
+            Some(label) if !self.config.color_only => label,
+            _ => return Ok(()),
+        };
+        handlers::draw::write_decorated_line(
+            self.painter.writer,
+            label,
+            &self.config.decorations_width.get(),
+            self.config.stdin_label_style,
+        )
+    }
+
     /// Should a handle_* function be called on this element?
     // TODO: I'm not sure the above description is accurate; I think this
     // function needs a more accurate name.
@@ -254,6 +492,7 @@ pub fn format_raw_line<'a>(line: &'a str, config: &Config) -> Cow<'a, str> {
 /// Currently can detect:
 /// * git diff
 /// * diff -u
+/// * svn diff
 fn detect_source(line: &str) -> Source {
     if line.starts_with("commit ")
         || line.starts_with("diff --git ")
@@ -261,6 +500,12 @@ fn detect_source(line: &str) -> Source {
         || line.starts_with("diff --combined ")
     {
         Source::GitDiff
+    } else if handlers::diff_header_hg::is_hg_changeset_line(line)
+        || handlers::diff_header_hg::is_hg_diff_line(line)
+    {
+        Source::Mercurial
+    } else if handlers::diff_header_svn::is_svn_index_line(line) {
+        Source::Svn
     } else if line.starts_with("diff -u")
         || line.starts_with("diff -ru")
         || line.starts_with("diff -r -u")
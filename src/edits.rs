@@ -6,6 +6,44 @@ use unicode_width::UnicodeWidthStr;
 use crate::align;
 use crate::minusplus::MinusPlus;
 
+/// The maximum distance between two lines for them to be inferred to be homologous, see
+/// --max-line-distance.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MaxLineDistance {
+    Fixed(f64),
+    // Depending on the number of words of the lines.
+    Auto,
+}
+
+impl MaxLineDistance {
+    // With "auto", lines of up to 2 words may be entirely different, and the maximum distance
+    // decreases by 0.15 each time the number of words doubles, down to 0.3.
+    fn for_words(&self, n_words: usize) -> f64 {
+        match self {
+            Self::Fixed(distance) => *distance,
+            Self::Auto => (1.0 - 0.15 * ((n_words.max(1) as f64).log2() - 1.0)).clamp(0.3, 1.0),
+        }
+    }
+}
+
+/// Which sections of a homologous pair of lines are emphasized, see --emph-mode.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EmphMode {
+    /// The sections changed by the inferred edit operations.
+    Span,
+    /// The tokens between the longest common prefix and suffix of the lines, like diff-highlight.
+    UniqueTokens,
+}
+
+impl std::fmt::Display for MaxLineDistance {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Fixed(distance) => write!(f, "{}", distance),
+            Self::Auto => write!(f, "auto"),
+        }
+    }
+}
+
 /// Infer the edit operations responsible for the differences between a collection of old and new
 /// lines. A "line" is a string. An annotated line is a Vec of (op, &str) pairs, where the &str
 /// slices are slices of the line, and their concatenation equals the line. Return the input minus
@@ -19,6 +57,12 @@ use crate::minusplus::MinusPlus;
 /// `noop_deletions[i]` is the appropriate deletion operation tag to be used for `minus_lines[i]`;
 /// `noop_deletions` is guaranteed to be the same length as `minus_lines`. The equivalent statements
 /// hold for `plus_insertions` and `plus_lines`.
+///
+/// With `EmphMode::UniqueTokens`, only the tokens between the common prefix and suffix of a
+/// homologous pair are annotated as deleted and inserted. The runs of deletions and insertions of a
+/// homologous pair narrower than `min_emph_length` are annotated as noop deletions and insertions.
+/// Before that, unchanged text narrower than `word_diff_context` between two changes of either line
+/// of a homologous pair is annotated as deleted and inserted in both lines, joining the runs.
 #[allow(clippy::too_many_arguments)]
 #[allow(clippy::type_complexity)]
 pub fn infer_edits<'a, EditOperation>(
@@ -29,8 +73,12 @@ pub fn infer_edits<'a, EditOperation>(
     noop_insertions: Vec<EditOperation>,
     insertion: EditOperation,
     tokenization_regex: &Regex,
-    max_line_distance: f64,
+    word_diff_algorithm: align::WordDiffAlgorithm,
+    emph_mode: EmphMode,
+    max_line_distance: MaxLineDistance,
     max_line_distance_for_naively_paired_lines: f64,
+    min_emph_length: usize,
+    word_diff_context: usize,
 ) -> (
     Vec<Vec<(EditOperation, &'a str)>>,  // annotated minus lines
     Vec<Vec<(EditOperation, &'a str)>>,  // annotated plus lines
@@ -48,11 +96,15 @@ where
     'minus_lines_loop: for (minus_index, minus_line) in minus_lines.iter().enumerate() {
         let mut considered = 0; // plus lines considered so far as match for minus_line
         for plus_line in &plus_lines[plus_index..] {
-            let alignment = align::Alignment::new(
-                tokenize(minus_line, tokenization_regex),
-                tokenize(plus_line, tokenization_regex),
+            let minus_tokens = tokenize(minus_line, tokenization_regex);
+            let plus_tokens = tokenize(plus_line, tokenization_regex);
+            let n_words = count_words(&minus_tokens).max(count_words(&plus_tokens));
+            let alignment = align::Alignment::with_algorithm(
+                minus_tokens.clone(),
+                plus_tokens.clone(),
+                word_diff_algorithm,
             );
-            let (annotated_minus_line, annotated_plus_line, distance) = annotate(
+            let (mut annotated_minus_line, mut annotated_plus_line, distance) = annotate(
                 alignment,
                 noop_deletions[minus_index],
                 deletion,
@@ -63,9 +115,52 @@ where
             );
             if minus_lines.len() == plus_lines.len()
                 && distance <= max_line_distance_for_naively_paired_lines
-                || distance <= max_line_distance
+                || distance <= max_line_distance.for_words(n_words)
             {
                 // minus_line and plus_line are inferred to be a homologous pair.
+                if emph_mode == EmphMode::UniqueTokens {
+                    annotated_minus_line = annotate_unique_tokens(
+                        &minus_tokens,
+                        &plus_tokens,
+                        noop_deletions[minus_index],
+                        deletion,
+                        minus_line,
+                    );
+                    annotated_plus_line = annotate_unique_tokens(
+                        &plus_tokens,
+                        &minus_tokens,
+                        noop_insertions[plus_index],
+                        insertion,
+                        plus_line,
+                    );
+                }
+                if word_diff_context > 0 && emph_mode == EmphMode::UniqueTokens {
+                    // The unique tokens of the lines are not aligned.
+                    emphasize_narrow_gaps(&mut annotated_minus_line, deletion, word_diff_context);
+                    emphasize_narrow_gaps(&mut annotated_plus_line, insertion, word_diff_context);
+                } else if word_diff_context > 0 {
+                    emphasize_narrow_gaps_of_pair(
+                        &mut annotated_minus_line,
+                        deletion,
+                        &mut annotated_plus_line,
+                        insertion,
+                        word_diff_context,
+                    );
+                }
+                if min_emph_length > 0 {
+                    unemphasize_narrow_runs(
+                        &mut annotated_minus_line,
+                        deletion,
+                        noop_deletions[minus_index],
+                        min_emph_length,
+                    );
+                    unemphasize_narrow_runs(
+                        &mut annotated_plus_line,
+                        insertion,
+                        noop_insertions[plus_index],
+                        min_emph_length,
+                    );
+                }
 
                 // Emit as unpaired the plus lines already considered and rejected
                 for plus_line in &plus_lines[plus_index..(plus_index + considered)] {
@@ -116,6 +211,185 @@ pub fn make_lines_have_homolog(
     )
 }
 
+// Annotate the tokens of `line` between the longest common prefix and suffix of its tokens and
+// those of its homolog with `emph`, unless these differ only in whitespace, and the rest with
+// `noop`.
+fn annotate_unique_tokens<'a, EditOperation>(
+    tokens: &[&str],
+    homolog_tokens: &[&str],
+    noop: EditOperation,
+    emph: EditOperation,
+    line: &'a str,
+) -> Vec<(EditOperation, &'a str)>
+where
+    EditOperation: Copy,
+{
+    let n_prefix = tokens
+        .iter()
+        .zip(homolog_tokens)
+        .take_while(|(token, homolog_token)| token == homolog_token)
+        .count();
+    let n_suffix = tokens[n_prefix..]
+        .iter()
+        .rev()
+        .zip(homolog_tokens[n_prefix..].iter().rev())
+        .take_while(|(token, homolog_token)| token == homolog_token)
+        .count();
+    let width = |tokens: &[&str]| tokens.iter().map(|token| token.len()).sum::<usize>();
+    let middle_start = width(&tokens[..n_prefix]);
+    let middle_end = line.len() - width(&tokens[tokens.len() - n_suffix..]);
+    let homolog_middle = &homolog_tokens[n_prefix..homolog_tokens.len() - n_suffix];
+    let middle = &line[middle_start..middle_end];
+    let is_whitespace_change =
+        middle.trim().is_empty() && homolog_middle.iter().all(|t| t.trim().is_empty());
+    let sections = [
+        (noop, &line[..middle_start]),
+        (if is_whitespace_change { noop } else { emph }, middle),
+        (noop, &line[middle_end..]),
+    ];
+    let annotated_line: Vec<_> = sections
+        .iter()
+        .copied()
+        .filter(|(_, section)| !section.is_empty())
+        .collect();
+    if annotated_line.is_empty() {
+        vec![(noop, line)]
+    } else {
+        annotated_line
+    }
+}
+
+// The number of tokens which are not whitespace.
+fn count_words(tokens: &[&str]) -> usize {
+    tokens
+        .iter()
+        .filter(|token| !token.trim().is_empty())
+        .count()
+}
+
+// Annotate the runs of consecutive sections with the `emph` operation whose width, ignoring
+// surrounding whitespace, is less than `min_emph_length` with the `noop` operation instead.
+fn unemphasize_narrow_runs<EditOperation>(
+    annotated_line: &mut [(EditOperation, &str)],
+    emph: EditOperation,
+    noop: EditOperation,
+    min_emph_length: usize,
+) where
+    EditOperation: Copy + PartialEq,
+{
+    let mut start = 0;
+    while start < annotated_line.len() {
+        let is_emph = annotated_line[start].0 == emph;
+        let end = annotated_line[start..]
+            .iter()
+            .position(|(op, _)| (*op == emph) != is_emph)
+            .map_or(annotated_line.len(), |n| start + n);
+        if is_emph {
+            let run: String = annotated_line[start..end]
+                .iter()
+                .map(|(_, section)| *section)
+                .collect();
+            if UnicodeWidthStr::width(run.trim()) < min_emph_length {
+                for (op, _) in &mut annotated_line[start..end] {
+                    *op = noop;
+                }
+            }
+        }
+        start = end;
+    }
+}
+
+// Annotate the runs of consecutive sections with another operation than `emph`, between two
+// sections with the `emph` operation, whose width is less than `word_diff_context` with `emph`.
+fn emphasize_narrow_gaps<EditOperation>(
+    annotated_line: &mut [(EditOperation, &str)],
+    emph: EditOperation,
+    word_diff_context: usize,
+) where
+    EditOperation: Copy + PartialEq,
+{
+    let mut start = match annotated_line.iter().position(|(op, _)| *op == emph) {
+        Some(start) => start,
+        None => return,
+    };
+    while start < annotated_line.len() {
+        let is_emph = annotated_line[start].0 == emph;
+        let end = annotated_line[start..]
+            .iter()
+            .position(|(op, _)| (*op == emph) != is_emph)
+            .map_or(annotated_line.len(), |n| start + n);
+        // A gap is followed by an emph section unless it ends the line.
+        if !is_emph && end < annotated_line.len() {
+            let width: usize = annotated_line[start..end]
+                .iter()
+                .map(|(_, section)| UnicodeWidthStr::width(*section))
+                .sum();
+            if width < word_diff_context {
+                for (op, _) in &mut annotated_line[start..end] {
+                    *op = emph;
+                }
+            }
+        }
+        start = end;
+    }
+}
+
+// Annotate the runs of unchanged sections of a homologous pair of lines, between two sections of
+// either line with the `deletion` or `insertion` operation, whose width is less than
+// `word_diff_context` with `deletion` and `insertion`. The unchanged sections of the lines are
+// those of the same alignment operations, and so a gap is joined in both lines or in neither.
+fn emphasize_narrow_gaps_of_pair<EditOperation>(
+    annotated_minus_line: &mut [(EditOperation, &str)],
+    deletion: EditOperation,
+    annotated_plus_line: &mut [(EditOperation, &str)],
+    insertion: EditOperation,
+    word_diff_context: usize,
+) where
+    EditOperation: Copy + PartialEq,
+{
+    let (mut i, mut j) = (0, 0);
+    let mut follows_change = false;
+    loop {
+        while annotated_minus_line.get(i).map(|(op, _)| *op) == Some(deletion) {
+            i += 1;
+            follows_change = true;
+        }
+        while annotated_plus_line.get(j).map(|(op, _)| *op) == Some(insertion) {
+            j += 1;
+            follows_change = true;
+        }
+        let (gap_start_i, gap_start_j) = (i, j);
+        while i < annotated_minus_line.len()
+            && annotated_minus_line[i].0 != deletion
+            && j < annotated_plus_line.len()
+            && annotated_plus_line[j].0 != insertion
+        {
+            i += 1;
+            j += 1;
+        }
+        if i == gap_start_i {
+            return;
+        }
+        // A gap is followed by a change unless it ends the lines.
+        let precedes_change = i < annotated_minus_line.len() || j < annotated_plus_line.len();
+        if follows_change && precedes_change {
+            let width: usize = annotated_minus_line[gap_start_i..i]
+                .iter()
+                .map(|(_, section)| UnicodeWidthStr::width(*section))
+                .sum();
+            if width < word_diff_context {
+                for (op, _) in &mut annotated_minus_line[gap_start_i..i] {
+                    *op = deletion;
+                }
+                for (op, _) in &mut annotated_plus_line[gap_start_j..j] {
+                    *op = insertion;
+                }
+            }
+        }
+        follows_change = true;
+    }
+}
+
 /// Split line into tokens for alignment. The alignment algorithm aligns sequences of substrings;
 /// not individual characters.
 fn tokenize<'a>(line: &'a str, regex: &Regex) -> Vec<&'a str> {
@@ -755,11 +1029,267 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_max_line_distance_auto() {
+        assert_eq!(MaxLineDistance::Fixed(0.6).for_words(100), 0.6);
+        assert_eq!(MaxLineDistance::Auto.for_words(0), 1.0);
+        assert_eq!(MaxLineDistance::Auto.for_words(2), 1.0);
+        assert_eq!(MaxLineDistance::Auto.for_words(8), 0.7);
+        assert_eq!(MaxLineDistance::Auto.for_words(1000), 0.3);
+
+        // A short line may be entirely different from its homolog.
+        let expected_edits = (
+            vec![vec![(MinusNoop, ""), (Deletion, "aaa")]],
+            vec![vec![(PlusNoop, ""), (Insertion, "bbb")]],
+        );
+        assert_edits_with(
+            vec!["aaa"],
+            vec!["bbb"],
+            expected_edits,
+            EmphMode::Span,
+            MaxLineDistance::Auto,
+            0,
+        );
+        assert_no_edits(vec!["aaa"], vec!["bbb"], 0.6);
+
+        // A long line half of whose words are changed has no homolog, unlike with a distance of 0.6.
+        let minus_line = (0..30).map(|i| format!("w{}", i)).join(" ");
+        let plus_line = (0..30)
+            .map(|i| format!("{}{}", if i % 2 == 1 { "x" } else { "w" }, i))
+            .join(" ");
+        let expected_edits = (
+            vec![vec![(MinusNoop, minus_line.as_str())]],
+            vec![vec![(PlusNoop, plus_line.as_str())]],
+        );
+        assert_edits_with(
+            vec![&minus_line],
+            vec![&plus_line],
+            expected_edits.clone(),
+            EmphMode::Span,
+            MaxLineDistance::Auto,
+            0,
+        );
+        let (_, _, line_alignment) = infer_edits(
+            vec![&minus_line],
+            vec![&plus_line],
+            vec![MinusNoop],
+            Deletion,
+            vec![PlusNoop],
+            Insertion,
+            &DEFAULT_TOKENIZATION_REGEXP,
+            align::WordDiffAlgorithm::Levenshtein,
+            EmphMode::Span,
+            MaxLineDistance::Fixed(0.6),
+            0.0,
+            0,
+            0,
+        );
+        assert_eq!(line_alignment, vec![(Some(0), Some(0))]);
+    }
+
+    #[test]
+    fn test_word_diff_context() {
+        let minus_line = vec![
+            (MinusNoop, ""),
+            (Deletion, "aaa"),
+            (MinusNoop, " bbb "),
+            (Deletion, "ccc"),
+            (MinusNoop, " ddd"),
+        ];
+        let plus_line = vec![
+            (PlusNoop, ""),
+            (Insertion, "xxx"),
+            (PlusNoop, " bbb "),
+            (Insertion, "zzz"),
+            (PlusNoop, " ddd"),
+        ];
+        for (word_diff_context, gap) in [(0, MinusNoop), (5, MinusNoop), (6, Deletion)] {
+            let mut expected_minus_line = minus_line.clone();
+            expected_minus_line[2].0 = gap;
+            let mut expected_plus_line = plus_line.clone();
+            if gap == Deletion {
+                expected_plus_line[2].0 = Insertion;
+            }
+            assert_edits_with_word_diff_context(
+                vec!["aaa bbb ccc ddd"],
+                vec!["xxx bbb zzz ddd"],
+                (vec![expected_minus_line], vec![expected_plus_line]),
+                EmphMode::Span,
+                MaxLineDistance::Fixed(1.0),
+                0,
+                word_diff_context,
+            );
+        }
+    }
+
+    #[test]
+    fn test_word_diff_context_of_pair() {
+        // The gap follows a change of the minus line and precedes a change of the plus line, and
+        // is joined in both lines.
+        for (word_diff_context, minus_gap, plus_gap) in
+            [(7, MinusNoop, PlusNoop), (8, Deletion, Insertion)]
+        {
+            assert_edits_with_word_diff_context(
+                vec!["aaa b c d e f"],
+                vec!["b c d e zzz f"],
+                (
+                    vec![vec![
+                        (MinusNoop, ""),
+                        (Deletion, "aaa "),
+                        (minus_gap, "b c d e"),
+                        (MinusNoop, " f"),
+                    ]],
+                    vec![vec![
+                        (PlusNoop, ""),
+                        (plus_gap, "b c d e"),
+                        (Insertion, " zzz"),
+                        (PlusNoop, " f"),
+                    ]],
+                ),
+                EmphMode::Span,
+                MaxLineDistance::Fixed(1.0),
+                0,
+                word_diff_context,
+            );
+        }
+    }
+
+    #[test]
+    fn test_min_emph_length() {
+        assert_edits_with(
+            vec!["aaa ccc"],
+            vec!["aba ccc"],
+            (
+                vec![vec![
+                    (MinusNoop, ""),
+                    (Deletion, "aaa"),
+                    (MinusNoop, " ccc"),
+                ]],
+                vec![vec![(PlusNoop, ""), (Insertion, "aba"), (PlusNoop, " ccc")]],
+            ),
+            EmphMode::Span,
+            MaxLineDistance::Fixed(1.0),
+            3,
+        );
+        assert_edits_with(
+            vec!["let x = f(a);"],
+            vec!["let x = f(b);"],
+            (
+                vec![vec![
+                    (MinusNoop, "let x = f("),
+                    (MinusNoop, "a"),
+                    (MinusNoop, ");"),
+                ]],
+                vec![vec![
+                    (PlusNoop, "let x = f("),
+                    (PlusNoop, "b"),
+                    (PlusNoop, ");"),
+                ]],
+            ),
+            EmphMode::Span,
+            MaxLineDistance::Fixed(1.0),
+            2,
+        );
+    }
+
+    #[test]
+    fn test_emph_mode_unique_tokens() {
+        assert_edits_with(
+            vec!["f(a, b);"],
+            vec!["f(x, b, y);"],
+            (
+                vec![vec![
+                    (MinusNoop, "f("),
+                    (Deletion, "a, b"),
+                    (MinusNoop, ");"),
+                ]],
+                vec![vec![
+                    (PlusNoop, "f("),
+                    (Insertion, "x, b, y"),
+                    (PlusNoop, ");"),
+                ]],
+            ),
+            EmphMode::UniqueTokens,
+            MaxLineDistance::Fixed(1.0),
+            0,
+        );
+        // Only inserted tokens.
+        assert_edits_with(
+            vec!["f(a);"],
+            vec!["f(a, b);"],
+            (
+                vec![vec![(MinusNoop, "f(a"), (MinusNoop, ");")]],
+                vec![vec![
+                    (PlusNoop, "f(a"),
+                    (Insertion, ", b"),
+                    (PlusNoop, ");"),
+                ]],
+            ),
+            EmphMode::UniqueTokens,
+            MaxLineDistance::Fixed(1.0),
+            0,
+        );
+        // Reindentation.
+        assert_edits_with(
+            vec!["  f(a);"],
+            vec!["    f(a);"],
+            (
+                vec![vec![(MinusNoop, "  "), (MinusNoop, "f(a);")]],
+                vec![vec![
+                    (PlusNoop, "  "),
+                    (PlusNoop, "  "),
+                    (PlusNoop, "f(a);"),
+                ]],
+            ),
+            EmphMode::UniqueTokens,
+            MaxLineDistance::Fixed(1.0),
+            0,
+        );
+    }
+
     fn assert_edits(
         minus_lines: Vec<&str>,
         plus_lines: Vec<&str>,
         expected_edits: Edits,
         max_line_distance: f64,
+    ) {
+        assert_edits_with(
+            minus_lines,
+            plus_lines,
+            expected_edits,
+            EmphMode::Span,
+            MaxLineDistance::Fixed(max_line_distance),
+            0,
+        )
+    }
+
+    fn assert_edits_with(
+        minus_lines: Vec<&str>,
+        plus_lines: Vec<&str>,
+        expected_edits: Edits,
+        emph_mode: EmphMode,
+        max_line_distance: MaxLineDistance,
+        min_emph_length: usize,
+    ) {
+        assert_edits_with_word_diff_context(
+            minus_lines,
+            plus_lines,
+            expected_edits,
+            emph_mode,
+            max_line_distance,
+            min_emph_length,
+            0,
+        )
+    }
+
+    fn assert_edits_with_word_diff_context(
+        minus_lines: Vec<&str>,
+        plus_lines: Vec<&str>,
+        expected_edits: Edits,
+        emph_mode: EmphMode,
+        max_line_distance: MaxLineDistance,
+        min_emph_length: usize,
+        word_diff_context: usize,
     ) {
         let (minus_lines, noop_deletions): (Vec<&str>, Vec<EditOperation>) =
             minus_lines.into_iter().map(|s| (s, MinusNoop)).unzip();
@@ -773,8 +1303,12 @@ mod tests {
             noop_insertions,
             Insertion,
             &*DEFAULT_TOKENIZATION_REGEXP,
+            align::WordDiffAlgorithm::Levenshtein,
+            emph_mode,
             max_line_distance,
             0.0,
+            min_emph_length,
+            word_diff_context,
         );
         // compare_annotated_lines(actual_edits, expected_edits);
         // TODO: test line alignment
@@ -1,9 +1,11 @@
 use std::env;
 
+const CLICOLOR_FORCE: &str = "CLICOLOR_FORCE";
 const COLORTERM: &str = "COLORTERM";
 const BAT_THEME: &str = "BAT_THEME";
 const GIT_CONFIG_PARAMETERS: &str = "GIT_CONFIG_PARAMETERS";
 const GIT_PREFIX: &str = "GIT_PREFIX";
+const NO_COLOR: &str = "NO_COLOR";
 const DELTA_FEATURES: &str = "DELTA_FEATURES";
 const DELTA_NAVIGATE: &str = "DELTA_NAVIGATE";
 const DELTA_EXPERIMENTAL_MAX_LINE_DISTANCE_FOR_NAIVELY_PAIRED_LINES: &str =
@@ -11,10 +13,13 @@ const DELTA_EXPERIMENTAL_MAX_LINE_DISTANCE_FOR_NAIVELY_PAIRED_LINES: &str =
 const DELTA_PAGER: &str = "DELTA_PAGER";
 const BAT_PAGER: &str = "BAT_PAGER";
 const PAGER: &str = "PAGER";
+const TERM: &str = "TERM";
+const TERM_PROGRAM: &str = "TERM_PROGRAM";
 
 #[derive(Default, Clone)]
 pub struct DeltaEnv {
     pub bat_theme: Option<String>,
+    pub clicolor_force: Option<String>,
     pub colorterm: Option<String>,
     pub current_dir: Option<std::path::PathBuf>,
     pub experimental_max_line_distance_for_naively_paired_lines: Option<String>,
@@ -22,13 +27,17 @@ pub struct DeltaEnv {
     pub git_config_parameters: Option<String>,
     pub git_prefix: Option<String>,
     pub navigate: Option<String>,
+    pub no_color: Option<String>,
     pub pagers: (Option<String>, Option<String>, Option<String>),
+    pub term: Option<String>,
+    pub term_program: Option<String>,
 }
 
 impl DeltaEnv {
     /// Create a structure with current environment variable
     pub fn init() -> Self {
         let bat_theme = env::var(BAT_THEME).ok();
+        let clicolor_force = env::var(CLICOLOR_FORCE).ok();
         let colorterm = env::var(COLORTERM).ok();
         let experimental_max_line_distance_for_naively_paired_lines =
             env::var(DELTA_EXPERIMENTAL_MAX_LINE_DISTANCE_FOR_NAIVELY_PAIRED_LINES).ok();
@@ -36,6 +45,9 @@ impl DeltaEnv {
         let git_config_parameters = env::var(GIT_CONFIG_PARAMETERS).ok();
         let git_prefix = env::var(GIT_PREFIX).ok();
         let navigate = env::var(DELTA_NAVIGATE).ok();
+        let no_color = env::var(NO_COLOR).ok();
+        let term = env::var(TERM).ok();
+        let term_program = env::var(TERM_PROGRAM).ok();
 
         let current_dir = env::current_dir().ok();
         let pagers = (
@@ -46,6 +58,7 @@ impl DeltaEnv {
 
         Self {
             bat_theme,
+            clicolor_force,
             colorterm,
             current_dir,
             experimental_max_line_distance_for_naively_paired_lines,
@@ -53,7 +66,10 @@ impl DeltaEnv {
             git_config_parameters,
             git_prefix,
             navigate,
+            no_color,
             pagers,
+            term,
+            term_program,
         }
     }
 }
@@ -18,6 +18,12 @@ pub fn _make_feature(bold: bool) -> Vec<(String, OptionValueFunction)> {
             Some("color.diff.commit"),
             _opt => "raw"
         ),
+        (
+            "emph-mode",
+            String,
+            None,
+            _opt => "unique-tokens"
+        ),
         (
             "minus-style",
             String,
@@ -78,6 +84,7 @@ mod test_utils {
         assert_eq!(opt.plus_style, "green");
         assert_eq!(opt.plus_non_emph_style, "green");
         assert_eq!(opt.plus_emph_style, "green reverse");
+        assert_eq!(opt.emph_mode, "unique-tokens");
     }
 
     #[test]
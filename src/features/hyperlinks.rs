@@ -2,12 +2,15 @@ use std::borrow::Cow;
 use std::path::Path;
 use std::str::FromStr;
 
+use itertools::Itertools;
 use lazy_static::lazy_static;
 use regex::{Captures, Regex};
 
+use crate::ansi;
 use crate::config::Config;
 use crate::features::OptionValueFunction;
-use crate::git_config::{GitConfig, GitConfigEntry, GitRemoteRepo};
+use crate::git_config::{GitConfigEntry, GitRemoteRepo};
+use crate::utils;
 
 pub fn make_feature() -> Vec<(String, OptionValueFunction)> {
     builtin_feature!([
@@ -24,27 +27,139 @@ pub fn format_commit_line_with_osc8_commit_hyperlink<'a>(
     line: &'a str,
     config: &Config,
 ) -> Cow<'a, str> {
+    if let Some(commit) = get_commit_from_commit_regex(line, config) {
+        return match get_commit_url(&commit, config) {
+            Some(url) => {
+                Cow::from(line.replacen(&commit, &format_osc8_hyperlink(&url, &commit), 1))
+            }
+            None => Cow::from(line),
+        };
+    }
     if let Some(commit_link_format) = &config.hyperlinks_commit_link_format {
         COMMIT_LINE_REGEX.replace(line, |captures: &Captures| {
-            let commit = captures.get(2).unwrap().as_str();
-            format_osc8_hyperlink(&commit_link_format.replace("{commit}", commit), commit)
+            format_commit_line_captures_with_osc8_commit_hyperlink(captures, |commit| {
+                commit_link_format.replace("{commit}", commit)
+            })
         })
-    } else if let Some(GitConfigEntry::GitRemote(repo)) =
-        config.git_config.as_ref().and_then(get_remote_url)
-    {
+    } else if let Some(GitConfigEntry::GitRemote(repo)) = get_remote_url(config) {
         COMMIT_LINE_REGEX.replace(line, |captures: &Captures| {
-            format_commit_line_captures_with_osc8_commit_hyperlink(captures, &repo)
+            format_commit_line_captures_with_osc8_commit_hyperlink(captures, |commit| {
+                repo.format_commit_url(commit)
+            })
         })
     } else {
         Cow::from(line)
     }
 }
 
-fn get_remote_url(git_config: &GitConfig) -> Option<GitConfigEntry> {
-    git_config
+/// Return the commit hash in a commit line: the "commit" capture group of --commit-regex if it
+/// matches, otherwise the first word that looks like a commit hash.
+pub fn get_commit_from_commit_line(line: &str, config: &Config) -> Option<String> {
+    get_commit_from_commit_regex(line, config).or_else(|| {
+        COMMIT_LINE_REGEX
+            .captures(line)
+            .map(|captures| captures.get(2).unwrap().as_str().to_string())
+    })
+}
+
+// The regex is matched against the line with ANSI escape sequences removed.
+fn get_commit_from_commit_regex(line: &str, config: &Config) -> Option<String> {
+    if !config
+        .commit_regex
+        .capture_names()
+        .any(|name| name == Some("commit"))
+    {
+        return None;
+    }
+    config
+        .commit_regex
+        .captures(&ansi::strip_ansi_codes(line))?
+        .name("commit")
+        .map(|commit| commit.as_str().to_string())
+        .filter(|commit| !commit.is_empty())
+}
+
+fn get_commit_url(commit: &str, config: &Config) -> Option<String> {
+    if let Some(commit_link_format) = &config.hyperlinks_commit_link_format {
+        Some(commit_link_format.replace("{commit}", commit))
+    } else if let Some(GitConfigEntry::GitRemote(repo)) = get_remote_url(config) {
+        Some(repo.format_commit_url(commit))
+    } else {
+        None
+    }
+}
+
+/// Create a hyperlink to `commit`, displaying `text`. Return None if there is no commit link
+/// format and the remote repository is not known.
+pub fn format_osc8_commit_hyperlink(commit: &str, text: &str, config: &Config) -> Option<String> {
+    get_commit_url(commit, config).map(|url| format_osc8_hyperlink(&url, text))
+}
+
+/// Create a hyperlink to the file at `path` as of `commit` on the remote repository, displaying
+/// `text`. Return None if the remote repository is not known.
+pub fn format_osc8_remote_file_hyperlink(
+    path: &Path,
+    commit: &str,
+    text: &str,
+    config: &Config,
+) -> Option<String> {
+    let repo = config.git_config.as_ref()?.repo.as_ref()?;
+    let path_in_repo = path
+        .strip_prefix(repo.workdir()?)
+        .ok()?
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .join("/");
+    match get_remote_url(config)? {
+        GitConfigEntry::GitRemote(remote) => Some(format_osc8_hyperlink(
+            &remote.format_file_url(commit, &path_in_repo),
+            text,
+        )),
+        _ => None,
+    }
+}
+
+/// Create a hyperlink to the comparison of the commits `from` and `to` of the submodule at `path`
+/// on its remote repository, displaying `text`. Return None if the remote repository is not known.
+pub fn format_osc8_submodule_compare_hyperlink(
+    path: &str,
+    from: &str,
+    to: &str,
+    text: &str,
+    config: &Config,
+) -> Option<String> {
+    let submodule = config
+        .git_config
+        .as_ref()?
         .repo
         .as_ref()?
-        .find_remote("origin")
+        .find_submodule(path)
+        .ok()?;
+    // The URL in .gitmodules may be relative to the URL of the superproject.
+    let url = submodule
+        .open()
+        .ok()
+        .and_then(|repo| {
+            repo.find_remote(&config.hyperlinks_remote)
+                .ok()?
+                .url()
+                .map(str::to_string)
+        })
+        .or_else(|| submodule.url().map(str::to_string))?;
+    let remote = GitRemoteRepo::from_str(&url).ok()?;
+    Some(format_osc8_hyperlink(
+        &remote.format_compare_url(from, to),
+        text,
+    ))
+}
+
+fn get_remote_url(config: &Config) -> Option<GitConfigEntry> {
+    config
+        .git_config
+        .as_ref()?
+        .repo
+        .as_ref()?
+        .find_remote(&config.hyperlinks_remote)
         .ok()?
         .url()
         .and_then(|url| {
@@ -54,10 +169,15 @@ fn get_remote_url(git_config: &GitConfig) -> Option<GitConfigEntry> {
         })
 }
 
-/// Create a file hyperlink, displaying `text`.
+/// Create a file hyperlink, displaying `text`. If `commit` is given and
+/// --hyperlinks-file-commit-link-format is set, link to the file as of that commit, otherwise to
+/// the local file. A "{column}" placeholder is replaced by 1 if the column is not known, so that
+/// the URL remains valid.
 pub fn format_osc8_file_hyperlink<'a, P>(
     absolute_path: P,
     line_number: Option<usize>,
+    column: Option<usize>,
+    commit: Option<&str>,
     text: &str,
     config: &Config,
 ) -> Cow<'a, str>
@@ -66,17 +186,104 @@ where
     P: std::fmt::Debug,
 {
     debug_assert!(absolute_path.as_ref().is_absolute());
-    let mut url = config
-        .hyperlinks_file_link_format
-        .replace("{path}", &absolute_path.as_ref().to_string_lossy());
+    let rule = (!config.hyperlinks_file_link_rules.is_empty())
+        .then(|| get_path_relative_to_repo_root(absolute_path.as_ref(), config))
+        .and_then(|path_in_repo| {
+            config
+                .hyperlinks_file_link_rules
+                .iter()
+                .find(|rule| rule.glob.is_match(&path_in_repo))
+                .map(|rule| (rule, path_in_repo))
+        });
+    let mut url = match (rule, commit, &config.hyperlinks_file_commit_link_format) {
+        // As in --hyperlinks-file-commit-link-format, a web URL contains the path in the
+        // repository, whereas e.g. an editor opens the local file.
+        (Some((rule, path_in_repo)), _, _) if is_web_url(&rule.format) => {
+            rule.format.replace("{path}", &path_in_repo)
+        }
+        (Some((rule, _)), _, _) => rule
+            .format
+            .replace("{path}", &absolute_path.as_ref().to_string_lossy()),
+        (None, Some(commit), Some(file_commit_link_format)) => {
+            file_commit_link_format.replace("{commit}", commit).replace(
+                "{path}",
+                &get_path_relative_to_repo_root(absolute_path.as_ref(), config),
+            )
+        }
+        _ => config
+            .hyperlinks_file_link_format
+            .replace("{path}", &absolute_path.as_ref().to_string_lossy()),
+    };
     if let Some(n) = line_number {
         url = url.replace("{line}", &format!("{}", n))
     } else {
         url = url.replace("{line}", "")
     };
+    url = url.replace("{column}", &format!("{}", column.unwrap_or(1)));
     Cow::from(format_osc8_hyperlink(&url, text))
 }
 
+/// A rule of --hyperlinks-file-link-rules: files whose path relative to the repository root
+/// matches `glob` are linked using `format`.
+#[derive(Clone, Debug)]
+pub struct FileLinkRule {
+    glob: Regex,
+    format: String,
+}
+
+fn is_web_url(format: &str) -> bool {
+    format.starts_with("https://") || format.starts_with("http://")
+}
+
+/// Parse rules of the form "GLOB=FORMAT", separated by ';'.
+pub fn parse_file_link_rules(rules: &str) -> Result<Vec<FileLinkRule>, String> {
+    rules
+        .split(';')
+        .map(str::trim)
+        .filter(|rule| !rule.is_empty())
+        .map(|rule| match rule.split_once('=') {
+            Some((glob, format)) if !glob.trim().is_empty() && !format.trim().is_empty() => {
+                Ok(FileLinkRule {
+                    glob: utils::path::glob_to_regex(glob.trim())
+                        .map_err(|err| format!("Invalid rule \"{}\": {}", rule, err))?,
+                    format: format.trim().to_string(),
+                })
+            }
+            _ => Err(format!(
+                "Invalid rule \"{}\": expected GLOB=FORMAT, e.g. \"src/**=file://{{path}}\"",
+                rule
+            )),
+        })
+        .collect()
+}
+
+/// Return true if files of `commit` link to the file as of the commit rather than to the local
+/// file.
+pub fn links_to_file_at_commit(commit: Option<&str>, config: &Config) -> bool {
+    commit.is_some() && config.hyperlinks_file_commit_link_format.is_some()
+}
+
+// Return the path of a file in the repository, with '/' separators, as used in forge URLs.
+fn get_path_relative_to_repo_root(absolute_path: &Path, config: &Config) -> String {
+    let repo_workdir = config
+        .git_config
+        .as_ref()
+        .and_then(|git_config| git_config.repo.as_ref())
+        .and_then(|repo| repo.workdir());
+    // If delta was invoked by git then its cwd is the repository root.
+    match repo_workdir
+        .into_iter()
+        .chain(config.cwd_of_delta_process.as_deref())
+        .find_map(|root| absolute_path.strip_prefix(root).ok())
+    {
+        Some(path) => path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .join("/"),
+        None => absolute_path.to_string_lossy().to_string(),
+    }
+}
+
 fn format_osc8_hyperlink(url: &str, text: &str) -> String {
     format!(
         "{osc}8;;{url}{st}{text}{osc}8;;{st}",
@@ -88,17 +295,20 @@ fn format_osc8_hyperlink(url: &str, text: &str) -> String {
 }
 
 lazy_static! {
-    static ref COMMIT_LINE_REGEX: Regex = Regex::new("(.* )?([0-9a-f]{8,40})(.*)").unwrap();
+    // Commit hashes are abbreviated to 7 characters by e.g. `git log --abbrev-commit`. The first
+    // word which looks like a commit hash is the commit, later ones may be e.g. in its subject.
+    static ref COMMIT_LINE_REGEX: Regex =
+        Regex::new(r"^(.*?)\b([0-9a-f]{7,40})\b(.*)").unwrap();
 }
 
 fn format_commit_line_captures_with_osc8_commit_hyperlink(
     captures: &Captures,
-    repo: &GitRemoteRepo,
+    format_commit_url: impl Fn(&str) -> String,
 ) -> String {
     let commit = captures.get(2).unwrap().as_str();
     format!(
         "{prefix}{osc}8;;{url}{st}{commit}{osc}8;;{st}{suffix}",
-        url = repo.format_commit_url(commit),
+        url = format_commit_url(commit),
         commit = commit,
         prefix = captures.get(1).map(|m| m.as_str()).unwrap_or(""),
         suffix = captures.get(3).unwrap().as_str(),
@@ -111,7 +321,7 @@ fn format_commit_line_captures_with_osc8_commit_hyperlink(
 #[cfg(test)]
 pub mod tests {
     use std::iter::FromIterator;
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
 
     use super::*;
     use crate::{
@@ -285,6 +495,323 @@ pub mod tests {
         });
     }
 
+    #[test]
+    fn test_hyperlinks_to_untransformed_path() {
+        let mut config = integration_test_utils::make_config_from_args(&[
+            "--navigate",
+            "--hyperlinks",
+            "--hyperlinks-file-link-format",
+            "{path}",
+            "--file-transformation",
+            "s,^packages/,,;s,/src/,/,",
+        ]);
+        config.cwd_relative_to_repo_root = Some("".to_string());
+        config.cwd_of_user_shell_process = utils::path::cwd_of_user_shell_process(
+            config.cwd_of_delta_process.as_ref(),
+            config.cwd_relative_to_repo_root.as_deref(),
+        );
+        let path = "packages/app/src/a.rs";
+        DeltaTest::with_config(&config)
+            .with_calling_process("git diff")
+            .with_input(&GIT_DIFF_OUTPUT.replace("__path__", path))
+            .expect_raw_contains(&format!(
+                "Δ {}",
+                format_osc8_hyperlink(
+                    &utils::path::fake_delta_cwd_for_tests()
+                        .join(path)
+                        .to_string_lossy(),
+                    "app/a.rs"
+                )
+            ));
+    }
+
+    #[test]
+    fn test_hyperlinks_grep_column() {
+        let make_config = || {
+            let mut config = integration_test_utils::make_config_from_args(&[
+                "--hyperlinks",
+                "--hyperlinks-file-link-format",
+                "{path}:{line}:{column}",
+                "--grep-file-style",
+                "raw",
+                "--grep-line-number-style",
+                "raw",
+            ]);
+            config.cwd_relative_to_repo_root = Some("".to_string());
+            config.cwd_of_user_shell_process = utils::path::cwd_of_user_shell_process(
+                config.cwd_of_delta_process.as_ref(),
+                config.cwd_relative_to_repo_root.as_deref(),
+            );
+            config
+        };
+        let url = |suffix: &str| {
+            format!(
+                "{}{}",
+                utils::path::fake_delta_cwd_for_tests()
+                    .join("b/a.txt")
+                    .to_string_lossy(),
+                suffix
+            )
+        };
+
+        for calling_process in ["git grep -n --column foo", "rg --vimgrep foo"] {
+            let config = make_config();
+            DeltaTest::with_config(&config)
+                .with_calling_process(calling_process)
+                .with_input("b/a.txt:7:5:some foo\n")
+                .expect_raw_contains(&format_osc8_hyperlink(&url(":7:5"), "b/a.txt:7:"));
+        }
+
+        // Without a known column, the placeholder is replaced by 1.
+        let config = make_config();
+        DeltaTest::with_config(&config)
+            .with_calling_process("git grep -n foo")
+            .with_input("b/a.txt:7:some foo\n")
+            .expect_raw_contains(&format_osc8_hyperlink(&url(":7:1"), "b/a.txt:7:"));
+    }
+
+    #[test]
+    fn test_hyperlinks_line_numbers() {
+        let root = std::env::current_dir().unwrap();
+        let url = |line| format!("{}:{}", root.join("src/align.rs").to_string_lossy(), line);
+        let input = GIT_DIFF_OUTPUT
+            .replace("__path__", "src/align.rs")
+            .replace("@@ -1 +1 @@", "@@ -7 +8 @@");
+        let make_config = |args: &[&str]| {
+            let mut config = integration_test_utils::make_config_from_args(
+                &[
+                    &[
+                        "--line-numbers",
+                        "--line-numbers-min-width",
+                        "4",
+                        "--hyperlinks",
+                    ],
+                    args,
+                    &["--hyperlinks-file-link-format", "{path}:{line}"],
+                ]
+                .concat(),
+            );
+            config.cwd_of_delta_process = Some(root.clone());
+            config
+        };
+
+        // Only the digits are linked, not the padding of the line number field.
+        let output = DeltaTest::with_config(&make_config(&[]))
+            .with_input(&input)
+            .expect_raw_contains(&format!("  {} ", format_osc8_hyperlink(&url(8), "8")));
+        assert!(!output
+            .raw_output
+            .contains(&format_osc8_hyperlink(&url(7), "7")));
+        DeltaTest::with_config(&make_config(&["--hyperlinks-minus-line-numbers"]))
+            .with_input(&input)
+            .expect_raw_contains(&format!("  {} ", format_osc8_hyperlink(&url(7), "7")));
+
+        // Files which do not exist locally are not linked.
+        let input = GIT_DIFF_OUTPUT.replace("__path__", "does/not/exist.rs");
+        let output = DeltaTest::with_config(&make_config(&[])).with_input(&input);
+        let url = format!("{}:1", root.join("does/not/exist.rs").to_string_lossy());
+        assert!(!output
+            .raw_output
+            .contains(&format_osc8_hyperlink(&url, "1")));
+    }
+
+    #[test]
+    fn test_hyperlinks_file_commit_link_format() {
+        let config = integration_test_utils::make_config_from_args(&[
+            "--line-numbers",
+            "--hyperlinks",
+            "--hyperlinks-file-link-format",
+            "{path}:{line}",
+            "--hyperlinks-file-commit-link-format",
+            "https://forge/blob/{commit}/{path}#L{line}",
+            "--file-style",
+            "raw",
+        ]);
+        let commit = "9a5d3ff4b1ed1ab5e4ed28fb2dd8bde1b2bd4e94";
+        let diff = GIT_DIFF_OUTPUT.replace("__path__", "src/removed/file.rs");
+        let url = |line| {
+            format!(
+                "https://forge/blob/{}/src/removed/file.rs#L{}",
+                commit, line
+            )
+        };
+
+        // The files of a commit link to the forge even if they do not exist locally.
+        let output = DeltaTest::with_config(&config)
+            .with_input(&format!("commit {}\n{}", commit, diff))
+            .expect_raw_contains(&format_osc8_hyperlink(&url(""), "src/removed/file.rs"))
+            .expect_raw_contains(&format_osc8_hyperlink(&url("1"), "1"));
+        assert!(!output.raw_output.contains("/fake/delta/cwd"));
+
+        // Without a commit the local file is linked.
+        let local_path = utils::path::fake_delta_cwd_for_tests().join("src/removed/file.rs");
+        DeltaTest::with_config(&config)
+            .with_input(&diff)
+            .expect_raw_contains(&format_osc8_hyperlink(
+                &format!("{}:", local_path.to_string_lossy()),
+                "src/removed/file.rs",
+            ));
+    }
+
+    #[test]
+    fn test_commit_line_links_first_hash() {
+        let config = integration_test_utils::make_config_from_args(&[
+            "--hyperlinks",
+            "--hyperlinks-commit-link-format",
+            "https://forge/commit/{commit}",
+        ]);
+        let link =
+            |commit| format_osc8_hyperlink(&format!("https://forge/commit/{}", commit), commit);
+        assert_eq!(
+            format_commit_line_with_osc8_commit_hyperlink(
+                "commit 9a5d3ff4b1ed1ab5e4ed28fb2dd8bde1b2bd4e94 (tag: deadbeef01)",
+                &config
+            ),
+            format!(
+                "commit {} (tag: deadbeef01)",
+                link("9a5d3ff4b1ed1ab5e4ed28fb2dd8bde1b2bd4e94")
+            )
+        );
+        assert_eq!(
+            format_commit_line_with_osc8_commit_hyperlink(
+                "9a5d3ff Revert \"Bump to c0ffee12\"",
+                &config
+            ),
+            format!("{} Revert \"Bump to c0ffee12\"", link("9a5d3ff"))
+        );
+        assert_eq!(
+            format_commit_line_with_osc8_commit_hyperlink("changeset:   1:eb098a2d89f2", &config),
+            format!("changeset:   1:{}", link("eb098a2d89f2"))
+        );
+        // A word starting with hex digits is not a commit.
+        assert_eq!(
+            format_commit_line_with_osc8_commit_hyperlink("commit deadbeef1xyz 1234567", &config),
+            format!("commit deadbeef1xyz {}", link("1234567"))
+        );
+    }
+
+    #[test]
+    fn test_commit_regex() {
+        let args = [
+            "--hyperlinks",
+            "--hyperlinks-commit-link-format",
+            "https://forge/commit/{commit}",
+            "--hyperlinks-file-commit-link-format",
+            "https://forge/blob/{commit}/{path}",
+            "--file-style",
+            "raw",
+            "--commit-style",
+            "red",
+            "--commit-regex",
+            r"^(?P<commit>[0-9a-f]{7,40}) ",
+        ];
+        let diff = GIT_DIFF_OUTPUT.replace("__path__", "src/file.rs");
+        let config = integration_test_utils::make_config_from_args(&args);
+        let output = DeltaTest::with_config(&config)
+            .with_input(&format!("\x1b[33m9a5d3ff\x1b[m Fix it\n{}", diff))
+            .expect_raw_contains(&format_osc8_hyperlink(
+                "https://forge/blob/9a5d3ff/src/file.rs",
+                "src/file.rs",
+            ));
+        assert!(output.raw_output.contains(&format!(
+            "{} Fix it",
+            format_osc8_hyperlink("https://forge/commit/9a5d3ff", "9a5d3ff")
+        )));
+
+        // Standard commit lines are still recognized.
+        let commit = "9a5d3ff4b1ed1ab5e4ed28fb2dd8bde1b2bd4e94";
+        let input = format!("commit {}\n{}", commit, diff);
+        DeltaTest::with_config(&config)
+            .with_input(&input)
+            .expect_raw_contains(&format_osc8_hyperlink(
+                &format!("https://forge/blob/{}/src/file.rs", commit),
+                "src/file.rs",
+            ));
+
+        // Unless the commit regex is exclusive.
+        let config = integration_test_utils::make_config_from_args(
+            &[&args[..], &["--commit-regex-exclusive"]].concat(),
+        );
+        let output = DeltaTest::with_config(&config).with_input(&input);
+        assert!(!output.raw_output.contains("https://forge/blob/"));
+    }
+
+    #[test]
+    fn test_hyperlinks_file_link_rules() {
+        let config = integration_test_utils::make_config_from_args(&[
+            "--hyperlinks",
+            "--hyperlinks-file-link-format",
+            "fallback://{path}",
+            "--hyperlinks-file-link-rules",
+            "services/web/**=vscode://file/{path}:{line}; \
+             vendored/*/*.rs=https://github.com/upstream/repo/blob/main/{path}#L{line}",
+            "--file-style",
+            "raw",
+        ]);
+        let cwd = utils::path::fake_delta_cwd_for_tests();
+        for (path, url) in [
+            (
+                "services/web/src/app.ts",
+                format!(
+                    "vscode://file/{}:",
+                    cwd.join("services/web/src/app.ts").display()
+                ),
+            ),
+            (
+                "vendored/lib/file.rs",
+                "https://github.com/upstream/repo/blob/main/vendored/lib/file.rs#L".to_string(),
+            ),
+            (
+                "vendored/lib/src/file.rs",
+                format!(
+                    "fallback://{}",
+                    cwd.join("vendored/lib/src/file.rs").display()
+                ),
+            ),
+        ] {
+            DeltaTest::with_config(&config)
+                .with_input(&GIT_DIFF_OUTPUT.replace("__path__", path))
+                .expect_raw_contains(&format_osc8_hyperlink(&url, path));
+        }
+    }
+
+    #[test]
+    fn test_hyperlinks_collapsed_renamed_paths() {
+        let config = integration_test_utils::make_config_from_args(&[
+            "--hyperlinks",
+            "--hyperlinks-file-link-format",
+            "file://{path}",
+            "--collapse-renamed-paths",
+        ]);
+        let cwd = utils::path::fake_delta_cwd_for_tests();
+        let input = "\
+diff --git a/src/utils/io.rs b/src/helpers/io.rs
+similarity index 100%
+rename from src/utils/io.rs
+rename to src/helpers/io.rs
+";
+        DeltaTest::with_config(&config)
+            .with_input(input)
+            .expect_raw_contains(&format_osc8_hyperlink(
+                &format!("file://{}", cwd.join("src/helpers/io.rs").display()),
+                "helpers}/io.rs",
+            ));
+    }
+
+    #[test]
+    fn test_parse_file_link_rules() {
+        let rules = parse_file_link_rules(" a/**=x ;; b/*.rs=y;").unwrap();
+        assert_eq!(rules.len(), 2);
+        assert!(rules[0].glob.is_match("a/b/c"));
+        assert!(rules[1].glob.is_match("b/c.rs"));
+        assert!(!rules[1].glob.is_match("b/c/d.rs"));
+        assert!(!rules[1].glob.is_match("b/c.rsx"));
+        assert_eq!(
+            parse_file_link_rules("a/**=x; b/**").unwrap_err(),
+            "Invalid rule \"b/**\": expected GLOB=FORMAT, e.g. \"src/**=file://{path}\""
+        );
+    }
+
     const GIT_DIFF_OUTPUT: &str = r#"
 diff --git a/__path__ b/__path__
 index 587be6b..975fbec 100644
@@ -440,9 +967,8 @@ __path__:  some matching line
             }
         }
 
-        pub fn expected_hyperlink_path(&self) -> PathBuf {
-            utils::path::fake_delta_cwd_for_tests()
-                .join(self.true_location_of_file_relative_to_repo_root)
+        pub fn expected_hyperlink_path(&self, cwd: &Path) -> PathBuf {
+            cwd.join(self.true_location_of_file_relative_to_repo_root)
         }
     }
 
@@ -455,6 +981,18 @@ __path__:  some matching line
                 .collect::<Vec<&str>>()
                 .as_slice(),
         );
+        // Line numbers only link to files which exist, so the file is created in a directory
+        // standing in for the repository root, from which delta is invoked.
+        let cwd = integration_test_utils::make_temp_dir(
+            &std::thread::current()
+                .name()
+                .unwrap_or("test_paths_and_hyperlinks")
+                .replace("::", "_"),
+        );
+        let file = cwd.join(test_case.true_location_of_file_relative_to_repo_root);
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(&file, "").unwrap();
+        config.cwd_of_delta_process = Some(cwd.clone());
         // The test is simulating delta invoked by git hence these are the same
         config.cwd_relative_to_repo_root = test_case.git_prefix_env_var.map(|s| s.to_string());
         config.cwd_of_user_shell_process = utils::path::cwd_of_user_shell_process(
@@ -488,7 +1026,7 @@ __path__:  some matching line
         };
         let make_expected_hyperlink = |text| {
             format_osc8_hyperlink(
-                &PathBuf::from(test_case.expected_hyperlink_path()).to_string_lossy(),
+                &test_case.expected_hyperlink_path(&cwd).to_string_lossy(),
                 text,
             )
         };
@@ -515,5 +1053,6 @@ __path__:  some matching line
                     ));
             }
         }
+        std::fs::remove_dir_all(cwd).unwrap();
     }
 }
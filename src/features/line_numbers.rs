@@ -1,10 +1,13 @@
+use std::cell::Cell;
 use std::cmp::max;
 
+use itertools::Itertools;
 use lazy_static::lazy_static;
 use regex::Regex;
 
 use crate::config;
 use crate::delta::State;
+use crate::fatal;
 use crate::features::hyperlinks;
 use crate::features::side_by_side::ansifill::{self, ODD_PAD_CHAR};
 use crate::features::side_by_side::{Left, PanelSide, Right};
@@ -141,16 +144,55 @@ pub fn format_and_paint_line_numbers<'a>(
     formatted_numbers
 }
 
+const LINE_NUMBERS_PLACEHOLDERS: &[&str] = &["nm", "np", "rm", "rp", "snm", "snp", "hunk", "file"];
+
 lazy_static! {
-    static ref LINE_NUMBERS_PLACEHOLDER_REGEX: Regex = format::make_placeholder_regex(&["nm", "np"]);
+    static ref LINE_NUMBERS_PLACEHOLDER_REGEX: Regex =
+        format::make_placeholder_regex(LINE_NUMBERS_PLACEHOLDERS);
+    static ref ANY_PLACEHOLDER_REGEX: Regex = format::make_placeholder_regex(&[r"\w*"]);
+}
+
+/// Exit with an error if the line number format string of `option` contains a placeholder which
+/// is not one of the known line number placeholders.
+pub fn check_format_string(format: &str, option: &str) {
+    for captures in ANY_PLACEHOLDER_REGEX.captures_iter(format) {
+        let label = captures.get(1).unwrap().as_str();
+        if !LINE_NUMBERS_PLACEHOLDERS.contains(&label) {
+            fatal(format!(
+                "Invalid placeholder {{{}}} in --{}: expected one of {}.",
+                label,
+                option,
+                LINE_NUMBERS_PLACEHOLDERS
+                    .iter()
+                    .map(|p| format!("{{{}}}", p))
+                    .join(", ")
+            ));
+        }
+    }
 }
 
 #[derive(Default, Debug)]
 pub struct LineNumbersData<'a> {
     pub format_data: MinusPlus<format::FormatStringData<'a>>,
     pub line_number: MinusPlus<usize>,
-    pub hunk_max_line_number_width: usize,
+    // First line numbers of the current hunk, and the line numbers of its first change once it
+    // is reached. Relative line numbers are counted from the latter, or if the hunk has no
+    // change, from the former.
+    pub hunk_start_line_number: MinusPlus<usize>,
+    pub first_change_line_number: Option<MinusPlus<usize>>,
+    // Digit width of the largest line number in the hunk headers of the current file so far. It
+    // is set at each hunk header, before the lines of the hunk are painted, so that the line
+    // number columns do not shift within a hunk and never narrow within a file.
+    pub max_line_number_width: usize,
     pub plus_file: String,
+    // The commit of the current hunk, if it belongs to one.
+    pub commit: Option<String>,
+    // 1-based index of the current file within the diff, and of the current hunk within the file.
+    pub file_index: usize,
+    pub hunk_index: usize,
+    // The line numbers last painted in the left and right fields, used to blank out repeated
+    // values of the {snm} and {snp} placeholders.
+    pub previous_line_numbers: MinusPlus<Cell<(Option<usize>, Option<usize>)>>,
 }
 
 pub type SideBySideLineWidth = MinusPlus<usize>;
@@ -158,38 +200,99 @@ pub type SideBySideLineWidth = MinusPlus<usize>;
 // Although it's probably unusual, a single format string can contain multiple placeholders. E.g.
 // line-numbers-right-format = "{nm} {np}|"
 impl<'a> LineNumbersData<'a> {
+    /// If a side of `min_width` is given it replaces the widths of the placeholders in the format
+    /// string of that side, so that the line number columns are only as wide as the line numbers
+    /// of each file require.
     pub fn from_format_strings(
         format: &'a MinusPlus<String>,
         use_full_width: ansifill::UseFullPanelWidth,
+        min_width: MinusPlus<Option<usize>>,
     ) -> LineNumbersData<'a> {
         let insert_center_space_on_odd_width = use_full_width.pad_width();
+        let with_min_width = |mut format_data: format::FormatStringData<'a>, min_width| {
+            if let Some(min_width) = min_width {
+                for placeholder in format_data.iter_mut().filter(|p| p.placeholder.is_some()) {
+                    placeholder.width = Some(min_width);
+                }
+            }
+            format_data
+        };
         Self {
             format_data: MinusPlus::new(
-                format::parse_line_number_format(
-                    &format[Left],
-                    &*LINE_NUMBERS_PLACEHOLDER_REGEX,
-                    false,
+                with_min_width(
+                    format::parse_line_number_format(
+                        &format[Left],
+                        &LINE_NUMBERS_PLACEHOLDER_REGEX,
+                        false,
+                    ),
+                    min_width[Left],
                 ),
-                format::parse_line_number_format(
-                    &format[Right],
-                    &*LINE_NUMBERS_PLACEHOLDER_REGEX,
-                    insert_center_space_on_odd_width,
+                with_min_width(
+                    format::parse_line_number_format(
+                        &format[Right],
+                        &LINE_NUMBERS_PLACEHOLDER_REGEX,
+                        insert_center_space_on_odd_width,
+                    ),
+                    min_width[Right],
                 ),
             ),
             ..Self::default()
         }
     }
 
+    /// Count a new file, at its diff header.
+    pub fn initialize_file(&mut self) {
+        self.file_index += 1;
+        self.hunk_index = 0;
+        self.max_line_number_width = 0;
+    }
+
     /// Initialize line number data for a hunk.
-    pub fn initialize_hunk(&mut self, line_numbers: &[(usize, usize)], plus_file: String) {
+    pub fn initialize_hunk(
+        &mut self,
+        line_numbers: &[(usize, usize)],
+        plus_file: String,
+        commit: Option<String>,
+    ) {
         // Typically, line_numbers has length 2: an entry for the minus file, and one for the plus
         // file. In the case of merge commits, it may be longer.
         self.line_number =
             MinusPlus::new(line_numbers[0].0, line_numbers[line_numbers.len() - 1].0);
         let hunk_max_line_number = line_numbers.iter().map(|(n, d)| n + d).max().unwrap();
-        self.hunk_max_line_number_width =
-            1 + (hunk_max_line_number as f64).log10().floor() as usize;
+        self.max_line_number_width = max(
+            self.max_line_number_width,
+            format::log10_plus_1(hunk_max_line_number),
+        );
+        self.hunk_start_line_number = self.line_number;
+        self.first_change_line_number = None;
         self.plus_file = plus_file;
+        self.commit = commit;
+        // Input without file headers still counts as one file.
+        self.file_index = max(self.file_index, 1);
+        self.hunk_index += 1;
+    }
+
+    /// Record the line numbers of the first change of the hunk, which follows the next
+    /// `n_context_lines` unchanged lines, unless it has already been reached.
+    pub fn reach_first_change(&mut self, n_context_lines: usize) {
+        if self.first_change_line_number.is_none() {
+            self.first_change_line_number = Some(MinusPlus::new(
+                self.line_number[Minus] + n_context_lines,
+                self.line_number[Plus] + n_context_lines,
+            ));
+        }
+    }
+
+    // The width of the values of a placeholder, to which they are padded unless the format gives
+    // a greater width: the width of the largest line number of the file so far for line numbers,
+    // and the width of the index itself for {file} and {hunk}.
+    fn placeholder_width(&self, placeholder: &Option<Placeholder>) -> usize {
+        match placeholder {
+            Some(Placeholder::FileIndex) => format::log10_plus_1(self.file_index),
+            Some(Placeholder::HunkIndex) => format::log10_plus_1(self.hunk_index),
+            Some(_) => self.max_line_number_width,
+            None => 0,
+        }
     }
 
     pub fn empty_for_sbs(use_full_width: ansifill::UseFullPanelWidth) -> LineNumbersData<'a> {
@@ -212,18 +315,19 @@ impl<'a> LineNumbersData<'a> {
 
     pub fn formatted_width(&self) -> SideBySideLineWidth {
         let format_data_width = |format_data: &format::FormatStringData<'a>| {
-            // Provide each Placeholder with the max_line_number_width to calculate the
+            // Provide each Placeholder with the width of its values to calculate the
             // actual width. Only use prefix and suffix of the last element, otherwise
             // only the prefix (as the suffix also contains the following prefix).
             format_data
                 .last()
                 .map(|last| {
-                    let (prefix_width, suffix_width) = last.width(self.hunk_max_line_number_width);
+                    let (prefix_width, suffix_width) =
+                        last.width(self.placeholder_width(&last.placeholder));
                     format_data
                         .iter()
                         .rev()
                         .skip(1)
-                        .map(|p| p.width(self.hunk_max_line_number_width).0)
+                        .map(|p| p.width(self.placeholder_width(&p.placeholder)).0)
                         .sum::<usize>()
                         + prefix_width
                         + suffix_width
@@ -245,17 +349,27 @@ fn format_and_paint_line_number_field<'a>(
     line_numbers: &MinusPlus<Option<usize>>,
     config: &config::Config,
 ) -> Vec<ansi_term::ANSIGenericString<'a, str>> {
-    let min_field_width = line_numbers_data.hunk_max_line_number_width;
-
     let format_data = &line_numbers_data.format_data[side];
-    let plus_file = &line_numbers_data.plus_file;
+    let plus_file = Some(line_numbers_data.plus_file.as_str());
+    // Minus line numbers link to the (new) file only if requested.
+    let minus_file = plus_file.filter(|_| config.hyperlinks_minus_line_numbers);
+    let commit = line_numbers_data.commit.as_deref();
     let style = &config.line_numbers_style_leftright[side];
 
+    let previous_line_numbers = line_numbers_data.previous_line_numbers[side].get();
+    let current_line_numbers = (line_numbers[Minus], line_numbers[Plus]);
+    line_numbers_data.previous_line_numbers[side].set(current_line_numbers);
+    // Repeated line numbers are blanked out by the suppressed placeholders.
+    let unless_repeated = |line_number: Option<usize>, previous: Option<usize>| {
+        line_number.filter(|n| Some(*n) != previous)
+    };
+
     let mut ansi_strings = Vec::new();
     let mut suffix = "";
     for placeholder in format_data {
         ansi_strings.push(style.paint(placeholder.prefix.as_str()));
 
+        let min_field_width = line_numbers_data.placeholder_width(&placeholder.placeholder);
         let width = if let Some(placeholder_width) = placeholder.width {
             max(placeholder_width, min_field_width)
         } else {
@@ -263,27 +377,95 @@ fn format_and_paint_line_number_field<'a>(
         };
 
         let alignment_spec = placeholder.alignment_spec.unwrap_or(Align::Center);
+        let anchor = line_numbers_data
+            .first_change_line_number
+            .as_ref()
+            .unwrap_or(&line_numbers_data.hunk_start_line_number);
         match placeholder.placeholder {
             Some(Placeholder::NumberMinus) => {
                 ansi_strings.push(styles[Minus].paint(format_line_number(
                     line_numbers[Minus],
+                    None,
                     alignment_spec,
                     width,
                     placeholder.precision,
-                    None,
+                    minus_file,
+                    commit,
                     config,
                 )))
             }
             Some(Placeholder::NumberPlus) => {
                 ansi_strings.push(styles[Plus].paint(format_line_number(
                     line_numbers[Plus],
+                    None,
+                    alignment_spec,
+                    width,
+                    placeholder.precision,
+                    plus_file,
+                    commit,
+                    config,
+                )))
+            }
+            Some(Placeholder::RelativeNumberMinus) => {
+                ansi_strings.push(styles[Minus].paint(format_line_number(
+                    line_numbers[Minus],
+                    Some(anchor[Minus]),
+                    alignment_spec,
+                    width,
+                    placeholder.precision,
+                    minus_file,
+                    commit,
+                    config,
+                )))
+            }
+            Some(Placeholder::RelativeNumberPlus) => {
+                ansi_strings.push(styles[Plus].paint(format_line_number(
+                    line_numbers[Plus],
+                    Some(anchor[Plus]),
                     alignment_spec,
                     width,
                     placeholder.precision,
-                    Some(plus_file),
+                    plus_file,
+                    commit,
                     config,
                 )))
             }
+            Some(Placeholder::SuppressedNumberMinus) => {
+                ansi_strings.push(styles[Minus].paint(format_line_number(
+                    unless_repeated(line_numbers[Minus], previous_line_numbers.0),
+                    None,
+                    alignment_spec,
+                    width,
+                    placeholder.precision,
+                    minus_file,
+                    commit,
+                    config,
+                )))
+            }
+            Some(Placeholder::SuppressedNumberPlus) => {
+                ansi_strings.push(styles[Plus].paint(format_line_number(
+                    unless_repeated(line_numbers[Plus], previous_line_numbers.1),
+                    None,
+                    alignment_spec,
+                    width,
+                    placeholder.precision,
+                    plus_file,
+                    commit,
+                    config,
+                )))
+            }
+            Some(Placeholder::HunkIndex) => ansi_strings.push(style.paint(format::pad(
+                line_numbers_data.hunk_index,
+                width,
+                alignment_spec,
+                placeholder.precision,
+            ))),
+            Some(Placeholder::FileIndex) => ansi_strings.push(style.paint(format::pad(
+                line_numbers_data.file_index,
+                width,
+                alignment_spec,
+                placeholder.precision,
+            ))),
             None => {}
             _ => unreachable!("Invalid placeholder"),
         }
@@ -293,26 +475,55 @@ fn format_and_paint_line_number_field<'a>(
     ansi_strings
 }
 
-/// Return line number formatted according to `alignment` and `width`.
+/// Return line number formatted according to `alignment` and `width`. If `relative_to` is given
+/// then the distance to this line number, before or after it, is displayed instead, except for
+/// that line itself. If
+/// hyperlinks are enabled and `link_file` exists locally, the digits link to that file at the line.
+/// If `commit` is given, see --hyperlinks-file-commit-link-format, the file need not exist locally.
+#[allow(clippy::too_many_arguments)]
 fn format_line_number(
     line_number: Option<usize>,
+    relative_to: Option<usize>,
     alignment: Align,
     width: usize,
     precision: Option<usize>,
-    plus_file: Option<&str>,
+    link_file: Option<&str>,
+    commit: Option<&str>,
     config: &config::Config,
 ) -> String {
-    let pad = |n| format::pad(n, width, alignment, precision);
-    match (line_number, config.hyperlinks, plus_file) {
-        (None, _, _) => " ".repeat(width),
-        (Some(n), true, Some(file)) => match utils::path::absolute_path(file, config) {
-            Some(absolute_path) => {
-                hyperlinks::format_osc8_file_hyperlink(absolute_path, line_number, &pad(n), config)
-                    .to_string()
-            }
-            None => file.to_owned(),
-        },
-        (Some(n), _, _) => pad(n),
+    let pad = |n: usize| {
+        let displayed = match relative_to {
+            Some(anchor) if n != anchor => n.abs_diff(anchor),
+            _ => n,
+        };
+        format::pad(displayed, width, alignment, precision)
+    };
+    let absolute_path = link_file
+        .filter(|_| config.hyperlinks)
+        .and_then(|file| utils::path::absolute_path(file, config))
+        .filter(|path| hyperlinks::links_to_file_at_commit(commit, config) || path.is_file());
+    match (line_number, absolute_path) {
+        (None, _) => " ".repeat(width),
+        (Some(n), Some(absolute_path)) => {
+            // Only the digits are linked, so that the padding does not become clickable.
+            let padded = pad(n);
+            let start = padded.len() - padded.trim_start().len();
+            let end = padded.trim_end().len().max(start);
+            format!(
+                "{}{}{}",
+                &padded[..start],
+                hyperlinks::format_osc8_file_hyperlink(
+                    absolute_path,
+                    line_number,
+                    None,
+                    commit,
+                    &padded[start..end],
+                    config
+                ),
+                &padded[end..]
+            )
+        }
+        (Some(n), None) => pad(n),
     }
 }
 
@@ -572,6 +783,15 @@ pub mod tests {
         assert_eq!(data[0].width(4), (7, 1));
     }
 
+    #[test]
+    fn test_line_number_placeholder_width_of_decoration() {
+        let data = parse_line_number_format_with_default_regex("行{np:>3}│");
+        assert_eq!(data[0].width(1), (5, 1));
+
+        let data = parse_line_number_format_with_default_regex("\x1b[31m▶\x1b[0m {nm:<2}\x1b[2m┃");
+        assert_eq!(data[0].width(1), (4, 1));
+    }
+
     #[test]
     fn test_line_number_placeholder_width_two() {
         let data = parse_line_number_format_with_default_regex("│{nm}│{np}│");
@@ -592,32 +812,37 @@ pub mod tests {
         use crate::features::side_by_side::ansifill;
         let w = ansifill::UseFullPanelWidth(false);
         let format = MinusPlus::new("".into(), "".into());
-        let mut data = LineNumbersData::from_format_strings(&format, w.clone());
-        data.initialize_hunk(&[(10, 11), (10000, 100001)], "a".into());
+        let mut data =
+            LineNumbersData::from_format_strings(&format, w.clone(), MinusPlus::default());
+        data.initialize_hunk(&[(10, 11), (10000, 100001)], "a".into(), None);
         assert_eq!(data.formatted_width(), MinusPlus::new(0, 0));
 
         let format = MinusPlus::new("│".into(), "│+│".into());
-        let mut data = LineNumbersData::from_format_strings(&format, w.clone());
+        let mut data =
+            LineNumbersData::from_format_strings(&format, w.clone(), MinusPlus::default());
 
-        data.initialize_hunk(&[(10, 11), (10000, 100001)], "a".into());
+        data.initialize_hunk(&[(10, 11), (10000, 100001)], "a".into(), None);
         assert_eq!(data.formatted_width(), MinusPlus::new(1, 3));
 
         let format = MinusPlus::new("│{nm:^3}│".into(), "│{np:^3}│".into());
-        let mut data = LineNumbersData::from_format_strings(&format, w.clone());
+        let mut data =
+            LineNumbersData::from_format_strings(&format, w.clone(), MinusPlus::default());
 
-        data.initialize_hunk(&[(10, 11), (10000, 100001)], "a".into());
+        data.initialize_hunk(&[(10, 11), (10000, 100001)], "a".into(), None);
         assert_eq!(data.formatted_width(), MinusPlus::new(8, 8));
 
         let format = MinusPlus::new("│{nm:^3}│ │{np:<12}│ │{nm}│".into(), "".into());
-        let mut data = LineNumbersData::from_format_strings(&format, w.clone());
+        let mut data =
+            LineNumbersData::from_format_strings(&format, w.clone(), MinusPlus::default());
 
-        data.initialize_hunk(&[(10, 11), (10000, 100001)], "a".into());
+        data.initialize_hunk(&[(10, 11), (10000, 100001)], "a".into(), None);
         assert_eq!(data.formatted_width(), MinusPlus::new(32, 0));
 
         let format = MinusPlus::new("│{np:^3}│ │{nm:<12}│ │{np}│".into(), "".into());
-        let mut data = LineNumbersData::from_format_strings(&format, w.clone());
+        let mut data =
+            LineNumbersData::from_format_strings(&format, w.clone(), MinusPlus::default());
 
-        data.initialize_hunk(&[(10, 11), (10000, 100001)], "a".into());
+        data.initialize_hunk(&[(10, 11), (10000, 100001)], "a".into(), None);
         assert_eq!(data.formatted_width(), MinusPlus::new(32, 0));
     }
 
@@ -755,8 +980,8 @@ pub mod tests {
         let output = run_delta(TWO_MINUS_LINES_DIFF, &config);
         let mut lines = output.lines().skip(5);
         let (line_1, line_2) = (lines.next().unwrap(), lines.next().unwrap());
-        assert_eq!(strip_ansi_codes(line_1), "  1 ⋮    │-a = 1");
-        assert_eq!(strip_ansi_codes(line_2), "  2 ⋮    │-b = 23456");
+        assert_eq!(strip_ansi_codes(line_1), "1⋮ │-a = 1");
+        assert_eq!(strip_ansi_codes(line_2), "2⋮ │-b = 23456");
     }
 
     #[test]
@@ -765,13 +990,13 @@ pub mod tests {
         let output = run_delta(TWO_LINE_DIFFS, &config);
         let output = strip_ansi_codes(&output);
         let mut lines = output.lines().skip(4);
-        assert_eq!(lines.next().unwrap(), "  1 ⋮  1 │a = 1");
-        assert_eq!(lines.next().unwrap(), "  2 ⋮    │b = 2");
-        assert_eq!(lines.next().unwrap(), "    ⋮  2 │bb = 2");
+        assert_eq!(lines.next().unwrap(), "1⋮1│a = 1");
+        assert_eq!(lines.next().unwrap(), "2⋮ │b = 2");
+        assert_eq!(lines.next().unwrap(), " ⋮2│bb = 2");
         assert_eq!(lines.next().unwrap(), "");
-        assert_eq!(lines.next().unwrap(), " 499⋮ 499│a = 3");
-        assert_eq!(lines.next().unwrap(), " 500⋮    │b = 4");
-        assert_eq!(lines.next().unwrap(), "    ⋮ 500│bb = 4");
+        assert_eq!(lines.next().unwrap(), "499⋮499│a = 3");
+        assert_eq!(lines.next().unwrap(), "500⋮   │b = 4");
+        assert_eq!(lines.next().unwrap(), "   ⋮500│bb = 4");
     }
 
     #[test]
@@ -780,18 +1005,245 @@ pub mod tests {
             .with_input(DIFF_PLUS_MINUS_WITH_1_CONTEXT_DIFF)
             .expect_after_header(
                 r#"
-                │  1 │abc             │  1 │abc
-                │  2 │a = left side   │  2 │a = right side
-                │  3 │xyz             │  3 │xyz"#,
+                │1│abc                │1│abc
+                │2│a = left side      │2│a = right side
+                │3│xyz                │3│xyz"#,
             );
     }
 
+    #[test]
+    fn test_line_numbers_relative() {
+        DeltaTest::with_args(&["--line-numbers", "--line-numbers-relative"])
+            .with_input(DIFF_WITH_HUNK_NOT_AT_START)
+            .expect_after_header(
+                r#"
+                #indent_mark
+                 1⋮ 1│abc
+                11⋮  │a = left side
+                  ⋮21│a = right side
+                  ⋮ 1│b = new
+                 1⋮ 2│xyz"#,
+            );
+
+        // Custom formats can mix absolute and relative line numbers
+        DeltaTest::with_args(&[
+            "--line-numbers",
+            "--line-numbers-left-format",
+            "{nm:>3}:{rm:<2}|",
+            "--line-numbers-right-format",
+            "{rp:>2}|",
+        ])
+        .with_input(DIFF_WITH_HUNK_NOT_AT_START)
+        .expect_after_header(
+            r#"
+            #indent_mark
+             10:1 | 1|abc
+             11:11|  |a = left side
+               :  |21|a = right side
+               :  | 1|b = new
+             12:1 | 2|xyz"#,
+        );
+    }
+
+    #[test]
+    fn test_line_numbers_relative_side_by_side() {
+        DeltaTest::with_args(&[
+            "--side-by-side",
+            "--line-numbers-relative",
+            "--width",
+            "44",
+            "--line-fill-method=ansi",
+        ])
+        .with_input(DIFF_WITH_HUNK_NOT_AT_START)
+        .expect_after_header(
+            r#"
+            │ 1│abc               │ 1│abc
+            │11│a = left side     │21│a = right side
+            │  │                  │ 1│b = new
+            │ 1│xyz               │ 2│xyz"#,
+        );
+    }
+
+    #[test]
+    fn test_line_numbers_hunk_and_file_placeholders() {
+        let config = make_config_from_args(&[
+            "--line-numbers",
+            "--hunk-header-style",
+            "omit",
+            "--line-numbers-left-format",
+            "{file:>}.{hunk:<2}{nm:>3}⋮",
+            "--line-numbers-right-format",
+            "{np:>3}│",
+        ]);
+        let input = format!(
+            "{}{}",
+            TWO_LINE_DIFFS,
+            TWO_LINE_DIFFS.replace("a.py", "b.py")
+        );
+        let output = run_delta(&input, &config);
+        let output = strip_ansi_codes(&output);
+        // The indices take their own width, not that of the line numbers, so that the columns
+        // do not shift between the hunks of a file.
+        let lines = output
+            .lines()
+            .filter(|line| line.contains('│'))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            [
+                "1.1   1⋮  1│a = 1",
+                "1.1   2⋮   │b = 2",
+                "1.1    ⋮  2│bb = 2",
+                "1.2 499⋮499│a = 3",
+                "1.2 500⋮   │b = 4",
+                "1.2    ⋮500│bb = 4",
+                "2.1   1⋮  1│a = 1",
+                "2.1   2⋮   │b = 2",
+                "2.1    ⋮  2│bb = 2",
+                "2.2 499⋮499│a = 3",
+                "2.2 500⋮   │b = 4",
+                "2.2    ⋮500│bb = 4",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_line_numbers_suppressed_placeholders() {
+        let config = make_config_from_args(&[
+            "--line-numbers",
+            "--line-numbers-left-format",
+            "{snm:>2}⋮",
+            "--line-numbers-right-format",
+            "{snp:>2}│",
+        ]);
+        let format = MinusPlus::new(
+            config.line_numbers_format[Left].clone(),
+            config.line_numbers_format[Right].clone(),
+        );
+        let mut data = LineNumbersData::from_format_strings(
+            &format,
+            ansifill::UseFullPanelWidth(false),
+            MinusPlus::default(),
+        );
+        data.max_line_number_width = 2;
+        let paint = |minus, plus| {
+            let styles = MinusPlus::new(Style::new(), Style::new());
+            let line_numbers = MinusPlus::new(minus, plus);
+            let painted = format_and_paint_line_numbers(&data, None, styles, line_numbers, &config);
+            strip_ansi_codes(&painted.iter().map(|s| s.to_string()).collect::<String>())
+        };
+        assert_eq!(paint(Some(3), Some(3)), " 3⋮ 3│");
+        // The values equal to those of the previous line are left blank.
+        assert_eq!(paint(Some(3), Some(3)), "  ⋮  │");
+        assert_eq!(paint(Some(4), Some(3)), " 4⋮  │");
+        assert_eq!(paint(None, Some(3)), "  ⋮  │");
+        assert_eq!(paint(None, Some(4)), "  ⋮ 4│");
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid placeholder {nx} in --line-numbers-right-format")]
+    fn test_line_numbers_unknown_placeholder() {
+        make_config_from_args(&["--line-numbers", "--line-numbers-right-format", "{nx:^4}│"]);
+    }
+
+    #[test]
+    fn test_line_numbers_min_width() {
+        DeltaTest::with_args(&["--line-numbers", "--line-numbers-min-width", "1"])
+            .with_input(DIFF_WITH_HUNK_NOT_AT_START)
+            .expect_after_header(
+                r#"
+                #indent_mark
+                10⋮20│abc
+                11⋮  │a = left side
+                  ⋮21│a = right side
+                  ⋮22│b = new
+                12⋮23│xyz"#,
+            );
+
+        DeltaTest::with_args(&[
+            "--side-by-side",
+            "--line-numbers-min-width",
+            "1",
+            "--width",
+            "40",
+            "--line-fill-method=ansi",
+        ])
+        .with_input(DIFF_WITH_HUNK_NOT_AT_START)
+        .expect_after_header(
+            r#"
+            │10│abc             │20│abc
+            │11│a = left side   │21│a = right side
+            │  │                │22│b = new
+            │12│xyz             │23│xyz"#,
+        );
+    }
+
+    #[test]
+    fn test_line_numbers_width_of_file() {
+        let format = MinusPlus::new("{nm}".into(), "{np}".into());
+        let w = ansifill::UseFullPanelWidth(false);
+        let mut data =
+            LineNumbersData::from_format_strings(&format, w, MinusPlus::new(Some(1), Some(1)));
+        data.initialize_file();
+        data.initialize_hunk(&[(1, 5), (1, 5)], "a".into(), None);
+        assert_eq!(data.formatted_width(), MinusPlus::new(1, 1));
+        data.initialize_hunk(&[(990, 20), (990, 20)], "a".into(), None);
+        assert_eq!(data.formatted_width(), MinusPlus::new(4, 4));
+        // The columns do not narrow within a file.
+        data.initialize_hunk(&[(2000, 5), (2000, 5)], "a".into(), None);
+        data.initialize_hunk(&[(3000, 5), (3000, 5)], "a".into(), None);
+        assert_eq!(data.formatted_width(), MinusPlus::new(4, 4));
+        data.initialize_file();
+        data.initialize_hunk(&[(1, 5), (1, 5)], "b".into(), None);
+        assert_eq!(data.formatted_width(), MinusPlus::new(1, 1));
+
+        // Crossing from 999 to 1000 within a hunk does not shift the columns.
+        let config = make_config_from_args(&["--line-numbers", "--line-numbers-min-width", "1"]);
+        let output = run_delta(DIFF_CROSSING_1000, &config);
+        let output = strip_ansi_codes(&output);
+        assert!(output.contains("\n5⋮5│a\n"));
+        assert!(output.contains("\n 999⋮ 999│b\n"));
+        assert!(output.contains("\n1000⋮    │c\n"));
+    }
+
+    #[test]
+    fn test_line_numbers_width_by_default() {
+        // The default formats take the width of the file.
+        let config = make_config_from_args(&["--line-numbers"]);
+        let output = strip_ansi_codes(&run_delta(DIFF_CROSSING_1000, &config));
+        assert!(output.contains("\n5⋮5│a\n"));
+        assert!(output.contains("\n1000⋮    │c\n"));
+
+        let config = make_config_from_args(&["--side-by-side", "--width", "40"]);
+        let output = strip_ansi_codes(&run_delta(DIFF_CROSSING_1000, &config));
+        assert!(output.contains("\n│5│a "));
+
+        // The widths of custom formats are kept.
+        let config =
+            make_config_from_args(&["--line-numbers", "--line-numbers-left-format", "{nm:>4}⋮"]);
+        let output = strip_ansi_codes(&run_delta(DIFF_CROSSING_1000, &config));
+        assert!(output.contains("\n   5⋮5│a\n"));
+    }
+
+    #[test]
+    fn test_line_numbers_width_of_each_file() {
+        // The lines of a file are painted with its own width, even when those of the next file
+        // are wider.
+        let config = make_config_from_args(&["--line-numbers", "--line-numbers-min-width", "1"]);
+        let output = run_delta(DIFF_TWO_FILES_OF_DIFFERENT_WIDTHS, &config);
+        let output = strip_ansi_codes(&output);
+        assert!(output.contains("\n1⋮ │a\n ⋮1│b\n ⋮2│c\n"));
+        assert!(output.contains("\n123456⋮      │d\n      ⋮123456│e\n"));
+    }
+
     #[test]
     fn test_line_numbers_continue_correctly_after_wrapping() {
         DeltaTest::with_args(&[
             "--side-by-side",
             "--width",
             "32",
+            "--line-numbers-min-width",
+            "4",
             "--line-fill-method=ansi",
             "--wrap-left-symbol",
             "@",
@@ -811,6 +1263,8 @@ pub mod tests {
             "--side-by-side",
             "--width",
             "42",
+            "--line-numbers-min-width",
+            "4",
             "--line-fill-method=ansi",
             "--wrap-left-symbol",
             "@",
@@ -938,6 +1392,39 @@ index 223ca50..367a6f6 100644
  abc
 -a = left side
 +a = right side
+ xyz";
+
+    const DIFF_CROSSING_1000: &str = "\
+--- a/a.py
++++ b/a.py
+@@ -5 +5 @@
+ a
+@@ -999,2 +999 @@
+ b
+-c";
+
+    const DIFF_TWO_FILES_OF_DIFFERENT_WIDTHS: &str = "\
+--- a/a.txt
++++ b/a.txt
+@@ -1,1 +1,2 @@
+-a
++b
++c
+--- a/b.txt
++++ b/b.txt
+@@ -123456 +123456 @@
+-d
++e
+";
+
+    const DIFF_WITH_HUNK_NOT_AT_START: &str = "\
+--- a/a.py
++++ b/b.py
+@@ -10,3 +20,4 @@
+ abc
+-a = left side
++a = right side
++b = new
  xyz";
 
     const DIFF_WITH_LONGER_MINUS_1_CONTEXT: &str = "\
@@ -55,6 +55,14 @@ pub fn make_builtin_features() -> HashMap<String, BuiltinFeature> {
             "side-by-side".to_string(),
             side_by_side::make_feature().into_iter().collect(),
         ),
+        (
+            "monokai-delta".to_string(),
+            themes::make_monokai_delta_feature().into_iter().collect(),
+        ),
+        (
+            "solarized".to_string(),
+            themes::make_solarized_feature().into_iter().collect(),
+        ),
     ]
     .into_iter()
     .collect()
@@ -87,6 +95,7 @@ pub mod line_numbers;
 pub mod navigate;
 pub mod raw;
 pub mod side_by_side;
+pub mod themes;
 
 #[cfg(test)]
 pub mod tests {
@@ -96,13 +105,20 @@ pub mod tests {
     use crate::cli;
     use crate::env::DeltaEnv;
     use crate::features::make_builtin_features;
+    use crate::features::themes;
+    use crate::options::option_value::OptionProvenance;
     use crate::tests::integration_test_utils::make_options_from_args_and_git_config;
 
     #[test]
     fn test_builtin_features_have_flags_and_these_set_features() {
         let builtin_features = make_builtin_features();
+        // Themes are selected with --theme rather than a flag of their own.
+        let flag_features: Vec<&String> = builtin_features
+            .keys()
+            .filter(|feature| !themes::THEMES.contains(&feature.as_str()))
+            .collect();
         let mut args = vec!["delta".to_string()];
-        args.extend(builtin_features.keys().map(|s| format!("--{}", s)));
+        args.extend(flag_features.iter().map(|s| format!("--{}", s)));
         let opt = cli::Opt::from_iter_and_git_config(DeltaEnv::default(), args, None);
         let features: HashSet<&str> = opt
             .features
@@ -110,7 +126,7 @@ pub mod tests {
             .unwrap_or("")
             .split_whitespace()
             .collect();
-        for feature in builtin_features.keys() {
+        for feature in flag_features {
             assert!(features.contains(feature.as_str()))
         }
     }
@@ -393,6 +409,65 @@ pub mod tests {
         remove_file(git_config_path).unwrap();
     }
 
+    #[test]
+    fn test_features_order() {
+        let git_config_contents = b"
+[delta]
+    features = my-feature-1
+
+[delta \"my-feature-1\"]
+    features = my-feature-2
+    minus-style = green
+
+[delta \"my-feature-2\"]
+    minus-style = yellow
+    plus-style = blue
+
+[delta \"my-feature-3\"]
+    line-numbers-left-style = magenta
+";
+        let git_config_path = "delta__test_features_order.gitconfig";
+
+        let opt = make_options_from_args_and_git_config(
+            &["--features-order", "my-feature-1 my-feature-x my-feature-2"],
+            Some(git_config_contents),
+            Some(git_config_path),
+        );
+        assert_eq!(opt.features.unwrap(), "my-feature-1 my-feature-2");
+        assert_eq!(opt.minus_style, "yellow");
+        assert_eq!(
+            opt.computed.option_provenances["minus-style"],
+            OptionProvenance::CustomFeature("my-feature-2".to_string())
+        );
+
+        // Builtin features enabled by flags rank below --features by default.
+        let opt = make_options_from_args_and_git_config(
+            &["--features", "my-feature-3", "--line-numbers"],
+            Some(git_config_contents),
+            Some(git_config_path),
+        );
+        assert_eq!(opt.line_numbers_left_style, "magenta");
+        let opt = make_options_from_args_and_git_config(
+            &[
+                "--features",
+                "my-feature-3",
+                "--line-numbers",
+                "--features-order",
+                "my-feature-3 line-numbers",
+            ],
+            Some(git_config_contents),
+            Some(git_config_path),
+        );
+        assert_eq!(opt.features.unwrap(), "my-feature-3 line-numbers");
+        assert_eq!(opt.line_numbers_left_style, "blue");
+        assert_eq!(
+            opt.computed.option_provenances["line-numbers-left-style"],
+            OptionProvenance::BuiltinFeature("line-numbers".to_string())
+        );
+
+        remove_file(git_config_path).unwrap();
+    }
+
     #[test]
     fn test_invalid_features() {
         let git_config_contents = b"
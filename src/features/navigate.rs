@@ -3,8 +3,13 @@ use std::io::Write;
 use std::io::{Error, ErrorKind};
 use std::path::PathBuf;
 
+use itertools::Itertools;
+use lazy_static::lazy_static;
+use regex::Regex;
+
 use crate::config::Config;
 use crate::features::OptionValueFunction;
+use crate::handlers::collapsed_file::COLLAPSED_FILE_MARKER;
 
 pub fn make_feature() -> Vec<(String, OptionValueFunction)> {
     builtin_feature!([
@@ -24,41 +29,89 @@ pub fn make_feature() -> Vec<(String, OptionValueFunction)> {
             "hunk-label",
             String,
             None,
-            _opt => "•"
+            opt => if opt.navigate_granularity == "hunk" { "•" } else { "" }
         )
     ])
 }
 
+/// The sections between which n and N jump in navigate mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NavigateGranularity {
+    Commit,
+    File,
+    Hunk,
+}
+
 // Construct the regexp used by less for paging, if --show-themes or --navigate is enabled.
+#[allow(clippy::too_many_arguments)]
 pub fn make_navigate_regex(
     show_themes: bool,
+    granularity: NavigateGranularity,
+    commit_regex: &str,
     file_modified_label: &str,
     file_added_label: &str,
     file_removed_label: &str,
     file_renamed_label: &str,
     hunk_label: &str,
+    collapse_files: bool,
+    mercurial: bool,
 ) -> String {
     if show_themes {
         "^Theme:".to_string()
     } else {
-        let optional_regexp = |find: &str| {
-            if !find.is_empty() {
-                format!("|{}", regex::escape(find))
-            } else {
-                "".to_string()
-            }
-        };
-        format!(
-            "^(commit{}{}{}{}{})",
-            optional_regexp(file_added_label),
-            optional_regexp(file_removed_label),
-            optional_regexp(file_renamed_label),
-            optional_regexp(file_modified_label),
-            optional_regexp(hunk_label),
-        )
+        let mut labels = vec!["commit"];
+        // "changeset:" starts the commits of `hg log`.
+        if mercurial {
+            labels.push("changeset:");
+        }
+        if granularity != NavigateGranularity::Commit {
+            labels.extend([
+                file_added_label,
+                file_removed_label,
+                file_renamed_label,
+                file_modified_label,
+            ]);
+        }
+        if granularity == NavigateGranularity::Hunk {
+            labels.push(hunk_label);
+        }
+        // The line replacing the hunks of a collapsed file.
+        if collapse_files && granularity != NavigateGranularity::Commit {
+            labels.push(COLLAPSED_FILE_MARKER);
+        }
+        // Delta always writes a space after a label, matching it as well avoids stopping at
+        // lines which merely start with the same text. Only the text preceding the placeholders of
+        // a label is matched. Empty labels cannot be matched.
+        let navigate_regex = format!(
+            "^({})",
+            labels
+                .into_iter()
+                .map(|label| match label.find('{') {
+                    Some(i) => regex::escape(&label[..i]),
+                    None if label.is_empty() => "".to_string(),
+                    None => regex::escape(&format!("{} ", label)),
+                })
+                .filter(|label| !label.is_empty())
+                .join("|")
+        );
+        // Commit lines of custom formats do not start with a label, so less searches for the
+        // commit regex itself, which may not use named groups.
+        if commit_regex == "^commit " {
+            navigate_regex
+        } else {
+            format!(
+                "{}|{}",
+                navigate_regex,
+                NAMED_GROUP_REGEX.replace_all(commit_regex, "(")
+            )
+        }
     }
 }
 
+lazy_static! {
+    static ref NAMED_GROUP_REGEX: Regex = Regex::new(r"\(\?P?<[A-Za-z_][A-Za-z0-9_]*>").unwrap();
+}
+
 // Create a less history file to be used by delta's child less process. This file is initialized
 // with the contents of user's real less hist file, to which the navigate regex is appended. This
 // has the effect that 'n' or 'N' in delta's less process will search for the navigate regex,
@@ -229,6 +282,109 @@ mod tests {
         remove_file(git_config_path).unwrap();
     }
 
+    #[test]
+    fn test_navigate_granularity() {
+        let config = integration_test_utils::make_config_from_args(&["--navigate"]);
+        assert_eq!(config.hunk_label, "•");
+        assert_eq!(
+            config.navigate_regex.unwrap(),
+            "^(commit |added: |removed: |renamed: |Δ |• )"
+        );
+
+        let config = integration_test_utils::make_config_from_args(&[
+            "--navigate",
+            "--navigate-granularity",
+            "file",
+        ]);
+        assert_eq!(config.hunk_label, "");
+        assert_eq!(
+            config.navigate_regex.unwrap(),
+            "^(commit |added: |removed: |renamed: |Δ )"
+        );
+
+        // An explicit hunk label is still displayed, but not navigated to.
+        let config = integration_test_utils::make_config_from_args(&[
+            "--navigate",
+            "--navigate-granularity",
+            "file",
+            "--hunk-label",
+            "#",
+        ]);
+        assert_eq!(config.hunk_label, "#");
+        assert_eq!(
+            config.navigate_regex.unwrap(),
+            "^(commit |added: |removed: |renamed: |Δ )"
+        );
+
+        let config = integration_test_utils::make_config_from_args(&[
+            "--navigate",
+            "--navigate-granularity",
+            "commit",
+        ]);
+        assert_eq!(config.hunk_label, "");
+        assert_eq!(config.navigate_regex.unwrap(), "^(commit )");
+
+        let config = integration_test_utils::make_config_from_args(&[
+            "--navigate",
+            "--navigate-granularity",
+            "commit",
+            "--commit-regex",
+            "^(?P<commit>[0-9a-f]{7,}) ",
+        ]);
+        assert_eq!(
+            config.navigate_regex.unwrap(),
+            "^(commit )|^([0-9a-f]{7,}) "
+        );
+
+        // Empty labels are left out.
+        let config = integration_test_utils::make_config_from_args(&[
+            "--navigate",
+            "--file-added-label",
+            "",
+            "--file-modified-label",
+            "mod.",
+        ]);
+        assert_eq!(
+            config.navigate_regex.unwrap(),
+            r"^(commit |removed: |renamed: |mod\. |• )"
+        );
+
+        // Only the text preceding the placeholders of a label is matched.
+        let config = integration_test_utils::make_config_from_args(&[
+            "--navigate",
+            "--navigate-granularity",
+            "file",
+            "--file-modified-label",
+            "M ({insertions}+)",
+            "--file-added-label",
+            "{path} (new)",
+        ]);
+        assert_eq!(
+            config.navigate_regex.unwrap(),
+            r"^(commit |removed: |renamed: |M \()"
+        );
+
+        // The changesets of `hg log` are navigated to with --vcs hg.
+        let config = integration_test_utils::make_config_from_args(&[
+            "--navigate",
+            "--navigate-granularity",
+            "commit",
+            "--vcs",
+            "hg",
+        ]);
+        assert_eq!(config.navigate_regex.unwrap(), "^(commit |changeset: )");
+
+        // An explicit navigate regex takes precedence.
+        let config = integration_test_utils::make_config_from_args(&[
+            "--navigate",
+            "--navigate-granularity",
+            "commit",
+            "--navigate-regex",
+            "^@@",
+        ]);
+        assert_eq!(config.navigate_regex.unwrap(), "^@@");
+    }
+
     #[test]
     fn test_navigate_activated_by_custom_feature() {
         let git_config_contents = b"
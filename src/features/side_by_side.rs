@@ -1,3 +1,5 @@
+use std::cmp::max;
+
 use itertools::Itertools;
 use syntect::highlighting::Style as SyntectStyle;
 use unicode_segmentation::UnicodeSegmentation;
@@ -36,7 +38,16 @@ pub use MinusPlusIndex::Plus as Right;
 
 use super::line_numbers::LineNumbersData;
 
-#[derive(Debug, Clone)]
+/// How removed and added lines are arranged in rows, see --side-by-side-align.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SideBySideAlign {
+    // Lines are paired by position, regardless of the homologs found by the within-line diff.
+    None,
+    // Only homologous lines share a row, the default.
+    Content,
+}
+
+#[derive(Debug, Clone, Copy)]
 pub struct Panel {
     pub width: usize,
 }
@@ -46,13 +57,21 @@ pub type LeftRight<T> = MinusPlus<T>;
 pub type SideBySideData = LeftRight<Panel>;
 
 impl SideBySideData {
-    /// Create a [`LeftRight<Panel>`](LeftRight<Panel>) named [`SideBySideData`].
-    pub fn new_sbs(decorations_width: &cli::Width, available_terminal_width: &usize) -> Self {
-        let panel_width = match decorations_width {
-            cli::Width::Fixed(w) => w / 2,
-            _ => available_terminal_width / 2,
+    /// Create a [`LeftRight<Panel>`](LeftRight<Panel>) named [`SideBySideData`]. The left panel
+    /// takes `left_permille` thousandths of the width, see --side-by-side-left-percent.
+    pub fn new_sbs(
+        decorations_width: &cli::Width,
+        available_terminal_width: &usize,
+        left_permille: usize,
+    ) -> Self {
+        let width = match decorations_width {
+            cli::Width::Fixed(w) => *w,
+            _ => *available_terminal_width,
         };
-        SideBySideData::new(Panel { width: panel_width }, Panel { width: panel_width })
+        // As with equal panels, a column is left over only if the width is odd, see `ansifill`.
+        let left_width = width * left_permille / 1000;
+        let right_width = (width - width % 2).saturating_sub(left_width);
+        SideBySideData::new(Panel { width: left_width }, Panel { width: right_width })
     }
 }
 
@@ -65,10 +84,10 @@ pub fn available_line_width(
     // The width can be reduced by the line numbers and/or
     // a possibly added/restored 1-wide "+/-/ " prefix.
     let line_width = |side: PanelSide| {
-        config.side_by_side_data[side]
+        config.side_by_side_data.get()[side]
             .width
             .saturating_sub(linennumbers_width[side])
-            .saturating_sub(config.keep_plus_minus_markers as usize)
+            .saturating_sub(config.zero_marker.len())
     };
 
     LeftRight::new(line_width(Left), line_width(Right))
@@ -118,6 +137,7 @@ pub fn paint_minus_and_plus_lines_side_by_side(
     line_numbers_data: &mut Option<LineNumbersData>,
     output_buffer: &mut String,
     config: &config::Config,
+    wrap_lines: bool,
 ) {
     let line_states = LeftRight::new(
         lines[Left].iter().map(|(_, state)| state.clone()).collect(),
@@ -131,6 +151,13 @@ pub fn paint_minus_and_plus_lines_side_by_side(
         .as_mut()
         .unwrap_or_else(|| delta_unreachable("side-by-side requires Some(line_numbers_data)"));
 
+    let line_alignment = match config.side_by_side_align {
+        SideBySideAlign::Content => line_alignment,
+        SideBySideAlign::None => {
+            make_positional_line_alignment(lines[Left].len(), lines[Right].len())
+        }
+    };
+
     let bg_should_fill = LeftRight::new(
         // Using an ANSI sequence to fill the left panel would not work.
         BgShouldFill::With(BgFillMethod::Spaces),
@@ -143,7 +170,7 @@ pub fn paint_minus_and_plus_lines_side_by_side(
     // If so, remember the calculated line width and which of the lines are too
     // long for later re-use.
     let (should_wrap, line_width, long_lines) = {
-        if config.wrap_config.max_lines == 1 {
+        if config.side_by_side_wrap_config.max_lines == 1 || !wrap_lines {
             (false, LeftRight::default(), LeftRight::default())
         } else {
             let line_width = available_line_width(config, line_numbers_data);
@@ -233,17 +260,22 @@ pub fn paint_zero_lines_side_by_side<'a>(
     line_numbers_data: &mut Option<&mut line_numbers::LineNumbersData>,
     painted_prefix: Option<ansi_term::ANSIString>,
     background_color_extends_to_terminal_width: BgShouldFill,
+    wrap_lines: bool,
 ) {
     let states = vec![State::HunkZero(DiffType::Unified, None)];
 
-    let (states, syntax_style_sections, diff_style_sections) = wrap_zero_block(
-        config,
-        line,
-        states,
-        syntax_style_sections,
-        diff_style_sections,
-        line_numbers_data,
-    );
+    let (states, syntax_style_sections, diff_style_sections) = if wrap_lines {
+        wrap_zero_block(
+            config,
+            line,
+            states,
+            syntax_style_sections,
+            diff_style_sections,
+            line_numbers_data,
+        )
+    } else {
+        (states, syntax_style_sections, diff_style_sections)
+    };
 
     for (line_index, ((syntax_sections, diff_sections), state)) in syntax_style_sections
         .into_iter()
@@ -445,11 +477,11 @@ fn paint_minus_or_plus_panel_line<'a>(
             )
         };
 
-    let painted_prefix = match (config.keep_plus_minus_markers, panel_side, state) {
-        (true, _, State::HunkPlusWrapped) => Some(config.plus_style.paint(" ")),
-        (true, _, State::HunkMinusWrapped) => Some(config.minus_style.paint(" ")),
-        (true, Left, _) => Some(config.minus_style.paint("-")),
-        (true, Right, _) => Some(config.plus_style.paint("+")),
+    let painted_prefix = match (!config.zero_marker.is_empty(), panel_side, state) {
+        (true, _, State::HunkPlusWrapped) => Some(config.plus_style.paint(&config.zero_marker)),
+        (true, _, State::HunkMinusWrapped) => Some(config.minus_style.paint(&config.zero_marker)),
+        (true, Left, _) => Some(config.minus_style.paint(&config.plus_minus_markers[Minus])),
+        (true, Right, _) => Some(config.plus_style.paint(&config.plus_minus_markers[Plus])),
         _ => None,
     };
 
@@ -484,17 +516,27 @@ fn pad_panel_line_to_width<'a>(
     // Emit empty line marker if the panel line is empty but not empty-by-construction. IOW if the
     // other panel contains a real line, and we are currently emitting an empty counterpart panel
     // to form the other half of the line, then don't emit the empty line marker.
-    if panel_line_is_empty && line_index.is_some() {
+    // A configured marker glyph is emitted whenever the line content is empty, even if the line
+    // was not painted as empty (e.g. because syntax highlighting produced a newline section).
+    let empty_line_marker = line_index
+        .filter(|&i| {
+            diff_style_sections[i]
+                .iter()
+                .all(|(_, text)| text.trim_end_matches('\n').is_empty())
+        })
+        .and(Painter::empty_line_marker(state, config));
+    if (panel_line_is_empty && line_index.is_some()) || empty_line_marker.is_some() {
+        let marker = empty_line_marker.unwrap_or(" ");
         match state {
             State::HunkMinus(_, _) => Painter::mark_empty_line(
                 &config.minus_empty_line_marker_style,
                 panel_line,
-                Some(" "),
+                Some(marker),
             ),
             State::HunkPlus(_, _) => Painter::mark_empty_line(
                 &config.plus_empty_line_marker_style,
                 panel_line,
-                Some(" "),
+                Some(marker),
             ),
             State::HunkZero(_, _) => {}
             _ => unreachable!(),
@@ -502,7 +544,7 @@ fn pad_panel_line_to_width<'a>(
     };
 
     let text_width = ansi::measure_text_width(panel_line);
-    let panel_width = config.side_by_side_data[panel_side].width;
+    let panel_width = config.side_by_side_data.get()[panel_side].width;
 
     if text_width > panel_width {
         *panel_line =
@@ -561,7 +603,10 @@ pub mod ansifill {
         pub fn new(config: &Config) -> Self {
             Self(
                 config.side_by_side
-                    && Self::is_odd_with_ansi(&config.decorations_width, &config.line_fill_method),
+                    && Self::is_odd_with_ansi(
+                        &config.decorations_width.get(),
+                        &config.line_fill_method,
+                    ),
             )
         }
         pub fn sbs_odd_fix(
@@ -589,11 +634,27 @@ pub mod ansifill {
     }
 }
 
+// Pair the i-th minus line with the i-th plus line.
+pub fn make_positional_line_alignment(
+    n_minus: usize,
+    n_plus: usize,
+) -> Vec<(Option<usize>, Option<usize>)> {
+    (0..max(n_minus, n_plus))
+        .map(|i| {
+            (
+                Some(i).filter(|&i| i < n_minus),
+                Some(i).filter(|&i| i < n_plus),
+            )
+        })
+        .collect()
+}
+
 #[cfg(test)]
 pub mod tests {
     use crate::ansi::strip_ansi_codes;
     use crate::features::line_numbers::tests::*;
     use crate::tests::integration_test_utils::{make_config_from_args, run_delta, DeltaTest};
+    use crate::wrapping::LongLineDisplay;
 
     #[test]
     fn test_two_minus_lines() {
@@ -601,8 +662,8 @@ pub mod tests {
             .with_input(TWO_MINUS_LINES_DIFF)
             .expect_after_header(
                 r#"
-                │  1 │a = 1         │    │
-                │  2 │b = 23456     │    │"#,
+                │1│a = 1            │ │
+                │2│b = 23456        │ │"#,
             );
     }
 
@@ -614,6 +675,8 @@ pub mod tests {
             "0",
             "--width",
             "28",
+            "--line-numbers-min-width",
+            "4",
             "--line-fill-method=spaces",
         ])
         .set_config(|cfg| cfg.truncation_symbol = ">".into())
@@ -636,8 +699,8 @@ pub mod tests {
         .with_input(TWO_PLUS_LINES_DIFF)
         .expect_after_header(
             r#"
-            │    │              │  1 │a = 1         
-            │    │              │  2 │b = 234567    "#,
+            │ │                 │1│a = 1            
+            │ │                 │2│b = 234567       "#,
         );
     }
 
@@ -652,8 +715,8 @@ pub mod tests {
         .explain_ansi()
         .with_input(TWO_PLUS_LINES_DIFF)
         .expect_after_header(r#"
-        (blue)│(88)    (blue)│(normal)              (blue)│(28)  1 (blue)│(231 22)a (203)=(231) (141)1(normal 22)         (normal)
-        (blue)│(88)    (blue)│(normal)              (blue)│(28)  2 (blue)│(231 22)b (203)=(231) (141)234567(normal 22)    (normal)"#);
+        (blue)│(88) (blue)│(normal)                 (blue)│(28)1(blue)│(231 22)a (203)=(231) (141)1(normal 22)            (normal)
+        (blue)│(88) (blue)│(normal)                 (blue)│(28)2(blue)│(231 22)b (203)=(231) (141)234567(normal 22)       (normal)"#);
 
         DeltaTest::with_args(&[
             "--side-by-side",
@@ -664,8 +727,8 @@ pub mod tests {
         .explain_ansi()
         .with_input(TWO_PLUS_LINES_DIFF)
         .expect_after_header(r#"
-        (blue)│(88)    (blue)│(normal)              (blue) │(28)  1 (blue)│(231 22)a (203)=(231) (141)1(normal)
-        (blue)│(88)    (blue)│(normal)              (blue) │(28)  2 (blue)│(231 22)b (203)=(231) (141)234567(normal)"#);
+        (blue)│(88) (blue)│(normal)                 (blue) │(28)1(blue)│(231 22)a (203)=(231) (141)1(normal)
+        (blue)│(88) (blue)│(normal)                 (blue) │(28)2(blue)│(231 22)b (203)=(231) (141)234567(normal)"#);
     }
 
     #[test]
@@ -676,6 +739,8 @@ pub mod tests {
             "0",
             "--width",
             "30",
+            "--line-numbers-min-width",
+            "4",
             "--line-fill-method=spaces",
         ]);
         config.truncation_symbol = ">".into();
@@ -689,8 +754,14 @@ pub mod tests {
 
     #[test]
     fn test_two_plus_lines_exact_fit() {
-        let config =
-            make_config_from_args(&["--side-by-side", "--width", "33", "--line-fill-method=ansi"]);
+        let config = make_config_from_args(&[
+            "--side-by-side",
+            "--width",
+            "33",
+            "--line-numbers-min-width",
+            "4",
+            "--line-fill-method=ansi",
+        ]);
         let output = run_delta(TWO_PLUS_LINES_DIFF, &config);
         let mut lines = output.lines().skip(crate::config::HEADER_LEN);
         let (line_1, line_2) = (lines.next().unwrap(), lines.next().unwrap());
@@ -710,8 +781,119 @@ pub mod tests {
         .with_input(ONE_MINUS_ONE_PLUS_LINE_DIFF)
         .expect_after_header(
             r#"
-            │  1 │a = 1         │  1 │a = 1
-            │  2 │b = 2         │  2 │bb = 2        "#,
+            │1│a = 1            │1│a = 1
+            │2│b = 2            │2│bb = 2           "#,
+        );
+    }
+
+    #[test]
+    fn test_side_by_side_left_percent() {
+        DeltaTest::with_args(&[
+            "--side-by-side",
+            "--side-by-side-left-percent",
+            "30%",
+            "--width",
+            "40",
+            "--line-numbers-min-width",
+            "4",
+            "--line-fill-method=spaces",
+        ])
+        .with_input(ONE_MINUS_ONE_PLUS_LINE_DIFF)
+        .expect_after_header(
+            r#"
+            │  1 │a = 1 │  1 │a = 1
+            │  2 │b = 2 │  2 │bb = 2                "#,
+        );
+        // The line numbers take the whole left panel.
+        DeltaTest::with_args(&[
+            "--side-by-side",
+            "--side-by-side-left-percent",
+            "15",
+            "--width",
+            "40",
+            "--line-numbers-min-width",
+            "4",
+            "--line-fill-method=spaces",
+        ])
+        .with_input(ONE_MINUS_ONE_PLUS_LINE_DIFF)
+        .expect_after_header(
+            r#"
+            │  1 ││  1 │a = 1
+            │  2 ││  2 │bb = 2                      "#,
+        );
+    }
+
+    #[test]
+    fn test_side_by_side_wrap() {
+        let args = [
+            "--side-by-side",
+            "--width",
+            "28",
+            "--line-numbers-min-width",
+            "4",
+            "--line-fill-method=spaces",
+        ];
+        DeltaTest::with_args(&args)
+            .with_input(TWO_MINUS_LINES_DIFF)
+            .expect_after_header(
+                r#"
+                │  1 │a = 1   │    │
+                │  2 │b = 234↵│    │
+                │    │56      │    │"#,
+            );
+        DeltaTest::with_args(&[&args[..], &["--side-by-side-wrap", "truncate"]].concat())
+            .set_config(|cfg| cfg.truncation_symbol = ">".into())
+            .with_input(TWO_MINUS_LINES_DIFF)
+            .expect_after_header(
+                r#"
+                │  1 │a = 1   │    │
+                │  2 │b = 234>│    │"#,
+            );
+        let config = make_config_from_args(&["--side-by-side-wrap", "truncate"]);
+        assert_eq!(config.wrap_config.long_line_display, LongLineDisplay::Wrap);
+        assert_eq!(
+            config.side_by_side_wrap_config.long_line_display,
+            LongLineDisplay::Truncate
+        );
+    }
+
+    #[test]
+    fn test_side_by_side_align() {
+        let input = "\
+--- a/a.py
++++ b/a.py
+@@ -1,2 +1,2 @@
+-first = 1
+-second = 2
++other = 0
++first = 11
+";
+        DeltaTest::with_args(&[
+            "--side-by-side",
+            "--width",
+            "40",
+            "--line-fill-method=spaces",
+        ])
+        .with_input(input)
+        .expect_after_header(
+            r#"
+            │ │                 │1│other = 0        
+            │1│first = 1        │2│first = 11       
+            │2│second = 2       │ │"#,
+        );
+        DeltaTest::with_args(&[
+            "--side-by-side",
+            "--side-by-side-align",
+            "none",
+            "--width",
+            "40",
+            "--line-fill-method=spaces",
+        ])
+        .with_input(input)
+        .expect_after_header(
+            r#"
+            │1│first = 1        │1│other = 0        
+            │2│second = 2       │2│first = 11       "#,
         );
     }
 }
@@ -0,0 +1,129 @@
+use crate::cli;
+use crate::features::{DefaultValue, OptionValueFunction};
+use crate::git_config::GitConfig;
+
+/// The builtin themes selectable with --theme. Each is a builtin feature of the same name which
+/// sets coordinated styles for the diff lines, line numbers, hunk headers and decorations.
+pub const THEMES: &[&str] = &["monokai-delta", "solarized"];
+
+pub fn make_monokai_delta_feature() -> Vec<(String, OptionValueFunction)> {
+    make_theme_feature(&[
+        ("minus-style", "syntax #4b1726"),
+        ("minus-emph-style", "syntax #8c1d3f"),
+        ("minus-non-emph-style", "syntax #4b1726"),
+        ("plus-style", "syntax #2b3b14"),
+        ("plus-emph-style", "syntax #4a6b16"),
+        ("plus-non-emph-style", "syntax #2b3b14"),
+        ("line-numbers-minus-style", "#f92672"),
+        ("line-numbers-plus-style", "#a6e22e"),
+        ("line-numbers-zero-style", "#75715e"),
+        ("line-numbers-left-style", "#75715e"),
+        ("line-numbers-right-style", "#75715e"),
+        ("hunk-header-style", "file line-number syntax"),
+        ("hunk-header-decoration-style", "#66d9ef box"),
+        ("hunk-header-file-style", "#66d9ef"),
+        ("hunk-header-line-number-style", "#ae81ff"),
+        ("file-style", "bold #e6db74"),
+        ("file-decoration-style", "#e6db74 ul"),
+        ("commit-style", "bold #fd971f"),
+        ("commit-decoration-style", "#fd971f box"),
+    ])
+}
+
+pub fn make_solarized_feature() -> Vec<(String, OptionValueFunction)> {
+    make_theme_feature(&[
+        ("minus-style", "syntax #3f2024"),
+        ("minus-emph-style", "syntax #70272a"),
+        ("minus-non-emph-style", "syntax #3f2024"),
+        ("plus-style", "syntax #1f3323"),
+        ("plus-emph-style", "syntax #36522a"),
+        ("plus-non-emph-style", "syntax #1f3323"),
+        ("line-numbers-minus-style", "#dc322f"),
+        ("line-numbers-plus-style", "#859900"),
+        ("line-numbers-zero-style", "#586e75"),
+        ("line-numbers-left-style", "#586e75"),
+        ("line-numbers-right-style", "#586e75"),
+        ("hunk-header-style", "file line-number syntax"),
+        ("hunk-header-decoration-style", "#268bd2 box"),
+        ("hunk-header-file-style", "#268bd2"),
+        ("hunk-header-line-number-style", "#2aa198"),
+        ("file-style", "bold #b58900"),
+        ("file-decoration-style", "#b58900 ul"),
+        ("commit-style", "bold #cb4b16"),
+        ("commit-decoration-style", "#cb4b16 box"),
+    ])
+}
+
+// Unlike other builtin features, themes do not consult git config: their values are the same
+// whatever the other options.
+fn make_theme_feature(styles: &[(&str, &'static str)]) -> Vec<(String, OptionValueFunction)> {
+    styles
+        .iter()
+        .map(|&(option_name, value)| {
+            (
+                option_name.to_string(),
+                Box::new(move |_: &cli::Opt, _: &Option<GitConfig>| DefaultValue(value.into()))
+                    as OptionValueFunction,
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::fs::remove_file;
+
+    use super::THEMES;
+    use crate::features::make_builtin_features;
+    use crate::tests::integration_test_utils;
+
+    #[test]
+    fn test_themes_are_builtin_features() {
+        let builtin_features = make_builtin_features();
+        for theme in THEMES {
+            assert!(builtin_features.contains_key(*theme));
+        }
+    }
+
+    #[test]
+    fn test_theme() {
+        let opt = integration_test_utils::make_options_from_args_and_git_config(
+            &["--theme", "solarized"],
+            None,
+            None,
+        );
+        assert_eq!(opt.file_style, "bold #b58900");
+        assert_eq!(opt.line_numbers_plus_style, "#859900");
+        assert_eq!(opt.hunk_header_decoration_style, "#268bd2 box");
+    }
+
+    #[test]
+    fn test_theme_is_overridden_by_other_options() {
+        let git_config_contents = b"
+[delta]
+    theme = monokai-delta
+    file-style = red
+
+[delta \"my-feature\"]
+    commit-style = blue
+";
+        let git_config_path = "delta__test_theme_is_overridden_by_other_options.gitconfig";
+
+        let opt = integration_test_utils::make_options_from_args_and_git_config(
+            &[
+                "--features",
+                "my-feature",
+                "--hunk-header-decoration-style",
+                "none",
+            ],
+            Some(git_config_contents),
+            Some(git_config_path),
+        );
+        assert_eq!(opt.file_style, "red");
+        assert_eq!(opt.commit_style, "blue");
+        assert_eq!(opt.hunk_header_decoration_style, "none");
+        assert_eq!(opt.file_decoration_style, "#e6db74 ul");
+
+        remove_file(git_config_path).unwrap();
+    }
+}
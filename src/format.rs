@@ -2,14 +2,20 @@ use std::convert::{TryFrom, TryInto};
 
 use regex::Regex;
 use smol_str::SmolStr;
-use unicode_segmentation::UnicodeSegmentation;
 
+use crate::ansi;
 use crate::features::side_by_side::ansifill::ODD_PAD_CHAR;
 
 #[derive(Debug, PartialEq)]
 pub enum Placeholder<'a> {
     NumberMinus,
     NumberPlus,
+    RelativeNumberMinus,
+    RelativeNumberPlus,
+    SuppressedNumberMinus,
+    SuppressedNumberPlus,
+    HunkIndex,
+    FileIndex,
     Str(&'a str),
 }
 
@@ -17,8 +23,14 @@ impl<'a> TryFrom<Option<&'a str>> for Placeholder<'a> {
     type Error = ();
     fn try_from(from: Option<&'a str>) -> Result<Self, Self::Error> {
         match from {
-            Some(placeholder) if placeholder == "nm" => Ok(Placeholder::NumberMinus),
-            Some(placeholder) if placeholder == "np" => Ok(Placeholder::NumberPlus),
+            Some("nm") => Ok(Placeholder::NumberMinus),
+            Some("np") => Ok(Placeholder::NumberPlus),
+            Some("rm") => Ok(Placeholder::RelativeNumberMinus),
+            Some("rp") => Ok(Placeholder::RelativeNumberPlus),
+            Some("snm") => Ok(Placeholder::SuppressedNumberMinus),
+            Some("snp") => Ok(Placeholder::SuppressedNumberPlus),
+            Some("hunk") => Ok(Placeholder::HunkIndex),
+            Some("file") => Ok(Placeholder::FileIndex),
             Some(placeholder) => Ok(Placeholder::Str(placeholder)),
             _ => Err(()),
         }
@@ -81,7 +93,7 @@ impl<T> FormatStringPlaceholderDataAnyPlaceholder<T> {
     pub fn only_string(s: &str) -> Self {
         Self {
             suffix: s.into(),
-            suffix_len: s.graphemes(true).count(),
+            suffix_len: ansi::measure_text_width(s),
             ..Self::default()
         }
     }
@@ -93,15 +105,13 @@ pub type FormatStringPlaceholderData<'a> =
 pub type FormatStringSimple = FormatStringPlaceholderDataAnyPlaceholder<()>;
 
 impl<'a> FormatStringPlaceholderData<'a> {
-    pub fn width(&self, hunk_max_line_number_width: usize) -> (usize, usize) {
+    pub fn width(&self, placeholder_width: usize) -> (usize, usize) {
         // Only if Some(placeholder) is present will there be a number formatted
         // by this placeholder, if not width is also None.
         (
             self.prefix_len
                 + std::cmp::max(
-                    self.placeholder
-                        .as_ref()
-                        .map_or(0, |_| hunk_max_line_number_width),
+                    self.placeholder.as_ref().map_or(0, |_| placeholder_width),
                     self.width.unwrap_or(0),
                 ),
             self.suffix_len,
@@ -174,9 +184,9 @@ pub fn parse_line_number_format<'a>(
         let match_ = captures.get(0).unwrap();
         let prefix = SmolStr::new(&format_string[offset..match_.start()]);
         let prefix = expand_first_prefix(prefix);
-        let prefix_len = prefix.graphemes(true).count();
+        let prefix_len = ansi::measure_text_width(&prefix);
         let suffix = SmolStr::new(&format_string[match_.end()..]);
-        let suffix_len = suffix.graphemes(true).count();
+        let suffix_len = ansi::measure_text_width(&suffix);
         format_data.push(FormatStringPlaceholderData {
             prefix,
             prefix_len,
@@ -204,13 +214,13 @@ pub fn parse_line_number_format<'a>(
     if offset == 0 {
         let prefix = SmolStr::new("");
         let prefix = expand_first_prefix(prefix);
-        let prefix_len = prefix.graphemes(true).count();
+        let prefix_len = ansi::measure_text_width(&prefix);
         // No placeholders
         format_data.push(FormatStringPlaceholderData {
             prefix,
             prefix_len,
             suffix: SmolStr::new(format_string),
-            suffix_len: format_string.graphemes(true).count(),
+            suffix_len: ansi::measure_text_width(format_string),
             ..Default::default()
         })
     }
@@ -12,10 +12,13 @@ pub enum GitConfigEntry {
     GitRemote(GitRemoteRepo),
 }
 
+#[allow(clippy::enum_variant_names)]
 #[derive(Clone, Debug, PartialEq)]
 pub enum GitRemoteRepo {
     GitHubRepo { repo_slug: String },
     GitLabRepo { repo_slug: String },
+    BitbucketRepo { repo_slug: String },
+    GiteaRepo { host: String, repo_slug: String },
 }
 
 impl GitRemoteRepo {
@@ -27,6 +30,63 @@ impl GitRemoteRepo {
             Self::GitLabRepo { repo_slug } => {
                 format!("https://gitlab.com/{}/-/commit/{}", repo_slug, commit)
             }
+            Self::BitbucketRepo { repo_slug } => {
+                format!("https://bitbucket.org/{}/commits/{}", repo_slug, commit)
+            }
+            Self::GiteaRepo { host, repo_slug } => {
+                format!("https://{}/{}/commit/{}", host, repo_slug, commit)
+            }
+        }
+    }
+
+    /// Return the URL of the comparison of the commits `from` and `to`.
+    pub fn format_compare_url(&self, from: &str, to: &str) -> String {
+        match self {
+            Self::GitHubRepo { repo_slug } => {
+                format!("https://github.com/{}/compare/{}...{}", repo_slug, from, to)
+            }
+            Self::GitLabRepo { repo_slug } => {
+                format!(
+                    "https://gitlab.com/{}/-/compare/{}...{}",
+                    repo_slug, from, to
+                )
+            }
+            Self::BitbucketRepo { repo_slug } => {
+                format!(
+                    "https://bitbucket.org/{}/branches/compare/{}%0D{}",
+                    repo_slug, to, from
+                )
+            }
+            Self::GiteaRepo { host, repo_slug } => {
+                format!("https://{}/{}/compare/{}...{}", host, repo_slug, from, to)
+            }
+        }
+    }
+
+    /// Return the URL of the file at `path`, relative to the repository root, as of `commit`.
+    pub fn format_file_url(&self, commit: &str, path: &str) -> String {
+        match self {
+            Self::GitHubRepo { repo_slug } => {
+                format!("https://github.com/{}/blob/{}/{}", repo_slug, commit, path)
+            }
+            Self::GitLabRepo { repo_slug } => {
+                format!(
+                    "https://gitlab.com/{}/-/blob/{}/{}",
+                    repo_slug, commit, path
+                )
+            }
+            Self::BitbucketRepo { repo_slug } => {
+                format!(
+                    "https://bitbucket.org/{}/src/{}/{}",
+                    repo_slug, commit, path
+                )
+            }
+            Self::GiteaRepo { host, repo_slug } => {
+                format!(
+                    "https://{}/{}/src/commit/{}/{}",
+                    host, repo_slug, commit, path
+                )
+            }
         }
     }
 }
@@ -35,7 +95,8 @@ lazy_static! {
     static ref GITHUB_REMOTE_URL: Regex = Regex::new(
         r"(?x)
         ^
-        (?:https://|git@)? # Support both HTTPS and SSH URLs, SSH URLs optionally omitting the git@
+        (?:(?:https|ssh)://)? # Support both HTTPS and SSH URLs, SSH URLs optionally omitting
+        (?:git@)?             # the git@ and the scheme
         github\.com
         [:/]              # This separator differs between SSH and HTTPS URLs
         ([^/]+)           # Capture the user/org name
@@ -49,7 +110,8 @@ lazy_static! {
     static ref GITLAB_REMOTE_URL: Regex = Regex::new(
         r"(?x)
         ^
-        (?:https://|git@)? # Support both HTTPS and SSH URLs, SSH URLs optionally omitting the git@
+        (?:(?:https|ssh)://)? # Support both HTTPS and SSH URLs, SSH URLs optionally omitting
+        (?:git@)?             # the git@ and the scheme
         gitlab\.com
         [:/]              # This separator differs between SSH and HTTPS URLs
         ([^/]+)           # Capture the user/org name
@@ -61,6 +123,36 @@ lazy_static! {
         "
     )
     .unwrap();
+    static ref BITBUCKET_REMOTE_URL: Regex = Regex::new(
+        r"(?x)
+        ^
+        (?:(?:https|ssh)://)? # Support both HTTPS and SSH URLs, SSH URLs optionally omitting
+        (?:[^@/]+@)?          # the git@ and the scheme. HTTPS URLs may contain a user name.
+        bitbucket\.org
+        [:/]              # This separator differs between SSH and HTTPS URLs
+        ([^/]+)           # Capture the workspace name
+        /
+        (.+?)             # Capture the repo name (lazy to avoid consuming '.git' if present)
+        (?:\.git)?        # Non-capturing group to consume '.git' if present
+        $
+        "
+    )
+    .unwrap();
+    static ref GITEA_REMOTE_URL: Regex = Regex::new(
+        r"(?x)
+        ^
+        (?:(?:https|ssh)://)? # Support both HTTPS and SSH URLs, SSH URLs optionally omitting
+        (?:git@)?             # the git@ and the scheme
+        (gitea\.com|codeberg\.org) # Capture the host, only public instances are recognized
+        [:/]              # This separator differs between SSH and HTTPS URLs
+        ([^/]+)           # Capture the user/org name
+        /
+        (.+?)             # Capture the repo name (lazy to avoid consuming '.git' if present)
+        (?:\.git)?        # Non-capturing group to consume '.git' if present
+        $
+        "
+    )
+    .unwrap();
 }
 
 impl FromStr for GitRemoteRepo {
@@ -83,8 +175,25 @@ impl FromStr for GitRemoteRepo {
                     repo = caps.get(3).unwrap().as_str()
                 ),
             })
+        } else if let Some(caps) = BITBUCKET_REMOTE_URL.captures(s) {
+            Ok(Self::BitbucketRepo {
+                repo_slug: format!(
+                    "{workspace}/{repo}",
+                    workspace = caps.get(1).unwrap().as_str(),
+                    repo = caps.get(2).unwrap().as_str()
+                ),
+            })
+        } else if let Some(caps) = GITEA_REMOTE_URL.captures(s) {
+            Ok(Self::GiteaRepo {
+                host: caps.get(1).unwrap().as_str().to_string(),
+                repo_slug: format!(
+                    "{user}/{repo}",
+                    user = caps.get(2).unwrap().as_str(),
+                    repo = caps.get(3).unwrap().as_str()
+                ),
+            })
         } else {
-            Err("Not a GitHub or GitLab repo.".into())
+            Err("Not a GitHub, GitLab, Bitbucket or Gitea repo.".into())
         }
     }
 }
@@ -102,6 +211,7 @@ mod tests {
             "git@github.com:dandavison/delta",
             "github.com:dandavison/delta.git",
             "github.com:dandavison/delta",
+            "ssh://git@github.com/dandavison/delta.git",
         ];
         for url in urls {
             let parsed = GitRemoteRepo::from_str(url);
@@ -127,6 +237,17 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_format_github_compare_link() {
+        let repo = GitRemoteRepo::GitHubRepo {
+            repo_slug: "dandavison/delta".to_string(),
+        };
+        assert_eq!(
+            repo.format_compare_url("7a26f0f", "30a23d7"),
+            "https://github.com/dandavison/delta/compare/7a26f0f...30a23d7"
+        )
+    }
+
     #[test]
     fn test_parse_gitlab_urls() {
         let urls = &[
@@ -171,4 +292,83 @@ mod tests {
             format!("https://gitlab.com/proj/grp/repo/-/commit/{}", commit_hash)
         )
     }
+
+    #[test]
+    fn test_parse_bitbucket_urls() {
+        let urls = &[
+            "https://bitbucket.org/workspace/repo.git",
+            "https://user@bitbucket.org/workspace/repo.git",
+            "git@bitbucket.org:workspace/repo.git",
+            "ssh://git@bitbucket.org/workspace/repo",
+        ];
+        for url in urls {
+            assert_eq!(
+                GitRemoteRepo::from_str(url).unwrap(),
+                GitRemoteRepo::BitbucketRepo {
+                    repo_slug: "workspace/repo".to_string()
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_gitea_urls() {
+        let urls = &[
+            ("https://codeberg.org/user/repo.git", "codeberg.org"),
+            ("git@codeberg.org:user/repo.git", "codeberg.org"),
+            ("ssh://git@gitea.com/user/repo", "gitea.com"),
+        ];
+        for (url, host) in urls {
+            assert_eq!(
+                GitRemoteRepo::from_str(url).unwrap(),
+                GitRemoteRepo::GiteaRepo {
+                    host: host.to_string(),
+                    repo_slug: "user/repo".to_string()
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_unknown_host_url() {
+        assert!(GitRemoteRepo::from_str("git@git.example.com:user/repo.git").is_err());
+    }
+
+    #[test]
+    fn test_format_file_links() {
+        let commit_hash = "d3b07384d113edec49eaa6238ad5ff00";
+        let repos = [
+            (
+                GitRemoteRepo::GitHubRepo {
+                    repo_slug: "user/repo".to_string(),
+                },
+                "https://github.com/user/repo/blob",
+            ),
+            (
+                GitRemoteRepo::GitLabRepo {
+                    repo_slug: "user/repo".to_string(),
+                },
+                "https://gitlab.com/user/repo/-/blob",
+            ),
+            (
+                GitRemoteRepo::BitbucketRepo {
+                    repo_slug: "user/repo".to_string(),
+                },
+                "https://bitbucket.org/user/repo/src",
+            ),
+            (
+                GitRemoteRepo::GiteaRepo {
+                    host: "codeberg.org".to_string(),
+                    repo_slug: "user/repo".to_string(),
+                },
+                "https://codeberg.org/user/repo/src/commit",
+            ),
+        ];
+        for (repo, prefix) in repos {
+            assert_eq!(
+                repo.format_file_url(commit_hash, "src/main.rs"),
+                format!("{}/{}/src/main.rs", prefix, commit_hash)
+            );
+        }
+    }
 }
@@ -84,6 +84,32 @@ impl GitConfig {
         }
     }
 
+    /// The names and values of the entries of the [delta] section and of its subsections, i.e.
+    /// of the custom features, in the order in which they were read.
+    pub fn get_delta_entries(&self) -> Vec<(String, String)> {
+        if !self.enabled {
+            return Vec::new();
+        }
+        let mut entries = Vec::new();
+        if let Ok(config_entries) = self.config.entries(Some(r"^delta\.")) {
+            for entry in config_entries.into_iter().flatten() {
+                // An entry without "=" is a boolean set to true.
+                let value = if entry.has_value() {
+                    entry.value()
+                } else {
+                    Some("true")
+                };
+                if let (Some(name), Some(value)) = (entry.name(), value) {
+                    entries.push((name.to_string(), value.to_string()));
+                }
+            }
+        }
+        let mut env_var_entries: Vec<_> = self.config_from_env_var.clone().into_iter().collect();
+        env_var_entries.sort();
+        entries.extend(env_var_entries);
+        entries
+    }
+
     pub fn get<T>(&self, key: &str) -> Option<T>
     where
         T: GitConfigGet,
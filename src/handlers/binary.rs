@@ -0,0 +1,653 @@
+use std::fs::File;
+use std::io::Read;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::diff_header::{self, FileEvent};
+use crate::config::Config;
+use crate::delta::{DiffType, Source, State, StateMachine};
+use crate::features::side_by_side::Right;
+use crate::style::Style;
+use crate::utils;
+
+lazy_static! {
+    // E.g. "index 663a5d4..a59fe84 100644"
+    static ref INDEX_LINE_REGEX: Regex = Regex::new(r"^index ([0-9a-f]+)\.\.([0-9a-f]+)").unwrap();
+    // A line of base85 data in a GIT binary patch: a length character followed by the data.
+    static ref BINARY_PATCH_DATA_LINE_REGEX: Regex =
+        Regex::new(r"^[A-Za-z][0-9A-Za-z!#$%&()*+;<=>?@^_`{|}~-]+$").unwrap();
+}
+
+const HEXDUMP_BYTES_PER_LINE: usize = 16;
+
+/// How a change to a binary file is displayed, see --binary-diff.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BinaryDiff {
+    // A line giving the sizes of the two versions of the file.
+    Summary,
+    // The summary line followed by a hex dump of the two files, in two-file mode.
+    Hexdump,
+}
+
+/// The sections of a `git diff --binary` patch read so far.
+#[derive(Debug, Default)]
+pub struct BinaryPatch {
+    // The size of the new file followed by that of the old file, as given by the "literal"
+    // sections of the forward and reverse patches. "delta" sections do not give the size.
+    sizes: Vec<Option<u64>>,
+}
+
+/// A version of a binary file.
+#[derive(Debug, PartialEq)]
+enum BinaryFile {
+    // The file does not exist, i.e. it was added or removed.
+    Absent,
+    Unknown,
+    // The size of the file and its first bytes, as many as required by --hexdump-binary.
+    Known(u64, Vec<u8>),
+}
+
+impl BinaryFile {
+    fn bytes(&self) -> &[u8] {
+        match self {
+            BinaryFile::Known(_, bytes) => bytes,
+            _ => &[],
+        }
+    }
+}
+
+impl<'a> StateMachine<'a> {
+    /// Check for the "index" line and cache the object names of the two versions of the file,
+    /// which are used to find the sizes of binary files. Unless --keep-index-line is in effect,
+    /// the line itself is left to be handled as other diff header lines.
+    pub fn handle_diff_header_index_line(&mut self) -> std::io::Result<bool> {
+        if !matches!(self.state, State::DiffHeader(_)) {
+            return Ok(false);
+        }
+        if let Some(captures) = INDEX_LINE_REGEX.captures(&self.line) {
+            self.blob_ids = Some((captures[1].to_string(), captures[2].to_string()));
+        }
+        if !self.config.keep_index_line || !self.should_handle() || self.config.color_only {
+            return Ok(false);
+        }
+        match diff_header::format_index_line(&self.line, self.config) {
+            Some(line) => {
+                writeln!(self.painter.writer, "{}", line)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Check for and handle the "Binary files a/x and b/x differ" line.
+    pub fn handle_binary_files_line(&mut self) -> std::io::Result<bool> {
+        if !self.line.starts_with("Binary files ") || self.config.color_only {
+            return Ok(false);
+        }
+        self.painter.paint_buffered_minus_and_plus_lines();
+        if !matches!(self.state, State::DiffHeader(_)) {
+            self.state = State::DiffHeader(DiffType::Unified);
+        }
+        if !self.should_handle() {
+            return Ok(false);
+        }
+        let file_pair = diff_header::parse_binary_files_differ_line(
+            &self.line,
+            matches!(self.source, Source::GitDiff | Source::Mercurial),
+        );
+        self.painter.emit()?;
+        let raw_line = self.raw_line.clone();
+        self.write_binary_file_summary(file_pair, &[], &raw_line)?;
+        Ok(true)
+    }
+
+    /// Check for and handle the lines of a GIT binary patch, which are replaced by a summary of
+    /// the sizes of the files unless --keep-binary-patch is in effect.
+    pub fn handle_binary_patch_line(&mut self) -> std::io::Result<bool> {
+        if self.line == "GIT binary patch"
+            && matches!(self.state, State::DiffHeader(_))
+            && !self.config.color_only
+            && self.should_handle()
+        {
+            self.painter.paint_buffered_minus_and_plus_lines();
+            self.painter.emit()?;
+            self.binary_patch = Some(BinaryPatch::default());
+            return self.keep_binary_patch_line_maybe();
+        }
+        let binary_patch = match self.binary_patch.as_mut() {
+            Some(binary_patch) => binary_patch,
+            None => return Ok(false),
+        };
+        if let Some(size) = self.line.strip_prefix("literal ") {
+            binary_patch.sizes.push(size.parse().ok());
+        } else if self.line.starts_with("delta ") {
+            binary_patch.sizes.push(None);
+        } else if !is_binary_patch_line(&self.line) {
+            // The patch has ended: let the line be handled as usual.
+            self.write_binary_patch_summary()?;
+            return Ok(false);
+        }
+        self.keep_binary_patch_line_maybe()
+    }
+
+    fn keep_binary_patch_line_maybe(&mut self) -> std::io::Result<bool> {
+        if self.config.keep_binary_patch {
+            self.emit_line_unchanged()?;
+        }
+        Ok(true)
+    }
+
+    /// Write the summary of the GIT binary patch read so far, if any.
+    pub fn write_binary_patch_summary(&mut self) -> std::io::Result<()> {
+        if let Some(binary_patch) = self.binary_patch.take() {
+            let mut sizes = binary_patch.sizes;
+            sizes.reverse();
+            self.write_binary_file_summary(None, &sizes, "GIT binary patch")?;
+        }
+        Ok(())
+    }
+
+    /// Write the file header if it has not been written yet, followed by a line giving the sizes
+    /// of the old and new versions of the file, or `fallback_line` if they are not known.
+    /// `literal_sizes` are the sizes of the old and new files given by a GIT binary patch.
+    fn write_binary_file_summary(
+        &mut self,
+        file_pair: Option<(String, String)>,
+        literal_sizes: &[Option<u64>],
+        fallback_line: &str,
+    ) -> std::io::Result<()> {
+        let file_pair = file_pair.or_else(|| {
+            diff_header::get_repeated_file_path_from_diff_line(&self.diff_line)
+                .map(|file| (file.clone(), file))
+        });
+        // File operation or rename lines may have named the files, otherwise the file pair is
+        // that of a previous file.
+        let is_named = match (&self.current_file_pair, &file_pair) {
+            (Some(current), Some(file_pair)) => names_files(current, file_pair),
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+        if !is_named {
+            let (minus_file, plus_file) = file_pair.unwrap_or_default();
+            self.minus_file = minus_file;
+            self.plus_file = plus_file;
+            self.minus_file_event = FileEvent::Change;
+            self.plus_file_event = FileEvent::Change;
+            self.current_file_pair = Some((self.minus_file.clone(), self.plus_file.clone()));
+        }
+        if self.handled_diff_header_header_line_file_pair != self.current_file_pair {
+            self._handle_diff_header_header_line(self.source == Source::DiffUnified)?;
+            self.handled_diff_header_header_line_file_pair = self.current_file_pair.clone();
+            self.write_image_preview()?;
+        }
+
+        // The files on disk are the versions being compared only if the diff is not of a commit,
+        // and the old file is the same file as the new file only if the diff is of the worktree.
+        let on_disk = self.current_commit.is_none();
+        let (minus_blob_id, plus_blob_id) = self.blob_ids.clone().unwrap_or_default();
+        let minus_file = get_binary_file(
+            &self.minus_file,
+            &minus_blob_id,
+            literal_sizes.first().copied().flatten(),
+            on_disk && self.minus_file != self.plus_file,
+            self.config,
+        );
+        let plus_file = get_binary_file(
+            &self.plus_file,
+            &plus_blob_id,
+            literal_sizes.get(1).copied().flatten(),
+            on_disk,
+            self.config,
+        );
+        let summary = format_binary_file_summary(&minus_file, &plus_file, self.config)
+            .unwrap_or_else(|| fallback_line.to_string());
+        writeln!(
+            self.painter.writer,
+            "{}",
+            self.config.binary_style.paint(summary)
+        )?;
+        if self.config.hexdump_binary > 0 {
+            for line in format_hexdump(minus_file.bytes(), plus_file.bytes(), true, self.config) {
+                writeln!(self.painter.writer, "{}", line)?;
+            }
+        }
+        if self.config.binary_diff == BinaryDiff::Hexdump {
+            if let Some((minus_bytes, plus_bytes)) = read_files_to_compare(self.config) {
+                let side_by_side = self.config.side_by_side;
+                for line in format_hexdump(&minus_bytes, &plus_bytes, side_by_side, self.config) {
+                    writeln!(self.painter.writer, "{}", line)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// Return true if `current`, a file pair which may have an added or removed side, names the files
+// of `file_pair`.
+fn names_files(current: &(String, String), file_pair: &(String, String)) -> bool {
+    let names_file = |current: &str, file: &str| current == file || current == "/dev/null";
+    names_file(&current.0, &file_pair.0) && names_file(&current.1, &file_pair.1)
+}
+
+/// Return true if the line can be part of a GIT binary patch following its first line.
+pub fn is_binary_patch_line(line: &str) -> bool {
+    line.is_empty()
+        || line.starts_with("literal ")
+        || line.starts_with("delta ")
+        || BINARY_PATCH_DATA_LINE_REGEX.is_match(line)
+}
+
+/// Find the size and first bytes of a version of a binary file: from the repository, given the
+/// object name of the file in the index line, or else from the size given by a GIT binary patch,
+/// or else from the file on disk if `on_disk`.
+fn get_binary_file(
+    path: &str,
+    blob_id: &str,
+    literal_size: Option<u64>,
+    on_disk: bool,
+    config: &Config,
+) -> BinaryFile {
+    if path == "/dev/null" || !blob_id.is_empty() && blob_id.bytes().all(|b| b == b'0') {
+        return BinaryFile::Absent;
+    }
+    let n_bytes = config.hexdump_binary;
+    // The size is read from the object header, the content only if a hexdump is requested.
+    let read_blob = |odb: git2::Odb| -> Option<BinaryFile> {
+        let oid = odb
+            .exists_prefix(git2::Oid::from_str(blob_id).ok()?, blob_id.len())
+            .ok()?;
+        let (size, _) = odb.read_header(oid).ok()?;
+        let bytes = match n_bytes {
+            0 => Vec::new(),
+            _ => {
+                let object = odb.read(oid).ok()?;
+                object.data()[..n_bytes.min(size)].to_vec()
+            }
+        };
+        Some(BinaryFile::Known(size as u64, bytes))
+    };
+    if let Some(file) = config
+        .git_config
+        .as_ref()
+        .and_then(|git_config| git_config.repo.as_ref())
+        .filter(|_| !blob_id.is_empty())
+        .and_then(|repo| read_blob(repo.odb().ok()?))
+    {
+        return file;
+    }
+    if let Some(size) = literal_size {
+        return BinaryFile::Known(size, Vec::new());
+    }
+    let read_file = |path| -> std::io::Result<BinaryFile> {
+        let file = File::open(path)?;
+        let size = file.metadata()?.len();
+        let mut bytes = Vec::new();
+        file.take(n_bytes as u64).read_to_end(&mut bytes)?;
+        Ok(BinaryFile::Known(size, bytes))
+    };
+    match utils::path::absolute_path(path, config) {
+        Some(absolute_path) if on_disk => read_file(absolute_path).unwrap_or(BinaryFile::Unknown),
+        _ => BinaryFile::Unknown,
+    }
+}
+
+/// Return e.g. "binary: 14.2 KiB ⟶ 15.0 KiB (+0.8 KiB)", or a single size if the file was added
+/// or removed, or None if the sizes are not known.
+fn format_binary_file_summary(
+    minus_file: &BinaryFile,
+    plus_file: &BinaryFile,
+    config: &Config,
+) -> Option<String> {
+    match (minus_file, plus_file) {
+        (BinaryFile::Known(minus_size, _), BinaryFile::Known(plus_size, _)) => {
+            let unit = get_size_unit(*minus_size.max(plus_size));
+            let difference = match plus_size.cmp(minus_size) {
+                std::cmp::Ordering::Equal => "same size".to_string(),
+                std::cmp::Ordering::Greater => {
+                    format!("+{}", format_size(plus_size - minus_size, unit))
+                }
+                std::cmp::Ordering::Less => {
+                    format!("-{}", format_size(minus_size - plus_size, unit))
+                }
+            };
+            Some(format!(
+                "binary: {} {} {} ({})",
+                format_size(*minus_size, get_size_unit(*minus_size)),
+                config.right_arrow,
+                format_size(*plus_size, get_size_unit(*plus_size)),
+                difference
+            ))
+        }
+        (BinaryFile::Absent, BinaryFile::Known(size, _))
+        | (BinaryFile::Known(size, _), BinaryFile::Absent) => Some(format!(
+            "binary: {}",
+            format_size(*size, get_size_unit(*size))
+        )),
+        _ => None,
+    }
+}
+
+const SIZE_UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+// Return the index in SIZE_UNITS of the largest unit not exceeding `size`.
+fn get_size_unit(size: u64) -> usize {
+    (1..SIZE_UNITS.len())
+        .take_while(|&unit| size >= 1 << (10 * unit))
+        .last()
+        .unwrap_or(0)
+}
+
+fn format_size(size: u64, unit: usize) -> String {
+    match unit {
+        0 => format!("{} B", size),
+        _ => format!(
+            "{:.1} {}",
+            size as f64 / (1u64 << (10 * unit)) as f64,
+            SIZE_UNITS[unit]
+        ),
+    }
+}
+
+/// In two-file mode, return the contents of the two files given on the command line, unless
+/// either is larger than --binary-diff-max-size.
+fn read_files_to_compare(config: &Config) -> Option<(Vec<u8>, Vec<u8>)> {
+    let read_file = |path: &Option<std::path::PathBuf>| {
+        let path = path.as_ref()?;
+        let metadata = std::fs::metadata(path).ok()?;
+        if metadata.is_dir() || metadata.len() > config.binary_diff_max_size as u64 {
+            return None;
+        }
+        std::fs::read(path).ok()
+    };
+    Some((
+        read_file(&config.minus_file)?,
+        read_file(&config.plus_file)?,
+    ))
+}
+
+/// Return the lines of a hex dump of the bytes of two files, 16 bytes per line, with their offset
+/// in the line number gutter. Lines which differ are shown as a removed line followed by an added
+/// line, or side by side if `side_by_side`, and the bytes which differ are emphasized.
+fn format_hexdump(
+    minus_bytes: &[u8],
+    plus_bytes: &[u8],
+    side_by_side: bool,
+    config: &Config,
+) -> Vec<String> {
+    let n_lines = minus_bytes
+        .len()
+        .max(plus_bytes.len())
+        .div_ceil(HEXDUMP_BYTES_PER_LINE);
+    let get_line = |bytes: &[u8], offset: usize| {
+        let start = offset.min(bytes.len());
+        bytes[start..(start + HEXDUMP_BYTES_PER_LINE).min(bytes.len())].to_vec()
+    };
+    // The offset of the line, or blank if this version of the file has ended, followed by the
+    // bytes of the line.
+    let format_line = |offset: usize, bytes: &[u8], other_bytes: &[u8], styles: [Style; 3]| {
+        let [line_number_style, style, emph_style] = styles;
+        let offset = if bytes.is_empty() {
+            " ".repeat(8)
+        } else {
+            format!("{:08x}", offset)
+        };
+        format!(
+            "{}{}{}",
+            line_number_style.paint(offset),
+            config.line_numbers_style_leftright[Right].paint(" │ "),
+            format_hex_line(bytes, other_bytes, style, emph_style)
+        )
+    };
+    let zero_styles = [
+        config.line_numbers_zero_style,
+        config.zero_style,
+        config.zero_style,
+    ];
+    let minus_styles = [
+        config.line_numbers_style_minusplus.minus,
+        config.minus_style,
+        config.minus_emph_style,
+    ];
+    let plus_styles = [
+        config.line_numbers_style_minusplus.plus,
+        config.plus_style,
+        config.plus_emph_style,
+    ];
+
+    let mut lines = Vec::new();
+    for offset in (0..n_lines).map(|line| line * HEXDUMP_BYTES_PER_LINE) {
+        let minus_line = get_line(minus_bytes, offset);
+        let plus_line = get_line(plus_bytes, offset);
+        let (minus_styles, plus_styles) = if minus_line == plus_line {
+            (zero_styles, zero_styles)
+        } else {
+            (minus_styles, plus_styles)
+        };
+        let minus = format_line(offset, &minus_line, &plus_line, minus_styles);
+        let plus = format_line(offset, &plus_line, &minus_line, plus_styles);
+        if side_by_side {
+            // Pad the left side to the width of a full line of bytes.
+            let width = (3 * minus_line.len()).saturating_sub(1);
+            let padding = 3 * HEXDUMP_BYTES_PER_LINE - 1 - width;
+            lines.push(format!("{}{} {}", minus, " ".repeat(padding), plus));
+        } else if minus_line == plus_line {
+            lines.push(minus);
+        } else {
+            lines.extend(
+                [(minus_line, minus), (plus_line, plus)]
+                    .iter()
+                    .filter(|(bytes, _)| !bytes.is_empty())
+                    .map(|(_, line)| line.clone()),
+            );
+        }
+    }
+    lines
+}
+
+// Format the bytes as hex, emphasizing those which differ from the byte at the same position in
+// `other_bytes`.
+fn format_hex_line(bytes: &[u8], other_bytes: &[u8], style: Style, emph_style: Style) -> String {
+    bytes
+        .iter()
+        .enumerate()
+        .map(|(i, byte)| {
+            let byte_style = if other_bytes.get(i) == Some(byte) {
+                style
+            } else {
+                emph_style
+            };
+            byte_style.paint(format!("{:02x}", byte)).to_string()
+        })
+        .collect::<Vec<_>>()
+        .join(&style.paint(" ").to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::ansi::strip_ansi_codes;
+    use crate::tests::integration_test_utils::{self, DeltaTest};
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512, get_size_unit(512)), "512 B");
+        assert_eq!(format_size(14540, get_size_unit(14540)), "14.2 KiB");
+        assert_eq!(format_size(819, get_size_unit(15360)), "0.8 KiB");
+        assert_eq!(format_size(3 << 20, get_size_unit(3 << 20)), "3.0 MiB");
+    }
+
+    #[test]
+    fn test_binary_patch_summary() {
+        DeltaTest::with_args(&[])
+            .with_input(GIT_DIFF_BINARY_PATCH)
+            .expect_contains("\nlogo.png\n")
+            .expect_contains("\nbinary: 14.2 KiB ⟶   15.0 KiB (+0.8 KiB)\n")
+            .expect_contains("\nadded: icon.png\n")
+            .expect_contains("\nbinary: 40 B\n");
+        let config = integration_test_utils::make_config_from_args(&[]);
+        let output = strip_ansi_codes(&integration_test_utils::run_delta(
+            GIT_DIFF_BINARY_PATCH,
+            &config,
+        ));
+        assert!(!output.contains("GIT binary patch"));
+        assert!(!output.contains("zcmV"));
+        // The hunk following the binary patch is displayed as usual.
+        assert!(output.contains("\nREADME.md\n"));
+        assert!(output.contains("\nnew line\n"));
+    }
+
+    #[test]
+    fn test_keep_binary_patch() {
+        DeltaTest::with_args(&["--keep-binary-patch"])
+            .with_input(GIT_DIFF_BINARY_PATCH)
+            .expect_contains("GIT binary patch\nliteral 15360\nzcmV-`41e>Vks8;)uqY0\n");
+    }
+
+    #[test]
+    fn test_binary_files_on_disk() {
+        let dir = integration_test_utils::make_temp_dir("test_binary_files_on_disk");
+        let (old, new) = (dir.join("old.bin"), dir.join("new.bin"));
+        fs::write(&old, b"\x89PNG\r\n\x1a\n\x00\x01").unwrap();
+        fs::write(&new, b"\x89PNG\r\n\x1a\n\x00\x02\x03").unwrap();
+        let input = format!(
+            "Binary files {} and {} differ\n",
+            old.display(),
+            new.display()
+        );
+
+        let config = integration_test_utils::make_config_from_args(&["--hexdump-binary", "9"]);
+        let output = strip_ansi_codes(&integration_test_utils::run_delta(&input, &config));
+        assert!(output.contains("\nbinary: 10 B ⟶   11 B (+1 B)\n"));
+        assert!(output.contains(&format!(
+            "\n00000000 │ 89 50 4e 47 0d 0a 1a 0a 00{} 00000000 │ 89 50 4e 47 0d 0a 1a 0a 00\n",
+            " ".repeat(21)
+        )));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_binary_file_following_another_file() {
+        let input = "\
+diff --git a/README.md b/README.md
+index 1111111..2222222 100644
+--- a/README.md
++++ b/README.md
+@@ -1 +1 @@
+-old line
++new line
+diff --git a/logo.png b/logo.png
+index 663a5d4..a59fe84 100644
+Binary files a/logo.png and b/logo.png differ
+";
+        DeltaTest::with_args(&[])
+            .with_input(input)
+            .expect_contains("\nREADME.md\n")
+            .expect_contains("\nlogo.png\n");
+    }
+
+    #[test]
+    fn test_binary_files_of_unknown_size() {
+        DeltaTest::with_args(&[])
+            .with_input(GIT_DIFF_BINARY_FILES_DIFFER)
+            .expect_contains("\nadded: foo\n")
+            .expect_contains("\nBinary files /dev/null and b/foo differ\n");
+    }
+
+    #[test]
+    fn test_binary_diff_hexdump() {
+        let dir = integration_test_utils::make_temp_dir("test_binary_diff_hexdump");
+        let (old, new) = (dir.join("old.bin"), dir.join("new.bin"));
+        let mut old_bytes = (0..32).collect::<Vec<u8>>();
+        fs::write(&old, &old_bytes).unwrap();
+        old_bytes[17] = 0xff;
+        old_bytes.push(0x20);
+        fs::write(&new, &old_bytes).unwrap();
+        let input = format!(
+            "Binary files {} and {} differ\n",
+            old.display(),
+            new.display()
+        );
+        // The files given on the command line in two-file mode.
+        let make_config = |args: &[&str]| {
+            let mut config = integration_test_utils::make_config_from_args(
+                &[&["--binary-diff", "hexdump"], args].concat(),
+            );
+            config.minus_file = Some(old.clone());
+            config.plus_file = Some(new.clone());
+            config
+        };
+
+        let config = make_config(&[]);
+        let output = integration_test_utils::run_delta(&input, &config);
+        assert!(strip_ansi_codes(&output).contains(
+            "\n00000000 │ 00 01 02 03 04 05 06 07 08 09 0a 0b 0c 0d 0e 0f\n\
+             00000010 │ 10 11 12 13 14 15 16 17 18 19 1a 1b 1c 1d 1e 1f\n\
+             00000010 │ 10 ff 12 13 14 15 16 17 18 19 1a 1b 1c 1d 1e 1f\n\
+             00000020 │ 20\n"
+        ));
+        assert!(output.contains(&config.minus_emph_style.paint("11").to_string()));
+        assert!(output.contains(&config.plus_emph_style.paint("ff").to_string()));
+        assert!(output.contains(&config.plus_style.paint("1f").to_string()));
+
+        let config = make_config(&["--side-by-side"]);
+        let output = strip_ansi_codes(&integration_test_utils::run_delta(&input, &config));
+        assert!(output.contains(
+            "\n00000010 │ 10 11 12 13 14 15 16 17 18 19 1a 1b 1c 1d 1e 1f \
+             00000010 │ 10 ff 12 13 14 15 16 17 18 19 1a 1b 1c 1d 1e 1f\n"
+        ));
+        assert!(output.contains(&format!(
+            "\n{} │ {} 00000020 │ 20\n",
+            " ".repeat(8),
+            " ".repeat(47)
+        )));
+
+        // Files larger than --binary-diff-max-size are only summarized.
+        let config = make_config(&["--binary-diff-max-size", "32"]);
+        let output = strip_ansi_codes(&integration_test_utils::run_delta(&input, &config));
+        assert!(output.contains("\nbinary: 32 B ⟶   33 B (+1 B)\n"));
+        assert!(!output.contains("00000000"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    const GIT_DIFF_BINARY_PATCH: &str = "\
+diff --git a/logo.png b/logo.png
+index 663a5d41b590c32b0aaa7eaafd2dfa586c3c2daf..a59fe84e682c7ea046098190a683d2d42ff78572 100644
+GIT binary patch
+literal 15360
+zcmV-`41e>Vks8;)uqY0
+
+literal 14540
+zcmV-?Ss!Hc7_H>QNv5
+
+diff --git a/icon.png b/icon.png
+new file mode 100644
+index 0000000000000000000000000000000000000000..b57292120f1ee5d3a1fbb4eb1fd2b9d9f1c6b4a3
+GIT binary patch
+literal 40
+zcmV-?Ss!Hc7_H>QNv5
+
+literal 0
+HcmV?d00001
+
+diff --git a/README.md b/README.md
+index 8e37a9e..6ce4863 100644
+--- a/README.md
++++ b/README.md
+@@ -1 +1,2 @@
+ line
++new line
+";
+
+    const GIT_DIFF_BINARY_FILES_DIFFER: &str = "\
+diff --git a/foo b/foo
+new file mode 100644
+index 0000000..b572921
+Binary files /dev/null and b/foo differ
+";
+}
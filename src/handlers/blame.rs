@@ -1,9 +1,10 @@
-use chrono::{DateTime, FixedOffset};
+use chrono::{DateTime, Duration, FixedOffset, Utc};
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::borrow::Cow;
+use std::collections::HashMap;
 
-use crate::ansi::measure_text_width;
+use crate::ansi::{self, measure_text_width};
 use crate::color;
 use crate::config;
 use crate::config::delta_unreachable;
@@ -11,10 +12,26 @@ use crate::delta::{self, State, StateMachine};
 use crate::fatal;
 use crate::format::{self, FormatStringSimple, Placeholder};
 use crate::format::{make_placeholder_regex, parse_line_number_format};
+use crate::handlers;
 use crate::paint::{self, BgShouldFill, StyleSectionSpecifier};
 use crate::style::Style;
 use crate::utils;
 
+/// How the colors of blame-palette are assigned to commits, see --blame-palette-assignment.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BlamePaletteAssignment {
+    // In order of appearance, consecutive blocks of lines differing in color.
+    Sequential,
+    // By a hash of the author, see --blame-palette-hash-key.
+    Hashed,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BlamePaletteHashKey {
+    Author,
+    Email,
+}
+
 #[derive(Clone, Debug)]
 pub enum BlameLineNumbers {
     // "none" equals a fixed string with just a separator
@@ -40,41 +57,6 @@ impl<'a> StateMachine<'a> {
         if try_parse {
             let line = self.line.to_owned();
             if let Some(blame) = parse_git_blame_line(&line, &self.config.blame_timestamp_format) {
-                // Format blame metadata
-                let format_data = format::parse_line_number_format(
-                    &self.config.blame_format,
-                    &*BLAME_PLACEHOLDER_REGEX,
-                    false,
-                );
-                let mut formatted_blame_metadata =
-                    format_blame_metadata(&format_data, &blame, self.config);
-                let key = formatted_blame_metadata.clone();
-                let is_repeat = previous_key.as_deref() == Some(&key);
-                if is_repeat {
-                    formatted_blame_metadata =
-                        " ".repeat(measure_text_width(&formatted_blame_metadata))
-                };
-                let metadata_style =
-                    self.blame_metadata_style(&key, previous_key.as_deref(), is_repeat);
-                let code_style = self.config.blame_code_style.unwrap_or(metadata_style);
-                let separator_style = self.config.blame_separator_style.unwrap_or(code_style);
-
-                let (nr_prefix, line_number, nr_suffix) = format_blame_line_number(
-                    &self.config.blame_separator_format,
-                    blame.line_number,
-                    is_repeat,
-                );
-
-                write!(
-                    self.painter.writer,
-                    "{}{}{}{}",
-                    metadata_style.paint(&formatted_blame_metadata),
-                    separator_style.paint(nr_prefix),
-                    metadata_style.paint(&line_number),
-                    separator_style.paint(nr_suffix),
-                )?;
-
-                // Emit syntax-highlighted code
                 if matches!(self.state, State::Unknown) {
                     if let Some(lang) = utils::process::git_blame_filename_extension()
                         .as_ref()
@@ -84,21 +66,180 @@ impl<'a> StateMachine<'a> {
                         self.painter.set_highlighter();
                     }
                 }
-                self.state = State::Blame(key);
-                self.painter.syntax_highlight_and_paint_line(
-                    &format!("{}\n", blame.code),
-                    StyleSectionSpecifier::Style(code_style),
-                    self.state.clone(),
-                    BgShouldFill::default(),
-                );
+                self.write_blame_line(&blame, previous_key)?;
                 handled_line = true
+            } else {
+                handled_line = self.handle_blame_porcelain_line(previous_key)?;
             }
         }
         Ok(handled_line)
     }
 
+    /// Handle a line of `git blame --porcelain` or `--line-porcelain` output. The header records
+    /// of a line are collected, by commit since they are only given for the first line of a
+    /// commit in --porcelain output, and the line is written once its code is read.
+    fn handle_blame_porcelain_line(
+        &mut self,
+        previous_key: Option<String>,
+    ) -> std::io::Result<bool> {
+        if let Some(caps) = BLAME_PORCELAIN_HEADER_REGEX.captures(&self.line) {
+            let commit = caps.get(1).unwrap().as_str().to_string();
+            let line_number = caps.get(2).unwrap().as_str().parse::<usize>().unwrap_or(0);
+            self.blame_porcelain
+                .commits
+                .entry(commit.clone())
+                .or_default();
+            self.blame_porcelain.line = Some((commit, line_number));
+            return Ok(true);
+        }
+        let (commit, line_number) = match &self.blame_porcelain.line {
+            Some(line) => line.clone(),
+            None => return Ok(false),
+        };
+        let code = match self.line.strip_prefix('\t') {
+            Some(code) => format!(" {}", code),
+            None => {
+                let blame_commit = self.blame_porcelain.commits.get_mut(&commit).unwrap();
+                match self.line.split_once(' ') {
+                    Some(("author", author)) => blame_commit.author = author.to_string(),
+                    Some(("author-mail", mail)) => blame_commit.author_mail = mail.to_string(),
+                    Some(("author-time", time)) => blame_commit.author_time = time.to_string(),
+                    Some(("author-tz", tz)) => blame_commit.author_tz = tz.to_string(),
+                    Some(("summary", summary)) => blame_commit.summary = summary.to_string(),
+                    Some(("filename", filename)) => blame_commit.filename = filename.to_string(),
+                    None if self.line == "boundary" => blame_commit.is_boundary = true,
+                    // The committer, and the commit and file of the line before the commit, are
+                    // not displayed.
+                    Some((
+                        "committer" | "committer-mail" | "committer-time" | "committer-tz"
+                        | "previous",
+                        _,
+                    )) => {}
+                    _ => return Ok(false),
+                }
+                return Ok(true);
+            }
+        };
+        self.blame_porcelain.line = None;
+        let blame_commit = self.blame_porcelain.commits[&commit].clone();
+        // Commits are abbreviated as by `git blame`, a boundary commit being marked by '^'.
+        let commit = if blame_commit.is_boundary {
+            format!("^{}", &commit[..7])
+        } else {
+            commit[..8].to_string()
+        };
+        let timestamp = format!("{} {}", blame_commit.author_time, blame_commit.author_tz);
+        if matches!(self.state, State::Unknown)
+            || self.blame_porcelain.filename.as_ref() != Some(&blame_commit.filename)
+        {
+            if let Some(lang) = handlers::diff_header::get_extension(&blame_commit.filename)
+                .or(self.config.default_language.as_deref())
+            {
+                self.painter.set_syntax(Some(lang));
+                self.painter.set_highlighter();
+            }
+            self.blame_porcelain.filename = Some(blame_commit.filename.clone());
+        }
+        let blame = BlameLine {
+            commit: &commit,
+            author: &blame_commit.author,
+            author_mail: Some(&blame_commit.author_mail),
+            summary: Some(&blame_commit.summary),
+            time: DateTime::parse_from_str(&timestamp, "%s %z").ok(),
+            timestamp: &timestamp,
+            line_number,
+            code: &code,
+        };
+        self.write_blame_line(&blame, previous_key)?;
+        Ok(true)
+    }
+
+    fn write_blame_line(
+        &mut self,
+        blame: &BlameLine,
+        previous_key: Option<String>,
+    ) -> std::io::Result<()> {
+        // Format blame metadata
+        let format_data = format::parse_line_number_format(
+            &self.config.blame_format,
+            &BLAME_PLACEHOLDER_REGEX,
+            false,
+        );
+        let mut blame_fields = format_blame_metadata(&format_data, blame, self.config);
+        let key = blame_fields.join(&self.config.blame_field_separator);
+        if self.config.blame_metadata_width > 0 {
+            fit_blame_fields(
+                &mut blame_fields,
+                &self.config.blame_field_separator,
+                self.config.blame_metadata_width,
+            );
+        }
+        let is_repeat = previous_key.as_deref() == Some(&key);
+        if is_repeat {
+            // Blank out the fields but keep their widths (and the separators), so that
+            // the code section stays aligned with the preceding lines.
+            for field in blame_fields.iter_mut() {
+                *field = " ".repeat(measure_text_width(field));
+            }
+        };
+        let metadata_style =
+            self.blame_metadata_style(blame, &key, previous_key.as_deref(), is_repeat);
+        let distinct_style = if blame.commit.starts_with('^') {
+            self.config.blame_boundary_style
+        } else if blame.commit.bytes().all(|b| b == b'0') {
+            self.config.blame_uncommitted_style
+        } else {
+            None
+        };
+        let metadata_style = match distinct_style {
+            Some(mut style) => {
+                style.is_syntax_highlighted = true;
+                style
+            }
+            None => metadata_style,
+        };
+        let code_style = self.config.blame_code_style.unwrap_or(metadata_style);
+        let separator_style = self.config.blame_separator_style.unwrap_or(code_style);
+
+        let (nr_prefix, line_number, nr_suffix) = format_blame_line_number(
+            &self.config.blame_separator_format,
+            blame.line_number,
+            is_repeat,
+        );
+
+        for (i, field) in blame_fields.iter().enumerate() {
+            // An empty separator, the default, is not painted.
+            if i > 0 && !self.config.blame_field_separator.is_empty() {
+                write!(
+                    self.painter.writer,
+                    "{}",
+                    separator_style.paint(&self.config.blame_field_separator)
+                )?;
+            }
+            write!(self.painter.writer, "{}", metadata_style.paint(field))?;
+        }
+        write!(
+            self.painter.writer,
+            "{}{}{}",
+            separator_style.paint(nr_prefix),
+            metadata_style.paint(&line_number),
+            separator_style.paint(nr_suffix),
+        )?;
+
+        // Emit syntax-highlighted code
+        self.state = State::Blame(key);
+        self.painter.syntax_highlight_and_paint_line(
+            &format!("{}\n", blame.code),
+            StyleSectionSpecifier::Style(code_style),
+            self.state.clone(),
+            BgShouldFill::default(),
+        );
+        Ok(())
+    }
+
     fn blame_metadata_style(
         &mut self,
+        blame: &BlameLine,
         key: &str,
         previous_key: Option<&str>,
         is_repeat: bool,
@@ -111,7 +252,12 @@ impl<'a> StateMachine<'a> {
             }
             _ => {
                 // Compute the color ourselves.
-                let color = self.get_color(key, previous_key, is_repeat);
+                let color = match self.config.blame_palette_assignment {
+                    BlamePaletteAssignment::Sequential => {
+                        self.get_color(key, previous_key, is_repeat)
+                    }
+                    BlamePaletteAssignment::Hashed => self.get_hashed_color(blame),
+                };
                 // TODO: This will often be pointlessly updating a key with the
                 // value it already has. It might be nicer to do this (and
                 // compute the style) in get_color(), but as things stand the
@@ -172,6 +318,18 @@ impl<'a> StateMachine<'a> {
         }
     }
 
+    /// Return the color of the author, or of their email, independent of the other lines, so that
+    /// an author has the same color in any output. Colors of different authors may collide.
+    fn get_hashed_color(&self, blame: &BlameLine) -> String {
+        let hash_key = match (self.config.blame_palette_hash_key, blame.author_mail) {
+            (BlamePaletteHashKey::Email, Some(author_mail)) => author_mail,
+            _ => blame.author,
+        };
+        let n_colors = self.config.blame_palette.len() as u64;
+        let hash = fnv1a_hash(hash_key.as_bytes(), self.config.blame_palette_seed as u64);
+        self.config.blame_palette[(hash % n_colors) as usize].clone()
+    }
+
     fn get_next_color(&self, other_than_color: Option<&str>) -> String {
         let n_keys = self.blame_key_colors.len();
         let n_colors = self.config.blame_palette.len();
@@ -184,11 +342,49 @@ impl<'a> StateMachine<'a> {
     }
 }
 
+// FNV-1a, which unlike the hashers of std is specified, and so stable across builds.
+fn fnv1a_hash(bytes: &[u8], seed: u64) -> u64 {
+    let mut hash = 0xcbf29ce484222325 ^ seed;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+/// The commits of `git blame --porcelain` output read so far, and the line whose header records are
+/// being read, if any.
+#[derive(Debug, Default)]
+pub struct BlamePorcelain {
+    commits: HashMap<String, BlameCommit>,
+    // The commit and final line number of the line.
+    line: Option<(String, usize)>,
+    // The file whose syntax highlights the code.
+    filename: Option<String>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct BlameCommit {
+    author: String,
+    author_mail: String,
+    author_time: String,
+    author_tz: String,
+    summary: String,
+    filename: String,
+    is_boundary: bool,
+}
+
 #[derive(Debug)]
 pub struct BlameLine<'a> {
     pub commit: &'a str,
     pub author: &'a str,
-    pub time: DateTime<FixedOffset>,
+    // Only known for `git blame --porcelain` output.
+    pub author_mail: Option<&'a str>,
+    // Only known for `git blame --porcelain` output.
+    pub summary: Option<&'a str>,
+    // The timestamp, unless it could not be parsed.
+    pub time: Option<DateTime<FixedOffset>>,
+    pub timestamp: &'a str,
     pub line_number: usize,
     pub code: &'a str,
 }
@@ -227,6 +423,14 @@ $
     .unwrap();
 }
 
+lazy_static! {
+    // E.g. "30a23d7fd33b1b8601509dea77fa2a96f78e5c8e 2 2 2": the commit, the line number in the
+    // original file, the line number in the final file and, for the first line of a group of lines
+    // from the same commit, the number of lines in the group.
+    static ref BLAME_PORCELAIN_HEADER_REGEX: Regex =
+        Regex::new(r"^([0-9a-f]{40}) [0-9]+ ([0-9]+)(?: [0-9]+)?$").unwrap();
+}
+
 pub fn parse_git_blame_line<'a>(line: &'a str, timestamp_format: &str) -> Option<BlameLine<'a>> {
     let caps = BLAME_LINE_REGEX.captures(line)?;
 
@@ -234,7 +438,7 @@ pub fn parse_git_blame_line<'a>(line: &'a str, timestamp_format: &str) -> Option
     let author = caps.get(2).unwrap().as_str();
     let timestamp = caps.get(3).unwrap().as_str();
 
-    let time = DateTime::parse_from_str(timestamp, timestamp_format).ok()?;
+    let time = DateTime::parse_from_str(timestamp, timestamp_format).ok();
 
     let line_number = caps.get(4).unwrap().as_str().parse::<usize>().ok()?;
 
@@ -243,7 +447,10 @@ pub fn parse_git_blame_line<'a>(line: &'a str, timestamp_format: &str) -> Option
     Some(BlameLine {
         commit,
         author,
+        author_mail: None,
+        summary: None,
         time,
+        timestamp,
         line_number,
         code,
     })
@@ -252,43 +459,150 @@ pub fn parse_git_blame_line<'a>(line: &'a str, timestamp_format: &str) -> Option
 lazy_static! {
     // line numbers (`{n}`) change with every line and are set separately via `blame-separator-format`
     pub static ref BLAME_PLACEHOLDER_REGEX: Regex =
-        format::make_placeholder_regex(&["timestamp", "author", "commit"]);
+        format::make_placeholder_regex(&[
+            "timestamp",
+            "author",
+            "email",
+            "summary",
+            "commit",
+            "short-hash",
+        ]);
 }
 
+/// Format the blame metadata, returning one string per placeholder of blame-format. The text
+/// around a placeholder is included in the string of that placeholder, and the trailing text of
+/// the format string is appended to the last one. The caller joins the fields using
+/// blame-field-separator.
 pub fn format_blame_metadata(
     format_data: &[format::FormatStringPlaceholderData],
     blame: &BlameLine,
     config: &config::Config,
-) -> String {
-    let mut s = String::new();
+) -> Vec<String> {
+    let mut fields = Vec::new();
     let mut suffix = "";
     for placeholder in format_data {
-        s.push_str(placeholder.prefix.as_str());
+        let mut s = String::from(placeholder.prefix.as_str());
 
         let alignment_spec = placeholder.alignment_spec.unwrap_or(format::Align::Left);
-        let width = placeholder.width.unwrap_or(15);
+        // The width of {short-hash} is the length of the abbreviated commit.
+        let width = match placeholder.placeholder {
+            Some(Placeholder::Str("short-hash")) => placeholder.width.unwrap_or(7),
+            _ => placeholder.width.unwrap_or(15),
+        };
 
         let field = match placeholder.placeholder {
-            Some(Placeholder::Str("timestamp")) => Some(Cow::from(
-                chrono_humanize::HumanTime::from(blame.time).to_string(),
-            )),
+            Some(Placeholder::Str("timestamp")) => Some(format_blame_timestamp(blame, config)),
             Some(Placeholder::Str("author")) => Some(Cow::from(blame.author)),
+            Some(Placeholder::Str("email")) => Some(Cow::from(get_blame_email(blame))),
+            Some(Placeholder::Str("summary")) => Some(Cow::from(blame.summary.unwrap_or(""))),
             Some(Placeholder::Str("commit")) => Some(delta::format_raw_line(blame.commit, config)),
+            Some(Placeholder::Str("short-hash")) => {
+                // As by `git blame`, the '^' marking a boundary commit takes the place of a
+                // character of the commit.
+                let short_hash = match blame.commit.strip_prefix('^') {
+                    Some(commit) => {
+                        format!("^{}", &commit[..width.saturating_sub(1).min(commit.len())])
+                    }
+                    None => blame.commit[..width.min(blame.commit.len())].to_string(),
+                };
+                Some(Cow::from(
+                    delta::format_raw_line(&short_hash, config).into_owned(),
+                ))
+            }
             None => None,
             _ => unreachable!("Unexpected `git blame` input"),
         };
         if let Some(field) = field {
-            s.push_str(&format::pad(
+            s.push_str(&pad_blame_field(
                 &field,
                 width,
                 alignment_spec,
                 placeholder.precision,
             ))
         }
+        fields.push(s);
         suffix = placeholder.suffix.as_str();
     }
-    s.push_str(suffix);
-    s
+    if let Some(last) = fields.last_mut() {
+        last.push_str(suffix);
+    }
+    fields
+}
+
+/// Format the timestamp of a blame line relative to now, e.g. "3 days ago", or, if it is older
+/// than blame-timestamp-relative-threshold, as its date. Relative timestamps are at most 14
+/// characters wide, so that they stay aligned within the default width of the placeholder.
+fn format_blame_timestamp<'a>(blame: &BlameLine<'a>, config: &config::Config) -> Cow<'a, str> {
+    match blame.time {
+        Some(time) if config.blame_timestamp_relative => {
+            let threshold = config.blame_timestamp_relative_threshold as i64;
+            if threshold > 0 && Utc::now().signed_duration_since(time) > Duration::days(threshold) {
+                Cow::from(time.format("%Y-%m-%d").to_string())
+            } else {
+                Cow::from(chrono_humanize::HumanTime::from(time).to_string())
+            }
+        }
+        Some(time) => Cow::from(time.format("%Y-%m-%d %H:%M:%S %z").to_string()),
+        None => Cow::from(blame.timestamp),
+    }
+}
+
+/// The author email without the angle brackets, if known: it is given by `git blame --porcelain`,
+/// and in place of the author name by `git blame -e`.
+fn get_blame_email<'a>(blame: &BlameLine<'a>) -> &'a str {
+    let strip_brackets = |s: &'a str| s.strip_prefix('<').and_then(|s| s.strip_suffix('>'));
+    match blame.author_mail {
+        Some(author_mail) => strip_brackets(author_mail).unwrap_or(author_mail),
+        None => strip_brackets(blame.author).unwrap_or(""),
+    }
+}
+
+/// Pad `field` to `width` and truncate it to `precision`, ending it with an ellipsis, both in
+/// display width rather than characters, so that wide author names stay aligned.
+fn pad_blame_field(
+    field: &str,
+    width: usize,
+    alignment: format::Align,
+    precision: Option<usize>,
+) -> String {
+    let field = match precision {
+        Some(precision) => ansi::truncate_str(field, precision, "…"),
+        None => Cow::from(field),
+    };
+    let padding = width.saturating_sub(measure_text_width(&field));
+    let (left, right) = match alignment {
+        format::Align::Left => (0, padding),
+        format::Align::Center => (padding / 2, padding - padding / 2),
+        format::Align::Right => (padding, 0),
+    };
+    format!("{}{}{}", " ".repeat(left), field, " ".repeat(right))
+}
+
+/// Truncate or pad the fields so that, joined by `separator`, they are `width` wide, see
+/// --blame-metadata-width. Fields which do not fit at all are dropped.
+fn fit_blame_fields(fields: &mut Vec<String>, separator: &str, width: usize) {
+    let separator_width = measure_text_width(separator);
+    let mut used = 0;
+    for i in 0..fields.len() {
+        let separator_width = if i > 0 { separator_width } else { 0 };
+        if used + separator_width >= width {
+            fields.truncate(i);
+            break;
+        }
+        let available = width - used - separator_width;
+        if measure_text_width(&fields[i]) > available {
+            // The field takes the rest of the width, whether or not it is truncated: its padding
+            // is dropped rather than truncated, and the field padded again, so that the fields
+            // are at the same columns on every line.
+            let field = ansi::truncate_str(fields[i].trim_end(), available, "…");
+            let padding = available - measure_text_width(&field);
+            fields[i] = format!("{}{}", field, " ".repeat(padding));
+        }
+        used += separator_width + measure_text_width(&fields[i]);
+    }
+    if let Some(last) = fields.last_mut() {
+        last.push_str(&" ".repeat(width.saturating_sub(used)));
+    }
 }
 
 pub fn format_blame_line_number(
@@ -490,6 +804,313 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_blame_palette_assignment_hashed() {
+        let input = "\
+aaaaaaa (Dan Davison       2021-08-22 18:20:19 -0700 120) A
+bbbbbbb (Ann             2020-07-18 15:34:43 -0400   1) B
+";
+        let input_in_other_order = "\
+bbbbbbb (Ann             2020-07-18 15:34:43 -0400   1) B
+ccccccc (Carl            2020-07-18 15:34:43 -0400   2) C
+aaaaaaa (Dan Davison       2021-08-22 18:20:19 -0700 120) A
+";
+        let palette = "1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16";
+        let colors = |input: &str, seed: &str| {
+            let config = integration_test_utils::make_config_from_args(&[
+                "--blame-format",
+                "{author}",
+                "--blame-palette",
+                palette,
+                "--blame-palette-assignment",
+                "hashed",
+                "--blame-palette-seed",
+                seed,
+            ]);
+            let output = integration_test_utils::run_delta(input, &config);
+            let mut colors: Vec<(String, String)> = output
+                .lines()
+                .map(|line| {
+                    let color = line.split('m').next().unwrap().to_string();
+                    (crate::ansi::strip_ansi_codes(line)[..3].to_string(), color)
+                })
+                .filter(|(author, _)| author != "Car")
+                .collect();
+            colors.sort();
+            colors
+        };
+        assert_eq!(colors(input, "0"), colors(input_in_other_order, "0"));
+        assert_ne!(colors(input, "0"), colors(input, "1"));
+        // The colors must not change between builds.
+        assert_eq!(fnv1a_hash(b"a", 0), 0xaf63dc4c8601ec8c);
+    }
+
+    #[test]
+    fn test_blame_field_separator() {
+        let config = integration_test_utils::make_config_from_args(&[
+            "--blame-format",
+            "{author:<12} {commit:<8}",
+            "--blame-field-separator",
+            "|",
+            "--blame-separator-format",
+            "│",
+        ]);
+        let input = "\
+aaaaaaa (Dan Davison       2021-08-22 18:20:19 -0700 120) A
+aaaaaaa (Dan Davison       2021-08-22 18:20:19 -0700 121) B
+bbbbbbb (Ann             2020-07-18 15:34:43 -0400   1) C
+";
+        let output = integration_test_utils::run_delta(input, &config);
+        let output = crate::ansi::strip_ansi_codes(&output);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "Dan Davison | aaaaaaa │ A");
+        assert_eq!(lines[1], "            |         │ B");
+        assert_eq!(lines[2], "Ann         | bbbbbbb │ C");
+
+        // The default, empty, separator leaves no empty styled segments between the fields.
+        let config = integration_test_utils::make_config_from_args(&[
+            "--blame-format",
+            "{author:<12} {commit:<8}",
+            "--blame-separator-style",
+            "red",
+        ]);
+        let output = integration_test_utils::run_delta(input, &config);
+        let empty_separator = config.blame_separator_style.unwrap().paint("").to_string();
+        assert!(!empty_separator.is_empty());
+        assert!(!output.contains(&empty_separator), "{:?}", output);
+    }
+
+    #[test]
+    fn test_blame_timestamp() {
+        let recent = (Utc::now() - Duration::days(3)).format("%Y-%m-%d %H:%M:%S %z");
+        let input = format!(
+            "\
+aaaaaaa (Dan Davison       2021-08-22 18:20:19 -0700 120) A
+bbbbbbb (Ann             {}   1) B
+",
+            recent
+        );
+        let run = |args: &[&str]| {
+            let args = [&["--blame-format", "{timestamp} {commit:<8}"], args].concat();
+            let config = integration_test_utils::make_config_from_args(&args);
+            let output = integration_test_utils::run_delta(&input, &config);
+            crate::ansi::strip_ansi_codes(&output)
+        };
+
+        let output = run(&[]);
+        let lines: Vec<&str> = output.lines().collect();
+        assert!(lines[0].contains(" years ago "), "{}", output);
+        assert_eq!(lines[0].find("aaaaaaa"), Some(16));
+        assert!(
+            lines[1].starts_with("3 days ago      bbbbbbb"),
+            "{}",
+            output
+        );
+
+        let output = run(&["--blame-timestamp-relative-threshold", "30"]);
+        let lines: Vec<&str> = output.lines().collect();
+        assert!(
+            lines[0].starts_with("2021-08-22      aaaaaaa"),
+            "{}",
+            output
+        );
+        assert!(
+            lines[1].starts_with("3 days ago      bbbbbbb"),
+            "{}",
+            output
+        );
+
+        let output = run(&["--blame-timestamp-relative", "false"]);
+        assert!(output.starts_with("2021-08-22 18:20:19 -0700 aaaaaaa"));
+
+        // A timestamp which cannot be parsed is displayed as received.
+        let output = run(&["--blame-timestamp-format", "%d.%m.%Y"]);
+        assert!(output.starts_with("2021-08-22 18:20:19 -0700 aaaaaaa"));
+    }
+
+    #[test]
+    fn test_blame_wide_author_names_are_aligned() {
+        let config = integration_test_utils::make_config_from_args(&[
+            "--blame-format",
+            "{author:<10.9} {short-hash}",
+            "--blame-separator-format",
+            "│",
+        ]);
+        let input = "\
+61f180c8 (Kangwook Lee (이강욱) 2021-06-09 23:33:59 +0900 130) A
+^b2257cfa (이강욱         2020-07-18 15:34:43 -0400   1) B
+";
+        let output = integration_test_utils::run_delta(input, &config);
+        let output = crate::ansi::strip_ansi_codes(&output);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "Kangwook…  61f180c│ A");
+        assert_eq!(lines[1], "이강욱     ^b2257c│ B");
+    }
+
+    #[test]
+    fn test_blame_metadata_width() {
+        let input = "\
+aaaaaaa (Dan Davison       2021-08-22 18:20:19 -0700 120) A
+bbbbbbb (Ann             2020-07-18 15:34:43 -0400   1) B
+";
+        let run = |width: &str| {
+            let config = integration_test_utils::make_config_from_args(&[
+                "--blame-format",
+                "{author} {commit}",
+                "--blame-field-separator",
+                "|",
+                "--blame-separator-format",
+                "│",
+                "--blame-metadata-width",
+                width,
+            ]);
+            let output = integration_test_utils::run_delta(input, &config);
+            crate::ansi::strip_ansi_codes(&output)
+        };
+        let output = run("20");
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "Dan Davison    | aa…│ A");
+        assert_eq!(lines[1], "Ann            | bb…│ B");
+        let output = run("10");
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "Dan Davis…│ A");
+        assert_eq!(lines[1], "Ann       │ B");
+    }
+
+    #[test]
+    fn test_blame_format_email() {
+        let config = integration_test_utils::make_config_from_args(&[
+            "--blame-format",
+            "{email:<18} {short-hash:6}",
+        ]);
+        let output = integration_test_utils::run_delta(GIT_BLAME_PORCELAIN, &config);
+        let output = crate::ansi::strip_ansi_codes(&output);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(
+            lines[1],
+            "dandavison7@gmail.com 30a23d│  2 │     let x = 1;"
+        );
+
+        let config = integration_test_utils::make_config_from_args(&["--blame-format", "{email}"]);
+        let input = "\
+aaaaaaa (<ann@example.com> 2021-08-22 18:20:19 -0700 1) A
+bbbbbbb (Ann 2021-08-22 18:20:19 -0700 2) B
+";
+        let output = integration_test_utils::run_delta(input, &config);
+        let output = crate::ansi::strip_ansi_codes(&output);
+        let lines: Vec<&str> = output.lines().collect();
+        assert!(lines[0].starts_with("ann@example.com│"), "{}", output);
+        assert!(lines[1].starts_with("               │"), "{}", output);
+    }
+
+    #[test]
+    fn test_blame_porcelain() {
+        let config = integration_test_utils::make_config_from_args(&[
+            "--blame-format",
+            "{author:<12} {commit:<8}",
+            "--blame-boundary-style",
+            "bold yellow",
+            "--blame-uncommitted-style",
+            "red",
+        ]);
+        let output = integration_test_utils::run_delta(GIT_BLAME_PORCELAIN, &config);
+        assert!(output.contains("\x1b[31mNot Committed"));
+        assert!(output.contains("\x1b[1;33m ^7a26f0f"));
+        let output = crate::ansi::strip_ansi_codes(&output);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], "Ann          ^7a26f0f│  1 │ fn main() {");
+        assert_eq!(lines[1], "Dan Davison  30a23d7f│  2 │     let x = 1;");
+        assert_eq!(lines[2], "Ann          ^7a26f0f│  3 │ }");
+        assert_eq!(lines[3], "Not Committed Yet 00000000│  4 │ // todo");
+    }
+
+    #[test]
+    fn test_blame_porcelain_is_not_blame_line() {
+        let config = integration_test_utils::make_config_from_args(&[]);
+        let input = "\
+author Ann
+\tcode
+";
+        let output = integration_test_utils::run_delta(input, &config);
+        assert_eq!(crate::ansi::strip_ansi_codes(&output), input);
+    }
+
+    #[test]
+    fn test_blame_porcelain_summary() {
+        let config =
+            integration_test_utils::make_config_from_args(&["--blame-format", "{summary:<14}"]);
+        let output = integration_test_utils::run_delta(GIT_BLAME_PORCELAIN, &config);
+        let output = crate::ansi::strip_ansi_codes(&output);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "Initial commit│  1 │ fn main() {");
+        assert_eq!(lines[1], "Add x         │  2 │     let x = 1;");
+    }
+
+    #[test]
+    fn test_blame_porcelain_unknown_record() {
+        let config = integration_test_utils::make_config_from_args(&["--blame-format", "{author}"]);
+        let input = "\
+7a26f0f770d0a7b442365ca5de7e4d3d1df58d0e 1 1 1
+author Ann
+author-time 1629681619
+author-tz -0700
+unknown record
+filename src/main.rs
+\tfn main() {
+";
+        let output = integration_test_utils::run_delta(input, &config);
+        let output = crate::ansi::strip_ansi_codes(&output);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "unknown record");
+        assert_eq!(lines[1], "Ann            │  1 │ fn main() {");
+    }
+
+    const GIT_BLAME_PORCELAIN: &str = "\
+7a26f0f770d0a7b442365ca5de7e4d3d1df58d0e 1 1 1
+author Ann
+author-mail <ann@example.com>
+author-time 1629681619
+author-tz -0700
+committer Ann
+committer-mail <ann@example.com>
+committer-time 1629681619
+committer-tz -0700
+summary Initial commit
+boundary
+filename src/main.rs
+\tfn main() {
+30a23d7fd33b1b8601509dea77fa2a96f78e5c8e 2 2 1
+author Dan Davison
+author-mail <dandavison7@gmail.com>
+author-time 1629681700
+author-tz -0700
+committer Dan Davison
+committer-mail <dandavison7@gmail.com>
+committer-time 1629681700
+committer-tz -0700
+summary Add x
+previous 7a26f0f770d0a7b442365ca5de7e4d3d1df58d0e src/main.rs
+filename src/main.rs
+\t    let x = 1;
+7a26f0f770d0a7b442365ca5de7e4d3d1df58d0e 3 3 1
+filename src/main.rs
+\t}
+0000000000000000000000000000000000000000 4 4 1
+author Not Committed Yet
+author-mail <not.committed.yet>
+author-time 1629681800
+author-tz +0000
+committer Not Committed Yet
+committer-mail <not.committed.yet>
+committer-time 1629681800
+committer-tz +0000
+summary Version of src/main.rs from src/main.rs
+previous 30a23d7fd33b1b8601509dea77fa2a96f78e5c8e src/main.rs
+filename src/main.rs
+\t// todo
+";
+
     fn hashmap_items(hashmap: &HashMap<String, String>) -> Vec<(&str, &str)> {
         hashmap
             .iter()
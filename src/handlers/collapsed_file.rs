@@ -0,0 +1,105 @@
+use crate::delta::{State, StateMachine};
+
+/// The text starting the line which replaces the hunks of a collapsed file.
+pub const COLLAPSED_FILE_MARKER: &str = "…";
+
+impl<'a> StateMachine<'a> {
+    /// Return true if the hunks of the current file are to be replaced by a summary line, see
+    /// --collapse-files-over and --collapse-file-glob. This is called with the counts of the
+    /// changes to the file known, once its header has been handled.
+    pub fn should_collapse_file(&self) -> bool {
+        let n_lines = self.file_stats.insertions + self.file_stats.deletions;
+        if self.config.collapse_files_over > 0 && n_lines > self.config.collapse_files_over {
+            return true;
+        }
+        self.current_file_matches_globs(&self.config.collapse_file_globs)
+    }
+
+    /// Write the line summarizing the changes to a collapsed file.
+    pub fn write_collapsed_file_summary(&mut self) -> std::io::Result<()> {
+        self.painter.paint_buffered_minus_and_plus_lines();
+        self.painter.emit()?;
+        // The lines following the skipped hunks are handled as lines outside of a diff.
+        self.state = State::Unknown;
+        self.diff_summary.files += 1;
+        self.diff_summary.insertions += self.file_stats.insertions;
+        self.diff_summary.deletions += self.file_stats.deletions;
+        let summary =
+            format_collapsed_file_summary(self.file_stats.insertions, self.file_stats.deletions);
+        writeln!(
+            self.painter.writer,
+            "{}",
+            self.config.collapsed_file_style.paint(summary)
+        )
+    }
+}
+
+// E.g. "… 842 lines (+512 −330) collapsed".
+fn format_collapsed_file_summary(insertions: usize, deletions: usize) -> String {
+    let n_lines = insertions + deletions;
+    format!(
+        "{} {} {} (+{} \u{2212}{}) collapsed",
+        COLLAPSED_FILE_MARKER,
+        n_lines,
+        if n_lines == 1 { "line" } else { "lines" },
+        insertions,
+        deletions
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::fixtures::GIT_LOG_THREE_COMMITS;
+    use crate::tests::integration_test_utils::{make_config_from_args, DeltaTest};
+
+    #[test]
+    fn test_format_collapsed_file_summary() {
+        assert_eq!(
+            format_collapsed_file_summary(512, 330),
+            "… 842 lines (+512 −330) collapsed"
+        );
+        assert_eq!(
+            format_collapsed_file_summary(0, 1),
+            "… 1 line (+0 −1) collapsed"
+        );
+    }
+
+    #[test]
+    fn test_collapse_files() {
+        let output = DeltaTest::with_args(&[
+            "--collapse-files-over",
+            "3",
+            "--collapse-file-glob",
+            "*.lock",
+        ])
+        .with_input(GIT_LOG_THREE_COMMITS)
+        // The headers of collapsed files are followed by the summary line instead of the hunks.
+        .expect_contains("\nCargo.lock\n")
+        .expect_contains("\n… 4 lines (+2 −2) collapsed\n")
+        .expect_contains("\nadded: src/new.rs\n")
+        .expect_contains("\n… 4 lines (+4 −0) collapsed\n\ncommit 8d8ba7c")
+        // Other files are not affected.
+        .expect_contains("\nsrc/lib.rs\n")
+        .expect_contains("mod c;")
+        .output;
+        assert!(!output.contains("version"));
+        assert!(!output.contains("fn a()"));
+        assert_eq!(output.matches("collapsed").count(), 2);
+    }
+
+    #[test]
+    fn test_collapse_files_with_color_only() {
+        let output = DeltaTest::with_args(&["--collapse-file-glob", "*.lock", "--color-only"])
+            .with_input(GIT_LOG_THREE_COMMITS)
+            .output;
+        assert_eq!(output, GIT_LOG_THREE_COMMITS);
+    }
+
+    #[test]
+    fn test_collapsed_file_summary_is_matched_by_navigate_regex() {
+        let config = make_config_from_args(&["--collapse-files-over", "3", "--navigate"]);
+        let navigate_regex = regex::Regex::new(config.navigate_regex.as_ref().unwrap()).unwrap();
+        assert!(navigate_regex.is_match("… 4 lines (+2 −2) collapsed"));
+    }
+}
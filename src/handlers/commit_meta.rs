@@ -1,13 +1,38 @@
 use std::borrow::Cow;
 
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::diff_header_hg;
+use super::diff_header_jj::{self, Vcs};
 use super::draw;
-use crate::delta::{State, StateMachine};
+use crate::delta::{self, Source, State, StateMachine};
 use crate::features;
+use crate::format::{self, Placeholder};
+use crate::style::DecorationStyle;
+
+lazy_static! {
+    // Lines following the commit line in `git log`, `git show` and `jj show` output.
+    static ref COMMIT_META_FIELD_REGEX: Regex = Regex::new(
+        r"^(Merge|Author|AuthorDate|Commit|CommitDate|Date|Committer|Change ID):\s"
+    )
+    .unwrap();
+    static ref COMMIT_FORMAT_PLACEHOLDER_REGEX: Regex =
+        format::make_placeholder_regex(&["label", "hash", "author", "date"]);
+}
 
 impl<'a> StateMachine<'a> {
     #[inline]
     fn test_commit_meta_header_line(&self) -> bool {
-        self.config.commit_regex.is_match(&self.line)
+        self.is_commit_line(&self.line)
+    }
+
+    pub fn is_commit_line(&self, line: &str) -> bool {
+        self.config.commit_regex.is_match(line)
+            || !self.config.commit_regex_exclusive && line.starts_with("commit ")
+            || diff_header_jj::is_jj_commit_line(line)
+                && diff_header_jj::vcs(self.config) == Vcs::Jj
+            || self.source == Source::Mercurial && diff_header_hg::is_hg_changeset_line(line)
     }
 
     pub fn handle_commit_meta_header_line(&mut self) -> std::io::Result<bool> {
@@ -15,9 +40,17 @@ impl<'a> StateMachine<'a> {
             return Ok(false);
         }
         let mut handled_line = false;
+        self.write_diff_summary()?;
         self.painter.paint_buffered_minus_and_plus_lines();
+        // The header of a file diff without hunks, e.g. a mode change, is still pending.
+        self.handle_pending_line_with_diff_name()?;
         self.state = State::CommitMeta;
-        if self.should_handle() {
+        self.painter.file_path_reminder = None;
+        if !diff_header_jj::is_jj_change_id_line(&self.line) {
+            self.current_commit =
+                features::hyperlinks::get_commit_from_commit_line(&self.line, self.config);
+        }
+        if self.should_handle() || self.config.commit_format.is_some() {
             self.painter.emit()?;
             self._handle_commit_meta_header_line()?;
             handled_line = true
@@ -25,10 +58,103 @@ impl<'a> StateMachine<'a> {
         Ok(handled_line)
     }
 
+    /// When the commit line is boxed or reformatted by --commit-format, the author and date lines
+    /// following it are collected so that they are drawn in the same box, or their values used.
+    pub fn handle_commit_meta_field_line(&mut self) -> std::io::Result<bool> {
+        if self.commit_meta_lines.is_empty() {
+            return Ok(false);
+        }
+        if COMMIT_META_FIELD_REGEX.is_match(&self.line)
+            || self.source == Source::Mercurial
+                && diff_header_hg::is_hg_changeset_field_line(&self.line)
+        {
+            self.commit_meta_lines
+                .push((self.line.clone(), self.raw_line.clone()));
+            return Ok(true);
+        }
+        self.write_commit_meta_lines()?;
+        Ok(false)
+    }
+
+    /// Write the collected commit line and the lines following it, if any.
+    pub fn write_commit_meta_lines(&mut self) -> std::io::Result<()> {
+        if self.commit_meta_lines.is_empty() {
+            return Ok(());
+        }
+        let mut lines: Vec<(String, String)> = self.commit_meta_lines.drain(..).collect();
+        if let Some(commit_format) = &self.config.commit_format {
+            let commit_line =
+                format_commit_line(commit_format, &lines, self.current_commit.as_deref());
+            lines[0] = (commit_line.clone(), commit_line);
+            if self.config.hide_commit_metadata {
+                let field_lines = lines.split_off(1);
+                lines.extend(
+                    field_lines
+                        .into_iter()
+                        .filter(|(line, _)| get_field_value(line, AUTHOR_AND_DATE).is_none()),
+                );
+            }
+        }
+        if self.config.hyperlinks {
+            let (line, raw_line) = &lines[0];
+            lines[0] = (
+                features::hyperlinks::format_commit_line_with_osc8_commit_hyperlink(
+                    line,
+                    self.config,
+                )
+                .into_owned(),
+                features::hyperlinks::format_commit_line_with_osc8_commit_hyperlink(
+                    raw_line,
+                    self.config,
+                )
+                .into_owned(),
+            );
+        }
+
+        let (mut draw_fn, pad, decoration_ansi_term_style) =
+            draw::get_draw_function(self.config.commit_style.decoration_style);
+        let pad = if pad { " " } else { "" };
+        // Only a box encloses the lines following the commit line.
+        let n_decorated_lines = if is_box(self.config.commit_style.decoration_style) {
+            lines.len()
+        } else {
+            1
+        };
+        let undecorated_lines = lines.split_off(n_decorated_lines);
+        let (lines, raw_lines): (Vec<String>, Vec<String>) = lines
+            .into_iter()
+            .map(|(line, raw_line)| (line + pad, raw_line + pad))
+            .unzip();
+        self.painter.emit()?;
+        draw_fn(
+            self.painter.writer,
+            &lines.join("\n"),
+            &raw_lines.join("\n"),
+            "",
+            &self.config.decorations_width.get(),
+            self.config.commit_style,
+            decoration_ansi_term_style,
+        )?;
+        for (_, raw_line) in undecorated_lines {
+            writeln!(
+                self.painter.writer,
+                "{}",
+                delta::format_raw_line(&raw_line, self.config)
+            )?;
+        }
+        Ok(())
+    }
+
     fn _handle_commit_meta_header_line(&mut self) -> std::io::Result<()> {
         if self.config.commit_style.is_omitted {
             return Ok(());
         }
+        if self.config.commit_format.is_some() || is_box(self.config.commit_style.decoration_style)
+        {
+            self.commit_meta_lines
+                .push((self.line.clone(), self.raw_line.clone()));
+            return Ok(());
+        }
         let (mut draw_fn, pad, decoration_ansi_term_style) =
             draw::get_draw_function(self.config.commit_style.decoration_style);
         let (formatted_line, formatted_raw_line) = if self.config.hyperlinks {
@@ -51,10 +177,77 @@ impl<'a> StateMachine<'a> {
             &format!("{}{}", formatted_line, if pad { " " } else { "" }),
             &format!("{}{}", formatted_raw_line, if pad { " " } else { "" }),
             "",
-            &self.config.decorations_width,
+            &self.config.decorations_width.get(),
             self.config.commit_style,
             decoration_ansi_term_style,
         )?;
         Ok(())
     }
 }
+
+const AUTHOR: &[&str] = &["Author", "user"];
+const DATE: &[&str] = &["Date", "AuthorDate", "date"];
+const AUTHOR_AND_DATE: &[&str] = &["Author", "Date", "AuthorDate", "user", "date"];
+
+fn is_box(decoration_style: DecorationStyle) -> bool {
+    matches!(
+        decoration_style,
+        DecorationStyle::Box(_)
+            | DecorationStyle::BoxWithUnderline(_)
+            | DecorationStyle::BoxWithOverline(_)
+            | DecorationStyle::BoxWithUnderOverline(_)
+    )
+}
+
+// Return the value of a line such as "Author: Name <email>" if the field is one of `names`.
+fn get_field_value<'b>(line: &'b str, names: &[&str]) -> Option<&'b str> {
+    line.split_once(':')
+        .filter(|(name, _)| names.contains(name))
+        .map(|(_, value)| value.trim())
+}
+
+/// Format the commit line according to --commit-format. `lines` are the commit line followed by
+/// the author and date lines, if present; missing values are replaced by the empty string.
+fn format_commit_line(
+    commit_format: &str,
+    lines: &[(String, String)],
+    commit: Option<&str>,
+) -> String {
+    let commit_line = lines[0].0.as_str();
+    // The text preceding the hash, e.g. "commit".
+    let label = match commit.and_then(|commit| commit_line.find(commit)) {
+        Some(i) => commit_line[..i].trim_end(),
+        None => commit_line.split_whitespace().next().unwrap_or(""),
+    };
+    let field = |names| {
+        lines[1..]
+            .iter()
+            .find_map(|(line, _)| get_field_value(line, names))
+            .unwrap_or("")
+    };
+    let format_data =
+        format::parse_line_number_format(commit_format, &COMMIT_FORMAT_PLACEHOLDER_REGEX, false);
+    let mut formatted = String::new();
+    let mut suffix = "";
+    for placeholder in &format_data {
+        formatted.push_str(&placeholder.prefix);
+        let value = match placeholder.placeholder {
+            Some(Placeholder::Str("label")) => Some(label),
+            Some(Placeholder::Str("hash")) => Some(commit.unwrap_or("")),
+            Some(Placeholder::Str("author")) => Some(field(AUTHOR)),
+            Some(Placeholder::Str("date")) => Some(field(DATE)),
+            _ => None,
+        };
+        if let Some(value) = value {
+            formatted.push_str(&format::pad(
+                value,
+                placeholder.width.unwrap_or(0),
+                placeholder.alignment_spec.unwrap_or(format::Align::Left),
+                placeholder.precision,
+            ));
+        }
+        suffix = placeholder.suffix.as_str();
+    }
+    formatted.push_str(suffix);
+    formatted
+}
@@ -1,13 +1,37 @@
 use std::borrow::Cow;
 use std::path::Path;
 
+use lazy_static::lazy_static;
+use regex::Regex;
 use unicode_segmentation::UnicodeSegmentation;
 
+use super::diff_header_hg;
+use super::diff_header_svn;
 use super::draw;
 use crate::config::Config;
 use crate::delta::{DiffType, Source, State, StateMachine};
+use crate::format::{self, Placeholder};
 use crate::paint::Painter;
-use crate::{features, utils};
+use crate::style::Style;
+use crate::{ansi, cli, features, utils};
+
+lazy_static! {
+    static ref FILE_RENAMED_FORMAT_PLACEHOLDER_REGEX: Regex =
+        format::make_placeholder_regex(&["label", "old", "new", "similarity"]);
+    static ref FILE_LABEL_PLACEHOLDER_REGEX: Regex = format::make_placeholder_regex(&[
+        "path",
+        "insertions",
+        "deletions",
+        "hunks",
+        "old-revision",
+        "new-revision",
+    ]);
+    static ref FILE_LABEL_STATS_PLACEHOLDER_REGEX: Regex =
+        format::make_placeholder_regex(&["insertions", "deletions", "hunks"]);
+    // The "index" line, with the mode of the file, if any.
+    static ref INDEX_LINE_WITH_MODE_REGEX: Regex =
+        Regex::new(r"^index ([0-9a-f]+(?:,[0-9a-f]+)*)\.\.([0-9a-f]+)(?: ([0-7]+))?$").unwrap();
+}
 
 // https://git-scm.com/docs/git-config#Documentation/git-config.txt-diffmnemonicPrefix
 const DIFF_PREFIXES: [&str; 6] = ["a/", "b/", "c/", "i/", "o/", "w/"];
@@ -22,6 +46,170 @@ pub enum FileEvent {
     NoEvent,
 }
 
+/// The counts of the changes to a file, displayed by the {insertions}, {deletions} and {hunks}
+/// placeholders of the file labels.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FileStats {
+    pub insertions: usize,
+    pub deletions: usize,
+    pub hunks: usize,
+}
+
+/// The revisions of the old and new file, displayed by the {old-revision} and {new-revision}
+/// placeholders of the file labels. Only `svn diff` output gives them.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FileRevisions {
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+impl FileStats {
+    /// Count the added and removed lines and the hunks in the lines of the diff of a file, as
+    /// returned by `parse_hunk_lines`.
+    pub fn from_hunk_lines(hunk_lines: &[HunkLine]) -> Self {
+        let mut file_stats = FileStats::default();
+        for hunk_line in hunk_lines {
+            match hunk_line {
+                HunkLine::Header => file_stats.hunks += 1,
+                HunkLine::Minus => file_stats.deletions += 1,
+                HunkLine::Plus => file_stats.insertions += 1,
+                HunkLine::Zero | HunkLine::Outside => {}
+            }
+        }
+        file_stats
+    }
+}
+
+/// The role of a line of the diff of a file with respect to its hunks.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HunkLine {
+    Header,
+    Minus,
+    Plus,
+    // Context lines, and lines such as "\ No newline at end of file".
+    Zero,
+    Outside,
+}
+
+/// Return the role of each of the lines of the diff of a file with respect to its hunks.
+pub fn parse_hunk_lines(lines: &[Vec<u8>]) -> Vec<HunkLine> {
+    // The number of parents of a combined diff hunk, and 0 outside of hunks.
+    let mut n_parents = 0;
+    lines
+        .iter()
+        .map(|line| {
+            let line = ansi::strip_ansi_codes(&String::from_utf8_lossy(line));
+            if line.starts_with("@@") {
+                n_parents = line.chars().take_while(|c| *c == '@').count() - 1;
+                return HunkLine::Header;
+            }
+            // Empty lines do not end the hunk, but are not considered part of it.
+            if n_parents == 0 || line.is_empty() {
+                return HunkLine::Outside;
+            }
+            // A "~" line ends a line of `git diff --word-diff=porcelain` output.
+            if line.starts_with('\\') || line == "~" {
+                return HunkLine::Zero;
+            }
+            match line.get(..n_parents) {
+                Some(prefix) if prefix.chars().all(|c| matches!(c, ' ' | '+' | '-')) => {
+                    if prefix.contains('-') {
+                        HunkLine::Minus
+                    } else if prefix.contains('+') {
+                        HunkLine::Plus
+                    } else {
+                        HunkLine::Zero
+                    }
+                }
+                // The hunk has ended.
+                _ => {
+                    n_parents = 0;
+                    HunkLine::Outside
+                }
+            }
+        })
+        .collect()
+}
+
+/// Finds the lines that start the diff of a file, in input that is read a file at a time.
+#[derive(Debug, Default)]
+pub struct FileBoundaries {
+    // Whether the lines since the start of the file are its header, e.g. those following a
+    // "diff" or "Index:" line.
+    in_header: bool,
+    // The numbers of lines of each parent, and then of the result, remaining in the hunk.
+    hunk_lengths_remaining: Vec<usize>,
+}
+
+impl FileBoundaries {
+    /// Return true if `line` starts the diff of a file: a "diff" line, an `svn diff` "Index:"
+    /// line, or a "---" line that does not follow one of those and is not in a hunk.
+    pub fn starts_file(&mut self, line: &str) -> bool {
+        if let Some(parsed_hunk_header) = super::hunk_header::parse_hunk_header(line) {
+            self.in_header = false;
+            self.hunk_lengths_remaining = parsed_hunk_header
+                .line_numbers_and_hunk_lengths
+                .iter()
+                .map(|(_, hunk_length)| *hunk_length)
+                .collect();
+            return false;
+        }
+        if self.is_hunk_line(line) {
+            return false;
+        }
+        if line.starts_with("diff ") || diff_header_svn::is_svn_index_line(line) {
+            self.in_header = true;
+            true
+        } else if line.starts_with("--- ") {
+            let starts_file = !self.in_header;
+            self.in_header = true;
+            starts_file
+        } else {
+            false
+        }
+    }
+
+    // Return true if `line` is in the current hunk, counting it against the remaining lengths.
+    fn is_hunk_line(&mut self, line: &str) -> bool {
+        if self.hunk_lengths_remaining.iter().all(|n| *n == 0) {
+            return false;
+        }
+        let n_parents = self.hunk_lengths_remaining.len() - 1;
+        let prefix = if line.is_empty() {
+            // A context line whose trailing space has been removed.
+            " ".repeat(n_parents)
+        } else if line.starts_with('\\') || line == "~" {
+            return true;
+        } else {
+            match line.get(..n_parents) {
+                Some(prefix) if prefix.chars().all(|c| matches!(c, ' ' | '+' | '-')) => {
+                    prefix.to_string()
+                }
+                _ => {
+                    self.hunk_lengths_remaining.clear();
+                    return false;
+                }
+            }
+        };
+        for (remaining, c) in self.hunk_lengths_remaining.iter_mut().zip(prefix.chars()) {
+            if c != '+' {
+                *remaining = remaining.saturating_sub(1);
+            }
+        }
+        if !prefix.contains('-') {
+            let remaining = self.hunk_lengths_remaining.last_mut().unwrap();
+            *remaining = remaining.saturating_sub(1);
+        }
+        true
+    }
+}
+
+/// Return true if the file label uses the {insertions}, {deletions} or {hunks} placeholders, in
+/// which case the diff of each file is read before its header is written.
+pub fn label_uses_file_stats(label: &str) -> bool {
+    FILE_LABEL_STATS_PLACEHOLDER_REGEX.is_match(label)
+}
+
 impl<'a> StateMachine<'a> {
     /// Check for the old mode|new mode lines and cache their info for later use.
     pub fn handle_diff_header_mode_line(&mut self) -> std::io::Result<bool> {
@@ -30,27 +218,37 @@ impl<'a> StateMachine<'a> {
             self.state = State::DiffHeader(DiffType::Unified);
             if self.should_handle() && !self.config.color_only {
                 self.mode_info = line_suf.to_string();
+                self.keep_mode_line_maybe()?;
                 handled_line = true;
             }
         } else if let Some(line_suf) = self.line.strip_prefix("new mode ") {
             self.state = State::DiffHeader(DiffType::Unified);
             if self.should_handle() && !self.config.color_only && !self.mode_info.is_empty() {
-                self.mode_info = match (self.mode_info.as_str(), line_suf) {
-                    // 100755 for executable and 100644 for non-executable are the only file modes Git records.
-                    // https://medium.com/@tahteche/how-git-treats-changes-in-file-permissions-f71874ca239d
-                    ("100644", "100755") => "mode +x".to_string(),
-                    ("100755", "100644") => "mode -x".to_string(),
-                    _ => format!(
-                        "mode {} {} {}",
-                        self.mode_info, self.config.right_arrow, line_suf
-                    ),
-                };
+                self.mode_info = format_mode_change(&self.mode_info, line_suf, self.config);
+                self.keep_mode_line_maybe()?;
                 handled_line = true;
             }
         }
         Ok(handled_line)
     }
 
+    /// Check for the "similarity index" line of a renamed or copied file and cache its value for
+    /// the file header. The line itself is left to be handled as other diff header lines.
+    pub fn handle_diff_header_similarity_line(&mut self) -> std::io::Result<bool> {
+        if let Some(similarity) = self.line.strip_prefix("similarity index ") {
+            self.similarity_index = Some(similarity.trim_end_matches('%').to_string());
+        }
+        Ok(false)
+    }
+
+    /// Emit a mode line unchanged if it is not to be replaced by the mode change summary.
+    fn keep_mode_line_maybe(&mut self) -> std::io::Result<()> {
+        if self.config.keep_mode_lines {
+            self.emit_line_unchanged()?;
+        }
+        Ok(())
+    }
+
     fn should_write_generic_diff_header_header_line(&mut self) -> std::io::Result<bool> {
         // In color_only mode, raw_line's structure shouldn't be changed.
         // So it needs to avoid fn _handle_diff_header_header_line
@@ -84,13 +282,22 @@ impl<'a> StateMachine<'a> {
             return Ok(false);
         }
 
-        let (path_or_mode, file_event) =
-            parse_diff_header_line(&self.line, self.source == Source::GitDiff);
+        let (path_or_mode, file_event) = parse_diff_header_line(
+            &self.line,
+            matches!(self.source, Source::GitDiff | Source::Mercurial),
+        );
 
         self.minus_file = utils::path::relativize_path_maybe(&path_or_mode, self.config)
             .map(|p| p.to_string_lossy().to_owned().to_string())
             .unwrap_or(path_or_mode);
         self.minus_file_event = file_event;
+        if self.source == Source::Svn {
+            self.file_revisions.old =
+                diff_header_svn::get_svn_revision(&self.line).map(str::to_string);
+            if diff_header_svn::is_svn_nonexistent_file(&self.line) {
+                self.minus_file = "/dev/null".to_string();
+            }
+        }
 
         if self.source == Source::DiffUnified {
             self.state = State::DiffHeader(DiffType::Unified);
@@ -121,13 +328,25 @@ impl<'a> StateMachine<'a> {
             return Ok(false);
         }
         let mut handled_line = false;
-        let (path_or_mode, file_event) =
-            parse_diff_header_line(&self.line, self.source == Source::GitDiff);
+        let (path_or_mode, file_event) = parse_diff_header_line(
+            &self.line,
+            matches!(self.source, Source::GitDiff | Source::Mercurial),
+        );
 
         self.plus_file = utils::path::relativize_path_maybe(&path_or_mode, self.config)
             .map(|p| p.to_string_lossy().to_owned().to_string())
             .unwrap_or(path_or_mode);
         self.plus_file_event = file_event;
+        if self.source == Source::Svn {
+            self.file_revisions.new =
+                diff_header_svn::get_svn_revision(&self.line).map(str::to_string);
+            if diff_header_svn::is_svn_nonexistent_file(&self.line) {
+                self.plus_file = "/dev/null".to_string();
+            }
+        }
+        if let Some(line_numbers_data) = self.painter.line_numbers_data.as_mut() {
+            line_numbers_data.initialize_file();
+        }
         self.painter
             .set_syntax(get_file_extension_from_diff_header_line_file_path(
                 &self.plus_file,
@@ -135,6 +354,7 @@ impl<'a> StateMachine<'a> {
         self.current_file_pair = Some((self.minus_file.clone(), self.plus_file.clone()));
 
         self.painter.paint_buffered_minus_and_plus_lines();
+        self.painter.raw_file = self.current_file_matches_globs(&self.config.raw_file_globs);
         if self.should_write_generic_diff_header_header_line()? {
             handled_line = true;
         } else if self.should_handle()
@@ -160,8 +380,10 @@ impl<'a> StateMachine<'a> {
             return Ok(false);
         }
         let mut handled_line = false;
-        let (_mode_info, file_event) =
-            parse_diff_header_line(&self.line, self.source == Source::GitDiff);
+        let (mode, file_event) = parse_diff_header_line(
+            &self.line,
+            matches!(self.source, Source::GitDiff | Source::Mercurial),
+        );
         let name = get_repeated_file_path_from_diff_line(&self.diff_line)
             .unwrap_or_else(|| "".to_string());
         match file_event {
@@ -178,6 +400,15 @@ impl<'a> StateMachine<'a> {
                 self.minus_file_event = FileEvent::Change;
                 self.plus_file_event = FileEvent::Change;
                 self.current_file_pair = Some((self.minus_file.clone(), self.plus_file.clone()));
+                if self.should_handle() && !self.config.color_only {
+                    if let Some(true) = is_executable(&mode) {
+                        self.mode_info = format!("new file mode: {} (+x)", format_mode(&mode));
+                    }
+                    if self.config.keep_mode_lines {
+                        self.emit_line_unchanged()?;
+                        return Ok(true);
+                    }
+                }
             }
             _ => (),
         }
@@ -192,13 +423,17 @@ impl<'a> StateMachine<'a> {
     }
 
     /// Construct file change line from minus and plus file and write with DiffHeader styling.
-    fn _handle_diff_header_header_line(&mut self, comparing: bool) -> std::io::Result<()> {
+    pub fn _handle_diff_header_header_line(&mut self, comparing: bool) -> std::io::Result<()> {
         let line = get_file_change_description_from_file_paths(
             &self.minus_file,
             &self.plus_file,
             comparing,
             &self.minus_file_event,
             &self.plus_file_event,
+            self.current_commit.as_deref(),
+            self.similarity_index.as_deref(),
+            &self.file_stats,
+            &self.file_revisions,
             self.config,
         );
         // FIXME: no support for 'raw'
@@ -221,30 +456,46 @@ impl<'a> StateMachine<'a> {
             return Ok(());
         }
 
-        if !self.mode_info.is_empty() {
-            let format_label = |label: &str| {
-                if !label.is_empty() {
-                    format!("{} ", label)
-                } else {
-                    "".to_string()
+        let name = get_repeated_file_path_from_diff_line(&self.diff_line).unwrap_or_default();
+        let is_file_header_pending = !self.config.color_only
+            && self.should_handle()
+            && self.handled_diff_header_header_line_file_pair != self.current_file_pair;
+        // E.g. an empty file was added: its header has not been written, and its label is not the
+        // label for modified files.
+        let is_file_operation_pending = is_file_header_pending
+            && matches!(&self.current_file_pair, Some((minus, plus)) if *minus == name || *plus == name);
+
+        if !self.mode_info.is_empty() && !is_file_operation_pending {
+            let format_file = |file| {
+                let formatted_file =
+                    if let Some(regex_replacement) = &self.config.file_regex_replacement {
+                        regex_replacement.execute(file)
+                    } else {
+                        Cow::from(file)
+                    };
+                match (
+                    self.config.hyperlinks,
+                    utils::path::absolute_path(file, self.config),
+                ) {
+                    (true, Some(absolute_path)) => {
+                        features::hyperlinks::format_osc8_file_hyperlink(
+                            absolute_path,
+                            None,
+                            None,
+                            self.current_commit.as_deref(),
+                            &formatted_file,
+                            self.config,
+                        )
+                    }
+                    _ => formatted_file,
                 }
             };
-            let format_file = |file| match (
-                self.config.hyperlinks,
-                utils::path::absolute_path(file, self.config),
-            ) {
-                (true, Some(absolute_path)) => features::hyperlinks::format_osc8_file_hyperlink(
-                    absolute_path,
-                    None,
-                    file,
-                    self.config,
-                ),
-                _ => Cow::from(file),
-            };
-            let label = format_label(&self.config.file_modified_label);
-            let name = get_repeated_file_path_from_diff_line(&self.diff_line)
-                .unwrap_or_else(|| "".to_string());
-            let line = format!("{}{}", label, format_file(&name));
+            let line = format_file_label(
+                &self.config.file_modified_label,
+                &format_file(&name),
+                &self.file_stats,
+                &self.file_revisions,
+            );
             write_generic_diff_header_header_line(
                 &line,
                 &line,
@@ -252,10 +503,7 @@ impl<'a> StateMachine<'a> {
                 &mut self.mode_info,
                 self.config,
             )
-        } else if !self.config.color_only
-            && self.should_handle()
-            && self.handled_diff_header_header_line_file_pair != self.current_file_pair
-        {
+        } else if is_file_header_pending {
             self._handle_diff_header_header_line(self.source == Source::DiffUnified)?;
             self.handled_diff_header_header_line_file_pair = self.current_file_pair.clone();
             Ok(())
@@ -263,6 +511,22 @@ impl<'a> StateMachine<'a> {
             Ok(())
         }
     }
+    /// Return true if the path of the current file, the old path if the file was removed, or
+    /// its name matches one of `globs`.
+    pub fn current_file_matches_globs(&self, globs: &[Regex]) -> bool {
+        let path = if self.plus_file == "/dev/null" {
+            &self.minus_file
+        } else {
+            &self.plus_file
+        };
+        let name = Path::new(path)
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy();
+        globs
+            .iter()
+            .any(|glob| glob.is_match(path) || glob.is_match(&name))
+    }
 }
 
 /// Write `line` with DiffHeader styling.
@@ -272,12 +536,35 @@ pub fn write_generic_diff_header_header_line(
     painter: &mut Painter,
     mode_info: &mut String,
     config: &Config,
+) -> std::io::Result<()> {
+    write_diff_header_header_line_in_style(
+        line,
+        raw_line,
+        painter,
+        mode_info,
+        config.file_style,
+        config,
+    )
+}
+
+/// Write a file header line with the decoration of file-style, and its text in `style`.
+pub fn write_diff_header_header_line_in_style(
+    line: &str,
+    raw_line: &str,
+    painter: &mut Painter,
+    mode_info: &mut String,
+    style: Style,
+    config: &Config,
 ) -> std::io::Result<()> {
     // If file_style is "omit", we'll skip the process and print nothing.
     // However in the case of color_only mode,
     // we won't skip because we can't change raw_line structure.
     if config.file_style.is_omitted && !config.color_only {
-        return Ok(());
+        return write_mode_change_line(painter, mode_info, config);
+    }
+    if !config.color_only {
+        painter.file_path_reminder = Some(line.to_string());
+        painter.lines_since_file_path_reminder = 0;
     }
     let (mut draw_fn, pad, decoration_ansi_term_style) =
         draw::get_draw_function(config.file_style.decoration_style);
@@ -285,21 +572,120 @@ pub fn write_generic_diff_header_header_line(
         // Maintain 1-1 correspondence between input and output lines.
         writeln!(painter.writer)?;
     }
+    // A box takes the padding space and its right border.
+    let line = truncate_header_line(line, if pad { 2 } else { 0 }, config);
     draw_fn(
         painter.writer,
         &format!("{}{}", line, if pad { " " } else { "" }),
         &format!("{}{}", raw_line, if pad { " " } else { "" }),
-        mode_info,
-        &config.decorations_width,
-        config.file_style,
+        "",
+        &config.decorations_width.get(),
+        style,
         decoration_ansi_term_style,
     )?;
+    write_mode_change_line(painter, mode_info, config)
+}
+
+/// Truncate a file header line which is too wide for the terminal on the side set by
+/// --truncation-side, so that e.g. both ends of a long path remain visible. Headers are not
+/// truncated on the right, the default, since this is where they have always extended beyond
+/// the terminal width.
+fn truncate_header_line<'a>(
+    line: &'a str,
+    decoration_width: usize,
+    config: &Config,
+) -> Cow<'a, str> {
+    let wrap_config = if config.side_by_side {
+        &config.side_by_side_wrap_config
+    } else {
+        &config.wrap_config
+    };
+    if wrap_config.truncation_side == ansi::TruncationSide::Right || config.color_only {
+        return Cow::from(line);
+    }
+    let width = match config.decorations_width.get() {
+        cli::Width::Fixed(width) => width,
+        cli::Width::Variable => config.available_terminal_width.get(),
+    };
+    ansi::truncate_str_on_side(
+        line,
+        width.saturating_sub(decoration_width),
+        // The line is painted in file-style once truncated.
+        &ansi::strip_ansi_codes(&config.truncation_symbol),
+        wrap_config.truncation_side,
+    )
+}
+
+/// Write the mode change summary following the file header, if there is one.
+fn write_mode_change_line(
+    painter: &mut Painter,
+    mode_info: &mut String,
+    config: &Config,
+) -> std::io::Result<()> {
     if !mode_info.is_empty() {
+        if !config.mode_change_style.is_omitted {
+            writeln!(
+                painter.writer,
+                "{}",
+                config.mode_change_style.paint(mode_info.as_str())
+            )?;
+        }
         mode_info.truncate(0);
     }
     Ok(())
 }
 
+/// Return a summary of a file mode change such as "mode changed: 644 → 755 (+x)".
+fn format_mode_change(old_mode: &str, new_mode: &str, config: &Config) -> String {
+    let executable_bit = match (is_executable(old_mode), is_executable(new_mode)) {
+        (Some(false), Some(true)) => " (+x)",
+        (Some(true), Some(false)) => " (-x)",
+        _ => "",
+    };
+    format!(
+        "mode changed: {} {} {}{}",
+        format_mode(old_mode),
+        config.right_arrow,
+        format_mode(new_mode),
+        executable_bit
+    )
+}
+
+// 100755 for executable and 100644 for non-executable are the only modes Git records for regular
+// files; symlinks (120000) and submodules (160000) have no executable bit.
+// https://medium.com/@tahteche/how-git-treats-changes-in-file-permissions-f71874ca239d
+fn is_executable(mode: &str) -> Option<bool> {
+    mode.strip_prefix("100")
+        .and_then(|permissions| u32::from_str_radix(permissions, 8).ok())
+        .map(|permissions| permissions & 0o111 != 0)
+}
+
+/// Return the permissions of a regular file's mode, e.g. "644" for "100644".
+fn format_mode(mode: &str) -> &str {
+    mode.strip_prefix("100").unwrap_or(mode)
+}
+
+// Paint the "index" line, e.g. "index 663a5d4..a59fe84 100644", or, in a combined diff,
+// "index 1111111,2222222..3333333".
+pub fn format_index_line(line: &str, config: &Config) -> Option<String> {
+    let captures = INDEX_LINE_WITH_MODE_REGEX.captures(line)?;
+    let mut painted = format!(
+        "{}{}{}{}",
+        config.index_line_style.paint("index "),
+        config.index_line_hash_style.paint(&captures[1]),
+        config.index_line_style.paint(".."),
+        config.index_line_hash_style.paint(&captures[2]),
+    );
+    if let Some(mode) = captures.get(3) {
+        painted.push_str(&format!(
+            "{}{}",
+            config.index_line_style.paint(" "),
+            config.index_line_mode_style.paint(mode.as_str())
+        ));
+    }
+    Some(painted)
+}
+
 #[allow(clippy::tabs_in_doc_comments)]
 /// Given input like
 /// "--- one.rs	2019-11-20 06:16:08.000000000 +0100"
@@ -328,7 +714,7 @@ pub fn get_extension(s: &str) -> Option<&str> {
         .or_else(|| path.file_name().and_then(|s| s.to_str()))
 }
 
-fn parse_diff_header_line(line: &str, git_diff_name: bool) -> (String, FileEvent) {
+pub fn parse_diff_header_line(line: &str, git_diff_name: bool) -> (String, FileEvent) {
     match line {
         line if line.starts_with("--- ") || line.starts_with("+++ ") => {
             let offset = 4;
@@ -336,16 +722,16 @@ fn parse_diff_header_line(line: &str, git_diff_name: bool) -> (String, FileEvent
             (file, FileEvent::Change)
         }
         line if line.starts_with("rename from ") => {
-            (line[12..].to_string(), FileEvent::Rename) // "rename from ".len()
+            (_parse_file_path(&line[12..], false), FileEvent::Rename) // "rename from ".len()
         }
         line if line.starts_with("rename to ") => {
-            (line[10..].to_string(), FileEvent::Rename) // "rename to ".len()
+            (_parse_file_path(&line[10..], false), FileEvent::Rename) // "rename to ".len()
         }
         line if line.starts_with("copy from ") => {
-            (line[10..].to_string(), FileEvent::Copy) // "copy from ".len()
+            (_parse_file_path(&line[10..], false), FileEvent::Copy) // "copy from ".len()
         }
         line if line.starts_with("copy to ") => {
-            (line[8..].to_string(), FileEvent::Copy) // "copy to ".len()
+            (_parse_file_path(&line[8..], false), FileEvent::Copy) // "copy to ".len()
         }
         line if line.starts_with("new file mode ") => {
             (line[14..].to_string(), FileEvent::Added) // "new file mode ".len()
@@ -357,9 +743,22 @@ fn parse_diff_header_line(line: &str, git_diff_name: bool) -> (String, FileEvent
     }
 }
 
+/// Given input like "Binary files a/logo.png and b/logo.png differ"
+/// return Some(("logo.png", "logo.png"))
+pub fn parse_binary_files_differ_line(line: &str, git_diff_name: bool) -> Option<(String, String)> {
+    let (minus_file, plus_file) = line
+        .strip_prefix("Binary files ")?
+        .strip_suffix(" differ")?
+        .split_once(" and ")?;
+    Some((
+        _parse_file_path(minus_file, git_diff_name),
+        _parse_file_path(plus_file, git_diff_name),
+    ))
+}
+
 /// Given input like "diff --git a/src/my file.rs b/src/my file.rs"
 /// return Some("src/my file.rs")
-fn get_repeated_file_path_from_diff_line(line: &str) -> Option<String> {
+pub fn get_repeated_file_path_from_diff_line(line: &str) -> Option<String> {
     if let Some(line) = line.strip_prefix("diff --git ") {
         let line: Vec<&str> = line.graphemes(true).collect();
         let midpoint = line.len() / 2;
@@ -382,87 +781,318 @@ fn _parse_file_path(s: &str, git_diff_name: bool) -> String {
     // index·d00491f..0cfbf08·100644␊
     // ---·a/a·b├──┤␊
     // +++·b/c·d├──┤␊
-    match s.strip_suffix('\t').unwrap_or(s) {
-        path if path == "/dev/null" => "/dev/null",
-        path if git_diff_name && DIFF_PREFIXES.iter().any(|s| path.starts_with(s)) => &path[2..],
-        path if git_diff_name => path,
+    let path = match s.strip_suffix('\t').unwrap_or(s) {
+        // Mercurial, whose paths have git's prefixes, appends a tab followed by a timestamp.
+        path if git_diff_name => diff_header_hg::strip_hg_timestamp(path),
         path => path.split('\t').next().unwrap_or(""),
+    };
+    let path = unquote_path(path).map_or(Cow::from(path), Cow::from);
+    match path.as_ref() {
+        "/dev/null" => "/dev/null",
+        path if git_diff_name && DIFF_PREFIXES.iter().any(|s| path.starts_with(s)) => &path[2..],
+        path => path,
     }
     .to_string()
 }
 
+/// Decode a path quoted by git, e.g. `"\346\227\245.txt"` for `日.txt`. With core.quotePath
+/// (the default), git quotes paths containing bytes outside printable ASCII, as well as paths
+/// containing double quotes, backslashes and control characters such as tabs. Return None if
+/// `path` is not quoted.
+pub fn unquote_path(path: &str) -> Option<String> {
+    let quoted = path.strip_prefix('"')?.strip_suffix('"')?;
+    let mut bytes = Vec::with_capacity(quoted.len());
+    let mut chars = quoted.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            let mut buf = [0; 4];
+            bytes.extend(c.encode_utf8(&mut buf).as_bytes());
+            continue;
+        }
+        let byte = match chars.next()? {
+            'a' => 0x07,
+            'b' => 0x08,
+            't' => b'\t',
+            'n' => b'\n',
+            'v' => 0x0b,
+            'f' => 0x0c,
+            'r' => b'\r',
+            c @ ('"' | '\\') => c as u8,
+            c @ '0'..='3' => {
+                let octal: String = [c, chars.next()?, chars.next()?].iter().collect();
+                u8::from_str_radix(&octal, 8).ok()?
+            }
+            _ => return None,
+        };
+        bytes.push(byte);
+    }
+    Some(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn get_file_change_description_from_file_paths(
     minus_file: &str,
     plus_file: &str,
     comparing: bool,
     minus_file_event: &FileEvent,
     plus_file_event: &FileEvent,
+    commit: Option<&str>,
+    similarity_index: Option<&str>,
+    file_stats: &FileStats,
+    file_revisions: &FileRevisions,
     config: &Config,
 ) -> String {
-    let format_label = |label: &str| {
-        if !label.is_empty() {
-            format!("{} ", label)
-        } else {
-            "".to_string()
-        }
-    };
     if comparing {
-        format!(
-            "{}{} {} {}",
-            format_label(&config.file_modified_label),
-            minus_file,
-            config.right_arrow,
-            plus_file
+        format_file_label(
+            &config.file_modified_label,
+            &format!("{} {} {}", minus_file, config.right_arrow, plus_file),
+            file_stats,
+            file_revisions,
         )
     } else {
-        let format_file = |file| {
-            let formatted_file = if let Some(regex_replacement) = &config.file_regex_replacement {
+        let format_file_text = |file| {
+            if let Some(regex_replacement) = &config.file_regex_replacement {
                 regex_replacement.execute(file)
             } else {
                 Cow::from(file)
-            };
-            match (config.hyperlinks, utils::path::absolute_path(file, config)) {
-                (true, Some(absolute_path)) => features::hyperlinks::format_osc8_file_hyperlink(
-                    absolute_path,
-                    None,
-                    &formatted_file,
-                    config,
-                ),
-                _ => formatted_file,
             }
         };
+        // Link to the file as of `commit` if given, otherwise to the local file. The old file does
+        // not exist as of the commit, so it is always given without a commit.
+        let format_file_as = |file, text: Cow<'_, str>, commit: Option<&str>| match (
+            config.hyperlinks,
+            utils::path::absolute_path(file, config),
+        ) {
+            (true, Some(absolute_path)) => features::hyperlinks::format_osc8_file_hyperlink(
+                absolute_path,
+                None,
+                None,
+                commit,
+                &text,
+                config,
+            )
+            .into_owned(),
+            _ => text.into_owned(),
+        };
+        let format_file = |file, commit| format_file_as(file, format_file_text(file), commit);
+        // The new file exists as of the commit, so it can link to the remote repository.
+        let format_new_file_as =
+            |file, text: Cow<'_, str>| match (config.hyperlinks, config.hyperlinks_infer_remote) {
+                (true, true) => commit
+                    .zip(utils::path::absolute_path(file, config))
+                    .and_then(|(commit, absolute_path)| {
+                        features::hyperlinks::format_osc8_remote_file_hyperlink(
+                            &absolute_path,
+                            commit,
+                            &text,
+                            config,
+                        )
+                    })
+                    .unwrap_or_else(|| format_file_as(file, text, commit)),
+                _ => format_file_as(file, text, commit),
+            };
+        let format_new_file = |file| format_new_file_as(file, format_file_text(file));
         match (minus_file, plus_file, minus_file_event, plus_file_event) {
-            (minus_file, plus_file, _, _) if minus_file == plus_file => format!(
-                "{}{}",
-                format_label(&config.file_modified_label),
-                format_file(minus_file)
+            (minus_file, plus_file, _, _) if minus_file == plus_file => format_file_label(
+                &config.file_modified_label,
+                &format_new_file(minus_file),
+                file_stats,
+                file_revisions,
             ),
-            (minus_file, "/dev/null", _, _) => format!(
-                "{}{}",
-                format_label(&config.file_removed_label),
-                format_file(minus_file)
+            (minus_file, "/dev/null", _, _) => format_file_label(
+                &config.file_removed_label,
+                &format_file(minus_file, None),
+                file_stats,
+                file_revisions,
             ),
-            ("/dev/null", plus_file, _, _) => format!(
-                "{}{}",
-                format_label(&config.file_added_label),
-                format_file(plus_file)
+            ("/dev/null", plus_file, _, _) => format_file_label(
+                &config.file_added_label,
+                &format_new_file(plus_file),
+                file_stats,
+                file_revisions,
             ),
             // minus_file_event == plus_file_event
-            (minus_file, plus_file, file_event, _) => format!(
-                "{}{} {} {}",
-                format_label(match file_event {
+            (minus_file, plus_file, file_event, _) => {
+                let label = match file_event {
                     FileEvent::Rename => &config.file_renamed_label,
                     FileEvent::Copy => &config.file_copied_label,
                     _ => &config.file_modified_label,
-                }),
-                format_file(minus_file),
-                config.right_arrow,
-                format_file(plus_file)
-            ),
+                };
+                let (old, new) = (format_file_text(minus_file), format_file_text(plus_file));
+                let (old, new) = if config.collapse_renamed_paths {
+                    collapse_renamed_paths(&old, &new)
+                        .map(|(old, new)| (Cow::from(old), Cow::from(new)))
+                        .unwrap_or((old, new))
+                } else {
+                    (old, new)
+                };
+                let old = format_file_as(minus_file, old, None);
+                let new = format_new_file_as(plus_file, new);
+                match &config.file_renamed_format {
+                    Some(file_renamed_format) => format_renamed_file(
+                        file_renamed_format,
+                        &format_file_label(label, "", file_stats, file_revisions),
+                        &old,
+                        &new,
+                        similarity_index,
+                    ),
+                    None => format_file_label(
+                        label,
+                        &format!("{} {} {}", old, config.right_arrow, new),
+                        file_stats,
+                        file_revisions,
+                    ),
+                }
+            }
         }
     }
 }
 
+/// Format a file label such as --file-modified-label followed by `path`, unless the label places
+/// the path itself with the {path} placeholder.
+pub fn format_file_label(
+    label: &str,
+    path: &str,
+    file_stats: &FileStats,
+    file_revisions: &FileRevisions,
+) -> String {
+    if !label.contains('{') {
+        return match (label, path) {
+            ("", _) => path.to_string(),
+            (_, "") => label.to_string(),
+            _ => format!("{} {}", label, path),
+        };
+    }
+    let format_data = format::parse_line_number_format(label, &FILE_LABEL_PLACEHOLDER_REGEX, false);
+    let mut formatted = String::new();
+    let mut suffix = "";
+    let mut has_path = false;
+    for placeholder in &format_data {
+        formatted.push_str(&placeholder.prefix);
+        let value = match placeholder.placeholder {
+            Some(Placeholder::Str("path")) => {
+                has_path = true;
+                Some(path.to_string())
+            }
+            Some(Placeholder::Str("insertions")) => Some(file_stats.insertions.to_string()),
+            Some(Placeholder::Str("deletions")) => Some(file_stats.deletions.to_string()),
+            Some(Placeholder::Str("hunks")) => Some(file_stats.hunks.to_string()),
+            Some(Placeholder::Str("old-revision")) => {
+                Some(file_revisions.old.clone().unwrap_or_default())
+            }
+            Some(Placeholder::Str("new-revision")) => {
+                Some(file_revisions.new.clone().unwrap_or_default())
+            }
+            _ => None,
+        };
+        if let Some(value) = value {
+            formatted.push_str(&format::pad(
+                value,
+                placeholder.width.unwrap_or(0),
+                placeholder.alignment_spec.unwrap_or(format::Align::Left),
+                placeholder.precision,
+            ));
+        }
+        suffix = placeholder.suffix.as_str();
+    }
+    formatted.push_str(suffix);
+    if has_path || path.is_empty() {
+        formatted
+    } else {
+        format!("{} {}", formatted, path)
+    }
+}
+
+/// Format the file header of a renamed or copied file according to --file-renamed-format.
+fn format_renamed_file(
+    file_renamed_format: &str,
+    label: &str,
+    old: &str,
+    new: &str,
+    similarity_index: Option<&str>,
+) -> String {
+    let format_data = format::parse_line_number_format(
+        file_renamed_format,
+        &FILE_RENAMED_FORMAT_PLACEHOLDER_REGEX,
+        false,
+    );
+    let mut formatted = String::new();
+    let mut suffix = "";
+    // An empty label is displayed without the whitespace separating it from what follows.
+    let mut follows_empty_label = false;
+    for placeholder in &format_data {
+        if follows_empty_label {
+            formatted.push_str(placeholder.prefix.trim_start());
+        } else {
+            formatted.push_str(&placeholder.prefix);
+        }
+        follows_empty_label = placeholder.placeholder == Some(Placeholder::Str("label"))
+            && label.is_empty()
+            && placeholder.width.is_none();
+        let value = match placeholder.placeholder {
+            Some(Placeholder::Str("label")) => Some(label),
+            Some(Placeholder::Str("old")) => Some(old),
+            Some(Placeholder::Str("new")) => Some(new),
+            Some(Placeholder::Str("similarity")) => Some(similarity_index.unwrap_or("")),
+            _ => None,
+        };
+        if let Some(value) = value {
+            formatted.push_str(&format::pad(
+                value,
+                placeholder.width.unwrap_or(0),
+                placeholder.alignment_spec.unwrap_or(format::Align::Left),
+                placeholder.precision,
+            ));
+        }
+        suffix = placeholder.suffix.as_str();
+    }
+    if follows_empty_label {
+        formatted.push_str(suffix.trim_start());
+    } else {
+        formatted.push_str(suffix);
+    }
+    formatted
+}
+
+/// Enclose the differing parts of two paths in braces within their common directory prefix and
+/// suffix, as git diff --stat does: "src/utils/io.rs" and "src/helpers/io.rs" become
+/// ("src/{utils", "helpers}/io.rs"). Return None if the paths have no directory in common.
+fn collapse_renamed_paths(old: &str, new: &str) -> Option<(String, String)> {
+    let (old_bytes, new_bytes) = (old.as_bytes(), new.as_bytes());
+    // The common prefix ends with a slash.
+    let prefix_len = old_bytes
+        .iter()
+        .zip(new_bytes)
+        .take_while(|(a, b)| a == b)
+        .enumerate()
+        .filter(|(_, (a, _))| **a == b'/')
+        .map(|(i, _)| i + 1)
+        .last()
+        .unwrap_or(0);
+    // The common suffix starts with a slash, which may be the last character of the prefix.
+    let min_len = old_bytes.len().min(new_bytes.len());
+    let suffix_len = old_bytes
+        .iter()
+        .rev()
+        .zip(new_bytes.iter().rev())
+        .take_while(|(a, b)| a == b)
+        .enumerate()
+        .take_while(|(i, _)| i + prefix_len.saturating_sub(1) < min_len)
+        .filter(|(_, (a, _))| **a == b'/')
+        .map(|(i, _)| i + 1)
+        .last()
+        .unwrap_or(0);
+    if prefix_len == 0 && suffix_len == 0 {
+        return None;
+    }
+    let old_middle = &old[prefix_len..(old.len() - suffix_len).max(prefix_len)];
+    let new_middle = &new[prefix_len..(new.len() - suffix_len).max(prefix_len)];
+    Some((
+        format!("{}{{{}", &old[..prefix_len], old_middle),
+        format!("{}}}{}", new_middle, &old[old.len() - suffix_len..]),
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -606,6 +1236,23 @@ mod tests {
             parse_diff_header_line("+++ src/delta.rs", false),
             ("src/delta.rs".to_string(), FileEvent::Change)
         );
+        // Git appends a tab to a path containing a space.
+        assert_eq!(
+            parse_diff_header_line("--- a/a b\t", true),
+            ("a b".to_string(), FileEvent::Change)
+        );
+        // Mercurial appends a tab followed by a timestamp.
+        assert_eq!(
+            parse_diff_header_line("+++ b/src/delta.rs\tWed Jan 03 08:00:00 2024 -0500", true),
+            ("src/delta.rs".to_string(), FileEvent::Change)
+        );
+        assert_eq!(
+            parse_diff_header_line(
+                "+++ src/delta.rs\t2024-01-03 08:00:00.000000000 -0500",
+                false
+            ),
+            ("src/delta.rs".to_string(), FileEvent::Change)
+        );
     }
 
     #[test]
@@ -632,4 +1279,207 @@ mod tests {
             Some(".config/Code - Insiders/User/settings.json".to_string())
         );
     }
+
+    #[test]
+    fn test_collapse_renamed_paths() {
+        let collapse = |old, new| collapse_renamed_paths(old, new);
+        assert_eq!(
+            collapse("src/utils/io.rs", "src/helpers/io.rs"),
+            Some(("src/{utils".to_string(), "helpers}/io.rs".to_string()))
+        );
+        assert_eq!(
+            collapse("src/a.rs", "src/b.rs"),
+            Some(("src/{a.rs".to_string(), "b.rs}".to_string()))
+        );
+        assert_eq!(
+            collapse("a/io.rs", "b/io.rs"),
+            Some(("{a".to_string(), "b}/io.rs".to_string()))
+        );
+        assert_eq!(
+            collapse("src/io.rs", "src/utils/io.rs"),
+            Some(("src/{".to_string(), "utils}/io.rs".to_string()))
+        );
+        assert_eq!(collapse("a.rs", "b.rs"), None);
+        assert_eq!(collapse("a/x.rs", "b/y.rs"), None);
+    }
+
+    #[test]
+    fn test_unquote_path() {
+        assert_eq!(
+            unquote_path(r#""\346\227\245\346\234\254\350\252\236.txt""#),
+            Some("日本語.txt".to_string())
+        );
+        assert_eq!(
+            unquote_path(r#""a/q\"t\tx \\ y.rs""#),
+            Some("a/q\"t\tx \\ y.rs".to_string())
+        );
+        // Invalid UTF-8 is decoded lossily.
+        assert_eq!(
+            unquote_path(r#""\377.txt""#),
+            Some("\u{fffd}.txt".to_string())
+        );
+        assert_eq!(unquote_path("a/src/main.rs"), None);
+        assert_eq!(unquote_path(r#""a\q""#), None);
+    }
+
+    #[test]
+    fn test_file_stats_from_lines() {
+        let lines = |diff: &str| -> Vec<Vec<u8>> {
+            diff.lines().map(|line| line.as_bytes().to_vec()).collect()
+        };
+        let file_stats = FileStats::from_hunk_lines(&parse_hunk_lines(&lines(
+            "\
+diff --git a/src/a.rs b/src/a.rs
+index 1111111..2222222 100644
+--- a/src/a.rs
++++ b/src/a.rs
+@@ -1,3 +1,3 @@
+ fn a() {
+-    1
++    2
+ }
+@@ -10,2 +10,3 @@
+ fn b() {}
++fn c() {}
+-fn d() {}
+\\ No newline at end of file
++fn d() {}
+\\ No newline at end of file
+commit 2222222222222222222222222222222222222222
+Author: A <a@example.com>
+
+    --- not a deletion
+",
+        )));
+        assert_eq!(
+            file_stats,
+            FileStats {
+                insertions: 3,
+                deletions: 2,
+                hunks: 2
+            }
+        );
+
+        let file_stats = FileStats::from_hunk_lines(&parse_hunk_lines(&lines(
+            "\
+diff --cc src/a.rs
+@@@ -1,2 -1,2 +1,3 @@@
+  a
+- b
+ +c
+++d
+",
+        )));
+        assert_eq!(
+            file_stats,
+            FileStats {
+                insertions: 2,
+                deletions: 1,
+                hunks: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_format_file_label() {
+        let file_stats = FileStats {
+            insertions: 12,
+            deletions: 3,
+            hunks: 2,
+        };
+        let file_revisions = FileRevisions::default();
+        assert_eq!(
+            format_file_label("", "a.rs", &file_stats, &file_revisions),
+            "a.rs"
+        );
+        assert_eq!(
+            format_file_label("added:", "a.rs", &file_stats, &file_revisions),
+            "added: a.rs"
+        );
+        assert_eq!(
+            format_file_label(
+                "M ({insertions}+ {deletions}-)",
+                "a.rs",
+                &file_stats,
+                &file_revisions
+            ),
+            "M (12+ 3-) a.rs"
+        );
+        assert_eq!(
+            format_file_label(
+                "{path} [{hunks} hunks, {insertions:>3}+]",
+                "a.rs",
+                &file_stats,
+                &file_revisions
+            ),
+            "a.rs [2 hunks,  12+]"
+        );
+        let file_revisions = FileRevisions {
+            old: Some("123".to_string()),
+            new: Some("working copy".to_string()),
+        };
+        assert_eq!(
+            format_file_label(
+                "{path} (r{old-revision} → {new-revision})",
+                "a.c",
+                &file_stats,
+                &file_revisions
+            ),
+            "a.c (r123 → working copy)"
+        );
+        assert!(label_uses_file_stats("M ({insertions}+)"));
+        assert!(!label_uses_file_stats("{path}:"));
+    }
+
+    #[test]
+    fn test_file_boundaries() {
+        let mut file_boundaries = FileBoundaries::default();
+        let starts_file = [
+            ("diff --git a/a.rs b/a.rs", true),
+            ("--- a/a.rs", false),
+            ("+++ b/a.rs", false),
+            ("@@ -1,2 +1 @@", false),
+            ("--- removed", false),
+            ("", false),
+            ("--- b.rs", true),
+            ("+++ b.rs", false),
+            ("@@ -1 +1 @@", false),
+            ("-b", false),
+            ("+c", false),
+            ("Index: c.c", true),
+            (
+                "===================================================================",
+                false,
+            ),
+            ("--- c.c\t(revision 1)", false),
+        ];
+        for (line, expected) in starts_file {
+            assert_eq!(file_boundaries.starts_file(line), expected, "{}", line);
+        }
+    }
+
+    #[test]
+    fn test_parse_quoted_file_paths() {
+        assert_eq!(
+            parse_diff_header_line(r#"+++ "b/\346\227\245.txt""#, true),
+            ("日.txt".to_string(), FileEvent::Change)
+        );
+        assert_eq!(
+            parse_diff_header_line(r#"rename from "\346\227\245.txt""#, true),
+            ("日.txt".to_string(), FileEvent::Rename)
+        );
+        assert_eq!(
+            parse_diff_header_line(r#"--- "a/q\"t\tx.rs""#, true),
+            ("q\"t\tx.rs".to_string(), FileEvent::Change)
+        );
+        assert_eq!(
+            get_repeated_file_path_from_diff_line(r#"diff --git "a/q\"t\tx.rs" "b/q\"t\tx.rs""#),
+            Some("q\"t\tx.rs".to_string())
+        );
+        // Unquoted paths are left untouched.
+        assert_eq!(
+            parse_diff_header_line(r#"+++ b/q\"t.rs"#, true),
+            (r#"q\"t.rs"#.to_string(), FileEvent::Change)
+        );
+    }
 }
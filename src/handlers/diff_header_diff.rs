@@ -1,9 +1,11 @@
-use crate::delta::{DiffType, InMergeConflict, MergeParents, State, StateMachine};
+use super::{diff_header_svn, draw};
+use crate::delta::{DiffType, InMergeConflict, MergeParents, Source, State, StateMachine};
 
 impl<'a> StateMachine<'a> {
     #[inline]
     fn test_diff_header_diff_line(&self) -> bool {
         self.line.starts_with("diff ")
+            || self.source == Source::Svn && diff_header_svn::is_svn_index_line(&self.line)
     }
 
     #[allow(clippy::unnecessary_wraps)]
@@ -25,9 +27,26 @@ impl<'a> StateMachine<'a> {
         self.handle_pending_line_with_diff_name()?;
         self.handled_diff_header_header_line_file_pair = None;
         self.diff_line = self.line.clone();
+        self.similarity_index = None;
+        self.blob_ids = None;
+        self.file_revisions = Default::default();
+        self.word_diff_porcelain = Default::default();
         if !self.should_skip_line() {
             self.emit_line_unchanged()?;
+        } else if self.config.show_git_diff_line {
+            self.write_git_diff_line()?;
         }
         Ok(true)
     }
+
+    /// Write the diff line in --git-diff-line-style, see --show-git-diff-line.
+    fn write_git_diff_line(&mut self) -> std::io::Result<()> {
+        self.painter.emit()?;
+        draw::write_decorated_line(
+            self.painter.writer,
+            &self.line,
+            &self.config.decorations_width.get(),
+            self.config.git_diff_line_style,
+        )
+    }
 }
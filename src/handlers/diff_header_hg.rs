@@ -0,0 +1,163 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    // E.g. "diff -r 3be176e2522d -r eb098a2d89f2 src/lib.rs", or with a single revision when
+    // comparing with the working directory.
+    static ref HG_DIFF_LINE_REGEX: Regex =
+        Regex::new(r"^diff (?:-r [0-9a-f]{12,40} ){1,2}\S").unwrap();
+    // E.g. "\tWed Jan 03 08:00:00 2024 -0500", following the path in a "---" or "+++" line.
+    static ref HG_TIMESTAMP_REGEX: Regex =
+        Regex::new(r"\t\w{3} \w{3} \d{2} \d{2}:\d{2}:\d{2} \d{4} [+-]\d{4}$").unwrap();
+    // Lines following the "changeset:" line in `hg log` output.
+    static ref HG_CHANGESET_FIELD_REGEX: Regex =
+        Regex::new(r"^(tag|branch|bookmark|phase|parent|user|date):\s").unwrap();
+}
+
+/// Return true if `line` is the diff line of a file diff in Mercurial's default diff format. The
+/// file paths in the "---" and "+++" lines which follow it have "a/" and "b/" prefixes, like git's.
+pub fn is_hg_diff_line(line: &str) -> bool {
+    HG_DIFF_LINE_REGEX.is_match(line)
+}
+
+/// Return true if `line` is the "changeset:" line starting a changeset in `hg log` output.
+pub fn is_hg_changeset_line(line: &str) -> bool {
+    line.starts_with("changeset:")
+}
+
+/// Return true if `line` is one of the fields, e.g. "user:", following the "changeset:" line in
+/// `hg log` output.
+pub fn is_hg_changeset_field_line(line: &str) -> bool {
+    HG_CHANGESET_FIELD_REGEX.is_match(line)
+}
+
+/// Remove the timestamp which Mercurial appends to the path of a "---" or "+++" line.
+pub fn strip_hg_timestamp(path: &str) -> &str {
+    match HG_TIMESTAMP_REGEX.find(path) {
+        Some(timestamp) => &path[..timestamp.start()],
+        None => path,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ansi::strip_ansi_codes;
+    use crate::tests::integration_test_utils;
+
+    // `hg log -p` in a repository of two changesets, with the hashes computed by Mercurial.
+    const HG_LOG: &str = "\
+changeset:   1:eb098a2d89f2
+tag:         tip
+user:        Dan Davison <dandavison7@gmail.com>
+date:        Wed Jan 03 08:00:00 2024 -0500
+summary:     Update files
+
+diff -r 3be176e2522d -r eb098a2d89f2 src/lib.rs
+--- a/src/lib.rs\tTue Jan 02 08:00:00 2024 -0500
++++ b/src/lib.rs\tWed Jan 03 08:00:00 2024 -0500
+@@ -1,3 +1,3 @@
+ mod a;
+-mod b;
++mod c;
+ mod d;
+diff -r 3be176e2522d -r eb098a2d89f2 src/new.rs
+--- /dev/null\tThu Jan 01 00:00:00 1970 +0000
++++ b/src/new.rs\tWed Jan 03 08:00:00 2024 -0500
+@@ -0,0 +1,1 @@
++fn new() {}
+
+changeset:   0:3be176e2522d
+user:        Dan Davison <dandavison7@gmail.com>
+date:        Tue Jan 02 08:00:00 2024 -0500
+summary:     Add files
+
+diff -r 000000000000 -r 3be176e2522d src/lib.rs
+--- /dev/null\tThu Jan 01 00:00:00 1970 +0000
++++ b/src/lib.rs\tTue Jan 02 08:00:00 2024 -0500
+@@ -0,0 +1,3 @@
++mod a;
++mod b;
++mod d;
+
+";
+
+    #[test]
+    fn test_is_hg_diff_line() {
+        assert!(is_hg_diff_line(
+            "diff -r 3be176e2522d -r eb098a2d89f2 src/lib.rs"
+        ));
+        assert!(is_hg_diff_line("diff -r 3be176e2522d src/lib.rs"));
+        assert!(!is_hg_diff_line("diff -r -u a/src/lib.rs b/src/lib.rs"));
+        assert!(!is_hg_diff_line("diff -ru a b"));
+        assert!(!is_hg_diff_line("diff --git a/src/lib.rs b/src/lib.rs"));
+    }
+
+    #[test]
+    fn test_strip_hg_timestamp() {
+        assert_eq!(
+            strip_hg_timestamp("a/src/lib.rs\tTue Jan 02 08:00:00 2024 -0500"),
+            "a/src/lib.rs"
+        );
+        assert_eq!(
+            strip_hg_timestamp("/dev/null\tThu Jan 01 00:00:00 1970 +0000"),
+            "/dev/null"
+        );
+        assert_eq!(strip_hg_timestamp("a/a\tb"), "a/a\tb");
+    }
+
+    #[test]
+    fn test_hg_log() {
+        let config = integration_test_utils::make_config_from_args(&[
+            "--commit-style",
+            "red",
+            "--file-added-label",
+            "added:",
+        ]);
+        let output = integration_test_utils::run_delta(HG_LOG, &config);
+        let red = ansi_term::Color::Red.normal();
+        assert!(output.contains(&red.paint("changeset:   1:eb098a2d89f2").to_string()));
+        assert!(output.contains(&red.paint("changeset:   0:3be176e2522d").to_string()));
+        let output = strip_ansi_codes(&output);
+        // The paths have neither the "a/" and "b/" prefixes nor the timestamps.
+        assert!(output.contains("\nsrc/lib.rs\n"));
+        assert!(output.contains("\nadded: src/new.rs\n"));
+        assert!(output.contains("\nadded: src/lib.rs\n"));
+        assert!(!output.contains("a/src/lib.rs"));
+        assert!(!output.contains("\ndiff -r "));
+    }
+
+    #[test]
+    fn test_hg_log_commit_format() {
+        let config = integration_test_utils::make_config_from_args(&[
+            "--commit-format",
+            "{hash} {author}",
+            "--hide-commit-metadata",
+        ]);
+        let output = integration_test_utils::run_delta(HG_LOG, &config);
+        let output = strip_ansi_codes(&output);
+        assert!(output.contains("eb098a2d89f2 Dan Davison <dandavison7@gmail.com>\n"));
+        assert!(!output.contains("\nuser: "));
+    }
+
+    #[test]
+    fn test_hg_fields_following_a_git_commit_line() {
+        // The fields of `hg log` are only collected for the changesets of Mercurial.
+        let config = integration_test_utils::make_config_from_args(&[
+            "--commit-format",
+            "{hash} {author}",
+            "--hide-commit-metadata",
+        ]);
+        let input = "\
+commit 94907c0f136f46dc46ffae2dc92dca9af7eb7c2e
+Author: Dan Davison <dandavison7@gmail.com>
+user: someone else
+";
+        let output = integration_test_utils::run_delta(input, &config);
+        let output = strip_ansi_codes(&output);
+        assert!(output.contains(
+            "94907c0f136f46dc46ffae2dc92dca9af7eb7c2e Dan Davison <dandavison7@gmail.com>\n"
+        ));
+        assert!(output.contains("\nuser: someone else\n"));
+    }
+}
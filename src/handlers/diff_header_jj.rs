@@ -0,0 +1,209 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::diff_header::{get_extension, FileEvent};
+use crate::config::Config;
+use crate::delta::{DiffType, State, StateMachine};
+use crate::utils::process;
+
+/// The version control system producing the input.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Vcs {
+    Git,
+    // https://github.com/martinvonz/jj
+    Jj,
+    // https://www.mercurial-scm.org
+    Hg,
+}
+
+lazy_static! {
+    // E.g. "Modified regular file src/delta.rs:"
+    static ref JJ_FILE_HEADER_REGEX: Regex = Regex::new(
+        r"^(Added|Modified|Removed) (?:regular file|executable file|symlink|conflict|git submodule) (.+):$"
+    )
+    .unwrap();
+    // The "Commit ID: ..." and "Change ID: ..." lines of `jj show`.
+    static ref JJ_COMMIT_LINE_REGEX: Regex = Regex::new(r"^(Commit|Change) ID: \S+").unwrap();
+}
+
+/// Return the version control system given by --vcs, or else jj if delta is called by jj, and git
+/// otherwise.
+pub fn vcs(config: &Config) -> Vcs {
+    config.vcs.unwrap_or_else(calling_vcs)
+}
+
+fn calling_vcs() -> Vcs {
+    #[cfg(not(test))]
+    {
+        *CACHED_CALLING_VCS
+    }
+    #[cfg(test)]
+    {
+        compute_calling_vcs()
+    }
+}
+
+lazy_static! {
+    static ref CACHED_CALLING_VCS: Vcs = compute_calling_vcs();
+}
+
+fn compute_calling_vcs() -> Vcs {
+    if process::calling_process().is_jj() {
+        Vcs::Jj
+    } else {
+        Vcs::Git
+    }
+}
+
+/// Return true if `line` is a commit or change ID line of `jj show` output.
+pub fn is_jj_commit_line(line: &str) -> bool {
+    JJ_COMMIT_LINE_REGEX.is_match(line)
+}
+
+/// Return true if `line` is a change ID line of `jj show` output. Change IDs are not commit
+/// hashes and cannot be used to link to the remote repository.
+pub fn is_jj_change_id_line(line: &str) -> bool {
+    line.starts_with("Change ID: ")
+}
+
+impl<'a> StateMachine<'a> {
+    #[inline]
+    fn test_diff_header_jj_line(&self) -> bool {
+        JJ_FILE_HEADER_REGEX.is_match(&self.line) && vcs(self.config) == Vcs::Jj
+    }
+
+    /// Check for and handle the "Modified regular file ...:" line of jj's default diff format.
+    pub fn handle_diff_header_jj_line(&mut self) -> std::io::Result<bool> {
+        if !self.test_diff_header_jj_line() {
+            return Ok(false);
+        }
+        let captures = JJ_FILE_HEADER_REGEX.captures(&self.line).unwrap();
+        let path = captures.get(2).unwrap().as_str().to_string();
+        let (minus_file, plus_file) = match captures.get(1).unwrap().as_str() {
+            "Added" => ("/dev/null".to_string(), path),
+            "Removed" => (path, "/dev/null".to_string()),
+            _ => (path.clone(), path),
+        };
+        self.painter.paint_buffered_minus_and_plus_lines();
+        self.state = State::DiffHeader(DiffType::Unified);
+        self.minus_file = minus_file;
+        self.plus_file = plus_file;
+        self.minus_file_event = FileEvent::Change;
+        self.plus_file_event = FileEvent::Change;
+        self.current_file_pair = Some((self.minus_file.clone(), self.plus_file.clone()));
+        if let Some(line_numbers_data) = self.painter.line_numbers_data.as_mut() {
+            line_numbers_data.initialize_file();
+        }
+        let syntax_file = if self.plus_file == "/dev/null" {
+            &self.minus_file
+        } else {
+            &self.plus_file
+        };
+        self.painter.set_syntax(get_extension(syntax_file));
+
+        let mut handled_line = false;
+        if self.should_handle() {
+            self.painter.emit()?;
+            if self.config.color_only {
+                super::diff_header::write_generic_diff_header_header_line(
+                    &self.line,
+                    &self.raw_line,
+                    &mut self.painter,
+                    &mut self.mode_info,
+                    self.config,
+                )?;
+            } else {
+                self._handle_diff_header_header_line(false)?;
+            }
+            handled_line = true;
+        }
+        self.handled_diff_header_header_line_file_pair = self.current_file_pair.clone();
+        // The file contents following the header are not in unified diff format (jj's
+        // color-words format, or the output of a custom diff tool): pass them through.
+        self.state = State::Unknown;
+        Ok(handled_line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ansi::strip_ansi_codes;
+    use crate::tests::integration_test_utils::{self, DeltaTest};
+
+    const JJ_SHOW: &str = "\
+Commit ID: 3b6e4b9a2c1d8e7f6a5b4c3d2e1f0a9b8c7d6e5f
+Change ID: kxqpvuntsmwlqtyzrnozkzxklpnkzuwy
+Author: A <a@example.com> (2024-01-01 00:00:00)
+Committer: A <a@example.com> (2024-01-01 00:00:00)
+
+    Describe the change
+
+Added regular file src/new.rs:
+        1: fn main() {}
+Modified regular file src/lib.rs:
+   1    1: pub mod a;
+   2     : pub mod b;
+Removed regular file src/old.rs:
+   1     : fn old() {}
+";
+
+    #[test]
+    fn test_jj_file_headers() {
+        let config = integration_test_utils::make_config_from_args(&[
+            "--vcs",
+            "jj",
+            "--file-added-label",
+            "added:",
+            "--file-removed-label",
+            "removed:",
+        ]);
+        let output = integration_test_utils::run_delta(JJ_SHOW, &config);
+        let output = strip_ansi_codes(&output);
+        assert!(output.contains("\nadded: src/new.rs\n"));
+        assert!(output.contains("\nsrc/lib.rs\n"));
+        assert!(output.contains("\nremoved: src/old.rs\n"));
+        assert!(!output.contains("regular file"));
+        // File contents are passed through.
+        assert!(output.contains("\n   2     : pub mod b;\n"));
+        assert!(output.contains("\n        1: fn main() {}\n"));
+    }
+
+    #[test]
+    fn test_jj_file_headers_not_recognized_with_vcs_git() {
+        let config = integration_test_utils::make_config_from_args(&[]);
+        let output = integration_test_utils::run_delta(JJ_SHOW, &config);
+        assert!(strip_ansi_codes(&output).contains("\nModified regular file src/lib.rs:\n"));
+    }
+
+    #[test]
+    fn test_jj_file_headers_recognized_when_called_by_jj() {
+        DeltaTest::with_args(&[])
+            .with_calling_process("jj show")
+            .with_input(JJ_SHOW)
+            .expect_contains("\nsrc/lib.rs\n");
+        DeltaTest::with_args(&["--vcs", "git"])
+            .with_calling_process("jj show")
+            .with_input(JJ_SHOW)
+            .expect_contains("\nModified regular file src/lib.rs:\n");
+    }
+
+    #[test]
+    fn test_jj_commit_and_change_ids_use_commit_style() {
+        let config = integration_test_utils::make_config_from_args(&[
+            "--vcs",
+            "jj",
+            "--commit-style",
+            "red",
+        ]);
+        let output = integration_test_utils::run_delta(JJ_SHOW, &config);
+        let red = ansi_term::Color::Red.normal();
+        assert!(output.contains(
+            &red.paint("Commit ID: 3b6e4b9a2c1d8e7f6a5b4c3d2e1f0a9b8c7d6e5f")
+                .to_string()
+        ));
+        assert!(output.contains(
+            &red.paint("Change ID: kxqpvuntsmwlqtyzrnozkzxklpnkzuwy")
+                .to_string()
+        ));
+    }
+}
@@ -1,10 +1,13 @@
+use std::path::{Component, Path};
+
+use crate::config::Config;
 use crate::delta::{DiffType, Source, State, StateMachine};
+use crate::handlers::diff_header;
 
 impl<'a> StateMachine<'a> {
     #[inline]
     fn test_diff_header_misc_cases(&self) -> bool {
-        self.source == Source::DiffUnified && self.line.starts_with("Only in ")
-            || self.line.starts_with("Binary files ")
+        self.line.starts_with("Binary files ")
     }
 
     pub fn handle_diff_header_misc_line(&mut self) -> std::io::Result<bool> {
@@ -16,4 +19,127 @@ impl<'a> StateMachine<'a> {
             _ => State::DiffHeader(DiffType::Unified),
         })
     }
+
+    #[inline]
+    fn test_diff_only_in_line(&self) -> bool {
+        self.source == Source::DiffUnified && self.line.starts_with("Only in ")
+    }
+
+    /// Handle an "Only in dir: file" line of `diff -r` output, which names a file that is in only
+    /// one of the directories compared. It is displayed in only-in-style as a notice that the file
+    /// was removed or added, according to whether dir is in the old or the new directory, as
+    /// indicated by the paths of the preceding file diff.
+    pub fn handle_diff_only_in_line(&mut self) -> std::io::Result<bool> {
+        if !self.test_diff_only_in_line() {
+            return Ok(false);
+        }
+        self.painter.paint_buffered_minus_and_plus_lines();
+        self.state = match self.state {
+            State::DiffHeader(_) => self.state.clone(),
+            _ => State::DiffHeader(DiffType::Unified),
+        };
+        if !self.should_handle() {
+            return Ok(false);
+        }
+        let line = if self.config.color_only {
+            self.line.clone()
+        } else {
+            format_only_in_line(&self.line, &self.minus_file, &self.plus_file, self.config)
+        };
+        self.painter.emit()?;
+        diff_header::write_diff_header_header_line_in_style(
+            &line,
+            &self.raw_line,
+            &mut self.painter,
+            &mut self.mode_info,
+            self.config.only_in_style,
+            self.config,
+        )?;
+        Ok(true)
+    }
+}
+
+/// Format an "Only in dir: file" line as "removed: dir/file" or "added: dir/file", using the
+/// file labels, if dir is in the directory of `minus_file` or `plus_file`, i.e. if it shares more
+/// leading path components with one than with the other. Otherwise return the line unchanged.
+fn format_only_in_line(line: &str, minus_file: &str, plus_file: &str, config: &Config) -> String {
+    let (dir, file) = match line
+        .strip_prefix("Only in ")
+        .and_then(|s| s.split_once(": "))
+    {
+        Some(dir_and_file) => dir_and_file,
+        None => return line.to_string(),
+    };
+    let components = |path| {
+        Path::new(path)
+            .components()
+            .filter(|c| *c != Component::CurDir)
+    };
+    let common_prefix_len = |path| {
+        components(dir)
+            .zip(components(path))
+            .take_while(|(a, b)| a == b)
+            .count()
+    };
+    let label = match common_prefix_len(minus_file).cmp(&common_prefix_len(plus_file)) {
+        std::cmp::Ordering::Greater => &config.file_removed_label,
+        std::cmp::Ordering::Less => &config.file_added_label,
+        std::cmp::Ordering::Equal => return line.to_string(),
+    };
+    let path = Path::new(dir).join(file);
+    diff_header::format_file_label(
+        label,
+        &path.to_string_lossy(),
+        &Default::default(),
+        &Default::default(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::integration_test_utils::{self, DeltaTest};
+
+    #[test]
+    fn test_format_only_in_line() {
+        let config = integration_test_utils::make_config_from_args(&[]);
+        let format = |line| format_only_in_line(line, "dirA/a b.c", "dirB/a b.c", &config);
+        assert_eq!(format("Only in dirA: x.c"), "removed: dirA/x.c");
+        assert_eq!(format("Only in dirB/sub: x y.c"), "added: dirB/sub/x y.c");
+        assert_eq!(format("Only in dirC: x.c"), "Only in dirC: x.c");
+        let format = |line| format_only_in_line(line, "./a/x", "./b/x", &config);
+        assert_eq!(format("Only in ./b: y"), "added: ./b/y");
+        assert_eq!(format("Only in /tmp: y"), "Only in /tmp: y");
+        let format = |line| format_only_in_line(line, "", "", &config);
+        assert_eq!(format("Only in dirA: x.c"), "Only in dirA: x.c");
+    }
+
+    #[test]
+    fn test_diff_recursive_with_timestamps() {
+        const DIFF_RECURSIVE: &str = "\
+diff -ruN dirA/my file.c dirB/my file.c
+--- dirA/my file.c\t2024-01-02 10:00:00.000000000 +0000
++++ dirB/my file.c\t2024-01-03 10:00:00.000000000 +0000
+@@ -1 +1 @@
+-int a;
++int b;
+Only in dirB: new.c
+diff -ruN dirA/x.py dirB/x.py
+--- dirA/x.py\t2024-01-02 10:00:00.000000000 +0000
++++ dirB/x.py\t2024-01-03 10:00:00.000000000 +0000
+@@ -1 +1 @@
+-a = 1
++a = 2
+Only in dirA: old.c
+";
+        DeltaTest::with_args(&["--only-in-style", "bold red"])
+            .explain_ansi()
+            .with_input(DIFF_RECURSIVE)
+            .expect_contains("(blue)dirA/my file.c ⟶   dirB/my file.c(normal)\n")
+            .expect_contains("\n(bold red)added: dirB/new.c(normal)\n")
+            .expect_contains("(blue)dirA/x.py ⟶   dirB/x.py(normal)\n")
+            // The language is that of the path, without the timestamp.
+            .expect_contains("(231 22)a (203)=(231) (141 28)2(normal)")
+            .expect_contains("\n(bold red)removed: dirA/old.c(normal)\n");
+    }
 }
@@ -0,0 +1,194 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::delta::{Source, State, StateMachine};
+
+lazy_static! {
+    // The revision following the path in the "---" and "+++" lines of `svn diff` output, e.g.
+    // "--- src/main.c\t(revision 123)" or "+++ src/main.c\t(working copy)".
+    static ref SVN_REVISION_REGEX: Regex =
+        Regex::new(r"\t\((?:revision (\d+)|(working copy|nonexistent))\)$").unwrap();
+}
+
+/// Return true if `line` is the "Index: path" line preceding the diff of a file in `svn diff`
+/// output. It is followed by a line of "=" characters, and then by the "---" and "+++" lines.
+pub fn is_svn_index_line(line: &str) -> bool {
+    line.starts_with("Index: ")
+}
+
+/// Return true if `line` is the line of "=" following the "Index: path" line in `svn diff`
+/// output.
+pub fn is_svn_separator_line(line: &str) -> bool {
+    line.len() > 1 && line.bytes().all(|b| b == b'=')
+}
+
+/// Return the revision in a "---" or "+++" line of `svn diff` output: the revision number, or
+/// "working copy" or "nonexistent".
+pub fn get_svn_revision(line: &str) -> Option<&str> {
+    let captures = SVN_REVISION_REGEX.captures(line)?;
+    captures
+        .get(1)
+        .or_else(|| captures.get(2))
+        .map(|m| m.as_str())
+}
+
+/// Return true if the file of a "---" or "+++" line of `svn diff` output does not exist in that
+/// revision, i.e. the file was added or deleted. Older versions of svn give revision 0.
+pub fn is_svn_nonexistent_file(line: &str) -> bool {
+    matches!(get_svn_revision(line), Some("nonexistent") | Some("0"))
+}
+
+impl<'a> StateMachine<'a> {
+    #[inline]
+    fn test_svn_property_line(&self) -> bool {
+        self.source == Source::Svn
+            && !self.config.color_only
+            && (self.svn_properties || self.line.starts_with("Property changes on: "))
+    }
+
+    /// Handle the lines of a block of property changes of `svn diff` output, e.g.
+    ///
+    /// Property changes on: src/main.c
+    /// ___________________________________________________________________
+    /// Added: svn:executable
+    /// ## -0,0 +1 ##
+    /// +*
+    ///
+    /// The path is displayed in file-style, the names of the changed properties as they are, and
+    /// the old and new values in minus-style and plus-style. The underline, the "##" lines and the
+    /// "\ No newline at end of property" lines are omitted.
+    pub fn handle_svn_property_line(&mut self) -> std::io::Result<bool> {
+        if !self.test_svn_property_line() {
+            return Ok(false);
+        }
+        if is_svn_index_line(&self.line) {
+            self.svn_properties = false;
+            return Ok(false);
+        }
+        if let Some(path) = self.line.strip_prefix("Property changes on: ") {
+            self.painter.paint_buffered_minus_and_plus_lines();
+            self.painter.emit()?;
+            self.state = State::Unknown;
+            self.svn_properties = true;
+            let line = format!("{} (properties)", path);
+            writeln!(
+                self.painter.writer,
+                "{}",
+                self.config.file_style.paint(line)
+            )?;
+            return Ok(true);
+        }
+        let style = match self.line.chars().next() {
+            Some('_') | Some('#') | Some('\\') => return Ok(true),
+            Some('-') => self.config.minus_style,
+            Some('+') => self.config.plus_style,
+            Some(' ') => self.config.zero_style,
+            _ => {
+                writeln!(self.painter.writer, "{}", self.line)?;
+                return Ok(true);
+            }
+        };
+        writeln!(
+            self.painter.writer,
+            "{}",
+            style.ansi_term_style.paint(&self.line[1..])
+        )?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ansi::strip_ansi_codes;
+    use crate::tests::integration_test_utils::{self, DeltaTest};
+
+    const SVN_DIFF: &str = "\
+Index: src/main.c
+===================================================================
+--- src/main.c\t(revision 123)
++++ src/main.c\t(working copy)
+@@ -1,3 +1,3 @@
+ int a;
+-int b;
++int c;
+ int d;
+Index: src/new.c
+===================================================================
+--- src/new.c\t(nonexistent)
++++ src/new.c\t(working copy)
+@@ -0,0 +1 @@
++int e;
+
+Property changes on: src/main.c
+___________________________________________________________________
+Added: svn:executable
+## -0,0 +1 ##
++*
+\\ No newline at end of property
+Index: src/old.c
+===================================================================
+--- src/old.c\t(revision 122)
++++ src/old.c\t(nonexistent)
+@@ -1 +0,0 @@
+-int f;
+";
+
+    #[test]
+    fn test_get_svn_revision() {
+        assert_eq!(get_svn_revision("--- a.c\t(revision 123)"), Some("123"));
+        assert_eq!(
+            get_svn_revision("+++ a.c\t(working copy)"),
+            Some("working copy")
+        );
+        assert_eq!(
+            get_svn_revision("--- a.c\t(nonexistent)"),
+            Some("nonexistent")
+        );
+        assert_eq!(get_svn_revision("--- a/a.c"), None);
+        assert!(is_svn_nonexistent_file("--- a.c\t(revision 0)"));
+        assert!(!is_svn_nonexistent_file("--- a.c\t(revision 10)"));
+    }
+
+    #[test]
+    fn test_svn_diff() {
+        let config = integration_test_utils::make_config_from_args(&[]);
+        let output = integration_test_utils::run_delta(SVN_DIFF, &config);
+        let output = strip_ansi_codes(&output);
+        assert!(output.contains("\nsrc/main.c\n"));
+        assert!(output.contains("\nadded: src/new.c\n"));
+        assert!(output.contains("\nremoved: src/old.c\n"));
+        assert!(!output.contains("Index: "));
+        assert!(!output.contains("====="));
+        assert!(!output.contains("revision"));
+        assert!(!output.contains("⟶"));
+        assert!(output.contains("\nsrc/main.c (properties)\nAdded: svn:executable\n*\n"));
+        assert!(!output.contains("## -0,0 +1 ##"));
+    }
+
+    #[test]
+    fn test_svn_diff_file_label_revisions() {
+        DeltaTest::with_args(&["--file-modified-label", "{path}@{old-revision}"])
+            .with_input(SVN_DIFF)
+            .expect_contains("\nsrc/main.c@123\n");
+        DeltaTest::with_args(&["--file-added-label", "added ({new-revision}):"])
+            .with_input(SVN_DIFF)
+            .expect_contains("\nadded (working copy): src/new.c\n");
+    }
+
+    #[test]
+    fn test_svn_diff_file_label_stats() {
+        DeltaTest::with_args(&[
+            "--file-modified-label",
+            "M ({insertions}+ {deletions}-)",
+            "--file-added-label",
+            "A ({insertions}+)",
+            "--file-removed-label",
+            "D ({deletions}-)",
+        ])
+        .with_input(SVN_DIFF)
+        .expect_contains("\nM (1+ 1-) src/main.c\n")
+        .expect_contains("\nA (1+) src/new.c\n")
+        .expect_contains("\nD (1-) src/old.c\n");
+    }
+}
@@ -1,29 +1,123 @@
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::borrow::Cow;
+use unicode_width::UnicodeWidthStr;
 
+use crate::cli;
 use crate::config::Config;
-use crate::delta::{State, StateMachine};
+use crate::delta::{format_raw_line, State, StateMachine};
 use crate::features;
+use crate::handlers::diff_header;
 use crate::utils;
 
+/// A line of a diff stat section, e.g. " src/delta.rs | 14 ++++++++++----".
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiffStatLine {
+    // The path as it is relative to the repository root, decoded if git quoted it.
+    path: String,
+    changes: DiffStatChanges,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum DiffStatChanges {
+    // The number of changed lines, and the numbers of + and - in the bar drawn by git.
+    Lines {
+        count: usize,
+        n_plus: usize,
+        n_minus: usize,
+    },
+    // E.g. "Bin 0 -> 1234 bytes".
+    Binary(String),
+}
+
 impl<'a> StateMachine<'a> {
     #[inline]
     fn test_diff_stat_line(&self) -> bool {
-        (self.state == State::CommitMeta || self.state == State::Unknown)
-            && self.line.starts_with(' ')
+        match self.state {
+            // In `git log` and `git show` output the lines of the commit message are indented by
+            // four spaces, and those of the diff stat following it by one.
+            State::CommitMeta => self.line.starts_with(' ') && !self.line.starts_with("    "),
+            State::Unknown => self.line.starts_with(' '),
+            _ => false,
+        }
     }
 
+    /// The lines of a diff stat section are collected until its summary line, so that they can
+    /// be aligned on the display width of the file paths, and their bars rescaled. Lines which
+    /// turn out not to be followed by a summary line are written as they were read.
     pub fn handle_diff_stat_line(&mut self) -> std::io::Result<bool> {
         if !self.test_diff_stat_line() {
+            self.write_diff_stat_lines()?;
             return Ok(false);
         }
+        if self.config.color_only {
+            return self.handle_diff_stat_line_color_only();
+        }
+        if let Some(diff_stat_line) = parse_diff_stat_line(&self.line) {
+            self.diff_stat_raw_lines.push(self.raw_line.clone());
+            // The line of a file hidden by --include-files or --exclude-files is dropped.
+            if !self.config.filter_files
+                || self.config.file_filter.is_displayed(&[
+                    get_old_path(&diff_stat_line.path),
+                    get_new_path(&diff_stat_line.path),
+                ])
+            {
+                self.diff_stat_lines.push(diff_stat_line);
+            }
+            return Ok(true);
+        }
+        if DIFF_STAT_SUMMARY_LINE_REGEX.is_match(&self.line) {
+            self.painter.emit()?;
+            for line in format_diff_stat_lines(
+                &self.diff_stat_lines,
+                self.current_commit.as_deref(),
+                self.config,
+            ) {
+                writeln!(self.painter.writer, "{}", line)?;
+            }
+            self.diff_stat_lines.clear();
+            self.diff_stat_raw_lines.clear();
+            writeln!(
+                self.painter.writer,
+                "{}",
+                self.config.diff_stat_summary_style.paint(&self.line)
+            )?;
+            return Ok(true);
+        }
+        self.write_diff_stat_lines()?;
+        Ok(false)
+    }
+
+    /// Write, as they were read, the collected lines that resembled those of a diff stat
+    /// section but were not followed by its summary line.
+    pub fn write_diff_stat_lines(&mut self) -> std::io::Result<()> {
+        if self.diff_stat_raw_lines.is_empty() {
+            return Ok(());
+        }
+        self.painter.emit()?;
+        for raw_line in self.diff_stat_raw_lines.drain(..) {
+            writeln!(
+                self.painter.writer,
+                "{}",
+                format_raw_line(&raw_line, self.config)
+            )?;
+        }
+        self.diff_stat_lines.clear();
+        Ok(())
+    }
+
+    // In --color-only mode the structure of the line is not altered, other than by relativizing
+    // its path.
+    fn handle_diff_stat_line_color_only(&mut self) -> std::io::Result<bool> {
         let mut handled_line = false;
         if self.config.relative_paths {
             if let Some(cwd) = self.config.cwd_relative_to_repo_root.as_deref() {
-                if let Some(replacement_line) =
-                    relativize_path_in_diff_stat_line(&self.raw_line, cwd, self.config)
-                {
+                if let Some(replacement_line) = relativize_path_in_diff_stat_line(
+                    &self.raw_line,
+                    cwd,
+                    self.current_commit.as_deref(),
+                    self.config,
+                ) {
                     self.painter.emit()?;
                     writeln!(self.painter.writer, "{}", replacement_line)?;
                     handled_line = true
@@ -34,6 +128,190 @@ impl<'a> StateMachine<'a> {
     }
 }
 
+lazy_static! {
+    // E.g. " src/delta.rs | 14 ++++++++++----", " src/mode.sh | 0" or
+    // " image.png | Bin 0 -> 1234 bytes".
+    static ref DIFF_STAT_LINE_PARTS_REGEX: Regex =
+        Regex::new(r"^ (.*[^ ]) +\| +(?:([0-9]+)(?: ([+-]*))?|(Bin .*))$").unwrap();
+    // E.g. " 3 files changed, 12 insertions(+), 4 deletions(-)".
+    static ref DIFF_STAT_SUMMARY_LINE_REGEX: Regex = Regex::new(
+        r"^ [0-9]+ files? changed(, [0-9]+ insertions?\(\+\))?(, [0-9]+ deletions?\(-\))?$"
+    )
+    .unwrap();
+}
+
+fn parse_diff_stat_line(line: &str) -> Option<DiffStatLine> {
+    let caps = DIFF_STAT_LINE_PARTS_REGEX.captures(line)?;
+    let path = caps.get(1).unwrap().as_str();
+    let path = diff_header::unquote_path(path).unwrap_or_else(|| path.to_string());
+    let changes = match (caps.get(2), caps.get(4)) {
+        (Some(count), _) => {
+            let bar = caps.get(3).map_or("", |m| m.as_str());
+            let n_plus = bar.chars().filter(|c| *c == '+').count();
+            DiffStatChanges::Lines {
+                count: count.as_str().parse().ok()?,
+                n_plus,
+                n_minus: bar.len() - n_plus,
+            }
+        }
+        (None, Some(binary)) => DiffStatChanges::Binary(binary.as_str().to_string()),
+        (None, None) => return None,
+    };
+    Some(DiffStatLine { path, changes })
+}
+
+fn format_diff_stat_lines(
+    lines: &[DiffStatLine],
+    commit: Option<&str>,
+    config: &Config,
+) -> Vec<String> {
+    let paths: Vec<Cow<str>> = lines
+        .iter()
+        .map(|line| relativize_path(&line.path, config))
+        .collect();
+    // Relativized paths are allocated --diff-stat-align-width, as in --color-only mode.
+    let path_width = if config.relative_paths && config.cwd_relative_to_repo_root.is_some() {
+        config.diff_stat_align_width
+    } else {
+        paths.iter().map(|path| path.width()).max().unwrap_or(0)
+    };
+    let max_count = lines
+        .iter()
+        .filter_map(|line| match line.changes {
+            DiffStatChanges::Lines { count, .. } => Some(count),
+            DiffStatChanges::Binary(_) => None,
+        })
+        .max()
+        .unwrap_or(0);
+    let count_width = max_count.to_string().len();
+    let width = match config.decorations_width.get() {
+        cli::Width::Fixed(width) => width,
+        cli::Width::Variable => config.available_terminal_width.get(),
+    };
+    // " {path} | {count} {bar}"
+    let max_bar_width = width.saturating_sub(path_width + count_width + 5).max(1);
+
+    lines
+        .iter()
+        .zip(paths)
+        .map(|(line, path)| {
+            let padding = " ".repeat(path_width.saturating_sub(path.width()));
+            let formatted_path = match (
+                config.hyperlinks,
+                utils::path::absolute_path(&get_new_path(&line.path), config),
+            ) {
+                (true, Some(absolute_path)) if !line.path.starts_with(".../") => {
+                    features::hyperlinks::format_osc8_file_hyperlink(
+                        absolute_path,
+                        None,
+                        None,
+                        commit,
+                        &path,
+                        config,
+                    )
+                }
+                _ => path.clone(),
+            };
+            let changes = match &line.changes {
+                DiffStatChanges::Lines {
+                    count,
+                    n_plus,
+                    n_minus,
+                } => {
+                    let (n_plus, n_minus) = if config.diff_stat_rescale {
+                        scale_bar(*n_plus, *n_minus, *count, max_count, max_bar_width)
+                    } else {
+                        (*n_plus, *n_minus)
+                    };
+                    let mut changes = format!("{:>width$}", count, width = count_width);
+                    if n_plus + n_minus > 0 {
+                        changes.push(' ');
+                    }
+                    if n_plus > 0 {
+                        let bar = "+".repeat(n_plus);
+                        changes.push_str(&config.plus_style.paint(bar).to_string());
+                    }
+                    if n_minus > 0 {
+                        let bar = "-".repeat(n_minus);
+                        changes.push_str(&config.minus_style.paint(bar).to_string());
+                    }
+                    changes
+                }
+                DiffStatChanges::Binary(binary) => binary.clone(),
+            };
+            format!(" {}{} | {}", formatted_path, padding, changes)
+        })
+        .collect()
+}
+
+// Return the path relative to the current directory if --relative-paths is in effect. The paths
+// of renamed files, e.g. "src/{old => new}/file.rs", are left as they are.
+fn relativize_path<'p>(path: &'p str, config: &Config) -> Cow<'p, str> {
+    match (
+        config.relative_paths,
+        config.cwd_relative_to_repo_root.as_deref(),
+    ) {
+        (true, Some(cwd)) if !path.contains(" => ") => pathdiff::diff_paths(path, cwd)
+            .and_then(|relative_path| relative_path.to_str().map(str::to_string))
+            .map_or(Cow::from(path), Cow::from),
+        _ => Cow::from(path),
+    }
+}
+
+// Return the new path of a renamed file, e.g. "src/new/file.rs" for "src/{old => new}/file.rs",
+// and the path itself otherwise.
+fn get_new_path(path: &str) -> String {
+    get_renamed_path(path, true)
+}
+
+// Return the old path of a renamed file, e.g. "src/old/file.rs" for "src/{old => new}/file.rs",
+// and the path itself otherwise.
+fn get_old_path(path: &str) -> String {
+    get_renamed_path(path, false)
+}
+
+fn get_renamed_path(path: &str, new: bool) -> String {
+    let side = |renaming: &'_ str| -> String {
+        let mut paths = renaming.splitn(2, " => ");
+        let old = paths.next().unwrap_or(renaming);
+        if new {
+            paths.next().unwrap_or(old).to_string()
+        } else {
+            old.to_string()
+        }
+    };
+    let (prefix, rest) = match path.split_once('{') {
+        Some((prefix, rest)) if rest.contains(" => ") && rest.contains('}') => (prefix, rest),
+        _ => return side(path),
+    };
+    let (renaming, suffix) = rest.split_once('}').unwrap();
+    format!("{}{}{}", prefix, side(renaming), suffix)
+        .replace("//", "/")
+        .trim_start_matches('/')
+        .to_string()
+}
+
+// Return the numbers of + and - of a bar scaled so that the bar of the file with the most changes
+// is `max_width` long. A file with both insertions and deletions keeps at least one of each.
+fn scale_bar(
+    n_plus: usize,
+    n_minus: usize,
+    count: usize,
+    max_count: usize,
+    max_width: usize,
+) -> (usize, usize) {
+    let n = n_plus + n_minus;
+    if n == 0 || max_count == 0 {
+        return (n_plus, n_minus);
+    }
+    let width = ((count * max_width + max_count / 2) / max_count).max(1);
+    let mut scaled_n_plus = (n_plus * width + n / 2) / n;
+    if width >= 2 && n_plus > 0 && n_minus > 0 {
+        scaled_n_plus = scaled_n_plus.clamp(1, width - 1);
+    }
+    (scaled_n_plus, width - scaled_n_plus)
+}
+
 // A regex to capture the path, and the content from the pipe onwards, in lines
 // like these:
 // " src/delta.rs  | 14 ++++++++++----"
@@ -46,6 +324,7 @@ lazy_static! {
 pub fn relativize_path_in_diff_stat_line(
     line: &str,
     cwd_relative_to_repo_root: &str,
+    commit: Option<&str>,
     config: &Config,
 ) -> Option<String> {
     let caps = DIFF_STAT_LINE_REGEX.captures(line)?;
@@ -61,6 +340,8 @@ pub fn relativize_path_in_diff_stat_line(
         (true, Some(absolute_path)) => features::hyperlinks::format_osc8_file_hyperlink(
             absolute_path,
             None,
+            None,
+            commit,
             relative_path,
             config,
         ),
@@ -77,6 +358,8 @@ pub fn relativize_path_in_diff_stat_line(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ansi::strip_ansi_codes;
+    use crate::tests::integration_test_utils::{self, DeltaTest};
 
     #[test]
     fn test_diff_stat_line_regex_1() {
@@ -96,6 +379,238 @@ mod tests {
         assert_eq!(caps.get(2).unwrap().as_str(), "|  2 ++");
     }
 
+    #[test]
+    fn test_parse_diff_stat_line() {
+        assert_eq!(
+            parse_diff_stat_line(" src/delta.rs  | 14 ++++++++++----"),
+            Some(DiffStatLine {
+                path: "src/delta.rs".into(),
+                changes: DiffStatChanges::Lines {
+                    count: 14,
+                    n_plus: 10,
+                    n_minus: 4
+                }
+            })
+        );
+        assert_eq!(
+            parse_diff_stat_line(" \"\\346\\227\\245.txt\" | 0"),
+            Some(DiffStatLine {
+                path: "日.txt".into(),
+                changes: DiffStatChanges::Lines {
+                    count: 0,
+                    n_plus: 0,
+                    n_minus: 0
+                }
+            })
+        );
+        assert_eq!(
+            parse_diff_stat_line(" image.png | Bin 0 -> 1234 bytes"),
+            Some(DiffStatLine {
+                path: "image.png".into(),
+                changes: DiffStatChanges::Binary("Bin 0 -> 1234 bytes".into())
+            })
+        );
+        assert_eq!(
+            parse_diff_stat_line(" 2 files changed, 5 insertions(+)"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_get_new_path() {
+        assert_eq!(get_new_path("src/delta.rs"), "src/delta.rs");
+        assert_eq!(get_new_path("a.rs => b.rs"), "b.rs");
+        assert_eq!(get_new_path("src/{old => new}/file.rs"), "src/new/file.rs");
+        assert_eq!(get_new_path("src/{ => new}/file.rs"), "src/new/file.rs");
+        assert_eq!(get_new_path("{old => }/file.rs"), "file.rs");
+    }
+
+    #[test]
+    fn test_get_old_path() {
+        assert_eq!(get_old_path("src/delta.rs"), "src/delta.rs");
+        assert_eq!(get_old_path("a.rs => b.rs"), "a.rs");
+        assert_eq!(get_old_path("src/{old => new}/file.rs"), "src/old/file.rs");
+        assert_eq!(get_old_path("src/{ => new}/file.rs"), "src/file.rs");
+        assert_eq!(get_old_path("{old => }/file.rs"), "old/file.rs");
+    }
+
+    #[test]
+    fn test_scale_bar() {
+        assert_eq!(scale_bar(6, 2, 8, 8, 40), (30, 10));
+        assert_eq!(scale_bar(3, 1, 4, 8, 40), (15, 5));
+        // A file with both insertions and deletions keeps a + and a -.
+        assert_eq!(scale_bar(20, 1, 21, 21, 10), (9, 1));
+        assert_eq!(scale_bar(1, 0, 1, 1000, 10), (1, 0));
+        assert_eq!(scale_bar(0, 0, 0, 8, 40), (0, 0));
+    }
+
+    #[test]
+    fn test_diff_stat_lines_are_aligned_on_display_width() {
+        let config = integration_test_utils::make_config_from_args(&[]);
+        let output = integration_test_utils::run_delta(DIFF_STAT_WITH_WIDE_CHARACTERS, &config);
+        let output = strip_ansi_codes(&output);
+        assert!(output.contains(
+            "\
+ src/日本語.rs | 12 ++++++++----
+ src/lib.rs    |  3 +++
+ image.png     | Bin 0 -> 1234 bytes
+ 3 files changed, 11 insertions(+), 4 deletions(-)
+"
+        ));
+    }
+
+    #[test]
+    fn test_diff_stat_relative_paths_are_aligned_on_align_width() {
+        DeltaTest::with_args(&["--relative-paths", "--diff-stat-align-width", "12"])
+            .set_config(|config| config.cwd_relative_to_repo_root = Some("src/".to_string()))
+            .with_input(DIFF_STAT_WITH_WIDE_CHARACTERS)
+            .expect_contains(
+                "\
+ 日本語.rs    | 12 ++++++++----
+ lib.rs       |  3 +++
+ ../image.png | Bin 0 -> 1234 bytes
+",
+            );
+    }
+
+    #[test]
+    fn test_lines_resembling_diff_stat_lines_are_not_altered() {
+        let input = "\
+ src/lib.rs    | 1 +
+ a table | 22
+Not a summary line
+";
+        let output = DeltaTest::with_args(&[]).with_input(input).output;
+        assert_eq!(output, input);
+    }
+
+    #[test]
+    fn test_diff_stat_rescale() {
+        let config = integration_test_utils::make_config_from_args(&[
+            "--diff-stat-rescale",
+            "--width",
+            "40",
+        ]);
+        let output = integration_test_utils::run_delta(DIFF_STAT_WITH_WIDE_CHARACTERS, &config);
+        let output = strip_ansi_codes(&output);
+        assert!(output.contains(
+            "\
+ src/日本語.rs | 12 +++++++++++++-------
+ src/lib.rs    |  3 +++++
+"
+        ));
+    }
+
+    #[test]
+    fn test_diff_stat_summary_style() {
+        let config =
+            integration_test_utils::make_config_from_args(&["--diff-stat-summary-style", "bold"]);
+        let output = integration_test_utils::run_delta(DIFF_STAT_WITH_WIDE_CHARACTERS, &config);
+        assert!(output.contains("\x1b[1m 3 files changed, 11 insertions(+), 4 deletions(-)\x1b[0m"));
+    }
+
+    #[test]
+    fn test_commit_message_lines_are_not_diff_stat_lines() {
+        let config = integration_test_utils::make_config_from_args(&[]);
+        let output = integration_test_utils::run_delta(GIT_LOG_STAT_PATCH, &config);
+        let output = strip_ansi_codes(&output);
+        assert!(output.contains(
+            "
+    Swap a | 1
+    for the longer name | 22
+---
+ src/lib.rs | 1 +
+ 1 file changed, 1 insertion(+)
+"
+        ));
+        let config = integration_test_utils::make_config_from_args(&["--include-files", "*.sh"]);
+        let output = integration_test_utils::run_delta(GIT_LOG_STAT_PATCH, &config);
+        let output = strip_ansi_codes(&output);
+        assert!(output.contains("    Swap a | 1\n    for the longer name | 22\n---\n"));
+        assert!(!output.contains("src/lib.rs"));
+    }
+
+    #[test]
+    fn test_diff_stat_of_commit_without_hunks() {
+        let config = integration_test_utils::make_config_from_args(&[]);
+        let output = integration_test_utils::run_delta(GIT_LOG_STAT_PATCH, &config);
+        let output = strip_ansi_codes(&output);
+        assert!(output.contains(
+            "\
+ run.sh | 0
+ 1 file changed, 0 insertions(+), 0 deletions(-)
+"
+        ));
+        assert!(output.contains("run.sh\n"));
+        assert!(output.contains("mode changed: 644 ⟶   755 (+x)"));
+        // The diff stat of the next commit, which has no patch, is still formatted.
+        assert!(output.contains(
+            "\
+    Update files
+
+ src/lib.rs  |  1 +
+ src/main.rs | 12 ++++++------
+ 2 files changed, 7 insertions(+), 6 deletions(-)
+"
+        ));
+    }
+
+    const GIT_LOG_STAT_PATCH: &str = "\
+commit 94907c0f136f46dc46ffae2dc92dca9af7eb7c2e
+Author: Dan Davison <dandavison7@gmail.com>
+Date:   Wed Jan 3 08:00:00 2024 -0500
+
+    Swap a | 1
+    for the longer name | 22
+---
+ src/lib.rs | 1 +
+ 1 file changed, 1 insertion(+)
+
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1 +1,2 @@
+ fn lib() {}
++fn lib2() {}
+
+commit 8d8ba7c9e0c2b7e16a82d4fc31d9eb1bf2d6a2f5
+Author: Dan Davison <dandavison7@gmail.com>
+Date:   Tue Jan 2 08:00:00 2024 -0500
+
+    Make run.sh executable
+---
+ run.sh | 0
+ 1 file changed, 0 insertions(+), 0 deletions(-)
+
+diff --git a/run.sh b/run.sh
+old mode 100644
+new mode 100755
+
+commit 3c1b5e4fd2a9b7c0e4f6a8d2b1c3e5f7a9b0c2d4
+Author: Dan Davison <dandavison7@gmail.com>
+Date:   Mon Jan 1 08:00:00 2024 -0500
+
+    Update files
+
+ src/lib.rs | 1 +
+ src/main.rs | 12 ++++++------
+ 2 files changed, 7 insertions(+), 6 deletions(-)
+";
+
+    const DIFF_STAT_WITH_WIDE_CHARACTERS: &str = "\
+commit 94907c0f136f46dc46ffae2dc92dca9af7eb7c2e
+Author: Dan Davison <dandavison7@gmail.com>
+Date:   Wed Jan 3 08:00:00 2024 -0500
+
+    Update files
+---
+ \"src/\\346\\227\\245\\346\\234\\254\\350\\252\\236.rs\" | 12 ++++++++----
+ src/lib.rs       |  3 +++
+ image.png        | Bin 0 -> 1234 bytes
+ 3 files changed, 11 insertions(+), 4 deletions(-)
+";
+
     #[test]
     fn test_relative_path() {
         for (path, cwd_relative_to_repo_root, expected) in &[
@@ -0,0 +1,110 @@
+use crate::delta::StateMachine;
+
+/// The counts of the changes of a commit, or of the whole input if it does not consist of
+/// commits, written at its end by --diff-summary.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DiffSummary {
+    pub files: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+    // Merge conflicts are not summarized.
+    pub has_merge_conflict: bool,
+}
+
+impl<'a> StateMachine<'a> {
+    /// Write the line summarizing the changes counted since the last commit line, if any, and
+    /// reset the counts.
+    pub fn write_diff_summary(&mut self) -> std::io::Result<()> {
+        let summary = std::mem::take(&mut self.diff_summary);
+        if !self.config.diff_summary || summary.files == 0 || summary.has_merge_conflict {
+            return Ok(());
+        }
+        self.painter.paint_buffered_minus_and_plus_lines();
+        self.painter.emit()?;
+        writeln!(
+            self.painter.writer,
+            "{}",
+            self.config
+                .diff_summary_style
+                .paint(format_diff_summary(&summary))
+        )
+    }
+}
+
+// E.g. "Σ 14 files, +362 −121".
+fn format_diff_summary(summary: &DiffSummary) -> String {
+    format!(
+        "\u{03a3} {} {}, +{} \u{2212}{}",
+        summary.files,
+        if summary.files == 1 { "file" } else { "files" },
+        summary.insertions,
+        summary.deletions
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::fixtures::GIT_LOG_THREE_COMMITS;
+    use crate::tests::integration_test_utils::DeltaTest;
+
+    #[test]
+    fn test_format_diff_summary() {
+        let summary = DiffSummary {
+            files: 14,
+            insertions: 362,
+            deletions: 121,
+            has_merge_conflict: false,
+        };
+        assert_eq!(format_diff_summary(&summary), "Σ 14 files, +362 −121");
+        let summary = DiffSummary {
+            files: 1,
+            insertions: 0,
+            deletions: 1,
+            has_merge_conflict: false,
+        };
+        assert_eq!(format_diff_summary(&summary), "Σ 1 file, +0 −1");
+    }
+
+    #[test]
+    fn test_diff_summary_is_written_after_each_commit() {
+        let output = DeltaTest::with_args(&["--diff-summary"])
+            .with_input(GIT_LOG_THREE_COMMITS)
+            // Each summary ends the diff of a commit.
+            .expect_contains("\nΣ 3 files, +5 −4\ncommit 6d1b7a2")
+            .expect_contains("\nΣ 2 files, +4 −2\ncommit 8d8ba7c")
+            .output;
+        let summaries: Vec<&str> = output
+            .lines()
+            .filter(|line| line.starts_with('Σ'))
+            .collect();
+        assert_eq!(
+            summaries,
+            vec!["Σ 3 files, +5 −4", "Σ 2 files, +4 −2", "Σ 1 file, +1 −1"]
+        );
+        assert!(output.trim_end().ends_with("Σ 1 file, +1 −1"));
+    }
+
+    #[test]
+    fn test_diff_summary_is_not_written_by_default() {
+        let output = DeltaTest::with_args(&[])
+            .with_input(GIT_LOG_THREE_COMMITS)
+            .output;
+        assert!(!output.contains('Σ'));
+    }
+
+    #[test]
+    fn test_diff_summary_is_not_written_for_input_without_hunks() {
+        let output = DeltaTest::with_args(&["--diff-summary"])
+            .with_input("src/lib.rs:1:mod a;\nsrc/lib.rs:2:mod b;\n")
+            .output;
+        assert!(!output.contains('Σ'));
+    }
+
+    #[test]
+    fn test_diff_summary_style() {
+        DeltaTest::with_args(&["--diff-summary", "--diff-summary-style", "bold red"])
+            .with_input(GIT_LOG_THREE_COMMITS)
+            .expect_raw_contains("\x1b[1;31mΣ 1 file, +1 −1\x1b[0m");
+    }
+}
@@ -52,6 +52,30 @@ pub fn get_draw_function(
     }
 }
 
+/// Write `text` as a line of its own in `style`, decorated as the style requests, e.g. in a box.
+pub fn write_decorated_line(
+    writer: &mut dyn Write,
+    text: &str,
+    line_width: &Width,
+    style: Style,
+) -> std::io::Result<()> {
+    let (mut draw_fn, pad, decoration_ansi_term_style) = get_draw_function(style.decoration_style);
+    let text = if pad {
+        format!("{} ", text)
+    } else {
+        text.to_string()
+    };
+    draw_fn(
+        writer,
+        &text,
+        &text,
+        "",
+        line_width,
+        style,
+        decoration_ansi_term_style,
+    )
+}
+
 fn write_no_decoration(
     writer: &mut dyn Write,
     text: &str,
@@ -85,7 +109,7 @@ pub fn write_boxed(
     } else {
         box_drawing::light::UP_LEFT
     };
-    let box_width = ansi::measure_text_width(text);
+    let box_width = measure_box_width(text);
     write_boxed_partial(
         writer,
         text,
@@ -110,7 +134,7 @@ fn write_boxed_with_underline(
     text_style: Style,
     decoration_style: ansi_term::Style,
 ) -> std::io::Result<()> {
-    let box_width = ansi::measure_text_width(text);
+    let box_width = measure_box_width(text);
     write_boxed_with_horizontal_whisker(
         writer,
         text,
@@ -319,15 +343,35 @@ fn write_boxed_partial(
         decoration_style.paint(&horizontal_edge),
         decoration_style.paint(down_left),
     )?;
-    if text_style.is_raw {
-        write!(writer, "{}", raw_text)?;
-    } else {
-        write!(writer, "{}", paint_text(text_style, text, addendum))?;
+    // Text spanning several lines is padded so that the right edge of the box is straight.
+    let n_lines = text.split('\n').count();
+    for (i, (line, raw_line)) in text.split('\n').zip(raw_text.split('\n')).enumerate() {
+        let is_last_line = i + 1 == n_lines;
+        if text_style.is_raw {
+            write!(writer, "{}", raw_line)?;
+        } else {
+            write!(
+                writer,
+                "{}",
+                paint_text(text_style, line, if is_last_line { addendum } else { "" })
+            )?;
+        }
+        if n_lines > 1 {
+            let padding = box_width.saturating_sub(ansi::measure_text_width(line));
+            write!(writer, "{}", " ".repeat(padding))?;
+        }
+        write!(writer, "{}", decoration_style.paint(vertical))?;
+        if !is_last_line {
+            writeln!(writer)?;
+        }
     }
-    write!(
-        writer,
-        "{}\n{}",
-        decoration_style.paint(vertical),
-        decoration_style.paint(&horizontal_edge),
-    )
+    write!(writer, "\n{}", decoration_style.paint(&horizontal_edge))
+}
+
+// The width of the widest line of `text`.
+fn measure_box_width(text: &str) -> usize {
+    text.split('\n')
+        .map(ansi::measure_text_width)
+        .max()
+        .unwrap_or(0)
 }
@@ -0,0 +1,285 @@
+use std::path::Path;
+
+use regex::Regex;
+
+use crate::ansi;
+use crate::delta::StateMachine;
+use crate::handlers::{binary, diff_header, diff_header_hg, diff_header_svn};
+use crate::utils;
+
+/// The patterns selecting the files whose diffs are displayed, see --include-files and
+/// --exclude-files.
+#[derive(Clone, Debug, Default)]
+pub struct FileFilter {
+    include: Vec<Regex>,
+    exclude: Vec<Regex>,
+}
+
+/// The lines of a commit held back by --hide-empty-commits until it is known whether any of its
+/// files are displayed.
+#[derive(Debug, Default)]
+pub struct HeldCommit {
+    lines: Vec<Vec<u8>>,
+    has_hidden_files: bool,
+}
+
+impl FileFilter {
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self, String> {
+        Ok(Self {
+            include: parse_patterns(include)?,
+            exclude: parse_patterns(exclude)?,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// Return true if the diff of a file with these paths is displayed: a renamed file is
+    /// displayed if either its old or its new path passes the filter.
+    pub fn is_displayed<S: AsRef<str>>(&self, paths: &[S]) -> bool {
+        paths
+            .iter()
+            .map(|path| path.as_ref())
+            .filter(|path| *path != "/dev/null" && !path.is_empty())
+            .any(|path| self.path_passes(path))
+    }
+
+    fn path_passes(&self, path: &str) -> bool {
+        let name = Path::new(path)
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy();
+        let matches = |pattern: &Regex| pattern.is_match(path) || pattern.is_match(&name);
+        (self.include.is_empty() || self.include.iter().any(matches))
+            && !self.exclude.iter().any(matches)
+    }
+}
+
+// A pattern is a glob, matched against the path of a file and against its name, unless it starts
+// with "regex:", in which case the rest is a regular expression searched for in the path.
+fn parse_patterns(patterns: &[String]) -> Result<Vec<Regex>, String> {
+    patterns
+        .iter()
+        .map(|pattern| match pattern.strip_prefix("regex:") {
+            Some(regex) => Regex::new(regex),
+            None => utils::path::glob_to_regex(pattern),
+        })
+        .collect::<Result<_, _>>()
+        .map_err(|err| err.to_string())
+}
+
+impl<'a> StateMachine<'a> {
+    /// Remove the lines of the diff of a file excluded by --include-files and --exclude-files
+    /// from the start of `file_lines`, which were read up to the next diff line. With
+    /// --hide-empty-commits, the lines from a commit line onwards are held back until a file of
+    /// the commit is displayed, and dropped if all of its files are hidden.
+    pub fn filter_file_lines(&mut self, file_lines: &mut Vec<Vec<u8>>) -> std::io::Result<()> {
+        let lines: Vec<String> = file_lines
+            .iter()
+            .map(|line| ansi::strip_ansi_codes(&String::from_utf8_lossy(line)))
+            .collect();
+        let n_file_lines = if matches!(lines.first(), Some(line) if line.starts_with("diff ")) {
+            count_file_lines(&lines, &diff_header::parse_hunk_lines(file_lines))
+        } else {
+            0
+        };
+        let mut kept_lines = Vec::new();
+        let mut rest = file_lines.drain(..).enumerate();
+        if n_file_lines > 0 {
+            let file_part: Vec<Vec<u8>> =
+                rest.by_ref().take(n_file_lines).map(|(_, l)| l).collect();
+            if self
+                .config
+                .file_filter
+                .is_displayed(&get_file_paths(&lines[..n_file_lines]))
+            {
+                self.release_held_commit()?;
+                kept_lines = file_part;
+            } else if let Some(held_commit) = self.held_commit.as_mut() {
+                held_commit.has_hidden_files = true;
+            }
+        }
+        for (i, line) in rest {
+            if self.config.hide_empty_commits && self.is_commit_line(&lines[i]) {
+                self.end_held_commit()?;
+                self.held_commit = Some(HeldCommit::default());
+            }
+            match self.held_commit.as_mut() {
+                Some(held_commit) => held_commit.lines.push(line),
+                None => kept_lines.push(line),
+            }
+        }
+        *file_lines = kept_lines;
+        Ok(())
+    }
+
+    /// Drop the held lines of a commit if it has hidden files and none displayed, otherwise
+    /// handle them.
+    pub fn end_held_commit(&mut self) -> std::io::Result<()> {
+        match self.held_commit.as_ref() {
+            Some(held_commit) if held_commit.has_hidden_files => {
+                self.held_commit = None;
+                Ok(())
+            }
+            _ => self.release_held_commit(),
+        }
+    }
+
+    fn release_held_commit(&mut self) -> std::io::Result<()> {
+        if let Some(held_commit) = self.held_commit.take() {
+            for line in held_commit.lines {
+                self.handle_line(&line)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// The number of lines at the start of `lines` which belong to the diff of the file: its header,
+// and its hunks or binary patch.
+pub(crate) fn count_file_lines(lines: &[String], hunk_lines: &[diff_header::HunkLine]) -> usize {
+    let mut n_file_lines = 0;
+    let mut in_hunks = false;
+    let mut in_binary_patch = false;
+    for (i, (line, hunk_line)) in lines.iter().zip(hunk_lines).enumerate() {
+        match hunk_line {
+            diff_header::HunkLine::Outside if in_hunks => break,
+            diff_header::HunkLine::Outside => {
+                in_binary_patch |= line == "GIT binary patch";
+                if !(i == 0
+                    || is_file_header_line(line)
+                    || in_binary_patch && binary::is_binary_patch_line(line))
+                {
+                    break;
+                }
+            }
+            _ => in_hunks = true,
+        }
+        n_file_lines = i + 1;
+    }
+    n_file_lines
+}
+
+fn is_file_header_line(line: &str) -> bool {
+    [
+        "index ",
+        "old mode ",
+        "new mode ",
+        "deleted file mode ",
+        "new file mode ",
+        "similarity index ",
+        "dissimilarity index ",
+        "rename from ",
+        "rename to ",
+        "copy from ",
+        "copy to ",
+        "Binary files ",
+        "--- ",
+        "+++ ",
+        "GIT binary patch",
+    ]
+    .iter()
+    .any(|prefix| line.starts_with(prefix))
+        || diff_header_svn::is_svn_separator_line(line)
+}
+
+// The old and new paths of the file named in its header lines.
+fn get_file_paths(header_lines: &[String]) -> Vec<String> {
+    let git_diff_name = ["diff --git ", "diff --cc ", "diff --combined "]
+        .iter()
+        .any(|prefix| header_lines[0].starts_with(prefix))
+        || diff_header_hg::is_hg_diff_line(&header_lines[0]);
+    let mut paths = Vec::new();
+    for line in header_lines {
+        if let Some((minus_file, plus_file)) =
+            diff_header::parse_binary_files_differ_line(line, git_diff_name)
+        {
+            paths.push(minus_file);
+            paths.push(plus_file);
+        } else if [
+            "--- ",
+            "+++ ",
+            "rename from ",
+            "rename to ",
+            "copy from ",
+            "copy to ",
+        ]
+        .iter()
+        .any(|prefix| line.starts_with(prefix))
+        {
+            let (path, _) = diff_header::parse_diff_header_line(line, git_diff_name);
+            paths.push(path);
+        }
+    }
+    if let Some(path) = diff_header::get_repeated_file_path_from_diff_line(&header_lines[0]) {
+        paths.push(path);
+    }
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::fixtures::GIT_LOG_THREE_COMMITS;
+    use crate::tests::integration_test_utils::DeltaTest;
+
+    #[test]
+    fn test_include_files() {
+        let output = DeltaTest::with_args(&["--include-files", "src/**"])
+            .with_input(GIT_LOG_THREE_COMMITS)
+            .expect_contains("\nsrc/lib.rs\n")
+            .expect_contains("mod c;")
+            .expect_contains("\nadded: src/new.rs\n")
+            // The commit whose files are all hidden is still displayed.
+            .expect_contains("Update tests only")
+            .output;
+        assert!(!output.contains("tests/test_lib.rs"));
+        assert!(!output.contains("Cargo.lock"));
+        assert!(!output.contains("fn test_lib"));
+    }
+
+    #[test]
+    fn test_exclude_files() {
+        let output = DeltaTest::with_args(&[
+            "--exclude-files",
+            "regex:^tests/",
+            "--exclude-files",
+            "*.lock",
+        ])
+        .with_input(GIT_LOG_THREE_COMMITS)
+        .expect_contains("\n src/lib.rs | 3 ++-\n")
+        .expect_contains("mod c;")
+        .expect_contains("Update tests only")
+        .output;
+        assert!(!output.contains("test_lib"));
+        assert!(!output.contains("version"));
+    }
+
+    #[test]
+    fn test_hide_empty_commits() {
+        let output = DeltaTest::with_args(&["--include-files", "src/**", "--hide-empty-commits"])
+            .with_input(GIT_LOG_THREE_COMMITS)
+            .expect_contains("Update lib and tests")
+            .expect_contains("mod c;")
+            .output;
+        assert!(!output.contains("Update tests only"));
+        assert!(!output.contains("8d8ba7c9e0c2b7e16a82d4fc31d9eb1bf2d6a2f5"));
+    }
+
+    #[test]
+    fn test_renamed_file_is_displayed_if_either_path_matches() {
+        let output = DeltaTest::with_args(&["--include-files", "src/old.rs"])
+            .with_input(GIT_LOG_THREE_COMMITS)
+            .expect_contains("\nrenamed: src/old.rs ⟶   src/moved.rs\n")
+            .output;
+        assert!(!output.contains("src/gone.rs"));
+    }
+
+    #[test]
+    fn test_files_are_not_filtered_in_color_only_mode() {
+        let output = DeltaTest::with_args(&["--include-files", "src/**", "--color-only"])
+            .with_input(GIT_LOG_THREE_COMMITS)
+            .output;
+        assert_eq!(output, GIT_LOG_THREE_COMMITS);
+    }
+}
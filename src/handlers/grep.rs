@@ -1,4 +1,5 @@
 use std::borrow::Cow;
+use std::io::Write;
 
 use lazy_static::lazy_static;
 use regex::Regex;
@@ -6,6 +7,8 @@ use serde::Deserialize;
 use unicode_segmentation::UnicodeSegmentation;
 
 use crate::ansi;
+use crate::cli;
+use crate::config;
 use crate::delta::{State, StateMachine};
 use crate::handlers::{self, ripgrep_json};
 use crate::paint::{self, expand_tabs, BgShouldFill, StyleSectionSpecifier};
@@ -16,6 +19,7 @@ use crate::utils::process;
 pub struct GrepLine<'b> {
     pub path: Cow<'b, str>,
     pub line_number: Option<usize>,
+    pub column: Option<usize>,
     pub line_type: LineType,
     pub code: Cow<'b, str>,
     pub submatches: Option<Vec<(usize, usize)>>,
@@ -36,10 +40,6 @@ struct GrepOutputConfig {
     pad_line_number: bool,
 }
 
-lazy_static! {
-    static ref OUTPUT_CONFIG: GrepOutputConfig = make_output_config();
-}
-
 impl<'a> StateMachine<'a> {
     // If this is a line of git grep output then render it accordingly.
     pub fn handle_grep_line(&mut self) -> std::io::Result<bool> {
@@ -55,19 +55,33 @@ impl<'a> StateMachine<'a> {
                     return Ok(handled_line);
                 }
 
-                // Emit syntax-highlighted code
+                // Emit syntax-highlighted code. The highlighter is reset for each line since grep
+                // lines are not contiguous.
                 // TODO: Determine the language less frequently, e.g. only when the file changes.
-                if let Some(lang) = handlers::diff_header::get_extension(&grep_line.path)
-                    .or(self.config.default_language.as_deref())
-                {
-                    self.painter.set_syntax(Some(lang));
-                    self.painter.set_highlighter();
+                if self.config.grep_syntax_highlighting {
+                    if let Some(lang) = handlers::diff_header::get_extension(&grep_line.path)
+                        .or(self.config.default_language.as_deref())
+                    {
+                        self.painter.set_syntax(Some(lang));
+                        self.painter.set_highlighter();
+                    }
                 }
                 self.state = State::Grep;
+                let output_config = make_output_config();
+
+                // rg --json output has no separator lines, so emit them where rg would.
+                if grep_line.submatches.is_some()
+                    && is_start_of_ripgrep_json_group(
+                        &grep_line,
+                        &mut self.previous_ripgrep_json_line,
+                    )
+                {
+                    write_grep_context_separator(self.painter.writer, "--", self.config)?;
+                }
 
                 match (
                     &grep_line.line_type,
-                    OUTPUT_CONFIG.render_context_header_as_hunk_header,
+                    output_config.render_context_header_as_hunk_header,
                 ) {
                     // Emit context header line
                     (LineType::ContextHeader, true) => handlers::hunk_header::write_hunk_header(
@@ -76,6 +90,7 @@ impl<'a> StateMachine<'a> {
                         &mut self.painter,
                         &self.line,
                         &grep_line.path,
+                        None,
                         self.config,
                     )?,
                     _ => {
@@ -85,7 +100,7 @@ impl<'a> StateMachine<'a> {
                                 "{}",
                                 match (
                                     &grep_line.line_type,
-                                    OUTPUT_CONFIG.add_navigate_marker_to_matches
+                                    output_config.add_navigate_marker_to_matches
                                 ) {
                                     (LineType::Match, true) => "• ",
                                     (_, true) => "  ",
@@ -119,8 +134,10 @@ impl<'a> StateMachine<'a> {
                             "{}",
                             paint::paint_file_path_with_line_number(
                                 grep_line.line_number,
+                                grep_line.column,
+                                None,
                                 &grep_line.path,
-                                OUTPUT_CONFIG.pad_line_number,
+                                output_config.pad_line_number,
                                 separator,
                                 true,
                                 Some(self.config.grep_file_style),
@@ -138,7 +155,13 @@ impl<'a> StateMachine<'a> {
                                     // expansion must come after JSON parsing.
                                     // (At the time of writing, we are in this
                                     // arm iff we are handling `ripgrep --json`
-                                    // output.)
+                                    // output.) The submatch byte offsets must
+                                    // be shifted accordingly.
+                                    let submatches = expand_tabs_in_offsets(
+                                        &grep_line.code,
+                                        submatches,
+                                        self.config.tab_width,
+                                    );
                                     grep_line.code = paint::expand_tabs(
                                         grep_line.code.graphemes(true),
                                         self.config.tab_width,
@@ -146,7 +169,7 @@ impl<'a> StateMachine<'a> {
                                     .into();
                                     make_style_sections(
                                         &grep_line.code,
-                                        submatches,
+                                        &submatches,
                                         self.config.grep_match_word_style,
                                         self.config.grep_match_line_style,
                                     )
@@ -174,6 +197,9 @@ impl<'a> StateMachine<'a> {
                                         ),
                                     )
                                 }
+                                (LineType::ContextHeader, _) => StyleSectionSpecifier::Style(
+                                    self.config.grep_function_context_style,
+                                ),
                                 _ => StyleSectionSpecifier::Style(
                                     self.config.grep_context_line_style,
                                 ),
@@ -187,12 +213,76 @@ impl<'a> StateMachine<'a> {
                     }
                 }
                 handled_line = true
+            } else if matches!(self.state, State::Grep) && is_context_separator_line(&self.line) {
+                write_grep_context_separator(self.painter.writer, &self.line, self.config)?;
+                handled_line = true
             }
         }
         Ok(handled_line)
     }
 }
 
+// Whether a line of rg --json output starts a new group of lines, i.e. whether rg would emit a
+// separator line before it. Groups are only separated when context lines are requested, and then a
+// group which is not contiguous with the previous one starts with or follows a context line (unless
+// it is at the end of the file).
+fn is_start_of_ripgrep_json_group(
+    grep_line: &GrepLine,
+    previous_line: &mut Option<(String, Option<usize>, LineType)>,
+) -> bool {
+    let previous = previous_line.replace((
+        grep_line.path.to_string(),
+        grep_line.line_number,
+        grep_line.line_type,
+    ));
+    match previous {
+        Some((path, line_number, line_type)) => {
+            let is_contiguous =
+                path == grep_line.path && line_number.map(|n| n + 1) == grep_line.line_number;
+            !is_contiguous
+                && (line_type == LineType::Context || grep_line.line_type == LineType::Context)
+        }
+        None => false,
+    }
+}
+
+fn write_grep_context_separator(
+    writer: &mut dyn Write,
+    separator: &str,
+    config: &config::Config,
+) -> std::io::Result<()> {
+    let separator = match config.grep_context_separator.as_str() {
+        "rule" => {
+            let width = match config.decorations_width.get() {
+                cli::Width::Fixed(width) => width,
+                cli::Width::Variable => config.available_terminal_width.get(),
+            };
+            box_drawing::light::HORIZONTAL.repeat(width)
+        }
+        "keep" => separator.to_string(),
+        replacement => replacement.to_string(),
+    };
+    writeln!(
+        writer,
+        "{}",
+        config.grep_context_separator_style.paint(separator)
+    )
+}
+
+// The line separating groups of lines of grep output: "--", or the separator given by
+// `rg --context-separator`.
+fn is_context_separator_line(line: &str) -> bool {
+    match &*process::calling_process() {
+        process::CallingProcess::OtherGrep(command_line) => {
+            command_line
+                .long_option_value("--context-separator")
+                .unwrap_or("--")
+                == line
+        }
+        _ => line == "--",
+    }
+}
+
 fn make_style_sections<'a>(
     line: &'a str,
     submatches: &[(usize, usize)],
@@ -202,7 +292,15 @@ fn make_style_sections<'a>(
     let mut sections = Vec::new();
     let mut curr = 0;
     for (start_, end_) in submatches {
-        let (start, end) = (*start_, *end_);
+        let (start, end) = (*start_, (*end_).min(line.len()));
+        // Skip offsets which do not fit the line, rather than panicking.
+        if start < curr
+            || start > end
+            || !line.is_char_boundary(start)
+            || !line.is_char_boundary(end)
+        {
+            continue;
+        }
         if start > curr {
             sections.push((non_match_style, &line[curr..start]))
         };
@@ -215,6 +313,24 @@ fn make_style_sections<'a>(
     StyleSectionSpecifier::StyleSections(sections)
 }
 
+// Map byte offsets into `line` to byte offsets into `line` with its tabs expanded.
+fn expand_tabs_in_offsets(
+    line: &str,
+    offsets: &[(usize, usize)],
+    tab_width: usize,
+) -> Vec<(usize, usize)> {
+    let expand = |offset: usize| {
+        let n_tabs = line
+            .get(..offset)
+            .map_or(0, |prefix| prefix.matches('\t').count());
+        offset + n_tabs * tab_width.saturating_sub(1)
+    };
+    offsets
+        .iter()
+        .map(|(start, end)| (expand(*start), expand(*end)))
+        .collect()
+}
+
 // Return style sections describing colors received from git.
 fn get_code_style_sections<'b>(
     raw_line: &'b str,
@@ -225,7 +341,10 @@ fn get_code_style_sections<'b>(
     if let Some(raw_code_start) = ansi::ansi_preserving_index(
         raw_line,
         match grep.line_number {
-            Some(n) => format!("{}:{}:", grep.path, n).len(),
+            Some(n) => match grep.column {
+                Some(column) => format!("{}:{}:{}:", grep.path, n, column).len(),
+                None => format!("{}:{}:", grep.path, n).len(),
+            },
             None => grep.path.len() + 1,
         },
     ) {
@@ -430,19 +549,47 @@ pub fn parse_grep_line(line: &str) -> Option<GrepLine> {
         ripgrep_json::parse_line(line)
     } else {
         match &*process::calling_process() {
-            process::CallingProcess::GitGrep(_) | process::CallingProcess::OtherGrep => [
+            process::CallingProcess::GitGrep(command_line)
+            | process::CallingProcess::OtherGrep(command_line) => [
                 &*GREP_LINE_REGEX_ASSUMING_FILE_EXTENSION_AND_LINE_NUMBER,
                 &*GREP_LINE_REGEX_ASSUMING_FILE_EXTENSION_NO_SPACES,
                 &*GREP_LINE_REGEX_ASSUMING_FILE_EXTENSION,
                 &*GREP_LINE_REGEX_ASSUMING_NO_INTERNAL_SEPARATOR_CHARS,
             ]
             .iter()
-            .find_map(|regex| _parse_grep_line(*regex, line)),
+            .find_map(|regex| _parse_grep_line(regex, line))
+            .map(|grep_line| {
+                if command_line.long_options.contains("--column")
+                    || command_line.long_options.contains("--vimgrep")
+                {
+                    parse_grep_line_column(grep_line)
+                } else {
+                    grep_line
+                }
+            }),
             _ => None,
         }
     }
 }
 
+/// With `git grep --column`, `rg --column` or `rg --vimgrep` the line number of a match is
+/// followed by the column: "src/co-7-fig.rs:7:3:xxx". Move the column from the code to the
+/// `column` field.
+fn parse_grep_line_column<'b>(mut grep_line: GrepLine<'b>) -> GrepLine<'b> {
+    if let (LineType::Match, Some(_), Cow::Borrowed(code)) =
+        (grep_line.line_type, grep_line.line_number, &grep_line.code)
+    {
+        let code: &'b str = code;
+        if let Some((column, rest)) = code.split_once(':') {
+            if let Ok(column) = column.parse() {
+                grep_line.column = Some(column);
+                grep_line.code = Cow::Borrowed(rest);
+            }
+        }
+    }
+    grep_line
+}
+
 pub fn _parse_grep_line<'b>(regex: &Regex, line: &'b str) -> Option<GrepLine<'b>> {
     let caps = regex.captures(line)?;
     let file = caps.get(1).unwrap().as_str().into();
@@ -466,6 +613,7 @@ pub fn _parse_grep_line<'b>(regex: &Regex, line: &'b str) -> Option<GrepLine<'b>
     Some(GrepLine {
         path: file,
         line_number: *line_number,
+        column: None,
         line_type: *line_type,
         code,
         submatches: None,
@@ -474,7 +622,14 @@ pub fn _parse_grep_line<'b>(regex: &Regex, line: &'b str) -> Option<GrepLine<'b>
 
 #[cfg(test)]
 mod tests {
-    use crate::handlers::grep::{parse_grep_line, GrepLine, LineType};
+    use unicode_segmentation::UnicodeSegmentation;
+
+    use crate::handlers::grep::{
+        expand_tabs_in_offsets, make_style_sections, parse_grep_line, GrepLine, LineType,
+    };
+    use crate::paint::{self, StyleSectionSpecifier};
+    use crate::style::Style;
+    use crate::tests::integration_test_utils::DeltaTest;
     use crate::utils::process::tests::FakeParentArgs;
 
     #[test]
@@ -487,6 +642,7 @@ mod tests {
             Some(GrepLine {
                 path: "src/co-7-fig.rs".into(),
                 line_number: None,
+                column: None,
                 line_type: LineType::Match,
                 code: "xxx".into(),
                 submatches: None,
@@ -498,6 +654,7 @@ mod tests {
             Some(GrepLine {
                 path: "src/config.rs".into(),
                 line_number: None,
+                column: None,
                 line_type: LineType::Match,
                 code: "use crate::minusplus::MinusPlus;".into(),
                 submatches: None,
@@ -511,6 +668,7 @@ mod tests {
             Some(GrepLine {
                 path: "src/config.rs".into(),
                 line_number: None,
+                column: None,
                 line_type: LineType::Match,
                 code: "    pub line_numbers_style_minusplus: MinusPlus<Style>,".into(),
                 submatches: None,
@@ -522,6 +680,7 @@ mod tests {
             Some(GrepLine {
                 path: "src/con-fig.rs".into(),
                 line_number: None,
+                column: None,
                 line_type: LineType::Match,
                 code: "use crate::minusplus::MinusPlus;".into(),
                 submatches: None,
@@ -535,6 +694,7 @@ mod tests {
             Some(GrepLine {
                 path: "src/con-fig.rs".into(),
                 line_number: None,
+                column: None,
                 line_type: LineType::Match,
                 code: "    pub line_numbers_style_minusplus: MinusPlus<Style>,".into(),
                 submatches: None,
@@ -548,6 +708,7 @@ mod tests {
             Some(GrepLine {
                 path: "src/de lta.rs".into(),
                 line_number: None,
+                column: None,
                 line_type: LineType::Match,
                 code: "pub fn delta<I>(lines: ByteLines<I>, writer: &mut dyn Write, config: &Config) -> std::io::Result<()>".into(),
                 submatches: None,
@@ -561,6 +722,7 @@ mod tests {
             Some(GrepLine {
                 path: "src/de lta.rs".into(),
                 line_number: None,
+                column: None,
                 line_type: LineType::Match,
                 code: "    pub fn new(writer: &'a mut dyn Write, config: &'a Config) -> Self {".into(),
                 submatches: None,
@@ -579,6 +741,7 @@ mod tests {
             Some(GrepLine {
                 path: "src/co-7-fig.rs".into(),
                 line_number: Some(7),
+                column: None,
                 line_type: LineType::Match,
                 code: "xxx".into(),
                 submatches: None,
@@ -590,6 +753,7 @@ mod tests {
             Some(GrepLine {
                 path: "src/config.rs".into(),
                 line_number: Some(21),
+                column: None,
                 line_type: LineType::Match,
                 code: "use crate::minusplus::MinusPlus;".into(),
                 submatches: None,
@@ -603,6 +767,7 @@ mod tests {
             Some(GrepLine {
                 path: "src/config.rs".into(),
                 line_number: Some(95),
+                column: None,
                 line_type: LineType::Match,
                 code: "    pub line_numbers_style_minusplus: MinusPlus<Style>,".into(),
                 submatches: None,
@@ -614,6 +779,7 @@ mod tests {
             Some(GrepLine {
                 path: "Makefile".into(),
                 line_number: Some(10),
+                column: None,
                 line_type: LineType::Match,
                 code: "test: unit-test end-to-end-test".into(),
                 submatches: None,
@@ -627,6 +793,7 @@ mod tests {
             Some(GrepLine {
                 path: "Makefile".into(),
                 line_number: Some(16),
+                column: None,
                 line_type: LineType::Match,
                 code: "    ./tests/test_raw_output_matches_git_on_full_repo_history".into(),
                 submatches: None,
@@ -648,6 +815,7 @@ mod tests {
             Some(GrepLine {
                 path: "etc/examples/119-within-line-edits".into(),
                 line_number: Some(4),
+                column: None,
                 line_type: LineType::Match,
                 code: "repo=$(mktemp -d)".into(),
                 submatches: None,
@@ -666,6 +834,7 @@ mod tests {
             Some(GrepLine {
                 path: "etc/META-INF/foo.properties".into(),
                 line_number: Some(4),
+                column: None,
                 line_type: LineType::Match,
                 code: "value=hi-there".into(),
                 submatches: None,
@@ -673,6 +842,69 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_grep_n_column_match() {
+        let _args = FakeParentArgs::for_scope("git grep -n --column pattern");
+
+        assert_eq!(
+            parse_grep_line("src/co-7-fig.rs:7:3:xxx: yyy"),
+            Some(GrepLine {
+                path: "src/co-7-fig.rs".into(),
+                line_number: Some(7),
+                column: Some(3),
+                line_type: LineType::Match,
+                code: "xxx: yyy".into(),
+                submatches: None,
+            })
+        );
+        // Context lines have no column.
+        assert_eq!(
+            parse_grep_line("src/co-7-fig.rs-8-12:00"),
+            Some(GrepLine {
+                path: "src/co-7-fig.rs".into(),
+                line_number: Some(8),
+                column: None,
+                line_type: LineType::Context,
+                code: "12:00".into(),
+                submatches: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_rg_vimgrep_match() {
+        let _args = FakeParentArgs::for_scope("rg --vimgrep pattern");
+
+        assert_eq!(
+            parse_grep_line("src/config.rs:47:9:    pub commit_regex: Regex,"),
+            Some(GrepLine {
+                path: "src/config.rs".into(),
+                line_number: Some(47),
+                column: Some(9),
+                line_type: LineType::Match,
+                code: "    pub commit_regex: Regex,".into(),
+                submatches: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_grep_n_without_column_option() {
+        let _args = FakeParentArgs::for_scope("rg -n pattern");
+
+        assert_eq!(
+            parse_grep_line("src/config.rs:47:12:00"),
+            Some(GrepLine {
+                path: "src/config.rs".into(),
+                line_number: Some(47),
+                column: None,
+                line_type: LineType::Match,
+                code: "12:00".into(),
+                submatches: None,
+            })
+        );
+    }
+
     #[test]
     fn test_parse_grep_no_match() {
         let fake_parent_grep_command =
@@ -684,6 +916,7 @@ mod tests {
             Some(GrepLine {
                 path: "src/co-7-fig.rs".into(),
                 line_number: None,
+                column: None,
                 line_type: LineType::Context,
                 code: "xxx".into(),
                 submatches: None,
@@ -695,6 +928,7 @@ mod tests {
             Some(GrepLine {
                 path: "src/config.rs".into(),
                 line_number: None,
+                column: None,
                 line_type: LineType::Context,
                 code: "    pub available_terminal_width: usize,".into(),
                 submatches: None,
@@ -706,6 +940,7 @@ mod tests {
             Some(GrepLine {
                 path: "src/con-fig.rs".into(),
                 line_number: None,
+                column: None,
                 line_type: LineType::Context,
                 code: "use crate::minusplus::MinusPlus;".into(),
                 submatches: None,
@@ -717,6 +952,7 @@ mod tests {
             Some(GrepLine {
                 path: "de-lta.rs".into(),
                 line_number: None,
+                column: None,
                 line_type: LineType::Context,
                 code: "            if self.source == Source::Unknown {".into(),
                 submatches: None,
@@ -728,6 +964,7 @@ mod tests {
             Some(GrepLine {
                 path: "aaa/bbb.scala".into(),
                 line_number: None,
+                column: None,
                 line_type: LineType::Context,
                 code: r#"              s"xxx.yyy.zzz: $ccc ddd""#.into(),
                 submatches: None,
@@ -739,6 +976,7 @@ mod tests {
             Some(GrepLine {
                 path: "aaa/bbb.scala".into(),
                 line_number: None,
+                column: None,
                 line_type: LineType::Context,
                 code: r#"  val atRegex = Regex.compile("(@.*)|(-shdw@.*)""#.into(),
                 submatches: None,
@@ -758,6 +996,7 @@ mod tests {
             Some(GrepLine {
                 path: "src/co-7-fig.rs".into(),
                 line_number: Some(7),
+                column: None,
                 line_type: LineType::Context,
                 code: "xxx".into(),
                 submatches: None,
@@ -769,6 +1008,7 @@ mod tests {
             Some(GrepLine {
                 path: "src/config.rs".into(),
                 line_number: Some(58),
+                column: None,
                 line_type: LineType::Context,
                 code: "    pub available_terminal_width: usize,".into(),
                 submatches: None,
@@ -780,6 +1020,7 @@ mod tests {
             Some(GrepLine {
                 path: "foo.rs".into(),
                 line_number: Some(12),
+                column: None,
                 line_type: LineType::Context,
                 code: r#"  .x-"#.into(),
                 submatches: None,
@@ -791,6 +1032,7 @@ mod tests {
             Some(GrepLine {
                 path: "foo.rs".into(),
                 line_number: Some(12),
+                column: None,
                 line_type: LineType::Context,
                 code: r#".x-"#.into(),
                 submatches: None,
@@ -809,6 +1051,7 @@ mod tests {
             Some(GrepLine {
                 path: "Makefile".into(),
                 line_number: None,
+                column: None,
                 line_type: LineType::Match,
                 code: "xxx".into(),
                 submatches: None,
@@ -827,6 +1070,7 @@ mod tests {
             Some(GrepLine {
                 path: "Makefile".into(),
                 line_number: Some(7),
+                column: None,
                 line_type: LineType::Match,
                 code: "xxx".into(),
                 submatches: None,
@@ -847,6 +1091,7 @@ mod tests {
             Some(GrepLine {
                 path: "src/config.rs".into(),
                 line_number: None,
+                column: None,
                 line_type: LineType::ContextHeader,
                 code: "pub struct Config {".into(),
                 submatches: None,
@@ -867,6 +1112,7 @@ mod tests {
             Some(GrepLine {
                 path: "src/config.rs".into(),
                 line_number: Some(57),
+                column: None,
                 line_type: LineType::ContextHeader,
                 code: "pub struct Config {".into(),
                 submatches: None,
@@ -900,4 +1146,121 @@ mod tests {
         let apparently_grep_output = "src/co-7-fig.rs:xxx";
         assert_eq!(parse_grep_line(apparently_grep_output), None);
     }
+
+    #[test]
+    fn test_make_style_sections_after_tab_expansion() {
+        let code = "\tlet x\t= 1;";
+        let submatches = expand_tabs_in_offsets(code, &[(1, 4), (7, 8)], 4);
+        assert_eq!(submatches, vec![(4, 7), (13, 14)]);
+        let expanded = paint::expand_tabs(code.graphemes(true), 4);
+        let style = Style::default();
+        match make_style_sections(&expanded, &submatches, style, style) {
+            StyleSectionSpecifier::StyleSections(sections) => {
+                let sections: Vec<&str> = sections.iter().map(|(_, s)| *s).collect();
+                assert_eq!(sections, vec!["    ", "let", " x    ", "=", " 1;"]);
+            }
+            _ => panic!("expected style sections"),
+        }
+    }
+
+    #[test]
+    fn test_make_style_sections_skips_invalid_offsets() {
+        let style = Style::default();
+        match make_style_sections("caf\u{e9}", &[(4, 5), (9, 12)], style, style) {
+            StyleSectionSpecifier::StyleSections(sections) => {
+                let sections: Vec<&str> = sections.iter().map(|(_, s)| *s).collect();
+                assert_eq!(sections, vec!["caf\u{e9}"]);
+            }
+            _ => panic!("expected style sections"),
+        }
+    }
+
+    #[test]
+    fn test_grep_context_separator() {
+        let input = "src/a.rs:1:foo\n--\nsrc/a.rs:9:foo\n";
+        DeltaTest::with_args(&["--width", "10"])
+            .with_calling_process("git grep -n -C1 foo")
+            .with_input(input)
+            .expect_contains("src/a.rs:1:foo\n──────────\nsrc/a.rs:9:foo");
+        DeltaTest::with_args(&["--grep-context-separator", "keep"])
+            .with_calling_process("git grep -n -C1 foo")
+            .with_input(input)
+            .expect_contains("src/a.rs:1:foo\n--\nsrc/a.rs:9:foo");
+        DeltaTest::with_args(&["--grep-context-separator", "···"])
+            .with_calling_process("rg -n --context-separator ~~ foo")
+            .with_input("src/a.rs:1:foo\n~~\nsrc/a.rs:9:foo\n")
+            .expect_contains("src/a.rs:1:foo\n···\nsrc/a.rs:9:foo");
+        // Other lines which are not grep output are not separators.
+        DeltaTest::with_args(&["--grep-context-separator", "···"])
+            .with_calling_process("rg -n --context-separator=~~ foo")
+            .with_input("src/a.rs:1:foo\nnot grep output\n~~\nsrc/a.rs:9:foo\n")
+            .expect_contains("src/a.rs:1:foo\nnot grep output\n···\nsrc/a.rs:9:foo");
+    }
+
+    #[test]
+    fn test_grep_function_context_style() {
+        DeltaTest::with_args(&[
+            "--grep-function-context-style",
+            "blue",
+            "--grep-syntax-highlighting",
+            "false",
+        ])
+        .with_calling_process("git grep -n -W foo")
+        .explain_ansi()
+        .with_input("src/a.rs=1=fn main() {\nsrc/a.rs:2:    foo();\nsrc/a.rs-3-}\n")
+        .expect_contains("(blue)fn main() {(normal)\n");
+    }
+
+    #[test]
+    fn test_grep_context_line_style() {
+        let args = &[
+            "--grep-context-line-style",
+            "blue",
+            "--grep-context-separator",
+            "keep",
+            "--grep-syntax-highlighting",
+            "false",
+        ];
+        DeltaTest::with_args(args)
+            .with_calling_process("rg -n -C1 foo")
+            .explain_ansi()
+            .with_input("src/a.rs-1-bar\nsrc/a.rs:2:foo\n--\nsrc/a.rs-9-baz\n")
+            .expect_contains("(blue)src/a.rs(normal):(blue)1(normal):(blue)bar")
+            .expect_contains("(blue)2(normal):foo\n(dim normal)--(normal)\n");
+        // rg --json output has no separator lines: they are emitted where the line numbers of
+        // adjacent lines of context are not contiguous.
+        let json_line = |line_type, text, line_number| {
+            format!(
+                r#"{{"type":"{}","data":{{"path":{{"text":"src/a.rs"}},"lines":{{"text":"{}\n"}},"line_number":{},"absolute_offset":0,"submatches":[]}}}}"#,
+                line_type, text, line_number
+            )
+        };
+        let input = [
+            json_line("context", "bar", 1),
+            json_line("match", "foo", 2),
+            json_line("context", "baz", 3),
+            json_line("context", "qux", 9),
+            json_line("match", "foo", 10),
+        ]
+        .join("\n");
+        DeltaTest::with_args(args)
+            .with_calling_process("rg --json -C1 foo")
+            .with_input(&input)
+            .expect_contains("src/a.rs:3:baz\n--\nsrc/a.rs:9:qux\nsrc/a.rs:10:foo");
+    }
+
+    #[test]
+    fn test_grep_syntax_highlighting() {
+        let input = "src/a.rs:1:fn foo() {}\n";
+        DeltaTest::with_args(&[])
+            .with_calling_process("git grep -n foo")
+            .explain_ansi()
+            .with_input(input)
+            .expect_contains("(81)fn(231) (149)foo(231)() {}");
+        let output = DeltaTest::with_args(&["--grep-syntax-highlighting", "false"])
+            .with_calling_process("git grep -n foo")
+            .explain_ansi()
+            .with_input(input);
+        assert!(!output.output.contains("(81)fn"), "{}", output.output);
+    }
 }
@@ -5,7 +5,7 @@ use lazy_static::lazy_static;
 use crate::cli;
 use crate::config::{delta_unreachable, Config};
 use crate::delta::{DiffType, InMergeConflict, MergeParents, State, StateMachine};
-use crate::paint::{expand_tabs, prepare, prepare_raw_line};
+use crate::paint::{expand_tabs, has_space_before_tab, prepare, prepare_raw_line};
 use crate::style;
 use crate::utils::process::{self, CallingProcess};
 use unicode_segmentation::UnicodeSegmentation;
@@ -85,32 +85,56 @@ impl<'a> StateMachine<'a> {
                     // and flush the line buffers.
                     self.painter.paint_buffered_minus_and_plus_lines();
                 }
+                self.painter.paint_context_lines(true);
                 let n_parents = diff_type.n_parents();
                 let line = prepare(&self.line, n_parents, self.config);
                 let state = HunkMinus(diff_type, raw_line);
                 self.painter.minus_lines.push((line, state.clone()));
+                self.diff_summary.deletions += 1;
                 state
             }
             Some(HunkPlus(diff_type, raw_line)) => {
+                self.painter.paint_context_lines(true);
                 let n_parents = diff_type.n_parents();
                 let line = prepare(&self.line, n_parents, self.config);
+                let space_before_tab =
+                    has_space_before_tab(self.line.get(n_parents..).unwrap_or(""));
                 let state = HunkPlus(diff_type, raw_line);
                 self.painter.plus_lines.push((line, state.clone()));
+                self.painter
+                    .plus_lines_space_before_tab
+                    .push(space_before_tab);
+                self.diff_summary.insertions += 1;
                 state
             }
             Some(HunkZero(diff_type, raw_line)) => {
                 // We are in a zero (unchanged) line, therefore we have just exited a subhunk (a
                 // sequence of consecutive minus (removed) and/or plus (added) lines). Process that
                 // subhunk and flush the line buffers.
-                self.painter.paint_buffered_minus_and_plus_lines();
+                if !self.painter.minus_lines.is_empty() || !self.painter.plus_lines.is_empty() {
+                    self.painter.paint_buffered_minus_and_plus_lines();
+                    self.painter.context_lines_follow_change = true;
+                }
                 let n_parents = if is_word_diff() {
                     0
                 } else {
                     diff_type.n_parents()
                 };
                 let line = prepare(&self.line, n_parents, self.config);
+                let space_before_tab =
+                    has_space_before_tab(self.line.get(n_parents..).unwrap_or(""));
                 let state = State::HunkZero(diff_type, raw_line);
-                self.painter.paint_zero_line(&line, state.clone());
+                if self.painter.collects_context_lines() && !is_word_diff() {
+                    // Whether, and with which line numbers, the line is displayed is known once
+                    // the next change, or the end of the hunk, is reached.
+                    self.painter.context_lines.push((line, state.clone()));
+                    self.painter
+                        .context_lines_space_before_tab
+                        .push(space_before_tab);
+                } else {
+                    self.painter
+                        .paint_zero_line(&line, state.clone(), space_before_tab);
+                }
                 state
             }
             _ => {
@@ -139,9 +163,14 @@ fn maybe_raw_line(
     non_raw_styles: &[style::Style],
     config: &Config,
 ) -> Option<String> {
+    let inspect_raw_line = || {
+        style::line_has_style_other_than(raw_line, non_raw_styles)
+            || config.inspect_raw_lines_threshold > 0
+                && style::count_sections_with_style_other_than(raw_line, non_raw_styles)
+                    >= config.inspect_raw_lines_threshold
+    };
     let emit_raw_line = is_word_diff()
-        || config.inspect_raw_lines == cli::InspectRawLines::True
-            && style::line_has_style_other_than(raw_line, non_raw_styles)
+        || config.inspect_raw_lines == cli::InspectRawLines::True && inspect_raw_line()
         || state_style_is_raw;
     if emit_raw_line {
         Some(prepare_raw_line(raw_line, n_parents, config))
@@ -232,9 +261,11 @@ fn new_line_state(
         )
     };
     let maybe_zero_raw_line = || {
+        // The raw line differs from the line only if it contains ANSI escape sequences.
+        let preserve_ansi = config.preserve_context_ansi && new_raw_line != new_line;
         maybe_raw_line(
             new_raw_line,
-            config.zero_style.is_raw,
+            config.zero_style.is_raw || preserve_ansi,
             diff_type.n_parents(),
             &[],
             config,
@@ -352,6 +383,77 @@ mod tests {
                 );
         }
 
+        #[test]
+        fn test_preserve_context_ansi() {
+            let input = "\
+diff --git a/file b/file
+index 72943a1..f761ec1 100644
+--- a/file
++++ b/file
+@@ -1,2 +1,2 @@
+ ctx \x1b[33myellow\x1b[0m
+-aaa
++bbb
+";
+            DeltaTest::with_args(&["--preserve-context-ansi"])
+                .explain_ansi()
+                .with_input(input)
+                .expect_after_skip(
+                    7,
+                    "
+ctx (yellow)yellow(normal)
+(normal 52)aaa(normal)
+(231 22)bbb(normal)
+",
+                );
+            DeltaTest::with_args(&[])
+                .explain_ansi()
+                .with_input(input)
+                .expect_after_skip(
+                    7,
+                    "
+(231)ctx yellow(normal)
+(normal 52)aaa(normal)
+(231 22)bbb(normal)
+",
+                );
+        }
+
+        #[test]
+        fn test_inspect_raw_lines_threshold() {
+            // The added line was colored by a custom diff driver, on top of git's coloring.
+            let input = "\
+\x1b[1mdiff --git a/file b/file\x1b[m
+\x1b[1mindex 72943a1..f761ec1 100644\x1b[m
+\x1b[1m--- a/file\x1b[m
+\x1b[1m+++ b/file\x1b[m
+\x1b[36m@@ -1 +1 @@\x1b[m
+\x1b[31m-aaa\x1b[m
+\x1b[32m+\x1b[m\x1b[32mfn \x1b[m\x1b[34mbbb\x1b[m\x1b[32m() \x1b[m\x1b[33m{}\x1b[m\x1b[32m  \x1b[m\x1b[41m \x1b[m
+";
+            DeltaTest::with_args(&["--inspect-raw-lines-threshold", "2"])
+                .explain_ansi()
+                .with_input(input)
+                .expect_after_skip(
+                    7,
+                    "
+(normal 52)aaa(normal)
+(green)fn (blue)bbb(green)() (yellow){}(green)  (normal red) (normal)
+",
+                );
+            // A single section colored otherwise, or the whitespace error, is not enough.
+            DeltaTest::with_args(&["--inspect-raw-lines-threshold", "3"])
+                .explain_ansi()
+                .with_input(input)
+                .expect_after_skip(
+                    7,
+                    "
+(normal 52)aaa(normal)
+(231 22)fn bbb() {}   (normal)
+",
+                );
+        }
+
         #[test]
         fn test_hunk_line_style_raw_map_styles() {
             DeltaTest::with_args(&[
@@ -419,4 +521,111 @@ Date:   Sat Dec 11 17:08:56 2021 -0500
     [31m[-aaa-][m[32m{+bbb+}[m
 "#;
     }
+
+    mod hide_context {
+        use super::*;
+
+        #[test]
+        fn test_hide_context() {
+            DeltaTest::with_args(&["--hide-context"])
+                .with_input(GIT_DIFF_WITH_CONTEXT)
+                .expect_after_skip(
+                    7,
+                    "
+                    ⋯ 4 unchanged lines
+                    l5
+                    L5
+                    ⋯ 6 unchanged lines
+                    l12
+                    L12
+                    ⋯ 1 unchanged line",
+                );
+        }
+
+        #[test]
+        fn test_hide_context_keeps_lines_around_changes() {
+            DeltaTest::with_args(&["--hide-context=1", "--line-numbers"])
+                .with_input(GIT_DIFF_WITH_CONTEXT)
+                .expect_after_skip(
+                    7,
+                    "
+                    #indent_mark
+                    ⋯ 3 unchanged lines
+                     4⋮ 4│l4
+                     5⋮  │l5
+                      ⋮ 5│L5
+                     6⋮ 6│l6
+                    ⋯ 4 unchanged lines
+                    11⋮11│l11
+                    12⋮  │l12
+                      ⋮12│L12
+                    13⋮13│l13",
+                );
+        }
+
+        #[test]
+        fn test_hide_context_side_by_side() {
+            DeltaTest::with_args(&[
+                "--hide-context",
+                "--side-by-side",
+                "--width",
+                "40",
+                "--hidden-context-style",
+                "blue reverse",
+            ])
+            .with_input(GIT_DIFF_WITH_CONTEXT)
+            .expect_after_skip(
+                7,
+                "
+                #indent_mark
+                ⋯ 4 unchanged lines                     
+                │ 5│l5              │  │
+                │  │                │ 5│L5
+                ⋯ 6 unchanged lines                     
+                │12│l12             │  │
+                │  │                │12│L12
+                ⋯ 1 unchanged line                      ",
+            )
+            .expect_raw_contains(&format!(
+                "\x1b[7;34m⋯ 4 unchanged lines{}\x1b[0m",
+                " ".repeat(21)
+            ));
+        }
+
+        #[test]
+        fn test_hide_context_is_ignored_in_color_only_mode() {
+            DeltaTest::with_args(&["--hide-context", "--color-only"])
+                .with_input(GIT_DIFF_WITH_CONTEXT)
+                .expect_after_skip(
+                    5,
+                    "#partial
+                    #indent_mark
+                     l1
+                     l2",
+                );
+        }
+
+        const GIT_DIFF_WITH_CONTEXT: &str = "\
+diff --git a/file.txt b/file.txt
+index 1111111..2222222 100644
+--- a/file.txt
++++ b/file.txt
+@@ -1,13 +1,13 @@
+ l1
+ l2
+ l3
+ l4
+-l5
++L5
+ l6
+ l7
+ l8
+ l9
+ l10
+ l11
+-l12
++L12
+ l13
+";
+    }
 }
@@ -33,7 +33,7 @@ use crate::style::DecorationStyle;
 #[derive(Clone, Default, Debug, PartialEq)]
 pub struct ParsedHunkHeader {
     code_fragment: String,
-    line_numbers_and_hunk_lengths: Vec<(usize, usize)>,
+    pub(crate) line_numbers_and_hunk_lengths: Vec<(usize, usize)>,
 }
 
 impl<'a> StateMachine<'a> {
@@ -53,6 +53,12 @@ impl<'a> StateMachine<'a> {
         }
         let mut handled_line = false;
         if let Some(parsed_hunk_header) = parse_hunk_header(&self.line) {
+            if matches!(self.state, DiffHeader(_)) {
+                // The first hunk of a file.
+                self.diff_summary.files += 1;
+            }
+            // The previous hunk is complete.
+            self.painter.paint_buffered_minus_and_plus_lines();
             let diff_type = match &self.state {
                 DiffHeader(Combined(MergeParents::Unknown, InMergeConflict::No)) => {
                     // https://git-scm.com/docs/git-diff#_combined_diff_format
@@ -97,7 +103,11 @@ impl<'a> StateMachine<'a> {
                 .line_numbers_data
                 .as_mut()
                 .unwrap()
-                .initialize_hunk(line_numbers_and_hunk_lengths, self.plus_file.to_string());
+                .initialize_hunk(
+                    line_numbers_and_hunk_lengths,
+                    self.plus_file.to_string(),
+                    self.current_commit.clone(),
+                );
         }
 
         if self.config.hunk_header_style.is_raw {
@@ -111,16 +121,19 @@ impl<'a> StateMachine<'a> {
                 writeln!(self.painter.writer)?;
             }
 
+            // A removed file does not exist as of the commit.
+            let (file, commit) = if self.plus_file == "/dev/null" {
+                (&self.minus_file, None)
+            } else {
+                (&self.plus_file, self.current_commit.as_deref())
+            };
             write_hunk_header(
                 code_fragment,
                 line_numbers_and_hunk_lengths,
                 &mut self.painter,
                 line,
-                if self.plus_file == "/dev/null" {
-                    &self.minus_file
-                } else {
-                    &self.plus_file
-                },
+                file,
+                commit,
                 self.config,
             )?;
         };
@@ -152,7 +165,7 @@ lazy_static! {
 /// Given input like
 /// "@@ -74,15 +74,14 @@ pub fn delta("
 /// Return " pub fn delta(" and a vector of (line_number, hunk_length) tuples.
-fn parse_hunk_header(line: &str) -> Option<ParsedHunkHeader> {
+pub(crate) fn parse_hunk_header(line: &str) -> Option<ParsedHunkHeader> {
     if let Some(caps) = HUNK_HEADER_REGEX.captures(line) {
         let file_coordinates = &caps[1];
         let line_numbers_and_hunk_lengths = HUNK_HEADER_FILE_COORDINATE_REGEX
@@ -195,7 +208,7 @@ fn write_hunk_header_raw(
         &format!("{}{}", line, if pad { " " } else { "" }),
         &format!("{}{}", raw_line, if pad { " " } else { "" }),
         "",
-        &config.decorations_width,
+        &config.decorations_width.get(),
         config.hunk_header_style,
         decoration_ansi_term_style,
     )?;
@@ -208,6 +221,7 @@ pub fn write_hunk_header(
     painter: &mut Painter,
     line: &str,
     plus_file: &str,
+    commit: Option<&str>,
     config: &Config,
 ) -> std::io::Result<()> {
     let (mut draw_fn, _, decoration_ansi_term_style) =
@@ -222,7 +236,7 @@ pub fn write_hunk_header(
 
     let plus_line_number = line_numbers_and_hunk_lengths[line_numbers_and_hunk_lengths.len() - 1].0;
     let file_with_line_number =
-        paint_file_path_with_line_number(Some(plus_line_number), plus_file, config);
+        paint_file_path_with_line_number(Some(plus_line_number), plus_file, commit, config);
 
     if !line.is_empty() || !file_with_line_number.is_empty() {
         write_to_output_buffer(&file_with_line_number, line, painter, config);
@@ -231,7 +245,7 @@ pub fn write_hunk_header(
             &painter.output_buffer,
             &painter.output_buffer,
             "",
-            &config.decorations_width,
+            &config.decorations_width.get(),
             config.null_style,
             decoration_ansi_term_style,
         )?;
@@ -244,6 +258,7 @@ pub fn write_hunk_header(
 fn paint_file_path_with_line_number(
     line_number: Option<usize>,
     plus_file: &str,
+    commit: Option<&str>,
     config: &Config,
 ) -> String {
     let file_style = if config.hunk_header_style_include_file_path {
@@ -263,6 +278,8 @@ fn paint_file_path_with_line_number(
 
     paint::paint_file_path_with_line_number(
         line_number,
+        None,
+        commit,
         plus_file,
         false,
         ":",
@@ -299,7 +316,11 @@ fn write_to_output_buffer(
     if !line.is_empty() {
         painter.syntax_highlight_and_paint_line(
             &line,
-            StyleSectionSpecifier::Style(config.hunk_header_style),
+            StyleSectionSpecifier::Style(if config.color_only {
+                config.hunk_header_style
+            } else {
+                config.hunk_header_function_style
+            }),
             delta::State::HunkHeader(
                 DiffType::Unified,
                 ParsedHunkHeader::default(),
@@ -316,7 +337,7 @@ fn write_to_output_buffer(
 pub mod tests {
     use super::*;
     use crate::ansi::strip_ansi_codes;
-    use crate::tests::integration_test_utils;
+    use crate::tests::integration_test_utils::{self, DeltaTest};
 
     #[test]
     fn test_parse_hunk_header() {
@@ -393,7 +414,7 @@ pub mod tests {
         // This test confirms that `paint_file_path_with_line_number` returns a painted line number.
         let config = integration_test_utils::make_config_from_args(&[]);
 
-        let result = paint_file_path_with_line_number(Some(3), "some-file", &config);
+        let result = paint_file_path_with_line_number(Some(3), "some-file", None, &config);
 
         assert_eq!(result, "\u{1b}[34m3\u{1b}[0m");
     }
@@ -414,8 +435,12 @@ pub mod tests {
         let config = integration_test_utils::make_config_from_args(&["--features", "hyperlinks"]);
         let relative_path = PathBuf::from_iter(["some-dir", "some-file"]);
 
-        let result =
-            paint_file_path_with_line_number(Some(3), &relative_path.to_string_lossy(), &config);
+        let result = paint_file_path_with_line_number(
+            Some(3),
+            &relative_path.to_string_lossy(),
+            None,
+            &config,
+        );
 
         assert_eq!(
             result,
@@ -439,7 +464,7 @@ pub mod tests {
             "omit",
         ]);
 
-        let result = paint_file_path_with_line_number(Some(3), "some-file", &config);
+        let result = paint_file_path_with_line_number(Some(3), "some-file", None, &config);
 
         assert_eq!(result, "");
     }
@@ -459,7 +484,7 @@ pub mod tests {
             "hyperlinks",
         ]);
 
-        let result = paint_file_path_with_line_number(Some(3), "some-file", &config);
+        let result = paint_file_path_with_line_number(Some(3), "some-file", None, &config);
 
         assert_eq!(result, "");
     }
@@ -474,7 +499,7 @@ pub mod tests {
             "--navigate",
         ]);
 
-        let result = paint_file_path_with_line_number(Some(3), "δ some-file", &config);
+        let result = paint_file_path_with_line_number(Some(3), "δ some-file", None, &config);
 
         assert_eq!(result, "");
     }
@@ -488,6 +513,35 @@ pub mod tests {
         assert!(output.contains("@@@2021-12-05"));
     }
 
+    #[test]
+    fn test_hunk_header_function_style() {
+        DeltaTest::with_args(&[
+            "--hunk-header-style",
+            "line-number syntax",
+            "--hunk-header-line-number-style",
+            "red",
+            "--hunk-header-function-style",
+            "bold yellow",
+            "--hunk-header-decoration-style",
+            "omit",
+        ])
+        .explain_ansi()
+        .with_input(GIT_DIFF_WITH_FUNCTION_CONTEXT)
+        .expect_contains("(red)75(normal):(bold yellow) pub fn delta( (normal)");
+    }
+
+    const GIT_DIFF_WITH_FUNCTION_CONTEXT: &str = "\
+diff --git a/src/delta.rs b/src/delta.rs
+index 1111111..2222222 100644
+--- a/src/delta.rs
++++ b/src/delta.rs
+@@ -74,3 +75,3 @@ pub fn delta(
+ let a = 1;
+-let b = 2;
++let b = 3;
+ let c = 4;
+";
+
     const GIT_LOG_OUTPUT_WITH_NOT_A_HUNK_HEADER: &str = "\
 @@@2021-12-05
 
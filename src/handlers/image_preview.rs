@@ -0,0 +1,207 @@
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::delta::StateMachine;
+use crate::env::DeltaEnv;
+use crate::utils;
+
+/// The terminal graphics protocol used to display image previews, see --image-previews.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ImageProtocol {
+    // https://sw.kovidgoyal.net/kitty/graphics-protocol/
+    Kitty,
+    // https://iterm2.com/documentation-images.html
+    Iterm2,
+}
+
+// The kitty graphics protocol requires the image data to be sent in chunks of at most 4096 bytes.
+const KITTY_CHUNK_SIZE: usize = 4096;
+
+/// Return the protocol to use for --image-previews=`mode`, or None if previews are disabled:
+/// the output is not a terminal, or goes through less, which does not pass the escape sequences
+/// of images through.
+pub fn get_image_protocol(
+    mode: &str,
+    env: &DeltaEnv,
+    stdout_is_term: bool,
+    pager: Option<&str>,
+) -> Option<ImageProtocol> {
+    let protocol = match mode {
+        "off" => None,
+        "kitty" => Some(ImageProtocol::Kitty),
+        "iterm2" => Some(ImageProtocol::Iterm2),
+        _ => detect_image_protocol(env),
+    }?;
+    let pager_program = pager
+        .and_then(|pager| shell_words::split(pager).ok())
+        .and_then(|args| args.into_iter().next());
+    let pager_is_less = matches!(
+        pager_program,
+        Some(program) if Path::new(&program).file_stem() == Some(OsStr::new("less"))
+    );
+    if !stdout_is_term || pager_is_less {
+        return None;
+    }
+    Some(protocol)
+}
+
+fn detect_image_protocol(env: &DeltaEnv) -> Option<ImageProtocol> {
+    match (env.term.as_deref(), env.term_program.as_deref()) {
+        (Some("xterm-kitty"), _) | (_, Some("ghostty")) => Some(ImageProtocol::Kitty),
+        (_, Some("iTerm.app")) | (_, Some("WezTerm")) => Some(ImageProtocol::Iterm2),
+        _ => None,
+    }
+}
+
+impl<'a> StateMachine<'a> {
+    /// Display the new version of an image file, if it is in the worktree.
+    pub fn write_image_preview(&mut self) -> std::io::Result<()> {
+        let protocol = match self.config.image_previews {
+            Some(protocol) if self.current_commit.is_none() => protocol,
+            _ => return Ok(()),
+        };
+        if let Some(path) = get_image_path(&self.plus_file, protocol, self.config) {
+            if let Ok(image) = std::fs::read(path) {
+                let preview =
+                    format_image_preview(&image, protocol, self.config.image_preview_max_height);
+                writeln!(self.painter.writer, "{}", preview)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// Return the path of the file on disk if it is an image which can be displayed with `protocol`.
+// kitty displays PNG images only.
+fn get_image_path(path: &str, protocol: ImageProtocol, config: &Config) -> Option<PathBuf> {
+    if path == "/dev/null" {
+        return None;
+    }
+    let extension = Path::new(path)
+        .extension()?
+        .to_string_lossy()
+        .to_lowercase();
+    let is_displayable = match protocol {
+        ImageProtocol::Kitty => extension == "png",
+        ImageProtocol::Iterm2 => matches!(extension.as_str(), "png" | "jpg" | "jpeg" | "gif"),
+    };
+    if !is_displayable {
+        return None;
+    }
+    utils::path::absolute_path(path, config).filter(|path| path.is_file())
+}
+
+/// Return the escape sequences displaying `image` at a height of `height` terminal cells.
+fn format_image_preview(image: &[u8], protocol: ImageProtocol, height: usize) -> String {
+    let data = base64::encode(image);
+    match protocol {
+        ImageProtocol::Kitty => {
+            let chunks = data.as_bytes().chunks(KITTY_CHUNK_SIZE).collect::<Vec<_>>();
+            chunks
+                .iter()
+                .enumerate()
+                .map(|(i, chunk)| {
+                    // q=2 suppresses the responses of the terminal, which would be read as input.
+                    let control = if i == 0 {
+                        format!("a=T,f=100,q=2,r={},", height)
+                    } else {
+                        "".to_string()
+                    };
+                    format!(
+                        "\x1b_G{}m={};{}\x1b\\",
+                        control,
+                        (i + 1 < chunks.len()) as u8,
+                        String::from_utf8_lossy(chunk)
+                    )
+                })
+                .collect()
+        }
+        ImageProtocol::Iterm2 => format!(
+            "\x1b]1337;File=inline=1;size={};height={}:{}\x07",
+            image.len(),
+            height,
+            data
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::tests::integration_test_utils;
+
+    #[test]
+    fn test_get_image_protocol() {
+        let env = |term: &str, term_program: &str| DeltaEnv {
+            term: Some(term.to_string()),
+            term_program: Some(term_program.to_string()),
+            ..DeltaEnv::default()
+        };
+        let kitty = env("xterm-kitty", "");
+        let iterm2 = env("xterm-256color", "iTerm.app");
+        let other = env("xterm-256color", "Apple_Terminal");
+        let kitty_protocol = Some(ImageProtocol::Kitty);
+        let iterm2_protocol = Some(ImageProtocol::Iterm2);
+
+        assert_eq!(
+            get_image_protocol("auto", &kitty, true, None),
+            kitty_protocol
+        );
+        assert_eq!(
+            get_image_protocol("auto", &iterm2, true, None),
+            iterm2_protocol
+        );
+        assert_eq!(get_image_protocol("auto", &other, true, None), None);
+        assert_eq!(
+            get_image_protocol("iterm2", &other, true, None),
+            iterm2_protocol
+        );
+        assert_eq!(get_image_protocol("off", &kitty, true, None), None);
+        // Previews are disabled when the output is not a terminal, or goes through less.
+        assert_eq!(get_image_protocol("kitty", &kitty, false, None), None);
+        let less = Some("/usr/bin/less -R");
+        assert_eq!(get_image_protocol("kitty", &kitty, true, less), None);
+        assert_eq!(
+            get_image_protocol("kitty", &kitty, true, Some("more")),
+            kitty_protocol
+        );
+    }
+
+    #[test]
+    fn test_image_preview() {
+        let dir = std::env::temp_dir().join("delta__test_image_preview");
+        fs::create_dir_all(&dir).unwrap();
+        let image = dir.join("logo.png");
+        fs::write(&image, b"\x89PNG\r\n").unwrap();
+        let input = format!(
+            "diff --git a/logo.png b/logo.png\n\
+             index 663a5d4..a59fe84 100644\n\
+             Binary files /dev/null and {} differ\n",
+            image.display()
+        );
+
+        let mut config =
+            integration_test_utils::make_config_from_args(&["--image-preview-max-height", "5"]);
+        config.image_previews = Some(ImageProtocol::Kitty);
+        let output = integration_test_utils::run_delta(&input, &config);
+        assert!(output.contains("\x1b_Ga=T,f=100,q=2,r=5,m=0;iVBORw0K\x1b\\\n"));
+
+        config.image_previews = Some(ImageProtocol::Iterm2);
+        let output = integration_test_utils::run_delta(&input, &config);
+        assert!(output.contains("\x1b]1337;File=inline=1;size=6;height=5:iVBORw0K\x07\n"));
+
+        // Without the file on disk, only the summary line is displayed.
+        fs::remove_dir_all(dir).unwrap();
+        let output = integration_test_utils::run_delta(&input, &config);
+        assert!(!output.contains("\x1b]1337"));
+    }
+
+    #[test]
+    fn test_image_previews_are_off_by_default() {
+        let config = integration_test_utils::make_config_from_args(&[]);
+        assert_eq!(config.image_previews, None);
+    }
+}
@@ -0,0 +1,335 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use serde::Serialize;
+
+use crate::ansi;
+use crate::delta::StateMachine;
+use crate::handlers::{diff_header, diff_header_hg, diff_header_svn, file_filter, hunk_header};
+
+/// The writer of the manifest of the changed files, see --emit-manifest.
+pub struct Manifest {
+    writer: BufWriter<File>,
+    n_entries: usize,
+}
+
+/// A changed file, written as an element of the JSON array of the manifest.
+#[derive(Debug, PartialEq, Serialize)]
+struct ManifestEntry {
+    status: FileStatus,
+    old_path: Option<String>,
+    new_path: Option<String>,
+    commit: Option<String>,
+    insertions: usize,
+    deletions: usize,
+    hunks: Vec<ManifestHunk>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum FileStatus {
+    Added,
+    Modified,
+    Renamed,
+    Copied,
+    Deleted,
+}
+
+/// The line ranges of a hunk, from its header. For a combined diff the old range is that of the
+/// first parent.
+#[derive(Debug, PartialEq, Serialize)]
+struct ManifestHunk {
+    old_start: usize,
+    old_lines: usize,
+    new_start: usize,
+    new_lines: usize,
+}
+
+impl Manifest {
+    pub fn create(path: &str) -> std::io::Result<Self> {
+        Ok(Self {
+            writer: BufWriter::new(File::create(path)?),
+            n_entries: 0,
+        })
+    }
+
+    fn write_entry(&mut self, entry: &ManifestEntry) -> std::io::Result<()> {
+        let separator = if self.n_entries == 0 { "[\n" } else { ",\n" };
+        self.writer.write_all(separator.as_bytes())?;
+        serde_json::to_writer(&mut self.writer, entry)?;
+        self.n_entries += 1;
+        self.writer.flush()
+    }
+
+    /// Close the JSON array of the entries.
+    pub fn finish(&mut self) -> std::io::Result<()> {
+        let end = if self.n_entries == 0 { "[]\n" } else { "\n]\n" };
+        self.writer.write_all(end.as_bytes())?;
+        self.writer.flush()
+    }
+}
+
+impl<'a> StateMachine<'a> {
+    /// Write the manifest entry of the file whose diff is `file_lines`, read up to the next diff
+    /// line. The entry is flushed, so that the manifest can be followed while delta is running.
+    pub fn write_manifest_entry(&mut self, file_lines: &[Vec<u8>]) -> std::io::Result<()> {
+        let manifest = match self.manifest.as_mut() {
+            Some(manifest) => manifest,
+            None => return Ok(()),
+        };
+        let lines: Vec<String> = file_lines
+            .iter()
+            .map(|line| ansi::strip_ansi_codes(&String::from_utf8_lossy(line)))
+            .collect();
+        // The diff of a file starts with a "diff" line, an `svn diff` "Index:" line, or, for
+        // `diff -u`, a "---" line.
+        if !matches!(lines.first(), Some(line) if line.starts_with("diff ")
+            || diff_header_svn::is_svn_index_line(line)
+            || line.starts_with("--- "))
+        {
+            return Ok(());
+        }
+        let hunk_lines = diff_header::parse_hunk_lines(file_lines);
+        let n_file_lines = file_filter::count_file_lines(&lines, &hunk_lines);
+        let entry = make_manifest_entry(
+            &lines[..n_file_lines],
+            &hunk_lines[..n_file_lines],
+            self.current_commit.as_deref(),
+        );
+        manifest.write_entry(&entry)
+    }
+}
+
+fn make_manifest_entry(
+    lines: &[String],
+    hunk_lines: &[diff_header::HunkLine],
+    commit: Option<&str>,
+) -> ManifestEntry {
+    let git_diff_name = ["diff --git ", "diff --cc ", "diff --combined "]
+        .iter()
+        .any(|prefix| lines[0].starts_with(prefix))
+        || diff_header_hg::is_hg_diff_line(&lines[0]);
+    let mut status = FileStatus::Modified;
+    let path = diff_header::get_repeated_file_path_from_diff_line(&lines[0]);
+    let (mut old_path, mut new_path) = (path.clone(), path);
+    let mut hunks = Vec::new();
+    for (line, hunk_line) in lines.iter().zip(hunk_lines) {
+        match hunk_line {
+            diff_header::HunkLine::Header => {
+                if let Some(parsed_hunk_header) = hunk_header::parse_hunk_header(line) {
+                    let ranges = &parsed_hunk_header.line_numbers_and_hunk_lengths;
+                    if let (Some(old), Some(new)) = (ranges.first(), ranges.last()) {
+                        hunks.push(ManifestHunk {
+                            old_start: old.0,
+                            old_lines: old.1,
+                            new_start: new.0,
+                            new_lines: new.1,
+                        });
+                    }
+                }
+            }
+            diff_header::HunkLine::Outside => {
+                if let Some((minus_file, plus_file)) =
+                    diff_header::parse_binary_files_differ_line(line, git_diff_name)
+                {
+                    old_path = Some(minus_file);
+                    new_path = Some(plus_file);
+                    continue;
+                }
+                let (path, file_event) = diff_header::parse_diff_header_line(line, git_diff_name);
+                match file_event {
+                    diff_header::FileEvent::Added => status = FileStatus::Added,
+                    diff_header::FileEvent::Removed => status = FileStatus::Deleted,
+                    diff_header::FileEvent::Rename | diff_header::FileEvent::Copy => {
+                        status = if file_event == diff_header::FileEvent::Rename {
+                            FileStatus::Renamed
+                        } else {
+                            FileStatus::Copied
+                        };
+                        if line.starts_with("rename from ") || line.starts_with("copy from ") {
+                            old_path = Some(path);
+                        } else {
+                            new_path = Some(path);
+                        }
+                    }
+                    // `svn diff` gives the revision "nonexistent" (or 0) for an added or deleted
+                    // file.
+                    diff_header::FileEvent::Change if line.starts_with("--- ") => {
+                        old_path = if diff_header_svn::is_svn_nonexistent_file(line) {
+                            Some("/dev/null".to_string())
+                        } else {
+                            Some(path)
+                        }
+                    }
+                    diff_header::FileEvent::Change => {
+                        new_path = if diff_header_svn::is_svn_nonexistent_file(line) {
+                            Some("/dev/null".to_string())
+                        } else {
+                            Some(path)
+                        }
+                    }
+                    diff_header::FileEvent::NoEvent => {}
+                }
+            }
+            _ => {}
+        }
+    }
+    // E.g. a file added by `diff -u` or `svn diff` has no "new file mode" line.
+    if old_path.as_deref() == Some("/dev/null") {
+        status = FileStatus::Added;
+    }
+    if new_path.as_deref() == Some("/dev/null") {
+        status = FileStatus::Deleted;
+    }
+    match status {
+        FileStatus::Added => old_path = None,
+        FileStatus::Deleted => new_path = None,
+        _ => {}
+    }
+    let file_stats = diff_header::FileStats::from_hunk_lines(hunk_lines);
+    ManifestEntry {
+        status,
+        old_path,
+        new_path,
+        commit: commit.map(str::to_string),
+        insertions: file_stats.insertions,
+        deletions: file_stats.deletions,
+        hunks,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use serde_json::{json, Value};
+
+    use crate::tests::fixtures::GIT_LOG_THREE_COMMITS;
+    use crate::tests::integration_test_utils::{self, DeltaTest};
+
+    // Return the entries of the manifest written by delta for `input`.
+    fn emit_manifest(name: &str, input: &str, expected_output: &str) -> Vec<Value> {
+        let dir = integration_test_utils::make_temp_dir(name);
+        let manifest_path = dir.join("manifest.json");
+        DeltaTest::with_args(&["--emit-manifest", manifest_path.to_str().unwrap()])
+            .with_input(input)
+            .expect_contains(expected_output);
+        let manifest = fs::read_to_string(&manifest_path).unwrap();
+        fs::remove_dir_all(dir).unwrap();
+        serde_json::from_str(&manifest).unwrap()
+    }
+
+    #[test]
+    fn test_emit_manifest() {
+        let entries = emit_manifest(
+            "test_emit_manifest",
+            GIT_LOG_THREE_COMMITS,
+            "\nsrc/lib.rs\n",
+        );
+        assert_eq!(entries.len(), 7);
+        let first_commit = "94907c0f136f46dc46ffae2dc92dca9af7eb7c2e";
+        let second_commit = "6d1b7a2e4c3f5a8b9d0e1f2a3b4c5d6e7f8a9b0c";
+        assert_eq!(
+            entries[0],
+            json!({
+                "status": "modified",
+                "old_path": "src/lib.rs",
+                "new_path": "src/lib.rs",
+                "commit": first_commit,
+                "insertions": 2,
+                "deletions": 1,
+                "hunks": [
+                    {"old_start": 1, "old_lines": 3, "new_start": 1, "new_lines": 3},
+                    {"old_start": 10, "old_lines": 1, "new_start": 10, "new_lines": 2},
+                ],
+            })
+        );
+        assert_eq!(
+            entries[3..6],
+            [
+                json!({
+                    "status": "renamed",
+                    "old_path": "src/old.rs",
+                    "new_path": "src/moved.rs",
+                    "commit": second_commit,
+                    "insertions": 0,
+                    "deletions": 0,
+                    "hunks": [],
+                }),
+                json!({
+                    "status": "deleted",
+                    "old_path": "src/gone.rs",
+                    "new_path": null,
+                    "commit": second_commit,
+                    "insertions": 0,
+                    "deletions": 2,
+                    "hunks": [{"old_start": 1, "old_lines": 2, "new_start": 0, "new_lines": 0}],
+                }),
+                json!({
+                    "status": "added",
+                    "old_path": null,
+                    "new_path": "src/new.rs",
+                    "commit": second_commit,
+                    "insertions": 4,
+                    "deletions": 0,
+                    "hunks": [{"old_start": 0, "old_lines": 0, "new_start": 1, "new_lines": 4}],
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_emit_manifest_svn() {
+        let entries = emit_manifest(
+            "test_emit_manifest_svn",
+            "\
+Index: src/main.c
+===================================================================
+--- src/main.c\t(revision 123)
++++ src/main.c\t(working copy)
+@@ -1,3 +1,3 @@
+ int a;
+-int b;
++int c;
+ int d;
+Index: src/new.c
+===================================================================
+--- src/new.c\t(nonexistent)
++++ src/new.c\t(working copy)
+@@ -0,0 +1 @@
++int e;
+Index: src/old.c
+===================================================================
+--- src/old.c\t(revision 122)
++++ src/old.c\t(nonexistent)
+@@ -1 +0,0 @@
+-int f;
+",
+            "\nsrc/main.c\n",
+        );
+        let statuses: Vec<_> = entries
+            .iter()
+            .map(|entry| (&entry["status"], &entry["old_path"], &entry["new_path"]))
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                (
+                    &json!("modified"),
+                    &json!("src/main.c"),
+                    &json!("src/main.c")
+                ),
+                (&json!("added"), &Value::Null, &json!("src/new.c")),
+                (&json!("deleted"), &json!("src/old.c"), &Value::Null),
+            ]
+        );
+        assert_eq!(entries[0]["insertions"], 1);
+        assert_eq!(entries[0]["deletions"], 1);
+    }
+
+    #[test]
+    fn test_emit_manifest_without_changed_files() {
+        let entries = emit_manifest("test_emit_manifest_without_changed_files", "", "");
+        assert!(entries.is_empty());
+    }
+}
@@ -2,11 +2,13 @@ use std::ops::{Index, IndexMut};
 
 use itertools::Itertools;
 use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
 use super::draw;
 use crate::cli;
 use crate::config::{self, delta_unreachable};
 use crate::delta::{DiffType, InMergeConflict, MergeParents, State, StateMachine};
+use crate::features::side_by_side::{Left, Right};
 use crate::minusplus::MinusPlus;
 use crate::paint::{self, prepare};
 use crate::style::Style;
@@ -18,6 +20,15 @@ pub enum MergeConflictCommit {
     Theirs,
 }
 
+/// How a merge conflict is displayed, see --merge-conflict-display.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MergeConflictDisplay {
+    // The lines of each commit below a header naming it.
+    Blocks,
+    // The diffs from the ancestral commit to each of the two branches.
+    DerivedDiffs,
+}
+
 pub struct MergeConflictCommits<T> {
     ours: T,
     ancestral: T,
@@ -40,8 +51,20 @@ impl<'a> StateMachine<'a> {
         }
 
         match self.state.clone() {
-            HunkHeader(Combined(merge_parents, InMergeConflict::No), _, _, _)
-            | HunkMinus(Combined(merge_parents, InMergeConflict::No), _)
+            HunkHeader(
+                Combined(merge_parents, InMergeConflict::No),
+                parsed_hunk_header,
+                line,
+                raw_line,
+            ) => {
+                if parse_merge_marker(&self.line, "++<<<<<<<").is_some() {
+                    // The hunk header, which sets the line numbers, is written before the
+                    // conflict, as it is before any other line of the hunk.
+                    self.emit_hunk_header_line(&parsed_hunk_header, &line, &raw_line)?;
+                }
+                handled_line = self.enter_merge_conflict(&merge_parents)
+            }
+            HunkMinus(Combined(merge_parents, InMergeConflict::No), _)
             | HunkZero(Combined(merge_parents, InMergeConflict::No), _)
             | HunkPlus(Combined(merge_parents, InMergeConflict::No), _) => {
                 handled_line = self.enter_merge_conflict(&merge_parents)
@@ -80,7 +103,10 @@ impl<'a> StateMachine<'a> {
         use State::*;
         if let Some(commit) = parse_merge_marker(&self.line, "++<<<<<<<") {
             self.state = MergeConflict(merge_parents.clone(), Ours);
+            self.diff_summary.has_merge_conflict = true;
             self.painter.merge_conflict_commit_names[Ours] = Some(commit.to_string());
+            // A two-way conflict has no base section.
+            self.painter.merge_conflict_commit_names[Ancestral] = None;
             true
         } else {
             false
@@ -100,7 +126,7 @@ impl<'a> StateMachine<'a> {
 
     fn enter_theirs(&mut self, merge_parents: &MergeParents) -> bool {
         use State::*;
-        if self.line.starts_with("++=======") {
+        if is_merge_marker(&self.line, "++=======") {
             self.state = MergeConflict(merge_parents.clone(), Theirs);
             true
         } else {
@@ -142,28 +168,48 @@ impl<'a> StateMachine<'a> {
             &mut self.painter,
             self.config,
         )?;
-        for (derived_commit_type, header_style) in &[
-            (Ours, self.config.merge_conflict_ours_diff_header_style),
-            (Theirs, self.config.merge_conflict_theirs_diff_header_style),
-        ] {
-            write_diff_header(
-                derived_commit_type,
-                *header_style,
-                &mut self.painter,
-                self.config,
-            )?;
-            self.painter.emit()?;
-            paint::paint_minus_and_plus_lines(
-                MinusPlus::new(
-                    &self.painter.merge_conflict_lines[Ancestral],
-                    &self.painter.merge_conflict_lines[derived_commit_type],
-                ),
-                &mut self.painter.line_numbers_data,
-                &mut self.painter.highlighter,
-                &mut self.painter.output_buffer,
-                self.config,
-            );
-            self.painter.emit()?;
+        match self.config.merge_conflict_display {
+            _ if self.config.side_by_side => self.paint_merge_conflict_lines_side_by_side()?,
+            MergeConflictDisplay::DerivedDiffs => {
+                for (derived_commit_type, header_style) in &[
+                    (Ours, self.config.merge_conflict_ours_diff_header_style),
+                    (Theirs, self.config.merge_conflict_theirs_diff_header_style),
+                ] {
+                    write_diff_header(
+                        derived_commit_type,
+                        *header_style,
+                        &mut self.painter,
+                        self.config,
+                    )?;
+                    self.painter.emit()?;
+                    self.paint_merge_conflict_lines(
+                        Some(Ancestral),
+                        Some(derived_commit_type.clone()),
+                    );
+                    self.painter.emit()?;
+                }
+            }
+            MergeConflictDisplay::Blocks => {
+                for (commit, header_style) in &[
+                    (Ours, self.config.merge_conflict_ours_diff_header_style),
+                    (Ancestral, self.config.merge_conflict_base_style),
+                    (Theirs, self.config.merge_conflict_theirs_diff_header_style),
+                ] {
+                    if *commit == Ancestral
+                        && self.painter.merge_conflict_commit_names[Ancestral].is_none()
+                    {
+                        continue;
+                    }
+                    write_block_header(commit, *header_style, &mut self.painter, self.config)?;
+                    self.painter.emit()?;
+                    // The base is displayed as removed lines, the branches as added lines.
+                    match commit {
+                        Ancestral => self.paint_merge_conflict_lines(Some(Ancestral), None),
+                        _ => self.paint_merge_conflict_lines(None, Some(commit.clone())),
+                    }
+                    self.painter.emit()?;
+                }
+            }
         }
         // write_merge_conflict_decoration("bold ol", &mut self.painter, self.config)?;
         write_merge_conflict_bar(
@@ -175,6 +221,71 @@ impl<'a> StateMachine<'a> {
         self.state = HunkZero(Combined(merge_parents.clone(), InMergeConflict::No), None);
         Ok(())
     }
+
+    // In side-by-side mode the 'ours' and 'theirs' lines are displayed in the left and right
+    // panels, aligned as removed and added lines are, below the base section if there is one.
+    fn paint_merge_conflict_lines_side_by_side(&mut self) -> std::io::Result<()> {
+        use State::*;
+        if self.painter.merge_conflict_commit_names[Ancestral].is_some() {
+            write_block_header(
+                &Ancestral,
+                self.config.merge_conflict_base_style,
+                &mut self.painter,
+                self.config,
+            )?;
+            let base_lines = std::mem::take(&mut self.painter.merge_conflict_lines[Ancestral]);
+            self.painter.paint_full_width_lines(&base_lines);
+            self.painter.emit()?;
+        }
+        write_side_by_side_header(
+            self.config.merge_conflict_ours_diff_header_style,
+            &mut self.painter,
+            self.config,
+        )?;
+        // The 'ours' lines are painted in the left panel, as removed lines.
+        let ours_lines = self.painter.merge_conflict_lines[Ours]
+            .iter()
+            .map(|(line, state)| match state {
+                HunkPlus(diff_type, raw_line) => {
+                    (line.clone(), HunkMinus(diff_type.clone(), raw_line.clone()))
+                }
+                _ => (line.clone(), state.clone()),
+            })
+            .collect();
+        paint::paint_minus_and_plus_lines(
+            MinusPlus::new(&ours_lines, &self.painter.merge_conflict_lines[Theirs]),
+            &[],
+            &mut self.painter.line_numbers_data,
+            &mut self.painter.highlighter,
+            &mut self.painter.output_buffer,
+            self.painter.tokenization_regex,
+            self.config,
+        );
+        self.painter.emit()
+    }
+
+    // Paint the lines of `minus` as removed lines and those of `plus` as added lines.
+    fn paint_merge_conflict_lines(
+        &mut self,
+        minus: Option<MergeConflictCommit>,
+        plus: Option<MergeConflictCommit>,
+    ) {
+        let no_lines = Vec::new();
+        let lines = &self.painter.merge_conflict_lines;
+        let get_lines = |commit: Option<MergeConflictCommit>| match commit {
+            Some(commit) => &lines[commit],
+            None => &no_lines,
+        };
+        paint::paint_minus_and_plus_lines(
+            MinusPlus::new(get_lines(minus), get_lines(plus)),
+            &[],
+            &mut self.painter.line_numbers_data,
+            &mut self.painter.highlighter,
+            &mut self.painter.output_buffer,
+            self.painter.tokenization_regex,
+            self.config,
+        );
+    }
 }
 
 fn write_diff_header(
@@ -201,21 +312,84 @@ fn write_diff_header(
         &text,
         &text,
         "",
-        &config.decorations_width,
+        &config.decorations_width.get(),
         style,
         decoration_ansi_term_style,
     )?;
     Ok(())
 }
 
+fn write_block_header(
+    commit: &MergeConflictCommit,
+    style: Style,
+    painter: &mut paint::Painter,
+    config: &config::Config,
+) -> std::io::Result<()> {
+    let label = get_block_label(commit, painter);
+    draw::write_decorated_line(
+        painter.writer,
+        &label,
+        &config.decorations_width.get(),
+        style,
+    )
+}
+
+// The header above the panels in side-by-side mode, with the label of each branch at the start of
+// its panel, spans the full width.
+fn write_side_by_side_header(
+    style: Style,
+    painter: &mut paint::Painter,
+    config: &config::Config,
+) -> std::io::Result<()> {
+    let (_, pad, _) = draw::get_draw_function(style.decoration_style);
+    let left_panel_width = config.side_by_side_data.get()[Left].width;
+    let right_panel_width = config.side_by_side_data.get()[Right].width;
+    let text = format!(
+        "{}{}",
+        pad_to_width(&get_block_label(&Ours, painter), left_panel_width),
+        pad_to_width(
+            &get_block_label(&Theirs, painter),
+            // A space and a box decoration are drawn after the text.
+            right_panel_width.saturating_sub(if pad { 2 } else { 0 })
+        ),
+    );
+    draw::write_decorated_line(
+        painter.writer,
+        &text,
+        &config.decorations_width.get(),
+        style,
+    )
+}
+
+fn get_block_label(commit: &MergeConflictCommit, painter: &paint::Painter) -> String {
+    let label = match commit {
+        Ours => "ours",
+        Ancestral => "base",
+        Theirs => "theirs",
+    };
+    match &painter.merge_conflict_commit_names[commit] {
+        Some(name) => format!("{}: {}", label, name),
+        None => label.to_string(),
+    }
+}
+
+fn pad_to_width(text: &str, width: usize) -> String {
+    let text_width = text.width();
+    if text_width < width {
+        format!("{}{}", text, " ".repeat(width - text_width))
+    } else {
+        text.to_string()
+    }
+}
+
 fn write_merge_conflict_bar(
     s: &str,
     painter: &mut paint::Painter,
     config: &config::Config,
 ) -> std::io::Result<()> {
-    let width = match config.decorations_width {
+    let width = match config.decorations_width.get() {
         cli::Width::Fixed(width) => width,
-        cli::Width::Variable => config.available_terminal_width,
+        cli::Width::Variable => config.available_terminal_width.get(),
     };
     writeln!(
         painter.writer,
@@ -225,7 +399,19 @@ fn write_merge_conflict_bar(
     Ok(())
 }
 
+// The marker must not be followed by another marker character: the conflicts of a recursive merge
+// which are nested in the base section are marked by longer markers.
+fn is_merge_marker(line: &str, marker: &str) -> bool {
+    match line.strip_prefix(marker) {
+        Some(suffix) => !suffix.starts_with(marker.chars().last().unwrap()),
+        None => false,
+    }
+}
+
 fn parse_merge_marker<'a>(line: &'a str, marker: &str) -> Option<&'a str> {
+    if !is_merge_marker(line, marker) {
+        return None;
+    }
     match line.strip_prefix(marker) {
         Some(suffix) => {
             let suffix = suffix.trim();
@@ -353,6 +539,73 @@ ancestor ⟶   HEAD │
         assert!(output.contains("\n▲▲▲▲▲▲▲▲▲▲▲▲▲▲▲▲▲▲"));
     }
 
+    #[test]
+    fn test_merge_conflict_display_blocks() {
+        let config =
+            integration_test_utils::make_config_from_args(&["--merge-conflict-display", "blocks"]);
+        let output = integration_test_utils::run_delta(GIT_TOY_MERGE_CONFLICT_NO_CONTEXT, &config);
+        let output = strip_ansi_codes(&output);
+        assert!(output.contains(
+            "\
+───────────┐
+ours: HEAD │
+───────────┘
+a
+───────────────────────────────┐
+base: parent of 0c20c9d... wip │
+───────────────────────────────┘
+───────────────────────┐
+theirs: 0c20c9d... wip │
+───────────────────────┘
+b
+"
+        ));
+    }
+
+    #[test]
+    fn test_nested_merge_conflict_in_base() {
+        let config = integration_test_utils::make_config_from_args(&[]);
+        let output = integration_test_utils::run_delta(GIT_NESTED_MERGE_CONFLICT_IN_BASE, &config);
+        let output = strip_ansi_codes(&output);
+        assert!(output.contains(
+            "\
+────────────────────┐
+ancestor ⟶   theirs │
+────────────────────┘
+<<<<<<<<< Temporary merge branch 1
+x
+=========
+y
+>>>>>>>>> Temporary merge branch 2
+b
+▲▲▲▲"
+        ));
+    }
+
+    #[test]
+    fn test_merge_conflict_side_by_side() {
+        let config =
+            integration_test_utils::make_config_from_args(&["--side-by-side", "--width", "60"]);
+        let output = integration_test_utils::run_delta(GIT_NESTED_MERGE_CONFLICT_IN_BASE, &config);
+        let output = strip_ansi_codes(&output);
+        assert!(output.contains(
+            "
+base: merged common ancestors │
+──────────────────────────────┘
+<<<<<<<<< Temporary merge branch 1
+x
+=========
+y
+>>>>>>>>> Temporary merge branch 2
+───────────────────────────────────────────────────────────┐
+ours: HEAD                    theirs: theirs               │
+───────────────────────────────────────────────────────────┘
+│ 1│a                         │  │
+│  │                          │ 1│b
+▲▲▲▲"
+        ));
+    }
+
     const GIT_TOY_MERGE_CONFLICT_NO_CONTEXT: &str = "\
 diff --cc file
 index 6178079,7898192..0000000
@@ -365,6 +618,25 @@ index 6178079,7898192..0000000
 ++=======
 + b
 ++>>>>>>> 0c20c9d... wip
+";
+
+    const GIT_NESTED_MERGE_CONFLICT_IN_BASE: &str = "\
+diff --cc file
+index 6178079,7898192..0000000
+--- a/file
++++ b/file
+@@@ -1,1 -1,1 +1,10 @@@
+++<<<<<<< HEAD
+ +a
+++||||||| merged common ancestors
+++<<<<<<<<< Temporary merge branch 1
+++x
+++=========
+++y
+++>>>>>>>>> Temporary merge branch 2
+++=======
++ b
+++>>>>>>> theirs
 ";
 
     const GIT_MERGE_CONFLICT: &str = r#"\
@@ -803,7 +1075,7 @@ index 27d47c0,3a7e7b9..0000000
 +         painter.writer,
 +         &text,
 +         &text,
-+         &config.decorations_width,
++         &config.decorations_width.get(),
 +         config.hunk_header_style,
 +         decoration_ansi_term_style,
 +     )?;
@@ -834,7 +1106,7 @@ index 27d47c0,3a7e7b9..0000000
 +         painter.writer,
 +         "",
 +         "",
-+         &config.decorations_width,
++         &config.decorations_width.get(),
 +         config.hunk_header_style,
 +         decoration_ansi_term_style,
 +     )?;
@@ -1281,7 +1553,7 @@ index 27d47c0,3a7e7b9..0000000
 +         painter.writer,
 +         &text,
 +         &text,
-+         &config.decorations_width,
++         &config.decorations_width.get(),
 +         config.hunk_header_style,
 +         decoration_ansi_term_style,
 +     )?;
@@ -1312,7 +1584,7 @@ index 27d47c0,3a7e7b9..0000000
 +         painter.writer,
 +         "",
 +         "",
-+         &config.decorations_width,
++         &config.decorations_width.get(),
 +         config.hunk_header_style,
 +         decoration_ansi_term_style,
 +     )?;
@@ -1,19 +1,30 @@
 /// This module contains functions handling input lines encountered during the
 /// main `StateMachine::consume()` loop.
+pub mod binary;
 pub mod blame;
+pub mod collapsed_file;
 pub mod commit_meta;
 pub mod diff_header;
 pub mod diff_header_diff;
+pub mod diff_header_hg;
+pub mod diff_header_jj;
 pub mod diff_header_misc;
+pub mod diff_header_svn;
 pub mod diff_stat;
+pub mod diff_summary;
 pub mod draw;
+pub mod file_filter;
 pub mod git_show_file;
 pub mod grep;
 pub mod hunk;
 pub mod hunk_header;
+pub mod image_preview;
+pub mod manifest;
 pub mod merge_conflict;
+pub mod range_diff;
 mod ripgrep_json;
 pub mod submodule;
+pub mod word_diff;
 
 use crate::delta::{State, StateMachine};
 
@@ -23,10 +34,7 @@ impl<'a> StateMachine<'a> {
 
         // Additional cases:
         //
-        // 1. When comparing directories with diff -u, if filenames match between the
-        //    directories, the files themselves will be compared. However, if an equivalent
-        //    filename is not present, diff outputs a single line (Only in...) starting
-        //    indicating that the file is present in only one of the directories.
+        // 1. Diff emits a single line (Binary files...) for a pair of binary files which differ.
         //
         // 2. Git diff emits lines describing submodule state such as "Submodule x/y/z contains
         //    untracked content"
@@ -0,0 +1,182 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::delta::{DiffType, State, StateMachine};
+use crate::features;
+use crate::handlers;
+use crate::paint::{has_space_before_tab, prepare};
+
+lazy_static! {
+    // E.g. "2:  1234567 ! 2:  89abcde Commit subject", where the numbers and the commits of a
+    // commit which is in only one of the ranges are replaced by dashes.
+    static ref RANGE_DIFF_PAIR_LINE_REGEX: Regex = Regex::new(
+        r"^\s*(?:\d+|-):\s+([0-9a-f]{4,40}|-{4,40}) ([=!<>]) \s*(?:\d+|-):\s+([0-9a-f]{4,40}|-{4,40}) "
+    )
+    .unwrap();
+}
+
+// The lines of the diff between the patches of a pair of commits are indented by this.
+const RANGE_DIFF_INDENT: &str = "    ";
+
+impl<'a> StateMachine<'a> {
+    #[inline]
+    fn test_range_diff_line(&self) -> bool {
+        !self.config.color_only
+            && (self.range_diff
+                || matches!(self.state, State::Unknown)
+                    && RANGE_DIFF_PAIR_LINE_REGEX.is_match(&self.line))
+    }
+
+    /// Handle a line of `git range-diff` output: a line pairing a commit of the old range with one
+    /// of the new range, or a line of the diff between their patches. The lines of that diff have
+    /// two prefixes: the outer one, of the diff between the patches, determines how the line is
+    /// painted, and the inner one, of the patches themselves, is displayed as part of the line.
+    pub fn handle_range_diff_line(&mut self) -> std::io::Result<bool> {
+        if !self.test_range_diff_line() {
+            return Ok(false);
+        }
+        let raw_line = self.line.clone();
+        if let Some(caps) = RANGE_DIFF_PAIR_LINE_REGEX.captures(&raw_line) {
+            self.range_diff = true;
+            self.end_range_diff_hunk()?;
+            let style = match &caps[2] {
+                "=" => self.config.range_diff_unchanged_style,
+                "!" => self.config.range_diff_modified_style,
+                "<" => self.config.range_diff_removed_style,
+                _ => self.config.range_diff_added_style,
+            };
+            let (pair, subject) = raw_line.split_at(caps.get(0).unwrap().end());
+            // The commit of the new range, or of the old range if it is only in that one.
+            let commit = Some(&caps[3])
+                .filter(|commit| !commit.starts_with('-'))
+                .unwrap_or(&caps[1]);
+            let subject = if self.config.hyperlinks {
+                features::hyperlinks::format_osc8_commit_hyperlink(commit, subject, self.config)
+                    .unwrap_or_else(|| subject.to_string())
+            } else {
+                subject.to_string()
+            };
+            writeln!(
+                self.painter.writer,
+                "{}{}",
+                style.paint(pair),
+                style.paint(subject)
+            )?;
+            return Ok(true);
+        }
+        let line = match self.line.strip_prefix(RANGE_DIFF_INDENT) {
+            Some(line) => line.to_string(),
+            None if self.line.trim().is_empty() => {
+                self.end_range_diff_hunk()?;
+                writeln!(self.painter.writer)?;
+                return Ok(true);
+            }
+            None => {
+                // The range-diff has ended.
+                self.end_range_diff_hunk()?;
+                self.range_diff = false;
+                return Ok(false);
+            }
+        };
+        // E.g. "@@ src/main.rs: fn main() {", "@@ Metadata" and " ## src/main.rs ##".
+        let path = match line.strip_prefix("@@ ") {
+            Some(header) => Some(header.split(':').next().unwrap_or(header)),
+            None => line
+                .get(1..)
+                .and_then(|s| s.strip_prefix("## "))
+                .and_then(|s| s.strip_suffix(" ##")),
+        };
+        if let Some(path) = path {
+            self.end_range_diff_hunk()?;
+            self.painter
+                .set_syntax(handlers::diff_header::get_extension(path));
+            self.painter.set_highlighter();
+        }
+        if line.starts_with("@@") {
+            writeln!(
+                self.painter.writer,
+                "{}",
+                self.config.hunk_header_style.paint(line)
+            )?;
+            return Ok(true);
+        }
+        let diff_type = DiffType::Unified;
+        let prepared_line = prepare(&line, 1, self.config);
+        let space_before_tab = has_space_before_tab(line.get(1..).unwrap_or(""));
+        match line.chars().next() {
+            Some('-') => {
+                if let State::HunkPlus(_, _) = self.state {
+                    self.painter.paint_buffered_minus_and_plus_lines();
+                }
+                self.state = State::HunkMinus(diff_type, None);
+                self.painter
+                    .minus_lines
+                    .push((prepared_line, self.state.clone()));
+            }
+            Some('+') => {
+                self.state = State::HunkPlus(diff_type, None);
+                self.painter
+                    .plus_lines
+                    .push((prepared_line, self.state.clone()));
+                self.painter
+                    .plus_lines_space_before_tab
+                    .push(space_before_tab);
+            }
+            _ => {
+                self.painter.paint_buffered_minus_and_plus_lines();
+                self.state = State::HunkZero(diff_type, None);
+                self.painter
+                    .paint_zero_line(&prepared_line, self.state.clone(), space_before_tab);
+            }
+        }
+        self.painter.emit()?;
+        Ok(true)
+    }
+
+    // Paint the buffered lines of the diff between two patches.
+    fn end_range_diff_hunk(&mut self) -> std::io::Result<()> {
+        self.painter.paint_buffered_minus_and_plus_lines();
+        self.painter.emit()?;
+        self.state = State::Unknown;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::integration_test_utils::DeltaTest;
+
+    const GIT_RANGE_DIFF: &str = "\
+1:  1234567 = 1:  89abcde Keep this
+2:  2345678 ! 2:  9abcdef Change this
+    @@ src/main.rs: fn main() {
+          fn main() {
+     -    let a = 1;
+    -+    let a = 2;
+    ++    let a = 3;
+          }
+3:  3456789 < -:  ------- Drop this
+-:  ------- > 3:  4567890 Add this
+";
+
+    #[test]
+    fn test_range_diff() {
+        let output = DeltaTest::with_args(&[
+            "--range-diff-unchanged-style",
+            "blue",
+            "--range-diff-modified-style",
+            "yellow",
+        ])
+        .explain_ansi()
+        .with_input(GIT_RANGE_DIFF);
+        output
+            .expect_contains("(blue)1:  1234567 = 1:  89abcde (blue)Keep this(normal)")
+            .expect_contains("(yellow)2:  2345678 ! 2:  9abcdef (yellow)Change this(normal)")
+            .expect_contains("(red)3:  3456789 < -:  ------- (red)Drop this(normal)")
+            .expect_contains("(green)-:  ------- > 3:  4567890 (green)Add this(normal)")
+            // The outer prefixes determine the painting; the inner ones are displayed.
+            .expect_contains("(203)-(231)    (81)let(231) a (203)=(231) (141)1(231);(normal)")
+            .expect_contains("(normal 52)+    let a = (normal 124)2(normal 52);(normal)")
+            .expect_contains("(203 22)+(231)    (81)let(231) a (203)=(231) (141 28)3(231 22);");
+    }
+}
@@ -14,7 +14,13 @@ pub fn parse_line(line: &str) -> Option<grep::GrepLine> {
         Some(ripgrep_line) => {
             // A real line of rg --json output, i.e. either of type "match" or
             // "context".
-            let mut code = ripgrep_line.data.lines.text;
+            let (mut code, bytes) = ripgrep_line.data.lines.decode()?;
+            // The submatches are byte offsets into the line, which are shifted if it is not valid
+            // UTF-8 and so was decoded lossily.
+            let offset = |offset: usize| match &bytes {
+                Some(bytes) => String::from_utf8_lossy(&bytes[..offset.min(bytes.len())]).len(),
+                None => offset,
+            };
             if code.ends_with('\n') {
                 code.truncate(code.len() - 1);
                 if code.ends_with('\r') {
@@ -24,14 +30,16 @@ pub fn parse_line(line: &str) -> Option<grep::GrepLine> {
             Some(grep::GrepLine {
                 line_type: ripgrep_line._type,
                 line_number: ripgrep_line.data.line_number,
-                path: Cow::from(ripgrep_line.data.path.text),
+                // rg reports byte offsets; columns are 1-based.
+                column: ripgrep_line.data.submatches.first().map(|m| m.start + 1),
+                path: Cow::from(ripgrep_line.data.path.decode()?.0),
                 code: Cow::from(code),
                 submatches: Some(
                     ripgrep_line
                         .data
                         .submatches
                         .iter()
-                        .map(|m| (m.start, m.end))
+                        .map(|m| (offset(m.start), offset(m.end)))
                         .collect(),
                 ),
             })
@@ -46,6 +54,7 @@ pub fn parse_line(line: &str) -> Option<grep::GrepLine> {
                         // line has been handled.
                         line_type: grep::LineType::Ignore,
                         line_number: None,
+                        column: None,
                         path: "".into(),
                         code: "".into(),
                         submatches: None,
@@ -100,9 +109,27 @@ struct RipGrepLineData {
     submatches: Vec<RipGrepLineSubmatch>,
 }
 
+// Text which is not valid UTF-8 is given as base64-encoded bytes instead.
 #[derive(Deserialize, PartialEq, Debug)]
 struct RipGrepLineText {
-    text: String,
+    #[serde(default)]
+    text: Option<String>,
+    #[serde(default)]
+    bytes: Option<String>,
+}
+
+impl RipGrepLineText {
+    // Return the text, decoded lossily from the bytes if need be, and the bytes.
+    fn decode(self) -> Option<(String, Option<Vec<u8>>)> {
+        match (self.text, self.bytes) {
+            (Some(text), _) => Some((text, None)),
+            (None, Some(bytes)) => {
+                let bytes = base64::decode(bytes).ok()?;
+                Some((String::from_utf8_lossy(&bytes).into_owned(), Some(bytes)))
+            }
+            (None, None) => None,
+        }
+    }
 }
 
 #[derive(Deserialize, PartialEq, Debug)]
@@ -127,15 +154,20 @@ mod tests {
                 _type: grep::LineType::Match,
                 data: RipGrepLineData {
                     path: RipGrepLineText {
-                        text: "src/cli.rs".into()
+                        text: Some("src/cli.rs".into()),
+                        bytes: None
                     },
                     lines: RipGrepLineText {
-                        text: "    fn from_clap_and_git_config(\n".into(),
+                        text: Some("    fn from_clap_and_git_config(\n".into()),
+                        bytes: None
                     },
                     line_number: None,
                     absolute_offset: 35837,
                     submatches: vec![RipGrepLineSubmatch {
-                        _match: RipGrepLineText { text: "fn".into() },
+                        _match: RipGrepLineText {
+                            text: Some("fn".into()),
+                            bytes: None
+                        },
                         start: 4,
                         end: 6
                     }]
@@ -153,52 +185,48 @@ mod tests {
             RipGrepLine {
                 _type: grep::LineType::Match,
                 data: RipGrepLineData {
-                    path: RipGrepLineText {
-                        text: "src/handlers/submodule.rs".into()
-                    },
-                    lines: RipGrepLineText {
-                        text: "                        .paint(minus_commit.chars().take(7).collect::<String>()),\n".into(),
-                    },
+                    path: RipGrepLineText { text: Some("src/handlers/submodule.rs".into()), bytes: None },
+                    lines: RipGrepLineText { text: Some("                        .paint(minus_commit.chars().take(7).collect::<String>()),\n".into()), bytes: None },
                     line_number: Some(41),
                     absolute_offset: 1430,
                     submatches: vec![
                         RipGrepLineSubmatch {
-                            _match: RipGrepLineText { text: "(".into() },
+                            _match: RipGrepLineText { text: Some("(".into()), bytes: None },
                             start: 30,
                             end: 31
                         },
                         RipGrepLineSubmatch {
-                            _match: RipGrepLineText { text: "(".into() },
+                            _match: RipGrepLineText { text: Some("(".into()), bytes: None },
                             start: 49,
                             end: 50
                         },
                         RipGrepLineSubmatch {
-                            _match: RipGrepLineText { text: ")".into() },
+                            _match: RipGrepLineText { text: Some(")".into()), bytes: None },
                             start: 50,
                             end: 51
                         },
                         RipGrepLineSubmatch {
-                            _match: RipGrepLineText { text: "(".into() },
+                            _match: RipGrepLineText { text: Some("(".into()), bytes: None },
                             start: 56,
                             end: 57
                         },
                         RipGrepLineSubmatch {
-                            _match: RipGrepLineText { text: ")".into() },
+                            _match: RipGrepLineText { text: Some(")".into()), bytes: None },
                             start: 58,
                             end: 59
                         },
                         RipGrepLineSubmatch {
-                            _match: RipGrepLineText { text: "(".into() },
+                            _match: RipGrepLineText { text: Some("(".into()), bytes: None },
                             start: 77,
                             end: 78
                         },
                         RipGrepLineSubmatch {
-                            _match: RipGrepLineText { text: ")".into() },
+                            _match: RipGrepLineText { text: Some(")".into()), bytes: None },
                             start: 78,
                             end: 79
                         },
                         RipGrepLineSubmatch {
-                            _match: RipGrepLineText { text: ")".into() },
+                            _match: RipGrepLineText { text: Some(")".into()), bytes: None },
                             start: 79,
                             end: 80
                         },
@@ -207,4 +235,15 @@ mod tests {
             }
         )
     }
+
+    #[test]
+    fn test_parse_line_bytes() {
+        // "caf\xe9 fn x\n", which is not valid UTF-8.
+        let line = r#"{"type":"match","data":{"path":{"bytes":"c3JjL2xhdGluMS5ycw=="},"lines":{"bytes":"Y2Fm6SBmbiB4Cg=="},"line_number":3,"absolute_offset":0,"submatches":[{"match":{"text":"fn"},"start":5,"end":7}]}}"#;
+        let grep_line = parse_line(line).unwrap();
+        assert_eq!(grep_line.path, "src/latin1.rs");
+        assert_eq!(grep_line.code, "caf\u{FFFD} fn x");
+        let (start, end) = grep_line.submatches.unwrap()[0];
+        assert_eq!(&grep_line.code[start..end], "fn");
+    }
 }
@@ -1,7 +1,25 @@
+use bytelines::ByteLinesReader;
 use lazy_static::lazy_static;
 use regex::Regex;
 
+use crate::ansi;
+use crate::cli;
+use crate::config::Config;
 use crate::delta::{State, StateMachine};
+use crate::features;
+use crate::features::side_by_side::{ansifill, SideBySideData};
+use crate::handlers::diff_header;
+
+const SUBMODULE_DIFF_BORDER: &str = "│ ";
+const SUBMODULE_DIFF_BORDER_WIDTH: usize = 2;
+
+/// The diff of a submodule, with gitconfig diff.submodule = diff: the diffs of its files follow
+/// the "Submodule" line, with paths prefixed by the path of the submodule.
+pub struct SubmoduleDiff {
+    // The path of the submodule, followed by "/".
+    path_prefix: String,
+    lines: Vec<u8>,
+}
 
 impl<'a> StateMachine<'a> {
     #[inline]
@@ -13,7 +31,175 @@ impl<'a> StateMachine<'a> {
         if !self.test_submodule_log() {
             return Ok(false);
         }
-        self.handle_additional_cases(State::SubmoduleLog)
+        let handled_line = match self.format_submodule_line() {
+            Some(line) => {
+                self.painter.paint_buffered_minus_and_plus_lines();
+                self.state = State::SubmoduleLog;
+                if self.should_handle() {
+                    self.painter.emit()?;
+                    diff_header::write_generic_diff_header_header_line(
+                        &line,
+                        &self.raw_line,
+                        &mut self.painter,
+                        &mut self.mode_info,
+                        self.config,
+                    )?;
+                }
+                self.should_handle()
+            }
+            None => self.handle_additional_cases(State::SubmoduleLog)?,
+        };
+        if !self.config.color_only {
+            self.submodule_diff = get_submodule_diff_path(&self.line).map(|path| SubmoduleDiff {
+                path_prefix: format!("{}/", path),
+                lines: Vec::new(),
+            });
+        }
+        Ok(handled_line)
+    }
+
+    /// Return the "Submodule" line with the commits of its range in the styles of the commits
+    /// removed and added, and the range linked to their comparison on the remote repository of the
+    /// submodule if --hyperlinks is set.
+    fn format_submodule_line(&self) -> Option<String> {
+        if self.config.color_only {
+            return None;
+        }
+        let caps = SUBMODULE_LINE_REGEX.captures(&self.line)?;
+        let path = caps.get(1).unwrap().as_str();
+        let (from, dots, to) = (
+            caps.get(2).unwrap().as_str(),
+            caps.get(3).unwrap().as_str(),
+            caps.get(4).unwrap().as_str(),
+        );
+        let file_style = self.config.file_style;
+        let range = format!(
+            "{}{}{}",
+            self.config.submodule_commit_removed_style.paint(from),
+            file_style.paint(dots),
+            self.config.submodule_commit_added_style.paint(to),
+        );
+        let is_null = |commit: &str| commit.bytes().all(|b| b == b'0');
+        let range = if self.config.hyperlinks && !is_null(from) && !is_null(to) {
+            features::hyperlinks::format_osc8_submodule_compare_hyperlink(
+                path,
+                from,
+                to,
+                &range,
+                self.config,
+            )
+            .unwrap_or(range)
+        } else {
+            range
+        };
+        Some(format!(
+            "Submodule {} {}{}",
+            path,
+            range,
+            file_style.paint(caps.get(5).unwrap().as_str())
+        ))
+    }
+
+    #[inline]
+    fn test_submodule_log_commit_line(&self) -> bool {
+        self.state == State::SubmoduleLog
+            && (self.line.starts_with("  > ") || self.line.starts_with("  < "))
+    }
+
+    /// Paint a commit listed after a "Submodule" line, with gitconfig diff.submodule = log.
+    pub fn handle_submodule_log_commit_line(&mut self) -> std::io::Result<bool> {
+        if !self.test_submodule_log_commit_line() || self.config.color_only {
+            return Ok(false);
+        }
+        let style = if self.line.starts_with("  > ") {
+            self.config.submodule_commit_added_style
+        } else {
+            self.config.submodule_commit_removed_style
+        };
+        self.painter.emit()?;
+        writeln!(self.painter.writer, "{}", style.paint(&self.line))?;
+        Ok(true)
+    }
+
+    /// Whether the line belongs to the diff of the submodule whose "Submodule" line was the last
+    /// one, if any. The diff ends at a line of the diff of a file outside the submodule, or of
+    /// another submodule, or at a commit line.
+    pub fn is_submodule_diff_line(&self, raw_line_bytes: &[u8]) -> bool {
+        let submodule_diff = match &self.submodule_diff {
+            Some(submodule_diff) => submodule_diff,
+            None => return false,
+        };
+        let line = ansi::strip_ansi_codes(&String::from_utf8_lossy(raw_line_bytes));
+        if let Some(paths) = line.strip_prefix("diff --git ") {
+            // The paths may have a prefix such as "a/", or none.
+            let mut chars = paths.chars();
+            paths.starts_with(&submodule_diff.path_prefix)
+                || chars.next().is_some()
+                    && chars.as_str().starts_with('/')
+                    && chars.as_str()[1..].starts_with(&submodule_diff.path_prefix)
+        } else if let Some(rest) = line.strip_prefix("Submodule ") {
+            // A submodule of the submodule.
+            rest.starts_with(&submodule_diff.path_prefix)
+        } else {
+            !submodule_diff.lines.is_empty()
+                && !line.starts_with("diff ")
+                && !line.starts_with("commit ")
+        }
+    }
+
+    /// Collect the line if it belongs to the diff of a submodule. Otherwise write the diff of the
+    /// submodule collected until then, if any, and return false.
+    pub fn handle_submodule_diff_line(&mut self, raw_line_bytes: &[u8]) -> std::io::Result<bool> {
+        if self.submodule_diff.is_none() {
+            return Ok(false);
+        }
+        if self.is_submodule_diff_line(raw_line_bytes) {
+            let lines = &mut self.submodule_diff.as_mut().unwrap().lines;
+            lines.extend_from_slice(raw_line_bytes);
+            lines.push(b'\n');
+            return Ok(true);
+        }
+        self.write_submodule_diff()?;
+        Ok(false)
+    }
+
+    /// Handle the collected diff of a submodule, if any, by a state machine of its own, and write
+    /// its output preceded by a border in submodule-style.
+    pub fn write_submodule_diff(&mut self) -> std::io::Result<()> {
+        let submodule_diff = match self.submodule_diff.take() {
+            Some(submodule_diff) if !submodule_diff.lines.is_empty() => submodule_diff,
+            _ => return Ok(()),
+        };
+        self.painter.paint_buffered_minus_and_plus_lines();
+        self.painter.emit()?;
+        let output_widths = OutputWidths::of(self.config);
+        output_widths.narrowed(self.config).set(self.config);
+        let mut output = Vec::new();
+        let mut machine = StateMachine::new(&mut output, self.config);
+        let result = machine.handle_lines(submodule_diff.lines.as_slice().byte_lines());
+        let diff_summary = std::mem::take(&mut machine.diff_summary);
+        drop(machine);
+        output_widths.set(self.config);
+        result?;
+        self.diff_summary.files += diff_summary.files;
+        self.diff_summary.insertions += diff_summary.insertions;
+        self.diff_summary.deletions += diff_summary.deletions;
+        self.diff_summary.has_merge_conflict |= diff_summary.has_merge_conflict;
+
+        for line in String::from_utf8_lossy(&output).lines() {
+            let border = if line.is_empty() {
+                SUBMODULE_DIFF_BORDER.trim_end()
+            } else {
+                SUBMODULE_DIFF_BORDER
+            };
+            writeln!(
+                self.painter.writer,
+                "{}{}",
+                self.config.submodule_style.paint(border),
+                line
+            )?;
+        }
+        Ok(())
     }
 
     #[inline]
@@ -49,6 +235,75 @@ impl<'a> StateMachine<'a> {
     }
 }
 
+/// The widths of the output: the diff of a submodule is displayed in the width remaining beside
+/// its border.
+#[derive(Clone, Copy)]
+struct OutputWidths {
+    available_terminal_width: usize,
+    decorations_width: cli::Width,
+    side_by_side_data: SideBySideData,
+}
+
+impl OutputWidths {
+    fn of(config: &Config) -> Self {
+        Self {
+            available_terminal_width: config.available_terminal_width.get(),
+            decorations_width: config.decorations_width.get(),
+            side_by_side_data: config.side_by_side_data.get(),
+        }
+    }
+
+    fn set(self, config: &Config) {
+        config
+            .available_terminal_width
+            .set(self.available_terminal_width);
+        config.decorations_width.set(self.decorations_width);
+        config.side_by_side_data.set(self.side_by_side_data);
+    }
+
+    fn narrowed(self, config: &Config) -> Self {
+        let available_terminal_width = self
+            .available_terminal_width
+            .saturating_sub(SUBMODULE_DIFF_BORDER_WIDTH);
+        let decorations_width = match self.decorations_width {
+            cli::Width::Fixed(width) => {
+                cli::Width::Fixed(width.saturating_sub(SUBMODULE_DIFF_BORDER_WIDTH))
+            }
+            cli::Width::Variable => cli::Width::Variable,
+        };
+        let side_by_side_data = ansifill::UseFullPanelWidth::sbs_odd_fix(
+            &decorations_width,
+            &config.line_fill_method,
+            SideBySideData::new_sbs(
+                &decorations_width,
+                &available_terminal_width,
+                config.side_by_side_left_permille,
+            ),
+        );
+        Self {
+            available_terminal_width,
+            decorations_width,
+            side_by_side_data,
+        }
+    }
+}
+
+lazy_static! {
+    // E.g. "Submodule sub 7a26f0f..30a23d7:" or "Submodule sub 7a26f0f...30a23d7 (rewind):"
+    static ref SUBMODULE_LINE_REGEX: Regex = Regex::new(
+        r"^Submodule (.+) ([0-9a-f]{7,})(\.\.\.?)([0-9a-f]{7,})((?: \(.+\))?:?)$"
+    )
+    .unwrap();
+}
+
+/// The path of the submodule of a "Submodule" line which may be followed by the diff of the
+/// submodule, e.g. "Submodule sub 7a26f0f..30a23d7:".
+pub fn get_submodule_diff_path(line: &str) -> Option<&str> {
+    SUBMODULE_LINE_REGEX
+        .captures(line)
+        .map(|caps| caps.get(1).unwrap().as_str())
+}
+
 lazy_static! {
     static ref SUBMODULE_SHORT_LINE_REGEX: Regex =
         Regex::new("^[-+]Subproject commit ([0-9a-f]{40})$").unwrap();
@@ -0,0 +1,390 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::delta::{DiffType, State, StateMachine};
+use crate::paint::{expand_tabs, has_space_before_tab, prepare};
+
+/// The kind of a record of `git diff --word-diff=porcelain` output: unchanged, removed or added
+/// text of a line. The records of a line are followed by a "~" line, e.g.
+///
+/// ```text
+///  let
+/// -x
+/// +y
+///  = 1;
+/// ~
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WordDiffToken {
+    Zero,
+    Minus,
+    Plus,
+}
+
+/// Whether the hunks are in `git diff --word-diff=porcelain` format. This is known once a "~"
+/// line is read, or a line which cannot follow the records read since the hunk header, which are
+/// held until then.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum WordDiffPorcelain {
+    #[default]
+    Unknown,
+    Yes,
+    // The current hunk is not: the next one may be.
+    No,
+}
+
+impl<'a> StateMachine<'a> {
+    #[inline]
+    fn test_word_diff_porcelain_line(&self) -> bool {
+        !self.config.color_only
+            && matches!(
+                self.state,
+                State::HunkHeader(DiffType::Unified, _, _, _)
+                    | State::HunkZero(DiffType::Unified, _)
+                    | State::HunkMinus(DiffType::Unified, _)
+                    | State::HunkPlus(DiffType::Unified, _)
+            )
+    }
+
+    /// Handle a line of a hunk of `git diff --word-diff=porcelain` output. The records of a line
+    /// are collected until its "~" line, and then painted as a single line.
+    pub fn handle_word_diff_porcelain_line(&mut self) -> std::io::Result<bool> {
+        if !self.test_word_diff_porcelain_line() {
+            return Ok(false);
+        }
+        if let State::HunkHeader(_, parsed_hunk_header, line, raw_line) = &self.state.clone() {
+            if self.word_diff_porcelain == WordDiffPorcelain::No {
+                self.word_diff_porcelain = WordDiffPorcelain::Unknown;
+            }
+            if self.word_diff_porcelain == WordDiffPorcelain::Unknown
+                && get_word_diff_token(&self.line).is_none()
+                && self.line != "~"
+            {
+                return Ok(false);
+            }
+            self.emit_hunk_header_line(parsed_hunk_header, line, raw_line)?;
+            self.state = State::HunkZero(DiffType::Unified, None);
+        }
+        if self.word_diff_porcelain == WordDiffPorcelain::No {
+            return Ok(false);
+        }
+        if self.line == "~" {
+            self.word_diff_porcelain = WordDiffPorcelain::Yes;
+            self.paint_word_diff_line()?;
+            return Ok(true);
+        }
+        let token = get_word_diff_token(&self.line);
+        let is_record = match (self.word_diff_porcelain, token) {
+            (WordDiffPorcelain::Yes, Some(_)) => true,
+            // In porcelain format the removed, added and unchanged text between two changes is
+            // in a single record, and removed text precedes added text.
+            (WordDiffPorcelain::Unknown, Some(token)) => !matches!(
+                (self.word_diff_records.last(), token),
+                (Some((last, _)), token)
+                    if get_word_diff_token(last) == Some(token)
+                        || get_word_diff_token(last) == Some(WordDiffToken::Plus)
+                            && token == WordDiffToken::Minus
+            ),
+            _ => false,
+        };
+        if is_record {
+            self.word_diff_records
+                .push((self.line.clone(), self.raw_line.clone()));
+            return Ok(true);
+        }
+        if self.word_diff_porcelain == WordDiffPorcelain::Yes {
+            // A line which was not ended by a "~" line.
+            self.write_word_diff_line()?;
+            return Ok(false);
+        }
+        // The held lines, and this one, are handled as lines of a unified diff.
+        self.word_diff_records
+            .push((self.line.clone(), self.raw_line.clone()));
+        self.write_word_diff_line()?;
+        Ok(true)
+    }
+
+    /// Paint the line made of the collected records, if any, or, if the hunk is not in porcelain
+    /// format, handle the held lines as lines of a unified diff.
+    pub fn write_word_diff_line(&mut self) -> std::io::Result<()> {
+        if self.word_diff_records.is_empty() {
+            return Ok(());
+        }
+        if self.word_diff_porcelain == WordDiffPorcelain::Yes {
+            self.paint_word_diff_line()
+        } else {
+            self.word_diff_porcelain = WordDiffPorcelain::No;
+            for (_, raw_line) in std::mem::take(&mut self.word_diff_records) {
+                self.handle_line(raw_line.as_bytes())?;
+            }
+            Ok(())
+        }
+    }
+
+    // A line with only removed or only added text is a minus or plus line, painted with the
+    // minus and plus lines around it.
+    fn paint_word_diff_line(&mut self) -> std::io::Result<()> {
+        let tab_width = self.config.tab_width;
+        let tokens: Vec<(WordDiffToken, String)> = self
+            .word_diff_records
+            .drain(..)
+            .filter_map(|(line, _)| {
+                let token = get_word_diff_token(&line)?;
+                let text = expand_tabs(line.graphemes(true).skip(1), tab_width);
+                Some((token, text))
+            })
+            .collect();
+        let is_only = |only: WordDiffToken| {
+            !tokens.is_empty() && tokens.iter().all(|(token, _)| *token == only)
+        };
+        if is_only(WordDiffToken::Minus) || is_only(WordDiffToken::Plus) {
+            let text: String = tokens.iter().map(|(_, text)| text.as_str()).collect();
+            let line = prepare(&text, 0, self.config);
+            self.painter.paint_context_lines(true);
+            if is_only(WordDiffToken::Minus) {
+                let state = State::HunkMinus(DiffType::Unified, None);
+                self.painter.minus_lines.push((line, state));
+                self.diff_summary.deletions += 1;
+            } else {
+                let state = State::HunkPlus(DiffType::Unified, None);
+                self.painter.plus_lines.push((line, state));
+                self.painter
+                    .plus_lines_space_before_tab
+                    .push(has_space_before_tab(&text));
+                self.diff_summary.insertions += 1;
+            }
+        } else if tokens
+            .iter()
+            .all(|(token, _)| *token == WordDiffToken::Zero)
+        {
+            if !self.painter.minus_lines.is_empty() || !self.painter.plus_lines.is_empty() {
+                self.painter.paint_buffered_minus_and_plus_lines();
+                self.painter.context_lines_follow_change = true;
+            }
+            let text: String = tokens.iter().map(|(_, text)| text.as_str()).collect();
+            let line = prepare(&text, 0, self.config);
+            let state = State::HunkZero(DiffType::Unified, None);
+            let space_before_tab = has_space_before_tab(&text);
+            if self.painter.collects_context_lines() {
+                self.painter.context_lines.push((line, state));
+                self.painter
+                    .context_lines_space_before_tab
+                    .push(space_before_tab);
+            } else {
+                self.painter.paint_zero_line(&line, state, space_before_tab);
+            }
+        } else {
+            self.painter.paint_context_lines(true);
+            self.painter.paint_buffered_minus_and_plus_lines();
+            self.painter.paint_word_diff_line(&tokens);
+            self.painter.context_lines_follow_change = true;
+            self.diff_summary.deletions += 1;
+            self.diff_summary.insertions += 1;
+        }
+        self.painter.emit()
+    }
+}
+
+fn get_word_diff_token(line: &str) -> Option<WordDiffToken> {
+    match line.chars().next() {
+        Some(' ') => Some(WordDiffToken::Zero),
+        Some('-') => Some(WordDiffToken::Minus),
+        Some('+') => Some(WordDiffToken::Plus),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::integration_test_utils::DeltaTest;
+
+    #[test]
+    fn test_word_diff_porcelain() {
+        let output = DeltaTest::with_args(&[])
+            .with_input(GIT_DIFF_WORD_DIFF_PORCELAIN)
+            .expect_contains(
+                "
+fn main() {
+    let xy = 1;2;
+
+    println!(\"{}\", x);
+    oldy);
+    new line
+    added
+}
+",
+            )
+            .output;
+        assert!(!output.contains('~'));
+    }
+
+    #[test]
+    fn test_word_diff_porcelain_emph_styles() {
+        DeltaTest::with_args(&[
+            "--minus-emph-style",
+            "bold red",
+            "--plus-emph-style",
+            "bold green",
+        ])
+        .with_input(GIT_DIFF_WORD_DIFF_PORCELAIN)
+        .expect_raw_contains("\x1b[1;31m1;\x1b[32m2;\x1b[0m")
+        .expect_raw_contains("\x1b[1;32madded\x1b[0m");
+    }
+
+    #[test]
+    fn test_word_diff_porcelain_side_by_side() {
+        DeltaTest::with_args(&["--side-by-side", "--width", "60"])
+            .with_input(GIT_DIFF_WORD_DIFF_PORCELAIN)
+            .expect_contains("│2│    let x = 1;")
+            .expect_contains("│2│    let y = 2;")
+            .expect_contains("│7│    added");
+    }
+
+    #[test]
+    fn test_word_diff_porcelain_side_by_side_syntax_highlighting() {
+        // Each line is highlighted once: the comment opened on the old version of the first line
+        // does not extend to the next one.
+        let input = "\
+diff --git a/a.rs b/a.rs
+index 752ccb1..6c55f2e 100644
+--- a/a.rs
++++ b/a.rs
+@@ -1,2 +1,2 @@
+ let s = 1; 
+-/*
+~
+ let t = 1;
+~
+";
+        DeltaTest::with_args(&["--side-by-side", "--width", "60"])
+            .explain_ansi()
+            .with_input(input)
+            .expect_after_skip(
+                7,
+                r#"
+(blue)│(88)1(blue)│(81 52)let(231) s (203)=(231) (141)1(231); (242 124)/*(normal 52)              (blue)│(28)1(blue)│(81 22)let(231) s (203)=(231) (141)1(231); (normal)
+(blue)│(238)2(blue)│(81)let(231) t (203)=(231) (141)1(231);(normal)                 (blue)│(238)2(blue)│(81)let(231) t (203)=(231) (141)1(231);(normal)"#,
+            );
+    }
+
+    #[test]
+    fn test_word_diff_porcelain_files() {
+        // The hunks of a file after one in porcelain format are recognized as unified hunks.
+        let input = format!(
+            "{}\
+diff --git a/b.txt b/b.txt
+index 1111111..2222222 100644
+--- a/b.txt
++++ b/b.txt
+@@ -1,2 +1,2 @@
+-a
++b
+ c
+",
+            GIT_DIFF_WORD_DIFF_PORCELAIN
+        );
+        let output = DeltaTest::with_args(&[]).with_input(&input).output;
+        assert!(output.ends_with("\na\nb\nc\n"), "{}", output);
+    }
+
+    #[test]
+    fn test_word_diff_plain_and_color() {
+        let input = "\
+diff --git a/a.rs b/a.rs
+index c226300..7a11056 100644
+--- a/a.rs
++++ b/a.rs
+@@ -1,4 +1,4 @@
+fn main() {
+    let [-x-]{+y+} = 1;
+    println!(\"{}\", [-x);-]{+y);+}
+}
+";
+        DeltaTest::with_args(&[])
+            .with_calling_process("git diff --word-diff=plain")
+            .with_input(input)
+            .expect_after_skip(
+                7,
+                "
+#indent_mark
+fn main() {
+    let [-x-]{+y+} = 1;
+    println!(\"{}\", [-x);-]{+y);+}
+}",
+            );
+        let input = "\
+\x1b[1mdiff --git a/a.rs b/a.rs\x1b[m
+\x1b[1mindex c226300..7a11056 100644\x1b[m
+\x1b[1m--- a/a.rs\x1b[m
+\x1b[1m+++ b/a.rs\x1b[m
+\x1b[36m@@ -1,4 +1,4 @@\x1b[m
+fn main() {\x1b[m
+    let \x1b[31mx\x1b[m\x1b[32my\x1b[m = 1;
+    println!(\"{}\", \x1b[31mx);\x1b[m\x1b[32my);\x1b[m
+}\x1b[m
+";
+        DeltaTest::with_args(&[])
+            .with_calling_process("git diff --word-diff=color")
+            .with_input(input)
+            .expect_after_skip(
+                7,
+                "
+#indent_mark
+fn main() {
+    let xy = 1;
+    println!(\"{}\", x);y);
+}",
+            )
+            .expect_raw_contains("    let \x1b[31mx\x1b[32my\x1b[0m = 1;");
+    }
+
+    #[test]
+    fn test_unified_hunk_resembling_word_diff_porcelain() {
+        let input = "\
+diff --git a/a.txt b/a.txt
+index 1111111..2222222 100644
+--- a/a.txt
++++ b/a.txt
+@@ -1,3 +1,3 @@
+-a
++b
+ c
+-d
++e
+";
+        let output = DeltaTest::with_args(&[]).with_input(input).output;
+        assert!(output.ends_with("\na\nb\nc\nd\ne\n"), "{}", output);
+    }
+
+    const GIT_DIFF_WORD_DIFF_PORCELAIN: &str = "\
+diff --git a/a.rs b/a.rs
+index 752ccb1..6c55f2e 100644
+--- a/a.rs
++++ b/a.rs
+@@ -1,6 +1,7 @@
+ fn main() {
+~
+     let 
+-x
++y
+  = 
+-1;
++2;
+~
+ 
+~
+     println!(\"{}\", 
+-x);
+~
+-    old
++y);
+~
++    new
+  line
+~
+     
++added
+~
+ }
+~
+";
+}
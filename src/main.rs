@@ -30,6 +30,7 @@ mod subcommands;
 mod tests;
 
 use std::io::{self, ErrorKind};
+use std::path::Path;
 use std::process;
 
 use bytelines::ByteLinesReader;
@@ -93,10 +94,19 @@ fn run_app() -> std::io::Result<i32> {
         assets,
     );
 
+    if opt.validate_config {
+        let stdout = io::stdout();
+        let mut stdout = stdout.lock();
+        let is_valid = subcommands::validate_config::validate_config(&opt, &mut stdout)?;
+        return Ok(if is_valid { 0 } else { 1 });
+    }
+
     let subcommand_result = if opt.list_languages {
         Some(list_languages())
     } else if opt.list_syntax_themes {
         Some(subcommands::list_syntax_themes::list_syntax_themes())
+    } else if opt.list_themes {
+        Some(subcommands::list_themes::list_themes())
     } else if opt.show_syntax_themes {
         Some(subcommands::show_syntax_themes::show_syntax_themes())
     } else if opt.show_themes {
@@ -123,17 +133,41 @@ fn run_app() -> std::io::Result<i32> {
     };
 
     let _show_config = opt.show_config;
+    let _generate_lesskey = opt.generate_lesskey;
+    // The features and whether stdout is a terminal are reported by --dry-run, but are not kept in
+    // the config.
+    let _dry_run = opt
+        .dry_run
+        .then(|| (opt.features.clone(), opt.computed.stdout_is_term));
     let config = config::Config::from(opt);
 
-    if _show_config {
+    if let Some((features, stdout_is_term)) = _dry_run {
+        let stderr = io::stderr();
+        let mut stderr = stderr.lock();
+        subcommands::dry_run::dry_run(
+            &config,
+            features.as_deref().unwrap_or_default(),
+            stdout_is_term,
+            &env,
+            &mut stderr,
+        )?;
+        return Ok(0);
+    } else if _show_config {
         let stdout = io::stdout();
         let mut stdout = stdout.lock();
-        subcommands::show_config::show_config(&config, &mut stdout)?;
+        subcommands::show_config::show_config(&config, &env, &mut stdout)?;
+        return Ok(0);
+    } else if _generate_lesskey {
+        subcommands::generate_lesskey::generate_lesskey(&config)?;
         return Ok(0);
     }
 
-    let mut output_type =
-        OutputType::from_mode(&env, config.paging_mode, config.pager.clone(), &config).unwrap();
+    let mut output_type = match &config.output {
+        Some(path) => OutputType::file(path).unwrap_or_else(|err| fatal(err.to_string())),
+        None => {
+            OutputType::from_mode(&env, config.paging_mode, config.pager.clone(), &config).unwrap()
+        }
+    };
     let mut writer = output_type.handle().unwrap();
 
     match (config.minus_file.as_ref(), config.plus_file.as_ref()) {
@@ -147,13 +181,21 @@ fn run_app() -> std::io::Result<i32> {
                 "\
     The main way to use delta is to configure it as the pager for git: \
     see https://github.com/dandavison/delta#configuration. \
-    You can also use delta to diff two files: `delta file_A file_B`."
+    You can also use delta to diff two files or directories: `delta file_A file_B`."
             );
             return Ok(config.error_exit_code);
         }
     }
 
-    if let Err(error) = delta(io::stdin().lock().byte_lines(), &mut writer, &config) {
+    let result = match &config.input {
+        Some(path) => {
+            let input = utils::input::open_input_file(Path::new(path))
+                .unwrap_or_else(|err| fatal(format!("Failed to read {}: {}", path, err)));
+            delta(input.byte_lines(), &mut writer, &config)
+        }
+        None => delta(io::stdin().lock().byte_lines(), &mut writer, &config),
+    };
+    if let Err(error) = result {
         match error.kind() {
             ErrorKind::BrokenPipe => return Ok(0),
             _ => eprintln!("{}", error),
@@ -5,7 +5,7 @@ use std::collections::HashMap;
 use crate::cli;
 use crate::features;
 use crate::git_config::{self, GitConfigGet};
-use crate::options::option_value::{OptionValue, ProvenancedOptionValue};
+use crate::options::option_value::{OptionProvenance, OptionValue, ProvenancedOptionValue};
 use ProvenancedOptionValue::*;
 
 // Look up a value of type `T` associated with `option name`. The search rules are:
@@ -25,12 +25,14 @@ use ProvenancedOptionValue::*;
 //        Otherwise, record the default value and continue searching.
 //
 // 3. Return the last default value that was encountered.
+//
+// The value is returned together with where it was found.
 pub fn get_option_value<T>(
     option_name: &str,
     builtin_features: &HashMap<String, features::BuiltinFeature>,
     opt: &cli::Opt,
     git_config: &mut Option<git_config::GitConfig>,
-) -> Option<T>
+) -> Option<(T, OptionProvenance)>
 where
     T: GitConfigGet,
     T: GetOptionValue,
@@ -67,7 +69,7 @@ pub trait GetOptionValue {
         builtin_features: &HashMap<String, features::BuiltinFeature>,
         opt: &cli::Opt,
         git_config: &mut Option<git_config::GitConfig>,
-    ) -> Option<Self>
+    ) -> Option<(Self, OptionProvenance)>
     where
         Self: Sized,
         Self: GitConfigGet,
@@ -76,7 +78,7 @@ pub trait GetOptionValue {
     {
         if let Some(git_config) = git_config {
             if let Some(value) = git_config.get::<Self>(&format!("delta.{}", option_name)) {
-                return Some(value);
+                return Some((value, OptionProvenance::GitConfig));
             }
         }
         if let Some(features) = &opt.features {
@@ -88,8 +90,9 @@ pub trait GetOptionValue {
                     opt,
                     git_config,
                 ) {
-                    Some(GitConfigValue(value)) | Some(DefaultValue(value)) => {
-                        return Some(value.into());
+                    Some((GitConfigValue(value), provenance))
+                    | Some((DefaultValue(value), provenance)) => {
+                        return Some((value.into(), provenance));
                     }
                     None => {}
                 }
@@ -100,14 +103,15 @@ pub trait GetOptionValue {
 
     /// Return the value, or default value, associated with `option_name` under feature name
     /// `feature`. This may refer to a custom feature, or a builtin feature, or both. Only builtin
-    /// features have defaults. See `GetOptionValue::get_option_value`.
+    /// features have defaults. The value is returned with the kind of feature it was found in. See
+    /// `GetOptionValue::get_option_value`.
     fn get_provenanced_value_for_feature(
         option_name: &str,
         feature: &str,
         builtin_features: &HashMap<String, features::BuiltinFeature>,
         opt: &cli::Opt,
         git_config: &mut Option<git_config::GitConfig>,
-    ) -> Option<ProvenancedOptionValue>
+    ) -> Option<(ProvenancedOptionValue, OptionProvenance)>
     where
         Self: Sized,
         Self: GitConfigGet,
@@ -117,12 +121,18 @@ pub trait GetOptionValue {
             if let Some(value) =
                 git_config.get::<Self>(&format!("delta.{}.{}", feature, option_name))
             {
-                return Some(GitConfigValue(value.into()));
+                return Some((
+                    GitConfigValue(value.into()),
+                    OptionProvenance::CustomFeature(feature.to_string()),
+                ));
             }
         }
         if let Some(builtin_feature) = builtin_features.get(feature) {
             if let Some(value_function) = builtin_feature.get(option_name) {
-                return Some(value_function(opt, git_config));
+                return Some((
+                    value_function(opt, git_config),
+                    OptionProvenance::BuiltinFeature(feature.to_string()),
+                ));
             }
         }
         None
@@ -263,8 +273,8 @@ pub mod tests {
             git_config_contents,
             git_config_path,
             "'delta.max-line-distance=0.7'".into(),
-            &|opt: Opt| assert_eq!(opt.max_line_distance, 0.6),
-            &|opt: Opt| assert_eq!(opt.max_line_distance, 0.7),
+            &|opt: Opt| assert_eq!(opt.max_line_distance, "0.6"),
+            &|opt: Opt| assert_eq!(opt.max_line_distance, "0.7"),
         );
     }
 
@@ -1,3 +1,5 @@
+use std::fmt;
+
 use crate::config::delta_unreachable;
 
 /// A value associated with a Delta command-line option name.
@@ -15,6 +17,34 @@ pub enum ProvenancedOptionValue {
     DefaultValue(OptionValue),
 }
 
+/// Where the value of an option was found. Options absent from the map of provenances recorded
+/// when setting options have their default value.
+#[derive(Clone, Debug, PartialEq)]
+pub enum OptionProvenance {
+    CommandLine,
+    // The main [delta] section of git config.
+    GitConfig,
+    // A [delta "feature"] section of git config.
+    CustomFeature(String),
+    BuiltinFeature(String),
+    // An environment variable such as NO_COLOR that decided the value of an "auto" option.
+    EnvironmentVariable(String),
+}
+
+impl fmt::Display for OptionProvenance {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OptionProvenance::CommandLine => write!(f, "command line"),
+            OptionProvenance::GitConfig => write!(f, "[delta]"),
+            OptionProvenance::CustomFeature(feature) => write!(f, "[delta \"{}\"]", feature),
+            OptionProvenance::BuiltinFeature(feature) => write!(f, "builtin feature {}", feature),
+            OptionProvenance::EnvironmentVariable(name) => {
+                write!(f, "environment variable {}", name)
+            }
+        }
+    }
+}
+
 impl From<bool> for OptionValue {
     fn from(value: bool) -> Self {
         OptionValue::Boolean(value)
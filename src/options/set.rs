@@ -13,7 +13,7 @@ use crate::errors::*;
 use crate::fatal;
 use crate::features;
 use crate::git_config::{GitConfig, GitConfigEntry};
-use crate::options::option_value::{OptionValue, ProvenancedOptionValue};
+use crate::options::option_value::{OptionProvenance, OptionValue, ProvenancedOptionValue};
 use crate::options::theme;
 use crate::utils::bat::output::PagingMode;
 
@@ -24,15 +24,21 @@ macro_rules! set_options {
         $(
             let kebab_case_field_name = stringify!($field_ident).replace("_", "-");
             let option_name = $expected_option_name_map[kebab_case_field_name.as_str()];
-            if !$crate::config::user_supplied_option(&kebab_case_field_name, $arg_matches) {
-                if let Some(value) = $crate::options::get::get_option_value(
+            if $crate::config::user_supplied_option(&kebab_case_field_name, $arg_matches) {
+                $opt.computed.option_provenances.insert(
+                    option_name.to_string(),
+                    $crate::options::option_value::OptionProvenance::CommandLine,
+                );
+            } else if let Some((value, provenance)) =
+                $crate::options::get::get_option_value(
                     option_name,
                     &$builtin_features,
                     $opt,
                     $git_config
-                ) {
-                    $opt.$field_ident = value;
-                }
+                )
+            {
+                $opt.$field_ident = value;
+                $opt.computed.option_provenances.insert(option_name.to_string(), provenance);
             }
             if $check_names {
                 option_names.insert(option_name);
@@ -43,13 +49,17 @@ macro_rules! set_options {
                 "24-bit-color",
                 "diff-highlight", // Does not exist as a flag on config
                 "diff-so-fancy", // Does not exist as a flag on config
+                "exclude-files", // Repeatable, so not read from git config
                 "features",  // Processed differently
+                "features-order",  // Processed differently
                 "help", // automatically added by clap
+                "include-files", // Repeatable, so not read from git config
                 // Set prior to the rest
                 "no-gitconfig",
                 "dark",
                 "light",
                 "syntax-theme",
+                "theme", // Processed as a feature
                 "version", // automatically added by clap
             ]);
             let expected_option_names: HashSet<_> = $expected_option_name_map.values().cloned().collect();
@@ -76,7 +86,8 @@ pub fn set_options(
         }
         set_git_config_entries(opt, git_config);
     }
-    opt.navigate = opt.navigate || opt.env.navigate.is_some();
+    // The lesskey bindings must match the labels emitted in navigate mode.
+    opt.navigate = opt.navigate || opt.env.navigate.is_some() || opt.generate_lesskey;
     if opt.syntax_theme.is_none() {
         opt.syntax_theme = opt.env.bat_theme.clone();
     }
@@ -126,94 +137,202 @@ pub fn set_options(
 
     set_options!(
         [
+            binary_diff,
+            binary_diff_max_size,
+            binary_style,
+            blame_boundary_style,
             blame_code_style,
+            blame_field_separator,
             blame_format,
+            blame_metadata_width,
             blame_separator_format,
             blame_palette,
+            blame_palette_assignment,
+            blame_palette_hash_key,
+            blame_palette_seed,
             blame_separator_style,
             blame_timestamp_format,
+            blame_timestamp_relative,
+            blame_timestamp_relative_threshold,
+            blame_uncommitted_style,
+            change_magnitude_shading,
+            collapse_file_glob,
+            collapse_files_over,
+            collapse_renamed_paths,
+            collapsed_file_style,
+            color,
             color_only,
             commit_decoration_style,
+            commit_format,
             commit_regex,
+            commit_regex_exclusive,
             commit_style,
             default_language,
+            detect_background,
             diff_stat_align_width,
+            diff_stat_rescale,
+            diff_stat_summary_style,
+            diff_summary,
+            diff_summary_style,
+            dim_unchanged_words,
+            emit_manifest,
+            emph_mode,
+            exclude,
             file_added_label,
             file_copied_label,
             file_decoration_style,
             file_modified_label,
+            file_path_reminder_interval,
             file_removed_label,
+            file_renamed_format,
             file_renamed_label,
             file_regex_replacement,
             right_arrow,
+            // Hack: navigate-granularity must come before hunk-label because the navigate
+            // feature's default of the latter depends on the former.
+            navigate_granularity,
             hunk_label,
             file_style,
+            git_diff_line_style,
             grep_context_line_style,
+            grep_context_separator,
+            grep_context_separator_style,
             grep_file_style,
+            grep_function_context_style,
             grep_line_number_style,
             grep_match_line_style,
             grep_match_word_style,
             grep_separator_symbol,
+            grep_syntax_highlighting,
+            hexdump_binary,
+            image_preview_max_height,
+            image_previews,
+            hidden_context_style,
+            hide_commit_metadata,
+            hide_empty_commits,
+            hide_context,
             hunk_header_decoration_style,
             hunk_header_file_style,
+            hunk_header_function_style,
             hunk_header_line_number_style,
             hunk_header_style,
             hyperlinks,
             hyperlinks_commit_link_format,
+            hyperlinks_file_commit_link_format,
             hyperlinks_file_link_format,
+            hyperlinks_file_link_rules,
+            hyperlinks_infer_remote,
+            hyperlinks_minus_line_numbers,
+            hyperlinks_remote,
+            idempotent,
+            index_line_hash_style,
+            index_line_mode_style,
+            index_line_style,
             inline_hint_style,
+            input,
             inspect_raw_lines,
+            inspect_raw_lines_threshold,
+            keep_binary_patch,
+            keep_index_line,
+            keep_mode_lines,
             keep_plus_minus_markers,
             line_buffer_size,
+            long_line_display,
             map_styles,
             max_line_distance,
             max_line_length,
+            max_syntax_highlighting_length,
             // Hack: minus-style must come before minus-*emph-style because the latter default
             // dynamically to the value of the former.
+            merge_conflict_base_decoration_style,
+            merge_conflict_base_style,
             merge_conflict_begin_symbol,
+            merge_conflict_display,
             merge_conflict_end_symbol,
             merge_conflict_ours_diff_header_decoration_style,
             merge_conflict_ours_diff_header_style,
             merge_conflict_theirs_diff_header_decoration_style,
             merge_conflict_theirs_diff_header_style,
+            merge_parent_palette,
+            min_emph_length,
             minus_style,
+            minus_symbol,
             minus_emph_style,
+            minus_empty_line_marker,
             minus_empty_line_marker_style,
             minus_non_emph_style,
             minus_non_emph_style,
+            mode_change_style,
             navigate,
             navigate_regex,
             line_fill_method,
             line_numbers,
             line_numbers_left_format,
             line_numbers_left_style,
+            line_numbers_min_width,
             line_numbers_minus_style,
             line_numbers_plus_style,
+            line_numbers_relative,
             line_numbers_right_format,
             line_numbers_right_style,
             line_numbers_zero_style,
+            only_in_style,
+            output,
             pager,
+            pager_fallbacks,
+            pager_less_options,
+            pager_quit_if_one_screen,
             paging_mode,
             parse_ansi,
+            plain,
             // Hack: plus-style must come before plus-*emph-style because the latter default
             // dynamically to the value of the former.
             plus_style,
+            plus_symbol,
             plus_emph_style,
+            plus_empty_line_marker,
             plus_empty_line_marker_style,
             plus_non_emph_style,
+            preserve_context_ansi,
             raw,
+            range_diff_added_style,
+            range_diff_modified_style,
+            range_diff_removed_style,
+            range_diff_unchanged_style,
+            raw_file_glob,
             relative_paths,
             show_colors,
+            show_git_diff_line,
             show_themes,
             side_by_side,
+            side_by_side_align,
+            side_by_side_left_percent,
+            side_by_side_wrap,
+            stdin_label,
+            stdin_label_style,
+            submodule_commit_added_style,
+            submodule_commit_removed_style,
+            submodule_style,
+            wrap_at,
             wrap_max_lines,
+            wrap_prefer_word_boundary,
             wrap_right_prefix_symbol,
             wrap_right_percent,
             wrap_right_symbol,
+            wrap_truncation_format,
+            wrap_truncation_style,
+            wrap_word_boundary_slack,
             wrap_left_symbol,
             tab_width,
             tokenization_regex,
+            word_diff_regex_overrides,
+            word_diff_algorithm,
+            word_diff_context,
             true_color,
+            truncation_side,
+            truncation_symbol,
+            vcs,
+            whitespace_error_scope,
             whitespace_error_style,
             width,
             zero_style
@@ -229,10 +348,12 @@ pub fn set_options(
     // Setting ComputedValues
     set_widths_and_isatty(opt);
     set_true_color(opt);
+    set_color(opt);
     theme::set__is_light_mode__syntax_theme__syntax_set(opt, assets);
     opt.computed.inspect_raw_lines =
         cli::InspectRawLines::from_str(&opt.inspect_raw_lines).unwrap();
     opt.computed.paging_mode = parse_paging_mode(&opt.paging_mode);
+    set_pager(opt);
 
     // --color-only is used for interactive.diffFilter (git add -p). side-by-side, and
     // **-decoration-style cannot be used there (does not emit lines in 1-1 correspondence with raw git output).
@@ -242,6 +363,9 @@ pub fn set_options(
         opt.file_decoration_style = "none".to_string();
         opt.commit_decoration_style = "none".to_string();
         opt.hunk_header_decoration_style = "none".to_string();
+        // The +/- markers of the input are kept as they are.
+        opt.minus_symbol = None;
+        opt.plus_symbol = None;
     }
 }
 
@@ -415,9 +539,47 @@ fn gather_features(
         );
     }
 
+    // Gather the builtin theme, which has lowest priority.
+    let theme = opt.theme.clone().or_else(|| {
+        git_config
+            .as_ref()
+            .and_then(|git_config| git_config.get::<String>("delta.theme"))
+    });
+    if let Some(theme) = theme {
+        if !features::themes::THEMES.contains(&theme.as_str()) {
+            fatal(format!(
+                "Invalid value for theme: {}. Expected one of: {}. \
+                 Use --list-themes to list the available themes.",
+                theme,
+                features::themes::THEMES.join(", ")
+            ));
+        }
+        gather_builtin_features_recursively(&theme, &mut features, builtin_features, opt);
+    }
+
+    let features_order = opt.features_order.clone().or_else(|| {
+        git_config
+            .as_ref()
+            .and_then(|git_config| git_config.get::<String>("delta.features-order"))
+    });
+    if let Some(features_order) = features_order {
+        apply_features_order(&mut features, &features_order);
+    }
+
     Vec::<String>::from(features)
 }
 
+/// Move the features named in `features_order` to the end of the feature list, i.e. give them the
+/// highest priority, in the order in which they are named.
+fn apply_features_order(features: &mut VecDeque<String>, features_order: &str) {
+    for feature in features_order.split_whitespace() {
+        if let Some(i) = features.iter().position(|f| f == feature) {
+            let feature = features.remove(i).unwrap();
+            features.push_back(feature);
+        }
+    }
+}
+
 /// Add to feature list `features` all features in the tree rooted at `feature`.
 fn gather_features_recursively(
     feature: &str,
@@ -544,9 +706,10 @@ fn parse_paging_mode(paging_mode_string: &str) -> PagingMode {
         "always" => PagingMode::Always,
         "never" => PagingMode::Never,
         "auto" => PagingMode::QuitIfOneScreen,
+        "internal" => PagingMode::Internal,
         _ => {
             fatal(format!(
-                "Invalid value for --paging option: {} (valid values are \"always\", \"never\", and \"auto\")",
+                "Invalid value for --paging option: {} (valid values are \"always\", \"never\", \"auto\", and \"internal\")",
                 paging_mode_string
             ));
         }
@@ -650,6 +813,51 @@ fn set_true_color(opt: &mut cli::Opt) {
     };
 }
 
+// DELTA_PAGER takes precedence over the delta.pager git config value, but not over --pager.
+fn set_pager(opt: &mut cli::Opt) {
+    if opt.env.pagers.0.is_some()
+        && opt.computed.option_provenances.get("pager") != Some(&OptionProvenance::CommandLine)
+    {
+        opt.pager = None;
+    }
+}
+
+fn set_color(opt: &mut cli::Opt) {
+    opt.computed.color = match opt.color.as_ref() {
+        "always" => true,
+        "never" => false,
+        "auto" => match color_environment_variable(&opt.env) {
+            Some((name, color)) => {
+                opt.computed.option_provenances.insert(
+                    "color".to_string(),
+                    OptionProvenance::EnvironmentVariable(name.to_string()),
+                );
+                color
+            }
+            None => true,
+        },
+        _ => {
+            fatal(format!(
+                "Invalid value for --color option: {} (valid values are \"always\", \"never\", and \"auto\")",
+                opt.color
+            ));
+        }
+    };
+}
+
+/// The environment variable which decides whether --color=auto styles the output, and its
+/// decision. NO_COLOR takes precedence over CLICOLOR_FORCE.
+fn color_environment_variable(env: &DeltaEnv) -> Option<(&'static str, bool)> {
+    if matches!(env.no_color.as_deref(), Some(value) if !value.is_empty()) {
+        Some(("NO_COLOR", false))
+    } else if matches!(env.clicolor_force.as_deref(), Some(value) if !value.is_empty() && value != "0")
+    {
+        Some(("CLICOLOR_FORCE", true))
+    } else {
+        None
+    }
+}
+
 fn is_truecolor_terminal(env: &DeltaEnv) -> bool {
     env.colorterm
         .as_ref()
@@ -671,6 +879,7 @@ pub mod tests {
     use std::fs::remove_file;
 
     use crate::cli;
+    use crate::env::DeltaEnv;
     use crate::tests::integration_test_utils;
     use crate::utils::bat::output::PagingMode;
 
@@ -723,7 +932,7 @@ pub mod tests {
     plus-style = black black
     raw = true
     side-by-side = true
-    syntax-theme = xxxyyyzzz
+    syntax-theme = Nord
     tabs = 77
     true-color = never
     whitespace-error-style = black black
@@ -774,7 +983,7 @@ pub mod tests {
         assert_eq!(opt.line_numbers_right_format, "xxxyyyzzz");
         assert_eq!(opt.line_numbers_right_style, "black black");
         assert_eq!(opt.line_numbers_zero_style, "black black");
-        assert_eq!(opt.max_line_distance, 77 as f64);
+        assert_eq!(opt.max_line_distance, "77");
         assert_eq!(opt.max_line_length, 77);
         assert_eq!(opt.minus_emph_style, "black black");
         assert_eq!(opt.minus_empty_line_marker_style, "black black");
@@ -789,7 +998,7 @@ pub mod tests {
         assert_eq!(opt.plus_style, "black black");
         assert_eq!(opt.raw, true);
         assert_eq!(opt.side_by_side, true);
-        assert_eq!(opt.syntax_theme, Some("xxxyyyzzz".to_string()));
+        assert_eq!(opt.syntax_theme, Some("Nord".to_string()));
         assert_eq!(opt.tab_width, 77);
         assert_eq!(opt.true_color, "never");
         assert_eq!(opt.whitespace_error_style, "black black");
@@ -824,6 +1033,59 @@ pub mod tests {
         remove_file(git_config_path).unwrap();
     }
 
+    #[test]
+    fn test_color_environment_variables() {
+        let color = |no_color: Option<&str>, clicolor_force: Option<&str>, args: &[&str]| {
+            let env = DeltaEnv {
+                no_color: no_color.map(String::from),
+                clicolor_force: clicolor_force.map(String::from),
+                ..DeltaEnv::default()
+            };
+            integration_test_utils::make_options_from_args_and_git_config_with_custom_env(
+                env, args, None, None,
+            )
+            .computed
+            .color
+        };
+
+        assert!(color(None, None, &[]));
+        assert!(!color(Some("1"), None, &[]));
+        assert!(color(Some(""), None, &[]));
+        assert!(!color(Some("1"), Some("1"), &[]));
+        assert!(color(None, Some("1"), &[]));
+        assert!(color(Some("1"), None, &["--color", "always"]));
+        assert!(!color(None, Some("1"), &["--color", "never"]));
+    }
+
+    #[test]
+    fn test_pager_precedence() {
+        let git_config_contents = b"
+[delta]
+    pager = most
+";
+        let git_config_path = "delta__test_pager_precedence.gitconfig";
+        let pager = |delta_pager: Option<&str>, pager: Option<&str>, args: &[&str]| {
+            let env = DeltaEnv {
+                pagers: (delta_pager.map(String::from), None, pager.map(String::from)),
+                ..DeltaEnv::default()
+            };
+            let opt = integration_test_utils::make_options_from_args_and_git_config_with_custom_env(
+                env.clone(),
+                args,
+                Some(git_config_contents),
+                Some(git_config_path),
+            );
+            crate::utils::bat::output::get_pager_command(&env, opt.pager).0
+        };
+
+        assert_eq!(pager(None, None, &[]), "most");
+        assert_eq!(pager(None, Some("more"), &[]), "most");
+        assert_eq!(pager(Some("less -R"), Some("more"), &[]), "less -R");
+        assert_eq!(pager(Some("less -R"), None, &["--pager", "bat"]), "bat");
+
+        remove_file(git_config_path).unwrap();
+    }
+
     #[test]
     fn test_parse_width_specifier() {
         use super::parse_width_specifier;
@@ -4,32 +4,62 @@
 ///     default color choices
 /// This module sets those options. If the light/dark background mode choice is not made explicitly
 /// by the user, it is determined by the classification of the syntax theme into light-background
-/// vs dark-background syntax themes. If the user didn't choose a syntax theme, a dark-background
-/// default is selected.
+/// vs dark-background syntax themes, unless --detect-background is given and the terminal reports
+/// its background color. If the user didn't choose a syntax theme, a default matching the mode is
+/// selected.
 use bat;
 use bat::assets::HighlightingAssets;
+use itertools::Itertools;
+use unicode_segmentation::UnicodeSegmentation;
 
+use crate::align::Alignment;
 use crate::cli;
+use crate::fatal;
+use crate::utils::syntect::LazySyntaxSet;
+use crate::utils::terminal;
 
 #[allow(non_snake_case)]
 pub fn set__is_light_mode__syntax_theme__syntax_set(
     opt: &mut cli::Opt,
     assets: HighlightingAssets,
 ) {
-    let syntax_theme_name_from_bat_theme = &opt.env.bat_theme;
-    let (is_light_mode, syntax_theme_name) = get_is_light_mode_and_syntax_theme_name(
+    // An unknown theme name is an error if the user chose it, but is ignored if it is the value of
+    // BAT_THEME, which syntax-theme defaults to.
+    let theme_names = assets.themes().collect::<Vec<_>>();
+    let resolve_syntax_theme_name =
+        |name: Option<&String>, is_user_supplied: bool| match resolve_syntax_theme_name(
+            name?,
+            &theme_names,
+        ) {
+            Ok(name) => Some(name),
+            Err(err) if is_user_supplied => fatal(err),
+            Err(_) => None,
+        };
+    let syntax_theme_name = resolve_syntax_theme_name(
         opt.syntax_theme.as_ref(),
+        opt.computed.option_provenances.contains_key("syntax-theme"),
+    );
+    let syntax_theme_name_from_bat_theme =
+        resolve_syntax_theme_name(opt.env.bat_theme.as_ref(), false);
+    let detected_is_light_mode = if opt.detect_background && !(opt.light || opt.dark) {
+        terminal::detect_is_light_background()
+    } else {
+        None
+    };
+    let (is_light_mode, syntax_theme_name) = get_is_light_mode_and_syntax_theme_name(
+        syntax_theme_name.as_ref(),
         syntax_theme_name_from_bat_theme.as_ref(),
-        opt.light,
+        opt.light || detected_is_light_mode == Some(true),
     );
-    opt.computed.is_light_mode = is_light_mode;
+    // A detected dark background overrides a light mode inferred from the syntax theme.
+    opt.computed.is_light_mode = detected_is_light_mode.unwrap_or(is_light_mode);
 
     opt.computed.syntax_theme = if is_no_syntax_highlighting_syntax_theme_name(&syntax_theme_name) {
         None
     } else {
         Some(assets.get_theme(&syntax_theme_name).clone())
     };
-    opt.computed.syntax_set = assets.get_syntax_set().unwrap().clone();
+    opt.computed.syntax_set = LazySyntaxSet::load(move || assets.get_syntax_set().unwrap().clone());
 }
 
 pub fn is_light_syntax_theme(theme: &str) -> bool {
@@ -52,6 +82,48 @@ fn is_no_syntax_highlighting_syntax_theme_name(theme_name: &str) -> bool {
     theme_name.to_lowercase() == "none"
 }
 
+// The greatest edit distance, relative to the length of the name, of a theme name suggested for an
+// unknown one.
+const MAX_SUGGESTED_THEME_NAME_DISTANCE: f64 = 0.5;
+
+/// Return the name of the theme among `theme_names` which is `name` when case is ignored, or an
+/// error suggesting the theme names closest to `name` if there is none.
+fn resolve_syntax_theme_name(name: &str, theme_names: &[&str]) -> Result<String, String> {
+    if is_no_syntax_highlighting_syntax_theme_name(name) || theme_names.contains(&name) {
+        return Ok(name.to_string());
+    }
+    let lowercase_name = name.to_lowercase();
+    if let Some(theme_name) = theme_names
+        .iter()
+        .find(|theme_name| theme_name.to_lowercase() == lowercase_name)
+    {
+        return Ok(theme_name.to_string());
+    }
+    let max_distance =
+        (MAX_SUGGESTED_THEME_NAME_DISTANCE * name.graphemes(true).count() as f64).ceil() as usize;
+    let suggestions = theme_names
+        .iter()
+        .map(|theme_name| {
+            let distance = Alignment::new(
+                lowercase_name.graphemes(true).collect(),
+                theme_name.to_lowercase().graphemes(true).collect(),
+            )
+            .levenshtein_distance();
+            (distance, theme_name)
+        })
+        .filter(|(distance, _)| *distance <= max_distance)
+        .sorted()
+        .take(3)
+        .map(|(_, theme_name)| format!("'{}'", theme_name))
+        .collect::<Vec<_>>();
+    let mut error = format!("Unknown syntax theme: '{}'.", name);
+    if !suggestions.is_empty() {
+        error += &format!(" Did you mean {}?", suggestions.join(", "));
+    }
+    error += " Use --list-syntax-themes to see the available themes.";
+    Err(error)
+}
+
 /// Return a (theme_name, is_light_mode) tuple.
 /// theme_name == None in return value means syntax highlighting is disabled.
 ///
@@ -103,6 +175,65 @@ mod tests {
     use crate::color;
     use crate::tests::integration_test_utils;
 
+    #[test]
+    fn test_resolve_syntax_theme_name() {
+        let theme_names = [
+            "GitHub",
+            "Monokai Extended",
+            "Monokai Extended Light",
+            "Nord",
+        ];
+        assert_eq!(
+            resolve_syntax_theme_name("GitHub", &theme_names),
+            Ok("GitHub".to_string())
+        );
+        assert_eq!(
+            resolve_syntax_theme_name("monokai extended", &theme_names),
+            Ok("Monokai Extended".to_string())
+        );
+        assert_eq!(
+            resolve_syntax_theme_name("None", &theme_names),
+            Ok("None".to_string())
+        );
+        assert_eq!(
+            resolve_syntax_theme_name("Monokai Extnded", &theme_names),
+            Err("Unknown syntax theme: 'Monokai Extnded'. \
+                 Did you mean 'Monokai Extended', 'Monokai Extended Light'? \
+                 Use --list-syntax-themes to see the available themes."
+                .to_string())
+        );
+        assert_eq!(
+            resolve_syntax_theme_name("solarized", &theme_names),
+            Err("Unknown syntax theme: 'solarized'. \
+                 Use --list-syntax-themes to see the available themes."
+                .to_string())
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Did you mean 'GitHub'?")]
+    fn test_unknown_syntax_theme_is_fatal() {
+        integration_test_utils::make_config_from_args(&["--syntax-theme", "githb"]);
+    }
+
+    #[test]
+    fn test_unknown_bat_theme_is_ignored() {
+        let env = crate::env::DeltaEnv {
+            bat_theme: Some("githb".to_string()),
+            ..crate::env::DeltaEnv::default()
+        };
+        let opt = integration_test_utils::make_options_from_args_and_git_config_with_custom_env(
+            env,
+            &[],
+            None,
+            None,
+        );
+        assert_eq!(
+            opt.computed.syntax_theme.unwrap().name.unwrap(),
+            DEFAULT_DARK_SYNTAX_THEME
+        );
+    }
+
     // TODO: Test influence of BAT_THEME env var. E.g. see utils::process::tests::FakeParentArgs.
     #[test]
     fn test_syntax_theme_selection() {
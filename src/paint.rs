@@ -4,11 +4,15 @@ use std::io::Write;
 
 use ansi_term::ANSIString;
 use itertools::Itertools;
+use regex::Regex;
 use syntect::easy::HighlightLines;
 use syntect::highlighting::Style as SyntectStyle;
 use syntect::parsing::{SyntaxReference, SyntaxSet};
 use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
+use crate::cli;
+use crate::color;
 use crate::config::{self, delta_unreachable, Config};
 use crate::delta::{DiffType, InMergeConflict, MergeParents, State};
 use crate::features::hyperlinks;
@@ -16,9 +20,11 @@ use crate::features::line_numbers::{self, LineNumbersData};
 use crate::features::side_by_side::ansifill;
 use crate::features::side_by_side::{self, PanelSide};
 use crate::handlers::merge_conflict;
+use crate::handlers::word_diff::WordDiffToken;
 use crate::minusplus::*;
 use crate::paint::superimpose_style_sections::superimpose_style_sections;
 use crate::style::Style;
+use crate::wrapping::{self, LongLineDisplay};
 use crate::{ansi, style};
 use crate::{edits, utils};
 
@@ -27,11 +33,32 @@ pub type LineSections<'a, S> = Vec<(S, &'a str)>;
 pub struct Painter<'p> {
     pub minus_lines: Vec<(String, State)>,
     pub plus_lines: Vec<(String, State)>,
+    // The unchanged lines of a hunk, while they are collected to hide those far from a change,
+    // see --hide-context, and whether they follow a change.
+    pub context_lines: Vec<(String, State)>,
+    pub context_lines_follow_change: bool,
+    // Whether the indentation of each of the plus lines, and of the collected unchanged lines,
+    // has a space before a tab: a whitespace error which the lines no longer show once their tabs
+    // are expanded.
+    pub plus_lines_space_before_tab: Vec<bool>,
+    pub context_lines_space_before_tab: Vec<bool>,
+    // Whether the hunks of the current file are painted with the minus and plus styles only,
+    // without syntax highlighting, emph styles and wrapping, see --raw-file-glob.
+    pub raw_file: bool,
     pub writer: &'p mut dyn Write,
-    pub syntax: &'p SyntaxReference,
+    // The extension of the current file, from which its syntax is looked up when it is needed,
+    // so that the syntax set need not be loaded before output starts.
+    pub syntax_extension: Option<String>,
+    // The regex splitting lines into words to infer edits, which may depend on the language, see
+    // --word-diff-regex-overrides.
+    pub tokenization_regex: &'p Regex,
     pub highlighter: Option<HighlightLines<'p>>,
     pub config: &'p config::Config,
     pub output_buffer: String,
+    // The file header repeated every file-path-reminder-interval lines of the hunks of the
+    // current file, and the number of lines written since it was last written.
+    pub file_path_reminder: Option<String>,
+    pub lines_since_file_path_reminder: usize,
     // If config.line_numbers is true, then the following is always Some().
     // In side-by-side mode it is always Some (but possibly an empty one), even
     // if config.line_numbers is false. See `UseFullPanelWidth` as well.
@@ -63,6 +90,16 @@ impl Default for BgShouldFill {
     }
 }
 
+// The lines in which whitespace errors are highlighted, see --whitespace-error-scope.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum WhitespaceErrorScope {
+    // Only highlight the added lines which consist of whitespace.
+    Added,
+    // Also highlight the trailing whitespace and the space before a tab of added lines, and the
+    // whitespace errors of unchanged context lines.
+    All,
+}
+
 #[derive(PartialEq, Debug)]
 pub enum StyleSectionSpecifier<'l> {
     Style(Style),
@@ -71,14 +108,13 @@ pub enum StyleSectionSpecifier<'l> {
 
 impl<'p> Painter<'p> {
     pub fn new(writer: &'p mut dyn Write, config: &'p config::Config) -> Self {
-        let default_syntax = Self::get_syntax(&config.syntax_set, None);
-
         let panel_width_fix = ansifill::UseFullPanelWidth::new(config);
 
         let line_numbers_data = if config.line_numbers {
             Some(line_numbers::LineNumbersData::from_format_strings(
                 &config.line_numbers_format,
                 panel_width_fix,
+                config.line_numbers_min_width,
             ))
         } else if config.side_by_side {
             // If line numbers are disabled in side-by-side then the data is still used
@@ -93,8 +129,16 @@ impl<'p> Painter<'p> {
         Self {
             minus_lines: Vec::new(),
             plus_lines: Vec::new(),
+            context_lines: Vec::new(),
+            context_lines_follow_change: false,
+            plus_lines_space_before_tab: Vec::new(),
+            context_lines_space_before_tab: Vec::new(),
+            raw_file: false,
             output_buffer: String::new(),
-            syntax: default_syntax,
+            file_path_reminder: None,
+            lines_since_file_path_reminder: 0,
+            syntax_extension: None,
+            tokenization_regex: &config.tokenization_regex,
             highlighter: None,
             writer,
             config,
@@ -105,7 +149,16 @@ impl<'p> Painter<'p> {
     }
 
     pub fn set_syntax(&mut self, extension: Option<&str>) {
-        self.syntax = Painter::get_syntax(&self.config.syntax_set, extension);
+        self.syntax_extension = extension.map(str::to_string);
+        self.tokenization_regex = if self.config.word_diff_regex_overrides.is_empty() {
+            &self.config.tokenization_regex
+        } else {
+            self.config.get_tokenization_regex(self.syntax())
+        };
+    }
+
+    fn syntax(&self) -> &'p SyntaxReference {
+        Painter::get_syntax(&self.config.syntax_set, self.syntax_extension.as_deref())
     }
 
     fn get_syntax<'a>(syntax_set: &'a SyntaxSet, extension: Option<&str>) -> &'a SyntaxReference {
@@ -121,29 +174,116 @@ impl<'p> Painter<'p> {
 
     pub fn set_highlighter(&mut self) {
         if let Some(ref syntax_theme) = self.config.syntax_theme {
-            self.highlighter = Some(HighlightLines::new(self.syntax, syntax_theme))
+            self.highlighter = Some(HighlightLines::new(self.syntax(), syntax_theme))
         };
     }
 
     pub fn paint_buffered_minus_and_plus_lines(&mut self) {
+        self.paint_context_lines(false);
         if self.minus_lines.is_empty() && self.plus_lines.is_empty() {
             return;
         }
-        paint_minus_and_plus_lines(
-            MinusPlus::new(&self.minus_lines, &self.plus_lines),
-            &mut self.line_numbers_data,
-            &mut self.highlighter,
-            &mut self.output_buffer,
-            self.config,
-        );
+        if self.raw_file {
+            paint_raw_minus_and_plus_lines(
+                MinusPlus::new(&self.minus_lines, &self.plus_lines),
+                &mut self.line_numbers_data,
+                &mut self.output_buffer,
+                self.config,
+            );
+        } else {
+            paint_minus_and_plus_lines(
+                MinusPlus::new(&self.minus_lines, &self.plus_lines),
+                &self.plus_lines_space_before_tab,
+                &mut self.line_numbers_data,
+                &mut self.highlighter,
+                &mut self.output_buffer,
+                self.tokenization_regex,
+                self.config,
+            );
+        }
         self.minus_lines.clear();
         self.plus_lines.clear();
+        self.plus_lines_space_before_tab.clear();
+    }
+
+    // The highlighter of the current file, unless it is not to be syntax highlighted.
+    fn syntax_highlighter(&mut self) -> Option<&mut HighlightLines<'p>> {
+        if self.raw_file {
+            None
+        } else {
+            self.highlighter.as_mut()
+        }
     }
 
-    pub fn paint_zero_line(&mut self, line: &str, state: State) {
+    /// Paint the collected unchanged lines, replacing those further than --hide-context lines
+    /// from a change by a line saying how many were hidden. The hidden lines are still counted by
+    /// the line numbers and seen by the syntax highlighter.
+    pub fn paint_context_lines(&mut self, precede_change: bool) {
+        let follow_change = std::mem::replace(&mut self.context_lines_follow_change, false);
+        if precede_change {
+            let n_context_lines = self.context_lines.len();
+            if let Some(line_numbers_data) = self.line_numbers_data.as_mut() {
+                line_numbers_data.reach_first_change(n_context_lines);
+            }
+        }
+        if self.context_lines.is_empty() {
+            return;
+        }
+        let lines = std::mem::take(&mut self.context_lines);
+        let mut space_before_tab = std::mem::take(&mut self.context_lines_space_before_tab);
+        space_before_tab.resize(lines.len(), false);
+        let n_kept = self.config.hide_context.unwrap_or(usize::MAX);
+        let n_head = if follow_change { n_kept } else { 0 };
+        let n_tail = if precede_change { n_kept } else { 0 };
+        if n_head.saturating_add(n_tail) >= lines.len() {
+            for ((line, state), space_before_tab) in lines.into_iter().zip(space_before_tab) {
+                self.paint_zero_line(&line, state, space_before_tab);
+            }
+            return;
+        }
+        let (head, rest) = lines.split_at(n_head);
+        let (hidden, tail) = rest.split_at(rest.len() - n_tail);
+        for ((line, state), space_before_tab) in head.iter().zip(&space_before_tab) {
+            self.paint_zero_line(line, state.clone(), *space_before_tab);
+        }
+        let config = self.config;
+        get_syntax_style_sections_for_lines(hidden, self.syntax_highlighter(), config);
+        if let Some(line_numbers_data) = self.line_numbers_data.as_mut() {
+            line_numbers_data.line_number[Minus] += hidden.len();
+            line_numbers_data.line_number[Plus] += hidden.len();
+        }
+        let mut marker = format_hidden_context_marker(hidden.len());
+        if config.side_by_side {
+            // The line spans both panels.
+            let width = config.side_by_side_data.get()[side_by_side::Left].width
+                + config.side_by_side_data.get()[side_by_side::Right].width;
+            marker.push_str(&" ".repeat(width.saturating_sub(marker.width())));
+        }
+        self.output_buffer
+            .push_str(&config.hidden_context_style.paint(marker).to_string());
+        self.output_buffer.push('\n');
+        let tail_space_before_tab = &space_before_tab[lines.len() - tail.len()..];
+        for ((line, state), space_before_tab) in tail.iter().zip(tail_space_before_tab) {
+            self.paint_zero_line(line, state.clone(), *space_before_tab);
+        }
+    }
+
+    /// Whether unchanged lines are collected rather than painted immediately: with --hide-context,
+    /// and until the first change of a hunk if line numbers are displayed relative to it.
+    pub fn collects_context_lines(&self) -> bool {
+        self.config.hide_context.is_some()
+            || self.config.line_numbers_relative
+                && matches!(
+                    &self.line_numbers_data,
+                    Some(data) if data.first_change_line_number.is_none()
+                )
+    }
+
+    pub fn paint_zero_line(&mut self, line: &str, state: State, space_before_tab: bool) {
         let lines = &[(line.to_string(), state.clone())];
+        let config = self.config;
         let syntax_style_sections =
-            get_syntax_style_sections_for_lines(lines, self.highlighter.as_mut(), self.config);
+            get_syntax_style_sections_for_lines(lines, self.syntax_highlighter(), config);
         let mut diff_style_sections = vec![vec![(self.config.zero_style, lines[0].0.as_str())]]; // TODO: compute style from state
         Painter::update_diff_style_sections(
             lines,
@@ -153,6 +293,15 @@ impl<'p> Painter<'p> {
             &[false],
             self.config,
         );
+        if self.config.whitespace_error_scope == WhitespaceErrorScope::All
+            && matches!(state, State::HunkZero(_, None))
+        {
+            apply_whitespace_error_style(
+                &mut diff_style_sections[0],
+                space_before_tab,
+                self.config.whitespace_error_style,
+            );
+        }
         if self.config.side_by_side {
             // `lines[0].0` so the line has the '\n' already added (as in the +- case)
             side_by_side::paint_zero_lines_side_by_side(
@@ -164,6 +313,7 @@ impl<'p> Painter<'p> {
                 &mut self.line_numbers_data.as_mut(),
                 painted_prefix(state, self.config),
                 BgShouldFill::With(BgFillMethod::Spaces),
+                !self.raw_file,
             );
         } else {
             Painter::paint_lines(
@@ -180,6 +330,112 @@ impl<'p> Painter<'p> {
         }
     }
 
+    /// Paint a line of `git diff --word-diff=porcelain` output with removed and added text: in
+    /// unified mode as a single line with the removed and added text in the emph styles, and in
+    /// side-by-side mode as its old and new versions.
+    pub fn paint_word_diff_line(&mut self, tokens: &[(WordDiffToken, String)]) {
+        let config = self.config;
+        let unchanged_style = |style| {
+            if config.dim_unchanged_words {
+                dimmed(style)
+            } else {
+                style
+            }
+        };
+        if config.side_by_side {
+            let (minus_line, minus_section_lengths) =
+                assemble_word_diff_line(tokens, config.minus_non_emph_style, |token| match token {
+                    WordDiffToken::Zero => Some(unchanged_style(config.minus_non_emph_style)),
+                    WordDiffToken::Minus => Some(config.minus_emph_style),
+                    WordDiffToken::Plus => None,
+                });
+            let (plus_line, plus_section_lengths) =
+                assemble_word_diff_line(tokens, config.plus_non_emph_style, |token| match token {
+                    WordDiffToken::Zero => Some(unchanged_style(config.plus_non_emph_style)),
+                    WordDiffToken::Minus => None,
+                    WordDiffToken::Plus => Some(config.plus_emph_style),
+                });
+            let minus_lines = vec![(minus_line, State::HunkMinus(DiffType::Unified, None))];
+            let plus_lines = vec![(plus_line, State::HunkPlus(DiffType::Unified, None))];
+            // The highlighter follows the new version of the file, so it is given only the new
+            // line, and the old line is highlighted on its own.
+            let mut minus_highlighter = match &config.syntax_theme {
+                Some(syntax_theme) if self.syntax_highlighter().is_some() => {
+                    Some(HighlightLines::new(self.syntax(), syntax_theme))
+                }
+                _ => None,
+            };
+            let syntax_style_sections = MinusPlus::new(
+                get_syntax_style_sections_for_lines(
+                    &minus_lines,
+                    minus_highlighter.as_mut(),
+                    config,
+                ),
+                get_syntax_style_sections_for_lines(&plus_lines, self.syntax_highlighter(), config),
+            );
+            let diff_style_sections = MinusPlus::new(
+                vec![split_into_sections(
+                    &minus_lines[0].0,
+                    &minus_section_lengths,
+                )],
+                vec![split_into_sections(&plus_lines[0].0, &plus_section_lengths)],
+            );
+            paint_minus_and_plus_style_sections(
+                MinusPlus::new(&minus_lines, &plus_lines),
+                syntax_style_sections,
+                diff_style_sections,
+                MinusPlus::new(vec![true], vec![true]),
+                vec![(Some(0), Some(0))],
+                &mut self.line_numbers_data,
+                &mut self.output_buffer,
+                config,
+                true,
+            );
+        } else {
+            let (line, section_lengths) =
+                assemble_word_diff_line(tokens, config.zero_style, |token| match token {
+                    WordDiffToken::Zero => Some(unchanged_style(config.zero_style)),
+                    WordDiffToken::Minus => Some(config.minus_emph_style),
+                    WordDiffToken::Plus => Some(config.plus_emph_style),
+                });
+            let lines = vec![(line, State::HunkZero(DiffType::Unified, None))];
+            let syntax_style_sections =
+                get_syntax_style_sections_for_lines(&lines, self.syntax_highlighter(), config);
+            let diff_style_sections = vec![split_into_sections(&lines[0].0, &section_lengths)];
+            Painter::paint_lines(
+                &lines,
+                &syntax_style_sections,
+                &diff_style_sections,
+                &[false],
+                &mut self.output_buffer,
+                config,
+                &mut self.line_numbers_data.as_mut(),
+                None,
+                BgShouldFill::With(BgFillMethod::Spaces),
+            );
+        }
+    }
+
+    /// Paint lines in their minus or plus style across the full width, without line numbers, e.g.
+    /// the base section of a merge conflict in side-by-side mode.
+    pub fn paint_full_width_lines(&mut self, lines: &[(String, State)]) {
+        let config = self.config;
+        let syntax_style_sections =
+            get_syntax_style_sections_for_lines(lines, self.syntax_highlighter(), config);
+        let diff_style_sections = get_base_style_sections_for_lines(lines, config);
+        Painter::paint_lines(
+            lines,
+            &syntax_style_sections,
+            &diff_style_sections,
+            &vec![false; lines.len()],
+            &mut self.output_buffer,
+            config,
+            &mut None,
+            None,
+            BgShouldFill::default(),
+        );
+    }
+
     /// Superimpose background styles and foreground syntax
     /// highlighting styles, and write colored lines to output buffer.
     #[allow(clippy::too_many_arguments)]
@@ -201,7 +457,7 @@ impl<'p> Painter<'p> {
         // 2. We must ensure that we fill rightwards with the appropriate
         //    non-emph background color. In that case we don't use the last
         //    style of the line, because this might be emph.
-        for ((((_, state), syntax_sections), diff_sections), &line_has_homolog) in lines
+        for ((((content, state), syntax_sections), diff_sections), &line_has_homolog) in lines
             .iter()
             .zip_eq(syntax_style_sections)
             .zip_eq(diff_style_sections)
@@ -216,6 +472,14 @@ impl<'p> Painter<'p> {
                 painted_prefix(state.clone(), config),
                 config,
             );
+            let empty_line_marker = if content.trim_end_matches('\n').is_empty() {
+                empty_line_style.and(Painter::empty_line_marker(state, config))
+            } else {
+                None
+            };
+            if let (Some(marker), Some(empty_line_style)) = (empty_line_marker, empty_line_style) {
+                line.push_str(&empty_line_style.paint(marker).to_string());
+            }
             let (bg_fill_mode, fill_style) =
                 Painter::get_should_right_fill_background_color_and_fill_style(
                     diff_sections,
@@ -231,10 +495,10 @@ impl<'p> Painter<'p> {
                 let text_width = ansi::measure_text_width(&line);
                 line.push_str(
                     &fill_style
-                        .paint(" ".repeat(config.available_terminal_width - text_width))
+                        .paint(" ".repeat(config.available_terminal_width.get() - text_width))
                         .to_string(),
                 );
-            } else if line_is_empty {
+            } else if line_is_empty && empty_line_marker.is_none() {
                 if let Some(empty_line_style) = empty_line_style {
                     Painter::mark_empty_line(
                         &empty_line_style,
@@ -295,7 +559,7 @@ impl<'p> Painter<'p> {
         let fill_style = match state {
             State::HunkMinus(_, None) | State::HunkMinusWrapped => {
                 if let Some(true) = line_has_homolog {
-                    config.minus_non_emph_style
+                    get_non_emph_fill_style(diff_sections, config.minus_non_emph_style, config)
                 } else {
                     config.minus_style
                 }
@@ -303,7 +567,7 @@ impl<'p> Painter<'p> {
             State::HunkZero(_, None) | State::HunkZeroWrapped => config.zero_style,
             State::HunkPlus(_, None) | State::HunkPlusWrapped => {
                 if let Some(true) = line_has_homolog {
-                    config.plus_non_emph_style
+                    get_non_emph_fill_style(diff_sections, config.plus_non_emph_style, config)
                 } else {
                     config.plus_style
                 }
@@ -356,6 +620,16 @@ impl<'p> Painter<'p> {
         line.push_str(ansi::ANSI_SGR_RESET);
     }
 
+    /// Return the marker configured for an empty removed or added line, if any.
+    pub fn empty_line_marker<'c>(state: &State, config: &'c config::Config) -> Option<&'c str> {
+        let marker = match state {
+            State::HunkMinus(_, _) => &config.minus_empty_line_marker,
+            State::HunkPlus(_, _) => &config.plus_empty_line_marker,
+            _ => return None,
+        };
+        Some(marker.as_str()).filter(|marker| !marker.is_empty())
+    }
+
     /// Use ANSI sequences to visually mark the current line as empty. If `marker` is None then the
     /// line is marked using terminal emulator colors only, i.e. without appending any marker text
     /// to the line. This is typically appropriate only when the `line` buffer is empty, since
@@ -402,19 +676,85 @@ impl<'p> Painter<'p> {
                 ))
             }
         }
-        let superimposed = superimpose_style_sections(
+        let mut superimposed = superimpose_style_sections(
             syntax_sections,
             diff_sections,
             config.true_color,
             config.null_syntect_style,
         );
 
+        let is_hunk_line = matches!(
+            state,
+            State::HunkMinus(_, _)
+                | State::HunkZero(_, _)
+                | State::HunkPlus(_, _)
+                | State::HunkMinusWrapped
+                | State::HunkZeroWrapped
+                | State::HunkPlusWrapped
+        );
+        let terminal_width = match config.decorations_width.get() {
+            cli::Width::Fixed(width) => width,
+            cli::Width::Variable => config.available_terminal_width.get(),
+        };
+        let wrap_config = if side_by_side_panel.is_some() {
+            &config.side_by_side_wrap_config
+        } else {
+            &config.wrap_config
+        };
+        let long_line_display = wrap_config.long_line_display;
+        let truncation_side = wrap_config.truncation_side;
+        // In side-by-side mode lines which are still too long after wrapping are truncated,
+        // in unified mode only if requested.
+        let truncate_with_indicator = !wrap_config.truncation_format.is_empty()
+            && truncation_side == ansi::TruncationSide::Right
+            && (side_by_side_panel.is_some() && long_line_display == LongLineDisplay::Wrap
+                || long_line_display == LongLineDisplay::Truncate);
+        // If the line numbers take the whole panel, the line is not displayed at all: truncating it
+        // would cut the line numbers to make room for the truncation symbol.
+        if let (true, Some(panel_side), Some(data)) =
+            (is_hunk_line, side_by_side_panel, line_numbers_data.as_ref())
+        {
+            if side_by_side::available_line_width(config, data)[panel_side] == 0 {
+                superimposed.clear();
+            }
+        }
+        if is_hunk_line && truncate_with_indicator {
+            let line_width = match (side_by_side_panel, line_numbers_data.as_ref()) {
+                (Some(panel_side), Some(data)) => {
+                    side_by_side::available_line_width(config, data)[panel_side]
+                }
+                (_, data) => {
+                    let line_numbers_width = data.map_or(0, |data| {
+                        let width = data.formatted_width();
+                        width[Minus] + width[Plus]
+                    });
+                    let prefix_width = painted_prefix
+                        .as_ref()
+                        .map_or(0, |prefix| ansi::measure_text_width(prefix));
+                    terminal_width
+                        .saturating_sub(line_numbers_width)
+                        .saturating_sub(prefix_width)
+                }
+            };
+            superimposed = wrapping::truncate_line_sections_with_indicator(
+                superimposed,
+                line_width,
+                &wrap_config.truncation_format,
+                &ansi::strip_ansi_codes(&config.truncation_symbol),
+                config.wrap_truncation_style,
+            );
+        }
+
         let mut handled_prefix = false;
+        // The line numbers and the prefix are kept when the line is truncated on the left or in
+        // the middle.
+        let mut n_leading_strings = ansi_strings.len();
         for (section_style, text) in &superimposed {
             // If requested re-insert the +/- prefix with proper styling.
             if !handled_prefix {
                 if let Some(painted_prefix) = painted_prefix.take() {
-                    ansi_strings.push(painted_prefix)
+                    ansi_strings.push(painted_prefix);
+                    n_leading_strings = ansi_strings.len();
                 }
             }
 
@@ -426,12 +766,57 @@ impl<'p> Painter<'p> {
 
         // Only if syntax is empty (implies diff empty) can a line actually be empty.
         let is_empty = syntax_sections.is_empty();
-        (ansi_term::ANSIStrings(&ansi_strings).to_string(), is_empty)
+        let line = ansi_term::ANSIStrings(&ansi_strings).to_string();
+        // In side-by-side mode the panel line is truncated on the right when it is padded to the
+        // panel width: on the left or in the middle the line numbers must be kept.
+        let truncation_width = match side_by_side_panel {
+            Some(panel_side) if truncation_side != ansi::TruncationSide::Right => {
+                Some(config.side_by_side_data.get()[panel_side].width)
+            }
+            None if long_line_display == LongLineDisplay::Truncate && !truncate_with_indicator => {
+                Some(terminal_width)
+            }
+            _ => None,
+        };
+        if let (true, Some(width)) = (is_hunk_line, truncation_width) {
+            let line = if truncation_side == ansi::TruncationSide::Right {
+                ansi::truncate_str(&line, width, &config.truncation_symbol).to_string()
+            } else {
+                let leading =
+                    ansi_term::ANSIStrings(&ansi_strings[..n_leading_strings]).to_string();
+                let text = ansi_term::ANSIStrings(&ansi_strings[n_leading_strings..]).to_string();
+                let text = ansi::truncate_str_on_side(
+                    &text,
+                    width.saturating_sub(ansi::measure_text_width(&leading)),
+                    &config.truncation_symbol,
+                    truncation_side,
+                );
+                format!("{}{}", leading, text)
+            };
+            return (line, is_empty);
+        }
+        (line, is_empty)
     }
 
     /// Write output buffer to output stream, and clear the buffer.
     pub fn emit(&mut self) -> std::io::Result<()> {
-        write!(self.writer, "{}", self.output_buffer)?;
+        match &self.file_path_reminder {
+            Some(file_path) if self.config.file_path_reminder_interval > 0 => {
+                let mut style = self.config.file_style;
+                style.ansi_term_style.is_dimmed = true;
+                for line in self.output_buffer.split_inclusive('\n') {
+                    if self.lines_since_file_path_reminder
+                        >= self.config.file_path_reminder_interval
+                    {
+                        writeln!(self.writer, "{}", style.paint(file_path))?;
+                        self.lines_since_file_path_reminder = 0;
+                    }
+                    write!(self.writer, "{}", line)?;
+                    self.lines_since_file_path_reminder += 1;
+                }
+            }
+            _ => write!(self.writer, "{}", self.output_buffer)?,
+        }
         self.output_buffer.clear();
         Ok(())
     }
@@ -463,7 +848,7 @@ impl<'p> Painter<'p> {
             }
             State::Blame(_) => true,
             State::GitShowFile => true,
-            State::Grep => true,
+            State::Grep => config.grep_syntax_highlighting,
             State::Unknown
             | State::CommitMeta
             | State::DiffHeader(_)
@@ -519,7 +904,11 @@ impl<'p> Painter<'p> {
             }
             let line_has_emph_and_non_emph_sections =
                 style_sections_contain_more_than_one_style(style_sections);
-            let should_update_non_emph_styles = non_emph_style.is_some() && *line_has_homolog;
+            // A line changed relative to a single parent of a combined diff keeps the style of
+            // the parent.
+            let should_update_non_emph_styles = non_emph_style.is_some()
+                && *line_has_homolog
+                && config.get_merge_parent_style(state).is_none();
             let is_whitespace_error =
                 whitespace_error_style.is_some() && is_whitespace_error(style_sections);
             for (style, _) in style_sections.iter_mut() {
@@ -586,26 +975,25 @@ where
 
 pub fn paint_minus_and_plus_lines(
     lines: MinusPlus<&Vec<(String, State)>>,
+    plus_lines_space_before_tab: &[bool],
     line_numbers_data: &mut Option<LineNumbersData>,
     highlighter: &mut Option<HighlightLines>,
     output_buffer: &mut String,
+    tokenization_regex: &Regex,
     config: &config::Config,
 ) {
     let syntax_style_sections = MinusPlus::new(
         get_syntax_style_sections_for_lines(lines[Minus], highlighter.as_mut(), config),
         get_syntax_style_sections_for_lines(lines[Plus], highlighter.as_mut(), config),
     );
-    let (mut diff_style_sections, line_alignment) = get_diff_style_sections(&lines, config);
+    let (mut diff_style_sections, line_alignment) =
+        get_diff_style_sections(&lines, tokenization_regex, config);
     let lines_have_homolog = edits::make_lines_have_homolog(&line_alignment);
     Painter::update_diff_style_sections(
         lines[Minus],
         &mut diff_style_sections[Minus],
         None,
-        if config.minus_non_emph_style != config.minus_emph_style {
-            Some(config.minus_non_emph_style)
-        } else {
-            None
-        },
+        get_non_emph_style(config.minus_non_emph_style, config.minus_emph_style, config),
         &lines_have_homolog[Minus],
         config,
     );
@@ -613,14 +1001,98 @@ pub fn paint_minus_and_plus_lines(
         lines[Plus],
         &mut diff_style_sections[Plus],
         Some(config.whitespace_error_style),
-        if config.plus_non_emph_style != config.plus_emph_style {
-            Some(config.plus_non_emph_style)
-        } else {
-            None
-        },
+        get_non_emph_style(config.plus_non_emph_style, config.plus_emph_style, config),
         &lines_have_homolog[Plus],
         config,
     );
+    // With scope "added" only the lines consisting of whitespace are whitespace errors, which
+    // have been styled as a whole above.
+    if config.whitespace_error_scope == WhitespaceErrorScope::All {
+        for (i, ((_, state), style_sections)) in lines[Plus]
+            .iter()
+            .zip(diff_style_sections[Plus].iter_mut())
+            .enumerate()
+        {
+            if matches!(state, State::HunkPlus(_, None)) && !is_whitespace_error(style_sections) {
+                apply_whitespace_error_style(
+                    style_sections,
+                    plus_lines_space_before_tab.get(i).copied().unwrap_or(false),
+                    config.whitespace_error_style,
+                );
+            }
+        }
+    }
+    if config.change_magnitude_shading {
+        for side in &[Minus, Plus] {
+            shade_by_change_magnitude(
+                lines[*side],
+                &mut diff_style_sections[*side],
+                &lines_have_homolog[*side],
+                config,
+            );
+        }
+    }
+    paint_minus_and_plus_style_sections(
+        lines,
+        syntax_style_sections,
+        diff_style_sections,
+        lines_have_homolog,
+        line_alignment,
+        line_numbers_data,
+        output_buffer,
+        config,
+        true,
+    );
+}
+
+/// Paint minus and plus lines with the minus and plus styles only, see --raw-file-glob. Unlike
+/// paint_minus_and_plus_lines, this neither highlights syntax nor infers edits, and the lines
+/// are not wrapped.
+pub fn paint_raw_minus_and_plus_lines(
+    lines: MinusPlus<&Vec<(String, State)>>,
+    line_numbers_data: &mut Option<LineNumbersData>,
+    output_buffer: &mut String,
+    config: &config::Config,
+) {
+    let syntax_style_sections = MinusPlus::new(
+        get_syntax_style_sections_for_lines(lines[Minus], None, config),
+        get_syntax_style_sections_for_lines(lines[Plus], None, config),
+    );
+    let diff_style_sections = MinusPlus::new(
+        get_base_style_sections_for_lines(lines[Minus], config),
+        get_base_style_sections_for_lines(lines[Plus], config),
+    );
+    let lines_have_homolog = MinusPlus::new(
+        vec![false; lines[Minus].len()],
+        vec![false; lines[Plus].len()],
+    );
+    let line_alignment =
+        side_by_side::make_positional_line_alignment(lines[Minus].len(), lines[Plus].len());
+    paint_minus_and_plus_style_sections(
+        lines,
+        syntax_style_sections,
+        diff_style_sections,
+        lines_have_homolog,
+        line_alignment,
+        line_numbers_data,
+        output_buffer,
+        config,
+        false,
+    );
+}
+
+#[allow(clippy::too_many_arguments)]
+fn paint_minus_and_plus_style_sections(
+    lines: MinusPlus<&Vec<(String, State)>>,
+    syntax_style_sections: MinusPlus<Vec<LineSections<SyntectStyle>>>,
+    diff_style_sections: MinusPlus<Vec<LineSections<Style>>>,
+    lines_have_homolog: MinusPlus<Vec<bool>>,
+    line_alignment: Vec<(Option<usize>, Option<usize>)>,
+    line_numbers_data: &mut Option<LineNumbersData>,
+    output_buffer: &mut String,
+    config: &config::Config,
+    wrap_lines: bool,
+) {
     if config.side_by_side {
         side_by_side::paint_minus_and_plus_lines_side_by_side(
             lines,
@@ -631,6 +1103,7 @@ pub fn paint_minus_and_plus_lines(
             line_numbers_data,
             output_buffer,
             config,
+            wrap_lines,
         )
     } else {
         // Unified diff mode:
@@ -663,6 +1136,135 @@ pub fn paint_minus_and_plus_lines(
     }
 }
 
+// Fade the background of the sections of each line with a homolog which are not emphasized, the
+// more so the smaller the fraction of the line which is emphasized, see
+// --change-magnitude-shading.
+fn shade_by_change_magnitude(
+    lines: &[(String, State)],
+    diff_style_sections: &mut [LineSections<Style>],
+    lines_have_homolog: &[bool],
+    config: &config::Config,
+) {
+    // The lines with styles parsed from the raw line, see update_diff_style_sections, are skipped.
+    for ((_, style_sections), _) in lines
+        .iter()
+        .zip(diff_style_sections)
+        .zip(lines_have_homolog)
+        .filter(|(((_, state), _), line_has_homolog)| {
+            **line_has_homolog
+                && matches!(state, State::HunkMinus(_, None) | State::HunkPlus(_, None))
+        })
+    {
+        let width = |is_emph: bool| -> usize {
+            style_sections
+                .iter()
+                .filter(|(style, _)| style.is_emph == is_emph)
+                .map(|(_, section)| section.trim_end_matches('\n').width())
+                .sum()
+        };
+        let (n_changed, n_unchanged) = (width(true), width(false));
+        if n_changed + n_unchanged == 0 {
+            continue;
+        }
+        let intensity = 0.4 + 0.6 * n_changed as f64 / (n_changed + n_unchanged) as f64;
+        for (style, _) in style_sections
+            .iter_mut()
+            .filter(|(style, _)| !style.is_emph)
+        {
+            if let Some(background) = style.ansi_term_style.background {
+                style.ansi_term_style.background = Some(color::fade_color(
+                    background,
+                    intensity,
+                    config.is_light_mode,
+                    config.true_color,
+                ));
+            }
+        }
+    }
+}
+
+// The style filling a line with a homolog: that of its non-emph sections, which is shaded by
+// --change-magnitude-shading.
+fn get_non_emph_fill_style(
+    diff_sections: &[(Style, &str)],
+    non_emph_style: Style,
+    config: &config::Config,
+) -> Style {
+    if config.change_magnitude_shading {
+        diff_sections
+            .iter()
+            .find(|(style, _)| !style.is_emph)
+            .map_or(non_emph_style, |(style, _)| *style)
+    } else {
+        non_emph_style
+    }
+}
+
+// The style replacing the style of the sections of a line with a homolog which are not
+// emphasized, if any: dimmed by --dim-unchanged-words.
+fn get_non_emph_style(
+    non_emph_style: Style,
+    emph_style: Style,
+    config: &config::Config,
+) -> Option<Style> {
+    if config.dim_unchanged_words {
+        Some(dimmed(non_emph_style))
+    } else if non_emph_style != emph_style {
+        Some(non_emph_style)
+    } else {
+        None
+    }
+}
+
+fn dimmed(mut style: Style) -> Style {
+    style.ansi_term_style.is_dimmed = true;
+    style
+}
+
+// Join the text of the word diff tokens for which `get_style` returns a style, terminated with a
+// newline in `newline_style`, and return it with the style and length of each of its sections.
+fn assemble_word_diff_line(
+    tokens: &[(WordDiffToken, String)],
+    newline_style: Style,
+    get_style: impl Fn(WordDiffToken) -> Option<Style>,
+) -> (String, Vec<(Style, usize)>) {
+    let mut line = String::new();
+    let mut section_lengths = Vec::new();
+    for (token, text) in tokens {
+        if let Some(style) = get_style(*token) {
+            line.push_str(text);
+            section_lengths.push((style, text.len()));
+        }
+    }
+    line.push('\n');
+    section_lengths.push((newline_style, 1));
+    (line, section_lengths)
+}
+
+fn split_into_sections<'a>(
+    line: &'a str,
+    section_lengths: &[(Style, usize)],
+) -> LineSections<'a, Style> {
+    let mut rest = line;
+    section_lengths
+        .iter()
+        .map(|(style, length)| {
+            let (section, tail) = rest.split_at(*length);
+            rest = tail;
+            (*style, section)
+        })
+        .collect()
+}
+
+// E.g. "⋯ 17 unchanged lines".
+fn format_hidden_context_marker(n_lines: usize) -> String {
+    format!(
+        "\u{22ef} {} unchanged {}",
+        n_lines,
+        if n_lines == 1 { "line" } else { "lines" }
+    )
+}
+
 pub fn get_syntax_style_sections_for_lines<'a>(
     lines: &'a [(String, State)],
     highlighter: Option<&mut HighlightLines>,
@@ -677,7 +1279,13 @@ pub fn get_syntax_style_sections_for_lines<'a>(
     ) {
         (Some(highlighter), true) => {
             for (line, _) in lines.iter() {
-                line_sections.push(highlighter.highlight(line, &config.syntax_set));
+                if config.max_syntax_highlighting_length > 0
+                    && line.len() > config.max_syntax_highlighting_length
+                {
+                    line_sections.push(vec![(config.null_syntect_style, line.as_str())]);
+                } else {
+                    line_sections.push(highlighter.highlight(line, &config.syntax_set));
+                }
             }
         }
         _ => {
@@ -689,10 +1297,22 @@ pub fn get_syntax_style_sections_for_lines<'a>(
     line_sections
 }
 
+/// Get the minus or plus style of each line, as a single section.
+fn get_base_style_sections_for_lines<'a>(
+    lines: &'a [(String, State)],
+    config: &config::Config,
+) -> Vec<LineSections<'a, Style>> {
+    lines
+        .iter()
+        .map(|(line, state)| vec![(*config.get_style(state), line.as_str())])
+        .collect()
+}
+
 /// Get background styles to represent diff for minus and plus lines in buffer.
 #[allow(clippy::type_complexity)]
 fn get_diff_style_sections<'a>(
     lines: &MinusPlus<&'a Vec<(String, State)>>,
+    tokenization_regex: &Regex,
     config: &config::Config,
 ) -> (
     MinusPlus<Vec<LineSections<'a, Style>>>,
@@ -714,9 +1334,13 @@ fn get_diff_style_sections<'a>(
             config.minus_emph_style, // FIXME
             plus_styles,
             config.plus_emph_style, // FIXME
-            &config.tokenization_regex,
+            tokenization_regex,
+            config.word_diff_algorithm,
+            config.emph_mode,
             config.max_line_distance,
             config.max_line_distance_for_naively_paired_lines,
+            config.min_emph_length,
+            config.word_diff_context,
         );
     let diff_sections = MinusPlus::new(
         minus_line_diff_style_sections,
@@ -728,29 +1352,63 @@ fn get_diff_style_sections<'a>(
 fn painted_prefix(state: State, config: &config::Config) -> Option<ANSIString> {
     use DiffType::*;
     use State::*;
-    match (state, config.keep_plus_minus_markers) {
+    match (state, !config.zero_marker.is_empty()) {
         // For a combined diff, unless we are in a merge conflict, we do not honor
         // keep_plus_minus_markers -- i.e. we always emit the prefix -- because there is currently
         // no way to distinguish, say, a '+ ' line from a ' +' line, by styles alone. In a merge
         // conflict we do honor the setting because the way merge conflicts are displayed indicates
         // from which commit the lines derive.
+        // The markers are distinguished by the colors of merge-parent-palette though.
         (HunkMinus(Combined(MergeParents::Prefix(prefix), InMergeConflict::No), _), _) => {
-            Some(config.minus_style.paint(prefix))
+            Some(paint_combined_prefix(&prefix, config.minus_style, config))
         }
         (HunkZero(Combined(MergeParents::Prefix(prefix), InMergeConflict::No), _), _) => {
             Some(config.zero_style.paint(prefix))
         }
         (HunkPlus(Combined(MergeParents::Prefix(prefix), InMergeConflict::No), _), _) => {
-            Some(config.plus_style.paint(prefix))
+            Some(paint_combined_prefix(&prefix, config.plus_style, config))
         }
-        // But otherwise we honor keep_plus_minus_markers
-        (HunkMinus(_, _), true) => Some(config.minus_style.paint("-".to_string())),
-        (HunkZero(_, _), true) => Some(config.zero_style.paint(" ".to_string())),
-        (HunkPlus(_, _), true) => Some(config.plus_style.paint("+".to_string())),
+        // But otherwise we honor keep_plus_minus_markers and the symbols replacing the markers
+        (HunkMinus(_, _), true) => Some(
+            config
+                .minus_style
+                .paint(config.plus_minus_markers[Minus].clone()),
+        ),
+        (HunkZero(_, _), true) => Some(config.zero_style.paint(config.zero_marker.clone())),
+        (HunkPlus(_, _), true) => Some(
+            config
+                .plus_style
+                .paint(config.plus_minus_markers[Plus].clone()),
+        ),
         _ => None,
     }
 }
 
+// Paint the +/- markers of a combined diff line, that in the column of the Nth parent with the Nth
+// color of merge-parent-palette, if any.
+fn paint_combined_prefix(
+    prefix: &str,
+    style: Style,
+    config: &config::Config,
+) -> ANSIString<'static> {
+    if config.merge_parent_palette.is_empty() {
+        return style.paint(prefix.to_string());
+    }
+    let painted: String = prefix
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let mut style = style;
+            if c != ' ' {
+                let color = config.merge_parent_palette[i % config.merge_parent_palette.len()];
+                style.ansi_term_style.foreground = Some(color);
+            }
+            style.paint(c.to_string()).to_string()
+        })
+        .collect();
+    ANSIString::from(painted)
+}
+
 // Parse ANSI styles encountered in `raw_line` and apply `styles_map`.
 pub fn parse_style_sections<'a>(
     raw_line: &'a str,
@@ -778,6 +1436,8 @@ pub fn parse_style_sections<'a>(
 #[allow(clippy::too_many_arguments)]
 pub fn paint_file_path_with_line_number(
     line_number: Option<usize>,
+    column: Option<usize>, // Only used in the hyperlink
+    commit: Option<&str>,  // Only used in the hyperlink
     file_path: &str,
     pad_line_number: bool,
     separator: &str,
@@ -831,6 +1491,8 @@ pub fn paint_file_path_with_line_number(
         Some(absolute_path) => hyperlinks::format_osc8_file_hyperlink(
             absolute_path,
             line_number,
+            column,
+            commit,
             &file_with_line_number,
             config,
         )
@@ -870,6 +1532,76 @@ fn is_whitespace_error(sections: &[(Style, &str)]) -> bool {
     false
 }
 
+/// Give the whitespace errors of the line represented by `sections` the whitespace error style:
+/// its trailing whitespace, and its indentation if that has a space before a tab (which must be
+/// determined before tabs are expanded).
+fn apply_whitespace_error_style(
+    sections: &mut LineSections<'_, Style>,
+    space_before_tab: bool,
+    whitespace_error_style: Style,
+) {
+    if space_before_tab {
+        apply_whitespace_error_style_to_indentation(sections, whitespace_error_style);
+    }
+    apply_whitespace_error_style_to_trailing_whitespace(sections, whitespace_error_style);
+}
+
+/// Whether the indentation of `line` has a space before a tab, which git calls a
+/// "space-before-tab" whitespace error.
+pub fn has_space_before_tab(line: &str) -> bool {
+    let indentation_length = line.len() - line.trim_start_matches([' ', '\t']).len();
+    line[..indentation_length].contains(" \t")
+}
+
+/// Split off the indentation of the line represented by `sections` and give it the whitespace
+/// error style.
+fn apply_whitespace_error_style_to_indentation(
+    sections: &mut LineSections<'_, Style>,
+    whitespace_error_style: Style,
+) {
+    let mut indented_sections = Vec::new();
+    let mut in_indentation = true;
+    for (style, text) in sections.drain(..) {
+        let trimmed = if in_indentation {
+            text.trim_start_matches([' ', '\t'])
+        } else {
+            text
+        };
+        if trimmed.len() < text.len() {
+            indented_sections.push((whitespace_error_style, &text[..text.len() - trimmed.len()]));
+        }
+        if !trimmed.is_empty() {
+            indented_sections.push((style, trimmed));
+            in_indentation = false;
+        }
+    }
+    *sections = indented_sections;
+}
+
+/// Split off the trailing whitespace of the line represented by `sections` (excluding the final
+/// newline) and give it the whitespace error style.
+fn apply_whitespace_error_style_to_trailing_whitespace<'a>(
+    sections: &mut LineSections<'a, Style>,
+    whitespace_error_style: Style,
+) {
+    let mut trailing_sections = Vec::new();
+    while let Some((style, text)) = sections.pop() {
+        let content = text.trim_end_matches('\n');
+        let trimmed = content.trim_end_matches([' ', '\t']);
+        if content.len() < text.len() {
+            trailing_sections.push((style, &text[content.len()..]));
+        }
+        if trimmed.len() < content.len() {
+            trailing_sections.push((whitespace_error_style, &content[trimmed.len()..]));
+        }
+        if !trimmed.is_empty() {
+            sections.push((style, trimmed));
+            break;
+        }
+    }
+    sections.extend(trailing_sections.into_iter().rev());
+}
+
 mod superimpose_style_sections {
     use syntect::highlighting::Style as SyntectStyle;
 
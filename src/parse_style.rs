@@ -142,12 +142,41 @@ impl DecorationStyle {
     }
 }
 
+/// Check a style string, or a decoration style string, returning the error with which delta would
+/// exit when constructing the style, if any.
+pub fn validate_style_string(
+    style_string: &str,
+    is_decoration_style_string: bool,
+    git_config: Option<&GitConfig>,
+) -> Result<(), String> {
+    let (_, style_string) =
+        _extract_special_decoration_attributes(style_string, is_decoration_style_string);
+    let (_, _, is_raw, is_syntax_highlighted) =
+        try_parse_ansi_term_style(&style_string, None, true, git_config)?;
+    if is_decoration_style_string && is_raw {
+        Err("'raw' may not be used in a decoration style.".to_string())
+    } else if is_decoration_style_string && is_syntax_highlighted {
+        Err("'syntax' may not be used in a decoration style.".to_string())
+    } else {
+        Ok(())
+    }
+}
+
 fn parse_ansi_term_style(
     s: &str,
     default: Option<Style>,
     true_color: bool,
     git_config: Option<&GitConfig>,
 ) -> (ansi_term::Style, bool, bool, bool) {
+    try_parse_ansi_term_style(s, default, true_color, git_config).unwrap_or_else(|err| fatal(err))
+}
+
+fn try_parse_ansi_term_style(
+    s: &str,
+    default: Option<Style>,
+    true_color: bool,
+    git_config: Option<&GitConfig>,
+) -> Result<(ansi_term::Style, bool, bool, bool), String> {
     let mut style = ansi_term::Style::new();
     let mut seen_foreground = false;
     let mut seen_background = false;
@@ -195,25 +224,26 @@ fn parse_ansi_term_style(
                 style.foreground = default.and_then(|s| s.ansi_term_style.foreground);
                 is_syntax_highlighted = default.map(|s| s.is_syntax_highlighted).unwrap_or(false);
             } else {
-                style.foreground = color::parse_color(word, true_color, git_config);
+                style.foreground = color::try_parse_color(word, true_color, git_config)?;
             }
             seen_foreground = true;
         } else if !seen_background {
             if word == "syntax" {
-                fatal(
+                return Err(
                     "You have used the special color 'syntax' as a background color \
                        (second color in a style string). It may only be used as a foreground \
-                       color (first color in a style string).",
+                       color (first color in a style string)."
+                        .to_string(),
                 );
             } else if word == "auto" {
                 background_is_auto = true;
                 style.background = default.and_then(|s| s.ansi_term_style.background);
             } else {
-                style.background = color::parse_color(word, true_color, git_config);
+                style.background = color::try_parse_color(word, true_color, git_config)?;
             }
             seen_background = true;
         } else {
-            fatal(format!(
+            return Err(format!(
                 "Invalid style string: {}. See the STYLES section of delta --help.",
                 s
             ));
@@ -227,7 +257,7 @@ fn parse_ansi_term_style(
             is_raw = default.map(|s| s.is_raw).unwrap_or(false);
         }
     }
-    (style, is_omitted, is_raw, is_syntax_highlighted)
+    Ok((style, is_omitted, is_raw, is_syntax_highlighted))
 }
 
 /// Extract set of 'special decoration attributes' and return it along with modified style string.
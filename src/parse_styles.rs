@@ -12,7 +12,7 @@ enum StyleReference {
     Reference(String),
 }
 
-fn is_style_reference(style_string: &str) -> bool {
+pub fn is_style_reference(style_string: &str) -> bool {
     style_string.ends_with("-style") && !style_string.chars().any(|c| c == ' ')
 }
 
@@ -330,6 +330,14 @@ fn make_commit_file_hunk_header_styles(opt: &cli::Opt, styles: &mut HashMap<&str
                 opt.git_config.as_ref(),
             ),
         ),
+        (
+            "only-in-style",
+            if let Some(s) = &opt.only_in_style {
+                style_from_str(s, None, None, true_color, opt.git_config.as_ref())
+            } else {
+                StyleReference::Reference("file-style".to_owned())
+            },
+        ),
         (
             "hunk-header-style",
             style_from_str_with_handling_of_special_decoration_attributes(
@@ -360,10 +368,30 @@ fn make_commit_file_hunk_header_styles(opt: &cli::Opt, styles: &mut HashMap<&str
                 opt.git_config.as_ref(),
             ),
         ),
+        (
+            "hunk-header-function-style",
+            if let Some(s) = &opt.hunk_header_function_style {
+                style_from_str(s, None, None, true_color, opt.git_config.as_ref())
+            } else {
+                StyleReference::Reference("hunk-header-style".to_owned())
+            },
+        ),
     ]);
 }
 
 fn make_blame_styles(opt: &cli::Opt, styles: &mut HashMap<&str, StyleReference>) {
+    if let Some(style_string) = &opt.blame_boundary_style {
+        styles.insert(
+            "blame-boundary-style",
+            style_from_str(
+                style_string,
+                None,
+                None,
+                opt.computed.true_color,
+                opt.git_config.as_ref(),
+            ),
+        );
+    };
     if let Some(style_string) = &opt.blame_code_style {
         styles.insert(
             "blame-code-style",
@@ -388,6 +416,18 @@ fn make_blame_styles(opt: &cli::Opt, styles: &mut HashMap<&str, StyleReference>)
             ),
         );
     };
+    if let Some(style_string) = &opt.blame_uncommitted_style {
+        styles.insert(
+            "blame-uncommitted-style",
+            style_from_str(
+                style_string,
+                None,
+                None,
+                opt.computed.true_color,
+                opt.git_config.as_ref(),
+            ),
+        );
+    };
 }
 
 fn make_grep_styles(opt: &cli::Opt, styles: &mut HashMap<&str, StyleReference>) {
@@ -434,6 +474,16 @@ fn make_grep_styles(opt: &cli::Opt, styles: &mut HashMap<&str, StyleReference>)
                 StyleReference::Reference("zero-style".to_owned())
             },
         ),
+        (
+            "grep-context-separator-style",
+            style_from_str(
+                &opt.grep_context_separator_style,
+                None,
+                None,
+                opt.computed.true_color,
+                opt.git_config.as_ref(),
+            ),
+        ),
         (
             "grep-file-style",
             if let Some(s) = &opt.grep_file_style {
@@ -448,6 +498,16 @@ fn make_grep_styles(opt: &cli::Opt, styles: &mut HashMap<&str, StyleReference>)
                 StyleReference::Reference("hunk-header-file-style".to_owned())
             },
         ),
+        (
+            "grep-function-context-style",
+            style_from_str(
+                &opt.grep_function_context_style,
+                None,
+                None,
+                opt.computed.true_color,
+                opt.git_config.as_ref(),
+            ),
+        ),
         (
             "grep-line-number-style",
             if let Some(s) = &opt.grep_line_number_style {
@@ -466,6 +526,16 @@ fn make_grep_styles(opt: &cli::Opt, styles: &mut HashMap<&str, StyleReference>)
 }
 
 fn make_merge_conflict_styles(opt: &cli::Opt, styles: &mut HashMap<&str, StyleReference>) {
+    styles.insert(
+        "merge-conflict-base-style",
+        style_from_str_with_handling_of_special_decoration_attributes(
+            &opt.merge_conflict_base_style,
+            None,
+            Some(&opt.merge_conflict_base_decoration_style),
+            opt.computed.true_color,
+            opt.git_config.as_ref(),
+        ),
+    );
     styles.insert(
         "merge-conflict-ours-diff-header-style",
         style_from_str_with_handling_of_special_decoration_attributes(
@@ -489,6 +559,176 @@ fn make_merge_conflict_styles(opt: &cli::Opt, styles: &mut HashMap<&str, StyleRe
 }
 
 fn make_misc_styles(opt: &cli::Opt, styles: &mut HashMap<&str, StyleReference>) {
+    styles.insert(
+        "binary-style",
+        style_from_str(
+            &opt.binary_style,
+            None,
+            None,
+            opt.computed.true_color,
+            opt.git_config.as_ref(),
+        ),
+    );
+    styles.insert(
+        "collapsed-file-style",
+        style_from_str(
+            &opt.collapsed_file_style,
+            None,
+            None,
+            opt.computed.true_color,
+            opt.git_config.as_ref(),
+        ),
+    );
+    styles.insert(
+        "diff-stat-summary-style",
+        style_from_str(
+            &opt.diff_stat_summary_style,
+            None,
+            None,
+            opt.computed.true_color,
+            opt.git_config.as_ref(),
+        ),
+    );
+    styles.insert(
+        "git-diff-line-style",
+        style_from_str_with_handling_of_special_decoration_attributes(
+            &opt.git_diff_line_style,
+            None,
+            None,
+            opt.computed.true_color,
+            opt.git_config.as_ref(),
+        ),
+    );
+    styles.insert(
+        "index-line-hash-style",
+        style_from_str(
+            &opt.index_line_hash_style,
+            None,
+            None,
+            opt.computed.true_color,
+            opt.git_config.as_ref(),
+        ),
+    );
+    styles.insert(
+        "index-line-mode-style",
+        style_from_str(
+            &opt.index_line_mode_style,
+            None,
+            None,
+            opt.computed.true_color,
+            opt.git_config.as_ref(),
+        ),
+    );
+    styles.insert(
+        "index-line-style",
+        style_from_str(
+            &opt.index_line_style,
+            None,
+            None,
+            opt.computed.true_color,
+            opt.git_config.as_ref(),
+        ),
+    );
+    styles.insert(
+        "range-diff-added-style",
+        style_from_str(
+            &opt.range_diff_added_style,
+            None,
+            None,
+            opt.computed.true_color,
+            opt.git_config.as_ref(),
+        ),
+    );
+    styles.insert(
+        "range-diff-modified-style",
+        style_from_str(
+            &opt.range_diff_modified_style,
+            None,
+            None,
+            opt.computed.true_color,
+            opt.git_config.as_ref(),
+        ),
+    );
+    styles.insert(
+        "range-diff-removed-style",
+        style_from_str(
+            &opt.range_diff_removed_style,
+            None,
+            None,
+            opt.computed.true_color,
+            opt.git_config.as_ref(),
+        ),
+    );
+    styles.insert(
+        "range-diff-unchanged-style",
+        style_from_str(
+            &opt.range_diff_unchanged_style,
+            None,
+            None,
+            opt.computed.true_color,
+            opt.git_config.as_ref(),
+        ),
+    );
+    styles.insert(
+        "stdin-label-style",
+        style_from_str_with_handling_of_special_decoration_attributes(
+            &opt.stdin_label_style,
+            None,
+            None,
+            opt.computed.true_color,
+            opt.git_config.as_ref(),
+        ),
+    );
+    styles.insert(
+        "submodule-commit-added-style",
+        style_from_str(
+            &opt.submodule_commit_added_style,
+            None,
+            None,
+            opt.computed.true_color,
+            opt.git_config.as_ref(),
+        ),
+    );
+    styles.insert(
+        "submodule-commit-removed-style",
+        style_from_str(
+            &opt.submodule_commit_removed_style,
+            None,
+            None,
+            opt.computed.true_color,
+            opt.git_config.as_ref(),
+        ),
+    );
+    styles.insert(
+        "submodule-style",
+        style_from_str(
+            &opt.submodule_style,
+            None,
+            None,
+            opt.computed.true_color,
+            opt.git_config.as_ref(),
+        ),
+    );
+    styles.insert(
+        "diff-summary-style",
+        style_from_str(
+            &opt.diff_summary_style,
+            None,
+            None,
+            opt.computed.true_color,
+            opt.git_config.as_ref(),
+        ),
+    );
+    styles.insert(
+        "hidden-context-style",
+        style_from_str(
+            &opt.hidden_context_style,
+            None,
+            None,
+            opt.computed.true_color,
+            opt.git_config.as_ref(),
+        ),
+    );
     styles.insert(
         "inline-hint-style",
         style_from_str(
@@ -499,6 +739,26 @@ fn make_misc_styles(opt: &cli::Opt, styles: &mut HashMap<&str, StyleReference>)
             opt.git_config.as_ref(),
         ),
     );
+    styles.insert(
+        "mode-change-style",
+        style_from_str(
+            &opt.mode_change_style,
+            None,
+            None,
+            opt.computed.true_color,
+            opt.git_config.as_ref(),
+        ),
+    );
+    styles.insert(
+        "wrap-truncation-style",
+        style_from_str(
+            &opt.wrap_truncation_style,
+            None,
+            None,
+            opt.computed.true_color,
+            opt.git_config.as_ref(),
+        ),
+    );
     styles.insert(
         "git-minus-style",
         StyleReference::Style(match opt.git_config_entries.get("color.diff.old") {
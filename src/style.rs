@@ -368,6 +368,21 @@ pub fn line_has_style_other_than(line: &str, styles: &[Style]) -> bool {
     true
 }
 
+/// Return the number of sections of `line` styled by SGR sequences other than those of `styles`,
+/// ignoring unstyled sections and sections consisting only of whitespace.
+pub fn count_sections_with_style_other_than(line: &str, styles: &[Style]) -> usize {
+    ansi::parse_style_sections(line)
+        .into_iter()
+        .filter(|(section_style, text)| {
+            !text.trim().is_empty()
+                && !section_style.is_plain()
+                && !styles
+                    .iter()
+                    .any(|style| ansi_term_style_equality(*section_style, style.ansi_term_style))
+        })
+        .count()
+}
+
 #[cfg(test)]
 pub mod tests {
 
@@ -476,6 +491,31 @@ pub mod tests {
         ));
     }
 
+    #[test]
+    fn test_count_sections_with_style_other_than() {
+        let git_styles = [*GIT_DEFAULT_MINUS_STYLE, *GIT_DEFAULT_PLUS_STYLE];
+        assert_eq!(count_sections_with_style_other_than("+aaa", &git_styles), 0);
+        assert_eq!(
+            count_sections_with_style_other_than(
+                "\x1b[32m+\x1b[m\x1b[32m____\x1b[m\n",
+                &git_styles
+            ),
+            0
+        );
+        // Sections consisting only of whitespace are ignored.
+        assert_eq!(
+            count_sections_with_style_other_than(
+                "\x1b[32m+\x1b[m\x1b[32mfn \x1b[m\x1b[34mf\x1b[m\x1b[1;33m()\x1b[m\x1b[41m \x1b[m",
+                &git_styles
+            ),
+            2
+        );
+        assert_eq!(
+            count_sections_with_style_other_than("\x1b[32m+\x1b[m\x1b[32m____\x1b[m", &[]),
+            2
+        );
+    }
+
     #[test]
     fn test_style_compact_debug_fmt() {
         let mut s = Style::new();
@@ -1,8 +1,10 @@
+use std::collections::BTreeSet;
 use std::io::{ErrorKind, Write};
 use std::path::{Path, PathBuf};
 use std::process;
 
 use bytelines::ByteLinesReader;
+use regex::Regex;
 
 use crate::config::{self, delta_unreachable};
 use crate::delta;
@@ -13,6 +15,113 @@ pub fn diff(
     plus_file: &Path,
     config: &config::Config,
     writer: &mut dyn Write,
+) -> i32 {
+    if minus_file.is_dir() && plus_file.is_dir() {
+        diff_directories(minus_file, plus_file, config, writer)
+    } else {
+        diff_files(minus_file, plus_file, config, writer)
+    }
+}
+
+/// Diff the files under two directories, pairing them by their path relative to the directory.
+/// Files present under only one of the directories are shown as added or removed.
+fn diff_directories(
+    minus_dir: &Path,
+    plus_dir: &Path,
+    config: &config::Config,
+    writer: &mut dyn Write,
+) -> i32 {
+    // Entries which cannot be read are reported and skipped, as by `diff -r`.
+    let mut exit_code = 0;
+    let mut list_files = |dir| {
+        let (files, errors) = list_files(dir, &config.exclude);
+        for err in errors {
+            eprintln!("Failed to read directory entry: {}", err);
+            exit_code = config.error_exit_code;
+        }
+        files
+    };
+    let (minus_files, plus_files) = (list_files(minus_dir), list_files(plus_dir));
+    let path_in = |dir: &Path, files: &BTreeSet<PathBuf>, relative_path| {
+        if files.contains(relative_path) {
+            dir.join(relative_path)
+        } else {
+            PathBuf::from("/dev/null")
+        }
+    };
+    for relative_path in minus_files.union(&plus_files) {
+        match diff_files(
+            &path_in(minus_dir, &minus_files, relative_path),
+            &path_in(plus_dir, &plus_files, relative_path),
+            config,
+            writer,
+        ) {
+            1 if exit_code == 0 => exit_code = 1,
+            0 | 1 => {}
+            error_exit_code => return error_exit_code,
+        }
+    }
+    exit_code
+}
+
+/// Return the paths, relative to `dir`, of the files under `dir` which are not excluded, and the
+/// errors of the entries which could not be read.
+fn list_files(dir: &Path, exclude: &[Regex]) -> (BTreeSet<PathBuf>, Vec<std::io::Error>) {
+    let is_excluded = |relative_path: &Path| {
+        let name = relative_path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy();
+        let relative_path = relative_path.to_string_lossy();
+        exclude
+            .iter()
+            .any(|glob| glob.is_match(&relative_path) || glob.is_match(&name))
+    };
+    let mut files = BTreeSet::new();
+    let mut errors = Vec::new();
+    let mut dirs = vec![PathBuf::new()];
+    while let Some(relative_dir) = dirs.pop() {
+        let path = dir.join(&relative_dir);
+        let entries = match std::fs::read_dir(&path) {
+            Ok(entries) => entries,
+            Err(err) => {
+                errors.push(with_path(err, &path));
+                continue;
+            }
+        };
+        for entry in entries {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(err) => {
+                    errors.push(with_path(err, &path));
+                    continue;
+                }
+            };
+            let relative_path = relative_dir.join(entry.file_name());
+            if is_excluded(&relative_path) {
+                continue;
+            }
+            match entry.file_type() {
+                Ok(file_type) if file_type.is_dir() => dirs.push(relative_path),
+                Ok(_) => {
+                    files.insert(relative_path);
+                }
+                Err(err) => errors.push(with_path(err, &entry.path())),
+            }
+        }
+    }
+    (files, errors)
+}
+
+fn with_path(err: std::io::Error, path: &Path) -> std::io::Error {
+    std::io::Error::new(err.kind(), format!("{}: {}", path.display(), err))
+}
+
+fn diff_files(
+    minus_file: &Path,
+    plus_file: &Path,
+    config: &config::Config,
+    writer: &mut dyn Write,
 ) -> i32 {
     use std::io::BufReader;
 
@@ -81,10 +190,12 @@ pub fn diff(
 
 #[cfg(test)]
 mod main_tests {
+    use std::fs;
     use std::io::{Cursor, Read, Seek, SeekFrom};
     use std::path::PathBuf;
 
     use super::diff;
+    use crate::ansi::strip_ansi_codes;
     use crate::tests::integration_test_utils;
 
     #[test]
@@ -111,6 +222,80 @@ mod main_tests {
         _do_diff_test("/etc/group", "/etc/passwd", true);
     }
 
+    #[test]
+    #[cfg_attr(target_os = "windows", ignore)]
+    fn test_diff_directories() {
+        let root = integration_test_utils::make_temp_dir("test_diff_directories");
+        let (dir_a, dir_b) = (root.join("a"), root.join("b"));
+        for (path, contents) in [
+            (dir_a.join("src/lib.rs"), "a"),
+            (dir_b.join("src/lib.rs"), "b"),
+            (dir_a.join("same.txt"), "same"),
+            (dir_b.join("same.txt"), "same"),
+            (dir_a.join("removed.txt"), "removed"),
+            (dir_b.join("src/added.txt"), "added"),
+            (dir_a.join(".git/HEAD"), "x"),
+            (dir_b.join(".git/HEAD"), "y"),
+        ] {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        }
+
+        let config = integration_test_utils::make_config_from_args(&["--exclude", ".git"]);
+        let mut writer = Cursor::new(vec![]);
+        let exit_code = diff(&dir_a, &dir_b, &config, &mut writer);
+        let output = strip_ansi_codes(&_read_to_string(&mut writer));
+        assert_eq!(exit_code, 1);
+        assert!(output.contains("removed: "));
+        assert!(output.contains("removed.txt\n"));
+        assert!(output.contains("added: "));
+        assert!(output.contains("src/added.txt\n"));
+        assert!(output.contains("a/src/lib.rs ⟶   "));
+        assert!(!output.contains("same.txt"));
+        assert!(!output.contains("HEAD"));
+
+        let exit_code = diff(&dir_a, &dir_a, &config, &mut Cursor::new(vec![]));
+        assert_eq!(exit_code, 0);
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_diff_directories_with_unreadable_entry() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = integration_test_utils::make_temp_dir("test_diff_directories_with_unreadable");
+        let (dir_a, dir_b) = (root.join("a"), root.join("b"));
+        for (path, contents) in [
+            (dir_a.join("file.txt"), "a"),
+            (dir_b.join("file.txt"), "b"),
+            (dir_b.join("locked/file.txt"), "locked"),
+        ] {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        }
+        let locked = dir_b.join("locked");
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o000)).unwrap();
+        // Permissions do not apply to root.
+        let is_unreadable = fs::read_dir(&locked).is_err();
+
+        let config = integration_test_utils::make_config_from_args(&[]);
+        let mut writer = Cursor::new(vec![]);
+        let exit_code = diff(&dir_a, &dir_b, &config, &mut writer);
+        let output = strip_ansi_codes(&_read_to_string(&mut writer));
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+        fs::remove_dir_all(root).unwrap();
+
+        // The other files are still compared.
+        assert!(output.contains("a/file.txt ⟶   "));
+        if is_unreadable {
+            assert_eq!(exit_code, config.error_exit_code);
+            assert!(!output.contains("locked"));
+        } else {
+            assert_eq!(exit_code, 1);
+        }
+    }
+
     fn _do_diff_test(file_a: &str, file_b: &str, expect_diff: bool) {
         let config = integration_test_utils::make_config_from_args(&[]);
         let mut writer = Cursor::new(vec![]);
@@ -0,0 +1,97 @@
+use std::io::Write;
+
+use crate::config::Config;
+use crate::env::DeltaEnv;
+use crate::utils::bat::output::{get_pager_command_line, PagingMode};
+
+/// Report how delta is configured to run, without reading any input.
+pub fn dry_run(
+    config: &Config,
+    features: &str,
+    stdout_is_term: bool,
+    env: &DeltaEnv,
+    writer: &mut dyn Write,
+) -> std::io::Result<()> {
+    let paging_mode = match config.paging_mode {
+        PagingMode::Always => "always",
+        PagingMode::QuitIfOneScreen => "auto",
+        PagingMode::Never => "never",
+        PagingMode::Internal => "internal",
+    };
+    // The command line that would be run, as reported by --show-config.
+    let pager = match config.paging_mode {
+        PagingMode::Never => "none".to_string(),
+        PagingMode::Internal => "internal".to_string(),
+        _ => match get_pager_command_line(
+            env,
+            config.pager.clone(),
+            config.paging_mode == PagingMode::QuitIfOneScreen,
+            config,
+        ) {
+            Ok(Some(command_line)) => command_line,
+            Ok(None) => "none".to_string(),
+            Err(error) => error,
+        },
+    };
+    writeln!(
+        writer,
+        "    features                      = {features}
+    pager                         = {pager} (paging={paging_mode})
+    mode                          = {mode}
+    terminal-width                = {terminal_width}
+    stdout-is-terminal            = {stdout_is_term}
+    hyperlinks                    = {hyperlinks}
+    true-color                    = {true_color}",
+        features = features,
+        pager = pager,
+        paging_mode = paging_mode,
+        mode = if config.is_light_mode {
+            "light"
+        } else {
+            "dark"
+        },
+        terminal_width = config.available_terminal_width.get(),
+        stdout_is_term = stdout_is_term,
+        hyperlinks = config.hyperlinks,
+        true_color = config.true_color,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::integration_test_utils;
+
+    #[test]
+    fn test_dry_run() {
+        let env = DeltaEnv {
+            pagers: (Some("env LESS= cat -u".to_string()), None, None),
+            ..DeltaEnv::default()
+        };
+        let opt = integration_test_utils::make_options_from_args_and_git_config_with_custom_env(
+            env.clone(),
+            &[
+                "--navigate",
+                "--light",
+                "--paging",
+                "always",
+                "--width",
+                "77",
+            ],
+            None,
+            None,
+        );
+        let features = opt.features.clone().unwrap();
+        let config = Config::from(opt);
+        let mut output = Vec::new();
+        dry_run(&config, &features, false, &env, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("    features                      = navigate\n"));
+        // The path of the program is resolved, as by --show-config.
+        assert!(output.contains("    pager                         = LESS='' /"));
+        assert!(output.contains("/cat -u (paging=always)\n"));
+        assert!(output.contains("    mode                          = light\n"));
+        assert!(output.contains("    stdout-is-terminal            = false\n"));
+        assert!(output.contains("    hyperlinks                    = false\n"));
+    }
+}
@@ -0,0 +1,147 @@
+use std::io::{self, Write};
+
+use crate::config::Config;
+use crate::utils::bat::less::retrieve_less_version;
+
+// less reads lesskey source files directly (--lesskey-src) since version 582. Older versions
+// only read files compiled by the lesskey program.
+const LESSKEY_SOURCE_MIN_LESS_VERSION: usize = 582;
+
+// Environment settings matching those delta uses when it starts less itself.
+const LESS_ENV: &[(&str, &str)] = &[
+    ("LESS", "-R"),
+    ("LESSCHARSET", "UTF-8"),
+    ("LESSANSIENDCHARS", "mK"),
+];
+
+// Actions and section markers of the compiled lesskey format, see cmd.h and lesskey.h in the
+// less sources.
+const A_B_SEARCH: u8 = 5;
+const A_F_SEARCH: u8 = 15;
+const A_EXTRA: u8 = 0o200;
+const EV_OK: u8 = 1;
+const CMD_SECTION: u8 = b'c';
+const VAR_SECTION: u8 = b'v';
+const END_SECTION: u8 = b'x';
+const LESSKEY_MAGIC: &[u8] = b"\0M+G";
+const END_LESSKEY_MAGIC: &[u8] = b"End";
+const KRADIX: usize = 64;
+
+pub fn generate_lesskey(config: &Config) -> std::io::Result<()> {
+    let navigate_regex = config.navigate_regex.as_deref().unwrap_or_default();
+    let compile = matches!(
+        retrieve_less_version(),
+        Some(version) if version < LESSKEY_SOURCE_MIN_LESS_VERSION
+    );
+    match &config.output {
+        Some(path) if compile => {
+            std::fs::write(path, make_compiled_lesskey(navigate_regex))?;
+        }
+        Some(path) => std::fs::write(path, make_lesskey_source(navigate_regex))?,
+        None => {
+            let stdout = io::stdout();
+            let mut stdout = stdout.lock();
+            if compile {
+                writeln!(
+                    stdout,
+                    "# The installed less cannot read this file directly: compile it with \
+                    `lesskey`,\n# or use delta --generate-lesskey --output=FILE."
+                )?;
+            }
+            stdout.write_all(make_lesskey_source(navigate_regex).as_bytes())?;
+        }
+    }
+    Ok(())
+}
+
+/// Return lesskey source binding n and N to a forward and backward search for `navigate_regex`.
+pub fn make_lesskey_source(navigate_regex: &str) -> String {
+    let mut source = format!(
+        "# Generated by delta --generate-lesskey\n\
+        #command\n\
+        n forw-search {regex}\\n\n\
+        N back-search {regex}\\n\n\
+        #env\n",
+        regex = escape_lesskey_string(navigate_regex),
+    );
+    for (name, value) in LESS_ENV {
+        source.push_str(&format!("{} = {}\n", name, value));
+    }
+    source
+}
+
+/// Return the same bindings as `make_lesskey_source` in the binary format written by the lesskey
+/// program, which is what less versions before 582 read.
+pub fn make_compiled_lesskey(navigate_regex: &str) -> Vec<u8> {
+    let mut commands = Vec::new();
+    for (key, action) in [(b'n', A_F_SEARCH), (b'N', A_B_SEARCH)] {
+        commands.extend([key, 0, action | A_EXTRA]);
+        commands.extend(navigate_regex.as_bytes());
+        commands.extend(b"\n\0");
+    }
+    let mut variables = Vec::new();
+    for (name, value) in LESS_ENV {
+        variables.extend(name.as_bytes());
+        variables.extend([0, EV_OK | A_EXTRA]);
+        variables.extend(value.as_bytes());
+        variables.push(0);
+    }
+
+    let mut compiled = LESSKEY_MAGIC.to_vec();
+    for (section, table) in [(CMD_SECTION, commands), (VAR_SECTION, variables)] {
+        compiled.push(section);
+        compiled.extend([(table.len() % KRADIX) as u8, (table.len() / KRADIX) as u8]);
+        compiled.extend(table);
+    }
+    compiled.push(END_SECTION);
+    compiled.extend(END_LESSKEY_MAGIC);
+    compiled
+}
+
+// In lesskey source '\' escapes the following character and '^' introduces a control character.
+fn escape_lesskey_string(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('^', "\\^")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::integration_test_utils;
+
+    #[test]
+    fn test_lesskey_source_uses_navigate_regex() {
+        let config = integration_test_utils::make_config_from_args(&[
+            "--generate-lesskey",
+            "--hunk-label",
+            "#",
+        ]);
+        let source = make_lesskey_source(config.navigate_regex.as_deref().unwrap());
+        assert!(source
+            .contains("\nn forw-search \\^(commit |added: |removed: |renamed: |Δ |\\\\# )\\n\n"));
+        assert!(source
+            .contains("\nN back-search \\^(commit |added: |removed: |renamed: |Δ |\\\\# )\\n\n"));
+        assert!(source.ends_with("#env\nLESS = -R\nLESSCHARSET = UTF-8\nLESSANSIENDCHARS = mK\n"));
+    }
+
+    #[test]
+    fn test_lesskey_source_uses_custom_navigate_regex() {
+        let config = integration_test_utils::make_config_from_args(&[
+            "--generate-lesskey",
+            "--navigate-regex",
+            r"^\s*@@",
+        ]);
+        let source = make_lesskey_source(config.navigate_regex.as_deref().unwrap());
+        assert!(source.contains(r"n forw-search \^\\s*@@\n"));
+    }
+
+    #[test]
+    fn test_compiled_lesskey() {
+        let compiled = make_compiled_lesskey("^x");
+        let mut expected = b"\0M+Gc\x0e\0".to_vec();
+        expected.extend(b"n\0\x8f^x\n\0N\0\x85^x\n\0");
+        expected.extend(b"v\x31\0");
+        expected.extend(b"LESS\0\x81-R\0LESSCHARSET\0\x81UTF-8\0LESSANSIENDCHARS\0\x81mK\0");
+        expected.extend(b"xEnd");
+        assert_eq!(compiled, expected);
+    }
+}
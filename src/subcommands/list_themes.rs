@@ -0,0 +1,31 @@
+use std::io::{self, Write};
+
+use crate::features::themes::THEMES;
+
+pub fn list_themes() -> std::io::Result<()> {
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+    _list_themes(&mut stdout)
+}
+
+pub fn _list_themes(writer: &mut dyn Write) -> std::io::Result<()> {
+    for theme in THEMES {
+        writeln!(writer, "{}", theme)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_themes() {
+        let mut output = Vec::new();
+        _list_themes(&mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "monokai-delta\nsolarized\n"
+        );
+    }
+}
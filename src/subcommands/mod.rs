@@ -1,8 +1,12 @@
 pub mod diff;
+pub mod dry_run;
+pub mod generate_lesskey;
 pub mod list_syntax_themes;
+pub mod list_themes;
 pub mod parse_ansi;
 mod sample_diff;
 pub mod show_colors;
 pub mod show_config;
 pub mod show_syntax_themes;
 pub mod show_themes;
+pub mod validate_config;
@@ -4,63 +4,84 @@ use itertools::Itertools;
 
 use crate::cli;
 use crate::config;
+use crate::env::DeltaEnv;
 use crate::features::side_by_side::{Left, Right};
+use crate::handlers::diff_header_jj::{self, Vcs};
 use crate::minusplus::*;
 use crate::paint::BgFillMethod;
 use crate::style;
-use crate::utils::bat::output::PagingMode;
+use crate::utils::bat::output::{self, PagingMode};
+use crate::utils::process;
 
-pub fn show_config(config: &config::Config, writer: &mut dyn Write) -> std::io::Result<()> {
-    // styles first
+pub fn show_config(
+    config: &config::Config,
+    env: &DeltaEnv,
+    writer: &mut dyn Write,
+) -> std::io::Result<()> {
+    // styles first, followed by where their values were set, if not by default
+    let styles = [
+        ("commit-style", config.commit_style),
+        ("file-style", config.file_style),
+        ("hunk-header-style", config.hunk_header_style),
+        ("minus-style", config.minus_style),
+        ("minus-non-emph-style", config.minus_non_emph_style),
+        ("minus-emph-style", config.minus_emph_style),
+        (
+            "minus-empty-line-marker-style",
+            config.minus_empty_line_marker_style,
+        ),
+        ("zero-style", config.zero_style),
+        ("plus-style", config.plus_style),
+        ("plus-non-emph-style", config.plus_non_emph_style),
+        ("plus-emph-style", config.plus_emph_style),
+        (
+            "plus-empty-line-marker-style",
+            config.plus_empty_line_marker_style,
+        ),
+        ("grep-file-style", config.grep_file_style),
+        ("grep-line-number-style", config.grep_line_number_style),
+        (
+            "grep-function-context-style",
+            config.grep_function_context_style,
+        ),
+        (
+            "grep-context-separator-style",
+            config.grep_context_separator_style,
+        ),
+        ("whitespace-error-style", config.whitespace_error_style),
+    ];
+    for (option_name, style) in &styles {
+        writeln!(
+            writer,
+            "    {:<31}= {}{}",
+            option_name,
+            style.to_painted_string(),
+            format_option_provenance(option_name, config)
+        )?;
+    }
     writeln!(
         writer,
-        "    commit-style                  = {commit_style}
-    file-style                    = {file_style}
-    hunk-header-style             = {hunk_header_style}
-    minus-style                   = {minus_style}
-    minus-non-emph-style          = {minus_non_emph_style}
-    minus-emph-style              = {minus_emph_style}
-    minus-empty-line-marker-style = {minus_empty_line_marker_style}
-    zero-style                    = {zero_style}
-    plus-style                    = {plus_style}
-    plus-non-emph-style           = {plus_non_emph_style}
-    plus-emph-style               = {plus_emph_style}
-    plus-empty-line-marker-style  = {plus_empty_line_marker_style}
-    grep-file-style               = {grep_file_style}
-    grep-line-number-style        = {grep_line_number_style}
-    whitespace-error-style        = {whitespace_error_style}
-    blame-palette                 = {blame_palette}",
+        "    blame-palette                  = {blame_palette}{provenance}",
         blame_palette = config
             .blame_palette
             .iter()
             .map(|s| style::paint_color_string(s, config.true_color, config.git_config.as_ref()))
             .join(" "),
-        commit_style = config.commit_style.to_painted_string(),
-        file_style = config.file_style.to_painted_string(),
-        hunk_header_style = config.hunk_header_style.to_painted_string(),
-        minus_emph_style = config.minus_emph_style.to_painted_string(),
-        minus_empty_line_marker_style = config.minus_empty_line_marker_style.to_painted_string(),
-        minus_non_emph_style = config.minus_non_emph_style.to_painted_string(),
-        minus_style = config.minus_style.to_painted_string(),
-        plus_emph_style = config.plus_emph_style.to_painted_string(),
-        plus_empty_line_marker_style = config.plus_empty_line_marker_style.to_painted_string(),
-        plus_non_emph_style = config.plus_non_emph_style.to_painted_string(),
-        plus_style = config.plus_style.to_painted_string(),
-        grep_file_style = config.grep_file_style.to_painted_string(),
-        grep_line_number_style = config.grep_line_number_style.to_painted_string(),
-        whitespace_error_style = config.whitespace_error_style.to_painted_string(),
-        zero_style = config.zero_style.to_painted_string(),
+        provenance = format_option_provenance("blame-palette", config),
     )?;
     // Everything else
     writeln!(
         writer,
-        "    true-color                    = {true_color}
-    file-added-label              = {file_added_label}
-    file-modified-label           = {file_modified_label}
-    file-removed-label            = {file_removed_label}
-    file-renamed-label            = {file_renamed_label}
-    right-arrow                   = {right_arrow}",
+        "    true-color                     = {true_color}
+    color                          = {color}{color_provenance}
+    file-added-label               = {file_added_label}
+    file-modified-label            = {file_modified_label}
+    file-removed-label             = {file_removed_label}
+    file-renamed-label             = {file_renamed_label}
+    right-arrow                    = {right_arrow}",
         true_color = config.true_color,
+        color = config.color,
+        color_provenance = format_option_provenance("color", config),
         file_added_label = format_option_value(&config.file_added_label),
         file_modified_label = format_option_value(&config.file_modified_label),
         file_removed_label = format_option_value(&config.file_removed_label),
@@ -69,20 +90,20 @@ pub fn show_config(config: &config::Config, writer: &mut dyn Write) -> std::io::
     )?;
     writeln!(
         writer,
-        "    hyperlinks                    = {hyperlinks}",
+        "    hyperlinks                     = {hyperlinks}",
         hyperlinks = config.hyperlinks
     )?;
     if config.hyperlinks {
         writeln!(
             writer,
-            "    hyperlinks-file-link-format   = {hyperlinks_file_link_format}",
+            "    hyperlinks-file-link-format    = {hyperlinks_file_link_format}",
             hyperlinks_file_link_format = format_option_value(&config.hyperlinks_file_link_format),
         )?
     }
     writeln!(
         writer,
-        "    inspect-raw-lines             = {inspect_raw_lines}
-    keep-plus-minus-markers       = {keep_plus_minus_markers}",
+        "    inspect-raw-lines              = {inspect_raw_lines}
+    keep-plus-minus-markers        = {keep_plus_minus_markers}",
         inspect_raw_lines = match config.inspect_raw_lines {
             cli::InspectRawLines::True => "true",
             cli::InspectRawLines::False => "false",
@@ -91,19 +112,19 @@ pub fn show_config(config: &config::Config, writer: &mut dyn Write) -> std::io::
     )?;
     writeln!(
         writer,
-        "    line-numbers                  = {line_numbers}",
+        "    line-numbers                   = {line_numbers}",
         line_numbers = config.line_numbers
     )?;
     if config.line_numbers {
         writeln!(
             writer,
-            "    line-numbers-minus-style      = {line_numbers_minus_style}
-    line-numbers-zero-style       = {line_numbers_zero_style}
-    line-numbers-plus-style       = {line_numbers_plus_style}
-    line-numbers-left-style       = {line_numbers_left_style}
-    line-numbers-right-style      = {line_numbers_right_style}
-    line-numbers-left-format      = {line_numbers_left_format}
-    line-numbers-right-format     = {line_numbers_right_format}",
+            "    line-numbers-minus-style       = {line_numbers_minus_style}
+    line-numbers-zero-style        = {line_numbers_zero_style}
+    line-numbers-plus-style        = {line_numbers_plus_style}
+    line-numbers-left-style        = {line_numbers_left_style}
+    line-numbers-right-style       = {line_numbers_right_style}
+    line-numbers-left-format       = {line_numbers_left_format}
+    line-numbers-right-format      = {line_numbers_right_format}",
             line_numbers_minus_style =
                 config.line_numbers_style_minusplus[Minus].to_painted_string(),
             line_numbers_zero_style = config.line_numbers_zero_style.to_painted_string(),
@@ -117,22 +138,24 @@ pub fn show_config(config: &config::Config, writer: &mut dyn Write) -> std::io::
     }
     writeln!(
         writer,
-        "    max-line-distance             = {max_line_distance}
-    max-line-length               = {max_line_length}
-    diff-stat-align-width         = {diff_stat_align_width}
-    line-fill-method              = {line_fill_method}
-    navigate                      = {navigate}
-    navigate-regex                = {navigate_regex}
-    pager                         = {pager}
-    paging                        = {paging_mode}
-    side-by-side                  = {side_by_side}
-    syntax-theme                  = {syntax_theme}
-    width                         = {width}
-    tabs                          = {tab_width}
-    word-diff-regex               = {tokenization_regex}",
+        "    max-line-distance              = {max_line_distance}
+    max-line-length                = {max_line_length}
+    max-syntax-highlighting-length = {max_syntax_highlighting_length}
+    diff-stat-align-width          = {diff_stat_align_width}
+    line-fill-method               = {line_fill_method}
+    navigate                       = {navigate}
+    navigate-regex                 = {navigate_regex}
+    pager                          = {pager}
+    paging                         = {paging_mode}
+    side-by-side                   = {side_by_side}
+    syntax-theme                   = {syntax_theme}
+    width                          = {width}
+    tabs                           = {tab_width}
+    word-diff-regex                = {tokenization_regex}",
         diff_stat_align_width = config.diff_stat_align_width,
         max_line_distance = config.max_line_distance,
         max_line_length = config.max_line_length,
+        max_syntax_highlighting_length = config.max_syntax_highlighting_length,
         line_fill_method = match config.line_fill_method {
             BgFillMethod::TryAnsiSequence => "ansi",
             BgFillMethod::Spaces => "spaces",
@@ -142,11 +165,25 @@ pub fn show_config(config: &config::Config, writer: &mut dyn Write) -> std::io::
             None => "".to_string(),
             Some(s) => format_option_value(s),
         },
-        pager = config.pager.clone().unwrap_or_else(|| "none".to_string()),
+        // The command line that would be run, e.g. "LESS= /usr/bin/less --RAW-CONTROL-CHARS".
+        pager = match config.paging_mode {
+            PagingMode::Internal => "internal".to_string(),
+            _ => match output::get_pager_command_line(
+                env,
+                config.pager.clone(),
+                config.paging_mode == PagingMode::QuitIfOneScreen,
+                config,
+            ) {
+                Ok(Some(command_line)) => command_line,
+                Ok(None) => "none".to_string(),
+                Err(error) => error,
+            },
+        },
         paging_mode = match config.paging_mode {
             PagingMode::Always => "always",
             PagingMode::Never => "never",
             PagingMode::QuitIfOneScreen => "auto",
+            PagingMode::Internal => "internal",
         },
         side_by_side = config.side_by_side,
         syntax_theme = config
@@ -154,13 +191,24 @@ pub fn show_config(config: &config::Config, writer: &mut dyn Write) -> std::io::
             .clone()
             .map(|t| t.name.unwrap_or_else(|| "none".to_string()))
             .unwrap_or_else(|| "none".to_string()),
-        width = match config.decorations_width {
+        width = match config.decorations_width.get() {
             cli::Width::Fixed(width) => width.to_string(),
             cli::Width::Variable => "variable".to_string(),
         },
         tab_width = config.tab_width,
         tokenization_regex = format_option_value(&config.tokenization_regex.to_string()),
     )?;
+    writeln!(
+        writer,
+        "    vcs                            = {vcs}
+    calling-process                = {calling_process}",
+        vcs = match diff_header_jj::vcs(config) {
+            Vcs::Git => "git",
+            Vcs::Jj => "jj",
+            Vcs::Hg => "hg",
+        },
+        calling_process = process::calling_process(),
+    )?;
     Ok(())
 }
 
@@ -182,9 +230,19 @@ where
     }
 }
 
+// Return a comment naming the source of the value of the option, e.g. `  # [delta "my-feature"]`,
+// or the empty string if the option has its default value.
+fn format_option_provenance(option_name: &str, config: &config::Config) -> String {
+    match config.option_provenances.get(option_name) {
+        Some(provenance) => format!("  # {}", provenance),
+        None => "".to_string(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::tests::integration_test_utils;
+    use crate::utils::process::tests::FakeParentArgs;
 
     use super::*;
     use crate::ansi;
@@ -194,12 +252,78 @@ mod tests {
     fn test_show_config() {
         let config = integration_test_utils::make_config_from_args(&[]);
         let mut writer = Cursor::new(vec![0; 1024]);
-        show_config(&config, &mut writer).unwrap();
+        show_config(&config, &DeltaEnv::default(), &mut writer).unwrap();
+        let mut s = String::new();
+        writer.seek(SeekFrom::Start(0)).unwrap();
+        writer.read_to_string(&mut s).unwrap();
+        let s = ansi::strip_ansi_codes(&s);
+        assert!(s.contains("    commit-style                   = raw\n"));
+        assert!(s.contains(r"    word-diff-regex                = '\w+'"));
+        assert!(s.contains("    vcs                            = git\n"));
+        assert!(s.contains("    max-syntax-highlighting-length = 0\n"));
+    }
+
+    #[test]
+    fn test_show_config_pager() {
+        let env = DeltaEnv {
+            pagers: (Some("env LESS= cat -u".to_string()), None, None),
+            ..DeltaEnv::default()
+        };
+        let config = integration_test_utils::make_config_from_args(&["--paging", "always"]);
+        let mut writer = Cursor::new(vec![0; 1024]);
+        show_config(&config, &env, &mut writer).unwrap();
+        let mut s = String::new();
+        writer.seek(SeekFrom::Start(0)).unwrap();
+        writer.read_to_string(&mut s).unwrap();
+        let s = ansi::strip_ansi_codes(&s);
+        // The path of the program is resolved.
+        assert!(s.contains("    pager                          = LESS='' /"));
+        assert!(s.contains("/cat -u\n"));
+    }
+
+    #[test]
+    fn test_show_config_calling_process() {
+        let _args = FakeParentArgs::for_scope("jj diff --git");
+        let config = integration_test_utils::make_config_from_args(&[]);
+        let mut writer = Cursor::new(vec![0; 1024]);
+        show_config(&config, &DeltaEnv::default(), &mut writer).unwrap();
         let mut s = String::new();
         writer.seek(SeekFrom::Start(0)).unwrap();
         writer.read_to_string(&mut s).unwrap();
         let s = ansi::strip_ansi_codes(&s);
-        assert!(s.contains("    commit-style                  = raw\n"));
-        assert!(s.contains(r"    word-diff-regex               = '\w+'"));
+        assert!(s.contains("    vcs                            = jj\n"));
+        assert!(s.contains("    calling-process                = jj diff --git\n"));
+    }
+
+    #[test]
+    fn test_show_config_option_provenances() {
+        let git_config_contents = b"
+[delta]
+    features = my-feature
+    file-style = blue
+
+[delta \"my-feature\"]
+    minus-style = green
+";
+        let git_config_path = "delta__test_show_config_option_provenances.gitconfig";
+        let config = integration_test_utils::make_config_from_args_and_git_config(
+            &["--plus-style", "red"],
+            Some(git_config_contents),
+            Some(git_config_path),
+        );
+        let mut writer = Cursor::new(vec![0; 1024]);
+        show_config(&config, &DeltaEnv::default(), &mut writer).unwrap();
+        let mut s = String::new();
+        writer.seek(SeekFrom::Start(0)).unwrap();
+        writer.read_to_string(&mut s).unwrap();
+        let s = ansi::strip_ansi_codes(&s);
+        assert!(s.contains("    file-style                     = blue  # [delta]\n"));
+        assert!(
+            s.contains("    minus-style                    = green  # [delta \"my-feature\"]\n")
+        );
+        assert!(s.contains("    plus-style                     = red  # command line\n"));
+        assert!(s.contains("    commit-style                   = raw\n"));
+
+        std::fs::remove_file(git_config_path).unwrap();
     }
 }
@@ -2,11 +2,13 @@ use crate::cli;
 use crate::config;
 use crate::delta;
 use crate::env::DeltaEnv;
+use crate::fatal;
 use crate::options::theme::is_light_syntax_theme;
 use crate::utils;
 use crate::utils::bat::output::{OutputType, PagingMode};
 use clap::Parser;
 use std::io::{self, ErrorKind, Read, Write};
+use std::path::Path;
 
 #[cfg(not(tarpaulin_include))]
 pub fn show_syntax_themes() -> std::io::Result<()> {
@@ -21,7 +23,36 @@ pub fn show_syntax_themes() -> std::io::Result<()> {
     .unwrap();
     let mut writer = output_type.handle().unwrap();
 
-    let stdin_data = if !atty::is(atty::Stream::Stdin) {
+    let make_opt = || {
+        let mut opt = cli::Opt::parse();
+        opt.computed.syntax_set = assets.get_syntax_set().unwrap().clone().into();
+        opt
+    };
+    let opt = make_opt();
+
+    let stdin_data = if let Some(sample_file) = &opt.sample_file {
+        let language = opt.language.as_deref().map(|language| {
+            assets
+                .get_syntax_set()
+                .unwrap()
+                .find_syntax_by_token(language)
+                .and_then(|syntax| syntax.file_extensions.first().cloned())
+                .unwrap_or_else(|| {
+                    fatal(format!(
+                        "Unknown language: {}. Use --list-languages to list supported languages.",
+                        language
+                    ))
+                })
+        });
+        let contents = std::fs::read_to_string(sample_file).unwrap_or_else(|err| {
+            fatal(format!(
+                "Failed to read sample file {}: {}",
+                sample_file.display(),
+                err
+            ))
+        });
+        Some(make_sample_file_diff(sample_file, language.as_deref(), &contents).into_bytes())
+    } else if !atty::is(atty::Stream::Stdin) {
         let mut buf = Vec::new();
         io::stdin().lock().read_to_end(&mut buf)?;
         if !buf.is_empty() {
@@ -33,13 +64,6 @@ pub fn show_syntax_themes() -> std::io::Result<()> {
         None
     };
 
-    let make_opt = || {
-        let mut opt = cli::Opt::parse();
-        opt.computed.syntax_set = assets.get_syntax_set().unwrap().clone();
-        opt
-    };
-    let opt = make_opt();
-
     if !(opt.dark || opt.light) {
         _show_syntax_themes(opt, false, &mut writer, stdin_data.as_ref())?;
         _show_syntax_themes(make_opt(), true, &mut writer, stdin_data.as_ref())?;
@@ -81,6 +105,7 @@ index f38589a..0f1bb83 100644
     };
 
     opt.computed.is_light_mode = is_light_mode;
+    let theme_filter = opt.theme_filter.as_deref().map(str::to_lowercase);
     let mut config = config::Config::from(opt);
     let title_style = ansi_term::Style::new().bold();
     let assets = utils::bat::assets::load_highlighting_assets();
@@ -88,6 +113,10 @@ index f38589a..0f1bb83 100644
     for syntax_theme in assets
         .themes()
         .filter(|t| is_light_syntax_theme(t) == is_light_mode)
+        .filter(|t| match &theme_filter {
+            Some(theme_filter) => t.to_lowercase().contains(theme_filter),
+            None => true,
+        })
     {
         writeln!(
             writer,
@@ -107,6 +136,35 @@ index f38589a..0f1bb83 100644
     Ok(())
 }
 
+/// Return a diff adding a file with `contents`. The file is named after `sample_file`, with the
+/// extension replaced by `language` if that is given.
+fn make_sample_file_diff(sample_file: &Path, language: Option<&str>, contents: &str) -> String {
+    let file_name = match language {
+        Some(language) => sample_file.with_extension(language),
+        None => sample_file.to_path_buf(),
+    };
+    let file_name = file_name
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    let lines: Vec<&str> = contents.lines().collect();
+    let mut diff = format!(
+        "diff --git a/{name} b/{name}\n\
+         new file mode 100644\n\
+         --- /dev/null\n\
+         +++ b/{name}\n\
+         @@ -0,0 +1,{n} @@\n",
+        name = file_name,
+        n = lines.len()
+    );
+    for line in lines {
+        diff.push('+');
+        diff.push_str(line);
+        diff.push('\n');
+    }
+    diff
+}
+
 #[cfg(test)]
 mod tests {
     use std::io::{Cursor, Seek, SeekFrom};
@@ -130,4 +188,44 @@ mod tests {
         println!("{}", s);
         assert!(s.contains("\nfn print_cube(num: f64) {\n"));
     }
+
+    #[test]
+    fn test_show_syntax_themes_with_sample_file_and_theme_filter() {
+        let mut opt =
+            integration_test_utils::make_options_from_args(&["--theme-filter", "GRUVBOX"]);
+        opt.computed.syntax_set = utils::bat::assets::load_highlighting_assets()
+            .get_syntax_set()
+            .unwrap()
+            .clone()
+            .into();
+        let input = make_sample_file_diff(
+            Path::new("/tmp/sample.txt"),
+            Some("py"),
+            "def square(x):\n    return x ** 2\n",
+        )
+        .into_bytes();
+
+        let mut writer = Cursor::new(Vec::new());
+        _show_syntax_themes(opt, true, &mut writer, Some(&input)).unwrap();
+        let s = ansi::strip_ansi_codes(std::str::from_utf8(writer.get_ref()).unwrap());
+        assert!(s.contains("\nSyntax theme: gruvbox-light\n"));
+        assert!(!s.contains("\nSyntax theme: GitHub\n"));
+        assert!(s.contains("added: sample.py\n"));
+        assert!(s.contains("\n    return x ** 2\n"));
+    }
+
+    #[test]
+    fn test_make_sample_file_diff() {
+        assert_eq!(
+            make_sample_file_diff(Path::new("dir/example.rs"), None, "fn f() {}\n\nfn g() {}"),
+            "diff --git a/example.rs b/example.rs\n\
+             new file mode 100644\n\
+             --- /dev/null\n\
+             +++ b/example.rs\n\
+             @@ -0,0 +1,3 @@\n\
+             +fn f() {}\n\
+             +\n\
+             +fn g() {}\n"
+        );
+    }
 }
@@ -0,0 +1,204 @@
+use std::collections::HashSet;
+use std::io::Write;
+
+use crate::cli;
+use crate::features;
+use crate::git_config::GitConfig;
+use crate::parse_style;
+use crate::parse_styles;
+
+/// Check the entries of the [delta] section of git config, and of the sections of custom features,
+/// for unknown options, invalid style strings and features which do not exist. All errors are
+/// written, grouped by section. Return whether there were none.
+pub fn validate_config(opt: &cli::Opt, writer: &mut dyn Write) -> std::io::Result<bool> {
+    let git_config = opt.git_config.as_ref();
+    let entries = git_config
+        .map(|git_config| git_config.get_delta_entries())
+        .unwrap_or_default();
+    // (section, key, value), where the section is "" for [delta] and the feature name otherwise.
+    let entries: Vec<(&str, &str, &str)> = entries
+        .iter()
+        .map(|(name, value)| {
+            let name = name.strip_prefix("delta.").unwrap_or(name);
+            let (section, key) = name.rsplit_once('.').unwrap_or(("", name));
+            (section, key, value.as_str())
+        })
+        .collect();
+
+    let option_names: HashSet<&str> = cli::Opt::get_argument_and_option_names()
+        .into_values()
+        .collect();
+    let builtin_features = features::make_builtin_features();
+    let custom_features: HashSet<&str> = entries
+        .iter()
+        .map(|(section, _, _)| *section)
+        .filter(|section| !section.is_empty())
+        .collect();
+    let delta_keys: HashSet<&str> = entries
+        .iter()
+        .filter(|(section, _, _)| section.is_empty())
+        .map(|(_, key, _)| *key)
+        .collect();
+    // Entries of [delta] which are not options may define the colors and styles named in style
+    // strings.
+    let names_in_style_strings: HashSet<String> = entries
+        .iter()
+        .filter(|(_, key, _)| is_style_key(key))
+        .flat_map(|(_, _, value)| value.split_whitespace())
+        .map(|word| word.trim_matches(|c| c == '"' || c == '\'').to_lowercase())
+        .collect();
+
+    let mut errors: Vec<(&str, Vec<String>)> = Vec::new();
+    for (section, key, value) in &entries {
+        let is_known_key = option_names.contains(key)
+            || section.is_empty() && names_in_style_strings.contains(*key);
+        let error = if !is_known_key {
+            Some(format!("{}: unknown option", key))
+        } else if is_style_key(key) {
+            validate_style_string(value, key, &option_names, &delta_keys, git_config)
+                .err()
+                .map(|err| format!("{} = {}: {}", key, value, err))
+        } else if *key == "features" {
+            let unknown_features: Vec<&str> = value
+                .split_whitespace()
+                .filter(|feature| {
+                    !builtin_features.contains_key(*feature) && !custom_features.contains(feature)
+                })
+                .collect();
+            (!unknown_features.is_empty()).then(|| {
+                format!(
+                    "{} = {}: unknown features: {}",
+                    key,
+                    value,
+                    unknown_features.join(", ")
+                )
+            })
+        } else {
+            None
+        };
+        if let Some(error) = error {
+            match errors.iter_mut().find(|(s, _)| s == section) {
+                Some((_, section_errors)) => section_errors.push(error),
+                None => errors.push((section, vec![error])),
+            }
+        }
+    }
+
+    for (section, section_errors) in &errors {
+        if section.is_empty() {
+            writeln!(writer, "[delta]")?;
+        } else {
+            writeln!(writer, "[delta \"{}\"]", section)?;
+        }
+        for error in section_errors {
+            writeln!(writer, "    {}", error)?;
+        }
+    }
+    let n_errors: usize = errors.iter().map(|(_, errors)| errors.len()).sum();
+    match n_errors {
+        0 => writeln!(writer, "No errors found.")?,
+        1 => writeln!(writer, "1 error found.")?,
+        n => writeln!(writer, "{} errors found.", n)?,
+    }
+    Ok(n_errors == 0)
+}
+
+fn is_style_key(key: &str) -> bool {
+    key.ends_with("-style")
+}
+
+// A style string may instead be the name of another style option, or of an entry of [delta]
+// holding a style string.
+fn validate_style_string(
+    value: &str,
+    key: &str,
+    option_names: &HashSet<&str>,
+    delta_keys: &HashSet<&str>,
+    git_config: Option<&GitConfig>,
+) -> Result<(), String> {
+    if parse_styles::is_style_reference(value) {
+        if option_names.contains(value) || delta_keys.contains(value) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Style key not found in git config: delta.{}",
+                value
+            ))
+        }
+    } else {
+        parse_style::validate_style_string(value, key.ends_with("decoration-style"), git_config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::remove_file;
+
+    use super::*;
+    use crate::tests::integration_test_utils;
+
+    fn run_validate_config(git_config_contents: &[u8], git_config_path: &str) -> (bool, String) {
+        let opt = integration_test_utils::make_options_from_args_and_git_config(
+            &[],
+            Some(git_config_contents),
+            Some(git_config_path),
+        );
+        let mut output = Vec::new();
+        let is_valid = validate_config(&opt, &mut output).unwrap();
+        remove_file(git_config_path).unwrap();
+        (is_valid, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn test_validate_config() {
+        let git_config_contents = b"
+[delta]
+    features = decorations
+    minus-stlye = red
+    plus-style = bold grene
+    my-color = \"#ff0000\"
+    my-custom-style = bold my-color
+    zero-style = my-custom-style
+    hunk-header-style = other-style
+
+[delta \"decorations\"]
+    features = line-numbers no-such-feature
+    file-decoration-style = syntax box
+    commit-style = raw
+";
+        let git_config_path = "delta__test_validate_config.gitconfig";
+        let (is_valid, output) = run_validate_config(git_config_contents, git_config_path);
+        assert!(!is_valid);
+        assert_eq!(
+            output,
+            "\
+[delta]
+    minus-stlye: unknown option
+    plus-style = bold grene: Invalid color or style attribute: grene
+    hunk-header-style = other-style: Style key not found in git config: delta.other-style
+[delta \"decorations\"]
+    features = line-numbers no-such-feature: unknown features: no-such-feature
+    file-decoration-style = syntax box: 'syntax' may not be used in a decoration style.
+5 errors found.
+"
+        );
+    }
+
+    #[test]
+    fn test_validate_valid_config() {
+        let git_config_contents = b"
+[delta]
+    features = side-by-side my-feature
+    navigate
+
+[delta \"my-feature\"]
+    minus-style = syntax \"#3f0001\"
+    file-style = bold yellow ul
+    file-decoration-style = yellow ol ul
+";
+        let git_config_path = "delta__test_validate_valid_config.gitconfig";
+        let (is_valid, output) = run_validate_config(git_config_contents, git_config_path);
+        assert!(is_valid, "{}", output);
+        assert_eq!(output, "No errors found.\n");
+    }
+}
@@ -0,0 +1,93 @@
+#![cfg(test)]
+
+// Input shared by the tests of several features.
+
+/// `git log -p --stat`, with three commits: the first modifies files in several hunks, the second
+/// renames, removes and adds files, and the third modifies a test file only.
+pub const GIT_LOG_THREE_COMMITS: &str = "\
+commit 94907c0f136f46dc46ffae2dc92dca9af7eb7c2e
+Author: Dan Davison <dandavison7@gmail.com>
+Date:   Wed Jan 3 08:00:00 2024 -0500
+
+    Update lib and tests
+
+ Cargo.lock        | 4 ++--
+ src/lib.rs        | 3 ++-
+ tests/test_lib.rs | 2 +-
+ 3 files changed, 5 insertions(+), 4 deletions(-)
+
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,3 +1,3 @@ mod tests
+ mod a;
+-mod b;
++mod c;
+ mod d;
+@@ -10 +10,2 @@
+ mod e;
++mod f;
+diff --git a/Cargo.lock b/Cargo.lock
+index 3333333..4444444 100644
+--- a/Cargo.lock
++++ b/Cargo.lock
+@@ -1,2 +1,3 @@
+ [[package]]
++name = \"a\"
+ version = \"1\"
+@@ -10,2 +11,1 @@
+-name = \"b\"
+-version = \"2\"
++version = \"3\"
+diff --git a/tests/test_lib.rs b/tests/test_lib.rs
+index 5555555..6666666 100644
+--- a/tests/test_lib.rs
++++ b/tests/test_lib.rs
+@@ -1 +1 @@
+-fn test_lib() {}
++fn test_lib2() {}
+
+commit 6d1b7a2e4c3f5a8b9d0e1f2a3b4c5d6e7f8a9b0c
+Author: Dan Davison <dandavison7@gmail.com>
+Date:   Tue Jan 2 08:00:00 2024 -0500
+
+    Move, remove and add files
+
+diff --git a/src/old.rs b/src/moved.rs
+similarity index 100%
+rename from src/old.rs
+rename to src/moved.rs
+diff --git a/src/gone.rs b/src/gone.rs
+deleted file mode 100644
+index 7777777..0000000
+--- a/src/gone.rs
++++ /dev/null
+@@ -1,2 +0,0 @@
+-fn gone() {}
+-fn gone_too() {}
+diff --git a/src/new.rs b/src/new.rs
+new file mode 100644
+index 0000000..8888888
+--- /dev/null
++++ b/src/new.rs
+@@ -0,0 +1,4 @@
++fn a() {}
++fn b() {}
++fn c() {}
++fn d() {}
+
+commit 8d8ba7c9e0c2b7e16a82d4fc31d9eb1bf2d6a2f5
+Author: Dan Davison <dandavison7@gmail.com>
+Date:   Mon Jan 1 08:00:00 2024 -0500
+
+    Update tests only
+
+diff --git a/tests/test_lib.rs b/tests/test_lib.rs
+index 9999999..5555555 100644
+--- a/tests/test_lib.rs
++++ b/tests/test_lib.rs
+@@ -1 +1 @@
+-fn test() {}
++fn test_lib() {}
+";
@@ -3,7 +3,7 @@
 use std::borrow::Cow;
 use std::fs::File;
 use std::io::{BufReader, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use bytelines::ByteLines;
 use itertools;
@@ -307,6 +307,15 @@ pub fn run_delta(input: &str, config: &config::Config) -> String {
     String::from_utf8(writer).unwrap()
 }
 
+/// Create an empty directory for the files written by the test `name`. The directory is unique to
+/// the test process, so that concurrent test runs do not interfere.
+pub fn make_temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("delta__{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
 pub mod tests {
     use super::*;
 
@@ -391,8 +400,8 @@ ignored!  2
                 r#"
                  #indent_mark
                  @@ -1,1 +1,1 @@ fn foo() {
-                   1 ⋮    │-1
-                     ⋮  1 │+2"#,
+                 1⋮ │-1
+                  ⋮1│+2"#,
             );
 
         DeltaTest::with_args(&[])
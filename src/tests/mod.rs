@@ -1,4 +1,5 @@
 pub mod ansi_test_utils;
+pub mod fixtures;
 pub mod integration_test_utils;
 pub mod test_example_diffs;
 pub mod test_utils;
@@ -55,6 +55,134 @@ mod tests {
         println!("{}", t.output);
     }
 
+    #[test]
+    fn test_file_renamed_format() {
+        DeltaTest::with_args(&[
+            "--file-renamed-format",
+            "{label} {old} → {new} ({similarity}%)",
+        ])
+        .with_input(RENAMED_FILE_WITH_CHANGES_INPUT)
+        .expect_contains_once(
+            "\nrenamed: Casks/font-dejavusansmono-nerd-font.rb → Casks/font-dejavu-sans-mono-nerd-font.rb (95%)\n",
+        );
+        DeltaTest::with_args(&[
+            "--file-renamed-format",
+            "{label} {new} ← {old} [{similarity}]",
+        ])
+        .with_input(GIT_DIFF_WITH_COPIED_FILE)
+        .expect_contains_once("\ncopied: copied_file ← first_file [100]\n");
+    }
+
+    #[test]
+    fn test_empty_file_labels() {
+        DeltaTest::with_args(&["--file-added-label", "", "--file-removed-label", ""])
+            .with_input(GIT_DIFF_FILE_LABEL_STATS)
+            .expect_contains_once("\nsrc/new.rs\n")
+            .expect_contains_once("\nsrc/old.rs\n");
+        DeltaTest::with_args(&[
+            "--file-renamed-label",
+            "",
+            "--file-renamed-format",
+            "{label} {old} → {new}",
+        ])
+        .with_input(RENAMED_FILE_INPUT)
+        .expect_contains_once("\na.py → b.py\n");
+    }
+
+    #[test]
+    fn test_collapse_renamed_paths() {
+        DeltaTest::with_args(&["--collapse-renamed-paths"])
+            .with_input(RENAMED_FILE_WITH_CHANGES_INPUT)
+            .expect_contains_once(
+                "\nrenamed: Casks/{font-dejavusansmono-nerd-font.rb ⟶   font-dejavu-sans-mono-nerd-font.rb}\n",
+            );
+        DeltaTest::with_args(&[
+            "--collapse-renamed-paths",
+            "--file-renamed-format",
+            "{label} {old} => {new}",
+        ])
+        .with_input(RENAMED_FILE_INPUT)
+        .expect_contains_once("\nrenamed: a.py => b.py\n");
+    }
+
+    #[test]
+    fn test_file_labels_with_stats() {
+        DeltaTest::with_args(&[
+            "--file-modified-label",
+            "M ({insertions}+ {deletions}-, {hunks} hunks)",
+            "--file-added-label",
+            "A {path} ({insertions}+)",
+            "--file-removed-label",
+            "D ({deletions}-)",
+        ])
+        .with_input(GIT_DIFF_FILE_LABEL_STATS)
+        .expect_contains_once("\nM (2+ 1-, 2 hunks) src/lib.rs\n")
+        .expect_contains_once("\nA src/new.rs (2+)\n")
+        .expect_contains_once("\nD (3-) src/old.rs\n");
+
+        // Files of a patch without "diff" lines are counted separately.
+        DeltaTest::with_args(&["--file-modified-label", "M ({insertions}+ {deletions}-)"])
+            .with_input(DIFF_UNIFIED_CONCATENATED)
+            .expect_contains_once("\nM (2+ 2-) 1/x ")
+            .expect_contains_once("\nM (1+ 1-) 1/y ");
+
+        // Labels which do not display counts are formatted as the lines are read.
+        DeltaTest::with_args(&["--file-modified-label", "[{path}]"])
+            .with_input(GIT_DIFF_FILE_LABEL_STATS)
+            .expect_contains_once("\n[src/lib.rs]\n");
+    }
+
+    #[test]
+    fn test_stdin_label() {
+        DeltaTest::with_args(&["--stdin-label", "Changes since v1.2"])
+            .with_input(ADDED_FILE_INPUT)
+            .expect(
+                "#partial
+                Changes since v1.2
+                commit d28dc1ac57e53432567ec5bf19ad49ff90f0f7a5",
+            );
+        DeltaTest::with_args(&[
+            "--stdin-label",
+            "Changes since v1.2",
+            "--stdin-label-style",
+            "bold underline",
+            "--width",
+            "24",
+        ])
+        .with_input(ADDED_FILE_INPUT)
+        .expect(
+            "#partial
+            Changes since v1.2
+            ────────────────────────
+            commit d28dc1ac57e53432567ec5bf19ad49ff90f0f7a5",
+        );
+        DeltaTest::with_args(&["--stdin-label", "Changes since v1.2", "--color-only"])
+            .with_input(ADDED_FILE_INPUT)
+            .expect(
+                "#partial
+                commit d28dc1ac57e53432567ec5bf19ad49ff90f0f7a5",
+            );
+    }
+
+    #[test]
+    fn test_show_git_diff_line() {
+        DeltaTest::with_args(&["--show-git-diff-line"])
+            .with_input(ADDED_FILE_INPUT)
+            .expect_contains_once("\ndiff --git a/a.py b/a.py\n\nadded: a.py\n");
+        DeltaTest::with_args(&["--show-git-diff-line", "--git-diff-line-style", "bold box"])
+            .with_input(ADDED_FILE_INPUT)
+            .expect_contains_once(
+                "
+─────────────────────────┐
+diff --git a/a.py b/a.py │
+─────────────────────────┘
+",
+            );
+        DeltaTest::with_args(&[])
+            .with_input(ADDED_FILE_INPUT)
+            .expect_contains_once("\n\nadded: a.py\n");
+    }
+
     #[test]
     fn test_recognized_file_type() {
         // In addition to the background color, the code has language syntax highlighting.
@@ -124,7 +252,7 @@ mod tests {
         // Change
         assert_eq!(lines.nth(7).unwrap(), "This is different from b");
         // File uniqueness
-        assert_eq!(lines.nth(2).unwrap(), "Only in a/: just_a");
+        assert_eq!(lines.nth(2).unwrap(), "removed: a/just_a");
         // DiffHeader divider
         assert!(lines.next().unwrap().starts_with("───────"));
         // Next hunk
@@ -179,6 +307,52 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_plain() {
+        let output =
+            DeltaTest::with_args(&["--plain", "--side-by-side", "--width", "60", "--hyperlinks"])
+                .with_input(GIT_DIFF_SINGLE_HUNK)
+                .expect_raw_contains("│71│");
+        assert!(!output.raw_output.contains('\x1b'));
+    }
+
+    #[test]
+    fn test_max_line_length() {
+        let long_line_end = "self.index(i + 1, j));";
+
+        let config = integration_test_utils::make_config_from_args(&["--max-line-length", "60"]);
+        let output = integration_test_utils::run_delta(GIT_DIFF_SINGLE_HUNK, &config);
+        assert!(!strip_ansi_codes(&output).contains(long_line_end));
+
+        // Zero keeps the whole line.
+        let config = integration_test_utils::make_config_from_args(&["--max-line-length", "0"]);
+        let output = integration_test_utils::run_delta(GIT_DIFF_SINGLE_HUNK, &config);
+        assert!(strip_ansi_codes(&output).contains(long_line_end));
+    }
+
+    #[test]
+    fn test_max_syntax_highlighting_length() {
+        let long_line_end = "self.index(i + 1, j));";
+        let long_line = |output: &str| {
+            output
+                .lines()
+                .find(|line| strip_ansi_codes(line).contains(long_line_end))
+                .unwrap()
+                .to_string()
+        };
+        let config = integration_test_utils::make_config_from_args(&[
+            "--max-syntax-highlighting-length",
+            "60",
+        ]);
+        let output = integration_test_utils::run_delta(GIT_DIFF_SINGLE_HUNK, &config);
+        let config = integration_test_utils::make_config_from_args(&["--syntax-theme", "none"]);
+        let output_without_highlighting =
+            integration_test_utils::run_delta(GIT_DIFF_SINGLE_HUNK, &config);
+        // The long line is displayed in full, but not highlighted.
+        assert_eq!(long_line(&output), long_line(&output_without_highlighting));
+        assert_ne!(output, output_without_highlighting);
+    }
+
     #[test]
     fn test_diff_with_merge_conflict_is_not_truncated() {
         let config = integration_test_utils::make_config_from_args(&[]);
@@ -193,6 +367,79 @@ mod tests {
         assert_eq!(strip_ansi_codes(&output), DIFF_WITH_MERGE_CONFLICT);
     }
 
+    #[test]
+    fn test_combined_diff_merge_parent_palette() {
+        let args = ["--minus-style", "red", "--plus-style", "green"];
+        let config = integration_test_utils::make_config_from_args(&args);
+        let output = integration_test_utils::run_delta(GIT_DIFF_COMBINED, &config);
+        // The markers, and the lines changed relative to a single parent, are painted with the
+        // color of the parent.
+        assert!(output.contains("\x1b[36m-\x1b[0m\x1b[31m \x1b[0m\x1b[36mfrom one"));
+        assert!(output.contains("\x1b[31m \x1b[0m\x1b[35m-\x1b[0m\x1b[35mfrom two"));
+        assert!(output.contains("\x1b[36m+\x1b[0m\x1b[32m \x1b[0m\x1b[36mkept from two"));
+        assert!(output.contains("\x1b[32m \x1b[0m\x1b[35m+\x1b[0m"));
+        assert!(output.contains("\x1b[36mfrom one"));
+        // Lines changed relative to both parents are painted with minus-style and plus-style.
+        assert!(output.contains("\x1b[36m+\x1b[0m\x1b[35m+\x1b[0m\x1b[32mresolution"));
+
+        let config = integration_test_utils::make_config_from_args(
+            &[&args[..], &["--merge-parent-palette", "blue yellow"]].concat(),
+        );
+        let output_with_palette = integration_test_utils::run_delta(GIT_DIFF_COMBINED, &config);
+        assert_eq!(
+            strip_ansi_codes(&output_with_palette),
+            strip_ansi_codes(&output)
+        );
+        assert!(output_with_palette.contains("\x1b[34m-\x1b[0m\x1b[31m \x1b[0m\x1b[34mfrom one"));
+        assert!(output_with_palette.contains("\x1b[31m \x1b[0m\x1b[33m-\x1b[0m\x1b[33mfrom two"));
+
+        let config = integration_test_utils::make_config_from_args(
+            &[&args[..], &["--merge-parent-palette", ""]].concat(),
+        );
+        let output = integration_test_utils::run_delta(GIT_DIFF_COMBINED, &config);
+        assert!(output.contains("\x1b[31m- from one"));
+        assert!(output.contains("\x1b[31m -from two"));
+        assert!(output.contains("\x1b[32m+ kept from two"));
+        assert!(output.contains("\x1b[32m++resolution"));
+    }
+
+    #[test]
+    fn test_file_path_reminder_interval() {
+        let input = "\
+diff --git a/src/a.rs b/src/a.rs
+index 1111111..2222222 100644
+--- a/src/a.rs
++++ b/src/a.rs
+@@ -1,6 +1,6 @@
+ line 0
+ line 1
+ line 2
+ line 3
+ line 4
+ line 5
+diff --git a/b.txt b/b.txt
+index 1111111..2222222 100644
+--- a/b.txt
++++ b/b.txt
+@@ -1,1 +1,1 @@
+-x
++y
+";
+        let config = integration_test_utils::make_config_from_args(&[
+            "--file-path-reminder-interval",
+            "4",
+            "--file-style",
+            "blue",
+        ]);
+        let output = integration_test_utils::run_delta(input, &config);
+        assert!(output.contains("\x1b[2;34msrc/a.rs\x1b[0m\n"));
+        let output = strip_ansi_codes(&output);
+        assert!(output.contains("line 3\nsrc/a.rs\nline 4\nline 5\n"));
+        // The line count restarts at each file.
+        assert_eq!(output.matches("src/a.rs").count(), 2);
+        assert_eq!(output.matches("b.txt").count(), 1);
+    }
+
     #[test]
     fn test_submodule_diff_log() {
         // See etc/examples/662-submodules
@@ -203,6 +450,40 @@ mod tests {
         assert_eq!(output, SUBMODULE_DIFF_LOG_EXPECTED_OUTPUT);
     }
 
+    #[test]
+    fn test_submodule_diff_log_styles() {
+        let config = integration_test_utils::make_config_from_args(&[
+            "--file-style",
+            "blue",
+            "--submodule-commit-added-style",
+            "green",
+            "--submodule-commit-removed-style",
+            "bold red",
+        ]);
+        let output = integration_test_utils::run_delta(SUBMODULE_DIFF_LOG, &config);
+        assert!(output.contains(
+            "\x1b[34mSubmodule submoduleB \x1b[1;31m0ffa700\x1b[0m\x1b[34m..\x1b[0m\x1b[32m0c8b00d\x1b[0m\x1b[34m:\x1b[0m"
+        ));
+        assert!(output.contains("\n\x1b[32m  > Submodule B stage change 3\x1b[0m\n"));
+        assert!(output.contains("\n\x1b[1;31m  < Submodule C extra change 2\x1b[0m\n"));
+
+        let output = integration_test_utils::run_delta(
+            "Submodule submoduleD 1111111...2222222 (commits not present)\n",
+            &config,
+        );
+        assert!(strip_ansi_codes(&output)
+            .starts_with("\nSubmodule submoduleD 1111111...2222222 (commits not present)\n"));
+    }
+
+    #[test]
+    fn test_submodule_diff_diff() {
+        // diff.submodule = diff
+        let config = integration_test_utils::make_config_from_args(&["--width", "40"]);
+        let output = integration_test_utils::run_delta(SUBMODULE_DIFF_DIFF, &config);
+        let output = strip_ansi_codes(&output);
+        assert_eq!(output, SUBMODULE_DIFF_DIFF_EXPECTED_OUTPUT);
+    }
+
     #[test]
     fn test_submodule_contains_untracked_content() {
         let config = integration_test_utils::make_config_from_args(&[]);
@@ -415,6 +696,13 @@ commit 94907c0f136f46dc46ffae2dc92dca9af7eb7c2e
         ansi_test_utils::assert_line_has_style(
             &output,
             2,
+            "Author: Dan Davison <dandavison7@gmail.com>     │",
+            "blue",
+            &config,
+        );
+        ansi_test_utils::assert_line_has_style(
+            &output,
+            4,
             "────────────────────────────────────────────────┘",
             "blue",
             &config,
@@ -424,6 +712,8 @@ commit 94907c0f136f46dc46ffae2dc92dca9af7eb7c2e
             "\
 ────────────────────────────────────────────────┐
 commit 94907c0f136f46dc46ffae2dc92dca9af7eb7c2e │
+Author: Dan Davison <dandavison7@gmail.com>     │
+Date:   Thu May 14 11:13:17 2020 -0400          │
 ────────────────────────────────────────────────┘
 "
         ));
@@ -449,6 +739,13 @@ commit 94907c0f136f46dc46ffae2dc92dca9af7eb7c2e │
         ansi_test_utils::assert_line_has_style(
             &output,
             2,
+            "Author: Dan Davison <dandavison7@gmail.com>     │",
+            "blue",
+            &config,
+        );
+        ansi_test_utils::assert_line_has_style(
+            &output,
+            4,
             "────────────────────────────────────────────────┴─",
             "blue",
             &config,
@@ -458,6 +755,8 @@ commit 94907c0f136f46dc46ffae2dc92dca9af7eb7c2e │
             "\
 ────────────────────────────────────────────────┐
 commit 94907c0f136f46dc46ffae2dc92dca9af7eb7c2e │
+Author: Dan Davison <dandavison7@gmail.com>     │
+Date:   Thu May 14 11:13:17 2020 -0400          │
 ────────────────────────────────────────────────┴─"
         ));
     }
@@ -482,6 +781,13 @@ commit 94907c0f136f46dc46ffae2dc92dca9af7eb7c2e │
         ansi_test_utils::assert_line_has_style(
             &output,
             2,
+            "Author: Dan Davison <dandavison7@gmail.com>     │",
+            "blue",
+            &config,
+        );
+        ansi_test_utils::assert_line_has_style(
+            &output,
+            4,
             "────────────────────────────────────────────────┘",
             "blue",
             &config,
@@ -491,6 +797,8 @@ commit 94907c0f136f46dc46ffae2dc92dca9af7eb7c2e │
             "\
 ────────────────────────────────────────────────┬─
 commit 94907c0f136f46dc46ffae2dc92dca9af7eb7c2e │
+Author: Dan Davison <dandavison7@gmail.com>     │
+Date:   Thu May 14 11:13:17 2020 -0400          │
 ────────────────────────────────────────────────┘
 "
         ));
@@ -514,10 +822,74 @@ commit 94907c0f136f46dc46ffae2dc92dca9af7eb7c2e │
             "\
 ────────────────────────────────────────────────┐
 commit 94907c0f136f46dc46ffae2dc92dca9af7eb7c2e │
+Author: Dan Davison <dandavison7@gmail.com>     │
+Date:   Thu May 14 11:13:17 2020 -0400          │
 ────────────────────────────────────────────────┴─"
         ));
     }
 
+    #[test]
+    fn test_commit_style_box_width_adapts_to_author_line() {
+        let config =
+            integration_test_utils::make_config_from_args(&["--commit-decoration-style", "box"]);
+        let input = GIT_DIFF_SINGLE_HUNK.replace(
+            "Author: Dan Davison <dandavison7@gmail.com>",
+            "Author: Dan Davison <dan.davison.with.a.long.address@example.com>",
+        );
+        let output = integration_test_utils::run_delta(&input, &config);
+        let output = strip_ansi_codes(&output);
+        assert!(output.contains(
+            "\
+──────────────────────────────────────────────────────────────────┐
+commit 94907c0f136f46dc46ffae2dc92dca9af7eb7c2e                   │
+Author: Dan Davison <dan.davison.with.a.long.address@example.com> │
+Date:   Thu May 14 11:13:17 2020 -0400                            │
+──────────────────────────────────────────────────────────────────┘
+"
+        ));
+    }
+
+    #[test]
+    fn test_commit_format() {
+        let config = integration_test_utils::make_config_from_args(&[
+            "--commit-format",
+            "{label} {hash:.8} — {author}, {date}",
+        ]);
+        let output = integration_test_utils::run_delta(GIT_DIFF_SINGLE_HUNK, &config);
+        let output = strip_ansi_codes(&output);
+        assert!(output.starts_with(
+            "\
+commit 94907c0f — Dan Davison <dandavison7@gmail.com>, Thu May 14 11:13:17 2020 -0400
+Author: Dan Davison <dandavison7@gmail.com>
+Date:   Thu May 14 11:13:17 2020 -0400
+"
+        ));
+
+        let config = integration_test_utils::make_config_from_args(&[
+            "--commit-format",
+            "{hash:.8} {author}",
+            "--hide-commit-metadata",
+        ]);
+        let output = integration_test_utils::run_delta(GIT_DIFF_SINGLE_HUNK, &config);
+        let output = strip_ansi_codes(&output);
+        assert!(output.starts_with("94907c0f Dan Davison <dandavison7@gmail.com>\n\n    rustfmt"));
+    }
+
+    #[test]
+    fn test_commit_format_without_author_and_date() {
+        let config = integration_test_utils::make_config_from_args(&[
+            "--commit-format",
+            "[{hash:.8}] {author}{date}",
+            "--hide-commit-metadata",
+        ]);
+        let input = GIT_DIFF_SINGLE_HUNK
+            .replace("Author: Dan Davison <dandavison7@gmail.com>\n", "")
+            .replace("Date:   Thu May 14 11:13:17 2020 -0400\n", "");
+        let output = integration_test_utils::run_delta(&input, &config);
+        let output = strip_ansi_codes(&output);
+        assert!(output.starts_with("[94907c0f] \n\n    rustfmt"));
+    }
+
     // TODO: test overline
 
     #[test]
@@ -944,7 +1316,7 @@ src/align.rs
             // If config.line_numbers is enabled,
             // we should remove line_numbers decoration while checking.
             let output_line = if config.line_numbers && n > 11 {
-                &output_lines[n][14..]
+                output_lines[n].split_once('│').unwrap().1
             } else {
                 output_lines[n]
             };
@@ -1443,6 +1815,29 @@ src/align.rs:71: impl<'a> Alignment<'a> { │
         }
     }
 
+    #[test]
+    fn test_empty_line_marker() {
+        let config = integration_test_utils::make_config_from_args(&[
+            "--minus-empty-line-marker",
+            "⏎",
+            "--minus-empty-line-marker-style",
+            "bold yellow",
+        ]);
+        let output = integration_test_utils::run_delta(DIFF_WITH_REMOVED_EMPTY_LINE, &config);
+        ansi_test_utils::assert_line_has_style(&output, 8, "⏎", "bold yellow", &config);
+
+        let config = integration_test_utils::make_config_from_args(&[
+            "--plus-empty-line-marker",
+            "⏎",
+            "--plus-empty-line-marker-style",
+            "bold yellow",
+            "--side-by-side",
+        ]);
+        let output = integration_test_utils::run_delta(DIFF_WITH_ADDED_EMPTY_LINE, &config);
+        let line = ansi::strip_ansi_codes(output.lines().nth(8).unwrap());
+        assert!(line.contains("│⏎"), "{}", line);
+    }
+
     #[test]
     fn test_whitespace_error() {
         let whitespace_error_style = "bold yellow red ul";
@@ -1462,6 +1857,297 @@ src/align.rs:71: impl<'a> Alignment<'a> { │
         );
     }
 
+    #[test]
+    fn test_output_is_flushed_when_a_hunk_is_complete() {
+        // The output written at each flush.
+        #[derive(Default)]
+        struct FlushedOutput {
+            buffer: Vec<u8>,
+            flushed: Vec<String>,
+        }
+        impl std::io::Write for FlushedOutput {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.buffer.extend_from_slice(buf);
+                Ok(buf.len())
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                let output = String::from_utf8(std::mem::take(&mut self.buffer)).unwrap();
+                self.flushed.push(strip_ansi_codes(&output));
+                Ok(())
+            }
+        }
+        let input = "\
+diff --git a/a.txt b/a.txt
+index 1111111..2222222 100644
+--- a/a.txt
++++ b/a.txt
+@@ -1,3 +1,3 @@
+ a
+-b
++c
+ d
+@@ -10,2 +10,2 @@
+-e
++f
+";
+        let flushed_output = |args: &[&str], input: &str| {
+            let config = integration_test_utils::make_config_from_args(args);
+            let mut writer = FlushedOutput::default();
+            crate::delta::delta(
+                bytelines::ByteLines::new(std::io::BufReader::new(input.as_bytes())),
+                &mut writer,
+                &config,
+            )
+            .unwrap();
+            writer
+                .flushed
+                .into_iter()
+                .filter(|output| !output.is_empty())
+                .collect::<Vec<_>>()
+        };
+        let flushed = flushed_output(&[], input);
+        // The file header, and then each hunk, is flushed once it is complete.
+        assert_eq!(flushed.len(), 3, "{:?}", flushed);
+        assert!(flushed[0].starts_with("\na.txt\n"));
+        assert!(flushed[1].ends_with("1: │\n───┘\na\nb\nc\nd\n"));
+        assert!(flushed[2].ends_with("10: │\n────┘\ne\nf\n"));
+
+        // Line numbers do not hold back the lines of a file.
+        let flushed = flushed_output(&["--line-numbers"], input);
+        assert_eq!(flushed.len(), 3, "{:?}", flushed);
+        assert!(flushed[1].ends_with("1: │\n───┘\n1⋮1│a\n2⋮ │b\n ⋮2│c\n3⋮3│d\n"));
+        assert!(flushed[2].ends_with("10: │\n────┘\n10⋮  │e\n  ⋮10│f\n"));
+
+        // Nor the lines of `git log` output without diffs.
+        let log = "\
+commit 94907c0f136f46dc46ffae2dc92dca9af7eb7c2e
+Author: Dan Davison <dandavison7@gmail.com>
+
+    First commit
+
+commit 6d1b7a2e4c3f5a8b9d0e1f2a3b4c5d6e7f8a9b0c
+Author: Dan Davison <dandavison7@gmail.com>
+
+    Second commit
+";
+        let flushed = flushed_output(&["--line-numbers"], log);
+        assert!(flushed.len() > 1, "{:?}", flushed);
+    }
+
+    #[test]
+    fn test_whitespace_error_scope() {
+        let diff = "\
+diff --git a/a.txt b/a.txt
+index 1111111..2222222 100644
+--- a/a.txt
++++ b/a.txt
+@@ -1,2 +1,2 @@
+ foo  \n-a
++b
+";
+        let config = integration_test_utils::make_config_from_args(&[
+            "--whitespace-error-style",
+            "bold yellow red ul",
+        ]);
+        let trailing_whitespace = config.whitespace_error_style.paint("  ").to_string();
+        let output = integration_test_utils::run_delta(diff, &config);
+        assert!(!output
+            .lines()
+            .nth(7)
+            .unwrap()
+            .contains(&trailing_whitespace));
+
+        let config = integration_test_utils::make_config_from_args(&[
+            "--whitespace-error-style",
+            "bold yellow red ul",
+            "--whitespace-error-scope",
+            "all",
+        ]);
+        let output = integration_test_utils::run_delta(diff, &config);
+        let line = output.lines().nth(7).unwrap();
+        assert!(line.contains(&trailing_whitespace));
+        assert_eq!(ansi::strip_ansi_codes(line), "foo  ");
+    }
+
+    #[test]
+    fn test_whitespace_errors_of_added_and_context_lines() {
+        let diff = "\
+diff --git a/a.txt b/a.txt
+index 1111111..2222222 100644
+--- a/a.txt
++++ b/a.txt
+@@ -1,2 +1,3 @@
+  \tfoo
+-a
++b  \n+ \tc
+";
+        let args = [
+            "--whitespace-error-style",
+            "bold yellow red ul",
+            "--tabs",
+            "2",
+        ];
+        let config = integration_test_utils::make_config_from_args(&args);
+        let paint = |s: &str| config.whitespace_error_style.paint(s).to_string();
+        let output = integration_test_utils::run_delta(diff, &config);
+        let lines: Vec<&str> = output.lines().skip(7).collect();
+        assert!(!lines[0].contains(&paint("   ")));
+        assert!(!lines[2].contains(&paint("  ")));
+        assert!(!lines[3].contains(&paint("   ")));
+        assert_eq!(ansi::strip_ansi_codes(lines[3]), "   c");
+
+        let config = integration_test_utils::make_config_from_args(
+            &[&args[..], &["--whitespace-error-scope", "all"]].concat(),
+        );
+        let output = integration_test_utils::run_delta(diff, &config);
+        let lines: Vec<&str> = output.lines().skip(7).collect();
+        assert!(lines[0].starts_with(&paint("   ")));
+        assert_eq!(ansi::strip_ansi_codes(lines[0]), "   foo");
+        assert!(lines[2].contains(&paint("  ")));
+        assert!(lines[3].starts_with(&paint("   ")));
+        assert_eq!(ansi::strip_ansi_codes(lines[3]), "   c");
+    }
+
+    #[test]
+    fn test_dim_unchanged_words() {
+        let diff = "\
+diff --git a/a.txt b/a.txt
+index 1111111..2222222 100644
+--- a/a.txt
++++ b/a.txt
+@@ -1 +1 @@
+-hello old world
++hello new world
+";
+        let args = [
+            "--minus-style",
+            "red",
+            "--minus-emph-style",
+            "bold red",
+            "--plus-style",
+            "green",
+            "--plus-emph-style",
+            "bold green",
+        ];
+        let config = integration_test_utils::make_config_from_args(&args);
+        let output = integration_test_utils::run_delta(diff, &config);
+        assert!(output.contains("\x1b[31mhello \x1b[1mold\x1b[0m\x1b[31m world\x1b[0m"));
+
+        let config = integration_test_utils::make_config_from_args(
+            &[&args[..], &["--dim-unchanged-words"]].concat(),
+        );
+        let output = integration_test_utils::run_delta(diff, &config);
+        assert!(
+            output.contains("\x1b[2;31mhello \x1b[0m\x1b[1;31mold\x1b[0m\x1b[2;31m world\x1b[0m")
+        );
+        assert!(
+            output.contains("\x1b[2;32mhello \x1b[0m\x1b[1;32mnew\x1b[0m\x1b[2;32m world\x1b[0m")
+        );
+    }
+
+    #[test]
+    fn test_change_magnitude_shading() {
+        let diff = "\
+diff --git a/a.txt b/a.txt
+index 1111111..2222222 100644
+--- a/a.txt
++++ b/a.txt
+@@ -1,2 +1,2 @@
+-abcd efgh ijkl mnop
+-abcd
++abcd efgh ijkl mnoq
++wxyz
+";
+        let config = integration_test_utils::make_config_from_args(&[
+            "--minus-style",
+            "normal \"#c80000\"",
+            "--minus-emph-style",
+            "normal \"#ff0000\"",
+            "--true-color",
+            "always",
+            "--syntax-theme",
+            "none",
+            "--change-magnitude-shading",
+            "--max-line-distance",
+            "1.0",
+        ]);
+        let output = integration_test_utils::run_delta(diff, &config);
+        // 4 of 19 characters are emphasized: the background keeps 0.4 + 0.6 * 4 / 19 of its
+        // intensity, also where it is extended to the terminal width.
+        assert!(output.contains(
+            "\x1b[48;2;105;0;0mabcd efgh ijkl \x1b[48;2;255;0;0mmnop\x1b[0m\x1b[48;2;105;0;0m\x1b[0K"
+        ));
+        // The rewritten line keeps the full background.
+        assert!(output.contains("\x1b[48;2;255;0;0mabcd\x1b[0m\x1b[48;2;200;0;0m\x1b[0K"));
+    }
+
+    #[test]
+    fn test_word_diff_regex_overrides() {
+        let diff = "\
+diff --git a/a.css b/a.css
+index 1111111..2222222 100644
+--- a/a.css
++++ b/a.css
+@@ -1,3 +1,3 @@
+ p {
+-  color: var(--my-var);
++  color: var(--new-var);
+ }
+";
+        let args = ["--minus-emph-style", "bold red", "--syntax-theme", "none"];
+        let config = integration_test_utils::make_config_from_args(&args);
+        let output = integration_test_utils::run_delta(diff, &config);
+        assert!(output.contains("\x1b[1;31mmy\x1b[0m"));
+
+        let config = integration_test_utils::make_config_from_args(
+            &[
+                &args[..],
+                &[
+                    "--word-diff-regex-overrides",
+                    r"css=[#\w-]+, lisp=[()]|[^\s()]+",
+                ],
+            ]
+            .concat(),
+        );
+        assert_eq!(config.word_diff_regex_overrides.len(), 2);
+        assert_eq!(config.word_diff_regex_overrides[1].0, "lisp");
+        let output = integration_test_utils::run_delta(diff, &config);
+        assert!(output.contains("\x1b[1;31m--my-var\x1b[0m"));
+    }
+
+    #[test]
+    fn test_raw_file_glob() {
+        let diff = "\
+diff --git a/Cargo.lock b/Cargo.lock
+index 1111111..2222222 100644
+--- a/Cargo.lock
++++ b/Cargo.lock
+@@ -1 +1 @@
+-let x = 1;
++let x = 2;
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1 +1 @@
+-let x = 1;
++let x = 2;
+";
+        let config = integration_test_utils::make_config_from_args(&[
+            "--raw-file-glob",
+            "**/*.lock",
+            "--plus-style",
+            "syntax green",
+        ]);
+        let output = integration_test_utils::run_delta(diff, &config);
+        let raw_plus_line = config.plus_style.paint("let x = 2;").to_string();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(ansi::strip_ansi_codes(lines[8]), "let x = 2;");
+        assert!(lines[8].starts_with(&raw_plus_line), "{:?}", lines[8]);
+        assert_eq!(ansi::strip_ansi_codes(lines[17]), "let x = 2;");
+        assert!(!lines[17].starts_with(&raw_plus_line), "{:?}", lines[17]);
+    }
+
     #[test]
     fn test_added_empty_line_is_not_whitespace_error() {
         let plus_style = "bold yellow red ul";
@@ -1539,34 +2225,134 @@ src/align.rs:71: impl<'a> Alignment<'a> { │
         ));
     }
 
+    #[test]
+    fn test_plus_minus_symbols() {
+        DeltaTest::with_args(&["--minus-symbol", "▍", "--plus-symbol", "▍"])
+            .with_input(DIFF_WITH_REMOVED_AND_ADDED_LINES)
+            .expect_contains("\n▍int a = 1;\n▍int a = 2;\n int b = 3;\n");
+        // The symbols are padded to the same width, and an empty symbol is a blank prefix.
+        DeltaTest::with_args(&[
+            "--keep-plus-minus-markers",
+            "--plus-symbol",
+            "",
+            "--minus-symbol",
+            "<<",
+        ])
+        .with_input(DIFF_WITH_REMOVED_AND_ADDED_LINES)
+        .expect_contains("\n<<int a = 1;\n  int a = 2;\n  int b = 3;\n");
+        DeltaTest::with_args(&["--side-by-side", "--width", "60", "--plus-symbol", "▍▍"])
+            .with_input(DIFF_WITH_REMOVED_AND_ADDED_LINES)
+            .expect_contains("│  int a = 1;")
+            .expect_contains("│▍▍int a = 2;");
+        // The markers of the input are kept under --color-only.
+        let config =
+            integration_test_utils::make_config_from_args(&["--color-only", "--plus-symbol", "▍"]);
+        let output = integration_test_utils::run_delta(DIFF_WITH_REMOVED_AND_ADDED_LINES, &config);
+        assert_eq!(strip_ansi_codes(&output), DIFF_WITH_REMOVED_AND_ADDED_LINES);
+    }
+
+    const DIFF_WITH_REMOVED_AND_ADDED_LINES: &str = "\
+diff --git a/example.c b/example.c
+index 386f291a..22666f79 100644
+--- a/example.c
++++ b/example.c
+@@ -1,2 +1,2 @@
+-int a = 1;
++int a = 2;
+ int b = 3;
+";
+
     #[test]
     fn test_file_mode_change_with_rename() {
         let config = integration_test_utils::make_config_from_args(&["--right-arrow=->"]);
         let output =
             integration_test_utils::run_delta(GIT_DIFF_FILE_MODE_CHANGE_WITH_RENAME, &config);
         let output = strip_ansi_codes(&output);
-        assert!(output.contains("renamed: old-longer-name -> shorter-name (mode +x)"));
+        assert!(output.contains("\nmode changed: 644 -> 755 (+x)\n"));
     }
 
     #[test]
     fn test_file_mode_change_gain_executable_bit() {
         DeltaTest::with_args(&[])
             .with_input(GIT_DIFF_FILE_MODE_CHANGE_GAIN_EXECUTABLE_BIT)
-            .expect_contains("src/delta.rs (mode +x)");
+            .expect_contains("\nmode changed: 644 ⟶   755 (+x)\n");
     }
 
     #[test]
     fn test_file_mode_change_lose_executable_bit() {
         DeltaTest::with_args(&[])
             .with_input(GIT_DIFF_FILE_MODE_CHANGE_LOSE_EXECUTABLE_BIT)
-            .expect_contains("src/delta.rs (mode -x)");
+            .expect_contains("\nmode changed: 755 ⟶   644 (-x)\n");
     }
 
     #[test]
     fn test_file_mode_change_unexpected_bits() {
         DeltaTest::with_args(&["--navigate", "--right-arrow=->"])
             .with_input(GIT_DIFF_FILE_MODE_CHANGE_UNEXPECTED_BITS)
-            .expect_contains("Δ src/delta.rs (mode 100700 -> 100644)");
+            .expect_contains("Δ src/delta.rs\n")
+            .expect_contains("\nmode changed: 700 -> 644 (-x)\n");
+    }
+
+    #[test]
+    fn test_file_mode_change_style() {
+        DeltaTest::with_args(&["--mode-change-style", "red"])
+            .with_input(GIT_DIFF_FILE_MODE_CHANGE_GAIN_EXECUTABLE_BIT)
+            .expect_raw_contains(
+                &ansi_term::Color::Red
+                    .paint("mode changed: 644 ⟶   755 (+x)")
+                    .to_string(),
+            );
+        let config =
+            integration_test_utils::make_config_from_args(&["--mode-change-style", "omit"]);
+        let output = integration_test_utils::run_delta(
+            GIT_DIFF_FILE_MODE_CHANGE_GAIN_EXECUTABLE_BIT,
+            &config,
+        );
+        let output = strip_ansi_codes(&output);
+        assert!(output.contains("src/delta.rs"));
+        assert!(!output.contains("mode"));
+    }
+
+    #[test]
+    fn test_keep_mode_lines() {
+        DeltaTest::with_args(&["--keep-mode-lines", "--right-arrow=->"])
+            .with_input(GIT_DIFF_FILE_MODE_CHANGE_WITH_DIFF)
+            .expect_contains("\nold mode 100644\nnew mode 100755\n")
+            .expect_contains("\nmode changed: 644 -> 755 (+x)\n");
+    }
+
+    #[test]
+    fn test_keep_index_line() {
+        let output = DeltaTest::with_args(&[])
+            .with_input(GIT_DIFF_FILE_MODE_CHANGE_WITH_DIFF)
+            .expect_contains("\nsrc/script\n");
+        assert!(!output.output.contains("index"));
+        let output = DeltaTest::with_args(&[
+            "--keep-index-line",
+            "--index-line-style",
+            "blue",
+            "--index-line-mode-style",
+            "bold red",
+        ])
+        .with_input(GIT_DIFF_FILE_MODE_CHANGE_WITH_DIFF)
+        .expect_contains("\nindex d00491f..0cfbf08 100644\n\nsrc/script\n");
+        assert!(output
+            .raw_output
+            .contains("\x1b[34md00491f\x1b[0m\x1b[34m..\x1b[0m\x1b[34m0cfbf08\x1b[0m\x1b[34m \x1b[0m\x1b[1;31m100644\x1b[0m"));
+    }
+
+    #[test]
+    fn test_new_executable_file() {
+        let config = integration_test_utils::make_config_from_args(&[]);
+        for input in [
+            GIT_DIFF_NEW_EXECUTABLE_FILE,
+            GIT_DIFF_NEW_EMPTY_EXECUTABLE_FILE,
+        ] {
+            let output = strip_ansi_codes(&integration_test_utils::run_delta(input, &config));
+            assert!(output.contains("added: src/script\n"));
+            assert!(output.contains("\nnew file mode: 755 (+x)\n"));
+            assert!(!output.contains("new file mode 100755"));
+        }
     }
 
     #[test]
@@ -1588,9 +2374,10 @@ src/align.rs:71: impl<'a> Alignment<'a> { │
     fn test_file_mode_change_with_diff() {
         DeltaTest::with_args(&["--navigate", "--keep-plus-minus-markers"])
             .with_input(GIT_DIFF_FILE_MODE_CHANGE_WITH_DIFF)
-            .expect_contains("Δ src/script (mode +x)")
+            .expect_contains("Δ src/script\n")
+            .expect_contains("\nmode changed: 644 ⟶   755 (+x)\n")
             .expect_after_skip(
-                5,
+                6,
                 "
                 ─────┐
                 • 1: │
@@ -1619,6 +2406,38 @@ src/align.rs:71: impl<'a> Alignment<'a> { │
                 );
     }
 
+    #[test]
+    fn test_hyperlinks_commit_link_format_abbreviated_commit() {
+        let input = GIT_DIFF_SINGLE_HUNK.replacen(
+            "commit 94907c0f136f46dc46ffae2dc92dca9af7eb7c2e",
+            "commit 94907c0 (HEAD -> main)",
+            1,
+        );
+        let args = [
+            "--commit-style",
+            "blue",
+            "--hyperlinks-commit-link-format",
+            "https://github.com/dandavison/delta/commit/{commit}",
+        ];
+        DeltaTest::with_args(&[&args[..], &["--hyperlinks"]].concat())
+            .with_input(&input)
+            .expect_raw_contains(
+                "\x1b]8;;https://github.com/dandavison/delta/commit/94907c0\x1b\\94907c0\x1b]8;;\x1b\\",
+            );
+        // Without --hyperlinks the format is not used.
+        let config = integration_test_utils::make_config_from_args(&args);
+        let output = integration_test_utils::run_delta(&input, &config);
+        assert!(!output.contains("https://github.com"));
+    }
+
+    #[test]
+    fn test_quoted_non_ascii_filenames() {
+        DeltaTest::with_args(&[])
+            .with_input(GIT_DIFF_QUOTED_NON_ASCII_FILENAMES)
+            .expect_contains("\nrenamed: 日本語.rs ⟶   新.rs\n")
+            .expect_contains("\nsrc/q\"t\tx.rs\n");
+    }
+
     #[test]
     fn test_filenames_with_spaces() {
         DeltaTest::with_args(&[])
@@ -1626,6 +2445,39 @@ src/align.rs:71: impl<'a> Alignment<'a> { │
             .expect_contains("a b ⟶   c d\n");
     }
 
+    const GIT_DIFF_FILE_LABEL_STATS: &str = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,3 +1,3 @@
+ mod a;
+-mod b;
++mod c;
+ mod d;
+@@ -20,2 +20,3 @@ fn f() {
+ fn g() {}
++fn h() {}
+ fn i() {}
+diff --git a/src/new.rs b/src/new.rs
+new file mode 100644
+index 0000000..3333333
+--- /dev/null
++++ b/src/new.rs
+@@ -0,0 +1,2 @@
++fn new() {}
++fn newer() {}
+diff --git a/src/old.rs b/src/old.rs
+deleted file mode 100644
+index 4444444..0000000
+--- a/src/old.rs
++++ /dev/null
+@@ -1,3 +0,0 @@
+-fn old() {}
+-
+-fn older() {}
+";
+
     const GIT_DIFF_SINGLE_HUNK: &str = "\
 commit 94907c0f136f46dc46ffae2dc92dca9af7eb7c2e
 Author: Dan Davison <dandavison7@gmail.com>
@@ -1985,6 +2837,51 @@ Submodule submoduleC 9f3b744...e04f848:
   < Submodule C extra change 1
 ";
 
+    const SUBMODULE_DIFF_DIFF: &str = "\
+Submodule sub 7a26f0f..30a23d7:
+diff --git a/sub/f.txt b/sub/f.txt
+index 422c2b7..7be73ce 100644
+--- a/sub/f.txt
++++ b/sub/f.txt
+@@ -1,2 +1,3 @@
+ a
+-b
++B
++c
+diff --git a/top.txt b/top.txt
+index 587be6b..975fbec 100644
+--- a/top.txt
++++ b/top.txt
+@@ -1 +1 @@
+-x
++y
+";
+
+    const SUBMODULE_DIFF_DIFF_EXPECTED_OUTPUT: &str = "
+Submodule sub 7a26f0f..30a23d7:
+────────────────────────────────────────
+│
+│ sub/f.txt
+│ ──────────────────────────────────────
+│
+│ ───┐
+│ 1: │
+│ ───┘
+│ a
+│ b
+│ B
+│ c
+
+top.txt
+────────────────────────────────────────
+
+───┐
+1: │
+───┘
+x
+y
+";
+
     const SUBMODULE_CONTAINS_UNTRACKED_CONTENT_INPUT: &str = "\
 --- a
 +++ b
@@ -2129,6 +3026,20 @@ index cba6064..ba1a4de 100644
              .ok()
 ";
 
+    const GIT_DIFF_COMBINED: &str = "\
+diff --cc a.txt
+index 1111111,2222222..3333333
+--- a/a.txt
++++ b/a.txt
+@@@ -1,3 -1,3 +1,4 @@@
+  common
+- from one
+ -from two
++ kept from two
+ +kept from one
+++resolution
+";
+
     const DIFF_WITH_MERGE_CONFLICT: &str = r#"
 diff --cc Makefile
 index 759070d,3daf9eb..0000000
@@ -2377,6 +3288,36 @@ index d00491f..0cfbf08 100644
 +#!/bin/bash
 ";
 
+    const GIT_DIFF_NEW_EXECUTABLE_FILE: &str = "
+diff --git a/src/script b/src/script
+new file mode 100755
+index 0000000..0cfbf08
+--- /dev/null
++++ b/src/script
+@@ -0,0 +1 @@
++#!/bin/bash
+";
+
+    const GIT_DIFF_NEW_EMPTY_EXECUTABLE_FILE: &str = "
+diff --git a/src/script b/src/script
+new file mode 100755
+index 0000000..e69de29
+";
+
+    const GIT_DIFF_QUOTED_NON_ASCII_FILENAMES: &str = r#"
+diff --git "a/\346\227\245\346\234\254\350\252\236.rs" "b/\346\226\260.rs"
+similarity index 100%
+rename from "\346\227\245\346\234\254\350\252\236.rs"
+rename to "\346\226\260.rs"
+diff --git "a/src/q\"t\tx.rs" "b/src/q\"t\tx.rs"
+index 7898192..6178079 100644
+--- "a/src/q\"t\tx.rs"
++++ "b/src/q\"t\tx.rs"
+@@ -1 +1 @@
+-a
++b
+"#;
+
     const GIT_DIFF_NO_INDEX_FILENAMES_WITH_SPACES: &str = "
 diff --git a/a b b/c d
 index d00491f..0cfbf08 100644
@@ -1,5 +1,5 @@
 pub mod assets;
 pub mod dirs;
-mod less;
+pub mod less;
 pub mod output;
 pub mod terminal;
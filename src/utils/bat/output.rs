@@ -1,10 +1,12 @@
 // https://github.com/sharkdp/bat a1b9334a44a2c652f52dddaa83dbacba57372468
 // src/output.rs
 // See src/utils/bat/LICENSE
-use std::ffi::OsString;
-use std::io::{self, Write};
-use std::path::PathBuf;
+use std::ffi::{OsStr, OsString};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Stdio};
+use std::sync::Once;
 
 use super::less::retrieve_less_version;
 
@@ -12,6 +14,7 @@ use crate::config;
 use crate::env::DeltaEnv;
 use crate::fatal;
 use crate::features::navigate;
+use crate::utils::internal_pager::InternalPager;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[allow(dead_code)]
@@ -19,12 +22,69 @@ pub enum PagingMode {
     Always,
     QuitIfOneScreen,
     Never,
+    Internal,
 }
 use crate::errors::*;
 
+/// A behavior of less selected by --pager-less-options.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LessOption {
+    Mouse(bool),
+    Wrap(bool),
+    QuitIfOneScreen(bool),
+}
+
+/// Parse a comma-separated list of the names of less behaviors, each of which may be prefixed by
+/// "no-" to turn it off, e.g. "mouse,no-wrap".
+pub fn parse_less_options(options: &str) -> std::result::Result<Vec<LessOption>, String> {
+    options
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            let (on, toggle) = match name.strip_prefix("no-") {
+                Some(toggle) => (false, toggle),
+                None => (true, name),
+            };
+            match toggle {
+                "mouse" => Ok(LessOption::Mouse(on)),
+                "wrap" => Ok(LessOption::Wrap(on)),
+                "quit-if-one-screen" => Ok(LessOption::QuitIfOneScreen(on)),
+                _ => Err(format!(
+                    "Invalid value for pager-less-options: {}. \
+                     Expected \"mouse\", \"wrap\", or \"quit-if-one-screen\", \
+                     optionally prefixed by \"no-\".",
+                    name
+                )),
+            }
+        })
+        .collect()
+}
+
+// The arguments to less implementing `options`. They follow any other arguments, so that they
+// take precedence: "-+" resets an option of less to its default. quit-if-one-screen is applied
+// as --pager-quit-if-one-screen, see Config::pager_quit_if_one_screen.
+fn less_option_args(options: &[LessOption]) -> Vec<&'static str> {
+    options
+        .iter()
+        .filter_map(|option| match option {
+            LessOption::Mouse(true) => Some("--mouse"),
+            // Reset --mouse, which may be given in $LESS.
+            LessOption::Mouse(false) => Some("-+--mouse"),
+            LessOption::Wrap(true) => Some("-+S"),
+            LessOption::Wrap(false) => Some("--chop-long-lines"),
+            LessOption::QuitIfOneScreen(_) => None,
+        })
+        .collect()
+}
+
+static WARN_PAGER_IS_NOT_LESS: Once = Once::new();
+
 pub enum OutputType {
     Pager(Child),
+    InternalPager(InternalPager),
     Stdout(io::Stdout),
+    File(BufWriter<File>),
 }
 
 impl OutputType {
@@ -38,90 +98,254 @@ impl OutputType {
         Ok(match mode {
             Always => OutputType::try_pager(env, false, pager, config)?,
             QuitIfOneScreen => OutputType::try_pager(env, true, pager, config)?,
-            _ => OutputType::stdout(),
+            Internal => {
+                OutputType::internal_pager(config.pager_quit_if_one_screen == Some(true), config)
+                    .unwrap_or_else(OutputType::stdout)
+            }
+            Never => OutputType::stdout(),
         })
     }
 
-    /// Try to launch the pager. Fall back to stdout in case of errors.
+    /// Try to launch the pager, and then each of --pager-fallbacks. Fall back to the internal pager
+    /// if none of them can be launched, or to stdout if that is not a terminal.
     fn try_pager(
         env: &DeltaEnv,
         quit_if_one_screen: bool,
         pager_from_config: Option<String>,
         config: &config::Config,
     ) -> Result<Self> {
-        let mut replace_arguments_to_less = false;
-
-        let pager_from_env = match env.pagers.clone() {
-            (Some(delta_pager), _, _) => Some(delta_pager),
-            (_, Some(bat_pager), _) => Some(bat_pager),
-            (_, _, Some(pager)) => {
-                // less needs to be called with the '-R' option in order to properly interpret ANSI
-                // color sequences. If someone has set PAGER="less -F", we therefore need to
-                // overwrite the arguments and add '-R'.
-                // We only do this for PAGER, since it is used in other contexts.
-                replace_arguments_to_less = true;
-                Some(pager)
-            }
-            _ => None,
-        };
+        let (pager, replace_arguments_to_less) = get_pager_command(env, pager_from_config);
 
-        if pager_from_config.is_some() {
-            replace_arguments_to_less = false;
-        }
+        let pager = match PagerCommand::parse(&pager)? {
+            Some(pager) => pager,
+            None => return Ok(OutputType::stdout()),
+        };
 
-        let pager = pager_from_config
-            .or(pager_from_env)
-            .unwrap_or_else(|| String::from("less"));
-
-        let pagerflags =
-            shell_words::split(&pager).chain_err(|| "Could not parse pager command.")?;
-
-        Ok(match pagerflags.split_first() {
-            Some((pager_name, args)) => {
-                let pager_path = PathBuf::from(pager_name);
-
-                let is_less = pager_path.file_stem() == Some(&OsString::from("less"));
-
-                let process = if is_less {
-                    _make_process_from_less_path(
-                        pager_path,
-                        args,
-                        replace_arguments_to_less,
-                        quit_if_one_screen,
-                        config,
-                    )
-                } else {
-                    _make_process_from_pager_path(pager_path, args)
-                };
-                if let Some(mut process) = process {
-                    process
-                        .stdin(Stdio::piped())
-                        .spawn()
-                        .map(OutputType::Pager)
-                        .unwrap_or_else(|_| OutputType::stdout())
-                } else {
-                    OutputType::stdout()
+        let mut error = None;
+        for (i, pager) in get_pager_commands(pager, &config.pager_fallbacks)
+            .iter()
+            .enumerate()
+        {
+            let replace_arguments_to_less = i == 0 && replace_arguments_to_less;
+            let child =
+                make_pager_process(pager, replace_arguments_to_less, quit_if_one_screen, config)
+                    .and_then(|mut process| {
+                        process.stdin(Stdio::piped()).spawn().map_err(|err| {
+                            format!("Could not start the pager {}: {}", pager.program, err)
+                        })
+                    });
+            match child {
+                Ok(child) => {
+                    if let Some(error) = error {
+                        eprintln!("{}. Using the pager {} instead.", error, pager.program);
+                    }
+                    return Ok(OutputType::Pager(child));
+                }
+                Err(err) => {
+                    error.get_or_insert(err);
                 }
             }
-            None => OutputType::stdout(),
-        })
+        }
+        if let Some(error) = error {
+            if let Some(internal_pager) = OutputType::internal_pager(quit_if_one_screen, config) {
+                eprintln!("{}. Using delta's internal pager instead.", error);
+                return Ok(internal_pager);
+            }
+            eprintln!("{}. Not using a pager.", error);
+        }
+        Ok(OutputType::stdout())
     }
 
     fn stdout() -> Self {
         OutputType::Stdout(io::stdout())
     }
 
+    /// Return None if stdout is not a terminal.
+    fn internal_pager(quit_if_one_screen: bool, config: &config::Config) -> Option<Self> {
+        let navigate_regex = config.navigate_regex.as_deref().filter(|_| config.navigate);
+        InternalPager::start(navigate_regex, quit_if_one_screen).map(OutputType::InternalPager)
+    }
+
+    /// Write to the file at `path`, which is created or truncated.
+    pub fn file(path: &str) -> Result<Self> {
+        let file = File::create(path)
+            .map_err(|err| format!("Could not create output file {}: {}", path, err))?;
+        Ok(OutputType::File(BufWriter::new(file)))
+    }
+
     pub fn handle(&mut self) -> Result<&mut dyn Write> {
         Ok(match *self {
             OutputType::Pager(ref mut command) => command
                 .stdin
                 .as_mut()
                 .chain_err(|| "Could not open stdin for pager")?,
+            OutputType::InternalPager(ref mut handle) => handle,
             OutputType::Stdout(ref mut handle) => handle,
+            OutputType::File(ref mut handle) => handle,
         })
     }
 }
 
+/// Return the pager command, from --pager or the environment, and whether the arguments of a less
+/// command are replaced by delta's own.
+pub fn get_pager_command(env: &DeltaEnv, pager_from_config: Option<String>) -> (String, bool) {
+    let mut replace_arguments_to_less = false;
+
+    let pager_from_env = match env.pagers.clone() {
+        (Some(delta_pager), _, _) => Some(delta_pager),
+        (_, Some(bat_pager), _) => Some(bat_pager),
+        (_, _, Some(pager)) => {
+            // less needs to be called with the '-R' option in order to properly interpret ANSI
+            // color sequences. If someone has set PAGER="less -F", we therefore need to
+            // overwrite the arguments and add '-R'.
+            // We only do this for PAGER, since it is used in other contexts.
+            replace_arguments_to_less = true;
+            Some(pager)
+        }
+        _ => None,
+    };
+
+    if pager_from_config.is_some() {
+        replace_arguments_to_less = false;
+    }
+
+    let pager = pager_from_config
+        .or(pager_from_env)
+        .unwrap_or_else(|| String::from("less"));
+    (pager, replace_arguments_to_less)
+}
+
+/// A pager command such as `less -R`, split into words as a shell would, respecting quotes and
+/// escapes. Environment variables may be assigned for the pager by a leading `env VAR=value` or
+/// `VAR=value`, e.g. `env LESS= less -R`.
+#[derive(Debug, PartialEq)]
+struct PagerCommand {
+    env: Vec<(String, String)>,
+    program: String,
+    args: Vec<String>,
+}
+
+impl PagerCommand {
+    /// Return None if `pager` has no program, e.g. if it is empty.
+    fn parse(pager: &str) -> Result<Option<Self>> {
+        let mut words = shell_words::split(pager)
+            .chain_err(|| format!("Could not parse pager command: {}", pager))?
+            .into_iter()
+            .peekable();
+        if words.peek().map(String::as_str) == Some("env") {
+            words.next();
+        }
+        let mut env = Vec::new();
+        while let Some(assignment) = words.peek().and_then(|word| parse_env_assignment(word)) {
+            env.push(assignment);
+            words.next();
+        }
+        Ok(words.next().map(|program| PagerCommand {
+            env,
+            program,
+            args: words.collect(),
+        }))
+    }
+
+    // The name of the program, without directory or extension.
+    fn program_name(&self) -> Option<&OsStr> {
+        Path::new(&self.program).file_stem()
+    }
+}
+
+// E.g. "LESS=-R", as a variable name and value.
+fn parse_env_assignment(word: &str) -> Option<(String, String)> {
+    let (name, value) = word.split_once('=')?;
+    let is_name = !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    is_name.then(|| (name.to_string(), value.to_string()))
+}
+
+/// Return the pager command followed by those of `fallbacks` which run a program different from
+/// that of the pager and of the preceding fallbacks.
+fn get_pager_commands(pager: PagerCommand, fallbacks: &[String]) -> Vec<PagerCommand> {
+    let mut commands = vec![pager];
+    for fallback in fallbacks {
+        if let Ok(Some(fallback)) = PagerCommand::parse(fallback) {
+            if commands
+                .iter()
+                .all(|command| command.program_name() != fallback.program_name())
+            {
+                commands.push(fallback);
+            }
+        }
+    }
+    commands
+}
+
+/// Return the command line which would be run for the pager, quoted as for a shell, with the path
+/// of the program found in PATH and preceded by the environment variables assigned for it, e.g.
+/// "LESS= /usr/bin/less --RAW-CONTROL-CHARS". Return None if no pager would be used.
+pub fn get_pager_command_line(
+    env: &DeltaEnv,
+    pager_from_config: Option<String>,
+    quit_if_one_screen: bool,
+    config: &config::Config,
+) -> std::result::Result<Option<String>, String> {
+    let (pager, replace_arguments_to_less) = get_pager_command(env, pager_from_config);
+    let pager = match PagerCommand::parse(&pager).map_err(|err| err.to_string())? {
+        Some(pager) => pager,
+        None => return Ok(None),
+    };
+    let process = make_pager_process(
+        &pager,
+        replace_arguments_to_less,
+        quit_if_one_screen,
+        config,
+    )?;
+    let argv = std::iter::once(process.get_program())
+        .chain(process.get_args())
+        .map(|arg| arg.to_string_lossy());
+    let words = pager
+        .env
+        .iter()
+        .map(|(name, value)| format!("{}={}", name, shell_words::quote(value)))
+        .chain(std::iter::once(shell_words::join(argv)));
+    Ok(Some(words.collect::<Vec<_>>().join(" ")))
+}
+
+fn make_pager_process(
+    pager: &PagerCommand,
+    replace_arguments_to_less: bool,
+    quit_if_one_screen: bool,
+    config: &config::Config,
+) -> std::result::Result<Command, String> {
+    let pager_path = PathBuf::from(&pager.program);
+
+    let is_less = pager_path.file_stem() == Some(&OsString::from("less"));
+
+    let process = if is_less {
+        _make_process_from_less_path(
+            pager_path,
+            &pager.args,
+            replace_arguments_to_less,
+            quit_if_one_screen,
+            config,
+        )
+    } else {
+        if !config.pager_less_options.is_empty() {
+            WARN_PAGER_IS_NOT_LESS.call_once(|| {
+                eprintln!(
+                    "Ignoring pager-less-options: the pager {} is not less.",
+                    pager.program
+                )
+            });
+        }
+        _make_process_from_pager_path(pager_path, &pager.args)
+    };
+    let mut process =
+        process.ok_or_else(|| format!("Pager command not found in PATH: {}", pager.program))?;
+    // These follow delta's own settings of environment variables for less, so take precedence.
+    process.envs(pager.env.iter().map(|(name, value)| (name, value)));
+    Ok(process)
+}
+
 fn _make_process_from_less_path(
     less_path: PathBuf,
     args: &[String],
@@ -129,34 +353,41 @@ fn _make_process_from_less_path(
     quit_if_one_screen: bool,
     config: &config::Config,
 ) -> Option<Command> {
-    if let Ok(less_path) = grep_cli::resolve_binary(less_path) {
+    if let Some(less_path) = resolve_program(less_path) {
         let mut p = Command::new(&less_path);
         if args.is_empty() || replace_arguments_to_less {
             p.args(vec!["--RAW-CONTROL-CHARS"]);
 
-            // Passing '--no-init' fixes a bug with '--quit-if-one-screen' in older
-            // versions of 'less'. Unfortunately, it also breaks mouse-wheel support.
-            //
-            // See: http://www.greenwoodsoftware.com/less/news.530.html
-            //
-            // For newer versions (530 or 558 on Windows), we omit '--no-init' as it
-            // is not needed anymore.
-            match retrieve_less_version() {
-                None => {
+            if config.pager_quit_if_one_screen.is_none() {
+                if less_needs_no_init() {
                     p.arg("--no-init");
                 }
-                Some(version) if (version < 530 || (cfg!(windows) && version < 558)) => {
-                    p.arg("--no-init");
+                if quit_if_one_screen {
+                    p.arg("--quit-if-one-screen");
                 }
-                _ => {}
-            }
-
-            if quit_if_one_screen {
-                p.arg("--quit-if-one-screen");
             }
+        } else if config.pager_quit_if_one_screen == Some(false) {
+            p.args(
+                args.iter()
+                    .filter(|arg| !matches!(arg.as_str(), "-F" | "--quit-if-one-screen")),
+            );
         } else {
             p.args(args);
         }
+        match config.pager_quit_if_one_screen {
+            Some(true) => {
+                if less_needs_no_init() {
+                    p.arg("--no-init");
+                }
+                p.arg("--quit-if-one-screen");
+            }
+            // Also overrides a -F in the LESS environment variable.
+            Some(false) => {
+                p.arg("-+F");
+            }
+            None => {}
+        }
+        p.args(less_option_args(&config.pager_less_options));
         p.env("LESSCHARSET", "UTF-8");
         p.env("LESSANSIENDCHARS", "mK");
         if config.navigate {
@@ -173,6 +404,19 @@ fn _make_process_from_less_path(
     }
 }
 
+// Passing '--no-init' fixes a bug with '--quit-if-one-screen' in older versions of 'less'.
+// Unfortunately, it also breaks mouse-wheel support.
+//
+// See: http://www.greenwoodsoftware.com/less/news.530.html
+//
+// For newer versions (530 or 558 on Windows), we omit '--no-init' as it is not needed anymore.
+fn less_needs_no_init() -> bool {
+    match retrieve_less_version() {
+        None => true,
+        Some(version) => version < 530 || (cfg!(windows) && version < 558),
+    }
+}
+
 fn _make_process_from_pager_path(pager_path: PathBuf, args: &[String]) -> Option<Command> {
     if pager_path.file_stem() == Some(&OsString::from("delta")) {
         fatal(
@@ -183,7 +427,7 @@ delta is not an appropriate value for $PAGER \
 (but it is an appropriate value for $GIT_PAGER).",
         );
     }
-    if let Ok(pager_path) = grep_cli::resolve_binary(pager_path) {
+    if let Some(pager_path) = resolve_program(pager_path) {
         let mut p = Command::new(&pager_path);
         p.args(args);
         Some(p)
@@ -192,10 +436,295 @@ delta is not an appropriate value for $PAGER \
     }
 }
 
+/// Return the path of the program: `program` itself if it is a path, or else the first file of that
+/// name in PATH. Unlike `grep_cli::resolve_binary`, which searches PATH only on Windows, this checks
+/// that the program exists on every platform, so that a missing pager can be reported.
+fn resolve_program(program: PathBuf) -> Option<PathBuf> {
+    if cfg!(windows) {
+        return grep_cli::resolve_binary(program).ok();
+    }
+    if program.components().count() > 1 {
+        return program.is_file().then_some(program);
+    }
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .map(|dir| dir.join(&program))
+        .find(|candidate| candidate.is_file())
+}
+
 impl Drop for OutputType {
     fn drop(&mut self) {
-        if let OutputType::Pager(ref mut command) = *self {
-            let _ = command.wait();
+        match *self {
+            OutputType::Pager(ref mut command) => {
+                let _ = command.wait();
+            }
+            OutputType::File(ref mut handle) => {
+                if let Err(error) = handle.flush() {
+                    eprintln!("Could not write output file: {}", error);
+                }
+            }
+            OutputType::InternalPager(_) | OutputType::Stdout(_) => {}
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_less_options() {
+        assert_eq!(
+            parse_less_options("mouse, no-wrap,quit-if-one-screen"),
+            Ok(vec![
+                LessOption::Mouse(true),
+                LessOption::Wrap(false),
+                LessOption::QuitIfOneScreen(true),
+            ])
+        );
+        assert_eq!(parse_less_options(""), Ok(vec![]));
+        assert!(parse_less_options("mouse,chop").is_err());
+        assert!(parse_less_options("no-no-wrap").is_err());
+    }
+
+    #[test]
+    fn test_parse_pager_command() {
+        let parse = |pager| PagerCommand::parse(pager).unwrap().unwrap();
+        assert_eq!(
+            parse("less -+F -+X --mouse"),
+            PagerCommand {
+                env: vec![],
+                program: "less".to_string(),
+                args: vec!["-+F".to_string(), "-+X".to_string(), "--mouse".to_string()],
+            }
+        );
+        assert_eq!(
+            parse("env LESS= less -R"),
+            PagerCommand {
+                env: vec![("LESS".to_string(), "".to_string())],
+                program: "less".to_string(),
+                args: vec!["-R".to_string()],
+            }
+        );
+        assert_eq!(
+            parse(r#"LESS="-R -S" LV=-c "/opt/my pager/less" --prompt='a b'"#),
+            PagerCommand {
+                env: vec![
+                    ("LESS".to_string(), "-R -S".to_string()),
+                    ("LV".to_string(), "-c".to_string()),
+                ],
+                program: "/opt/my pager/less".to_string(),
+                args: vec!["--prompt=a b".to_string()],
+            }
+        );
+        // Not an environment variable assignment.
+        assert_eq!(parse("1A=b less").program, "1A=b");
+        assert_eq!(PagerCommand::parse("").unwrap(), None);
+        assert_eq!(PagerCommand::parse("env LESS=").unwrap(), None);
+        assert!(PagerCommand::parse("less 'unterminated").is_err());
+    }
+
+    #[test]
+    fn test_get_pager_commands() {
+        let parse = |pager| PagerCommand::parse(pager).unwrap().unwrap();
+        let fallbacks = ["less".to_string(), "more".to_string()];
+        assert_eq!(
+            get_pager_commands(parse("most -s"), &fallbacks),
+            vec![parse("most -s"), parse("less"), parse("more")]
+        );
+        assert_eq!(
+            get_pager_commands(parse("LESS= /usr/bin/less -R"), &fallbacks),
+            vec![parse("LESS= /usr/bin/less -R"), parse("more")]
+        );
+        assert_eq!(
+            get_pager_commands(parse("less"), &["more -d".to_string(), "more".to_string()]),
+            vec![parse("less"), parse("more -d")]
+        );
+        assert_eq!(get_pager_commands(parse("less"), &[]), vec![parse("less")]);
+    }
+
+    #[test]
+    fn test_get_pager_command_line_not_found() {
+        let env = DeltaEnv::default();
+        let config = crate::tests::integration_test_utils::make_config_from_args(&[]);
+        assert_eq!(
+            get_pager_command_line(
+                &env,
+                Some("env LESS= delta-no-such-pager -R".to_string()),
+                false,
+                &config
+            ),
+            Err("Pager command not found in PATH: delta-no-such-pager".to_string())
+        );
+        assert_eq!(
+            get_pager_command_line(&env, Some("".to_string()), false, &config),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn test_pager_quit_if_one_screen() {
+        let args = |pager, quit_if_one_screen, value| {
+            let config = crate::tests::integration_test_utils::make_config_from_args(&[
+                "--pager-quit-if-one-screen",
+                value,
+            ]);
+            let pager = PagerCommand::parse(pager).unwrap().unwrap();
+            let process = make_pager_process(&pager, false, quit_if_one_screen, &config).unwrap();
+            process
+                .get_args()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .filter(|arg| arg != "--no-init")
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            args("less", true, "auto"),
+            ["--RAW-CONTROL-CHARS", "--quit-if-one-screen"]
+        );
+        assert_eq!(args("less", false, "auto"), ["--RAW-CONTROL-CHARS"]);
+        assert_eq!(args("less -R -F", true, "auto"), ["-R", "-F"]);
+        assert_eq!(
+            args("less", false, "true"),
+            ["--RAW-CONTROL-CHARS", "--quit-if-one-screen"]
+        );
+        assert_eq!(
+            args("less -R", false, "true"),
+            ["-R", "--quit-if-one-screen"]
+        );
+        assert_eq!(args("less", true, "false"), ["--RAW-CONTROL-CHARS", "-+F"]);
+        assert_eq!(
+            args("less -R -F --quit-if-one-screen", true, "false"),
+            ["-R", "-+F"]
+        );
+        // Pagers other than less are not affected.
+        assert_eq!(args("more -F", true, "false"), ["-F"]);
+    }
+
+    #[test]
+    fn test_pager_quit_if_one_screen_in_pager_less_options() {
+        let args = |pager, quit_if_one_screen, options: &[&str]| {
+            let config = crate::tests::integration_test_utils::make_config_from_args(options);
+            let pager = PagerCommand::parse(pager).unwrap().unwrap();
+            let process = make_pager_process(&pager, false, quit_if_one_screen, &config).unwrap();
+            process
+                .get_args()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .filter(|arg| arg != "--no-init")
+                .collect::<Vec<_>>()
+        };
+        // quit-if-one-screen in --pager-less-options is a value of --pager-quit-if-one-screen.
+        assert_eq!(
+            args(
+                "less -R",
+                false,
+                &["--pager-less-options", "mouse,quit-if-one-screen"]
+            ),
+            ["-R", "--quit-if-one-screen", "--mouse"]
+        );
+        assert_eq!(
+            args(
+                "less -R -F",
+                true,
+                &["--pager-less-options", "no-quit-if-one-screen"]
+            ),
+            ["-R", "-+F"]
+        );
+        // The last one given is used.
+        assert_eq!(
+            args(
+                "less",
+                true,
+                &[
+                    "--pager-less-options",
+                    "quit-if-one-screen,no-quit-if-one-screen"
+                ]
+            ),
+            ["--RAW-CONTROL-CHARS", "-+F"]
+        );
+        // --pager-quit-if-one-screen takes precedence.
+        assert_eq!(
+            args(
+                "less -R",
+                false,
+                &[
+                    "--pager-less-options",
+                    "no-quit-if-one-screen",
+                    "--pager-quit-if-one-screen",
+                    "true"
+                ]
+            ),
+            ["-R", "--quit-if-one-screen"]
+        );
+        assert_eq!(
+            args(
+                "less -R -F",
+                true,
+                &[
+                    "--pager-less-options",
+                    "quit-if-one-screen",
+                    "--pager-quit-if-one-screen",
+                    "false"
+                ]
+            ),
+            ["-R", "-+F"]
+        );
+    }
+
+    #[test]
+    fn test_less_option_args() {
+        assert_eq!(
+            less_option_args(&parse_less_options("mouse,no-mouse,wrap,no-wrap").unwrap()),
+            vec!["--mouse", "-+--mouse", "-+S", "--chop-long-lines"]
+        );
+        assert_eq!(
+            less_option_args(&parse_less_options("mouse,quit-if-one-screen").unwrap()),
+            vec!["--mouse"]
+        );
+    }
+
+    #[test]
+    fn test_output_file() {
+        use crate::tests::integration_test_utils::{
+            make_config_from_args, make_temp_dir, run_delta,
+        };
+
+        let input = "\
+diff --git a/a.rs b/a.rs
+index 1111111..2222222 100644
+--- a/a.rs
++++ b/a.rs
+@@ -1 +1 @@
+-a
++b
+";
+        let dir = make_temp_dir("test_output_file");
+        let path = dir.join("delta.out");
+        let path = path.to_str().unwrap();
+        // An existing file is truncated.
+        std::fs::write(path, "x".repeat(10000)).unwrap();
+        let config = make_config_from_args(&["--output", path]);
+        assert_eq!(config.output.as_deref(), Some(path));
+        {
+            let mut output_type = OutputType::file(path).unwrap();
+            let writer = output_type.handle().unwrap();
+            crate::delta::delta(
+                bytelines::ByteLines::new(std::io::BufReader::new(input.as_bytes())),
+                writer,
+                &config,
+            )
+            .unwrap();
+        }
+        // The output is styled, as it is when written to a pager.
+        let output = std::fs::read_to_string(path).unwrap();
+        assert!(output.contains('\x1b'));
+        assert_eq!(output, run_delta(input, &config));
+
+        let error = OutputType::file(dir.join("missing").join("delta.out").to_str().unwrap())
+            .err()
+            .unwrap();
+        assert!(error
+            .to_string()
+            .starts_with("Could not create output file"));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
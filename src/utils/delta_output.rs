@@ -0,0 +1,128 @@
+// Detection of input which is already delta's output, see --idempotent.
+
+use std::io::{BufRead, Read};
+
+use bytelines::ByteLines;
+
+use crate::ansi;
+
+/// The number of lines read to decide whether the input is delta's output. Delta's output, like
+/// git's, may start with commit metadata of any length before the first file.
+pub const MAX_LINES_TO_DETECT_DELTA_OUTPUT: usize = 1000;
+
+/// Return Some(true) if `line` shows that the input is delta's output, Some(false) if it shows that
+/// the input has not been processed by delta, and None if it shows neither. Delta replaces the
+/// "diff", "---", "+++" and "@@" lines of a diff by its decorations, such as the colored line
+/// of box-drawing characters underlining a file name or boxing a hunk header.
+pub fn is_delta_output_line(line: &[u8]) -> Option<bool> {
+    let raw_line = String::from_utf8_lossy(line);
+    let line = ansi::strip_ansi_codes(&raw_line);
+    if ["diff ", "--- ", "+++ ", "@@ "]
+        .iter()
+        .any(|prefix| line.starts_with(prefix))
+    {
+        Some(false)
+    } else if raw_line.contains('\x1b')
+        && !line.trim().is_empty()
+        && line
+            .trim()
+            .chars()
+            .all(|c| ('\u{2500}'..='\u{257F}').contains(&c))
+    {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+/// Read the lines of a `ByteLines` as bytes again, so that lines which have already been read can
+/// be chained in front of the remaining ones.
+pub struct ByteLinesReader<I: BufRead> {
+    lines: ByteLines<I>,
+    buffer: Vec<u8>,
+    position: usize,
+}
+
+impl<I: BufRead> ByteLinesReader<I> {
+    pub fn new(lines: ByteLines<I>) -> Self {
+        Self {
+            lines,
+            buffer: Vec::new(),
+            position: 0,
+        }
+    }
+}
+
+impl<I: BufRead> Read for ByteLinesReader<I> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let available = self.fill_buf()?;
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl<I: BufRead> BufRead for ByteLinesReader<I> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        if self.position >= self.buffer.len() {
+            self.buffer.clear();
+            self.position = 0;
+            if let Some(line) = self.lines.next() {
+                self.buffer.extend_from_slice(line?);
+                self.buffer.push(b'\n');
+            }
+        }
+        Ok(&self.buffer[self.position..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.position += amt;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::fixtures::GIT_LOG_THREE_COMMITS;
+    use crate::tests::integration_test_utils::DeltaTest;
+
+    #[test]
+    fn test_is_delta_output_line() {
+        assert_eq!(
+            is_delta_output_line(b"diff --git a/a.rs b/a.rs"),
+            Some(false)
+        );
+        assert_eq!(
+            is_delta_output_line(b"\x1b[1m@@ -1 +1 @@\x1b[0m"),
+            Some(false)
+        );
+        assert_eq!(
+            is_delta_output_line("\x1b[34m───────┐\x1b[0m".as_bytes()),
+            Some(true)
+        );
+        // Box-drawing characters in uncolored input are not delta's.
+        assert_eq!(is_delta_output_line("───────".as_bytes()), None);
+        assert_eq!(
+            is_delta_output_line(b"commit 94907c0f136f46dc46ffae2dc92dca9af7eb7c2e"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_idempotent() {
+        let delta = DeltaTest::with_args(&["--commit-decoration-style", "box"]);
+        let idempotent_delta =
+            DeltaTest::with_args(&["--commit-decoration-style", "box", "--idempotent"]);
+        let output = delta.with_input(GIT_LOG_THREE_COMMITS).raw_output;
+        assert_eq!(
+            idempotent_delta
+                .with_input(GIT_LOG_THREE_COMMITS)
+                .raw_output,
+            output
+        );
+        // Otherwise the commit line is boxed again.
+        assert_ne!(delta.with_input(&output).raw_output, output);
+        assert_eq!(idempotent_delta.with_input(&output).raw_output, output);
+    }
+}
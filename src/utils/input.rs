@@ -0,0 +1,129 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+#[derive(Debug, PartialEq)]
+enum Compression {
+    Gzip,
+    Zstd,
+    None,
+}
+
+/// Open the file from which the diff is read, see --input. A file compressed with gzip or zstd is
+/// recognized by its magic bytes and decompressed as it is read.
+pub fn open_input_file(path: &Path) -> io::Result<Box<dyn BufRead>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let compression = detect_compression(reader.fill_buf()?);
+    decompress(reader, compression)
+}
+
+fn detect_compression(bytes: &[u8]) -> Compression {
+    if bytes.starts_with(&[0x1f, 0x8b]) {
+        Compression::Gzip
+    } else if bytes.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+        Compression::Zstd
+    } else {
+        Compression::None
+    }
+}
+
+#[cfg(feature = "compressed-input")]
+fn decompress(reader: BufReader<File>, compression: Compression) -> io::Result<Box<dyn BufRead>> {
+    match compression {
+        // A gzip file may consist of several members, e.g. when files were concatenated.
+        Compression::Gzip => Ok(Box::new(BufReader::new(
+            flate2::bufread::MultiGzDecoder::new(reader),
+        ))),
+        Compression::Zstd => {
+            let decoder = ruzstd::StreamingDecoder::new(reader)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
+            Ok(Box::new(BufReader::new(decoder)))
+        }
+        Compression::None => Ok(Box::new(reader)),
+    }
+}
+
+#[cfg(not(feature = "compressed-input"))]
+fn decompress(reader: BufReader<File>, compression: Compression) -> io::Result<Box<dyn BufRead>> {
+    match compression {
+        Compression::None => Ok(Box::new(reader)),
+        _ => Err(io::Error::other(
+            "the input is compressed, but delta was built without the compressed-input feature",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+
+    use super::*;
+
+    const DIFF: &str = "\
+diff --git a/a.rs b/a.rs
+index 1111111..2222222 100644
+--- a/a.rs
++++ b/a.rs
+@@ -1 +1 @@
+-a
++b
+";
+
+    // Each test writes its file in a directory of its own, which is unique to the test process.
+    fn read_input_file(name: &str, contents: &[u8]) -> io::Result<String> {
+        let dir =
+            std::env::temp_dir().join(format!("delta__test_input_{}_{}", std::process::id(), name));
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join(name);
+        File::create(&path)?.write_all(contents)?;
+        let mut input = String::new();
+        let result =
+            open_input_file(&path).and_then(|mut reader| reader.read_to_string(&mut input));
+        std::fs::remove_dir_all(&dir)?;
+        result.map(|_| input)
+    }
+
+    #[test]
+    fn test_detect_compression() {
+        assert_eq!(detect_compression(&[0x1f, 0x8b, 0x08]), Compression::Gzip);
+        assert_eq!(
+            detect_compression(&[0x28, 0xb5, 0x2f, 0xfd, 0x04]),
+            Compression::Zstd
+        );
+        assert_eq!(detect_compression(DIFF.as_bytes()), Compression::None);
+        assert_eq!(detect_compression(&[]), Compression::None);
+    }
+
+    #[test]
+    fn test_uncompressed_input_is_read_unchanged() {
+        assert_eq!(
+            read_input_file("plain.diff", DIFF.as_bytes()).unwrap(),
+            DIFF
+        );
+    }
+
+    #[cfg(feature = "compressed-input")]
+    #[test]
+    fn test_gzip_input_is_decompressed() {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(DIFF.as_bytes()).unwrap();
+        let contents = encoder.finish().unwrap();
+        assert_eq!(read_input_file("diff.gz", &contents).unwrap(), DIFF);
+    }
+
+    #[cfg(feature = "compressed-input")]
+    #[test]
+    fn test_zstd_input_is_decompressed() {
+        // `printf 'diff\n' | zstd --no-check`
+        let contents = [
+            0x28, 0xb5, 0x2f, 0xfd, 0x00, 0x58, 0x29, 0x00, 0x00, 0x64, 0x69, 0x66, 0x66, 0x0a,
+        ];
+        assert_eq!(read_input_file("diff.zst", &contents).unwrap(), "diff\n");
+    }
+
+    #[cfg(not(feature = "compressed-input"))]
+    #[test]
+    fn test_compressed_input_is_an_error_without_the_feature() {
+        assert!(read_input_file("diff.gz.unsupported", &[0x1f, 0x8b, 0x08, 0x00]).is_err());
+    }
+}
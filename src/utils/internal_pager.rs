@@ -0,0 +1,488 @@
+// A minimal pager run by delta itself, see --paging=internal. It is also used when no external
+// pager can be started.
+
+use std::io::{self, Write};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::{self, JoinHandle};
+
+use console::{Key, Term};
+use regex::Regex;
+
+use crate::ansi;
+
+const ENTER_ALTERNATE_SCREEN: &str = "\x1b[?1049h\x1b[?25l";
+const LEAVE_ALTERNATE_SCREEN: &str = "\x1b[?25h\x1b[?1049l";
+const CURSOR_HOME: &str = "\x1b[H";
+
+enum Event {
+    // A line of delta's output, without the newline.
+    Line(String),
+    // Delta's output has ended.
+    End,
+    Key(Key),
+    // The keyboard could not be read.
+    KeyError,
+}
+
+/// The writer of delta's output when the internal pager is used. The pager runs in its own thread,
+/// so that lines are displayed while the rest of the input is processed.
+pub struct InternalPager {
+    sender: Sender<Event>,
+    buffer: Vec<u8>,
+    thread: Option<JoinHandle<io::Result<()>>>,
+}
+
+impl InternalPager {
+    /// Start the pager, or return None if stdout is not a terminal. `n` and `N` jump to the lines
+    /// matching `navigate_regex` until another search is made. If `quit_if_one_screen` is true and
+    /// the output fits on one screen, it is written to the terminal as it is.
+    pub fn start(navigate_regex: Option<&str>, quit_if_one_screen: bool) -> Option<Self> {
+        let term = Term::stdout();
+        if !term.is_term() {
+            return None;
+        }
+        let navigate_regex = navigate_regex.and_then(|regex| Regex::new(regex).ok());
+        let (sender, receiver) = mpsc::channel();
+        let (key_request_sender, key_request_receiver) = mpsc::channel::<()>();
+
+        // A key is read only when the pager requests one, so that the terminal is never left in
+        // the raw mode of a read in progress when delta exits.
+        let key_sender = sender.clone();
+        let key_term = term.clone();
+        thread::spawn(move || {
+            for () in key_request_receiver {
+                let event = match key_term.read_key() {
+                    Ok(key) => Event::Key(key),
+                    Err(_) => Event::KeyError,
+                };
+                if key_sender.send(event).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let thread = thread::spawn(move || {
+            let pager = Pager::new(navigate_regex);
+            run_pager(
+                pager,
+                &term,
+                receiver,
+                key_request_sender,
+                quit_if_one_screen,
+            )
+        });
+        Some(Self {
+            sender,
+            buffer: Vec::new(),
+            thread: Some(thread),
+        })
+    }
+
+    fn send(&self, event: Event) -> io::Result<()> {
+        // The pager has quit.
+        self.sender
+            .send(event)
+            .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))
+    }
+}
+
+impl Write for InternalPager {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        while let Some(newline) = self.buffer.iter().position(|&b| b == b'\n') {
+            let rest = self.buffer.split_off(newline + 1);
+            let line = String::from_utf8_lossy(&self.buffer[..newline]).into_owned();
+            self.buffer = rest;
+            self.send(Event::Line(line))?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for InternalPager {
+    // Wait for the user to quit the pager.
+    fn drop(&mut self) {
+        if !self.buffer.is_empty() {
+            let line = String::from_utf8_lossy(&self.buffer).into_owned();
+            let _ = self.send(Event::Line(line));
+        }
+        let _ = self.send(Event::End);
+        if let Some(thread) = self.thread.take() {
+            if let Ok(Err(error)) = thread.join() {
+                eprintln!("Internal pager error: {}", error);
+            }
+        }
+    }
+}
+
+fn run_pager(
+    mut pager: Pager,
+    term: &Term,
+    receiver: Receiver<Event>,
+    key_request_sender: Sender<()>,
+    quit_if_one_screen: bool,
+) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    let mut is_displayed = false;
+    let mut is_key_requested = false;
+    while let Ok(event) = receiver.recv() {
+        let (rows, columns) = term.size();
+        let height = (rows as usize).saturating_sub(1).max(1);
+        let mut needs_redraw = false;
+        // Lines arrive much faster than they can be displayed, so all events received meanwhile
+        // are handled before the screen is redrawn.
+        for event in std::iter::once(event).chain(receiver.try_iter()) {
+            match event {
+                Event::Line(line) => {
+                    needs_redraw |= pager.lines.len() < pager.top + height;
+                    pager.push_line(line);
+                }
+                Event::End => {
+                    pager.has_ended = true;
+                    needs_redraw = true;
+                }
+                Event::Key(key) => {
+                    is_key_requested = false;
+                    needs_redraw = true;
+                    if pager.handle_key(key, height) == Action::Quit {
+                        if is_displayed {
+                            write!(stdout, "{}", LEAVE_ALTERNATE_SCREEN)?;
+                            stdout.flush()?;
+                        }
+                        return Ok(());
+                    }
+                }
+                Event::KeyError => {
+                    if is_displayed {
+                        write!(stdout, "{}", LEAVE_ALTERNATE_SCREEN)?;
+                        stdout.flush()?;
+                    }
+                    return Ok(());
+                }
+            }
+        }
+        if !is_displayed {
+            // The pager is displayed once the first screen is full, or the output has ended.
+            if pager.has_ended && quit_if_one_screen && pager.lines.len() <= height {
+                for line in &pager.lines {
+                    writeln!(stdout, "{}", line)?;
+                }
+                return Ok(());
+            } else if pager.has_ended || pager.lines.len() > height {
+                write!(stdout, "{}", ENTER_ALTERNATE_SCREEN)?;
+                is_displayed = true;
+                needs_redraw = true;
+            }
+        }
+        if is_displayed {
+            if needs_redraw {
+                let mut screen = Vec::new();
+                pager.draw(&mut screen, height, columns as usize)?;
+                stdout.write_all(&screen)?;
+                stdout.flush()?;
+            }
+            if !is_key_requested && key_request_sender.send(()).is_ok() {
+                is_key_requested = true;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, PartialEq)]
+enum Action {
+    Continue,
+    Quit,
+}
+
+// The state of the pager: the lines received so far, the position in them, and the search.
+struct Pager {
+    lines: Vec<String>,
+    // The lines without ANSI escape sequences, which are searched.
+    plain_lines: Vec<String>,
+    has_ended: bool,
+    // The index of the line at the top of the screen.
+    top: usize,
+    search: Option<Regex>,
+    navigate_regex: Option<Regex>,
+    // The search being typed after '/'.
+    prompt: Option<String>,
+    message: Option<String>,
+}
+
+impl Pager {
+    fn new(navigate_regex: Option<Regex>) -> Self {
+        Self {
+            lines: Vec::new(),
+            plain_lines: Vec::new(),
+            has_ended: false,
+            top: 0,
+            search: None,
+            navigate_regex,
+            prompt: None,
+            message: None,
+        }
+    }
+
+    fn push_line(&mut self, line: String) {
+        self.plain_lines.push(ansi::strip_ansi_codes(&line));
+        self.lines.push(line);
+    }
+
+    // The greatest index of the top line with which the screen is full.
+    fn max_top(&self, height: usize) -> usize {
+        self.lines.len().saturating_sub(height)
+    }
+
+    fn handle_key(&mut self, key: Key, height: usize) -> Action {
+        self.message = None;
+        if let Some(prompt) = &mut self.prompt {
+            match key {
+                Key::Enter => {
+                    let prompt = self.prompt.take().unwrap();
+                    self.start_search(&prompt);
+                }
+                Key::Escape => self.prompt = None,
+                Key::Backspace if prompt.is_empty() => self.prompt = None,
+                Key::Backspace => {
+                    prompt.pop();
+                }
+                Key::Char(c) if !c.is_control() => prompt.push(c),
+                _ => {}
+            }
+            return Action::Continue;
+        }
+        match key {
+            Key::Char('q') | Key::Char('Q') | Key::Char('\x03') => return Action::Quit,
+            Key::ArrowDown | Key::Enter | Key::Char('j') | Key::Char('e') => {
+                self.scroll_down(1, height)
+            }
+            Key::ArrowUp | Key::Char('k') | Key::Char('y') => self.scroll_up(1),
+            Key::PageDown | Key::Char(' ') | Key::Char('f') => self.scroll_down(height, height),
+            Key::PageUp | Key::Char('b') => self.scroll_up(height),
+            Key::Char('d') => self.scroll_down(height / 2, height),
+            Key::Char('u') => self.scroll_up(height / 2),
+            Key::Home | Key::Char('g') | Key::Char('<') => self.top = 0,
+            Key::End | Key::Char('G') | Key::Char('>') => self.top = self.max_top(height),
+            Key::Char('/') => self.prompt = Some(String::new()),
+            Key::Char('n') => self.find(true),
+            Key::Char('N') => self.find(false),
+            _ => {}
+        }
+        Action::Continue
+    }
+
+    fn scroll_down(&mut self, n: usize, height: usize) {
+        // A search may have moved the top line beyond the last screen.
+        self.top = (self.top + n).min(self.max_top(height).max(self.top));
+    }
+
+    fn scroll_up(&mut self, n: usize) {
+        self.top = self.top.saturating_sub(n);
+    }
+
+    // An empty search repeats the previous one, as in less.
+    fn start_search(&mut self, pattern: &str) {
+        if !pattern.is_empty() {
+            match Regex::new(pattern) {
+                Ok(regex) => self.search = Some(regex),
+                Err(_) => {
+                    self.message = Some(format!("Invalid pattern: {}", pattern));
+                    return;
+                }
+            }
+        }
+        self.find(true);
+    }
+
+    // Move the next line, or the previous one, matching the search to the top of the screen. Until
+    // a search is made, the navigate regex is used.
+    fn find(&mut self, forward: bool) {
+        if self.search.is_none() {
+            self.search = self.navigate_regex.clone();
+        }
+        let regex = match &self.search {
+            Some(regex) => regex,
+            None => {
+                self.message = Some("No previous search".to_string());
+                return;
+            }
+        };
+        let is_match = |i: &usize| regex.is_match(&self.plain_lines[*i]);
+        let found = if forward {
+            (self.top + 1..self.lines.len()).find(is_match)
+        } else {
+            (0..self.top).rev().find(is_match)
+        };
+        match found {
+            Some(i) => self.top = i,
+            None => self.message = Some("Pattern not found".to_string()),
+        }
+    }
+
+    fn draw(&self, writer: &mut dyn Write, height: usize, width: usize) -> io::Result<()> {
+        write!(writer, "{}", CURSOR_HOME)?;
+        // Raw mode, in which keys are read, does not translate "\n" to "\r\n".
+        for i in self.top..self.top + height {
+            match self.lines.get(i) {
+                Some(line) => {
+                    let line = match &self.search {
+                        Some(regex) => {
+                            let ranges = regex
+                                .find_iter(&self.plain_lines[i])
+                                .map(|m| (m.start(), m.end()))
+                                .filter(|(start, end)| start < end)
+                                .collect::<Vec<_>>();
+                            ansi::reverse_text_ranges(line, &ranges)
+                        }
+                        None => line.clone(),
+                    };
+                    write!(
+                        writer,
+                        "{}{}",
+                        ansi::truncate_str(&line, width, ""),
+                        ansi::ANSI_SGR_RESET
+                    )?;
+                }
+                None => write!(writer, "~")?,
+            }
+            write!(writer, "{}\r\n", ansi::ANSI_CSI_CLEAR_TO_EOL)?;
+        }
+        let status = match (&self.prompt, &self.message) {
+            (Some(prompt), _) => format!("/{}", prompt),
+            (None, Some(message)) => reverse(message),
+            (None, None) if self.has_ended && self.top >= self.max_top(height) => reverse("(END)"),
+            (None, None) => ":".to_string(),
+        };
+        write!(
+            writer,
+            "{}{}",
+            ansi::truncate_str(&status, width, ""),
+            ansi::ANSI_CSI_CLEAR_TO_EOL
+        )
+    }
+}
+
+fn reverse(text: &str) -> String {
+    format!("{}{}{}", ansi::ANSI_SGR_REVERSE, text, ansi::ANSI_SGR_RESET)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_pager(n_lines: usize, navigate_regex: Option<&str>) -> Pager {
+        let mut pager = Pager::new(navigate_regex.map(|regex| Regex::new(regex).unwrap()));
+        for i in 0..n_lines {
+            let line = if i % 10 == 0 {
+                format!("\x1b[34mcommit {}\x1b[0m", i)
+            } else {
+                format!("line {}", i)
+            };
+            pager.push_line(line);
+        }
+        pager.has_ended = true;
+        pager
+    }
+
+    fn draw(pager: &Pager, height: usize, width: usize) -> Vec<String> {
+        let mut screen = Vec::new();
+        pager.draw(&mut screen, height, width).unwrap();
+        let screen = ansi::strip_ansi_codes(&String::from_utf8(screen).unwrap());
+        screen.split("\r\n").map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_internal_pager_scrolling() {
+        let mut pager = make_pager(25, None);
+        let height = 10;
+        pager.handle_key(Key::ArrowDown, height);
+        pager.handle_key(Key::Char('j'), height);
+        assert_eq!(pager.top, 2);
+        pager.handle_key(Key::PageDown, height);
+        assert_eq!(pager.top, 12);
+        pager.handle_key(Key::PageDown, height);
+        assert_eq!(pager.top, 15);
+        pager.handle_key(Key::ArrowUp, height);
+        assert_eq!(pager.top, 14);
+        pager.handle_key(Key::Char('g'), height);
+        assert_eq!(pager.top, 0);
+        pager.handle_key(Key::PageUp, height);
+        assert_eq!(pager.top, 0);
+        pager.handle_key(Key::Char('G'), height);
+        assert_eq!(pager.top, 15);
+        assert_eq!(pager.handle_key(Key::Char('q'), height), Action::Quit);
+    }
+
+    #[test]
+    fn test_internal_pager_search() {
+        let mut pager = make_pager(25, None);
+        let height = 10;
+        pager.handle_key(Key::Char('n'), height);
+        assert_eq!(pager.message.as_deref(), Some("No previous search"));
+        for key in [Key::Char('/'), Key::Char('3'), Key::Enter] {
+            pager.handle_key(key, height);
+        }
+        assert_eq!(pager.top, 3);
+        pager.handle_key(Key::Char('n'), height);
+        assert_eq!(pager.top, 13);
+        pager.handle_key(Key::Char('n'), height);
+        assert_eq!(pager.top, 23);
+        // Beyond the last screen, which is then scrolled back to.
+        pager.handle_key(Key::Char('n'), height);
+        assert_eq!(pager.message.as_deref(), Some("Pattern not found"));
+        assert_eq!(pager.top, 23);
+        pager.handle_key(Key::Char('N'), height);
+        assert_eq!(pager.top, 13);
+        pager.handle_key(Key::Char('k'), height);
+        assert_eq!(pager.top, 12);
+        for key in [Key::Char('/'), Key::Char('('), Key::Enter] {
+            pager.handle_key(key, height);
+        }
+        assert_eq!(pager.message.as_deref(), Some("Invalid pattern: ("));
+    }
+
+    #[test]
+    fn test_internal_pager_navigate() {
+        // Matches are searched for in the text without ANSI escape sequences.
+        let mut pager = make_pager(25, Some("^commit "));
+        let height = 10;
+        pager.handle_key(Key::Char('n'), height);
+        assert_eq!(pager.top, 10);
+        pager.handle_key(Key::Char('n'), height);
+        assert_eq!(pager.top, 20);
+        pager.handle_key(Key::Char('N'), height);
+        assert_eq!(pager.top, 10);
+    }
+
+    #[test]
+    fn test_internal_pager_draw() {
+        let mut pager = make_pager(12, None);
+        pager.has_ended = false;
+        let screen = draw(&pager, 3, 80);
+        assert_eq!(screen, ["commit 0", "line 1", "line 2", ":"]);
+
+        pager.has_ended = true;
+        pager.handle_key(Key::Char('G'), 3);
+        let screen = draw(&pager, 3, 80);
+        assert_eq!(screen, ["line 9", "commit 10", "line 11", "(END)"]);
+
+        // Long lines are cut at the width of the terminal.
+        let screen = draw(&pager, 3, 6);
+        assert_eq!(screen[1], "commit");
+
+        for key in [Key::Char('/'), Key::Char('1')] {
+            pager.handle_key(key, 3);
+        }
+        assert_eq!(draw(&pager, 3, 80)[3], "/1");
+        pager.handle_key(Key::Enter, 3);
+        let mut screen = Vec::new();
+        pager.draw(&mut screen, 3, 80).unwrap();
+        let screen = String::from_utf8(screen).unwrap();
+        assert!(screen.contains("line \x1b[7m1\x1b[27m\x1b[7m1\x1b[27m"));
+    }
+}
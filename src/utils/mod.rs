@@ -1,6 +1,10 @@
 #[cfg(not(tarpaulin_include))]
 pub mod bat;
+pub mod delta_output;
+pub mod input;
+pub mod internal_pager;
 pub mod path;
 pub mod process;
 pub mod regex_replacement;
 pub mod syntect;
+pub mod terminal;
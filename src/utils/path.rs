@@ -1,5 +1,7 @@
 use std::path::{Component, Path, PathBuf};
 
+use regex::Regex;
+
 use crate::config::Config;
 
 use super::process::calling_process;
@@ -96,6 +98,31 @@ where
     ret
 }
 
+// Translate a glob into an anchored regex: "**" matches across directories, "*" and "?" match
+// within a path component.
+pub fn glob_to_regex(glob: &str) -> Result<Regex, regex::Error> {
+    let mut regex = String::from("^");
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    regex.push_str("(?:.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    Regex::new(&regex)
+}
+
 #[cfg(test)]
 pub fn fake_delta_cwd_for_tests() -> PathBuf {
     #[cfg(not(target_os = "windows"))]
@@ -1,7 +1,9 @@
 use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::path::Path;
 use std::sync::{Arc, Condvar, Mutex, MutexGuard};
 
+use itertools::Itertools;
 use lazy_static::lazy_static;
 use sysinfo::{Pid, PidExt, Process, ProcessExt, ProcessRefreshKind, SystemExt};
 
@@ -14,9 +16,12 @@ pub enum CallingProcess {
     GitLog(CommandLine),
     GitReflog(CommandLine),
     GitGrep(CommandLine),
-    OtherGrep, // rg, grep, ag, ack, etc
-    None,      // no matching process could be found
-    Pending,   // calling process is currently being determined
+    OtherGrep(CommandLine), // rg, grep, ag, ack, etc
+    JjDiff(CommandLine),
+    JjLog(CommandLine),
+    JjShow(CommandLine),
+    None,    // no matching process could be found
+    Pending, // calling process is currently being determined
 }
 // TODO: Git blame is currently handled differently
 
@@ -26,19 +31,69 @@ impl CallingProcess {
             CallingProcess::GitDiff(cmd) if cmd.long_options.contains("--relative") => true,
             CallingProcess::GitShow(cmd, _) if cmd.long_options.contains("--relative") => true,
             CallingProcess::GitLog(cmd) if cmd.long_options.contains("--relative") => true,
-            CallingProcess::GitGrep(_) | CallingProcess::OtherGrep => true,
+            CallingProcess::GitGrep(_) | CallingProcess::OtherGrep(_) => true,
+            // jj displays paths relative to the current directory, except in git's format.
+            CallingProcess::JjDiff(cmd)
+            | CallingProcess::JjLog(cmd)
+            | CallingProcess::JjShow(cmd) => !cmd.long_options.contains("--git"),
             _ => false,
         }
     }
+
+    pub fn is_jj(&self) -> bool {
+        matches!(
+            self,
+            CallingProcess::JjDiff(_) | CallingProcess::JjLog(_) | CallingProcess::JjShow(_)
+        )
+    }
+}
+
+impl fmt::Display for CallingProcess {
+    /// E.g. "jj diff --git", for --show-config.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (command, command_line) = match self {
+            CallingProcess::GitDiff(cmd) => ("git diff", cmd),
+            CallingProcess::GitShow(cmd, _) => ("git show", cmd),
+            CallingProcess::GitLog(cmd) => ("git log", cmd),
+            CallingProcess::GitReflog(cmd) => ("git reflog", cmd),
+            CallingProcess::GitGrep(cmd) => ("git grep", cmd),
+            CallingProcess::OtherGrep(cmd) => ("grep", cmd),
+            CallingProcess::JjDiff(cmd) => ("jj diff", cmd),
+            CallingProcess::JjLog(cmd) => ("jj log", cmd),
+            CallingProcess::JjShow(cmd) => ("jj show", cmd),
+            CallingProcess::None => return write!(f, "none"),
+            CallingProcess::Pending => return write!(f, "pending"),
+        };
+        write!(f, "{}", command)?;
+        for option in command_line
+            .long_options
+            .iter()
+            .chain(command_line.short_options.iter())
+            .sorted()
+        {
+            write!(f, " {}", option)?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct CommandLine {
     pub long_options: HashSet<String>,
     pub short_options: HashSet<String>,
+    // The values of long options given as "--option=value" or "--option value": the argument
+    // following a long option is taken to be its value, if it is not an option itself.
+    long_option_values: HashMap<String, String>,
     last_arg: Option<String>,
 }
 
+impl CommandLine {
+    /// The value of a long option taking a value, e.g. "~~" for `rg --context-separator ~~`.
+    pub fn long_option_value(&self, option: &str) -> Option<&str> {
+        self.long_option_values.get(option).map(String::as_str)
+    }
+}
+
 lazy_static! {
     static ref CALLER: Arc<(Mutex<CallingProcess>, Condvar)> =
         Arc::new((Mutex::new(CallingProcess::Pending), Condvar::new()));
@@ -188,8 +243,24 @@ pub fn describe_calling_process(args: &[String]) -> ProcessArgs<CallingProcess>
             }
             // TODO: parse_style_sections is failing to parse ANSI escape sequences emitted by
             // grep (BSD and GNU), ag, pt. See #794
+            Some(s) if is_any_of(s.to_str(), ["jj"]) => {
+                // Global options such as `-R path` may precede the subcommand.
+                let mut args = args.skip_while(|s| *s != "diff" && *s != "show" && *s != "log");
+                match args.next() {
+                    Some("diff") => {
+                        ProcessArgs::Args(CallingProcess::JjDiff(parse_command_line(args)))
+                    }
+                    Some("log") => {
+                        ProcessArgs::Args(CallingProcess::JjLog(parse_command_line(args)))
+                    }
+                    Some("show") => {
+                        ProcessArgs::Args(CallingProcess::JjShow(parse_command_line(args)))
+                    }
+                    _ => ProcessArgs::ArgError,
+                }
+            }
             Some(s) if is_any_of(s.to_str(), ["rg", "ack", "sift"]) => {
-                ProcessArgs::Args(CallingProcess::OtherGrep)
+                ProcessArgs::Args(CallingProcess::OtherGrep(parse_command_line(args)))
             }
             Some(_) => {
                 // It's not git, and it's not another grep tool. Keep
@@ -256,23 +327,39 @@ where
 fn parse_command_line<'a>(args: impl Iterator<Item = &'a str>) -> CommandLine {
     let mut long_options = HashSet::new();
     let mut short_options = HashSet::new();
+    let mut long_option_values = HashMap::new();
     let mut last_arg = None;
+    let mut previous_long_option = None;
 
     for s in args {
         if s == "--" {
             break;
         } else if s.starts_with("--") {
-            long_options.insert(s.split('=').next().unwrap().to_owned());
+            let (option, value) = match s.split_once('=') {
+                Some((option, value)) => (option, Some(value)),
+                None => (s, None),
+            };
+            long_options.insert(option.to_owned());
+            if let Some(value) = value {
+                long_option_values.insert(option.to_owned(), value.to_owned());
+            }
+            previous_long_option = value.is_none().then_some(option);
+            continue;
         } else if let Some(suffix) = s.strip_prefix('-') {
             short_options.extend(suffix.chars().map(|c| format!("-{}", c)));
         } else {
+            if let Some(option) = previous_long_option {
+                long_option_values.insert(option.to_owned(), s.to_owned());
+            }
             last_arg = Some(s);
         }
+        previous_long_option = None;
     }
 
     CommandLine {
         long_options,
         short_options,
+        long_option_values,
         last_arg: last_arg.map(|s| s.to_string()),
     }
 }
@@ -1065,6 +1152,7 @@ pub mod tests {
         let empty_command_line = CommandLine {
             long_options: [].into(),
             short_options: [].into(),
+            long_option_values: [].into(),
             last_arg: Some("hello.txt".to_string()),
         };
         let parent = MockProcInfo::with(&[
@@ -1084,7 +1172,7 @@ pub mod tests {
         ]);
         assert_eq!(
             calling_process_cmdline(parent, describe_calling_process),
-            Some(CallingProcess::GitGrep(empty_command_line))
+            Some(CallingProcess::GitGrep(empty_command_line.clone()))
         );
 
         for grep_command in &[
@@ -1100,7 +1188,7 @@ pub mod tests {
             ]);
             assert_eq!(
                 calling_process_cmdline(parent, describe_calling_process),
-                Some(CallingProcess::OtherGrep)
+                Some(CallingProcess::OtherGrep(empty_command_line.clone()))
             );
         }
 
@@ -1110,6 +1198,7 @@ pub mod tests {
         let expected_result = Some(CallingProcess::GitGrep(CommandLine {
             long_options: set(&["--function-context", "--show-function", "--foo"]),
             short_options: set(&["-a", "-b", "-n", "-W"]),
+            long_option_values: [("--foo".to_string(), "val".to_string())].into(),
             last_arg: Some("hello.txt".to_string()),
         }));
 
@@ -1135,6 +1224,40 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn test_describe_calling_process_jj() {
+        for (command, expected) in [
+            ("jj diff --git", "jj diff --git"),
+            ("jj -R repo log -p --git -r @-", "jj log --git -p -r"),
+            ("/usr/bin/jj show --color-words", "jj show --color-words"),
+        ] {
+            let parent = MockProcInfo::with(&[
+                (2, 100, "-shell", None),
+                (3, 100, command, Some(2)),
+                (4, 100, "delta", Some(3)),
+            ]);
+            let calling_process = calling_process_cmdline(parent, describe_calling_process);
+            assert!(calling_process.as_ref().unwrap().is_jj());
+            assert_eq!(calling_process.unwrap().to_string(), expected);
+        }
+        let parent = MockProcInfo::with(&[
+            (2, 100, "-shell", None),
+            (3, 100, "jj diff", Some(2)),
+            (4, 100, "delta", Some(3)),
+        ]);
+        assert!(calling_process_cmdline(parent, describe_calling_process)
+            .unwrap()
+            .paths_in_input_are_relative_to_cwd());
+        let parent = MockProcInfo::with(&[
+            (2, 100, "-shell", None),
+            (3, 100, "jj diff --git", Some(2)),
+            (4, 100, "delta", Some(3)),
+        ]);
+        assert!(!calling_process_cmdline(parent, describe_calling_process)
+            .unwrap()
+            .paths_in_input_are_relative_to_cwd());
+    }
+
     #[test]
     fn test_describe_calling_process_git_show() {
         for (command, expected_extension) in [
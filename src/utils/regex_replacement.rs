@@ -58,6 +58,49 @@ impl RegexReplacement {
     }
 }
 
+/// A sequence of sed-style substitutions which are applied in order.
+#[derive(Clone, Debug)]
+pub struct RegexReplacements(Vec<RegexReplacement>);
+
+impl RegexReplacements {
+    /// Parse `;`-separated sed commands. A literal `;` can be written as `\;`.
+    pub fn from_sed_commands(sed_commands: &str) -> Result<Self, String> {
+        let mut replacements = Vec::new();
+        let mut command = String::new();
+        let mut chars = sed_commands.chars().peekable();
+        loop {
+            match chars.next() {
+                Some('\\') if chars.peek() == Some(&';') => command.push(chars.next().unwrap()),
+                Some(';') | None => {
+                    let trimmed = command.trim();
+                    if !trimmed.is_empty() {
+                        replacements.push(
+                            RegexReplacement::from_sed_command(trimmed)
+                                .ok_or_else(|| format!("invalid sed command: {:?}", trimmed))?,
+                        );
+                    }
+                    command.clear();
+                    if chars.peek().is_none() {
+                        break;
+                    }
+                }
+                Some(c) => command.push(c),
+            }
+        }
+        Ok(RegexReplacements(replacements))
+    }
+
+    pub fn execute<'t>(&self, s: &'t str) -> Cow<'t, str> {
+        let mut result = Cow::from(s);
+        for replacement in &self.0 {
+            if let Cow::Owned(replaced) = replacement.execute(&result) {
+                result = Cow::Owned(replaced);
+            }
+        }
+        result
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -109,4 +152,17 @@ mod tests {
         assert!(RegexReplacement::from_sed_command("s,,,").is_some());
         assert!(RegexReplacement::from_sed_command("s,,,i").is_some());
     }
+
+    #[test]
+    fn test_sed_commands() {
+        let rr = RegexReplacements::from_sed_commands("s,^packages/,,; s,/src/,/,").unwrap();
+        assert_eq!(rr.execute("packages/app/src/main.rs"), "app/main.rs");
+        assert_eq!(rr.execute("other/file.rs"), "other/file.rs");
+
+        let rr = RegexReplacements::from_sed_commands(r"s/a\;b/c/;").unwrap();
+        assert_eq!(rr.execute("a;b"), "c");
+
+        assert!(RegexReplacements::from_sed_commands("s,foo,bar,;s,(,x,").is_err());
+        assert!(RegexReplacements::from_sed_commands("s,foo,bar").is_err());
+    }
 }
@@ -1,6 +1,11 @@
+use std::fmt;
+use std::ops::Deref;
 use std::str::FromStr;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread::{self, JoinHandle};
 
 use syntect::highlighting::{Color, FontStyle, Style};
+use syntect::parsing::SyntaxSet;
 
 use crate::color;
 use crate::style as delta_style;
@@ -24,6 +29,58 @@ pub fn syntect_color_from_ansi_number(n: u8) -> Option<Color> {
     Color::from_str(&format!("#{:02x}000000", n)).ok()
 }
 
+/// A syntax set which is loaded in a background thread, so that delta can start reading its input
+/// and writing lines which are not syntax-highlighted, e.g. commit metadata, meanwhile. Loading
+/// the syntax definitions is the larger part of delta's startup time. Clones share the syntax set,
+/// and its loading.
+#[derive(Clone, Default)]
+pub struct LazySyntaxSet {
+    loader: Arc<Mutex<Option<JoinHandle<SyntaxSet>>>>,
+    syntax_set: Arc<OnceLock<SyntaxSet>>,
+}
+
+impl LazySyntaxSet {
+    pub fn load<F>(load: F) -> Self
+    where
+        F: FnOnce() -> SyntaxSet + Send + 'static,
+    {
+        Self {
+            loader: Arc::new(Mutex::new(Some(thread::spawn(load)))),
+            syntax_set: Arc::new(OnceLock::new()),
+        }
+    }
+}
+
+impl From<SyntaxSet> for LazySyntaxSet {
+    fn from(syntax_set: SyntaxSet) -> Self {
+        Self {
+            loader: Arc::new(Mutex::new(None)),
+            syntax_set: Arc::new(OnceLock::from(syntax_set)),
+        }
+    }
+}
+
+impl Deref for LazySyntaxSet {
+    type Target = SyntaxSet;
+
+    /// Wait for the syntax set to be loaded, the first time it is used.
+    fn deref(&self) -> &SyntaxSet {
+        self.syntax_set
+            .get_or_init(|| match self.loader.lock().unwrap().take() {
+                Some(loader) => loader.join().expect("Failed to load syntax definitions"),
+                None => SyntaxSet::default(),
+            })
+    }
+}
+
+impl fmt::Debug for LazySyntaxSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LazySyntaxSet")
+            .field("is_loaded", &self.syntax_set.get().is_some())
+            .finish()
+    }
+}
+
 pub trait FromAnsiTermStyle {
     fn from_ansi_term_style(ansi_term_style: ansi_term::Style) -> Self;
 }
@@ -0,0 +1,148 @@
+// Query the terminal for its background color (OSC 11), see --detect-background.
+
+use std::time::Duration;
+
+// How long to wait for the terminal to answer. Terminals that answer do so within a few
+// milliseconds; others never answer.
+const BACKGROUND_QUERY_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Return whether the terminal background is light, or None if the terminal could not be queried
+/// or did not answer in time.
+pub fn detect_is_light_background() -> Option<bool> {
+    if !atty::is(atty::Stream::Stdout) {
+        return None;
+    }
+    let response = query_background_color(BACKGROUND_QUERY_TIMEOUT)?;
+    let (r, g, b) = parse_background_color_response(&response)?;
+    Some(relative_luminance(r, g, b) > 0.5)
+}
+
+#[cfg(unix)]
+fn query_background_color(timeout: Duration) -> Option<String> {
+    use std::fs::OpenOptions;
+    use std::io::{Read, Write};
+    use std::os::unix::io::AsRawFd;
+    use std::time::Instant;
+
+    let mut tty = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+    let fd = tty.as_raw_fd();
+
+    // Put the terminal in non-canonical mode without echo, so that the response can be read
+    // without waiting for a newline and is not displayed.
+    let mut termios = std::mem::MaybeUninit::<libc::termios>::uninit();
+    if unsafe { libc::tcgetattr(fd, termios.as_mut_ptr()) } != 0 {
+        return None;
+    }
+    let original_termios = unsafe { termios.assume_init() };
+    let mut raw_termios = original_termios;
+    raw_termios.c_lflag &= !(libc::ICANON | libc::ECHO);
+    raw_termios.c_cc[libc::VMIN] = 0;
+    raw_termios.c_cc[libc::VTIME] = 0;
+    if unsafe { libc::tcsetattr(fd, libc::TCSANOW, &raw_termios) } != 0 {
+        return None;
+    }
+
+    let mut response = Vec::new();
+    if tty.write_all(b"\x1b]11;?\x1b\\").and(tty.flush()).is_ok() {
+        let deadline = Instant::now() + timeout;
+        let mut buf = [0; 64];
+        while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+            let mut pollfd = libc::pollfd {
+                fd,
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            let remaining_ms = remaining.as_millis().max(1) as libc::c_int;
+            if unsafe { libc::poll(&mut pollfd, 1, remaining_ms) } <= 0 {
+                break;
+            }
+            match tty.read(&mut buf) {
+                Ok(n) if n > 0 => response.extend_from_slice(&buf[..n]),
+                _ => break,
+            }
+            // The response is terminated by ST (ESC \) or BEL.
+            if response.ends_with(b"\x1b\\") || response.ends_with(b"\x07") {
+                break;
+            }
+        }
+    }
+
+    unsafe { libc::tcsetattr(fd, libc::TCSANOW, &original_termios) };
+    if response.is_empty() {
+        None
+    } else {
+        Some(String::from_utf8_lossy(&response).into_owned())
+    }
+}
+
+#[cfg(not(unix))]
+fn query_background_color(_timeout: Duration) -> Option<String> {
+    None
+}
+
+/// Parse a response such as "\x1b]11;rgb:1c1c/1c1c/1c1c\x1b\\" into red, green and blue
+/// components in [0, 1].
+fn parse_background_color_response(response: &str) -> Option<(f64, f64, f64)> {
+    let start = response.find("]11;rgb:")? + "]11;rgb:".len();
+    let color = response[start..].split(['\x1b', '\x07']).next()?;
+    let mut components = color.split('/').map(|component| {
+        // Each component has 1 to 4 hex digits, scaled to the maximum value of that many digits.
+        if component.is_empty() || component.len() > 4 {
+            return None;
+        }
+        let value = u32::from_str_radix(component, 16).ok()?;
+        let max = (1u32 << (4 * component.len())) - 1;
+        Some(value as f64 / max as f64)
+    });
+    let (r, g, b) = (
+        components.next()??,
+        components.next()??,
+        components.next()??,
+    );
+    match components.next() {
+        None => Some((r, g, b)),
+        Some(_) => None,
+    }
+}
+
+fn relative_luminance(r: f64, g: f64, b: f64) -> f64 {
+    0.2126 * r + 0.7152 * g + 0.0722 * b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_background_color_response() {
+        assert_eq!(
+            parse_background_color_response("\x1b]11;rgb:ffff/ffff/ffff\x1b\\"),
+            Some((1.0, 1.0, 1.0))
+        );
+        assert_eq!(
+            parse_background_color_response("\x1b]11;rgb:00/80/ff\x07"),
+            Some((0.0, 128.0 / 255.0, 1.0))
+        );
+        assert_eq!(parse_background_color_response("\x1b]11;?\x1b\\"), None);
+        assert_eq!(
+            parse_background_color_response("\x1b]11;rgb:ffff/ffff\x1b\\"),
+            None
+        );
+        assert_eq!(parse_background_color_response(""), None);
+    }
+
+    #[test]
+    fn test_relative_luminance() {
+        let is_light = |response| {
+            let (r, g, b) = parse_background_color_response(response).unwrap();
+            relative_luminance(r, g, b) > 0.5
+        };
+        // Solarized light and dark backgrounds.
+        assert!(is_light("\x1b]11;rgb:fdfd/f6f6/e3e3\x1b\\"));
+        assert!(!is_light("\x1b]11;rgb:0000/2b2b/3636\x1b\\"));
+    }
+}
@@ -1,6 +1,7 @@
 use syntect::highlighting::Style as SyntectStyle;
 use unicode_segmentation::UnicodeSegmentation;
 
+use crate::ansi::{self, TruncationSide};
 use crate::cli;
 use crate::config::INLINE_SYMBOL_WIDTH_1;
 use crate::fatal;
@@ -27,11 +28,84 @@ pub struct WrapConfig {
     // This value is --wrap-max-lines + 1, and unlimited is 0, see
     // adapt_wrap_max_lines_argument()
     pub max_lines: usize,
+    pub wrap_at: WrapAt,
+    // Set by --wrap-prefer-word-boundary to --wrap-word-boundary-slack.
+    pub word_boundary_slack: Option<usize>,
+    pub long_line_display: LongLineDisplay,
+    // --truncation-side, or left for --long-line-display=truncate-left.
+    pub truncation_side: TruncationSide,
+    pub truncation_format: String,
     pub inline_hint_syntect_style: SyntectStyle,
 }
 
+/// How lines which do not fit are displayed, see `--long-line-display`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LongLineDisplay {
+    Wrap,
+    Truncate,
+}
+
+/// Where a line which is too long may be broken, see `--wrap-at`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WrapAt {
+    /// Break at the exact column where the line reaches the panel width.
+    Char,
+    /// Prefer breaking after the last whitespace or punctuation character
+    /// which still fits, fall back to `Char` if there is none.
+    Word,
+}
+
 impl WrapConfig {
+    /// The configuration of unified mode, set by --long-line-display.
     pub fn from_opt(opt: &cli::Opt, inline_hint_style: Style) -> Self {
+        Self::new(
+            opt,
+            "long-line-display",
+            &opt.long_line_display,
+            inline_hint_style,
+        )
+    }
+
+    /// The configuration of side-by-side mode, set by --side-by-side-wrap if given, otherwise by
+    /// --long-line-display.
+    pub fn side_by_side_from_opt(opt: &cli::Opt, inline_hint_style: Style) -> Self {
+        match &opt.side_by_side_wrap {
+            Some(side_by_side_wrap) => Self::new(
+                opt,
+                "side-by-side-wrap",
+                side_by_side_wrap,
+                inline_hint_style,
+            ),
+            None => Self::from_opt(opt, inline_hint_style),
+        }
+    }
+
+    fn new(
+        opt: &cli::Opt,
+        option_name: &str,
+        long_line_display: &str,
+        inline_hint_style: Style,
+    ) -> Self {
+        let truncation_side = match opt.truncation_side.as_str() {
+            "right" => TruncationSide::Right,
+            "left" => TruncationSide::Left,
+            "middle" => TruncationSide::Middle,
+            _ => fatal(format!(
+                "Invalid value for truncation-side: {}. \
+                 Expected \"right\", \"left\", or \"middle\".",
+                opt.truncation_side
+            )),
+        };
+        let (long_line_display, truncation_side) = match long_line_display {
+            "wrap" => (LongLineDisplay::Wrap, truncation_side),
+            "truncate" => (LongLineDisplay::Truncate, truncation_side),
+            "truncate-left" => (LongLineDisplay::Truncate, TruncationSide::Left),
+            _ => fatal(format!(
+                "Invalid value for {}: {}. \
+                 Expected \"wrap\", \"truncate\", or \"truncate-left\".",
+                option_name, long_line_display
+            )),
+        };
         Self {
             left_symbol: ensure_display_width_1("wrap-left-symbol", opt.wrap_left_symbol.clone()),
             right_symbol: ensure_display_width_1(
@@ -58,7 +132,28 @@ impl WrapConfig {
                     fatal("Invalid value for wrap-right-percent, not between 0 and 100.")
                 }
             },
-            max_lines: adapt_wrap_max_lines_argument(opt.wrap_max_lines.clone()),
+            max_lines: if long_line_display == LongLineDisplay::Wrap {
+                adapt_wrap_max_lines_argument(opt.wrap_max_lines.clone())
+            } else {
+                // Never wrap
+                1
+            },
+            wrap_at: match opt.wrap_at.as_str() {
+                "char" => WrapAt::Char,
+                "word" => WrapAt::Word,
+                _ => fatal(format!(
+                    "Invalid value for wrap-at: {}. Expected \"char\" or \"word\".",
+                    opt.wrap_at
+                )),
+            },
+            word_boundary_slack: if opt.wrap_prefer_word_boundary {
+                Some(opt.wrap_word_boundary_slack)
+            } else {
+                None
+            },
+            long_line_display,
+            truncation_side,
+            truncation_format: opt.wrap_truncation_format.clone(),
             inline_hint_syntect_style: SyntectStyle::from_delta_style(inline_hint_style),
         }
     }
@@ -89,7 +184,7 @@ impl WrapConfig {
     }
 }
 
-fn remove_percent_suffix(arg: &str) -> &str {
+pub fn remove_percent_suffix(arg: &str) -> &str {
     match &arg.strip_suffix('%') {
         Some(s) => s,
         None => arg,
@@ -116,6 +211,61 @@ fn adapt_wrap_max_lines_argument(arg: String) -> usize {
     }
 }
 
+// Whitespace and punctuation (except '_', which is usually part of an identifier)
+// are the characters after which a line may be broken in `WrapAt::Word` mode.
+fn is_word_boundary(c: char) -> bool {
+    (c.is_whitespace() && c != '\n') || (c.is_ascii_punctuation() && c != '_')
+}
+
+/// Find the last position in `sections` after which a line can be broken at a
+/// word boundary. Returned as the index of the section and the byte offset within
+/// the text of that section.
+fn last_word_boundary<S>(sections: &[(S, &str)]) -> Option<(usize, usize)> {
+    sections
+        .iter()
+        .enumerate()
+        .rev()
+        .find_map(|(i, (_, text))| {
+            text.char_indices()
+                .rev()
+                .find(|(_, c)| is_word_boundary(*c))
+                .map(|(pos, c)| (i, pos + c.len_utf8()))
+        })
+}
+
+// Identifier characters belong to the same token, so a line may be broken between an identifier
+// and the following character, or after whitespace.
+fn is_token_boundary(before: char, after: char) -> bool {
+    let is_identifier_char = |c: char| c.is_alphanumeric() || c == '_';
+    (before.is_whitespace() && before != '\n')
+        || (!after.is_whitespace() && is_identifier_char(before) != is_identifier_char(after))
+}
+
+/// Find the last position in `sections`, at most `max_distance` characters before their end, at
+/// which a line can be broken between two tokens. `next` is the character following `sections`.
+/// At least one character is left before the returned position.
+fn last_token_boundary<S>(
+    sections: &[(S, &str)],
+    mut next: Option<char>,
+    max_distance: usize,
+) -> Option<(usize, usize)> {
+    let n_chars: usize = sections.iter().map(|(_, text)| text.chars().count()).sum();
+    let mut distance = 0;
+    for (i, (_, text)) in sections.iter().enumerate().rev() {
+        for (pos, c) in text.char_indices().rev() {
+            if matches!(next, Some(next) if is_token_boundary(c, next)) {
+                return Some((i, pos + c.len_utf8()));
+            }
+            distance += 1;
+            if distance > max_distance || distance >= n_chars {
+                return None;
+            }
+            next = Some(c);
+        }
+    }
+    None
+}
+
 #[derive(PartialEq)]
 enum Stop {
     StackEmpty,
@@ -133,6 +283,11 @@ enum Stop {
 /// on the next line [right_prefix_symbol](WrapConfig::right_prefix_symbol).
 /// The inserted characters will follow the
 /// [inline_hint_syntect_style](WrapConfig::inline_hint_syntect_style).
+/// In [`WrapAt::Word`] mode lines are broken after the last word boundary which
+/// still fits, so words and identifiers are not split across lines. This only
+/// depends on the text and not on the styles, so the syntax and diff style
+/// sections of a line are wrapped identically. The same holds for the token
+/// boundaries used with `--wrap-prefer-word-boundary`.
 pub fn wrap_line<'a, I, S>(
     config: &'a Config,
     line: I,
@@ -147,7 +302,7 @@ where
 {
     let mut result = Vec::new();
 
-    let wrap_config = &config.wrap_config;
+    let wrap_config = &config.side_by_side_wrap_config;
 
     // The current line being assembled from the input to fit exactly into the given width.
     // A somewhat leaky abstraction as the fields are also accessed directly.
@@ -260,6 +415,33 @@ where
             };
             stack.push((style, next_line));
 
+            // If the next line does not already start at a word boundary, move everything
+            // after the last boundary back onto the stack. Without any boundary the line
+            // is broken exactly at the line width, as in `WrapAt::Char` mode. Otherwise, with
+            // --wrap-prefer-word-boundary, do the same for the last token boundary within the
+            // configured slack.
+            let boundary = if wrap_config.wrap_at == WrapAt::Word {
+                if next_line.starts_with(is_word_boundary) {
+                    None
+                } else {
+                    last_word_boundary(&line_segments)
+                }
+            } else if let Some(slack) = wrap_config.word_boundary_slack {
+                last_token_boundary(&line_segments, next_line.chars().next(), slack)
+            } else {
+                None
+            };
+            if let Some((i, byte_pos)) = boundary {
+                for section in line_segments.drain(i + 1..).rev() {
+                    stack.push(section);
+                }
+                let (style, text) = line_segments[i];
+                if byte_pos < text.len() {
+                    stack.push((style, &text[byte_pos..]));
+                    line_segments[i] = (style, &text[..byte_pos]);
+                }
+            }
+
             line_segments.push((symbol_style, &wrap_config.left_symbol));
             result.push(line_segments);
 
@@ -329,6 +511,99 @@ where
     result
 }
 
+/// Remove the end of a line which is wider than `line_width` and append an indicator
+/// built from `format`, see `--wrap-truncation-format`. If the indicator does not fit
+/// then the single `fallback_symbol` is used instead.
+pub fn truncate_line_sections_with_indicator(
+    mut sections: Vec<(Style, String)>,
+    line_width: usize,
+    format: &str,
+    fallback_symbol: &str,
+    indicator_style: Style,
+) -> Vec<(Style, String)> {
+    let text_width: usize = sections
+        .iter()
+        .map(|(_, text)| ansi::measure_text_width(text))
+        .sum();
+    if text_width <= line_width {
+        return sections;
+    }
+
+    // Returns the (section index, byte position) where the text has to be cut so that it
+    // fits into `available`, and the number of hidden characters and their width.
+    let split = |sections: &[(Style, String)], available: usize| {
+        let mut used = 0;
+        let mut split_at = None;
+        let (mut hidden_chars, mut hidden_width) = (0, 0);
+        for (i, (_, text)) in sections.iter().enumerate() {
+            for (pos, grapheme) in text.grapheme_indices(true) {
+                let width = ansi::measure_text_width(grapheme);
+                if split_at.is_none() && used + width > available {
+                    split_at = Some((i, pos));
+                }
+                if split_at.is_some() {
+                    if grapheme != "\n" {
+                        hidden_chars += 1;
+                    }
+                    hidden_width += width;
+                } else {
+                    used += width;
+                }
+            }
+        }
+        (split_at, hidden_chars, hidden_width)
+    };
+    let format_indicator = |hidden_chars: usize, hidden_width: usize| {
+        let hidden_rows = hidden_width.div_ceil(line_width.max(1));
+        format
+            .replace("{hidden_chars}", &hidden_chars.to_string())
+            .replace("{hidden}", &hidden_rows.to_string())
+    };
+
+    // The indicator contains the amount of hidden text which in turn depends on the width
+    // of the indicator: start without reserving space and widen it until the indicator fits.
+    let mut reserved_width = None;
+    let (split_at, indicator) = loop {
+        let available = line_width.saturating_sub(reserved_width.unwrap_or(0));
+        let (split_at, hidden_chars, hidden_width) = split(&sections, available);
+        let indicator = format_indicator(hidden_chars, hidden_width);
+        let indicator_width = ansi::measure_text_width(&indicator);
+        if indicator_width >= line_width {
+            // At least one column of text should remain visible.
+            let available = line_width.saturating_sub(ansi::measure_text_width(fallback_symbol));
+            break (split(&sections, available).0, fallback_symbol.to_string());
+        } else if matches!(reserved_width, Some(reserved) if indicator_width <= reserved) {
+            break (split_at, indicator);
+        }
+        reserved_width = Some(indicator_width);
+    };
+
+    if let Some((i, pos)) = split_at {
+        sections.truncate(i + 1);
+        sections[i].1.truncate(pos);
+    }
+    let indicator_style = with_background_of(indicator_style, sections.last());
+    sections.push((indicator_style, indicator));
+    sections
+}
+
+// An inserted symbol gets the background of the adjacent text unless it has its own.
+fn with_background_of(symbol_style: Style, section: Option<&(Style, String)>) -> Style {
+    match (
+        symbol_style.ansi_term_style.background,
+        section.and_then(|(style, _)| style.ansi_term_style.background),
+    ) {
+        (None, Some(background)) => Style {
+            ansi_term_style: ansi_term::Style {
+                background: Some(background),
+                ..symbol_style.ansi_term_style
+            },
+            ..symbol_style
+        },
+        _ => symbol_style,
+    }
+}
+
 fn wrap_if_too_long<'a, S>(
     config: &'a Config,
     wrapped: &mut Vec<LineSections<'a, S>>,
@@ -420,7 +695,7 @@ pub fn wrap_minusplus_block<'c: 'a, 'a>(
             must_wrap,
             line_width,
             &config.null_syntect_style,
-            &Some(config.wrap_config.inline_hint_syntect_style),
+            &Some(config.side_by_side_wrap_config.inline_hint_syntect_style),
         );
 
         // TODO: Why is the background color set to white when
@@ -573,16 +848,16 @@ pub fn wrap_zero_block<'c: 'a, 'a>(
     Vec<LineSections<'a, SyntectStyle>>,
     Vec<LineSections<'a, Style>>,
 ) {
-    // The width is the minimum of the left/right side. The panels should be equally sized,
-    // but in rare cases the remaining panel width might differ due to the space the line
-    // numbers take up.
+    // The width is the minimum of the left/right side. The panels are equally sized unless
+    // --side-by-side-left-percent is given, but even then the remaining panel width might
+    // differ due to the space the line numbers take up.
     let line_width = if let Some(line_numbers_data) = line_numbers_data {
         let width = available_line_width(config, line_numbers_data);
         std::cmp::min(width[Left], width[Right])
     } else {
         std::cmp::min(
-            config.side_by_side_data[Left].width,
-            config.side_by_side_data[Right].width,
+            config.side_by_side_data.get()[Left].width,
+            config.side_by_side_data.get()[Right].width,
         )
     };
 
@@ -598,7 +873,7 @@ pub fn wrap_zero_block<'c: 'a, 'a>(
             syntax_style_sections.into_iter().flatten(),
             line_width,
             &SyntectStyle::default(),
-            &Some(config.wrap_config.inline_hint_syntect_style),
+            &Some(config.side_by_side_wrap_config.inline_hint_syntect_style),
         );
 
         // TODO: Why is the background color set to white when
@@ -638,8 +913,8 @@ mod tests {
     use lazy_static::lazy_static;
     use syntect::highlighting::Style as SyntectStyle;
 
-    use super::wrap_line;
-    use super::WrapConfig;
+    use super::{truncate_line_sections_with_indicator, wrap_line};
+    use super::{WrapAt, WrapConfig};
     use crate::config::Config;
     use crate::paint::LineSections;
     use crate::style::Style;
@@ -680,12 +955,14 @@ mod tests {
             "4",
             "--wrap-right-percent",
             "37.0%",
+            "--line-numbers-min-width",
+            "4",
         ];
     }
 
     lazy_static! {
         static ref TEST_WRAP_CFG: WrapConfig =
-            make_config_from_args(&WRAP_DEFAULT_ARGS).wrap_config;
+            make_config_from_args(&WRAP_DEFAULT_ARGS).side_by_side_wrap_config;
     }
 
     fn default_wrap_cfg_plus<'a>(args: &[&'a str]) -> Vec<&'a str> {
@@ -696,7 +973,7 @@ mod tests {
 
     fn mk_wrap_cfg(wrap_cfg: &WrapConfig) -> Config {
         let mut cfg: Config = make_config_from_args(&[]);
-        cfg.wrap_config = wrap_cfg.clone();
+        cfg.side_by_side_wrap_config = wrap_cfg.clone();
         cfg
     }
 
@@ -952,6 +1229,116 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_wrap_line_at_word() {
+        let cfg = mk_wrap_cfg(&WrapConfig {
+            wrap_at: WrapAt::Word,
+            ..TEST_WRAP_CFG.clone()
+        });
+
+        {
+            let line = vec![(*S1, "ab cd"), (*S2, "ef gh")];
+            let lines = wrap_test(&cfg, line, 6);
+            assert_eq!(
+                lines,
+                vec![
+                    vec![(*S1, "ab "), (*SD, W)],
+                    vec![(*S1, "cd"), (*S2, "ef "), (*SD, W)],
+                    vec![(*S2, "gh")]
+                ]
+            );
+        }
+
+        {
+            // Break after punctuation, but do not split identifiers at '_'
+            let line = vec![(*S1, "a_b.c_d_e")];
+            let lines = wrap_test(&cfg, line, 8);
+            assert_eq!(
+                lines,
+                vec![
+                    vec![(*S1, "a_b."), (*SD, W)],
+                    vec![(*S1, "c_d"), (*S1, "_e")]
+                ]
+            );
+        }
+
+        {
+            // The next line already starts at a word boundary
+            let line = vec![(*S1, "abc def ghi")];
+            let lines = wrap_test(&cfg, line, 8);
+            assert_eq!(
+                lines,
+                vec![vec![(*S1, "abc def"), (*SD, W)], vec![(*S1, " ghi")]]
+            );
+        }
+
+        {
+            // A single token which does not fit is broken at the line width
+            let line = vec![(*S1, "ab"), (*S2, "cdefghij")];
+            let lines = wrap_test(&cfg, line, 4);
+            assert_eq!(
+                lines,
+                vec![
+                    vec![(*S1, "ab"), (*S2, "c"), (*SD, W)],
+                    vec![(*S2, "def"), (*SD, W)],
+                    vec![(*S2, "ghij")]
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn test_wrap_line_prefer_word_boundary() {
+        let cfg = mk_wrap_cfg(&WrapConfig {
+            word_boundary_slack: Some(10),
+            ..TEST_WRAP_CFG.clone()
+        });
+        let line = vec![(*S1, "foo.bar"), (*S2, "_baz(x)")];
+        let lines = wrap_test(&cfg, line.clone(), 10);
+        assert_eq!(
+            lines,
+            vec![
+                vec![(*S1, "foo."), (*SD, W)],
+                vec![(*S1, "bar"), (*S2, "_b"), (*S2, "az(x)")]
+            ]
+        );
+
+        // Without a boundary within the slack the line is broken at the line width.
+        let cfg = mk_wrap_cfg(&WrapConfig {
+            word_boundary_slack: Some(2),
+            ..TEST_WRAP_CFG.clone()
+        });
+        let lines = wrap_test(&cfg, line, 10);
+        assert_eq!(
+            lines,
+            vec![
+                vec![(*S1, "foo.bar"), (*S2, "_b"), (*SD, W)],
+                vec![(*S2, "az(x)")]
+            ]
+        );
+
+        // The line is broken after whitespace, not before it.
+        let line = vec![(*S1, "let  x = y")];
+        let lines = wrap_test(&cfg, line, 7);
+        assert_eq!(
+            lines,
+            vec![
+                vec![(*S1, "let  "), (*SD, W)],
+                vec![(*S1, "x"), (*S1, " = y")]
+            ]
+        );
+
+        let cfg = make_config_from_args(&[]);
+        assert_eq!(cfg.side_by_side_wrap_config.word_boundary_slack, None);
+        let cfg = make_config_from_args(&[
+            "--wrap-prefer-word-boundary",
+            "--wrap-word-boundary-slack",
+            "3",
+        ]);
+        assert_eq!(cfg.side_by_side_wrap_config.word_boundary_slack, Some(3));
+        assert_eq!(cfg.wrap_config.word_boundary_slack, Some(3));
+    }
+
     const HUNK_ZERO_DIFF: &str = "\
 diff --git i/a.py w/a.py
 index 223ca50..e69de29 100644
@@ -1016,6 +1403,248 @@ index 223ca50..e69de29 100644
         );
     }
 
+    #[test]
+    fn test_wrap_at_word_with_unequal_hunk_zero_width() {
+        DeltaTest::with_args(&default_wrap_cfg_plus(&[
+            "--side-by-side",
+            "--wrap-at",
+            "word",
+            "--line-numbers-left-format",
+            "│L│",
+            "--line-numbers-right-format",
+            "│RRRR│",
+            "--width",
+            "40",
+            "--line-fill-method",
+            "spaces",
+        ]))
+        .set_config(|cfg| cfg.truncation_symbol = ">".into())
+        .with_input(HUNK_ZERO_DIFF)
+        .expect_after_header(
+            r#"
+            │L│abcdefghijklm+   │RRRR│abcdefghijklm+
+            │L│nopqrstuvwxzy+   │RRRR│nopqrstuvwxzy+
+            │L│ 0123456789 +    │RRRR│ 0123456789 +
+            │L│0123456789 +     │RRRR│0123456789 +
+            │L│0123456789 01234>│RRRR│0123456789 01>
+            │L│a = 1            │RRRR│a = 2         "#,
+        );
+    }
+
+    #[test]
+    fn test_truncate_line_sections_with_indicator() {
+        let line = |sections: &[(Style, &str)]| {
+            sections
+                .iter()
+                .map(|(style, text)| (*style, text.to_string()))
+                .collect::<Vec<_>>()
+        };
+        let truncate = |sections: &[(Style, &str)], width, format| {
+            truncate_line_sections_with_indicator(line(sections), width, format, ">", *SD)
+        };
+
+        let fits = [(*S1, "012"), (*S2, "345")];
+        assert_eq!(truncate(&fits, 6, "<{hidden}"), line(&fits));
+
+        // The space for the indicator is taken into account when counting hidden text
+        assert_eq!(
+            truncate(
+                &[(*S1, "0123456789"), (*S2, "abcdefghij")],
+                10,
+                "<{hidden}|{hidden_chars}"
+            ),
+            line(&[(*S1, "01234"), (*SD, "<2|15")])
+        );
+        assert_eq!(
+            truncate(&[(*S1, "0123"), (*S2, "456789")], 8, "<{hidden_chars}"),
+            line(&[(*S1, "0123"), (*S2, "45"), (*SD, "<4")])
+        );
+
+        // Wide characters are counted once, but are two columns wide
+        assert_eq!(
+            truncate(&[(*S1, "日本語日本語")], 7, "+{hidden_chars}"),
+            line(&[(*S1, "日本"), (*SD, "+4")])
+        );
+
+        // Too narrow for the indicator
+        assert_eq!(
+            truncate(&[(*S1, "0123456789")], 3, "… {hidden} more"),
+            line(&[(*S1, "01"), (*SD, ">")])
+        );
+    }
+
+    #[test]
+    fn test_wrap_truncation_format_side_by_side() {
+        DeltaTest::with_args(&[
+            "--side-by-side",
+            "--wrap-left-symbol",
+            W,
+            "--wrap-max-lines",
+            "1",
+            "--wrap-truncation-format",
+            "…{hidden}",
+            "--line-numbers-left-format",
+            "│L│",
+            "--line-numbers-right-format",
+            "│RRRR│",
+            "--width",
+            "40",
+            "--line-fill-method",
+            "spaces",
+        ])
+        .with_input(HUNK_ZERO_DIFF)
+        .expect_after_header(
+            r#"
+            │L│abcdefghijklm+   │RRRR│abcdefghijklm+
+            │L│nopqrstuvwxzy 0…4│RRRR│nopqrstuvwxz…4
+            │L│a = 1            │RRRR│a = 2         "#,
+        );
+    }
+
+    #[test]
+    fn test_wrap_truncation_format_unified() {
+        DeltaTest::with_args(&[
+            "--long-line-display",
+            "truncate",
+            "--wrap-truncation-format",
+            " [+{hidden_chars}]",
+            "--width",
+            "30",
+        ])
+        .with_input(HUNK_ZERO_DIFF)
+        .expect_after_header(
+            r#"
+            abcdefghijklmnopqrstuvwx [+57]
+            a = 1
+            a = 2"#,
+        );
+    }
+
+    #[test]
+    fn test_long_line_display_truncate_left_unified() {
+        DeltaTest::with_args(&[
+            "--long-line-display",
+            "truncate-left",
+            "--truncation-symbol",
+            "<",
+            "--width",
+            "30",
+        ])
+        .with_input(HUNK_ZERO_DIFF)
+        .expect_after_header(
+            r#"
+            <3456789 0123456789 0123456789
+            a = 1
+            a = 2"#,
+        );
+    }
+
+    #[test]
+    fn test_long_line_display_truncate_unified() {
+        DeltaTest::with_args(&["--long-line-display", "truncate", "--width", "30"])
+            .set_config(|cfg| cfg.truncation_symbol = ">".into())
+            .with_input(HUNK_ZERO_DIFF)
+            .expect_after_header(
+                r#"
+                abcdefghijklmnopqrstuvwxzy 01>
+                a = 1
+                a = 2"#,
+            );
+    }
+
+    #[test]
+    fn test_truncation_side_unified() {
+        DeltaTest::with_args(&[
+            "--long-line-display",
+            "truncate",
+            "--truncation-side",
+            "middle",
+            "--truncation-symbol",
+            "~",
+            "--width",
+            "30",
+        ])
+        .with_input(HUNK_ZERO_DIFF)
+        .expect_after_header(
+            r#"
+            abcdefghijklmno~789 0123456789
+            a = 1
+            a = 2"#,
+        );
+    }
+
+    #[test]
+    fn test_truncation_side_side_by_side() {
+        DeltaTest::with_args(&[
+            "--side-by-side",
+            "--long-line-display",
+            "truncate",
+            "--truncation-side",
+            "left",
+            "--truncation-symbol",
+            "<",
+            "--line-numbers-left-format",
+            "│L│",
+            "--line-numbers-right-format",
+            "│RRRR│",
+            "--width",
+            "40",
+            "--line-fill-method",
+            "spaces",
+        ])
+        .with_input(HUNK_ZERO_DIFF)
+        .expect_after_header(
+            r#"
+            │L│<56789 0123456789│RRRR│<89 0123456789
+            │L│a = 1            │RRRR│a = 2         "#,
+        );
+    }
+
+    #[test]
+    fn test_truncation_side_file_header() {
+        let input = "\
+diff --git a/src/deeply/nested/directory/of/the/project/file.rs b/src/deeply/nested/directory/of/the/project/file.rs
+index 223ca50..e69de29 100644
+--- a/src/deeply/nested/directory/of/the/project/file.rs
++++ b/src/deeply/nested/directory/of/the/project/file.rs
+@@ -1 +1 @@
+-a
++b
+";
+        let args = ["--truncation-symbol", "~", "--width", "30"];
+        DeltaTest::with_args(&[&args[..], &["--truncation-side", "middle"]].concat())
+            .with_input(input)
+            .expect_contains("\nsrc/deeply/nest~roject/file.rs\n");
+        DeltaTest::with_args(&args)
+            .with_input(input)
+            .expect_contains("\nsrc/deeply/nested/directory/of/the/project/file.rs\n");
+    }
+
+    #[test]
+    fn test_long_line_display_truncate_left_side_by_side() {
+        DeltaTest::with_args(&default_wrap_cfg_plus(&[
+            "--side-by-side",
+            "--long-line-display",
+            "truncate-left",
+            "--truncation-symbol",
+            "<",
+            "--line-numbers-left-format",
+            "│L│",
+            "--line-numbers-right-format",
+            "│RRRR│",
+            "--width",
+            "40",
+            "--line-fill-method",
+            "spaces",
+        ]))
+        .with_input(HUNK_ZERO_DIFF)
+        .expect_after_header(
+            r#"
+            │L│<56789 0123456789│RRRR│<89 0123456789
+            │L│a = 1            │RRRR│a = 2         "#,
+        );
+    }
+
     #[test]
     fn test_wrap_with_large_hunk_zero_line_numbers() {
         DeltaTest::with_args(&default_wrap_cfg_plus(&[
@@ -1178,7 +1807,7 @@ index 223ca50..e69de29 100644
         }
 
         {
-            config.wrap_config.max_lines = 2;
+            config.side_by_side_wrap_config.max_lines = 2;
             DeltaTest::with_config(&config)
                 .with_input(&format!(
                     "{}-{}+{}",